@@ -2,6 +2,7 @@ package com.vetclinic.pdf;
 
 import com.google.gson.*;
 import com.vetclinic.pdf.constants.*;
+import com.vetclinic.pdf.constants.parameters.ExigoParameterEnum;
 import com.vetclinic.pdf.constants.parameters.HealvetParameterEnum;
 import com.vetclinic.pdf.models.Patient;
 import com.vetclinic.pdf.models.parameters.HealvetParameter;
@@ -132,6 +133,73 @@ public class PdfGeneratorCLI {
                 File pdfFile = service.createPharmacyNote(prescriptionText, outputPath);
                 System.out.println("Pharmacy note PDF generated successfully: " + outputPath);
 
+            } else if ("day_sheet".equals(reportType)) {
+                // Day sheet mode - the day's appointments as a table per section
+                String date = json.has("date") ? json.get("date").getAsString() : "";
+
+                List<PDFReportService.DaySheetSection> sections = new ArrayList<>();
+                if (json.has("sections") && json.get("sections").isJsonArray()) {
+                    for (JsonElement sectionEl : json.getAsJsonArray("sections")) {
+                        JsonObject sectionObj = sectionEl.getAsJsonObject();
+                        String title = getStringOrNull(sectionObj, "title");
+                        List<PDFReportService.DaySheetRow> rows = new ArrayList<>();
+                        if (sectionObj.has("rows") && sectionObj.get("rows").isJsonArray()) {
+                            for (JsonElement rowEl : sectionObj.getAsJsonArray("rows")) {
+                                JsonObject rowObj = rowEl.getAsJsonObject();
+                                rows.add(new PDFReportService.DaySheetRow(
+                                    getStringOrNull(rowObj, "time"),
+                                    getStringOrNull(rowObj, "patient"),
+                                    getStringOrNull(rowObj, "owner"),
+                                    getStringOrNull(rowObj, "room"),
+                                    getStringOrNull(rowObj, "status"),
+                                    getStringOrNull(rowObj, "notes")));
+                            }
+                        }
+                        sections.add(new PDFReportService.DaySheetSection(title, rows));
+                    }
+                }
+
+                File pdfFile = service.createDaySheet(date, sections, outputPath);
+                System.out.println("Day sheet PDF generated successfully: " + outputPath);
+
+            } else if ("records".equals(reportType)) {
+                // Record selection mode - a referral export of chosen records
+                JsonObject patientJson = json.has("patient") && json.get("patient").isJsonObject()
+                        ? json.getAsJsonObject("patient") : new JsonObject();
+                PDFReportService.RecordSelectionPatient patient = new PDFReportService.RecordSelectionPatient(
+                    getStringOrNull(patientJson, "name"),
+                    getStringOrNull(patientJson, "owner"),
+                    getStringOrNull(patientJson, "species"),
+                    getStringOrNull(patientJson, "microchip_id"),
+                    getStringOrNull(patientJson, "date_of_birth"));
+
+                List<PDFReportService.SelectedRecord> records = new ArrayList<>();
+                if (json.has("records") && json.get("records").isJsonArray()) {
+                    for (JsonElement recordEl : json.getAsJsonArray("records")) {
+                        JsonObject recordObj = recordEl.getAsJsonObject();
+                        List<PDFReportService.SelectedAttachment> attachments = new ArrayList<>();
+                        if (recordObj.has("attachments") && recordObj.get("attachments").isJsonArray()) {
+                            for (JsonElement attachmentEl : recordObj.getAsJsonArray("attachments")) {
+                                JsonObject attachmentObj = attachmentEl.getAsJsonObject();
+                                boolean generated = attachmentObj.has("generated")
+                                        && attachmentObj.get("generated").getAsBoolean();
+                                attachments.add(new PDFReportService.SelectedAttachment(
+                                    getStringOrNull(attachmentObj, "name"), generated));
+                            }
+                        }
+                        records.add(new PDFReportService.SelectedRecord(
+                            getStringOrNull(recordObj, "date"),
+                            getStringOrNull(recordObj, "record_type"),
+                            getStringOrNull(recordObj, "name"),
+                            getStringOrNull(recordObj, "description"),
+                            getStringOrNull(recordObj, "prescription_notes"),
+                            attachments));
+                    }
+                }
+
+                File pdfFile = service.createRecordSelection(patient, records, outputPath);
+                System.out.println("Record selection PDF generated successfully: " + outputPath);
+
             } else if ("simple".equals(reportType)) {
                 // Simple report mode - uses template with title and description
                 String title = json.has("title") ? json.get("title").getAsString() : "";
@@ -199,6 +267,13 @@ public class PdfGeneratorCLI {
         }
     }
 
+    private static String getStringOrNull(JsonObject json, String key) {
+        if (json.has(key) && !json.get(key).isJsonNull()) {
+            return json.get(key).getAsString();
+        }
+        return null;
+    }
+
     private static Patient parsePatient(JsonObject json) {
         Patient patient = new Patient();
         patient.setName(json.get("name").getAsString());
@@ -297,36 +372,15 @@ public class PdfGeneratorCLI {
         JsonObject testResults = json.getAsJsonObject("test_results");
         List<Parameter> parameters = new ArrayList<>();
 
-        // Exigo parameter order (matching Rust version and print-app spec)
-        String[][] parameterOrder = {
-            {"PLT", "PLT - тромбоцити", "10e+9/L"},
-            {"MPV", "MPV", "fL"},
-            {"HGB", "HGB - хемоглобин", "g/dL"},
-            {"WBC", "WBC - леукоцити", "10e+9/L"},
-            {"LA", "LA - лимфоцити", "10e+9/L"},
-            {"MA", "MA - моноцити", "10e+9/L"},
-            {"GA", "NEUT - неутрофили", "10e+9/L"},
-            {"LR", "LR - лимфоцити %", "%"},
-            {"MR", "MR - моноцити %", "%"},
-            {"GR", "GR - гранулоцити %", "%"},
-            {"EA", "EA - еозинофили", "10e+9/L"},
-            {"ER", "ER - еозинофили %", "%"},
-            {"RBC", "RBC - еритроцити", "10e+12/L"},
-            {"MCV", "MCV", "fL"},
-            {"HCT", "HCT - хематокрит", "%"},
-            {"MCH", "MCH", "pg"},
-            {"MCHC", "MCHC", "g/dL"},
-            {"RDWR", "RDW %", "%"},
-            {"RDWA", "RDW", "fL"}
-        };
-
-        for (String[] param : parameterOrder) {
-            String key = param[0];
+        // Report order, names and units (mirrored by EXIGO_RESULT_PARAMETERS
+        // in the Rust device parser, which serves them to the UI)
+        for (ExigoParameterEnum param : ExigoParameterEnum.getAllParameters()) {
+            String key = param.getCode();
             if (testResults.has(key)) {
                 Parameter parameter = new Parameter();
-                parameter.setName(param[1]);
+                parameter.setName(param.getTranslated());
                 parameter.setResult(testResults.get(key).getAsString());
-                parameter.setUnit(param[2]);
+                parameter.setUnit(param.getUnit());
 
                 // Extract reference values from _L and _H keys
                 String refLowKey = key + "_L";
@@ -8,34 +8,36 @@ import java.util.logging.Logger;
 
 public enum ExigoParameterEnum {
 
-    PLT("PLT", "PLT - тромбоцити"),
-    MPV("MPV", "MPV"),
-    HGB("HGB", "HGB - хемоглобин"),
-    WBC("WBC", "WBC - леукоцити"),
-    LA("LA", "LA - лимфоцити"),
-    MA("MA", "MA - моноцити"),
-    GA("GA", "NEUT - неутрофили"),
-    LR("LR", "LR - лимфоцити %"),
-    MR("MR", "MR - моноцити %"),
-    GR("GR", "GR - гранулоцити %"),
-    EA("EA", "EA - еозинофили"),
-    ER("ER", "ER - еозинофили %"),
-    RBC("RBC", "RBC - еритроцити"),
-    MCV("MCV", "MCV"),
-    HCT("HCT", "HCT - хематокрит"),
-    MCH("MCH", "MCH"),
-    MCHC("MCHC", "MCHC"),
-    RDWR("RDWR", "RDW %"),
-    RDWA("RDWA", "RDW");
+    PLT("PLT", "PLT - тромбоцити", "10e+9/L"),
+    MPV("MPV", "MPV", "fL"),
+    HGB("HGB", "HGB - хемоглобин", "g/dL"),
+    WBC("WBC", "WBC - леукоцити", "10e+9/L"),
+    LA("LA", "LA - лимфоцити", "10e+9/L"),
+    MA("MA", "MA - моноцити", "10e+9/L"),
+    GA("GA", "NEUT - неутрофили", "10e+9/L"),
+    LR("LR", "LR - лимфоцити %", "%"),
+    MR("MR", "MR - моноцити %", "%"),
+    GR("GR", "GR - гранулоцити %", "%"),
+    EA("EA", "EA - еозинофили", "10e+9/L"),
+    ER("ER", "ER - еозинофили %", "%"),
+    RBC("RBC", "RBC - еритроцити", "10e+12/L"),
+    MCV("MCV", "MCV", "fL"),
+    HCT("HCT", "HCT - хематокрит", "%"),
+    MCH("MCH", "MCH", "pg"),
+    MCHC("MCHC", "MCHC", "g/dL"),
+    RDWR("RDWR", "RDW %", "%"),
+    RDWA("RDWA", "RDW", "fL");
 
 
     private String code;
     private String translated;
+    private String unit;
     private static Logger logger = Logger.getLogger(PointcareParameterEnum.class.getName());
 
-    ExigoParameterEnum(String code, String translated) {
+    ExigoParameterEnum(String code, String translated, String unit) {
         this.code = code;
         this.translated = translated;
+        this.unit = unit;
     }
     public static List<ExigoParameterEnum> getAllParameters() {
         List<ExigoParameterEnum> types = new ArrayList<>();
@@ -94,6 +96,10 @@ public enum ExigoParameterEnum {
         this.translated = translated;
     }
 
+    public String getUnit() {
+        return unit;
+    }
+
     public static Logger getLogger() {
         return logger;
     }
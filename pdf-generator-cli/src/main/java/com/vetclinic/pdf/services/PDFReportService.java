@@ -186,12 +186,13 @@ public class PDFReportService {
 
 
         // socials paragraph (right)
-        Image socialsImages = Image.getInstance(Util.getAbsolutePath(Constants.SOCIALS));
-        socialsImages.scaleToFit(35, 35);
-        Chunk socialsImagesChunk = new Chunk(socialsImages, 0, -8, false);
+        Image socialsImages = Util.loadImage(Constants.SOCIALS);
         Paragraph socialImagesParagraph = new Paragraph();
         socialImagesParagraph.setAlignment(Element.ALIGN_RIGHT);
-        socialImagesParagraph.add(socialsImagesChunk);
+        if (socialsImages != null) {
+            socialsImages.scaleToFit(35, 35);
+            socialImagesParagraph.add(new Chunk(socialsImages, 0, -8, false));
+        }
 
         Chunk socials = new Chunk( Content.SOCIALS,
                 FontUtil.getMacedonianFont(CELL_FONT_SIZE, FontUtil.DEFAULT_FONT_COLOR));
@@ -1075,9 +1076,11 @@ public class PDFReportService {
             Paragraph leftHeader = new Paragraph();
 
             // left header - logo
-            Image logo = Image.getInstance(Util.getAbsolutePath(Constants.LOGO));
-            logo.scaleToFit(80, 80);
-            leftHeader.add(new Chunk(logo, 0, 0, true));
+            Image logo = Util.loadImage(Constants.LOGO);
+            if (logo != null) {
+                logo.scaleToFit(80, 80);
+                leftHeader.add(new Chunk(logo, 0, 0, true));
+            }
             leftHeader.setIndentationLeft(20);
 
             // middle header - name
@@ -1209,7 +1212,10 @@ public class PDFReportService {
         Paragraph logoWrapper = new Paragraph();
         logoWrapper.setAlignment(Element.ALIGN_MIDDLE);
 
-        Image logo = Image.getInstance(Util.getAbsolutePath(Constants.LOGO));
+        Image logo = Util.loadImage(Constants.LOGO);
+        if (logo == null) {
+            return;
+        }
         logoWrapper.add(logo);
         PdfContentByte canvas = writer.getDirectContent();
 
@@ -2266,6 +2272,314 @@ public class PDFReportService {
 
     // ==================== END INVOICE FROM TEMPLATE ====================
 
+    // ==================== DAY SHEET ====================
+
+    /**
+     * One appointment line on the day sheet. Times arrive already formatted
+     * in the practice timezone.
+     */
+    public static class DaySheetRow {
+        public String time;
+        public String patient;
+        public String owner;
+        public String room;
+        public String status;
+        public String notes;
+
+        public DaySheetRow(String time, String patient, String owner, String room, String status, String notes) {
+            this.time = time;
+            this.patient = patient;
+            this.owner = owner;
+            this.room = room;
+            this.status = status;
+            this.notes = notes;
+        }
+    }
+
+    /**
+     * A block of rows printed under an optional heading (the room name when
+     * the sheet is grouped by room).
+     */
+    public static class DaySheetSection {
+        public String title;
+        public List<DaySheetRow> rows;
+
+        public DaySheetSection(String title, List<DaySheetRow> rows) {
+            this.title = title;
+            this.rows = rows;
+        }
+    }
+
+    /**
+     * Create the front desk's daily run sheet: one table per section listing
+     * the appointments in time order. Long days continue on further pages
+     * with the column headers repeated.
+     *
+     * @param date The date printed in the title
+     * @param sections Sections to print, in order
+     * @param outputPath The output file path
+     * @return The generated PDF file
+     */
+    public File createDaySheet(String date, List<DaySheetSection> sections, String outputPath)
+            throws IOException, DocumentException, CreatingDirectoryException {
+
+        File pdfFile = new File(outputPath);
+        File parentDir = pdfFile.getParentFile();
+        if (parentDir != null && !parentDir.exists()) {
+            boolean created = parentDir.mkdirs();
+            if (!created && !parentDir.exists()) {
+                throw new CreatingDirectoryException(parentDir.getAbsolutePath(), "Failed to create parent directory");
+            }
+        }
+
+        OutputStream file = new FileOutputStream(pdfFile);
+        Document document = new Document(PageSize.A4, 36, 36, 36, 36);
+        PdfWriter.getInstance(document, file);
+        document.open();
+
+        Font titleFont = FontUtil.getMacedonianFont(16, FontUtil.CYAN_DARK_FONT_COLOR);
+        titleFont.setStyle(Font.BOLD);
+        Font sectionFont = FontUtil.getMacedonianFont(12, FontUtil.CYAN_DARK_FONT_COLOR);
+        sectionFont.setStyle(Font.BOLD);
+        Font headerFont = FontUtil.getMacedonianFont(9, FontUtil.CYAN_DARK_FONT_COLOR);
+        Font cellFont = FontUtil.getMacedonianFont(9, FontUtil.DEFAULT_FONT_COLOR);
+
+        Paragraph title = new Paragraph("Распоред за " + date, titleFont);
+        title.setSpacingAfter(12);
+        document.add(title);
+
+        String[] headers = {"ВРЕМЕ", "ПАЦИЕНТ", "СОПСТВЕНИК", "ПРОСТОРИЈА", "СТАТУС", "БЕЛЕШКИ"};
+        float[] widths = {12, 16, 18, 14, 12, 28};
+        BaseColor lightTeal = new BaseColor(220, 245, 243);
+
+        for (DaySheetSection section : sections) {
+            if (section.title != null && !section.title.isEmpty()) {
+                Paragraph heading = new Paragraph(section.title, sectionFont);
+                heading.setSpacingBefore(8);
+                heading.setSpacingAfter(4);
+                document.add(heading);
+            }
+
+            PdfPTable table = new PdfPTable(widths);
+            table.setWidthPercentage(100);
+            table.setHeaderRows(1);
+            for (String header : headers) {
+                PdfPCell cell = new PdfPCell(new Phrase(header, headerFont));
+                cell.setBorder(Rectangle.BOTTOM);
+                cell.setBorderColor(Util.CYAN_COLOR);
+                cell.setPadding(4);
+                table.addCell(cell);
+            }
+
+            if (section.rows.isEmpty()) {
+                PdfPCell empty = new PdfPCell(new Phrase("Нема закажани термини", cellFont));
+                empty.setColspan(headers.length);
+                empty.setBorder(Rectangle.NO_BORDER);
+                empty.setPadding(4);
+                table.addCell(empty);
+            }
+
+            for (int i = 0; i < section.rows.size(); i++) {
+                DaySheetRow row = section.rows.get(i);
+                String[] values = {row.time, row.patient, row.owner, row.room, statusLabel(row.status), row.notes};
+                for (String value : values) {
+                    PdfPCell cell = new PdfPCell(new Phrase(value != null ? value : "", cellFont));
+                    cell.setBorder(Rectangle.NO_BORDER);
+                    cell.setPadding(4);
+                    if (i % 2 == 0) {
+                        cell.setBackgroundColor(lightTeal);
+                    }
+                    table.addCell(cell);
+                }
+            }
+
+            table.setSpacingAfter(10);
+            document.add(table);
+        }
+
+        document.close();
+        file.close();
+
+        return pdfFile;
+    }
+
+    private String statusLabel(String status) {
+        if (status == null) {
+            return "";
+        }
+        switch (status) {
+            case "scheduled": return "Закажан";
+            case "in_progress": return "Во тек";
+            case "completed": return "Завршен";
+            case "cancelled": return "Откажан";
+            default: return status;
+        }
+    }
+
+    // ==================== END DAY SHEET ====================
+
+    // ==================== RECORD SELECTION ====================
+
+    /**
+     * Patient header of a record selection; every field may be null.
+     */
+    public static class RecordSelectionPatient {
+        public String name;
+        public String owner;
+        public String species;
+        public String microchipId;
+        public String dateOfBirth;
+
+        public RecordSelectionPatient(String name, String owner, String species, String microchipId, String dateOfBirth) {
+            this.name = name;
+            this.owner = owner;
+            this.species = species;
+            this.microchipId = microchipId;
+            this.dateOfBirth = dateOfBirth;
+        }
+    }
+
+    /**
+     * An attachment listed under its record. Generated reports are flagged
+     * so the reader knows they exist as separate documents.
+     */
+    public static class SelectedAttachment {
+        public String name;
+        public boolean generated;
+
+        public SelectedAttachment(String name, boolean generated) {
+            this.name = name;
+            this.generated = generated;
+        }
+    }
+
+    /**
+     * One medical record of the selection. The date arrives already formatted.
+     */
+    public static class SelectedRecord {
+        public String date;
+        public String recordType;
+        public String name;
+        public String description;
+        public String prescriptionNotes;
+        public List<SelectedAttachment> attachments;
+
+        public SelectedRecord(String date, String recordType, String name, String description,
+                              String prescriptionNotes, List<SelectedAttachment> attachments) {
+            this.date = date;
+            this.recordType = recordType;
+            this.name = name;
+            this.description = description;
+            this.prescriptionNotes = prescriptionNotes;
+            this.attachments = attachments;
+        }
+    }
+
+    /**
+     * Create a referral export holding only the chosen records of a patient:
+     * a patient header followed by each record with its notes and the names
+     * of its attachments.
+     *
+     * @param patient The patient printed in the header
+     * @param records Records to print, in order
+     * @param outputPath The output file path
+     * @return The generated PDF file
+     */
+    public File createRecordSelection(RecordSelectionPatient patient, List<SelectedRecord> records, String outputPath)
+            throws IOException, DocumentException, CreatingDirectoryException {
+
+        File pdfFile = new File(outputPath);
+        File parentDir = pdfFile.getParentFile();
+        if (parentDir != null && !parentDir.exists()) {
+            boolean created = parentDir.mkdirs();
+            if (!created && !parentDir.exists()) {
+                throw new CreatingDirectoryException(parentDir.getAbsolutePath(), "Failed to create parent directory");
+            }
+        }
+
+        OutputStream file = new FileOutputStream(pdfFile);
+        Document document = new Document(PageSize.A4, 36, 36, 36, 36);
+        PdfWriter.getInstance(document, file);
+        document.open();
+
+        Font titleFont = FontUtil.getMacedonianFont(16, FontUtil.CYAN_DARK_FONT_COLOR);
+        titleFont.setStyle(Font.BOLD);
+        Font recordFont = FontUtil.getMacedonianFont(12, FontUtil.CYAN_DARK_FONT_COLOR);
+        recordFont.setStyle(Font.BOLD);
+        Font labelFont = FontUtil.getMacedonianFont(9, FontUtil.CYAN_DARK_FONT_COLOR);
+        Font textFont = FontUtil.getMacedonianFont(10, FontUtil.DEFAULT_FONT_COLOR);
+
+        Paragraph title = new Paragraph("Извадок од медицински картон", titleFont);
+        title.setSpacingAfter(8);
+        document.add(title);
+
+        PdfPTable header = new PdfPTable(new float[]{25, 75});
+        header.setWidthPercentage(100);
+        String[][] patientRows = {
+            {"ПАЦИЕНТ", patient.name},
+            {"СОПСТВЕНИК", patient.owner},
+            {"ВИД", patient.species},
+            {"МИКРОЧИП", patient.microchipId},
+            {"ДАТУМ НА РАЃАЊЕ", patient.dateOfBirth},
+        };
+        for (String[] row : patientRows) {
+            if (row[1] == null || row[1].isEmpty()) {
+                continue;
+            }
+            PdfPCell label = new PdfPCell(new Phrase(row[0], labelFont));
+            label.setBorder(Rectangle.NO_BORDER);
+            label.setPadding(3);
+            header.addCell(label);
+            PdfPCell value = new PdfPCell(new Phrase(row[1], textFont));
+            value.setBorder(Rectangle.NO_BORDER);
+            value.setPadding(3);
+            header.addCell(value);
+        }
+        header.setSpacingAfter(10);
+        document.add(header);
+
+        LineSeparator separator = new LineSeparator(0.5f, 100, Util.CYAN_COLOR, Element.ALIGN_CENTER, -2);
+
+        for (SelectedRecord record : records) {
+            document.add(new Chunk(separator));
+
+            String heading = (record.date != null ? record.date + " — " : "") + (record.name != null ? record.name : "");
+            Paragraph recordHeading = new Paragraph(heading, recordFont);
+            recordHeading.setSpacingBefore(6);
+            recordHeading.setSpacingAfter(4);
+            document.add(recordHeading);
+
+            if (record.description != null && !record.description.isEmpty()) {
+                Paragraph description = new Paragraph(record.description, textFont);
+                description.setSpacingAfter(4);
+                document.add(description);
+            }
+
+            if (record.prescriptionNotes != null && !record.prescriptionNotes.isEmpty()) {
+                document.add(new Paragraph("ТЕРАПИЈА", labelFont));
+                Paragraph prescription = new Paragraph(record.prescriptionNotes, textFont);
+                prescription.setSpacingAfter(4);
+                document.add(prescription);
+            }
+
+            if (!record.attachments.isEmpty()) {
+                document.add(new Paragraph("ПРИЛОЗИ", labelFont));
+                for (SelectedAttachment attachment : record.attachments) {
+                    String line = "• " + (attachment.name != null ? attachment.name : "")
+                            + (attachment.generated ? " (генериран извештај)" : "");
+                    document.add(new Paragraph(line, textFont));
+                }
+            }
+        }
+
+        document.close();
+        file.close();
+
+        return pdfFile;
+    }
+
+    // ==================== END RECORD SELECTION ====================
+
     public static PDFReportService getInstance() {
         return instance;
     }
@@ -203,6 +203,27 @@ public class Util {
         return "";
     }
 
+    /**
+     * Loads a bundled image (resolved from the classpath like getAbsolutePath, so it
+     * does not depend on the working directory). Returns null and logs a warning when
+     * the image is missing or unreadable, so callers can skip it instead of failing
+     * the whole report.
+     */
+    public static Image loadImage(String resourcePathRelativeToMainPackage) {
+        String path = getAbsolutePath(resourcePathRelativeToMainPackage);
+        if (path.isEmpty()) {
+            logger.warning("Image " + resourcePathRelativeToMainPackage + " not found, skipping it");
+            return null;
+        }
+
+        try {
+            return Image.getInstance(path);
+        } catch (BadElementException | IOException e) {
+            logger.log(Level.WARNING, "Failed to load image " + resourcePathRelativeToMainPackage + ", skipping it", e);
+            return null;
+        }
+    }
+
     public static void maskHelperRectangleAndAddToCanvas(Rectangle rectangle, PdfContentByte canvas) {
         rectangle.setBorder(Rectangle.BOX);
         rectangle.setBorderWidth(0.5f);
@@ -0,0 +1,23 @@
+package com.vetclinic.pdf;
+
+import com.itextpdf.text.Image;
+import com.vetclinic.pdf.constants.Constants;
+import org.junit.Test;
+
+import static org.junit.Assert.assertNotNull;
+import static org.junit.Assert.assertNull;
+
+public class UtilTest {
+
+    @Test
+    public void loadImageReturnsNullForAMissingImage() {
+        assertNull(Util.loadImage("assets/images/no-such-image.png"));
+    }
+
+    @Test
+    public void loadImageLoadsABundledImage() {
+        Image logo = Util.loadImage(Constants.LOGO);
+
+        assertNotNull(logo);
+    }
+}
@@ -1,14 +1,21 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 use crate::database::SeaOrmPool;
 use crate::services::appointments::AppointmentService;
+use crate::services::java_pdf_service::JavaPdfService;
 use crate::services::oauth::get_valid_access_token;
+use crate::services::settings::SettingsService;
+use crate::services::sync::{AppointmentPusher, SyncService};
+use crate::services::users::CurrentUser;
 use crate::models::{
-    Appointment, AppointmentDetail, AppointmentListResponse, AppointmentStatus,
-    CreateAppointmentInput, UpdateAppointmentInput, AppointmentFilter,
-    ConflictCheckInput, ConflictCheckResponse, DuplicateAppointmentInput
+    Appointment, AppointmentDetail, AppointmentImportEntry, AppointmentImportReport,
+    AppointmentListResponse, AppointmentStatistics, AppointmentStatsGroupBy, AppointmentStatus,
+    AvailableSlot, RoomReassignmentResult,
+    CalendarView, CalendarViewMode, CreateAppointmentInput, UpdateAppointmentInput, AppointmentFilter,
+    ConflictCheckInput, ConflictCheckResponse, DuplicateAppointmentInput,
+    SyncAction, SyncRetrySummary, SyncStatus
 };
 use std::sync::Arc;
-use chrono::Utc;
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use sea_orm::{DatabaseConnection, ConnectionTrait, Statement, DbBackend};
 
 #[tauri::command]
@@ -42,18 +49,30 @@ pub async fn get_appointment(
 #[tauri::command]
 pub async fn create_appointment(
     pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
     input: CreateAppointmentInput,
     created_by: Option<String>,
 ) -> Result<Appointment, String> {
-    let created_by = created_by.unwrap_or_else(|| "system".to_string());
+    let created_by = created_by
+        .or_else(|| current_user.actor())
+        .unwrap_or_else(|| "system".to_string());
+    let mut input = input;
+    if input.room_id.is_none() {
+        input.room_id = AppointmentService::auto_assign_room(&pool, input.start_time, input.end_time).await?;
+    }
     let appointment = AppointmentService::create_appointment(&pool, input, created_by).await?;
 
     // Trigger sync to Google Calendar if enabled (non-blocking)
     let appointment_id = appointment.id;
     let db = pool.inner().clone();
     tokio::spawn(async move {
-        if let Err(e) = trigger_sync_after_create(db, appointment_id).await {
-            log::error!("Failed to sync appointment to Google Calendar: {}", e);
+        match trigger_sync_after_create(db.clone(), appointment_id).await {
+            Ok(Some(event_id)) => record_sync_success(&db, appointment_id, SyncAction::Create, event_id).await,
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to sync appointment to Google Calendar: {}", e);
+                record_sync_failure(&db, appointment_id, SyncAction::Create, e).await;
+            }
         }
     });
 
@@ -63,27 +82,41 @@ pub async fn create_appointment(
 #[tauri::command]
 pub async fn update_appointment(
     pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
     id: i64,
     input: UpdateAppointmentInput,
     updated_by: Option<String>,
 ) -> Result<Appointment, String> {
-    let updated_by = updated_by.unwrap_or_else(|| "system".to_string());
+    let updated_by = updated_by
+        .or_else(|| current_user.actor())
+        .unwrap_or_else(|| "system".to_string());
 
     // Check if appointment is being cancelled
     let is_cancellation = input.status == Some(AppointmentStatus::Cancelled);
 
-    let appointment = AppointmentService::update_appointment(&pool, id, input, updated_by).await?;
+    let actor = current_user.get();
+    let appointment = AppointmentService::update_appointment(&pool, id, input, updated_by, actor.as_ref()).await?;
 
     // Trigger sync to Google Calendar if enabled (non-blocking)
     let db = pool.inner().clone();
     tokio::spawn(async move {
         if is_cancellation {
-            if let Err(e) = trigger_sync_after_cancel(db, id).await {
-                log::error!("Failed to sync cancellation to Google Calendar: {}", e);
+            match trigger_sync_after_cancel(db.clone(), id).await {
+                Ok(Some(event_id)) => record_sync_success(&db, id, SyncAction::Delete, event_id).await,
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Failed to sync cancellation to Google Calendar: {}", e);
+                    record_sync_failure(&db, id, SyncAction::Delete, e).await;
+                }
             }
         } else {
-            if let Err(e) = trigger_sync_after_update(db, id).await {
-                log::error!("Failed to sync update to Google Calendar: {}", e);
+            match trigger_sync_after_update(db.clone(), id).await {
+                Ok(Some(event_id)) => record_sync_success(&db, id, SyncAction::Update, event_id).await,
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Failed to sync update to Google Calendar: {}", e);
+                    record_sync_failure(&db, id, SyncAction::Update, e).await;
+                }
             }
         }
     });
@@ -110,19 +143,208 @@ pub async fn check_conflicts(
 #[tauri::command]
 pub async fn duplicate_appointment(
     pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
     input: DuplicateAppointmentInput,
     created_by: Option<String>,
 ) -> Result<Appointment, String> {
-    let created_by = created_by.unwrap_or_else(|| "system".to_string());
+    let created_by = created_by
+        .or_else(|| current_user.actor())
+        .unwrap_or_else(|| "system".to_string());
     AppointmentService::duplicate_appointment(&pool, input, created_by).await
 }
 
+/// Appointments for the day/week/month containing `anchor_date`, bucketed
+/// by day in the practice timezone with overlap lanes assigned.
+#[tauri::command]
+pub async fn get_calendar_view(
+    pool: State<'_, SeaOrmPool>,
+    view: CalendarViewMode,
+    anchor_date: NaiveDate,
+    room_ids: Option<Vec<i64>>,
+) -> Result<CalendarView, String> {
+    let room_ids = room_ids.unwrap_or_default();
+    match SettingsService::get_practice_timezone(&pool, "default").await? {
+        Some(tz) => AppointmentService::get_calendar_view(&pool, view, anchor_date, &room_ids, &tz).await,
+        None => AppointmentService::get_calendar_view(&pool, view, anchor_date, &room_ids, &Local).await,
+    }
+}
+
+/// Render the printable day sheet for `date` to a temp PDF and return its
+/// path. Several `room_ids` group the sheet by room; cancelled appointments
+/// are only listed with `include_cancelled`.
+#[tauri::command]
+pub async fn export_day_sheet_pdf(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    date: NaiveDate,
+    room_ids: Option<Vec<i64>>,
+    include_cancelled: Option<bool>,
+) -> Result<String, String> {
+    let room_ids = room_ids.unwrap_or_default();
+    let include_cancelled = include_cancelled.unwrap_or(false);
+    let sections = match SettingsService::get_practice_timezone(&pool, "default").await? {
+        Some(tz) => AppointmentService::get_day_sheet(&pool, date, &room_ids, include_cancelled, &tz).await?,
+        None => AppointmentService::get_day_sheet(&pool, date, &room_ids, include_cancelled, &Local).await?,
+    };
+
+    let output_path = std::env::temp_dir().join(format!(
+        "day_sheet_{}_{}.pdf",
+        date,
+        Utc::now().timestamp_millis()
+    ));
+    let output_path = output_path.to_str().ok_or("Invalid PDF path")?.to_string();
+
+    JavaPdfService::generate_day_sheet(
+        &app_handle,
+        &output_path,
+        &date.format("%d/%m/%Y").to_string(),
+        &sections,
+    )?;
+
+    Ok(output_path)
+}
+
+/// Appointment counts and durations for a period, read in the practice
+/// timezone (the system's when none is set).
+#[tauri::command]
+pub async fn get_appointment_statistics(
+    pool: State<'_, SeaOrmPool>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    group_by: AppointmentStatsGroupBy,
+) -> Result<AppointmentStatistics, String> {
+    match SettingsService::get_practice_timezone(&pool, "default").await? {
+        Some(tz) => AppointmentService::get_appointment_statistics(&pool, from, to, group_by, &tz).await,
+        None => AppointmentService::get_appointment_statistics(&pool, from, to, group_by, &Local).await,
+    }
+}
+
+/// Check a batch of appointments to import without writing anything; the
+/// report lists, per entry, what has to be fixed before importing.
+#[tauri::command]
+pub async fn validate_appointment_import(
+    pool: State<'_, SeaOrmPool>,
+    entries: Vec<AppointmentImportEntry>,
+) -> Result<AppointmentImportReport, String> {
+    AppointmentService::validate_appointment_import(&pool, &entries).await
+}
+
+/// Earliest free slot of `duration_minutes` at or after `after`, searching
+/// `search_days` days in the practice's working hours. Without `room_id` every
+/// active room is considered.
+#[tauri::command]
+pub async fn find_next_available_slot(
+    pool: State<'_, SeaOrmPool>,
+    room_id: Option<i64>,
+    duration_minutes: i64,
+    after: DateTime<Utc>,
+    search_days: u32,
+) -> Result<Option<AvailableSlot>, String> {
+    match SettingsService::get_practice_timezone(&pool, "default").await? {
+        Some(tz) => {
+            AppointmentService::find_next_available_slot(&pool, room_id, duration_minutes, after, search_days, &tz).await
+        }
+        None => {
+            AppointmentService::find_next_available_slot(&pool, room_id, duration_minutes, after, search_days, &Local).await
+        }
+    }
+}
+
+/// Move a room's appointments from `from_date` on to another room, e.g. when
+/// decommissioning it. Appointments that don't fit are reported and left in
+/// place; `deactivate_source` retires the room once it's empty.
+#[tauri::command]
+pub async fn reassign_room_appointments(
+    pool: State<'_, SeaOrmPool>,
+    from_room_id: i64,
+    to_room_id: i64,
+    from_date: DateTime<Utc>,
+    deactivate_source: Option<bool>,
+) -> Result<RoomReassignmentResult, String> {
+    let result = AppointmentService::reassign_room_appointments(
+        &pool,
+        from_room_id,
+        to_room_id,
+        from_date,
+        deactivate_source.unwrap_or(false),
+    ).await?;
+
+    // Push the new rooms to Google Calendar if enabled (non-blocking)
+    let db = pool.inner().clone();
+    let moved = result.moved_appointment_ids.clone();
+    tokio::spawn(async move {
+        for id in moved {
+            match trigger_sync_after_update(db.clone(), id).await {
+                Ok(Some(event_id)) => record_sync_success(&db, id, SyncAction::Update, event_id).await,
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Failed to sync room change to Google Calendar: {}", e);
+                    record_sync_failure(&db, id, SyncAction::Update, e).await;
+                }
+            }
+        }
+    });
+
+    Ok(result)
+}
+
+/// Re-push appointments whose last Google Calendar sync failed.
+#[tauri::command]
+pub async fn retry_failed_syncs(
+    pool: State<'_, SeaOrmPool>,
+    limit: Option<i64>,
+) -> Result<SyncRetrySummary, String> {
+    SyncService::retry_failed_syncs(pool.inner(), &GoogleCalendarPusher, limit.unwrap_or(50)).await
+}
+
 // ===== Google Calendar Sync Helpers (using SeaORM) =====
 
+async fn record_sync_failure(
+    db: &DatabaseConnection,
+    appointment_id: i64,
+    action: SyncAction,
+    error: String,
+) {
+    if let Err(e) = SyncService::record_appointment_sync(db, appointment_id, action, SyncStatus::Failed, None, Some(error)).await {
+        log::warn!("Failed to record sync failure for appointment {}: {}", appointment_id, e);
+    }
+}
+
+async fn record_sync_success(
+    db: &DatabaseConnection,
+    appointment_id: i64,
+    action: SyncAction,
+    event_id: String,
+) {
+    if let Err(e) = SyncService::record_appointment_sync(db, appointment_id, action, SyncStatus::Success, Some(event_id), None).await {
+        log::warn!("Failed to record sync success for appointment {}: {}", appointment_id, e);
+    }
+}
+
+/// Pushes through the Google Calendar API, same as the post-save triggers.
+pub struct GoogleCalendarPusher;
+
+impl AppointmentPusher for GoogleCalendarPusher {
+    async fn push(
+        &self,
+        db: &Arc<DatabaseConnection>,
+        appointment_id: i64,
+        action: SyncAction,
+    ) -> Result<Option<String>, String> {
+        match action {
+            SyncAction::Create => trigger_sync_after_create(db.clone(), appointment_id).await,
+            SyncAction::Update => trigger_sync_after_update(db.clone(), appointment_id).await,
+            SyncAction::Delete => trigger_sync_after_cancel(db.clone(), appointment_id).await,
+        }
+    }
+}
+
+// The triggers return the Google event id they wrote, or None when sync is
+// off or there is nothing to do; callers record the outcome.
 async fn trigger_sync_after_create(
     db: Arc<DatabaseConnection>,
     appointment_id: i64,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     // Check if sync is enabled
     let row = db.query_one(Statement::from_string(
         DbBackend::Sqlite,
@@ -133,7 +355,7 @@ async fn trigger_sync_after_create(
 
     let row = match row {
         Some(r) => r,
-        None => return Ok(()), // Sync not enabled
+        None => return Ok(None), // Sync not enabled
     };
 
     let calendar_id: String = row.try_get("", "calendar_id")
@@ -174,6 +396,7 @@ async fn trigger_sync_after_create(
     }
 
     let event_description = desc_parts.join("\n");
+    let time_zone = SettingsService::practice_timezone_name(&db, "default").await;
 
     // Create event in Google Calendar
     let client = reqwest::Client::new();
@@ -182,11 +405,11 @@ async fn trigger_sync_after_create(
         "description": event_description,
         "start": {
             "dateTime": appointment.appointment.start_time,
-            "timeZone": "America/New_York"
+            "timeZone": time_zone
         },
         "end": {
             "dateTime": appointment.appointment.end_time,
-            "timeZone": "America/New_York"
+            "timeZone": time_zone
         }
     });
 
@@ -217,18 +440,18 @@ async fn trigger_sync_after_create(
     db.execute(Statement::from_sql_and_values(
         DbBackend::Sqlite,
         "INSERT INTO calendar_event_mappings (appointment_id, event_id, calendar_id, last_synced_at) VALUES (?, ?, ?, ?)",
-        [appointment_id.into(), event_id.into(), calendar_id.into(), Utc::now().to_rfc3339().into()]
+        [appointment_id.into(), event_id.clone().into(), calendar_id.into(), Utc::now().to_rfc3339().into()]
     ))
     .await
     .map_err(|e| format!("Failed to save event mapping: {}", e))?;
 
-    Ok(())
+    Ok(Some(event_id))
 }
 
 async fn trigger_sync_after_update(
     db: Arc<DatabaseConnection>,
     appointment_id: i64,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     // Check if sync is enabled
     let row = db.query_one(Statement::from_string(
         DbBackend::Sqlite,
@@ -239,7 +462,7 @@ async fn trigger_sync_after_update(
 
     let row = match row {
         Some(r) => r,
-        None => return Ok(()), // Sync not enabled
+        None => return Ok(None), // Sync not enabled
     };
 
     let calendar_id: String = row.try_get("", "calendar_id")
@@ -297,6 +520,7 @@ async fn trigger_sync_after_update(
     }
 
     let event_description = desc_parts.join("\n");
+    let time_zone = SettingsService::practice_timezone_name(&db, "default").await;
 
     // Update event in Google Calendar
     let client = reqwest::Client::new();
@@ -305,11 +529,11 @@ async fn trigger_sync_after_update(
         "description": event_description,
         "start": {
             "dateTime": appointment.appointment.start_time,
-            "timeZone": "America/New_York"
+            "timeZone": time_zone
         },
         "end": {
             "dateTime": appointment.appointment.end_time,
-            "timeZone": "America/New_York"
+            "timeZone": time_zone
         }
     });
 
@@ -335,13 +559,13 @@ async fn trigger_sync_after_update(
     .await
     .map_err(|e| format!("Failed to update sync timestamp: {}", e))?;
 
-    Ok(())
+    Ok(Some(event_id))
 }
 
 async fn trigger_sync_after_cancel(
     db: Arc<DatabaseConnection>,
     appointment_id: i64,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     // Check if sync is enabled
     let row = db.query_one(Statement::from_string(
         DbBackend::Sqlite,
@@ -352,7 +576,7 @@ async fn trigger_sync_after_cancel(
 
     let row = match row {
         Some(r) => r,
-        None => return Ok(()), // Sync not enabled
+        None => return Ok(None), // Sync not enabled
     };
 
     let calendar_id: String = row.try_get("", "calendar_id")
@@ -372,7 +596,7 @@ async fn trigger_sync_after_cancel(
 
     let event_id: String = match mapping {
         Some(m) => m.try_get("", "event_id").map_err(|_| "Event ID not found".to_string())?,
-        None => return Ok(()), // No event to delete
+        None => return Ok(None), // No event to delete
     };
 
     // Delete event from Google Calendar
@@ -398,7 +622,7 @@ async fn trigger_sync_after_cancel(
     .await
     .map_err(|e| format!("Failed to delete event mapping: {}", e))?;
 
-    Ok(())
+    Ok(Some(event_id))
 }
 
 // Helper to fetch appointment detail using SeaORM
@@ -490,6 +714,8 @@ async fn fetch_appointment_detail_seaorm(
             capacity: r.try_get("", "capacity").unwrap_or(1),
             color: r.try_get("", "color").unwrap_or_else(|_| "#1890ff".to_string()),
             is_active: r.try_get("", "is_active").unwrap_or(true),
+            display_order: r.try_get("", "display_order").unwrap_or(0),
+            buffer_minutes: r.try_get("", "buffer_minutes").unwrap_or(0),
             created_at: r.try_get("", "created_at").unwrap_or_default(),
             updated_at: r.try_get("", "updated_at").unwrap_or_default(),
         })
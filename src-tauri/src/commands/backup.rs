@@ -1,5 +1,5 @@
 use crate::database::SeaOrmPool;
-use crate::services::backup::{BackupConfig, BackupService};
+use crate::services::backup::{BackupConfig, BackupHistoryEntry, BackupService};
 use std::path::PathBuf;
 use tauri::{AppHandle, State};
 
@@ -29,3 +29,36 @@ pub async fn run_backup_now(
     let dir = app_data_dir(&app)?;
     BackupService::run_backup(&dir, &pool).await
 }
+
+#[tauri::command]
+pub fn set_auto_backup(
+    app: AppHandle,
+    enabled: bool,
+    interval_hours: Option<u32>,
+    keep_count: Option<u32>,
+) -> Result<BackupConfig, String> {
+    let dir = app_data_dir(&app)?;
+    BackupService::set_auto_backup(&dir, enabled, interval_hours, keep_count)
+}
+
+#[tauri::command]
+pub async fn get_backup_history(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<BackupHistoryEntry>, String> {
+    BackupService::get_history(&pool).await
+}
+
+/// Stage the snapshot and restart; the swap happens at startup before the
+/// DB pool is opened.
+#[tauri::command]
+pub async fn restore_from_backup(
+    app: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    id: i64,
+) -> Result<(), String> {
+    let dir = app_data_dir(&app)?;
+    let entry = BackupService::stage_restore(&dir, &pool, id).await?;
+    log::info!("Backup: restore of {} staged, restarting", entry.file_path);
+    app.restart();
+    Ok(())
+}
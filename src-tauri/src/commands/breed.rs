@@ -1,4 +1,4 @@
-use crate::models::breed::{Breed, BreedFilter, CreateBreedInput, UpdateBreedInput};
+use crate::models::breed::{Breed, BreedFilter, CreateBreedInput, DuplicateBreedCluster, UpdateBreedInput};
 use crate::services::breed::BreedService;
 use crate::database::SeaOrmPool;
 use tauri::State;
@@ -12,6 +12,18 @@ pub async fn get_breeds(
     BreedService::get_all(&pool, filter.species_id, active_only).await
 }
 
+/// Breeds of a species matching what has been typed so far, for the
+/// patient form's breed autocomplete.
+#[tauri::command]
+pub async fn suggest_breeds(
+    pool: State<'_, SeaOrmPool>,
+    species_id: i64,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<Breed>, String> {
+    BreedService::suggest(&pool, species_id, &prefix, limit.unwrap_or(10)).await
+}
+
 #[tauri::command]
 pub async fn get_breed(pool: State<'_, SeaOrmPool>, id: i64) -> Result<Breed, String> {
     BreedService::get_by_id(&pool, id).await
@@ -46,3 +58,24 @@ pub async fn delete_breed(
         BreedService::soft_delete(&pool, id).await
     }
 }
+
+/// Groups of a species' breeds with similar names ("Lab" / "Labrador"),
+/// for cleaning up duplicates left by imports and free typing.
+#[tauri::command]
+pub async fn find_duplicate_breeds(
+    pool: State<'_, SeaOrmPool>,
+    species_id: i64,
+) -> Result<Vec<DuplicateBreedCluster>, String> {
+    BreedService::find_duplicates(&pool, species_id).await
+}
+
+/// Move the patients of breed `merge_id` to `keep_id` and delete
+/// `merge_id`. Returns the number of patients moved.
+#[tauri::command]
+pub async fn merge_breeds(
+    pool: State<'_, SeaOrmPool>,
+    keep_id: i64,
+    merge_id: i64,
+) -> Result<u64, String> {
+    BreedService::merge(&pool, keep_id, merge_id).await
+}
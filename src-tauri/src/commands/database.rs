@@ -1,5 +1,11 @@
-use tauri::AppHandle;
-use crate::database::{connection::create_pool, migrations::run_migrations};
+use tauri::{AppHandle, State};
+use crate::database::{connection::create_pool, migrations::run_migrations, SeaOrmPool};
+use crate::services::data_integrity::{
+    AttachmentIntegrityReport, DataIntegrityService, IntegrityReport, IntegrityRepairOptions,
+    PrimaryFlagRepairReport,
+};
+use crate::services::file_storage::FileStorageService;
+use crate::services::storage_usage::{StorageBreakdown, StorageUsageService};
 
 /// Initialize/reinitialize the database (admin command)
 /// Creates its own connection for DDL operations
@@ -46,3 +52,48 @@ pub async fn test_database_connection(app: AppHandle) -> Result<String, String>
 
     Ok("Database connection successful".to_string())
 }
+
+/// Report references that have drifted (see `DataIntegrityService`) and fix
+/// the categories enabled in `repair`. Without `repair` nothing is changed.
+#[tauri::command]
+pub async fn run_data_integrity_scan(
+    app: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    repair: Option<IntegrityRepairOptions>,
+) -> Result<IntegrityReport, String> {
+    let storage_dir = FileStorageService::get_storage_dir(&app)?;
+    DataIntegrityService::run_scan(&pool, &storage_dir, &repair.unwrap_or_default()).await
+}
+
+/// Re-hash stored attachment files and report those that are missing or no
+/// longer match their upload. Limited to one patient when `patient_id` is set.
+#[tauri::command]
+pub async fn verify_attachments_integrity(
+    app: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    patient_id: Option<i64>,
+) -> Result<AttachmentIntegrityReport, String> {
+    let storage_dir = FileStorageService::get_storage_dir(&app)?;
+    DataIntegrityService::verify_attachment_hashes(&pool, &storage_dir, patient_id).await
+}
+
+/// Attachment storage by type, the `top_n` largest attachments (10 by
+/// default) and bytes held by orphaned files.
+#[tauri::command]
+pub async fn get_storage_breakdown(
+    app: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    top_n: Option<i64>,
+) -> Result<StorageBreakdown, String> {
+    let storage_dir = FileStorageService::get_storage_dir(&app)?;
+    StorageUsageService::get_breakdown(&pool, &storage_dir, top_n.unwrap_or(10)).await
+}
+
+/// Leave exactly one primary person per household, contact per person and
+/// type, and household per patient. Returns what was changed.
+#[tauri::command]
+pub async fn repair_primary_flags(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<PrimaryFlagRepairReport, String> {
+    DataIntegrityService::repair_primary_flags(&pool).await
+}
@@ -1,6 +1,13 @@
-use crate::services::device_input::{scan_ports, start_listen, stop_listen, get_all_connection_statuses, enrich_port_info_with_device_names, PortInfo, DeviceConnectionStatus, ConnectionState};
+use crate::services::device_input::{scan_ports, start_listen, stop_listen, get_all_connection_statuses, dismiss_connection_status, active_listener_integration_ids, enrich_port_info_with_device_names, get_device_protocol_defaults as protocol_defaults, PortInfo, DeviceConnectionStatus, DeviceProtocolDefaults, ConnectionState};
 use crate::services::file_watcher::{get_all_file_watcher_statuses, FileWatcherStatus};
+use crate::services::device_data_buffer::{
+    get_last_device_result as last_device_result, get_recent_device_data as recent_device_data,
+    RECENT_DEVICE_DATA_CAPACITY,
+};
+use crate::services::device_raw_capture::{self, RawCapture, RAW_CAPTURE_DEFAULT_BYTES};
+use crate::services::device_parser::{DeviceData, DeviceParserService, DevicePayloadValidation, ResultParameter};
 use crate::services::device_integration::DeviceIntegrationService;
+use crate::services::patient::PatientService;
 use crate::models::device_integration::ConnectionType;
 use crate::database::SeaOrmPool;
 use crate::models::Patient;
@@ -21,12 +28,83 @@ pub fn get_device_connection_statuses() -> Vec<DeviceConnectionStatus> {
     get_all_connection_statuses()
 }
 
+/// Dismiss the stored connection status of an integration (e.g. a stale
+/// error after the device recovered) without restarting its listener. The
+/// frontend gets a disconnected status for each cleared port.
+#[tauri::command]
+pub fn clear_device_connection_status(app_handle: AppHandle, integration_id: i64) -> usize {
+    dismiss_connection_status(&app_handle, integration_id)
+}
+
+/// Start teeing the raw bytes of an integration's running listener into a
+/// bounded buffer (the newest `max_bytes`), for protocol debugging. Only
+/// in debug builds or with ARKIVET_DEVICE_DEBUG=1; stops recording by
+/// itself after ten minutes
+#[tauri::command]
+pub fn start_raw_capture(integration_id: i64, max_bytes: Option<usize>) -> Result<RawCapture, String> {
+    if !active_listener_integration_ids().contains(&integration_id) {
+        return Err(format!("Integration {} has no running listener", integration_id));
+    }
+    device_raw_capture::start_raw_capture(integration_id, max_bytes.unwrap_or(RAW_CAPTURE_DEFAULT_BYTES))
+}
+
+/// The raw bytes captured so far, hex-dumped. None without a capture
+#[tauri::command]
+pub fn get_raw_capture(integration_id: i64) -> Option<RawCapture> {
+    device_raw_capture::get_raw_capture(integration_id)
+}
+
+/// Stop a raw capture, returning what it held
+#[tauri::command]
+pub fn stop_raw_capture(integration_id: i64) -> Option<RawCapture> {
+    device_raw_capture::stop_raw_capture(integration_id)
+}
+
 /// Get all file watcher statuses
 #[tauri::command]
 pub fn get_file_watcher_statuses() -> Vec<FileWatcherStatus> {
     get_all_file_watcher_statuses()
 }
 
+/// Latest parsed results for an integration, newest first, so a screen
+/// opened after `device-data-received` fired can backfill
+#[tauri::command]
+pub fn get_recent_device_data(integration_id: i64, limit: Option<usize>) -> Vec<DeviceData> {
+    recent_device_data(integration_id, limit.unwrap_or(RECENT_DEVICE_DATA_CAPACITY))
+}
+
+/// An integration's most recent parsed result, with when it arrived and
+/// its patient identifier, for the "last reading" preview. None until the
+/// integration produces a result after startup
+#[tauri::command]
+pub fn get_last_device_result(integration_id: i64) -> Option<DeviceData> {
+    last_device_result(integration_id)
+}
+
+/// Check a captured device payload for missing or unexpected parameters
+#[tauri::command]
+pub fn validate_device_payload(
+    device_type: String,
+    raw_bytes: Vec<u8>,
+    connection_method: String,
+) -> Result<DevicePayloadValidation, String> {
+    DeviceParserService::validate_device_payload(&device_type, &raw_bytes, &connection_method)
+}
+
+/// Code, name and unit of each parameter in a device type's results, in the
+/// order the PDF report prints them
+#[tauri::command]
+pub fn get_device_result_schema(device_type: String) -> Result<Vec<ResultParameter>, String> {
+    DeviceParserService::result_schema(&device_type).map(|parameters| parameters.to_vec())
+}
+
+/// Default baud rate and framing of a device type, plus the list of
+/// supported types, for the device setup form
+#[tauri::command]
+pub fn get_device_protocol_defaults(device_type: String) -> Result<DeviceProtocolDefaults, String> {
+    protocol_defaults(&device_type)
+}
+
 /// Start listening to a device integration's serial port
 #[tauri::command]
 pub async fn start_device_integration_listener(
@@ -364,6 +442,7 @@ fn row_to_patient(row: &sea_orm::QueryResult) -> Result<Patient, String> {
     let date_of_birth: Option<NaiveDate> = row.try_get::<Option<String>>("", "date_of_birth")
         .unwrap_or(None)
         .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok());
+    let age = PatientService::age_on(date_of_birth, chrono::Local::now().date_naive());
 
     // Parse created_at and updated_at from string to DateTime<Utc>
     let created_at: DateTime<Utc> = row.try_get::<Option<String>>("", "created_at")
@@ -395,6 +474,8 @@ fn row_to_patient(row: &sea_orm::QueryResult) -> Result<Patient, String> {
         created_at,
         updated_at,
         microchip_id: row.try_get("", "microchip_id").unwrap_or(None),
+        age_months: age.as_ref().map(|(months, _)| *months),
+        age_display: age.map(|(_, display)| display),
     })
 }
 
@@ -1,9 +1,16 @@
 use crate::database::SeaOrmPool;
 use crate::models::device_integration::{
-    DeviceIntegration, CreateDeviceIntegrationInput, UpdateDeviceIntegrationInput, ConnectionType
+    DeviceIntegration, CreateDeviceIntegrationInput, UpdateDeviceIntegrationInput, ConnectionType,
+    DeviceParseMapping, SaveDeviceParseMappingInput,
 };
-use crate::services::device_integration::DeviceIntegrationService;
-use crate::services::device_input::{start_listen, stop_listen};
+use crate::services::device_integration::{DeviceIntegrationService, DeviceIntegrationWithStatus};
+use crate::services::device_parse_mapping::DeviceParseMappingService;
+use crate::services::device_parser::DeviceData;
+use crate::services::device_input::{
+    active_listener_integration_ids, get_all_connection_statuses, start_listen, stop_listen,
+};
+use crate::services::file_watcher::{get_all_file_watcher_statuses, FileWatcherState};
+use crate::services::device_data_buffer::clear_device_data;
 use tauri::{State, AppHandle};
 
 #[tauri::command]
@@ -13,6 +20,23 @@ pub async fn get_device_integrations(
     DeviceIntegrationService::get_all(&pool).await
 }
 
+/// Integrations for the list screen, each with its live connection status
+/// and whether a listener (or file watcher) is running for it.
+#[tauri::command]
+pub async fn get_device_integrations_with_status(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<DeviceIntegrationWithStatus>, String> {
+    let mut active_ids = active_listener_integration_ids();
+    active_ids.extend(
+        get_all_file_watcher_statuses()
+            .into_iter()
+            .filter(|w| w.status == FileWatcherState::Watching)
+            .map(|w| w.integration_id),
+    );
+
+    DeviceIntegrationService::get_all_with_status(&pool, get_all_connection_statuses(), &active_ids).await
+}
+
 #[tauri::command]
 pub async fn get_device_integration(
     pool: State<'_, SeaOrmPool>,
@@ -93,7 +117,9 @@ pub async fn delete_device_integration(
         }
     }
 
-    DeviceIntegrationService::delete(&pool, id).await
+    DeviceIntegrationService::delete(&pool, id).await?;
+    clear_device_data(id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -124,3 +150,60 @@ pub async fn toggle_device_integration_enabled(
 
     Ok(integration)
 }
+
+/// Enable or disable an integration and start or stop its listener to
+/// match, returning the resulting connection status.
+#[tauri::command]
+pub async fn set_device_integration_enabled(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    integration_id: i64,
+    enabled: bool,
+) -> Result<DeviceIntegrationWithStatus, String> {
+    DeviceIntegrationService::set_enabled_with_listener(&pool, integration_id, enabled, |integration, port_name| {
+        start_listen(
+            app_handle,
+            port_name.to_string(),
+            integration.device_type.to_db_string().to_string(),
+            integration.id,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_device_parse_mappings(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<DeviceParseMapping>, String> {
+    DeviceParseMappingService::get_all(&pool).await
+}
+
+/// Create or replace the JSON mapping of a device type. When
+/// `sample_payload` is given, every mapped path must exist in it.
+#[tauri::command]
+pub async fn save_device_parse_mapping(
+    pool: State<'_, SeaOrmPool>,
+    input: SaveDeviceParseMappingInput,
+    sample_payload: Option<String>,
+) -> Result<DeviceParseMapping, String> {
+    DeviceParseMappingService::save(&pool, input, sample_payload.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn delete_device_parse_mapping(
+    pool: State<'_, SeaOrmPool>,
+    device_type: String,
+) -> Result<(), String> {
+    DeviceParseMappingService::delete(&pool, &device_type).await
+}
+
+/// Parse a JSON payload with the stored mapping of `device_type`, to try a
+/// mapping out before a device sends real results.
+#[tauri::command]
+pub async fn parse_json_device_payload(
+    pool: State<'_, SeaOrmPool>,
+    device_type: String,
+    payload: String,
+) -> Result<DeviceData, String> {
+    DeviceParseMappingService::parse_payload(&pool, &device_type, payload.as_bytes(), "file_watch").await
+}
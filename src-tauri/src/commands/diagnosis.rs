@@ -5,10 +5,12 @@
 //!   - get_diagnosis(id)                    → Diagnosis
 //!   - create_diagnosis(input)              → Diagnosis
 //!   - update_diagnosis(id, input)          → Diagnosis
-//!   - delete_diagnosis(id, hard_delete?)   → ()
+//!   - delete_diagnosis(id, hard_delete?, force?, reassign_to?) → u64 (links moved/removed)
 //!   - get_diagnoses_for_record(record_id)  → Vec<Diagnosis>
 //!   - set_diagnoses_for_record(record_id, diagnosis_ids) → ()
 //!   - get_diagnoses_for_patient(patient_id) → Vec<Diagnosis> (deduped)
+//!   - add_diagnosis_to_records(diagnosis_id, record_ids) → u64 (links added)
+//!   - remove_diagnosis_from_records(diagnosis_id, record_ids) → u64 (links removed)
 //!
 //! The two record-scoped commands let the medical-record form load and
 //! save its tag set independently of the rest of the record's fields,
@@ -52,16 +54,26 @@ pub async fn update_diagnosis(
     DiagnosisService::update(&pool, id, input).await
 }
 
+/// Soft delete by default. A hard delete of a diagnosis still linked to
+/// records fails unless `force` is set (links removed) or `reassign_to`
+/// names the diagnosis the links should move to.
 #[tauri::command]
 pub async fn delete_diagnosis(
     pool: State<'_, SeaOrmPool>,
     id: i64,
     hard_delete: Option<bool>,
-) -> Result<(), String> {
-    if hard_delete.unwrap_or(false) {
-        DiagnosisService::hard_delete(&pool, id).await
+    force: Option<bool>,
+    reassign_to: Option<i64>,
+) -> Result<u64, String> {
+    if !hard_delete.unwrap_or(false) {
+        DiagnosisService::deactivate(&pool, id).await?;
+        return Ok(0);
+    }
+    if force.unwrap_or(false) || reassign_to.is_some() {
+        DiagnosisService::force_delete(&pool, id, reassign_to).await
     } else {
-        DiagnosisService::deactivate(&pool, id).await
+        DiagnosisService::hard_delete(&pool, id).await?;
+        Ok(0)
     }
 }
 
@@ -89,3 +101,21 @@ pub async fn get_diagnoses_for_patient(
 ) -> Result<Vec<Diagnosis>, String> {
     DiagnosisService::list_for_patient(&pool, patient_id).await
 }
+
+#[tauri::command]
+pub async fn add_diagnosis_to_records(
+    pool: State<'_, SeaOrmPool>,
+    diagnosis_id: i64,
+    medical_record_ids: Vec<i64>,
+) -> Result<u64, String> {
+    DiagnosisService::add_to_records(&pool, diagnosis_id, &medical_record_ids).await
+}
+
+#[tauri::command]
+pub async fn remove_diagnosis_from_records(
+    pool: State<'_, SeaOrmPool>,
+    diagnosis_id: i64,
+    medical_record_ids: Vec<i64>,
+) -> Result<u64, String> {
+    DiagnosisService::remove_from_records(&pool, diagnosis_id, &medical_record_ids).await
+}
@@ -0,0 +1,7 @@
+use crate::services::events::{event_channels, EventChannel};
+
+/// Catalog of the events the backend emits, with an example payload each.
+#[tauri::command]
+pub fn list_event_channels() -> Vec<EventChannel> {
+    event_channels()
+}
@@ -2,8 +2,11 @@
 use crate::database::SeaOrmPool;
 use crate::models::google_calendar::GoogleCalendarSettingsResponse;
 #[allow(unused_imports)]
-use crate::models::sync_log::{SyncLog, SyncDirection, SyncType, SyncStatus};
+use crate::models::sync_log::{AppointmentSyncLog, AppointmentSyncStatus, PendingSyncAppointment, SyncLog, SyncDirection, SyncType, SyncStatus};
+use crate::models::google_calendar::GoogleCalendar;
+use crate::services::google_connection::{GoogleConnectionService, GoogleConnectionTest, SyncCalendarChange};
 use crate::services::oauth::{OAuthFlowState, OAuthService};
+use crate::services::sync::SyncService;
 #[allow(unused_imports)]
 use chrono::Utc;
 use tauri::State;
@@ -281,6 +284,34 @@ pub async fn revoke_google_access(
     Ok(())
 }
 
+/// Check the stored Google credentials by refreshing the token if needed
+/// and listing calendars. Tells "not connected", "refresh failed" and
+/// "connected" apart.
+#[tauri::command]
+pub async fn test_google_connection(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<GoogleConnectionTest, String> {
+    GoogleConnectionService::test_connection(&pool).await
+}
+
+/// Calendars of the connected account, for picking the sync calendar
+#[tauri::command]
+pub async fn list_google_calendars(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<GoogleCalendar>, String> {
+    GoogleConnectionService::list_calendars(&pool).await
+}
+
+/// Sync appointments to `calendar_id`, which must be writable. Mappings to
+/// events in the previous calendar are cleared.
+#[tauri::command]
+pub async fn set_sync_calendar(
+    pool: State<'_, SeaOrmPool>,
+    calendar_id: String,
+) -> Result<SyncCalendarChange, String> {
+    GoogleConnectionService::set_sync_calendar(&pool, &calendar_id).await
+}
+
 // ===== T029: Sync Commands =====
 
 #[tauri::command]
@@ -497,6 +528,32 @@ pub async fn get_sync_history(
     sync_logs
 }
 
+#[tauri::command]
+pub async fn get_appointment_sync_status(
+    pool: State<'_, SeaOrmPool>,
+    appointment_id: i64,
+) -> Result<AppointmentSyncStatus, String> {
+    SyncService::get_appointment_sync_status(&pool, appointment_id).await
+}
+
+/// An appointment's push attempts, oldest first, with their errors.
+#[tauri::command]
+pub async fn get_appointment_sync_log(
+    pool: State<'_, SeaOrmPool>,
+    appointment_id: i64,
+) -> Result<Vec<AppointmentSyncLog>, String> {
+    SyncService::get_appointment_sync_log(&pool, appointment_id).await
+}
+
+/// Appointments the push sync still has to create, update or delete on
+/// Google; empty while sync is off.
+#[tauri::command]
+pub async fn get_appointments_pending_sync(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<PendingSyncAppointment>, String> {
+    SyncService::get_appointments_pending_sync(&pool).await
+}
+
 #[tauri::command]
 pub async fn check_sync_status(
     pool: State<'_, SeaOrmPool>,
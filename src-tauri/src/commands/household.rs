@@ -1,8 +1,11 @@
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 use serde::Deserialize;
 use crate::database::SeaOrmPool;
 use crate::models::household::*;
 use crate::database::queries::{household, household_search};
+use crate::services::events;
+use crate::services::household_export::{HouseholdDataPackage, HouseholdExportService};
+use crate::services::settings::SettingsService;
 use sea_orm::{ConnectionTrait, Statement, DbBackend, Value};
 
 #[allow(dead_code)]
@@ -29,6 +32,17 @@ pub async fn create_household(
     last_name: String,
     contacts: Option<Vec<ContactInfo>>,
 ) -> Result<Household, String> {
+    // Check the first contact's email and phone before anything is written
+    let first_contact = contacts.as_ref().and_then(|c| c.first());
+    let email = match first_contact.and_then(|c| c.email.as_deref()).filter(|e| !e.is_empty()) {
+        Some(email) => Some(validate_contact("email", email)?),
+        None => None,
+    };
+    let phone = match first_contact.and_then(|c| c.phone.as_deref()).filter(|p| !p.is_empty()) {
+        Some(phone) => Some(validate_contact("phone", phone)?),
+        None => None,
+    };
+
     // Create a simple household
     let result = pool.execute(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -73,44 +87,42 @@ pub async fn create_household(
                 let person_id = person_result.last_insert_id() as i64;
 
                 // Add email if provided
-                if let Some(email) = &first_contact.email {
-                    if !email.is_empty() {
-                        pool.execute(Statement::from_sql_and_values(
-                            DbBackend::Sqlite,
-                            "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
-                            [
-                                person_id.into(),
-                                "email".into(),
-                                email.clone().into(),
-                                true.into(),
-                            ]
-                        ))
-                        .await
-                        .map_err(|e| format!("Failed to create email contact: {}", e))?;
-                    }
+                if let Some(email) = &email {
+                    pool.execute(Statement::from_sql_and_values(
+                        DbBackend::Sqlite,
+                        "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
+                        [
+                            person_id.into(),
+                            "email".into(),
+                            email.clone().into(),
+                            true.into(),
+                        ]
+                    ))
+                    .await
+                    .map_err(|e| format!("Failed to create email contact: {}", e))?;
                 }
 
                 // Add phone if provided
-                if let Some(phone) = &first_contact.phone {
-                    if !phone.is_empty() {
-                        pool.execute(Statement::from_sql_and_values(
-                            DbBackend::Sqlite,
-                            "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
-                            [
-                                person_id.into(),
-                                "phone".into(),
-                                phone.clone().into(),
-                                first_contact.email.is_none().into(),  // Primary if no email
-                            ]
-                        ))
-                        .await
-                        .map_err(|e| format!("Failed to create phone contact: {}", e))?;
-                    }
+                if let Some(phone) = &phone {
+                    pool.execute(Statement::from_sql_and_values(
+                        DbBackend::Sqlite,
+                        "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
+                        [
+                            person_id.into(),
+                            "phone".into(),
+                            phone.clone().into(),
+                            first_contact.email.is_none().into(),  // Primary if no email
+                        ]
+                    ))
+                    .await
+                    .map_err(|e| format!("Failed to create phone contact: {}", e))?;
                 }
             }
         }
     }
 
+    household_search::refresh_household_display_name(pool.inner().as_ref(), household_id).await?;
+
     // Fetch and return the created household
     let row = pool.query_one(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -128,7 +140,7 @@ pub async fn create_household(
 pub async fn create_household_with_people(
     pool: State<'_, SeaOrmPool>,
     dto: CreateHouseholdWithPeopleDto,
-) -> Result<HouseholdWithPeople, String> {
+) -> Result<CreateHouseholdWithPeopleResult, String> {
     household::create_household_with_people(&pool, dto).await
 }
 
@@ -186,12 +198,32 @@ pub async fn update_household(
     .await
 }
 
+/// Soft-delete a household so it can be restored; `hard` removes it and its
+/// people, contacts and patient links for good.
 #[tauri::command]
 pub async fn delete_household(
     pool: State<'_, SeaOrmPool>,
     household_id: i32,
+    hard: Option<bool>,
+) -> Result<(), String> {
+    household::delete_household(&pool, household_id, hard.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn restore_household(
+    pool: State<'_, SeaOrmPool>,
+    household_id: i32,
 ) -> Result<(), String> {
-    household::delete_household(&pool, household_id).await
+    household::restore_household(&pool, household_id).await
+}
+
+/// Permanently remove households deleted more than `older_than_days` ago.
+#[tauri::command]
+pub async fn purge_households(
+    pool: State<'_, SeaOrmPool>,
+    older_than_days: i64,
+) -> Result<usize, String> {
+    household::purge_households(&pool, older_than_days).await
 }
 
 #[tauri::command]
@@ -204,6 +236,25 @@ pub async fn quick_search_households(
     household_search::quick_search_households(&pool, &query, limit).await
 }
 
+#[tauri::command]
+pub async fn search_households_by_phone(
+    pool: State<'_, SeaOrmPool>,
+    partial: String,
+) -> Result<Vec<HouseholdSearchResult>, String> {
+    household_search::search_households_by_phone(&pool, &partial).await
+}
+
+/// Everything held about the household, for an owner's data-access
+/// request. Soft-deleted data is only included with `include_deleted`.
+#[tauri::command]
+pub async fn export_household_data_package(
+    pool: State<'_, SeaOrmPool>,
+    household_id: i32,
+    include_deleted: Option<bool>,
+) -> Result<HouseholdDataPackage, String> {
+    HouseholdExportService::export_data_package(&pool, household_id, include_deleted.unwrap_or(false)).await
+}
+
 #[tauri::command]
 pub async fn get_all_households(
     pool: State<'_, SeaOrmPool>,
@@ -253,11 +304,48 @@ pub async fn get_all_households(
     Ok(households)
 }
 
+/// Rebuild the whole household search index in batches, emitting
+/// `household-search-reindex-progress` after each; returns how many
+/// households were indexed.
 #[tauri::command]
 pub async fn rebuild_household_search_index(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+) -> Result<usize, String> {
+    household_search::rebuild_search_index(&pool, |progress| {
+        let _ = app_handle.emit_all(events::HOUSEHOLD_SEARCH_REINDEX_PROGRESS, progress);
+    })
+    .await
+}
+
+/// Refresh one household's search row from its current people and contacts.
+#[tauri::command]
+pub async fn rebuild_household_search_index_for(
+    pool: State<'_, SeaOrmPool>,
+    household_id: i64,
+) -> Result<(), String> {
+    household_search::reindex_household(pool.inner().as_ref(), household_id).await
+}
+
+/// Recompute every household's search display name; returns how many
+/// changed.
+#[tauri::command]
+pub async fn recompute_household_display_names(
     pool: State<'_, SeaOrmPool>,
+) -> Result<usize, String> {
+    household_search::recompute_household_display_names(&pool).await
+}
+
+/// Recreate the household search index with another FTS5 tokenizer
+/// ("unicode61" or "porter") and remember the choice in settings.
+#[tauri::command]
+pub async fn rebuild_household_search_index_with_tokenizer(
+    pool: State<'_, SeaOrmPool>,
+    tokenizer: String,
 ) -> Result<(), String> {
-    household_search::rebuild_search_index(&pool).await
+    household_search::rebuild_search_index_with_tokenizer(&pool, &tokenizer).await?;
+    SettingsService::set_search_tokenizer(&pool, "default", &tokenizer).await?;
+    Ok(())
 }
 
 // New commands for household detail view
@@ -277,7 +365,8 @@ pub async fn get_household_detail(
         DbBackend::Sqlite,
         r#"
         SELECT p.id, p.name, s.name as species, b.name as breed, p.date_of_birth,
-               CAST(p.weight AS REAL) as weight, p.gender
+               CAST(p.weight AS REAL) as weight, p.gender,
+               ph.relationship_type, ph.is_primary
         FROM patients p
         JOIN patient_households ph ON p.id = ph.patient_id
         LEFT JOIN species s ON p.species_id = s.id
@@ -300,6 +389,9 @@ pub async fn get_household_detail(
             let date_of_birth: Option<String> = row.try_get("", "date_of_birth").ok();
             let weight: Option<f64> = row.try_get("", "weight").ok();
             let gender: Option<String> = row.try_get("", "gender").ok();
+            let relationship_type: Option<String> = row.try_get("", "relationship_type").ok();
+            // False when this is one of the patient's secondary households
+            let is_primary_household: bool = row.try_get("", "is_primary").unwrap_or(false);
 
             serde_json::json!({
                 "id": id,
@@ -309,6 +401,8 @@ pub async fn get_household_detail(
                 "dateOfBirth": date_of_birth,
                 "weight": weight,
                 "gender": gender,
+                "relationshipType": relationship_type,
+                "isPrimaryHousehold": is_primary_household,
                 "status": "active" // Default to active for now
             })
         })
@@ -393,6 +487,11 @@ pub async fn add_person_to_household(
     household_id: i32,
     person: CreatePersonWithContactsDto,
 ) -> Result<PersonWithContacts, String> {
+    let contact_values = person.contacts
+        .iter()
+        .map(|c| validate_contact(&c.contact_type, &c.contact_value))
+        .collect::<Result<Vec<_>, _>>()?;
+
     // Create person
     let person_result = pool.execute(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -410,14 +509,14 @@ pub async fn add_person_to_household(
     let person_id = person_result.last_insert_id() as i32;
 
     // Add contacts
-    for contact in &person.contacts {
+    for (contact, contact_value) in person.contacts.iter().zip(contact_values) {
         pool.execute(Statement::from_sql_and_values(
             DbBackend::Sqlite,
             "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
             [
                 person_id.into(),
                 contact.contact_type.clone().into(),
-                contact.contact_value.clone().into(),
+                contact_value.into(),
                 contact.is_primary.unwrap_or(false).into(),
             ]
         ))
@@ -437,37 +536,7 @@ pub async fn update_person(
     person_id: i32,
     updates: serde_json::Value,
 ) -> Result<PersonWithContacts, String> {
-    // Build dynamic update query
-    let mut query_parts = Vec::new();
-    let mut params: Vec<Value> = Vec::new();
-
-    if let Some(first_name) = updates.get("firstName").and_then(|v| v.as_str()) {
-        query_parts.push("first_name = ?");
-        params.push(first_name.to_string().into());
-    }
-
-    if let Some(last_name) = updates.get("lastName").and_then(|v| v.as_str()) {
-        query_parts.push("last_name = ?");
-        params.push(last_name.to_string().into());
-    }
-
-    if let Some(is_primary) = updates.get("isPrimary").and_then(|v| v.as_bool()) {
-        query_parts.push("is_primary = ?");
-        params.push(is_primary.into());
-    }
-
-    if !query_parts.is_empty() {
-        let sql = format!(
-            "UPDATE people SET {}, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-            query_parts.join(", ")
-        );
-
-        params.push(person_id.into());
-
-        pool.execute(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, params))
-            .await
-            .map_err(|e| format!("Failed to update person: {}", e))?;
-    }
+    household::update_person(&pool, person_id, &updates).await?;
 
     // Return updated person with contacts
     household::get_person_with_contacts(&pool, person_id)
@@ -518,7 +587,7 @@ pub async fn delete_person(
     .await
     .map_err(|e| format!("Failed to delete person: {}", e))?;
 
-    Ok(())
+    household_search::reindex_household(pool.inner().as_ref(), household_id as i64).await
 }
 
 #[tauri::command]
@@ -527,6 +596,15 @@ pub async fn update_person_contacts(
     person_id: i32,
     contacts: Vec<CreateContactDto>,
 ) -> Result<Vec<PersonContact>, String> {
+    // Reject the whole list before the existing contacts are dropped
+    let contacts = contacts
+        .into_iter()
+        .map(|c| {
+            let contact_value = validate_contact(&c.contact_type, &c.contact_value)?;
+            Ok(CreateContactDto { contact_value, ..c })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
     // Delete existing contacts
     pool.execute(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -552,6 +630,17 @@ pub async fn update_person_contacts(
         .map_err(|e| format!("Failed to create contact: {}", e))?;
     }
 
+    let household_row = pool.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT household_id FROM people WHERE id = ?",
+        [person_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to find person: {}", e))?;
+    if let Some(household_id) = household_row.and_then(|row| row.try_get::<i64>("", "household_id").ok()) {
+        household_search::reindex_household(pool.inner().as_ref(), household_id).await?;
+    }
+
     // Fetch and return all contacts for this person
     let rows = pool.query_all(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -596,7 +685,8 @@ pub async fn get_household_patients(
         DbBackend::Sqlite,
         r#"
         SELECT p.id, p.name, s.name as species, b.name as breed, p.date_of_birth,
-               CAST(p.weight AS REAL) as weight, p.gender
+               CAST(p.weight AS REAL) as weight, p.gender,
+               ph.relationship_type, ph.is_primary
         FROM patients p
         JOIN patient_households ph ON p.id = ph.patient_id
         LEFT JOIN species s ON p.species_id = s.id
@@ -623,6 +713,9 @@ pub async fn get_household_patients(
             let date_of_birth: Option<String> = row.try_get("", "date_of_birth").ok();
             let weight: Option<f64> = row.try_get("", "weight").ok();
             let gender: Option<String> = row.try_get("", "gender").ok();
+            let relationship_type: Option<String> = row.try_get("", "relationship_type").ok();
+            // False when this is one of the patient's secondary households
+            let is_primary_household: bool = row.try_get("", "is_primary").unwrap_or(false);
 
             let json = serde_json::json!({
                 "id": patient_id,
@@ -632,6 +725,8 @@ pub async fn get_household_patients(
                 "dateOfBirth": date_of_birth,
                 "weight": weight,
                 "gender": gender,
+                "relationshipType": relationship_type,
+                "isPrimaryHousehold": is_primary_household,
                 "status": "active" // Default to active for now
             });
 
@@ -672,7 +767,7 @@ pub async fn link_patient_to_household(
     // Check if household exists
     let row = pool.query_one(Statement::from_sql_and_values(
         DbBackend::Sqlite,
-        "SELECT COUNT(*) as count FROM households WHERE id = ?",
+        "SELECT COUNT(*) as count FROM households WHERE id = ? AND deleted_at IS NULL",
         [household_id.into()]
     ))
     .await
@@ -734,6 +829,26 @@ pub async fn link_patient_to_household(
     Ok(())
 }
 
+/// Every household the patient is linked to, primary first.
+#[tauri::command]
+pub async fn get_patient_households(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+) -> Result<Vec<LinkedHousehold>, String> {
+    household::get_patient_households(pool.inner().as_ref(), patient_id).await
+}
+
+/// Link the patient to another household, keeping its primary one.
+#[tauri::command]
+pub async fn add_secondary_household(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    household_id: i32,
+    relationship_type: Option<String>,
+) -> Result<LinkedHousehold, String> {
+    household::add_secondary_household(&pool, patient_id, household_id, relationship_type).await
+}
+
 #[tauri::command]
 pub async fn unlink_patient_from_household(
     pool: State<'_, SeaOrmPool>,
@@ -775,7 +890,7 @@ pub async fn update_patient_household(
         // Check if household exists
         let row = pool.query_one(Statement::from_sql_and_values(
             DbBackend::Sqlite,
-            "SELECT COUNT(*) as count FROM households WHERE id = ?",
+            "SELECT COUNT(*) as count FROM households WHERE id = ? AND deleted_at IS NULL",
             [hh_id.into()]
         ))
         .await
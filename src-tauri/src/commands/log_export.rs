@@ -0,0 +1,29 @@
+use crate::database::SeaOrmPool;
+use crate::services::log_export::{AuditLogFilter, LogExportService};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use tauri::State;
+
+/// Write the audit log to `target_path` as CSV; returns the rows written.
+#[tauri::command]
+pub async fn export_audit_log_csv(
+    pool: State<'_, SeaOrmPool>,
+    target_path: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    filter: Option<AuditLogFilter>,
+) -> Result<usize, String> {
+    let filter = filter.unwrap_or_default();
+    LogExportService::export_audit_log_csv(&pool, Path::new(&target_path), from, to, &filter).await
+}
+
+/// Write the sync log to `target_path` as CSV; returns the rows written.
+#[tauri::command]
+pub async fn export_sync_log_csv(
+    pool: State<'_, SeaOrmPool>,
+    target_path: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<usize, String> {
+    LogExportService::export_sync_log_csv(&pool, Path::new(&target_path), from, to).await
+}
@@ -1,9 +1,18 @@
-use tauri::{AppHandle, State};
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager, State};
 use crate::database::SeaOrmPool;
 use crate::models::medical::*;
-use crate::services::medical_record::MedicalRecordService;
+use crate::services::medical_record::{row_to_record_template, MedicalRecordService};
 use crate::services::file_storage::FileStorageService;
+use crate::services::attachment_ocr::AttachmentOcrService;
+use crate::services::attachment_thumbnails::{AttachmentThumbnailService, PageRenderer, DEFAULT_THUMBNAIL_WIDTH};
+use crate::services::events;
+use crate::services::users::CurrentUser;
+use crate::services::record_lock::lock_record;
+use crate::services::settings::SettingsService;
 use crate::services::pdf_render::PdfRenderService;
+use crate::services::pdf_jobs::{PdfCancelToken, PdfJob};
 use crate::services::device_parser::DeviceParserService;
 use crate::services::device_pdf_service::{DevicePdfService, PatientData, DeviceTestData};
 use sea_orm::{ConnectionTrait, Statement, DbBackend};
@@ -15,8 +24,16 @@ pub async fn get_medical_records(
     patient_id: i64,
     filter: Option<MedicalRecordFilter>,
     pagination: Option<PaginationParams>,
-) -> Result<MedicalRecordsResponse, String> {
-    MedicalRecordService::get_medical_records(&pool, patient_id, filter, pagination).await
+    fields: Option<Vec<String>>,
+) -> Result<MedicalRecordsResult, String> {
+    match fields {
+        Some(fields) => MedicalRecordService::get_medical_record_summaries(&pool, patient_id, filter, pagination, &fields)
+            .await
+            .map(MedicalRecordsResult::Summary),
+        None => MedicalRecordService::get_medical_records(&pool, patient_id, filter, pagination)
+            .await
+            .map(MedicalRecordsResult::Full),
+    }
 }
 
 // T032: Implement get_medical_record command
@@ -41,22 +58,12 @@ pub async fn get_medical_record(
 pub async fn create_medical_record(
     app_handle: AppHandle,
     pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
     input: CreateMedicalRecordInput,
 ) -> Result<MedicalRecord, String> {
-    // Validate input
-    if input.name.is_empty() {
-        return Err("Name is required".to_string());
-    }
-    if input.description.is_empty() {
-        return Err("Description is required".to_string());
-    }
-    if input.record_type != "procedure" && input.record_type != "note" && input.record_type != "test_result" {
-        return Err("Invalid record type".to_string());
-    }
-    // Note: We use the 'name' field for both procedures and notes
-    // No need to check procedure_name separately
+    MedicalRecordService::validate_create_input(&input)?;
 
-    MedicalRecordService::create_medical_record(&app_handle, &pool, input).await
+    MedicalRecordService::create_medical_record(&app_handle, &pool, input, current_user.get().as_ref()).await
 }
 
 // T034: Implement update_medical_record command
@@ -64,6 +71,7 @@ pub async fn create_medical_record(
 pub async fn update_medical_record(
     app_handle: AppHandle,
     pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
     record_id: i64,
     updates: UpdateMedicalRecordInput,
 ) -> Result<MedicalRecord, String> {
@@ -79,7 +87,7 @@ pub async fn update_medical_record(
         }
     }
 
-    MedicalRecordService::update_medical_record(&app_handle, &pool, record_id, updates).await
+    MedicalRecordService::update_medical_record(&app_handle, &pool, record_id, updates, current_user.get().as_ref()).await
 }
 
 // T035: Implement archive_medical_record command
@@ -92,6 +100,93 @@ pub async fn archive_medical_record(
     MedicalRecordService::archive_medical_record(&pool, record_id, archive).await
 }
 
+/// Archive records older than the configured auto-archive threshold now,
+/// instead of waiting for the scheduler.
+#[tauri::command]
+pub async fn apply_auto_archive(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<AutoArchiveResult, String> {
+    let after_days = SettingsService::get_auto_archive_after_days(&pool, "default")
+        .await?
+        .ok_or("Auto-archive is not configured")?;
+    MedicalRecordService::apply_auto_archive(&pool, after_days, chrono::Utc::now()).await
+}
+
+/// Schedule a recheck for a record; `None` clears it.
+#[tauri::command]
+pub async fn set_record_follow_up(
+    pool: State<'_, SeaOrmPool>,
+    record_id: i64,
+    follow_up_date: Option<chrono::NaiveDate>,
+) -> Result<(), String> {
+    MedicalRecordService::set_follow_up(&pool, record_id, follow_up_date).await
+}
+
+#[tauri::command]
+pub async fn complete_record_follow_up(
+    pool: State<'_, SeaOrmPool>,
+    record_id: i64,
+    done: Option<bool>,
+) -> Result<(), String> {
+    MedicalRecordService::set_follow_up_done(&pool, record_id, done.unwrap_or(true)).await
+}
+
+/// Follow-ups due within `within_days` (default 7), overdue ones first.
+#[tauri::command]
+pub async fn get_pending_followups(
+    pool: State<'_, SeaOrmPool>,
+    within_days: Option<i64>,
+) -> Result<Vec<PendingFollowUp>, String> {
+    MedicalRecordService::get_pending_followups(
+        &pool,
+        within_days.unwrap_or(7),
+        chrono::Local::now().date_naive(),
+    ).await
+}
+
+// Convert a record to another record_type (e.g. note -> procedure)
+#[tauri::command]
+pub async fn change_record_type(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    record_id: i64,
+    new_type: String,
+    price: Option<f64>,
+    currency_id: Option<i64>,
+) -> Result<MedicalRecord, String> {
+    MedicalRecordService::change_record_type(&pool, record_id, &new_type, price, currency_id, current_user.get().as_ref()).await
+}
+
+// Re-file wrongly-filed records under another patient; their attachments follow
+#[tauri::command]
+pub async fn move_records_to_patient(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    record_ids: Vec<i64>,
+    target_patient_id: i64,
+    force: Option<bool>,
+) -> Result<MoveRecordsResult, String> {
+    MedicalRecordService::move_records_to_patient(
+        &pool,
+        &record_ids,
+        target_patient_id,
+        force.unwrap_or(false),
+        current_user.get().as_ref(),
+    )
+    .await
+}
+
+// List attachments across all of a patient's records (patient "files" tab)
+#[tauri::command]
+pub async fn get_patient_attachments(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    filter: Option<PatientAttachmentFilter>,
+    pagination: Option<PaginationParams>,
+) -> Result<PatientAttachmentsResponse, String> {
+    MedicalRecordService::get_patient_attachments(&pool, patient_id, filter, pagination).await
+}
+
 // T036: Implement upload_medical_attachment command
 #[tauri::command]
 pub async fn upload_medical_attachment(
@@ -110,6 +205,8 @@ pub async fn upload_medical_attachment(
     // Validate file
     FileStorageService::validate_file(&file_data, &file_name, 100)?;
 
+    let _record_lock = lock_record(medical_record_id).await;
+
     // Check if medical record exists
     let _ = pool.query_one(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -133,6 +230,9 @@ pub async fn upload_medical_attachment(
         attachment_type,
     ).await?;
 
+    // Make scans searchable in the background if OCR is enabled
+    AttachmentOcrService::spawn_for_attachments(app_handle, pool.inner().clone(), vec![attachment.clone()]);
+
     // If this file came from file_access_history, update the tracking
     if let Some(file_id) = source_file_id {
         use crate::commands::file_history::update_file_attachment_internal_seaorm;
@@ -157,6 +257,79 @@ pub async fn upload_medical_attachment(
     Ok(attachment)
 }
 
+// Batch variant of upload_medical_attachment: stores several files (e.g. the
+// pages of a scanned document) in one call and one transaction
+#[tauri::command]
+pub async fn upload_medical_attachments(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    medical_record_id: i64,
+    files: Vec<UploadFile>,
+    skip_invalid: Option<bool>,
+) -> Result<BatchUploadResponse, String> {
+    let _record_lock = lock_record(medical_record_id).await;
+
+    // Check if medical record exists
+    let _ = pool.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT id FROM medical_records WHERE id = ?",
+        [medical_record_id.into()]
+    ))
+    .await
+    .map_err(|_| "Medical record not found".to_string())?
+    .ok_or("Medical record not found".to_string())?;
+
+    let response = FileStorageService::upload_attachments(
+        &app_handle,
+        &pool,
+        medical_record_id,
+        files,
+        100,
+        skip_invalid.unwrap_or(false),
+    ).await?;
+
+    AttachmentOcrService::spawn_for_attachments(app_handle, pool.inner().clone(), response.attachments.clone());
+    Ok(response)
+}
+
+/// Attach a file by reference (e.g. imaging on a network share) without
+/// copying it into app storage.
+#[tauri::command]
+pub async fn attach_external_file(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    medical_record_id: i64,
+    path: String,
+    mime_type: Option<String>,
+) -> Result<MedicalAttachment, String> {
+    let _record_lock = lock_record(medical_record_id).await;
+
+    // Check if medical record exists
+    let _ = pool.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT id FROM medical_records WHERE id = ?",
+        [medical_record_id.into()]
+    ))
+    .await
+    .map_err(|_| "Medical record not found".to_string())?
+    .ok_or("Medical record not found".to_string())?;
+
+    let attachment = FileStorageService::attach_external_file(&pool, medical_record_id, &path, mime_type).await?;
+    AttachmentOcrService::spawn_for_attachments(app_handle, pool.inner().clone(), vec![attachment.clone()]);
+    Ok(attachment)
+}
+
+/// Attachments whose OCR'd text matches `query`. Only attachments uploaded
+/// while OCR was enabled, and whose OCR succeeded, can match.
+#[tauri::command]
+pub async fn search_attachment_text(
+    pool: State<'_, SeaOrmPool>,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<AttachmentTextMatch>, String> {
+    AttachmentOcrService::search(&pool, &query, limit.unwrap_or(20)).await
+}
+
 // T037: Implement download_medical_attachment command
 #[tauri::command]
 pub async fn download_medical_attachment(
@@ -180,6 +353,8 @@ pub async fn delete_medical_attachment(
     pool: State<'_, SeaOrmPool>,
     attachment_id: i64,
 ) -> Result<(), String> {
+    let medical_record_id = FileStorageService::get_attachment_record_id(&pool, attachment_id).await?;
+    let _record_lock = lock_record(medical_record_id).await;
     FileStorageService::delete_attachment(&app_handle, &pool, attachment_id).await
 }
 
@@ -221,6 +396,17 @@ pub async fn search_medical_records(
     })
 }
 
+/// Records of a patient created or updated after `since`, for incremental
+/// refresh of an open patient screen.
+#[tauri::command]
+pub async fn get_records_changed_since(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<MedicalRecordChanges, String> {
+    MedicalRecordService::get_records_changed_since(&pool, patient_id, since).await
+}
+
 // T040: Implement get_currencies command
 #[tauri::command]
 pub async fn get_currencies(
@@ -229,6 +415,57 @@ pub async fn get_currencies(
     MedicalRecordService::get_currencies(&pool).await
 }
 
+/// Store how many `to_currency_id` units one `from_currency_id` unit is worth.
+#[tauri::command]
+pub async fn set_exchange_rate(
+    pool: State<'_, SeaOrmPool>,
+    from_currency_id: i64,
+    to_currency_id: i64,
+    rate: f64,
+) -> Result<(), String> {
+    MedicalRecordService::set_exchange_rate(&pool, from_currency_id, to_currency_id, rate).await
+}
+
+/// A patient's charges per record_type in one currency, optionally limited
+/// to records created between `start_date` and `end_date`.
+#[tauri::command]
+pub async fn get_patient_charge_summary(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    target_currency_id: i64,
+    start_date: Option<chrono::NaiveDate>,
+    end_date: Option<chrono::NaiveDate>,
+) -> Result<PatientChargeSummary, String> {
+    MedicalRecordService::get_patient_charge_summary(
+        &pool,
+        patient_id,
+        target_currency_id,
+        start_date,
+        end_date,
+    ).await
+}
+
+/// The clinic's records of one type created between `from` and `to`, with
+/// patient/owner context and a charge total in one currency.
+#[tauri::command]
+pub async fn list_records_by_type(
+    pool: State<'_, SeaOrmPool>,
+    record_type: String,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+    target_currency_id: i64,
+    pagination: Option<PaginationParams>,
+) -> Result<RecordTypeReport, String> {
+    MedicalRecordService::list_records_by_type(
+        &pool,
+        &record_type,
+        from,
+        to,
+        target_currency_id,
+        pagination,
+    ).await
+}
+
 // Helper command to clean up orphaned files
 #[tauri::command]
 pub async fn cleanup_orphaned_files(
@@ -270,6 +507,17 @@ pub async fn open_medical_attachment(
     FileStorageService::open_path_with_default_app(&path)
 }
 
+// Materialize an attachment and show it selected in the OS file manager
+#[tauri::command]
+pub async fn reveal_medical_attachment(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    attachment_id: i64,
+) -> Result<(), String> {
+    let path = FileStorageService::materialize_attachment(&app_handle, &pool, attachment_id).await?;
+    FileStorageService::reveal_in_file_manager(std::path::Path::new(&path))
+}
+
 // Print a PDF attachment using the system's native print functionality
 #[tauri::command]
 pub async fn print_medical_attachment(
@@ -330,54 +578,58 @@ async fn render_medical_attachment_pdf_thumbnail_with_options(
     log::debug!("render_medical_attachment_pdf_thumbnail called - attachment_id={}, page={:?}, width={:?}, force={}",
         attachment_id, page, width, force_regenerate);
 
-    // First materialize the attachment to a temp file to get a local path
-    let pdf_path = FileStorageService::materialize_attachment(&app_handle, &pool, attachment_id).await?;
-    log::debug!("PDF materialized at: {}", pdf_path);
-
-    // Build preview output path in a stable temp dir
-    let mut tmp_dir = std::env::temp_dir();
-    tmp_dir.push("vet-clinic-attachments");
-    tmp_dir.push("previews");
-    let page_index = page.unwrap_or(1).saturating_sub(1); // 1-based to 0-based
-    let target_width = width.unwrap_or(900);
-    let file_name = format!("attachment_{}_p{}_w{}.png", attachment_id, page_index + 1, target_width);
-    let out_path = tmp_dir.join(file_name);
-
-    // Check if preview exists and is valid (not 0 bytes) unless force regenerate
-    if !force_regenerate && out_path.exists() {
-        if let Ok(metadata) = std::fs::metadata(&out_path) {
-            if metadata.len() > 0 {
-                log::debug!("Using cached preview: {} ({} bytes)", out_path.display(), metadata.len());
-                return Ok(out_path.display().to_string());
-            } else {
-                log::debug!("Cached preview is 0 bytes, regenerating");
-                // Delete the broken file
-                let _ = std::fs::remove_file(&out_path);
-            }
-        }
-    } else if force_regenerate && out_path.exists() {
-        log::debug!("Force regenerating preview, removing old file");
-        let _ = std::fs::remove_file(&out_path);
-    }
-
-    let pdf_path_clone = pdf_path.clone();
-
-    // Render in a blocking thread - use bytes method instead for better reliability
-    let handle_clone = app_handle.clone();
-    let png_bytes = tauri::async_runtime::spawn_blocking(move || {
-        PdfRenderService::render_page_to_png_bytes(&handle_clone, &pdf_path_clone, page_index, target_width)
-    })
+    let storage_dir = FileStorageService::get_storage_dir(&app_handle)?;
+    AttachmentThumbnailService::render_page(
+        &storage_dir,
+        &FileStorageService::materialize_dir(),
+        &pool,
+        attachment_id,
+        page.unwrap_or(1).saturating_sub(1), // 1-based to 0-based
+        width.unwrap_or(DEFAULT_THUMBNAIL_WIDTH),
+        force_regenerate,
+        pdf_page_renderer(&app_handle),
+    )
     .await
-    .map_err(|e| format!("Spawn failed: {}", e))??;
+}
 
-    // Write bytes to output file
-    std::fs::create_dir_all(out_path.parent().unwrap())
-        .map_err(|e| format!("Failed to create preview dir: {}", e))?;
-    std::fs::write(&out_path, &png_bytes)
-        .map_err(|e| format!("Failed to write preview: {}", e))?;
+fn pdf_page_renderer(app_handle: &AppHandle) -> PageRenderer {
+    let handle = app_handle.clone();
+    Arc::new(move |pdf_path: &std::path::Path, page_index: u32, width: u32| {
+        PdfRenderService::render_page_to_png_bytes(&handle, &pdf_path.to_string_lossy(), page_index, width, &PdfCancelToken::none())
+    })
+}
 
-    log::debug!("Wrote {} bytes to {:?}", png_bytes.len(), out_path);
-    Ok(out_path.display().to_string())
+// Render first-page thumbnails for the PDFs of a record or a patient in the
+// background, reporting each on the attachment-thumbnails-progress event.
+// Returns how many PDFs were queued.
+#[tauri::command]
+pub async fn prewarm_attachment_thumbnails(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    record_id: Option<i64>,
+    patient_id: Option<i64>,
+    width: Option<u32>,
+) -> Result<usize, String> {
+    let attachment_ids = AttachmentThumbnailService::pdf_attachment_ids(&pool, record_id, patient_id).await?;
+    let queued = attachment_ids.len();
+    let storage_dir = FileStorageService::get_storage_dir(&app_handle)?;
+    let db = pool.inner().clone();
+    let render = pdf_page_renderer(&app_handle);
+    tauri::async_runtime::spawn(async move {
+        AttachmentThumbnailService::prewarm(
+            storage_dir,
+            FileStorageService::materialize_dir(),
+            db,
+            attachment_ids,
+            width.unwrap_or(DEFAULT_THUMBNAIL_WIDTH),
+            render,
+            |progress| {
+                let _ = app_handle.emit_all(events::ATTACHMENT_THUMBNAILS_PROGRESS, progress);
+            },
+        )
+        .await;
+    });
+    Ok(queued)
 }
 
 // Render a PDF attachment page to PNG bytes (faster & no FS read needed)
@@ -388,16 +640,17 @@ pub async fn render_medical_attachment_pdf_page_png(
     attachment_id: i64,
     page: Option<u32>,
     width: Option<u32>,
+    job_id: Option<String>,
 ) -> Result<Vec<u8>, String> {
-    let pdf_path = FileStorageService::materialize_attachment(&app_handle, &pool, attachment_id).await?;
     let page_index = page.unwrap_or(1).saturating_sub(1);
     let target_width = width.unwrap_or(900);
     let handle_clone = app_handle.clone();
-    tauri::async_runtime::spawn_blocking(move || {
-        PdfRenderService::render_page_to_png_bytes(&handle_clone, &pdf_path, page_index, target_width)
+    let pdf_job = PdfJob::start(job_id);
+    let cancel = pdf_job.token().clone();
+    FileStorageService::with_materialized_attachment(&app_handle, &pool, attachment_id, move |pdf_path| {
+        PdfRenderService::render_page_to_png_bytes(&handle_clone, &pdf_path.to_string_lossy(), page_index, target_width, &cancel)
     })
     .await
-    .map_err(|e| format!("Spawn failed: {}", e))?
 }
 // Get the total page count for a PDF attachment
 #[tauri::command]
@@ -406,12 +659,21 @@ pub async fn get_medical_attachment_pdf_page_count(
     pool: State<'_, SeaOrmPool>,
     attachment_id: i64,
 ) -> Result<u16, String> {
-    let pdf_path = FileStorageService::materialize_attachment(&app_handle, &pool, attachment_id).await?;
     let handle_clone = app_handle.clone();
-    let res = tauri::async_runtime::spawn_blocking(move || PdfRenderService::get_page_count(&handle_clone, &pdf_path))
-        .await
-        .map_err(|e| format!("Spawn failed: {}", e))?;
-    res
+    FileStorageService::with_materialized_attachment(&app_handle, &pool, attachment_id, move |pdf_path| {
+        PdfRenderService::get_page_count(&handle_clone, &pdf_path.to_string_lossy())
+    })
+    .await
+}
+
+// Fields changed by one version of a medical record
+#[tauri::command]
+pub async fn get_medical_record_diff(
+    pool: State<'_, SeaOrmPool>,
+    record_id: i64,
+    version: i32,
+) -> Result<Vec<FieldChange>, String> {
+    MedicalRecordService::get_medical_record_diff(&pool, record_id, version).await
 }
 
 // Revert a medical record one step to previous version
@@ -419,9 +681,29 @@ pub async fn get_medical_attachment_pdf_page_count(
 pub async fn revert_medical_record(
     app_handle: AppHandle,
     pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    record_id: i64,
+) -> Result<MedicalRecord, String> {
+    MedicalRecordService::revert_one_step(&app_handle, &pool, record_id, current_user.get().as_ref()).await
+}
+
+// Revert a medical record to a specific earlier version, saved as a new version
+#[tauri::command]
+pub async fn revert_medical_record_to_version(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
     record_id: i64,
+    version: i32,
 ) -> Result<MedicalRecord, String> {
-    MedicalRecordService::revert_one_step(&app_handle, &pool, record_id).await
+    MedicalRecordService::revert_medical_record_to_version(
+        &app_handle,
+        &pool,
+        record_id,
+        version,
+        current_user.get().as_ref(),
+    )
+    .await
 }
 
 // Get medical record snapshot at a specific version
@@ -463,6 +745,7 @@ pub async fn regenerate_pdf_from_attachment(
 
     let medical_record_id: i64 = attachment_row.try_get("", "medical_record_id")
         .map_err(|e| format!("Failed to get medical_record_id: {}", e))?;
+    let _record_lock = lock_record(medical_record_id).await;
     let device_type: Option<String> = attachment_row.try_get("", "device_type").ok();
     let device_name: Option<String> = attachment_row.try_get("", "device_name").ok();
     let connection_method: Option<String> = attachment_row.try_get("", "connection_method").ok();
@@ -515,22 +798,7 @@ pub async fn regenerate_pdf_from_attachment(
     .map_err(|e| format!("Failed to fetch patient: {}", e))?
     .ok_or("Patient not found".to_string())?;
 
-    // Try to fetch owner information separately (optional, won't fail if tables don't exist)
-    let owner_row = pool.query_one(Statement::from_sql_and_values(
-        DbBackend::Sqlite,
-        "SELECT o.name FROM patient_owners po \
-         JOIN owners o ON po.owner_id = o.id \
-         WHERE po.patient_id = ? AND po.is_primary = 1 \
-         LIMIT 1",
-        [patient_id.into()]
-    ))
-    .await
-    .ok()
-    .flatten();
-
-    let owner_name: String = owner_row
-        .and_then(|r| r.try_get::<String>("", "name").ok())
-        .unwrap_or_default(); // Empty string - PDF will skip owner row
+    let owner_name = MedicalRecordService::owner_name_for_pdf(&pool, patient_id).await;
 
     let patient_data = PatientData {
         name: patient_row.try_get("", "name").unwrap_or_else(|_| "Непознат Пациент".to_string()),
@@ -599,8 +867,14 @@ pub async fn regenerate_pdf_from_medical_record(
     app_handle: AppHandle,
     pool: State<'_, SeaOrmPool>,
     medical_record_id: i64,
+    job_id: Option<String>,
 ) -> Result<MedicalAttachment, String> {
     log::debug!("regenerate_pdf_from_medical_record medical_record_id={}", medical_record_id);
+    let pdf_job = PdfJob::start(job_id);
+
+    // Held until the new PDF is attached so the attachment set we read
+    // can't change underneath us
+    let _record_lock = lock_record(medical_record_id).await;
 
     // 1. Get all device data attachments for this medical record
     // Check for attachment_type = 'test_result' OR files with device metadata that aren't PDFs
@@ -653,32 +927,7 @@ pub async fn regenerate_pdf_from_medical_record(
     .map_err(|e| format!("Failed to fetch patient: {}", e))?
     .ok_or("Patient not found".to_string())?;
 
-    // Try to get owner information - prefer primary contact's full name, fall back to household name
-    let owner_row = pool.query_one(Statement::from_sql_and_values(
-        DbBackend::Sqlite,
-        "SELECT COALESCE( \
-            (SELECT p.first_name || ' ' || p.last_name \
-             FROM people p \
-             JOIN household_members hm ON hm.person_id = p.id \
-             JOIN patient_households ph ON ph.household_id = hm.household_id \
-             WHERE ph.patient_id = ? AND hm.is_primary_contact = 1 \
-             LIMIT 1), \
-            (SELECT h.name \
-             FROM households h \
-             JOIN patient_households ph ON ph.household_id = h.id \
-             WHERE ph.patient_id = ? \
-             LIMIT 1), \
-            '' \
-         ) as owner_name",
-        [patient_id.into(), patient_id.into()]
-    ))
-    .await
-    .ok()
-    .flatten();
-
-    let owner_name: String = owner_row
-        .and_then(|r| r.try_get::<String>("", "owner_name").ok())
-        .unwrap_or_default(); // Empty string - PDF will skip owner row
+    let owner_name = MedicalRecordService::owner_name_for_pdf(&pool, patient_id).await;
 
     let patient_data = PatientData {
         name: patient_row.try_get("", "name").unwrap_or_else(|_| "Непознат Пациент".to_string()),
@@ -817,6 +1066,7 @@ pub async fn regenerate_pdf_from_medical_record(
         pdf_path.to_str().ok_or("Invalid PDF path")?,
         &java_patient_data,
         &java_device_data,
+        pdf_job.token(),
     )?;
 
     log::debug!("PDF generated at {:?}", pdf_path);
@@ -869,14 +1119,19 @@ pub async fn generate_configured_report(
     medical_record_id: i64,
     selected_attachment_ids: Vec<i64>,
     patient_overrides: Option<PatientOverrides>,
+    job_id: Option<String>,
 ) -> Result<MedicalAttachment, String> {
     log::debug!("generate_configured_report medical_record_id={}, selected_attachments={:?}, overrides={:?}",
         medical_record_id, selected_attachment_ids, patient_overrides);
+    let pdf_job = PdfJob::start(job_id);
 
     if selected_attachment_ids.is_empty() {
         return Err("No attachments selected for the report.".to_string());
     }
 
+    // Covers the generated_pdf cleanup below through the final upload
+    let _record_lock = lock_record(medical_record_id).await;
+
     // 1. Get patient info from the medical record
     let record_row = pool.query_one(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -904,32 +1159,7 @@ pub async fn generate_configured_report(
     .map_err(|e| format!("Failed to fetch patient: {}", e))?
     .ok_or("Patient not found".to_string())?;
 
-    // Try to get owner information
-    let owner_row = pool.query_one(Statement::from_sql_and_values(
-        DbBackend::Sqlite,
-        "SELECT COALESCE( \
-            (SELECT p.first_name || ' ' || p.last_name \
-             FROM people p \
-             JOIN household_members hm ON hm.person_id = p.id \
-             JOIN patient_households ph ON ph.household_id = hm.household_id \
-             WHERE ph.patient_id = ? AND hm.is_primary_contact = 1 \
-             LIMIT 1), \
-            (SELECT h.name \
-             FROM households h \
-             JOIN patient_households ph ON ph.household_id = h.id \
-             WHERE ph.patient_id = ? \
-             LIMIT 1), \
-            '' \
-         ) as owner_name",
-        [patient_id.into(), patient_id.into()]
-    ))
-    .await
-    .ok()
-    .flatten();
-
-    let owner_name: String = owner_row
-        .and_then(|r| r.try_get::<String>("", "owner_name").ok())
-        .unwrap_or_default();
+    let owner_name = MedicalRecordService::owner_name_for_pdf(&pool, patient_id).await;
 
     // 3. Build patient data, applying overrides if provided
     let overrides = patient_overrides.unwrap_or_default();
@@ -1071,6 +1301,7 @@ pub async fn generate_configured_report(
         pdf_path.to_str().ok_or("Invalid PDF path")?,
         &patient_data,
         &all_device_data,
+        pdf_job.token(),
     )?;
 
     log::debug!("PDF generated at {:?}", pdf_path);
@@ -1115,24 +1346,50 @@ pub async fn generate_configured_report(
     Ok(pdf_attachment)
 }
 
+/// Render a device report from synthetic data (no patient, no capture) to
+/// check fonts, logo/socials and layout. Returns the path of the PDF, which
+/// is left in the temp directory for the caller to open.
+#[tauri::command]
+pub async fn render_sample_device_report(
+    app_handle: AppHandle,
+    device_type: String,
+    language: Option<String>,
+) -> Result<String, String> {
+    let language = language.unwrap_or_else(|| "en".to_string());
+    let path = DevicePdfService::render_sample_report(&app_handle, &device_type, &language)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Render only the chosen records of a patient to a temp PDF for a targeted
+/// referral and return its path. Refuses ids that aren't the patient's.
+#[tauri::command]
+pub async fn export_selected_records_pdf(
+    app_handle: AppHandle,
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    record_ids: Vec<i64>,
+) -> Result<String, String> {
+    use crate::services::java_pdf_service::JavaPdfService;
+
+    let records = MedicalRecordService::get_records_for_export(&pool, patient_id, &record_ids).await?;
+    let patient = MedicalRecordService::get_patient_for_pdf(&pool, patient_id).await?;
+
+    let output_path = std::env::temp_dir().join(format!(
+        "records_{}_{}.pdf",
+        patient_id,
+        chrono::Utc::now().timestamp_millis()
+    ));
+    let output_path = output_path.to_str().ok_or("Invalid PDF path")?.to_string();
+
+    JavaPdfService::generate_record_selection(&app_handle, &output_path, &patient, &records)?;
+
+    Ok(output_path)
+}
+
 // ============================================================================
 // Record Template Commands
 // ============================================================================
 
-// Helper function to map SeaORM row to RecordTemplate
-fn row_to_record_template(row: &sea_orm::QueryResult) -> Result<RecordTemplate, String> {
-    Ok(RecordTemplate {
-        id: row.try_get("", "id").map_err(|e| format!("Failed to get id: {}", e))?,
-        record_type: row.try_get("", "record_type").map_err(|e| format!("Failed to get record_type: {}", e))?,
-        title: row.try_get("", "title").map_err(|e| format!("Failed to get title: {}", e))?,
-        description: row.try_get("", "description").map_err(|e| format!("Failed to get description: {}", e))?,
-        price: row.try_get("", "price").ok(),
-        currency_id: row.try_get("", "currency_id").ok(),
-        created_at: row.try_get("", "created_at").map_err(|e| format!("Failed to get created_at: {}", e))?,
-        updated_at: row.try_get("", "updated_at").map_err(|e| format!("Failed to get updated_at: {}", e))?,
-    })
-}
-
 #[tauri::command]
 pub async fn get_record_templates(
     pool: State<'_, SeaOrmPool>,
@@ -1204,6 +1461,22 @@ pub async fn search_record_templates(
     Ok(templates)
 }
 
+/// Compose the record a template would create for a patient, without saving it
+#[tauri::command]
+pub async fn preview_record_from_template(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    template_id: i64,
+    overrides: Option<TemplatePreviewOverrides>,
+) -> Result<CreateMedicalRecordInput, String> {
+    MedicalRecordService::preview_record_from_template(
+        &pool,
+        patient_id,
+        template_id,
+        overrides.unwrap_or_default(),
+    ).await
+}
+
 #[tauri::command]
 pub async fn create_record_template(
     pool: State<'_, SeaOrmPool>,
@@ -24,6 +24,11 @@ pub mod managed_hid_scanner;
 pub mod hid_devices;
 pub mod diagnosis;
 pub mod telemetry;
+pub mod log_export;
+pub mod events;
+pub mod pdf_jobs;
+pub mod vaccination;
+pub mod users;
 
 pub use patient::*;
 pub use database::*;
@@ -51,3 +56,8 @@ pub use managed_hid_scanner::*;
 pub use hid_devices::*;
 pub use diagnosis::*;
 pub use telemetry::*;
+pub use log_export::*;
+pub use events::*;
+pub use pdf_jobs::*;
+pub use vaccination::*;
+pub use users::*;
@@ -1,11 +1,40 @@
 use tauri::State;
 use crate::database::SeaOrmPool;
-use crate::services::patient::PatientService;
-use crate::models::{Patient, CreatePatientDto, UpdatePatientDto};
+use crate::services::patient::{PatientService, DEFAULT_WEIGHT_ALERT_PERCENT};
+use crate::services::patient_import::{PatientImportRowResult, PatientImportService};
+use crate::services::users::CurrentUser;
+use crate::models::{PaginationParams, Patient, PatientAge, PatientListFilter, PatientListResponse, PatientMergeResult, PatientSort, PatientPrimaryContact, PatientWriteError, PatientProfile, PatientReclassification, PatientWeightTrend, RecallCandidate, CreatePatientDto, UpdatePatientDto};
 
+/// All patients; deleted (inactive) ones only with `include_inactive`.
 #[tauri::command]
-pub async fn get_patients(pool: State<'_, SeaOrmPool>) -> Result<Vec<Patient>, String> {
-    PatientService::get_all(&pool).await
+pub async fn get_patients(pool: State<'_, SeaOrmPool>, include_inactive: Option<bool>) -> Result<Vec<Patient>, String> {
+    PatientService::get_all(&pool, include_inactive.unwrap_or(false)).await
+}
+
+/// A page of the main patient table: resolved species/breed names, the
+/// primary owner, age and record counts, filtered and sorted.
+#[tauri::command]
+pub async fn list_patients(
+    pool: State<'_, SeaOrmPool>,
+    filter: Option<PatientListFilter>,
+    pagination: Option<PaginationParams>,
+    sort: Option<PatientSort>,
+) -> Result<PatientListResponse, String> {
+    PatientService::list_patients(
+        &pool,
+        filter,
+        pagination,
+        sort,
+        chrono::Local::now().date_naive(),
+        chrono::Utc::now(),
+    ).await
+}
+
+/// Recompute the cached per-patient record counts shown in the patient
+/// table. Returns the number of patients with records.
+#[tauri::command]
+pub async fn rebuild_patient_record_counts(pool: State<'_, SeaOrmPool>) -> Result<u64, String> {
+    PatientService::rebuild_record_counts(&pool).await
 }
 
 #[tauri::command]
@@ -13,27 +42,148 @@ pub async fn get_patient(pool: State<'_, SeaOrmPool>, id: i64) -> Result<Option<
     PatientService::get_by_id(&pool, id).await
 }
 
+/// Age today in months and as display text ("3y 2m", "5 months",
+/// "2 weeks"); both None without a (past) date of birth.
+#[tauri::command]
+pub async fn get_patient_age(pool: State<'_, SeaOrmPool>, patient_id: i64) -> Result<PatientAge, String> {
+    PatientService::get_age(&pool, patient_id, chrono::Local::now().date_naive()).await
+}
+
+/// Patient plus household, owner, counts and next appointment, for the
+/// detail screen.
 #[tauri::command]
-pub async fn create_patient(pool: State<'_, SeaOrmPool>, dto: CreatePatientDto) -> Result<Patient, String> {
-    PatientService::create(&pool, dto).await
+pub async fn get_patient_profile(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+) -> Result<Option<PatientProfile>, String> {
+    PatientService::get_profile(
+        &pool,
+        patient_id,
+        chrono::Local::now().date_naive(),
+        chrono::Utc::now(),
+    ).await
+}
+
+/// The owner to contact about a patient; `None` if it has no household.
+#[tauri::command]
+pub async fn get_patient_primary_contact(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+) -> Result<Option<PatientPrimaryContact>, String> {
+    PatientService::get_primary_contact(&pool, patient_id).await
+}
+
+/// Weight trend over the last `window_days`, flagged when the change reaches
+/// `threshold_percent` (default `DEFAULT_WEIGHT_ALERT_PERCENT`).
+#[tauri::command]
+pub async fn get_patient_weight_trend(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+    window_days: i64,
+    threshold_percent: Option<f64>,
+) -> Result<PatientWeightTrend, String> {
+    PatientService::get_weight_trend(
+        &pool,
+        patient_id,
+        window_days,
+        threshold_percent.unwrap_or(DEFAULT_WEIGHT_ALERT_PERCENT),
+        chrono::Utc::now(),
+    ).await
+}
+
+/// Fails with a `DUPLICATE_MICROCHIP` error naming the other patient when
+/// the microchip is already in use, unless `allow_duplicate_microchip`.
+#[tauri::command]
+pub async fn create_patient(
+    pool: State<'_, SeaOrmPool>,
+    dto: CreatePatientDto,
+    allow_duplicate_microchip: Option<bool>,
+) -> Result<Patient, PatientWriteError> {
+    PatientService::create_with_options(&pool, dto, allow_duplicate_microchip.unwrap_or(false)).await
 }
 
+/// Same microchip check as `create_patient`.
 #[tauri::command]
-pub async fn update_patient(pool: State<'_, SeaOrmPool>, id: i64, dto: UpdatePatientDto) -> Result<Option<Patient>, String> {
+pub async fn update_patient(
+    pool: State<'_, SeaOrmPool>,
+    id: i64,
+    dto: UpdatePatientDto,
+    allow_duplicate_microchip: Option<bool>,
+) -> Result<Option<Patient>, PatientWriteError> {
     log::debug!("update_patient called with id: {}, dto: {:?}", id, dto);
-    let result = PatientService::update(&pool, id, dto).await;
+    let result = PatientService::update_with_options(&pool, id, dto, allow_duplicate_microchip.unwrap_or(false)).await;
     log::debug!("update_patient result: {:?}", result);
     result
 }
 
+/// Soft delete: the patient is marked inactive and its history is kept.
 #[tauri::command]
 pub async fn delete_patient(pool: State<'_, SeaOrmPool>, id: i64) -> Result<bool, String> {
     PatientService::delete(&pool, id).await
 }
 
+/// Bring back a patient removed with `delete_patient`.
+#[tauri::command]
+pub async fn restore_patient(pool: State<'_, SeaOrmPool>, patient_id: i64) -> Result<Option<Patient>, String> {
+    PatientService::restore(&pool, patient_id).await
+}
+
+/// Permanently delete a patient with its appointments and medical records.
+/// Refused unless the current user is an admin.
+#[tauri::command]
+pub async fn hard_delete_patient(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    patient_id: i64,
+) -> Result<bool, String> {
+    PatientService::hard_delete(&pool, patient_id, current_user.get().as_ref()).await
+}
+
+/// Import patients from CSV (a header row, then one patient per line),
+/// e.g. when moving over from a spreadsheet. With `dry_run` nothing is
+/// written; the per-row results show what would happen.
+#[tauri::command]
+pub async fn import_patients_from_csv(
+    pool: State<'_, SeaOrmPool>,
+    csv_data: String,
+    dry_run: bool,
+    create_missing_breeds: Option<bool>,
+) -> Result<Vec<PatientImportRowResult>, String> {
+    PatientImportService::import_csv(&pool, &csv_data, dry_run, create_missing_breeds.unwrap_or(false)).await
+}
+
+/// Fold a duplicate patient into another: records, appointments,
+/// vaccinations, weights and household links move to `target_id` and
+/// `source_id` is soft-deleted.
+#[tauri::command]
+pub async fn merge_patients(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    source_id: i64,
+    target_id: i64,
+) -> Result<PatientMergeResult, String> {
+    PatientService::merge(&pool, source_id, target_id, current_user.get().as_ref()).await
+}
+
+/// Move several patients to another species (and breed) at once, e.g.
+/// after a species entry was split.
 #[tauri::command]
-pub async fn search_patients(pool: State<'_, SeaOrmPool>, query: String) -> Result<Vec<Patient>, String> {
-    PatientService::search(&pool, &query).await
+pub async fn reclassify_patients(
+    pool: State<'_, SeaOrmPool>,
+    patient_ids: Vec<i64>,
+    species_id: i64,
+    breed_id: Option<i64>,
+) -> Result<PatientReclassification, String> {
+    PatientService::reclassify(&pool, patient_ids, species_id, breed_id).await
+}
+
+#[tauri::command]
+pub async fn search_patients(
+    pool: State<'_, SeaOrmPool>,
+    query: String,
+    include_inactive: Option<bool>,
+) -> Result<Vec<Patient>, String> {
+    PatientService::search(&pool, &query, include_inactive.unwrap_or(false)).await
 }
 
 #[tauri::command]
@@ -53,3 +203,23 @@ pub async fn advanced_patient_search(
         species.as_deref(),
     ).await
 }
+
+/// Patients aged `min_months..=max_months` with owner contact details, for
+/// recall campaigns such as senior checkups.
+#[tauri::command]
+pub async fn get_patients_by_age_bracket(
+    pool: State<'_, SeaOrmPool>,
+    species_id: Option<i64>,
+    min_months: i64,
+    max_months: i64,
+    is_active: Option<bool>,
+) -> Result<Vec<RecallCandidate>, String> {
+    PatientService::get_by_age_bracket(
+        &pool,
+        species_id,
+        min_months,
+        max_months,
+        is_active,
+        chrono::Local::now().date_naive(),
+    ).await
+}
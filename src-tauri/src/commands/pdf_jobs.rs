@@ -0,0 +1,14 @@
+use crate::services::pdf_jobs::{cancel_job, create_job, PdfJobHandle};
+
+/// Register a PDF job before starting a report or render, so it can be
+/// cancelled with `cancel_pdf_job` while it runs.
+#[tauri::command]
+pub fn create_pdf_job() -> PdfJobHandle {
+    create_job()
+}
+
+/// Cancel a running PDF job; false if it already finished.
+#[tauri::command]
+pub fn cancel_pdf_job(job_id: String) -> bool {
+    cancel_job(&job_id)
+}
@@ -3,7 +3,7 @@ use chrono::{DateTime, Utc};
 use crate::database::SeaOrmPool;
 use crate::services::rooms::RoomService;
 use crate::models::{
-    Room, CreateRoomInput, UpdateRoomInput, RoomFilter, RoomAvailability
+    Room, CreateRoomInput, UpdateRoomInput, RoomFilter, RoomList, RoomAvailability
 };
 
 #[tauri::command]
@@ -15,6 +15,15 @@ pub async fn get_rooms(
     RoomService::get_rooms(&pool, filter).await
 }
 
+#[tauri::command]
+pub async fn get_room_list(
+    pool: State<'_, SeaOrmPool>,
+    filter: Option<RoomFilter>,
+) -> Result<RoomList, String> {
+    let filter = filter.unwrap_or_default();
+    RoomService::get_room_list(&pool, filter).await
+}
+
 #[tauri::command]
 pub async fn get_room(
     pool: State<'_, SeaOrmPool>,
@@ -40,6 +49,23 @@ pub async fn update_room(
     RoomService::update_room(&pool, id, input).await
 }
 
+#[tauri::command]
+pub async fn duplicate_room(
+    pool: State<'_, SeaOrmPool>,
+    room_id: i64,
+    new_name: String,
+) -> Result<Room, String> {
+    RoomService::duplicate_room(&pool, room_id, new_name).await
+}
+
+#[tauri::command]
+pub async fn reorder_rooms(
+    pool: State<'_, SeaOrmPool>,
+    room_ids: Vec<i64>,
+) -> Result<Vec<Room>, String> {
+    RoomService::reorder_rooms(&pool, room_ids).await
+}
+
 #[tauri::command]
 pub async fn get_room_availability(
     pool: State<'_, SeaOrmPool>,
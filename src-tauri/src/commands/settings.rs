@@ -1,5 +1,8 @@
 use crate::models::{SettingsResponse, UpdateSettingsRequest};
 use crate::services::settings::SettingsService;
+use crate::services::clinic_profile::{
+    ClinicProfile, ClinicProfileImportReport, ClinicProfileService, ProfileMergeStrategy,
+};
 use crate::database::SeaOrmPool;
 use tauri::State;
 use serde::Deserialize;
@@ -50,4 +53,66 @@ pub async fn update_app_settings(
     result
 }
 
+#[tauri::command]
+pub async fn set_default_room(
+    pool: State<'_, SeaOrmPool>,
+    room_id: Option<i64>,
+) -> Result<SettingsResponse, String> {
+    SettingsService::set_default_room(&pool, "default", room_id).await
+}
+
+/// The practice's IANA timezone, or `None` when the machine's is used.
+#[tauri::command]
+pub async fn get_practice_timezone(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Option<String>, String> {
+    Ok(SettingsService::get_practice_timezone(&pool, "default")
+        .await?
+        .map(|tz| tz.name().to_string()))
+}
+
+#[tauri::command]
+pub async fn set_practice_timezone(
+    pool: State<'_, SeaOrmPool>,
+    timezone: Option<String>,
+) -> Result<SettingsResponse, String> {
+    SettingsService::set_practice_timezone(&pool, "default", timezone.as_deref()).await
+}
+
+/// Turn OCR of uploaded scans on or off.
+#[tauri::command]
+pub async fn set_ocr_enabled(
+    pool: State<'_, SeaOrmPool>,
+    enabled: bool,
+) -> Result<SettingsResponse, String> {
+    SettingsService::set_ocr_enabled(&pool, "default", enabled).await
+}
+
+/// Archive records older than `days` automatically, or stop with `None`.
+#[tauri::command]
+pub async fn set_auto_archive_after_days(
+    pool: State<'_, SeaOrmPool>,
+    days: Option<i64>,
+) -> Result<SettingsResponse, String> {
+    SettingsService::set_auto_archive_after_days(&pool, "default", days).await
+}
+
+/// The clinic's configuration (species, rooms, templates, currencies and
+/// default settings) as a profile to load into another install.
+#[tauri::command]
+pub async fn export_clinic_profile(pool: State<'_, SeaOrmPool>) -> Result<ClinicProfile, String> {
+    ClinicProfileService::export_profile(&pool).await
+}
+
+/// Load a profile from `export_clinic_profile`. `replace` retires whatever
+/// the profile doesn't list; `merge` keeps it. Patient data is untouched.
+#[tauri::command]
+pub async fn import_clinic_profile(
+    pool: State<'_, SeaOrmPool>,
+    json: String,
+    merge_strategy: ProfileMergeStrategy,
+) -> Result<ClinicProfileImportReport, String> {
+    ClinicProfileService::import_profile(&pool, &json, merge_strategy).await
+}
+
 // Note: get_currencies is already defined in medical.rs and used throughout the app
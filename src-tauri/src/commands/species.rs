@@ -1,6 +1,6 @@
 use tauri::State;
 use crate::database::SeaOrmPool;
-use crate::models::{Species, CreateSpeciesInput, UpdateSpeciesInput};
+use crate::models::{Species, CreateSpeciesInput, UpdateSpeciesInput, SpeciesColor};
 use crate::services::species::SpeciesService;
 
 #[tauri::command]
@@ -11,6 +11,13 @@ pub async fn get_species(
     SpeciesService::get_all(&pool, active_only.unwrap_or(true)).await
 }
 
+#[tauri::command]
+pub async fn get_species_colors(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<SpeciesColor>, String> {
+    SpeciesService::get_colors(&pool).await
+}
+
 #[tauri::command]
 pub async fn get_species_by_id(
     pool: State<'_, SeaOrmPool>,
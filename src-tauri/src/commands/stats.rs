@@ -1,5 +1,9 @@
 use tauri::State;
 use crate::database::SeaOrmPool;
+use crate::services::dashboard::{DashboardService, TodayOverview, OVERVIEW_LIST_LIMIT};
+use crate::services::device_input::get_all_connection_statuses;
+use crate::services::settings::SettingsService;
+use chrono::{Local, Utc};
 use sea_orm::*;
 use serde::Serialize;
 
@@ -23,7 +27,7 @@ pub async fn get_dashboard_stats(
             SELECT
                 (SELECT COUNT(*) FROM patients) as total_patients,
                 (SELECT COUNT(*) FROM patients WHERE is_active = 1 OR is_active IS NULL) as active_patients,
-                (SELECT COUNT(*) FROM households) as total_households,
+                (SELECT COUNT(*) FROM households WHERE deleted_at IS NULL) as total_households,
                 (SELECT COUNT(*) FROM medical_records WHERE is_archived = 0) as total_medical_records
             "#.to_string(),
         ))
@@ -38,3 +42,25 @@ pub async fn get_dashboard_stats(
         total_medical_records: row.try_get("", "total_medical_records").unwrap_or(0),
     })
 }
+
+/// Landing-screen lists for the clinic's current day, in the practice timezone
+#[tauri::command]
+pub async fn get_today_overview(
+    pool: State<'_, SeaOrmPool>,
+    limit: Option<i64>,
+) -> Result<TodayOverview, String> {
+    let now = Utc::now();
+    let (day_start, day_end) = match SettingsService::get_practice_timezone(&pool, "default").await? {
+        Some(tz) => DashboardService::day_bounds(now, &tz)?,
+        None => DashboardService::day_bounds(now, &Local)?,
+    };
+
+    DashboardService::get_today_overview(
+        &pool,
+        day_start,
+        day_end,
+        get_all_connection_statuses(),
+        limit.unwrap_or(OVERVIEW_LIST_LIMIT).clamp(1, 50),
+    )
+    .await
+}
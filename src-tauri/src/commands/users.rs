@@ -0,0 +1,57 @@
+//! Tauri commands for clinic staff and the current user.
+
+use crate::database::SeaOrmPool;
+use crate::models::user::{CreateUserInput, UpdateUserInput, User};
+use crate::services::users::{CurrentUser, UserService};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_users(
+    pool: State<'_, SeaOrmPool>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<User>, String> {
+    UserService::get_users(&pool, include_inactive.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn create_user(
+    pool: State<'_, SeaOrmPool>,
+    input: CreateUserInput,
+) -> Result<User, String> {
+    UserService::create_user(&pool, input).await
+}
+
+#[tauri::command]
+pub async fn update_user(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    id: i64,
+    input: UpdateUserInput,
+) -> Result<User, String> {
+    UserService::update_user(&pool, &current_user, id, input).await
+}
+
+/// Returns false when the user was only deactivated because records
+/// still name them.
+#[tauri::command]
+pub async fn delete_user(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    id: i64,
+) -> Result<bool, String> {
+    UserService::delete_user(&pool, &current_user, id).await
+}
+
+#[tauri::command]
+pub async fn set_current_user(
+    pool: State<'_, SeaOrmPool>,
+    current_user: State<'_, CurrentUser>,
+    user_id: Option<i64>,
+) -> Result<Option<User>, String> {
+    UserService::set_current_user(&pool, &current_user, user_id).await
+}
+
+#[tauri::command]
+pub fn get_current_user(current_user: State<'_, CurrentUser>) -> Option<User> {
+    current_user.get()
+}
@@ -0,0 +1,57 @@
+//! Tauri commands for vaccine protocols and recorded vaccinations.
+
+use crate::database::SeaOrmPool;
+use crate::models::vaccination::{
+    CreateVaccineProtocolInput, RecordVaccinationInput, UpdateVaccineProtocolInput, Vaccination,
+    VaccineProtocol,
+};
+use crate::services::vaccination::VaccinationService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_vaccine_protocols(
+    pool: State<'_, SeaOrmPool>,
+) -> Result<Vec<VaccineProtocol>, String> {
+    VaccinationService::get_protocols(&pool).await
+}
+
+#[tauri::command]
+pub async fn create_vaccine_protocol(
+    pool: State<'_, SeaOrmPool>,
+    input: CreateVaccineProtocolInput,
+) -> Result<VaccineProtocol, String> {
+    VaccinationService::create_protocol(&pool, input).await
+}
+
+#[tauri::command]
+pub async fn update_vaccine_protocol(
+    pool: State<'_, SeaOrmPool>,
+    id: i64,
+    input: UpdateVaccineProtocolInput,
+) -> Result<VaccineProtocol, String> {
+    VaccinationService::update_protocol(&pool, id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_vaccine_protocol(
+    pool: State<'_, SeaOrmPool>,
+    id: i64,
+) -> Result<(), String> {
+    VaccinationService::delete_protocol(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn record_vaccination(
+    pool: State<'_, SeaOrmPool>,
+    input: RecordVaccinationInput,
+) -> Result<Vaccination, String> {
+    VaccinationService::record_vaccination(&pool, input).await
+}
+
+#[tauri::command]
+pub async fn get_patient_vaccinations(
+    pool: State<'_, SeaOrmPool>,
+    patient_id: i64,
+) -> Result<Vec<Vaccination>, String> {
+    VaccinationService::get_patient_vaccinations(&pool, patient_id).await
+}
@@ -1,9 +1,31 @@
-use sea_orm::{Database, DatabaseConnection, ConnectOptions, ConnectionTrait};
-use sqlx::SqlitePool;
+//! Database pools.
+//!
+//! Concurrency model: there is one SQLite pool of up to five connections,
+//! shared by UI commands, the sync scheduler, device-data saves and backups.
+//! SeaORM (`SeaOrmPool`, what commands take as state) and the legacy sqlx
+//! handle (`DatabasePool`) are two views of that same pool. The `Mutex` in
+//! `DatabasePool` is only taken for migrations and the admin/reset commands;
+//! nothing holds it across PDF rendering or other slow work, and no command
+//! serializes on it.
+//!
+//! Every connection runs in WAL mode, so readers never block on the writer
+//! and vice versa. SQLite still allows one writer at a time; a second writer
+//! waits up to `BUSY_TIMEOUT` for the lock instead of failing straight
+//! away with "database is locked". Keep write transactions short, and do
+//! slow work (file I/O, rendering, network) before or after them, never
+//! inside one.
+
+use sea_orm::{DatabaseConnection, DbErr, SqlxSqliteConnector};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{ConnectOptions, SqlitePool};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// How long a connection waits for another writer's lock before giving up.
+pub const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
 /// SeaORM database connection
 pub type SeaOrmPool = Arc<DatabaseConnection>;
 
@@ -12,24 +34,30 @@ pub type DatabasePool = Arc<Mutex<SqlitePool>>;
 
 /// Create both SeaORM connection and legacy SqlitePool
 /// Returns (SeaOrmPool, DatabasePool) tuple for transitional period
-pub async fn create_pools(database_url: &str) -> Result<(SeaOrmPool, DatabasePool), sea_orm::DbErr> {
-    let mut opt = ConnectOptions::new(database_url);
-    opt.max_connections(5)
+pub async fn create_pools(database_url: &str) -> Result<(SeaOrmPool, DatabasePool), DbErr> {
+    // Pragmas set on the connect options are applied to every connection the
+    // pool opens, and it opens them for its whole lifetime (max_lifetime
+    // recycles them every few seconds). Without foreign_keys the schema's
+    // ON DELETE CASCADE / SET NULL clauses silently don't fire.
+    let connect_options = SqliteConnectOptions::from_str(database_url)
+        .map_err(|e| DbErr::Custom(format!("Invalid database URL: {}", e)))?
+        .foreign_keys(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(BUSY_TIMEOUT)
+        .disable_statement_logging();
+
+    let sqlite_pool = SqlitePoolOptions::new()
+        .max_connections(5)
         .min_connections(1)
-        .connect_timeout(Duration::from_secs(8))
         .acquire_timeout(Duration::from_secs(8))
         .idle_timeout(Duration::from_secs(8))
         .max_lifetime(Duration::from_secs(8))
-        .sqlx_logging(false);
-
-    let db = Database::connect(opt).await?;
-
-    // Enable foreign key constraints and WAL mode
-    db.execute_unprepared("PRAGMA foreign_keys = ON").await?;
-    db.execute_unprepared("PRAGMA journal_mode = WAL").await?;
+        .connect_with(connect_options)
+        .await
+        .map_err(|e| DbErr::Custom(format!("Failed to connect to database: {}", e)))?;
 
-    // Get the underlying sqlx pool for legacy services
-    let sqlite_pool = db.get_sqlite_connection_pool().clone();
+    // SeaORM and the legacy services share the same pool
+    let db = SqlxSqliteConnector::from_sqlx_sqlite_pool(sqlite_pool.clone());
 
     Ok((Arc::new(db), Arc::new(Mutex::new(sqlite_pool))))
 }
@@ -47,3 +75,108 @@ pub async fn test_connection(pool: &DatabasePool) -> Result<(), sqlx::Error> {
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn count_people(conn: &mut sqlx::SqliteConnection, household_id: i64) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM people WHERE household_id = ?")
+            .bind(household_id)
+            .fetch_one(conn)
+            .await
+            .unwrap()
+    }
+
+    async fn delete_household_with_person(conn: &mut sqlx::SqliteConnection, household_id: i64) {
+        sqlx::query("INSERT INTO households (id, household_name) VALUES (?, 'Cascade')")
+            .bind(household_id)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO people (household_id, first_name, last_name) VALUES (?, 'A', 'B')")
+            .bind(household_id)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM households WHERE id = ?")
+            .bind(household_id)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+    }
+
+    // Migrated pool on a temp file; keep the TempDir alive while it's used
+    async fn migrated_pool() -> (tempfile::TempDir, SqlitePool) {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("pool.db").display());
+        let (_db, legacy) = create_pools(&url).await.unwrap();
+        let pool = legacy.lock().await.clone();
+        crate::database::migrations::run_migrations(&pool).await.unwrap();
+        (dir, pool)
+    }
+
+    #[tokio::test]
+    async fn every_pooled_connection_enforces_foreign_keys() {
+        let (_dir, pool) = migrated_pool().await;
+
+        // Hold every connection at once so each one gets checked
+        let mut conns = Vec::new();
+        for _ in 0..5 {
+            conns.push(pool.acquire().await.unwrap());
+        }
+        for conn in &mut conns {
+            let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+                .fetch_one(&mut **conn)
+                .await
+                .unwrap();
+            assert_eq!(foreign_keys, 1);
+        }
+
+        let conn = &mut *conns[0];
+        delete_household_with_person(conn, 1).await;
+        assert_eq!(count_people(conn, 1).await, 0, "people cascade with their household");
+
+        // The same delete with enforcement off leaves the person orphaned
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await.unwrap();
+        delete_household_with_person(conn, 2).await;
+        assert_eq!(count_people(conn, 2).await, 1);
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_writes_wait_instead_of_failing() {
+        let (_dir, pool) = migrated_pool().await;
+        let mut first = pool.acquire().await.unwrap();
+        let mut second = pool.acquire().await.unwrap();
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&mut *first)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        // `first` holds the write lock while `second` tries to write
+        sqlx::query("BEGIN IMMEDIATE").execute(&mut *first).await.unwrap();
+        sqlx::query("INSERT INTO households (household_name) VALUES ('First')")
+            .execute(&mut *first)
+            .await
+            .unwrap();
+
+        let second_write = sqlx::query("INSERT INTO households (household_name) VALUES ('Second')")
+            .execute(&mut *second);
+        let first_commit = async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            sqlx::query("COMMIT").execute(&mut *first).await
+        };
+        let (second_result, commit_result) = tokio::join!(second_write, first_commit);
+        commit_result.unwrap();
+        second_result.expect("second writer should wait for the lock, not fail");
+
+        let households: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM households")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(households, 2);
+    }
+}
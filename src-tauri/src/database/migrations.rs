@@ -1,5 +1,107 @@
+use serde::Serialize;
 use sqlx::{self, SqlitePool, Row};
 
+type MigrationFn = for<'a> fn(&'a SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + 'a>>;
+
+struct Migration {
+    name: &'static str,
+    run: MigrationFn,
+    /// Tables the migration drops or rebuilds; empty when it only adds
+    tables: &'static [&'static str],
+    /// The rows in `tables` are deleted rather than copied into the rebuilt table
+    drops_data: bool,
+}
+
+impl Migration {
+    const fn additive(name: &'static str, run: MigrationFn) -> Self {
+        Migration { name, run, tables: &[], drops_data: false }
+    }
+
+    const fn rebuilds(name: &'static str, run: MigrationFn, tables: &'static [&'static str]) -> Self {
+        Migration { name, run, tables, drops_data: false }
+    }
+
+    const fn drops(name: &'static str, run: MigrationFn, tables: &'static [&'static str]) -> Self {
+        Migration { name, run, tables, drops_data: true }
+    }
+}
+
+/// Every migration, in the order it runs. Mark one that drops or rebuilds
+/// tables with `rebuilds` / `drops` so `preview_pending_migrations` warns
+/// about it before an upgrade applies it.
+const MIGRATIONS: &[Migration] = &[
+    Migration::additive("001_initial_patients", create_patients_table),
+    Migration::additive("002_households", create_households_table),
+    Migration::additive("003_people", create_people_table),
+    Migration::additive("004_person_contacts", create_person_contacts_table),
+    Migration::additive("005_patient_households", create_patient_households_table),
+    Migration::additive("006_household_search_fts5", create_household_search_fts5),
+    Migration::additive("007_add_patient_gender", add_patient_gender),
+    Migration::additive("008_add_household_location_fields", add_household_location_fields),
+    Migration::additive("009_create_currencies_table", create_currencies_table),
+    Migration::additive("010_create_medical_records_table", create_medical_records_table),
+    Migration::additive("011_create_medical_attachments_table", create_medical_attachments_table),
+    Migration::additive("012_create_medical_record_history_table", create_medical_record_history_table),
+    Migration::additive("013_create_medical_records_fts", create_medical_records_fts),
+    Migration::additive("014_fix_medical_triggers", fix_medical_triggers),
+    // Dev task: drop and recreate ONLY the medical_record_history table to switch to snapshot strategy
+    Migration::drops("015_recreate_medical_record_history", recreate_medical_record_history, &["medical_record_history"]),
+    Migration::additive("016_add_missing_patient_columns", add_missing_patient_columns),
+    Migration::additive("017_create_app_settings_table", create_app_settings_table),
+    Migration::additive("018_create_appointments_tables", create_appointments_tables),
+    Migration::additive("019_create_update_preferences_table", create_update_preferences_table),
+    Migration::additive("020_create_calendar_event_mappings", create_calendar_event_mappings_table),
+    Migration::additive("021_create_sync_logs", create_sync_logs_table),
+    Migration::additive("022_add_token_expires_at", add_token_expires_at_column),
+    Migration::additive("023_create_species_table", create_species_table),
+    Migration::additive("024_add_room_color", add_room_color_column),
+    Migration::additive("025_create_breeds_table", create_breeds_table),
+    Migration::additive("026_add_species_color", add_species_color_column),
+    Migration::rebuilds("027_convert_patient_species_breed_to_fk", convert_patient_species_breed_to_fk, &["patients"]),
+    Migration::additive("028_create_device_integrations_table", create_device_integrations_table),
+    Migration::additive("029_add_device_metadata_to_attachments", add_device_metadata_to_attachments),
+    Migration::additive("030_add_attachment_type", add_attachment_type_column),
+    Migration::rebuilds("031_add_test_result_record_type", add_test_result_record_type, &["medical_records"]),
+    Migration::additive("032_create_file_access_history", create_file_access_history_table),
+    Migration::additive("033_create_record_templates", create_record_templates_table),
+    Migration::rebuilds("034_verify_breed_id_nullable", verify_breed_id_nullable, &["patients"]),
+    Migration::rebuilds("035_add_mnchip_pcr_analyzer_device", add_mnchip_pcr_analyzer_device, &["device_integrations"]),
+    Migration::rebuilds("036_rename_mnchip_chemistry_device", rename_mnchip_chemistry_device, &["device_integrations"]),
+    Migration::additive("037_create_pending_device_entries", create_pending_device_entries_table),
+    Migration::additive("038_add_prescription_notes", add_prescription_notes_column),
+    Migration::additive("039_create_line_items", create_line_items_tables),
+    Migration::additive("040_add_invoice_number", add_invoice_number_column),
+    Migration::rebuilds("041_relax_patient_required_fields", relax_patient_required_fields, &["patients"]),
+    Migration::additive("042_create_managed_hid_scanners", create_managed_hid_scanners_table),
+    Migration::additive("043_create_diagnoses_tables", create_diagnoses_tables),
+    Migration::rebuilds("044_fts5_unicode61_tokenizer", upgrade_fts5_to_unicode61, &["household_search", "medical_records_fts"]),
+    Migration::additive("045_add_attachment_sort_order", add_attachment_sort_order),
+    Migration::additive("046_create_backup_history", create_backup_history_table),
+    Migration::additive("047_add_default_room_setting", add_default_room_setting),
+    Migration::additive("048_add_sync_retry_columns", add_sync_retry_columns),
+    Migration::additive("049_add_record_follow_up", add_record_follow_up),
+    Migration::additive("050_add_search_tokenizer_setting", add_search_tokenizer_setting),
+    Migration::additive("051_create_exchange_rates", create_exchange_rates_table),
+    Migration::additive("052_create_calendar_sync_tokens", create_calendar_sync_tokens_table),
+    Migration::additive("053_add_external_attachments", add_external_attachment_columns),
+    Migration::additive("054_create_patient_weights", create_patient_weights_table),
+    Migration::additive("055_add_practice_timezone_setting", add_practice_timezone_setting),
+    Migration::additive("056_add_ocr_enabled_setting", add_ocr_enabled_setting),
+    Migration::additive("057_create_attachment_text", create_attachment_text_table),
+    Migration::additive("058_add_households_deleted_at", add_households_deleted_at),
+    Migration::additive("059_add_auto_archive_setting", add_auto_archive_setting),
+    Migration::additive("060_add_medical_records_updated_at_index", add_medical_records_updated_at_index),
+    Migration::additive("061_create_device_parse_mappings", create_device_parse_mappings_table),
+    Migration::additive("062_add_room_display_order", add_room_display_order),
+    Migration::additive("063_create_vaccination_tables", create_vaccination_tables),
+    Migration::additive("064_add_attachment_content_sha256", add_attachment_content_sha256),
+    Migration::additive("065_create_users_table", create_users_table),
+    Migration::additive("066_create_patient_record_counts", create_patient_record_counts_table),
+    Migration::additive("067_add_attachment_stored_file_id", add_attachment_stored_file_id),
+    Migration::additive("068_add_room_buffer_minutes", add_room_buffer_minutes),
+    Migration::rebuilds("069_store_record_prices_as_real", store_record_prices_as_real, &["medical_records"]),
+];
+
 pub async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Create migrations tracking table
     sqlx::query(
@@ -14,52 +116,9 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     .execute(pool)
     .await?;
 
-    // Run migrations in order
-    run_migration(pool, "001_initial_patients", create_patients_table).await?;
-    run_migration(pool, "002_households", create_households_table).await?;
-    run_migration(pool, "003_people", create_people_table).await?;
-    run_migration(pool, "004_person_contacts", create_person_contacts_table).await?;
-    run_migration(pool, "005_patient_households", create_patient_households_table).await?;
-    run_migration(pool, "006_household_search_fts5", create_household_search_fts5).await?;
-    run_migration(pool, "007_add_patient_gender", add_patient_gender).await?;
-    run_migration(pool, "008_add_household_location_fields", add_household_location_fields).await?;
-    run_migration(pool, "009_create_currencies_table", create_currencies_table).await?;
-    run_migration(pool, "010_create_medical_records_table", create_medical_records_table).await?;
-    run_migration(pool, "011_create_medical_attachments_table", create_medical_attachments_table).await?;
-    run_migration(pool, "012_create_medical_record_history_table", create_medical_record_history_table).await?;
-    run_migration(pool, "013_create_medical_records_fts", create_medical_records_fts).await?;
-    run_migration(pool, "014_fix_medical_triggers", fix_medical_triggers).await?;
-    // Dev task: drop and recreate ONLY the medical_record_history table to switch to snapshot strategy
-    run_migration(pool, "015_recreate_medical_record_history", recreate_medical_record_history).await?;
-    run_migration(pool, "016_add_missing_patient_columns", add_missing_patient_columns).await?;
-    run_migration(pool, "017_create_app_settings_table", create_app_settings_table).await?;
-    run_migration(pool, "018_create_appointments_tables", create_appointments_tables).await?;
-    run_migration(pool, "019_create_update_preferences_table", create_update_preferences_table).await?;
-    run_migration(pool, "020_create_calendar_event_mappings", create_calendar_event_mappings_table).await?;
-    run_migration(pool, "021_create_sync_logs", create_sync_logs_table).await?;
-    run_migration(pool, "022_add_token_expires_at", add_token_expires_at_column).await?;
-    run_migration(pool, "023_create_species_table", create_species_table).await?;
-    run_migration(pool, "024_add_room_color", add_room_color_column).await?;
-    run_migration(pool, "025_create_breeds_table", create_breeds_table).await?;
-    run_migration(pool, "026_add_species_color", add_species_color_column).await?;
-    run_migration(pool, "027_convert_patient_species_breed_to_fk", convert_patient_species_breed_to_fk).await?;
-    run_migration(pool, "028_create_device_integrations_table", create_device_integrations_table).await?;
-    run_migration(pool, "029_add_device_metadata_to_attachments", add_device_metadata_to_attachments).await?;
-    run_migration(pool, "030_add_attachment_type", add_attachment_type_column).await?;
-    run_migration(pool, "031_add_test_result_record_type", add_test_result_record_type).await?;
-    run_migration(pool, "032_create_file_access_history", create_file_access_history_table).await?;
-    run_migration(pool, "033_create_record_templates", create_record_templates_table).await?;
-    run_migration(pool, "034_verify_breed_id_nullable", verify_breed_id_nullable).await?;
-    run_migration(pool, "035_add_mnchip_pcr_analyzer_device", add_mnchip_pcr_analyzer_device).await?;
-    run_migration(pool, "036_rename_mnchip_chemistry_device", rename_mnchip_chemistry_device).await?;
-    run_migration(pool, "037_create_pending_device_entries", create_pending_device_entries_table).await?;
-    run_migration(pool, "038_add_prescription_notes", add_prescription_notes_column).await?;
-    run_migration(pool, "039_create_line_items", create_line_items_tables).await?;
-    run_migration(pool, "040_add_invoice_number", add_invoice_number_column).await?;
-    run_migration(pool, "041_relax_patient_required_fields", relax_patient_required_fields).await?;
-    run_migration(pool, "042_create_managed_hid_scanners", create_managed_hid_scanners_table).await?;
-    run_migration(pool, "043_create_diagnoses_tables", create_diagnoses_tables).await?;
-    run_migration(pool, "044_fts5_unicode61_tokenizer", upgrade_fts5_to_unicode61).await?;
+    for migration in MIGRATIONS {
+        run_migration(pool, migration.name, migration.run).await?;
+    }
 
     Ok(())
 }
@@ -100,6 +159,77 @@ where
     Ok(())
 }
 
+/// A table a pending migration drops or rebuilds, with its size today.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationTableImpact {
+    pub table: String,
+    /// 0 when the table doesn't exist yet
+    pub rows: i64,
+}
+
+/// A migration this database hasn't had applied yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingMigration {
+    pub name: String,
+    /// Drops or rebuilds tables
+    pub destructive: bool,
+    /// The rows in `tables` are lost rather than copied over
+    pub drops_data: bool,
+    pub tables: Vec<MigrationTableImpact>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPreview {
+    pub pending: Vec<PendingMigration>,
+    /// A destructive migration would touch existing rows
+    pub backup_recommended: bool,
+}
+
+/// What `run_migrations` would do to this database, without changing it:
+/// the migrations not yet applied, in order, with the row counts of the
+/// tables the destructive ones drop or rebuild.
+pub async fn preview_pending_migrations(pool: &SqlitePool) -> Result<MigrationPreview, sqlx::Error> {
+    let applied: Vec<String> = if table_exists(pool, "migrations").await? {
+        sqlx::query_scalar("SELECT filename FROM migrations")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    let mut pending = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| !applied.iter().any(|a| a == m.name)) {
+        let mut tables = Vec::new();
+        for &table in migration.tables {
+            let rows = if table_exists(pool, table).await? {
+                sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{}\"", table))
+                    .fetch_one(pool)
+                    .await?
+            } else {
+                0
+            };
+            tables.push(MigrationTableImpact { table: table.to_string(), rows });
+        }
+        pending.push(PendingMigration {
+            name: migration.name.to_string(),
+            destructive: !migration.tables.is_empty(),
+            drops_data: migration.drops_data,
+            tables,
+        });
+    }
+
+    let backup_recommended = pending.iter().flat_map(|m| &m.tables).any(|t| t.rows > 0);
+    Ok(MigrationPreview { pending, backup_recommended })
+}
+
+async fn table_exists(pool: &SqlitePool, name: &str) -> Result<bool, sqlx::Error> {
+    let count: (i32,) = sqlx::query_as("SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name = ?")
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+    Ok(count.0 > 0)
+}
+
 fn create_patients_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
     Box::pin(async move {
         // Patients table with foreign keys to species and breeds
@@ -2805,3 +2935,749 @@ fn upgrade_fts5_to_unicode61(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::fu
         Ok(())
     })
 }
+
+// Migration 045: Add sort_order to medical_attachments.
+//
+// Multi-page scans arrive as several files that belong together in a
+// specific order. Attachments are listed by (sort_order, id), so existing
+// rows keep their insertion order via the id tie-break with the default 0.
+fn add_attachment_sort_order(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_attachments') WHERE name = 'sort_order'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_attachments ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 046: Create backup_history table.
+//
+// One row per automatic DB snapshot written by the backup scheduler, so the
+// settings UI can list restore points without scanning the backup folder.
+// Rows are deleted together with their file when rotation prunes a snapshot.
+fn create_backup_history_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS backup_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                file_path TEXT NOT NULL,
+                file_size INTEGER NOT NULL,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_backup_history_created ON backup_history(created_at)")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 047: Add default_room_id to app_settings.
+//
+// New appointments without a room are placed in this room when it is free,
+// otherwise in the next free active room. Deleting the room clears it.
+fn add_default_room_setting(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('app_settings') WHERE name = 'default_room_id'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE app_settings ADD COLUMN default_room_id INTEGER REFERENCES rooms(id) ON DELETE SET NULL")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 048: Add retry bookkeeping to appointment_sync_log.
+//
+// retry_failed_syncs re-pushes failed rows; retry_count caps the attempts and
+// next_retry_at (RFC 3339, UTC) holds off the next one with exponential backoff.
+fn add_sync_retry_columns(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let retry_count_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('appointment_sync_log') WHERE name = 'retry_count'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if retry_count_exists.0 == 0 {
+            sqlx::query("ALTER TABLE appointment_sync_log ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0")
+                .execute(pool)
+                .await?;
+        }
+
+        let next_retry_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('appointment_sync_log') WHERE name = 'next_retry_at'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if next_retry_exists.0 == 0 {
+            sqlx::query("ALTER TABLE appointment_sync_log ADD COLUMN next_retry_at TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_sync_log_status ON appointment_sync_log(sync_status)")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 049: Add follow-up tracking to medical_records.
+//
+// follow_up_date turns "recheck in 2 weeks" notes into a dated worklist
+// entry; follow_up_done is set once the recheck has happened.
+fn add_record_follow_up(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let date_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_records') WHERE name = 'follow_up_date'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if date_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_records ADD COLUMN follow_up_date DATE")
+                .execute(pool)
+                .await?;
+        }
+
+        let done_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_records') WHERE name = 'follow_up_done'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if done_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_records ADD COLUMN follow_up_done BOOLEAN NOT NULL DEFAULT 0")
+                .execute(pool)
+                .await?;
+        }
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_medical_records_follow_up ON medical_records(follow_up_date) WHERE follow_up_date IS NOT NULL AND follow_up_done = 0")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 050: Add search_tokenizer to app_settings.
+//
+// Records which tokenizer household_search was last built with. Migration 044
+// built it with unicode61, hence the default; rebuilding the index with
+// another tokenizer updates it.
+fn add_search_tokenizer_setting(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('app_settings') WHERE name = 'search_tokenizer'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE app_settings ADD COLUMN search_tokenizer TEXT NOT NULL DEFAULT 'unicode61'")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 051: Create exchange_rates table.
+//
+// One rate per currency pair: 1 unit of from_currency_id is worth `rate`
+// units of to_currency_id. Used to total charges recorded in different
+// currencies; the reverse direction is derived when only one is stored.
+fn create_exchange_rates_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS exchange_rates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                from_currency_id INTEGER NOT NULL REFERENCES currencies(id) ON DELETE CASCADE,
+                to_currency_id INTEGER NOT NULL REFERENCES currencies(id) ON DELETE CASCADE,
+                rate REAL NOT NULL CHECK (rate > 0),
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE (from_currency_id, to_currency_id)
+            )
+        "#).execute(pool).await?;
+
+        Ok(())
+    })
+}
+
+// Migration 052: Create calendar_sync_tokens table.
+//
+// Google's `nextSyncToken` from the last completed pull, per calendar, so
+// the scheduler can ask for changed events only. Keyed by calendar so that
+// switching calendars starts with a full sync instead of reusing a token
+// issued for another one.
+fn create_calendar_sync_tokens_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS calendar_sync_tokens (
+                calendar_id TEXT PRIMARY KEY,
+                sync_token TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+        "#).execute(pool).await?;
+
+        Ok(())
+    })
+}
+
+// Migration 053: Add external file references to medical_attachments.
+//
+// Large imaging files can stay where they are (e.g. on a network share):
+// an external attachment stores the absolute path in external_path and has
+// no file in app storage under its file_id.
+fn add_external_attachment_columns(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let is_external_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_attachments') WHERE name = 'is_external'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if is_external_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_attachments ADD COLUMN is_external BOOLEAN NOT NULL DEFAULT 0")
+                .execute(pool)
+                .await?;
+        }
+
+        let external_path_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_attachments') WHERE name = 'external_path'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if external_path_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_attachments ADD COLUMN external_path TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 054: Create patient_weights table.
+//
+// patients.weight only holds the latest value; this keeps every value it
+// has had so a trend can be computed. Triggers on patients record each new
+// weight, and existing weights are backfilled as one point each, dated at the
+// patient's last update. recorded_at is UTC in SQLite's `YYYY-MM-DD HH:MM:SS`
+// form so it compares as text. A later migration that rebuilds `patients`
+// must recreate the triggers.
+fn create_patient_weights_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS patient_weights (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                patient_id INTEGER NOT NULL REFERENCES patients(id) ON DELETE CASCADE,
+                weight REAL NOT NULL CHECK(weight > 0),
+                recorded_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_patient_weights_patient ON patient_weights(patient_id, recorded_at)")
+            .execute(pool)
+            .await?;
+
+        sqlx::query(r#"
+            CREATE TRIGGER IF NOT EXISTS record_patient_weight_insert
+            AFTER INSERT ON patients
+            WHEN NEW.weight IS NOT NULL
+            BEGIN
+                INSERT INTO patient_weights (patient_id, weight) VALUES (NEW.id, CAST(NEW.weight AS REAL));
+            END;
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TRIGGER IF NOT EXISTS record_patient_weight_update
+            AFTER UPDATE OF weight ON patients
+            WHEN NEW.weight IS NOT NULL AND (OLD.weight IS NULL OR NEW.weight <> OLD.weight)
+            BEGIN
+                INSERT INTO patient_weights (patient_id, weight) VALUES (NEW.id, CAST(NEW.weight AS REAL));
+            END;
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            INSERT INTO patient_weights (patient_id, weight, recorded_at)
+            SELECT id, CAST(weight AS REAL), COALESCE(datetime(updated_at), datetime('now'))
+            FROM patients
+            WHERE weight IS NOT NULL
+              AND id NOT IN (SELECT patient_id FROM patient_weights)
+        "#).execute(pool).await?;
+
+        Ok(())
+    })
+}
+
+// Migration 055: Add practice_timezone to app_settings.
+//
+// IANA name (e.g. "Europe/Skopje") used to turn the UTC appointment times
+// into the clinic's local days and working hours. NULL keeps the previous
+// behaviour of using the machine's local timezone.
+fn add_practice_timezone_setting(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('app_settings') WHERE name = 'practice_timezone'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE app_settings ADD COLUMN practice_timezone TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 056: Add ocr_enabled to app_settings.
+//
+// OCR shells out to Tesseract, which isn't installed everywhere, so it is
+// off until the clinic turns it on.
+fn add_ocr_enabled_setting(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('app_settings') WHERE name = 'ocr_enabled'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE app_settings ADD COLUMN ocr_enabled BOOLEAN NOT NULL DEFAULT 0")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 057: Create attachment_text, the FTS5 index of text read from
+// scanned attachments by OCR. Attachments without a row simply aren't
+// searchable; the row goes away with its attachment.
+fn create_attachment_text_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS attachment_text USING fts5(
+                attachment_id UNINDEXED,
+                content,
+                tokenize = "unicode61 remove_diacritics 1"
+            )
+        "#)
+        .execute(pool)
+        .await?;
+
+        sqlx::query(r#"
+            CREATE TRIGGER IF NOT EXISTS attachment_text_delete
+            AFTER DELETE ON medical_attachments
+            BEGIN
+                DELETE FROM attachment_text WHERE attachment_id = OLD.id;
+            END;
+        "#)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 058: Add deleted_at to households.
+//
+// delete_household now soft-deletes so a misclick can be undone; people,
+// contacts and patient links stay in place until the household is purged.
+fn add_households_deleted_at(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('households') WHERE name = 'deleted_at'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE households ADD COLUMN deleted_at TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_households_deleted_at ON households(deleted_at)")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 059: Add auto_archive_after_days to app_settings.
+//
+// NULL leaves auto-archiving off, which is how existing clinics start.
+fn add_auto_archive_setting(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('app_settings') WHERE name = 'auto_archive_after_days'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE app_settings ADD COLUMN auto_archive_after_days INTEGER")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 060: Index medical_records by patient and updated_at for
+// incremental refresh (`get_records_changed_since`).
+fn add_medical_records_updated_at_index(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_medical_records_patient_updated_at ON medical_records(patient_id, updated_at)")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 061: Create device_parse_mappings table.
+//
+// JSON paths that tell the generic JSON parser where a device's patient
+// identifier and results are. result_paths is a JSON array of
+// {"resultKey", "path"} objects.
+fn create_device_parse_mappings_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS device_parse_mappings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                device_type TEXT NOT NULL UNIQUE,
+                patient_identifier_path TEXT,
+                result_paths TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TRIGGER IF NOT EXISTS update_device_parse_mappings_timestamp
+            AFTER UPDATE ON device_parse_mappings
+            BEGIN
+                UPDATE device_parse_mappings SET updated_at = CURRENT_TIMESTAMP
+                WHERE id = NEW.id;
+            END
+        "#).execute(pool).await?;
+
+        Ok(())
+    })
+}
+
+// Migration 062: Add display_order to rooms.
+//
+// Lets the clinic choose the order of the calendar's room columns
+// (reorder_rooms). Existing rooms start out in name order.
+fn add_room_display_order(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query("ALTER TABLE rooms ADD COLUMN display_order INTEGER NOT NULL DEFAULT 0")
+            .execute(pool)
+            .await?;
+
+        sqlx::query(r#"
+            UPDATE rooms SET display_order = (
+                SELECT COUNT(*) FROM rooms AS earlier
+                WHERE earlier.name < rooms.name
+            )
+        "#)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 063: Create vaccine_protocols + vaccinations tables.
+//
+// Vaccinations were only free-text medical records. They now get their own
+// rows with a next-due date, which a protocol (vaccine + species -> days
+// until the next dose) fills in when the user doesn't set one. Protocol
+// names are unique per species case-insensitively, checked in Rust.
+fn create_vaccination_tables(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS vaccine_protocols (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                vaccine_name TEXT NOT NULL,
+                species_id INTEGER NOT NULL,
+                interval_days INTEGER NOT NULL CHECK(interval_days > 0),
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (species_id) REFERENCES species(id) ON DELETE CASCADE
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TRIGGER IF NOT EXISTS update_vaccine_protocols_timestamp
+            AFTER UPDATE ON vaccine_protocols
+            BEGIN
+                UPDATE vaccine_protocols SET updated_at = CURRENT_TIMESTAMP
+                WHERE id = NEW.id;
+            END
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS vaccinations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                patient_id INTEGER NOT NULL,
+                vaccine_name TEXT NOT NULL,
+                administered_at TEXT NOT NULL,
+                next_due_at TEXT,
+                notes TEXT,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_vaccinations_patient ON vaccinations(patient_id, administered_at)")
+            .execute(pool)
+            .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_vaccinations_next_due ON vaccinations(next_due_at) WHERE next_due_at IS NOT NULL")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 064: SHA-256 of each stored attachment
+//
+// Written when a file is stored so verify_attachments_integrity can tell
+// when a file on disk no longer matches what was uploaded. Attachments
+// stored before this migration have no hash and are reported as unhashed.
+fn add_attachment_content_sha256(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_attachments') WHERE name = 'content_sha256'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_attachments ADD COLUMN content_sha256 TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    })
+}
+
+// Migration 065: Clinic staff
+//
+// The user picked with set_current_user is written as created_by /
+// updated_by (by id) on new changes. Users named anywhere are deactivated
+// rather than deleted.
+fn create_users_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                role TEXT NOT NULL,
+                active BOOLEAN NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+        "#).execute(pool).await?;
+
+        Ok(())
+    })
+}
+
+// Migration 066: Cached per-patient record and attachment counts
+//
+// Kept current by triggers so the patient table doesn't need a subquery per
+// row. The rule matches the patient profile: record_count skips archived
+// records, attachment_count includes attachments on archived records.
+// Patients without records have no row. A later migration that rebuilds
+// `medical_records` or `medical_attachments` must recreate the triggers.
+fn create_patient_record_counts_table(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS patient_record_counts (
+                patient_id INTEGER PRIMARY KEY REFERENCES patients(id) ON DELETE CASCADE,
+                record_count INTEGER NOT NULL DEFAULT 0,
+                attachment_count INTEGER NOT NULL DEFAULT 0
+            )
+        "#).execute(pool).await?;
+
+        // Recount one patient. Selecting through `patients` skips patients
+        // that are being deleted, and joining attachments through their
+        // record means a record deleted before its attachments (cascade
+        // order) no longer contributes them.
+        let refresh = |patient_id: &str| format!(r#"
+                INSERT OR REPLACE INTO patient_record_counts (patient_id, record_count, attachment_count)
+                SELECT p.id,
+                    (SELECT COUNT(*) FROM medical_records mr
+                      WHERE mr.patient_id = p.id AND mr.is_archived = 0),
+                    (SELECT COUNT(*) FROM medical_attachments ma
+                      JOIN medical_records mr ON ma.medical_record_id = mr.id
+                      WHERE mr.patient_id = p.id)
+                FROM patients p WHERE p.id = {};"#, patient_id);
+        let record_of = |record_id: &str| format!(
+            "(SELECT patient_id FROM medical_records WHERE id = {})", record_id
+        );
+
+        let triggers = [
+            ("patient_record_counts_record_insert", "AFTER INSERT ON medical_records", refresh("NEW.patient_id")),
+            ("patient_record_counts_record_delete", "AFTER DELETE ON medical_records", refresh("OLD.patient_id")),
+            (
+                "patient_record_counts_record_update",
+                "AFTER UPDATE OF patient_id, is_archived ON medical_records",
+                refresh("OLD.patient_id") + &refresh("NEW.patient_id"),
+            ),
+            (
+                "patient_record_counts_attachment_insert",
+                "AFTER INSERT ON medical_attachments",
+                refresh(&record_of("NEW.medical_record_id")),
+            ),
+            (
+                "patient_record_counts_attachment_delete",
+                "AFTER DELETE ON medical_attachments",
+                refresh(&record_of("OLD.medical_record_id")),
+            ),
+            (
+                "patient_record_counts_attachment_update",
+                "AFTER UPDATE OF medical_record_id ON medical_attachments",
+                refresh(&record_of("OLD.medical_record_id")) + &refresh(&record_of("NEW.medical_record_id")),
+            ),
+        ];
+        for (name, event, body) in triggers {
+            sqlx::query(&format!(
+                "CREATE TRIGGER IF NOT EXISTS {} {} BEGIN {} END;",
+                name, event, body
+            ))
+            .execute(pool)
+            .await?;
+        }
+
+        sqlx::query(r#"
+            INSERT OR REPLACE INTO patient_record_counts (patient_id, record_count, attachment_count)
+            SELECT mr.patient_id,
+                SUM(CASE WHEN mr.is_archived = 0 THEN 1 ELSE 0 END),
+                (SELECT COUNT(*) FROM medical_attachments ma
+                  JOIN medical_records r ON ma.medical_record_id = r.id
+                  WHERE r.patient_id = mr.patient_id)
+            FROM medical_records mr
+            JOIN patients p ON p.id = mr.patient_id
+            GROUP BY mr.patient_id
+        "#).execute(pool).await?;
+
+        Ok(())
+    })
+}
+
+// Migration 067: Attachments with identical content share one stored file
+//
+// stored_file_id names the file in storage; an upload whose content_sha256
+// matches a stored file points at it instead of writing a copy. Rows without
+// one are stored under their file_id, as before.
+fn add_attachment_stored_file_id(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let column_exists: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM pragma_table_info('medical_attachments') WHERE name = 'stored_file_id'"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if column_exists.0 == 0 {
+            sqlx::query("ALTER TABLE medical_attachments ADD COLUMN stored_file_id TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_medical_attachments_content_sha256 ON medical_attachments(content_sha256)")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+// Migration 068: Turnover time between appointments in a room
+//
+// An appointment in the room blocks the buffer_minutes after it ends (and
+// before it starts) for cleaning. 0, the default, allows back-to-back
+// bookings as before.
+fn add_room_buffer_minutes(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        sqlx::query("ALTER TABLE rooms ADD COLUMN buffer_minutes INTEGER NOT NULL DEFAULT 0 CHECK (buffer_minutes >= 0)")
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    })
+}
+
+/// `medical_records.price` was declared `DECIMAL(10,2)`, whose NUMERIC
+/// affinity stores whole amounts as INTEGER and the rest as REAL, so readers
+/// had to try both. Updating the values in place can't fix that (the
+/// affinity converts them back), so the column is redeclared as REAL: add,
+/// copy, drop, rename.
+fn store_record_prices_as_real(pool: &SqlitePool) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
+        let declared_type: String =
+            sqlx::query_scalar("SELECT type FROM pragma_table_info('medical_records') WHERE name = 'price'")
+                .fetch_one(pool)
+                .await?;
+        if declared_type.eq_ignore_ascii_case("REAL") {
+            println!("Migration 069: price is already REAL, skipping");
+            return Ok(());
+        }
+
+        let integer_rows: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM medical_records WHERE typeof(price) = 'integer'")
+                .fetch_one(pool)
+                .await?;
+
+        let mut tx = pool.begin().await?;
+        for sql in [
+            "ALTER TABLE medical_records ADD COLUMN price_real REAL",
+            "UPDATE medical_records SET price_real = price",
+            "ALTER TABLE medical_records DROP COLUMN price",
+            "ALTER TABLE medical_records RENAME COLUMN price_real TO price",
+        ] {
+            sqlx::query(sql).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        println!("Migration 069: stored medical record prices as REAL ({} were INTEGER)", integer_rows);
+        Ok(())
+    })
+}
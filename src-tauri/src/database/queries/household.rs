@@ -5,7 +5,7 @@ use crate::models::household::*;
 pub async fn create_household_with_people(
     db: &DatabaseConnection,
     dto: CreateHouseholdWithPeopleDto,
-) -> Result<HouseholdWithPeople, String> {
+) -> Result<CreateHouseholdWithPeopleResult, String> {
     // Validate the DTO
     dto.validate().map_err(|e| e.to_string())?;
 
@@ -55,13 +55,14 @@ pub async fn create_household_with_people(
         let mut contacts = Vec::new();
 
         for contact_dto in &person_dto.contacts {
+            let contact_value = validate_contact(&contact_dto.contact_type, &contact_dto.contact_value)?;
             let result = txn.execute(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
                 "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
                 [
                     person_id.into(),
                     contact_dto.contact_type.clone().into(),
-                    contact_dto.contact_value.clone().into(),
+                    contact_value.clone().into(),
                     contact_dto.is_primary.unwrap_or(false).into(),
                 ]
             ))
@@ -74,7 +75,7 @@ pub async fn create_household_with_people(
                 id: contact_id as i32,
                 person_id: person_id as i32,
                 contact_type: contact_dto.contact_type.clone(),
-                contact_value: contact_dto.contact_value.clone(),
+                contact_value,
                 is_primary: contact_dto.is_primary.unwrap_or(false),
                 created_at: chrono::Utc::now().naive_utc(),
             };
@@ -91,9 +92,15 @@ pub async fn create_household_with_people(
         });
     }
 
+    super::household_search::refresh_household_display_name(&txn, household_id).await?;
+
     // Commit transaction
     txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
 
+    // Search only works through the FTS row the insert triggers write, and a
+    // broken trigger fails silently, so check for it before returning.
+    let search_index = super::household_search::ensure_household_indexed(db, household_id).await?;
+
     // Fetch the created household
     let row = db.query_one(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -115,10 +122,13 @@ pub async fn create_household_with_people(
         updated_at: row.try_get("", "updated_at").map_err(|e| format!("Failed to get updated_at: {}", e))?,
     };
 
-    Ok(HouseholdWithPeople {
-        household,
-        people: people_with_contacts,
-        pet_count: 0,
+    Ok(CreateHouseholdWithPeopleResult {
+        created: HouseholdWithPeople {
+            household,
+            people: people_with_contacts,
+            pet_count: 0,
+        },
+        search_index,
     })
 }
 
@@ -183,13 +193,14 @@ pub async fn create_patient_with_household(
         let mut contacts = Vec::new();
 
         for contact_dto in &person_dto.contacts {
+            let contact_value = validate_contact(&contact_dto.contact_type, &contact_dto.contact_value)?;
             let result = txn.execute(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
                 "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
                 [
                     person_id.into(),
                     contact_dto.contact_type.clone().into(),
-                    contact_dto.contact_value.clone().into(),
+                    contact_value.clone().into(),
                     contact_dto.is_primary.unwrap_or(false).into(),
                 ]
             ))
@@ -202,7 +213,7 @@ pub async fn create_patient_with_household(
                 id: contact_id as i32,
                 person_id: person_id as i32,
                 contact_type: contact_dto.contact_type.clone(),
-                contact_value: contact_dto.contact_value.clone(),
+                contact_value,
                 is_primary: contact_dto.is_primary.unwrap_or(false),
                 created_at: chrono::Utc::now().naive_utc(),
             });
@@ -259,6 +270,8 @@ pub async fn create_patient_with_household(
     .await
     .map_err(|e| format!("Failed to link patient to household: {}", e))?;
 
+    super::household_search::refresh_household_display_name(&txn, household_id).await?;
+
     txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
 
     let household = Household {
@@ -279,15 +292,30 @@ pub async fn create_patient_with_household(
     }, patient_id))
 }
 
-// Get household with all people and contacts
+// Get household with all people and contacts; None once soft-deleted
 pub async fn get_household_with_people(
     db: &DatabaseConnection,
     household_id: i32,
 ) -> Result<Option<HouseholdWithPeople>, String> {
+    fetch_household_with_people(db, household_id, false).await
+}
+
+/// `get_household_with_people`, also finding a soft-deleted household when
+/// `include_deleted` is set.
+pub async fn fetch_household_with_people(
+    db: &DatabaseConnection,
+    household_id: i32,
+    include_deleted: bool,
+) -> Result<Option<HouseholdWithPeople>, String> {
+    let deleted_clause = if include_deleted { "" } else { " AND deleted_at IS NULL" };
+
     // Get household
     let household_row = db.query_one(Statement::from_sql_and_values(
         DbBackend::Sqlite,
-        "SELECT id, household_name, address, city, postal_code, notes, created_at, updated_at FROM households WHERE id = ?",
+        &format!(
+            "SELECT id, household_name, address, city, postal_code, notes, created_at, updated_at FROM households WHERE id = ?{}",
+            deleted_clause
+        ),
         [household_id.into()]
     ))
     .await
@@ -401,19 +429,156 @@ pub async fn update_household(
     Ok(())
 }
 
-// Delete household (cascades to people and contacts)
+// Delete household. By default it is only marked deleted and dropped from
+// search, so it can be restored; `hard` deletes the row, cascading to
+// people, contacts and patient links.
 pub async fn delete_household(
     db: &DatabaseConnection,
     household_id: i32,
+    hard: bool,
 ) -> Result<(), String> {
-    db.execute(Statement::from_sql_and_values(
+    if hard {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM households WHERE id = ?",
+            [household_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to delete household: {}", e))?;
+        return Ok(());
+    }
+
+    let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    txn.execute(Statement::from_sql_and_values(
         DbBackend::Sqlite,
-        "DELETE FROM households WHERE id = ?",
-        [household_id.into()]
+        "UPDATE households SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+        [chrono::Utc::now().to_rfc3339().into(), household_id.into()]
     ))
     .await
     .map_err(|e| format!("Failed to delete household: {}", e))?;
 
+    txn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "DELETE FROM household_search WHERE household_id = ?",
+        [household_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to remove household from search: {}", e))?;
+
+    txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+// Undo a soft delete and put the household back into search
+pub async fn restore_household(
+    db: &DatabaseConnection,
+    household_id: i32,
+) -> Result<(), String> {
+    let result = db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE households SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+        [household_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to restore household: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("No deleted household with id {}", household_id));
+    }
+
+    super::household_search::reindex_household(db, household_id as i64).await
+}
+
+// Permanently remove households soft-deleted more than `older_than_days`
+// days ago. Returns how many were removed.
+pub async fn purge_households(
+    db: &DatabaseConnection,
+    older_than_days: i64,
+) -> Result<usize, String> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days.max(0));
+    let result = db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "DELETE FROM households WHERE deleted_at IS NOT NULL AND deleted_at <= ?",
+        [cutoff.to_rfc3339().into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to purge households: {}", e))?;
+
+    Ok(result.rows_affected() as usize)
+}
+
+// Update a person's names and primary flag. Making a person primary demotes
+// the household's other primary in the same transaction; the insert trigger
+// does this for new people, but nothing does it on update.
+pub async fn update_person(
+    db: &DatabaseConnection,
+    person_id: i32,
+    updates: &serde_json::Value,
+) -> Result<(), String> {
+    let mut query_parts = Vec::new();
+    let mut params: Vec<sea_orm::Value> = Vec::new();
+
+    if let Some(first_name) = updates.get("firstName").and_then(|v| v.as_str()) {
+        query_parts.push("first_name = ?");
+        params.push(first_name.to_string().into());
+    }
+
+    if let Some(last_name) = updates.get("lastName").and_then(|v| v.as_str()) {
+        query_parts.push("last_name = ?");
+        params.push(last_name.to_string().into());
+    }
+
+    let is_primary = updates.get("isPrimary").and_then(|v| v.as_bool());
+    if let Some(is_primary) = is_primary {
+        query_parts.push("is_primary = ?");
+        params.push(is_primary.into());
+    }
+
+    if query_parts.is_empty() {
+        return Ok(());
+    }
+
+    let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    if is_primary == Some(true) {
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE people SET is_primary = 0 \
+             WHERE is_primary = 1 AND id <> ? \
+               AND household_id = (SELECT household_id FROM people WHERE id = ?)",
+            [person_id.into(), person_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to demote primary person: {}", e))?;
+    }
+
+    let sql = format!(
+        "UPDATE people SET {}, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        query_parts.join(", ")
+    );
+    params.push(person_id.into());
+
+    txn.execute(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, params))
+        .await
+        .map_err(|e| format!("Failed to update person: {}", e))?;
+
+    // A rename changes people_names in the household's search row
+    if updates.get("firstName").is_some() || updates.get("lastName").is_some() {
+        let household_id: i64 = txn.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT household_id FROM people WHERE id = ?",
+            [person_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to find person: {}", e))?
+        .ok_or("Person not found")?
+        .try_get("", "household_id")
+        .map_err(|e| format!("Failed to get household_id: {}", e))?;
+        super::household_search::reindex_household(&txn, household_id).await?;
+    }
+
+    txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
     Ok(())
 }
 
@@ -464,3 +629,109 @@ pub async fn get_person_with_contacts(
         contacts,
     }))
 }
+
+/// Every household a patient is linked to, primary first, then in the
+/// order they were linked. Soft-deleted households are left out.
+pub async fn get_patient_households<C: ConnectionTrait>(
+    db: &C,
+    patient_id: i64,
+) -> Result<Vec<LinkedHousehold>, String> {
+    let rows = db.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT h.id, h.household_name, h.address, h.city, h.postal_code, h.notes, h.created_at, h.updated_at, \
+                ph.relationship_type, ph.is_primary \
+         FROM patient_households ph \
+         JOIN households h ON h.id = ph.household_id \
+         WHERE ph.patient_id = ? AND h.deleted_at IS NULL \
+         ORDER BY ph.is_primary DESC, ph.created_at, ph.id",
+        [patient_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to fetch patient households: {}", e))?;
+
+    rows.iter().map(|row| {
+        Ok(LinkedHousehold {
+            household: Household {
+                id: row.try_get("", "id").map_err(|e| format!("Failed to get id: {}", e))?,
+                household_name: row.try_get("", "household_name").map_err(|e| format!("Failed to get household_name: {}", e))?,
+                address: row.try_get("", "address").ok(),
+                city: row.try_get("", "city").ok(),
+                postal_code: row.try_get("", "postal_code").ok(),
+                notes: row.try_get("", "notes").ok(),
+                created_at: row.try_get("", "created_at").map_err(|e| format!("Failed to get created_at: {}", e))?,
+                updated_at: row.try_get("", "updated_at").map_err(|e| format!("Failed to get updated_at: {}", e))?,
+            },
+            relationship_type: row.try_get::<Option<String>>("", "relationship_type").ok().flatten().unwrap_or_default(),
+            is_primary: row.try_get("", "is_primary").unwrap_or(false),
+        })
+    }).collect()
+}
+
+/// Link a patient to one more household without moving its primary one.
+/// A patient with no primary household gets this one as primary, so it
+/// always has exactly one; the `ensure_one_primary_household` trigger
+/// demotes any other primary link on that insert.
+pub async fn add_secondary_household(
+    db: &DatabaseConnection,
+    patient_id: i64,
+    household_id: i32,
+    relationship_type: Option<String>,
+) -> Result<LinkedHousehold, String> {
+    let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let row = txn.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT \
+            (SELECT COUNT(*) FROM patients WHERE id = ?) AS patient_count, \
+            (SELECT COUNT(*) FROM households WHERE id = ? AND deleted_at IS NULL) AS household_count, \
+            (SELECT COUNT(*) FROM patient_households WHERE patient_id = ? AND household_id = ?) AS link_count, \
+            (SELECT COUNT(*) FROM patient_households WHERE patient_id = ? AND is_primary = 1) AS primary_count",
+        [
+            patient_id.into(),
+            household_id.into(),
+            patient_id.into(),
+            household_id.into(),
+            patient_id.into(),
+        ]
+    ))
+    .await
+    .map_err(|e| format!("Failed to check patient households: {}", e))?
+    .ok_or("Failed to check patient households")?;
+    let count = |column: &str| -> Result<i64, String> {
+        row.try_get("", column).map_err(|e| format!("Failed to get {}: {}", column, e))
+    };
+
+    if count("patient_count")? == 0 {
+        return Err(format!("Patient {} not found", patient_id));
+    }
+    if count("household_count")? == 0 {
+        return Err(format!("Household {} not found", household_id));
+    }
+    if count("link_count")? > 0 {
+        return Err("Patient is already linked to this household".to_string());
+    }
+    let is_primary = count("primary_count")? == 0;
+
+    txn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO patient_households (patient_id, household_id, relationship_type, is_primary) VALUES (?, ?, ?, ?)",
+        [
+            patient_id.into(),
+            household_id.into(),
+            relationship_type.unwrap_or_else(|| "secondary_household".to_string()).into(),
+            is_primary.into(),
+        ]
+    ))
+    .await
+    .map_err(|e| format!("Failed to link patient to household: {}", e))?;
+
+    let linked = get_patient_households(&txn, patient_id)
+        .await?
+        .into_iter()
+        .find(|linked| linked.household.id == household_id)
+        .ok_or("Failed to read back the household link")?;
+
+    txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(linked)
+}
@@ -1,4 +1,6 @@
-use sea_orm::{DatabaseConnection, ConnectionTrait, Statement, DbBackend};
+use std::collections::HashMap;
+
+use sea_orm::{DatabaseConnection, ConnectionTrait, Statement, DbBackend, TransactionTrait};
 use crate::models::household::*;
 
 // Sanitize query for FTS5 to prevent syntax errors.
@@ -8,7 +10,7 @@ use crate::models::household::*;
 // emails and phone numbers — the FTS5 tokenizer already splits source content
 // on those boundaries when indexing, so a search for "alice" still finds
 // "alice@example.com".
-fn sanitize_fts5_query(query: &str) -> String {
+pub(crate) fn sanitize_fts5_query(query: &str) -> String {
     let terms: Vec<String> = query
         .chars()
         // Replace anything that isn't alphanumeric, whitespace, or `-` with a
@@ -45,6 +47,7 @@ async fn get_all_households_internal(
             household_name,
             address
         FROM households
+        WHERE deleted_at IS NULL
         ORDER BY household_name, id
         LIMIT ? OFFSET ?
         "#,
@@ -59,6 +62,7 @@ async fn get_all_households_internal(
         r#"
         SELECT COUNT(*) as count
         FROM households
+        WHERE deleted_at IS NULL
         "#,
         []
     ))
@@ -204,7 +208,7 @@ pub async fn search_households(
             snippet(household_search, -1, '<mark>', '</mark>', '...', 32) as snippet
         FROM household_search
         JOIN households h ON household_search.household_id = h.id
-        WHERE household_search MATCH ?
+        WHERE household_search MATCH ? AND h.deleted_at IS NULL
         ORDER BY relevance_score
         LIMIT ? OFFSET ?
         "#,
@@ -387,7 +391,7 @@ pub async fn search_households_by_contact(
         FROM households h
         JOIN people p ON p.household_id = h.id
         JOIN person_contacts pc ON pc.person_id = p.id
-        WHERE pc.contact_value LIKE ?
+        WHERE pc.contact_value LIKE ? AND h.deleted_at IS NULL
         LIMIT 10
         "#,
         [search_pattern.into()]
@@ -400,103 +404,291 @@ pub async fn search_households_by_contact(
     for household_row in household_rows {
         let household_id: i64 = household_row.try_get("", "id")
             .map_err(|e| format!("Failed to get id: {}", e))?;
+        results.push(
+            contact_search_result(
+                db,
+                household_id,
+                household_row.try_get("", "household_name").unwrap_or_default(),
+                household_row.try_get("", "address").ok(),
+            )
+            .await?,
+        );
+    }
 
-        // Get all people for this household
-        let people_rows = db.query_all(Statement::from_sql_and_values(
+    Ok(results)
+}
+
+// Minimum digits in a phone search; fewer would match most households
+const MIN_PHONE_SEARCH_DIGITS: usize = 3;
+
+// Digits of a phone number, so "070/340 846" and "070340846" compare equal
+fn phone_digits(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+// Search households by part of a phone number. Both the query and the
+// stored numbers are reduced to digits first, so "340846" finds
+// "070/340 846" whatever separators were typed when it was saved.
+pub async fn search_households_by_phone(
+    db: &DatabaseConnection,
+    partial: &str,
+) -> Result<Vec<HouseholdSearchResult>, String> {
+    let digits = phone_digits(partial);
+    if digits.len() < MIN_PHONE_SEARCH_DIGITS {
+        return Ok(Vec::new());
+    }
+
+    let rows = db.query_all(Statement::from_string(
+        DbBackend::Sqlite,
+        r#"
+        SELECT h.id, h.household_name, h.address, pc.contact_value
+        FROM households h
+        JOIN people p ON p.household_id = h.id
+        JOIN person_contacts pc ON pc.person_id = p.id
+        WHERE pc.contact_type IN ('phone', 'mobile', 'work_phone') AND h.deleted_at IS NULL
+        ORDER BY h.household_name, h.id
+        "#.to_string(),
+    ))
+    .await
+    .map_err(|e| format!("Failed to search by phone: {}", e))?;
+
+    let mut results: Vec<HouseholdSearchResult> = Vec::new();
+    for row in rows {
+        let contact_value: String = row.try_get("", "contact_value").unwrap_or_default();
+        if !phone_digits(&contact_value).contains(&digits) {
+            continue;
+        }
+        let household_id: i64 = row.try_get("", "id")
+            .map_err(|e| format!("Failed to get id: {}", e))?;
+        if results.iter().any(|r| r.id as i64 == household_id) {
+            continue;
+        }
+        results.push(
+            contact_search_result(
+                db,
+                household_id,
+                row.try_get("", "household_name").unwrap_or_default(),
+                row.try_get("", "address").ok(),
+            )
+            .await?,
+        );
+        if results.len() >= 10 {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+// A contact search hit: the household with its people, their contacts and
+// its pet count
+async fn contact_search_result(
+    db: &DatabaseConnection,
+    household_id: i64,
+    household_name: Option<String>,
+    address: Option<String>,
+) -> Result<HouseholdSearchResult, String> {
+    // Get all people for this household
+    let people_rows = db.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        r#"
+        SELECT
+            p.id,
+            p.first_name,
+            p.last_name,
+            p.is_primary
+        FROM people p
+        WHERE p.household_id = ?
+        ORDER BY p.is_primary DESC, p.last_name, p.first_name
+        "#,
+        [household_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to fetch people: {}", e))?;
+
+    let mut people_with_contacts = Vec::new();
+
+    for person_row in people_rows {
+        let person_id: i64 = person_row.try_get("", "id")
+            .map_err(|e| format!("Failed to get person id: {}", e))?;
+
+        let contact_rows = db.query_all(Statement::from_sql_and_values(
             DbBackend::Sqlite,
             r#"
             SELECT
-                p.id,
-                p.first_name,
-                p.last_name,
-                p.is_primary
-            FROM people p
-            WHERE p.household_id = ?
-            ORDER BY p.is_primary DESC, p.last_name, p.first_name
+                id,
+                person_id,
+                contact_type,
+                contact_value,
+                is_primary,
+                created_at
+            FROM person_contacts
+            WHERE person_id = ?
+            ORDER BY is_primary DESC, contact_type
             "#,
-            [household_id.into()]
+            [person_id.into()]
         ))
         .await
-        .map_err(|e| format!("Failed to fetch people: {}", e))?;
+        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+
+        let contacts: Vec<PersonContact> = contact_rows.iter().map(|contact_row| {
+            PersonContact {
+                id: contact_row.try_get("", "id").unwrap_or(0),
+                person_id: contact_row.try_get("", "person_id").unwrap_or(0),
+                contact_type: contact_row.try_get("", "contact_type").unwrap_or_default(),
+                contact_value: contact_row.try_get("", "contact_value").unwrap_or_default(),
+                is_primary: contact_row.try_get("", "is_primary").unwrap_or(false),
+                created_at: contact_row.try_get("", "created_at").unwrap_or_default(),
+            }
+        }).collect();
+
+        people_with_contacts.push(PersonWithContacts {
+            id: person_id as i32,
+            first_name: person_row.try_get("", "first_name").unwrap_or_default(),
+            last_name: person_row.try_get("", "last_name").unwrap_or_default(),
+            is_primary: person_row.try_get("", "is_primary").unwrap_or(false),
+            contacts,
+        });
+    }
 
-        let mut people_with_contacts = Vec::new();
+    // Get pet count for this household
+    let pet_count_row = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        r#"
+        SELECT COUNT(*) as count
+        FROM patient_households
+        WHERE household_id = ?
+        "#,
+        [household_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to count pets: {}", e))?
+    .ok_or("Failed to count pets")?;
+
+    let pet_count: i64 = pet_count_row.try_get("", "count")
+        .map_err(|e| format!("Failed to get pet count: {}", e))?;
+
+    Ok(HouseholdSearchResult {
+        id: household_id as i32,
+        household_name,
+        address,
+        people: people_with_contacts,
+        pet_count: pet_count as i32,
+        relevance_score: 1.0,
+        snippet: None,
+    })
+}
 
-        for person_row in people_rows {
-            let person_id: i64 = person_row.try_get("", "id")
-                .map_err(|e| format!("Failed to get person id: {}", e))?;
+/// Tokenizer household_search is created with unless configured otherwise
+/// (see migration 044).
+pub const DEFAULT_SEARCH_TOKENIZER: &str = "unicode61";
+
+// FTS5 tokenizer spec for each supported `search_tokenizer` setting. Both
+// fold case and strip diacritics via unicode61; "porter" adds English
+// stemming on top, which helps English words but does nothing for Cyrillic.
+fn search_tokenizer_spec(tokenizer: &str) -> Result<&'static str, String> {
+    match tokenizer {
+        "unicode61" => Ok("unicode61 remove_diacritics 1"),
+        "porter" => Ok("porter unicode61 remove_diacritics 1"),
+        _ => Err(format!("Invalid search tokenizer: {}. Must be 'unicode61' or 'porter'", tokenizer)),
+    }
+}
 
-            let contact_rows = db.query_all(Statement::from_sql_and_values(
-                DbBackend::Sqlite,
-                r#"
-                SELECT
-                    id,
-                    person_id,
-                    contact_type,
-                    contact_value,
-                    is_primary,
-                    created_at
-                FROM person_contacts
-                WHERE person_id = ?
-                ORDER BY is_primary DESC, contact_type
-                "#,
-                [person_id.into()]
-            ))
-            .await
-            .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+pub fn validate_search_tokenizer(tokenizer: &str) -> Result<(), String> {
+    search_tokenizer_spec(tokenizer).map(|_| ())
+}
 
-            let contacts: Vec<PersonContact> = contact_rows.iter().map(|contact_row| {
-                PersonContact {
-                    id: contact_row.try_get("", "id").unwrap_or(0),
-                    person_id: contact_row.try_get("", "person_id").unwrap_or(0),
-                    contact_type: contact_row.try_get("", "contact_type").unwrap_or_default(),
-                    contact_value: contact_row.try_get("", "contact_value").unwrap_or_default(),
-                    is_primary: contact_row.try_get("", "is_primary").unwrap_or(false),
-                    created_at: contact_row.try_get("", "created_at").unwrap_or_default(),
-                }
-            }).collect();
+/// Drop and recreate household_search with `tokenizer`, then repopulate it.
+/// FTS5 can't change a table's tokenizer in place; the household triggers
+/// only reference column names, so they keep working against the new table.
+pub async fn rebuild_search_index_with_tokenizer(
+    db: &DatabaseConnection,
+    tokenizer: &str,
+) -> Result<(), String> {
+    let spec = search_tokenizer_spec(tokenizer)?;
 
-            people_with_contacts.push(PersonWithContacts {
-                id: person_id as i32,
-                first_name: person_row.try_get("", "first_name").unwrap_or_default(),
-                last_name: person_row.try_get("", "last_name").unwrap_or_default(),
-                is_primary: person_row.try_get("", "is_primary").unwrap_or(false),
-                contacts,
-            });
-        }
+    let txn = db.begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
-        // Get pet count for this household
-        let pet_count_row = db.query_one(Statement::from_sql_and_values(
-            DbBackend::Sqlite,
-            r#"
-            SELECT COUNT(*) as count
-            FROM patient_households
-            WHERE household_id = ?
-            "#,
-            [household_id.into()]
-        ))
+    txn.execute_unprepared("DROP TABLE IF EXISTS household_search")
         .await
-        .map_err(|e| format!("Failed to count pets: {}", e))?
-        .ok_or("Failed to count pets")?;
+        .map_err(|e| format!("Failed to drop search index: {}", e))?;
 
-        let pet_count: i64 = pet_count_row.try_get("", "count")
-            .map_err(|e| format!("Failed to get pet count: {}", e))?;
+    txn.execute_unprepared(&format!(
+        r#"
+        CREATE VIRTUAL TABLE household_search USING fts5(
+            household_id UNINDEXED,
+            household_name,
+            address,
+            people_names,
+            contact_values,
+            display_name,
+            tokenize = "{}",
+            prefix = '2,3,4'
+        )
+        "#,
+        spec
+    ))
+    .await
+    .map_err(|e| format!("Failed to create search index: {}", e))?;
 
-        results.push(HouseholdSearchResult {
-            id: household_id as i32,
-            household_name: household_row.try_get("", "household_name").unwrap_or_default(),
-            address: household_row.try_get("", "address").ok(),
-            people: people_with_contacts,
-            pet_count: pet_count as i32,
-            relevance_score: 1.0,
-            snippet: None,
-        });
+    populate_search_index(&txn).await?;
+
+    txn.commit()
+        .await
+        .map_err(|e| format!("Failed to commit search index rebuild: {}", e))
+}
+
+/// Households reindexed per transaction by `rebuild_search_index`.
+pub const REINDEX_BATCH_SIZE: usize = 200;
+
+/// Rewrite every household's search row from the current tables (for
+/// maintenance). Runs in batches of `REINDEX_BATCH_SIZE`, each its own
+/// transaction, so searches and edits aren't held up for the whole rebuild;
+/// `on_progress` is called after each batch. Returns how many households
+/// were indexed.
+pub async fn rebuild_search_index(
+    db: &DatabaseConnection,
+    on_progress: impl Fn(&HouseholdSearchReindexProgress),
+) -> Result<usize, String> {
+    let rows = db.query_all(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT id FROM households WHERE deleted_at IS NULL ORDER BY id".to_string(),
+    ))
+    .await
+    .map_err(|e| format!("Failed to fetch households for reindex: {}", e))?;
+    let ids: Vec<i64> = rows.iter().filter_map(|row| row.try_get("", "id").ok()).collect();
+
+    // Rows of deleted or soft-deleted households
+    db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "DELETE FROM household_search \
+         WHERE household_id NOT IN (SELECT id FROM households WHERE deleted_at IS NULL)"
+            .to_string(),
+    ))
+    .await
+    .map_err(|e| format!("Failed to clear search index: {}", e))?;
+
+    let mut progress = HouseholdSearchReindexProgress { indexed: 0, total: ids.len() };
+    for batch in ids.chunks(REINDEX_BATCH_SIZE) {
+        let txn = db.begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        write_search_rows(&txn, batch).await?;
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit search index batch: {}", e))?;
+
+        progress.indexed += batch.len();
+        on_progress(&progress);
     }
 
-    Ok(results)
+    log::info!("Rebuilt household search index, {} households", progress.total);
+    Ok(progress.total)
 }
 
-// Rebuild FTS5 index (for maintenance)
-pub async fn rebuild_search_index(db: &DatabaseConnection) -> Result<(), String> {
+async fn populate_search_index<C: ConnectionTrait>(db: &C) -> Result<(), String> {
     // Clear existing index
     db.execute(Statement::from_sql_and_values(
         DbBackend::Sqlite,
@@ -506,10 +698,200 @@ pub async fn rebuild_search_index(db: &DatabaseConnection) -> Result<(), String>
     .await
     .map_err(|e| format!("Failed to clear search index: {}", e))?;
 
-    // Rebuild from households
+    insert_search_rows(db, None).await
+}
+
+/// Make sure `household_id` has its household_search row. The insert
+/// triggers normally write it; if they didn't (a dropped or drifted
+/// trigger), the household is indexed directly and a warning is logged.
+pub async fn ensure_household_indexed(
+    db: &DatabaseConnection,
+    household_id: i64,
+) -> Result<SearchIndexSource, String> {
+    let existing = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT household_id FROM household_search WHERE household_id = ?",
+        [household_id.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to check search index: {}", e))?;
+
+    if existing.is_some() {
+        return Ok(SearchIndexSource::Trigger);
+    }
+
+    log::warn!(
+        "household_search row missing for household {} after insert; indexing it directly",
+        household_id
+    );
+    insert_search_rows(db, Some(&[household_id])).await?;
+    Ok(SearchIndexSource::Fallback)
+}
+
+/// Name shown for a household in search results: its household_name when
+/// set, else the one person's full name, else two people's last names as
+/// "A & B", else "Household <id>". `people` is (first_name, last_name) in
+/// insertion order.
+///
+/// The people insert trigger computes the same thing in SQL so the index is
+/// right between writes; rows written here win whenever the two disagree
+/// (e.g. a blank household_name, which the trigger keeps as is).
+pub fn household_display_name(
+    household_id: i64,
+    household_name: Option<&str>,
+    people: &[(String, String)],
+) -> String {
+    if let Some(name) = household_name.map(str::trim).filter(|n| !n.is_empty()) {
+        return name.to_string();
+    }
+    match people {
+        [(first, last)] => format!("{} {}", first, last),
+        [(_, a), (_, b)] => format!("{} & {}", a, b),
+        _ => format!("Household {}", household_id),
+    }
+}
+
+// SQL condition on `column` matching `household_ids` (every household
+// with `None`), with its parameters.
+fn households_filter(column: &str, household_ids: Option<&[i64]>) -> (String, Vec<sea_orm::Value>) {
+    match household_ids {
+        None => ("1 = 1".to_string(), Vec::new()),
+        Some([]) => ("0 = 1".to_string(), Vec::new()),
+        Some(ids) => (
+            format!("{} IN ({})", column, vec!["?"; ids.len()].join(", ")),
+            ids.iter().map(|&id| id.into()).collect(),
+        ),
+    }
+}
+
+// household_name and people for every household, or just `household_ids`.
+async fn load_display_name_inputs<C: ConnectionTrait>(
+    db: &C,
+    household_ids: Option<&[i64]>,
+) -> Result<Vec<(i64, Option<String>, Vec<(String, String)>)>, String> {
+    let (filter, params) = households_filter("h.id", household_ids);
+    let household_filter = format!("WHERE {}", filter);
+
+    let rows = db.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        format!(r#"
+        SELECT h.id, h.household_name, p.first_name, p.last_name
+        FROM households h
+        LEFT JOIN people p ON p.household_id = h.id
+        {}
+        ORDER BY h.id, p.id
+        "#, household_filter),
+        params
+    ))
+    .await
+    .map_err(|e| format!("Failed to fetch household names: {}", e))?;
+
+    let mut households: Vec<(i64, Option<String>, Vec<(String, String)>)> = Vec::new();
+    for row in rows {
+        let id: i64 = row.try_get("", "id")
+            .map_err(|e| format!("Failed to get id: {}", e))?;
+        if households.last().map(|h| h.0) != Some(id) {
+            let household_name: Option<String> = row.try_get("", "household_name").ok().flatten();
+            households.push((id, household_name, Vec::new()));
+        }
+        let first: Option<String> = row.try_get("", "first_name").ok().flatten();
+        let last: Option<String> = row.try_get("", "last_name").ok().flatten();
+        if let (Some(first), Some(last)) = (first, last) {
+            if let Some(household) = households.last_mut() {
+                household.2.push((first, last));
+            }
+        }
+    }
+    Ok(households)
+}
+
+async fn display_names<C: ConnectionTrait>(
+    db: &C,
+    household_ids: Option<&[i64]>,
+) -> Result<HashMap<i64, String>, String> {
+    Ok(load_display_name_inputs(db, household_ids)
+        .await?
+        .into_iter()
+        .map(|(id, name, people)| (id, household_display_name(id, name.as_deref(), &people)))
+        .collect())
+}
+
+/// Rewrite `household_id`'s display_name in the search index. Called after
+/// a household and its people are created.
+pub async fn refresh_household_display_name<C: ConnectionTrait>(
+    db: &C,
+    household_id: i64,
+) -> Result<(), String> {
+    for (id, display_name) in display_names(db, Some(&[household_id])).await? {
+        update_display_name(db, id, display_name).await?;
+    }
+    Ok(())
+}
+
+/// Recompute display_name for every indexed household, e.g. for rows written
+/// before the naming rules changed. Returns how many rows changed.
+pub async fn recompute_household_display_names(db: &DatabaseConnection) -> Result<usize, String> {
+    let txn = db.begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let mut changed = 0;
+    for (id, display_name) in display_names(&txn, None).await? {
+        changed += update_display_name(&txn, id, display_name).await?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| format!("Failed to commit display names: {}", e))?;
+    log::info!("Recomputed household display names, {} changed", changed);
+    Ok(changed)
+}
+
+async fn update_display_name<C: ConnectionTrait>(
+    db: &C,
+    household_id: i64,
+    display_name: String,
+) -> Result<usize, String> {
+    let result = db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE household_search SET display_name = ? WHERE household_id = ? AND display_name IS NOT ?",
+        [display_name.clone().into(), household_id.into(), display_name.into()]
+    ))
+    .await
+    .map_err(|e| format!("Failed to update display name: {}", e))?;
+    Ok(result.rows_affected() as usize)
+}
+
+/// Rewrite `household_id`'s search row from the current tables, e.g. after
+/// it is restored from a soft delete or its people or contacts changed.
+pub async fn reindex_household<C: ConnectionTrait>(db: &C, household_id: i64) -> Result<(), String> {
+    write_search_rows(db, &[household_id]).await
+}
+
+// Replace the search rows of `household_ids` with fresh ones.
+async fn write_search_rows<C: ConnectionTrait>(db: &C, household_ids: &[i64]) -> Result<(), String> {
+    let (filter, params) = households_filter("household_id", Some(household_ids));
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        format!("DELETE FROM household_search WHERE {}", filter),
+        params
+    ))
+    .await
+    .map_err(|e| format!("Failed to clear search index: {}", e))?;
+
+    insert_search_rows(db, Some(household_ids)).await
+}
+
+// Index every household, or just `household_ids`, from the current tables.
+// Soft-deleted households are left out; one without people or contacts
+// gets empty strings for them.
+async fn insert_search_rows<C: ConnectionTrait>(db: &C, household_ids: Option<&[i64]>) -> Result<(), String> {
+    let (filter, params) = households_filter("h.id", household_ids);
+    let household_filter = format!("WHERE h.deleted_at IS NULL AND {}", filter);
+
     let household_rows = db.query_all(Statement::from_sql_and_values(
         DbBackend::Sqlite,
-        r#"
+        format!(r#"
         SELECT
             h.id,
             h.household_name,
@@ -518,17 +900,19 @@ pub async fn rebuild_search_index(db: &DatabaseConnection) -> Result<(), String>
             -- ("DISTINCT aggregates must have exactly one argument"). Drop the
             -- DISTINCT here: duplicate names/contacts within a household are
             -- very unlikely, and even if present, FTS5 tokenization dedupes.
-            GROUP_CONCAT(p.first_name || ' ' || p.last_name, ' ') as people_names,
-            GROUP_CONCAT(pc.contact_value, ' ') as contact_values
+            COALESCE(GROUP_CONCAT(p.first_name || ' ' || p.last_name, ' '), '') as people_names,
+            COALESCE(GROUP_CONCAT(pc.contact_value, ' '), '') as contact_values
         FROM households h
         LEFT JOIN people p ON p.household_id = h.id
         LEFT JOIN person_contacts pc ON pc.person_id = p.id
+        {}
         GROUP BY h.id
-        "#,
-        []
+        "#, household_filter),
+        params
     ))
     .await
     .map_err(|e| format!("Failed to fetch households for reindex: {}", e))?;
+    let mut names = display_names(db, household_ids).await?;
 
     for household_row in household_rows {
         let id: i64 = household_row.try_get("", "id")
@@ -538,19 +922,9 @@ pub async fn rebuild_search_index(db: &DatabaseConnection) -> Result<(), String>
         let people_names: Option<String> = household_row.try_get("", "people_names").ok();
         let contact_values: Option<String> = household_row.try_get("", "contact_values").ok();
 
-        // Generate display name
-        let display_name = if let Some(name) = &household_name {
-            name.clone()
-        } else if let Some(people) = &people_names {
-            let names: Vec<&str> = people.split(' ').collect();
-            if names.len() == 2 {
-                format!("{} & {}", names[0], names[1])
-            } else {
-                people.clone()
-            }
-        } else {
-            format!("Household {}", id)
-        };
+        let display_name = names
+            .remove(&id)
+            .unwrap_or_else(|| format!("Household {}", id));
 
         db.execute(Statement::from_sql_and_values(
             DbBackend::Sqlite,
@@ -13,6 +13,11 @@ pub struct Model {
     pub currency_id: Option<i64>,
     pub theme: String,
     pub date_format: String,
+    pub default_room_id: Option<i64>,
+    pub search_tokenizer: String,
+    pub practice_timezone: Option<String>,
+    pub ocr_enabled: bool,
+    pub auto_archive_after_days: Option<i64>,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
 }
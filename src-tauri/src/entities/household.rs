@@ -15,6 +15,7 @@ pub struct Model {
     pub notes: Option<String>,
     pub created_at: ChronoDateTime,
     pub updated_at: ChronoDateTime,
+    pub deleted_at: Option<ChronoDateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
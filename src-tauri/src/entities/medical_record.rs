@@ -22,6 +22,8 @@ pub struct Model {
     #[sea_orm(column_type = "Double")]
     pub manual_total: Option<f64>,
     pub is_archived: bool,
+    pub follow_up_date: Option<ChronoDate>,
+    pub follow_up_done: bool,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
     pub created_by: Option<String>,
@@ -13,6 +13,8 @@ pub struct Model {
     pub capacity: i32,
     pub color: String,
     pub is_active: bool,
+    pub display_order: i32,
+    pub buffer_minutes: i32,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
 }
@@ -14,6 +14,8 @@ pub struct Model {
     pub name: String,
     pub active: bool,
     pub display_order: i64,
+    /// Hex color (e.g. "#1890ff") used for the species across the UI
+    pub color: String,
     pub created_at: ChronoDateTime,
     pub updated_at: ChronoDateTime,
 }
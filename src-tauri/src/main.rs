@@ -18,6 +18,8 @@ use tauri::{Manager, SystemTray, SystemTrayEvent, CustomMenuItem, SystemTrayMenu
 use tauri_plugin_log::{LogTarget, Builder};
 use log::LevelFilter;
 use services::device_capture::start_device_capture;
+use services::events;
+use services::users::CurrentUser;
 
 fn main() {
     // Pick which "environment" this build reports as. The value flows into
@@ -192,7 +194,7 @@ fn main() {
                     let _ = window.show();
                     let _ = window.set_focus();
                 }
-                let _ = app.emit_all("wake-from-tray", serde_json::json!({"cause": "manual"}));
+                let _ = app.emit_all(events::WAKE_FROM_TRAY, serde_json::json!({"cause": "manual"}));
             }
             SystemTrayEvent::MenuItemClick { id, .. } => {
                 match id.as_str() {
@@ -201,7 +203,7 @@ fn main() {
                             let _ = window.show();
                             let _ = window.set_focus();
                         }
-                        let _ = app.emit_all("wake-from-tray", serde_json::json!({"cause": "manual"}));
+                        let _ = app.emit_all(events::WAKE_FROM_TRAY, serde_json::json!({"cause": "manual"}));
                     }
                     "hide" => {
                         if let Some(window) = app.get_window("main") {
@@ -216,7 +218,7 @@ fn main() {
                             let _ = window.show();
                             let _ = window.set_focus();
                         }
-                        let _ = app.emit_all("wake-from-tray", serde_json::json!({
+                        let _ = app.emit_all(events::WAKE_FROM_TRAY, serde_json::json!({
                             "cause": "scan",
                             "code": code
                         }));
@@ -228,7 +230,7 @@ fn main() {
                             let _ = window.show();
                             let _ = window.set_focus();
                         }
-                        let _ = app.emit_all("wake-from-tray", serde_json::json!({
+                        let _ = app.emit_all(events::WAKE_FROM_TRAY, serde_json::json!({
                             "cause": "file",
                             "fileName": "simulated_exigo.xml",
                             "device": "Exigo Eos Vet",
@@ -332,6 +334,16 @@ fn main() {
                     let _ = window.hide();
                 }
             }
+            // Swap in a DB snapshot staged by restore_from_backup before
+            // anything opens the database file.
+            if let Some(app_data) = app.path_resolver().app_data_dir() {
+                match services::backup::BackupService::apply_pending_restore(&app_data) {
+                    Ok(true) => log::info!("Backup: restored database from staged snapshot"),
+                    Ok(false) => {}
+                    Err(e) => log::error!("Backup: failed to apply staged restore: {}", e),
+                }
+            }
+
             // Get database URL
             let db_url = get_database_url(&app.handle())?;
             log::info!("Database URL: {}", db_url);
@@ -345,6 +357,23 @@ fn main() {
                 // Run migrations using the legacy pool
                 {
                     let pool_guard = legacy_pool.lock().await;
+                    match database::migrations::preview_pending_migrations(&*pool_guard).await {
+                        Ok(preview) => {
+                            for migration in preview.pending.iter().filter(|m| m.destructive) {
+                                let rows: i64 = migration.tables.iter().map(|t| t.rows).sum();
+                                if rows > 0 {
+                                    log::warn!(
+                                        "Migration {} will {} {} row(s) in {}",
+                                        migration.name,
+                                        if migration.drops_data { "drop" } else { "rebuild" },
+                                        rows,
+                                        migration.tables.iter().map(|t| t.table.as_str()).collect::<Vec<_>>().join(", ")
+                                    );
+                                }
+                            }
+                        }
+                        Err(e) => log::warn!("Failed to preview pending migrations: {}", e),
+                    }
                     run_migrations(&*pool_guard).await
                         .map_err(|e| format!("Failed to run migrations: {}", e))?;
                 }
@@ -355,6 +384,8 @@ fn main() {
 
             // Store SeaORM pool in app state (legacy pool only used internally for migrations)
             app.manage(sea_orm_pool.clone());
+            // Nobody is picked until the frontend calls set_current_user
+            app.manage(CurrentUser::default());
 
             // Start the Windows Raw Input capture for managed HID scanners. On
             // non-Windows targets this is a compile-time no-op. The capture
@@ -382,6 +413,12 @@ fn main() {
                 services::sync_scheduler::SyncScheduler::start(sea_orm_pool_for_scheduler);
             });
 
+            // Archive old records while an auto-archive threshold is set
+            let sea_orm_pool_for_auto_archive = sea_orm_pool.clone();
+            tauri::async_runtime::spawn(async move {
+                services::medical_record::MedicalRecordService::start_auto_archive_scheduler(sea_orm_pool_for_auto_archive);
+            });
+
             // Initialize file watcher for device integrations
             let sea_orm_pool_for_watcher = sea_orm_pool.clone();
             let app_handle_for_watcher = app.handle();
@@ -425,6 +462,20 @@ fn main() {
                 }
             });
 
+            // Periodic automatic backups (no-op until enabled in settings)
+            let sea_orm_pool_for_auto_backup = sea_orm_pool.clone();
+            let app_handle_for_auto_backup = app.handle();
+            tauri::async_runtime::spawn(async move {
+                let Some(app_data) = app_handle_for_auto_backup
+                    .path_resolver()
+                    .app_data_dir()
+                else {
+                    log::warn!("Auto backup: could not resolve app_data_dir, scheduler not started");
+                    return;
+                };
+                services::backup::BackupService::start_scheduler(app_data, sea_orm_pool_for_auto_backup);
+            });
+
             // Initialize serial port listeners for device integrations
             let sea_orm_pool_for_serial = sea_orm_pool.clone();
             let app_handle_for_serial = app.handle();
@@ -469,13 +520,25 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // Patient commands
             commands::get_patients,
+            commands::list_patients,
+            commands::rebuild_patient_record_counts,
             commands::get_patient,
+            commands::get_patient_profile,
+            commands::get_patient_age,
+            commands::get_patient_primary_contact,
+            commands::get_patient_weight_trend,
             commands::create_patient,
             commands::update_patient,
             commands::delete_patient,
+            commands::restore_patient,
+            commands::hard_delete_patient,
+            commands::reclassify_patients,
+            commands::merge_patients,
+            commands::import_patients_from_csv,
             commands::search_patients,
             commands::get_patients_by_species,
             commands::advanced_patient_search,
+            commands::get_patients_by_age_bracket,
             // Household commands
             commands::create_household,
             commands::create_household_with_people,
@@ -485,8 +548,15 @@ fn main() {
             commands::get_all_households,
             commands::update_household,
             commands::delete_household,
+            commands::restore_household,
+            commands::purge_households,
             commands::quick_search_households,
+            commands::search_households_by_phone,
+            commands::export_household_data_package,
             commands::rebuild_household_search_index,
+            commands::rebuild_household_search_index_for,
+            commands::recompute_household_display_names,
+            commands::rebuild_household_search_index_with_tokenizer,
             // Household detail view commands
             commands::get_household_detail,
             commands::update_household_fields,
@@ -496,6 +566,8 @@ fn main() {
             commands::update_person_contacts,
             commands::get_household_patients,
             commands::link_patient_to_household,
+            commands::get_patient_households,
+            commands::add_secondary_household,
             commands::unlink_patient_from_household,
             commands::update_patient_household,
             // Medical history commands
@@ -504,39 +576,73 @@ fn main() {
             commands::create_medical_record,
             commands::update_medical_record,
             commands::archive_medical_record,
+            commands::apply_auto_archive,
+            commands::set_record_follow_up,
+            commands::complete_record_follow_up,
+            commands::get_pending_followups,
             commands::upload_medical_attachment,
+            commands::upload_medical_attachments,
+            commands::attach_external_file,
+            commands::search_attachment_text,
+            commands::get_patient_attachments,
             commands::download_medical_attachment,
             commands::delete_medical_attachment,
             commands::get_attachment_content,
             commands::search_medical_records,
+            commands::get_records_changed_since,
             commands::get_currencies,
+            commands::set_exchange_rate,
+            commands::get_patient_charge_summary,
+            commands::list_records_by_type,
             commands::cleanup_orphaned_files,
             commands::get_medical_record_at_version,
             commands::materialize_medical_attachment,
             commands::write_medical_attachment_to_path,
             commands::open_medical_attachment,
+            commands::reveal_medical_attachment,
             commands::print_medical_attachment,
             commands::render_medical_attachment_pdf_thumbnail,
             commands::render_medical_attachment_pdf_thumbnail_force,
+            commands::prewarm_attachment_thumbnails,
             commands::render_medical_attachment_pdf_page_png,
             commands::get_medical_attachment_pdf_page_count,
+            commands::get_medical_record_diff,
             commands::revert_medical_record,
+            commands::revert_medical_record_to_version,
+            commands::change_record_type,
+            commands::move_records_to_patient,
             commands::regenerate_pdf_from_attachment,
             commands::regenerate_pdf_from_medical_record,
+            commands::create_pdf_job,
+            commands::cancel_pdf_job,
             commands::generate_configured_report,
+            commands::render_sample_device_report,
+            commands::export_selected_records_pdf,
             // Record template commands
             commands::get_record_templates,
             commands::search_record_templates,
+            commands::preview_record_from_template,
             commands::create_record_template,
             commands::update_record_template,
             commands::delete_record_template,
             // Settings commands
             commands::get_app_settings,
             commands::update_app_settings,
+            commands::set_default_room,
+            commands::get_practice_timezone,
+            commands::set_practice_timezone,
+            commands::set_ocr_enabled,
+            commands::set_auto_archive_after_days,
+            commands::export_clinic_profile,
+            commands::import_clinic_profile,
             // Note: get_currencies is already registered above for medical
             // Database commands
             commands::init_database,
             commands::test_database_connection,
+            commands::run_data_integrity_scan,
+            commands::verify_attachments_integrity,
+            commands::get_storage_breakdown,
+            commands::repair_primary_flags,
             // View preference commands
             commands::get_view_preference,
             commands::set_view_preference,
@@ -548,6 +654,7 @@ fn main() {
             commands::populate_database,
             // Stats commands
             commands::get_dashboard_stats,
+            commands::get_today_overview,
             // Appointment commands
             commands::get_appointments,
             commands::get_appointment,
@@ -556,11 +663,20 @@ fn main() {
             commands::delete_appointment,
             commands::check_conflicts,
             commands::duplicate_appointment,
+            commands::get_calendar_view,
+            commands::export_day_sheet_pdf,
+            commands::get_appointment_statistics,
+            commands::validate_appointment_import,
+            commands::find_next_available_slot,
+            commands::reassign_room_appointments,
             // Room commands
             commands::get_rooms,
+            commands::get_room_list,
             commands::get_room,
             commands::create_room,
             commands::update_room,
+            commands::duplicate_room,
+            commands::reorder_rooms,
             commands::delete_room,
             commands::get_room_availability,
             // Update settings commands
@@ -576,21 +692,34 @@ fn main() {
             commands::update_sync_enabled,
             commands::disconnect_google_calendar,
             commands::revoke_google_access,
+            commands::test_google_connection,
+            commands::list_google_calendars,
+            commands::set_sync_calendar,
             commands::trigger_manual_sync,
             commands::get_sync_history,
+            commands::get_appointment_sync_status,
+            commands::get_appointment_sync_log,
+            commands::get_appointments_pending_sync,
+            commands::retry_failed_syncs,
+            commands::export_sync_log_csv,
+            commands::export_audit_log_csv,
             commands::check_sync_status,
             // Species commands
             commands::get_species,
+            commands::get_species_colors,
             commands::get_species_by_id,
             commands::create_species,
             commands::update_species,
             commands::delete_species,
             // Breed commands
             commands::get_breeds,
+            commands::suggest_breeds,
             commands::get_breed,
             commands::create_breed,
             commands::update_breed,
             commands::delete_breed,
+            commands::find_duplicate_breeds,
+            commands::merge_breeds,
             // Device input commands
             commands::get_available_ports,
             commands::resolve_patient_from_identifier,
@@ -599,14 +728,29 @@ fn main() {
             commands::reconnect_device_listeners,
             commands::list_serial_port_names,
             commands::get_device_connection_statuses,
+            commands::clear_device_connection_status,
+            commands::start_raw_capture,
+            commands::get_raw_capture,
+            commands::stop_raw_capture,
             commands::get_file_watcher_statuses,
+            commands::get_recent_device_data,
+            commands::get_last_device_result,
+            commands::validate_device_payload,
+            commands::get_device_result_schema,
+            commands::get_device_protocol_defaults,
             // Device integration commands
             commands::get_device_integrations,
+            commands::get_device_integrations_with_status,
             commands::get_device_integration,
             commands::create_device_integration,
             commands::update_device_integration,
             commands::delete_device_integration,
             commands::toggle_device_integration_enabled,
+            commands::set_device_integration_enabled,
+            commands::get_device_parse_mappings,
+            commands::save_device_parse_mapping,
+            commands::delete_device_parse_mapping,
+            commands::parse_json_device_payload,
             // File history commands
             commands::get_recent_device_files,
             commands::get_file_history,
@@ -638,6 +782,9 @@ fn main() {
             commands::get_backup_config,
             commands::set_backup_directory,
             commands::run_backup_now,
+            commands::set_auto_backup,
+            commands::get_backup_history,
+            commands::restore_from_backup,
             // Managed HID scanner commands (Windows Raw Input filter list)
             commands::get_managed_hid_scanners,
             commands::create_managed_hid_scanner,
@@ -653,10 +800,27 @@ fn main() {
             commands::get_diagnoses_for_record,
             commands::set_diagnoses_for_record,
             commands::get_diagnoses_for_patient,
+            commands::add_diagnosis_to_records,
+            commands::remove_diagnosis_from_records,
+            // Vaccine protocols + recorded vaccinations
+            commands::get_vaccine_protocols,
+            commands::create_vaccine_protocol,
+            commands::update_vaccine_protocol,
+            commands::delete_vaccine_protocol,
+            commands::record_vaccination,
+            commands::get_patient_vaccinations,
+            // Clinic staff + current user for created_by attribution
+            commands::get_users,
+            commands::create_user,
+            commands::update_user,
+            commands::delete_user,
+            commands::set_current_user,
+            commands::get_current_user,
             // Bridges React-side telemetry (ErrorBoundary, invoke wrapper)
             // into Rust's structured logger so frontend events flow through
             // the same Arkivet.log → Loki pipeline.
             commands::log_event,
+            commands::list_event_channels,
         ])
         .run(context)
         .expect("error while running tauri application");
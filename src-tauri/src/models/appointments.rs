@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc, Timelike};
+use chrono::{DateTime, NaiveDate, Utc, Timelike};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -40,6 +40,25 @@ pub enum AppointmentStatus {
     Cancelled,
 }
 
+impl AppointmentStatus {
+    /// Completed and cancelled appointments keep their status.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, AppointmentStatus::Completed | AppointmentStatus::Cancelled)
+    }
+
+    /// Whether an appointment may move from this status to `next`:
+    /// scheduled -> in progress -> completed, or cancelled from scheduled
+    /// or in progress. Keeping the same status is always allowed.
+    pub fn can_transition_to(&self, next: &AppointmentStatus) -> bool {
+        use AppointmentStatus::*;
+        self == next
+            || matches!(
+                (self, next),
+                (Scheduled, InProgress) | (InProgress, Completed) | (Scheduled | InProgress, Cancelled)
+            )
+    }
+}
+
 impl std::fmt::Display for AppointmentStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -95,6 +114,10 @@ pub struct UpdateAppointmentInput {
     pub end_time: Option<DateTime<Utc>>,
     pub room_id: Option<i64>,
     pub status: Option<AppointmentStatus>,
+    /// Admin correction: set `status` even where the transition isn't
+    /// allowed
+    #[serde(default)]
+    pub override_status_check: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,10 +167,192 @@ pub struct ConflictCheckInput {
     pub exclude_appointment_id: Option<i64>,
 }
 
+/// Whether a booking in the checked slot would overbook the room. A room
+/// takes up to `capacity` overlapping appointments; without a room, any
+/// overlap is a conflict.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictCheckResponse {
     pub has_conflicts: bool,
+    /// Every overlapping appointment, whether or not the room is full
     pub conflicts: Vec<Appointment>,
+    /// How many appointments already overlap the slot in the room
+    pub occupancy: usize,
+    pub capacity: Option<i32>,
+}
+
+/// Span of a calendar grid. Weeks start on Monday, as in the calendar UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarViewMode {
+    Day,
+    Week,
+    Month,
+}
+
+/// An appointment placed on the grid. Overlapping appointments on the same
+/// day share a `lane_count` and each gets its own `lane` (0-based column).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEntry {
+    #[serde(flatten)]
+    pub appointment: Appointment,
+    pub lane: u32,
+    pub lane_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub entries: Vec<CalendarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarView {
+    pub view: CalendarViewMode,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// One bucket per date in `start_date..=end_date`, including empty days.
+    pub days: Vec<CalendarDay>,
+}
+
+/// One appointment on the printed day sheet. `time` is the start-end range
+/// formatted in the practice timezone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaySheetEntry {
+    pub appointment_id: i64,
+    pub start_time: DateTime<Utc>,
+    pub time: String,
+    pub patient_name: String,
+    /// Display name of the patient's primary household.
+    pub owner: Option<String>,
+    pub room_id: Option<i64>,
+    pub room_name: Option<String>,
+    pub status: AppointmentStatus,
+    pub title: String,
+    pub notes: Option<String>,
+}
+
+/// Day sheet rows under one heading. `room_name` is set when the sheet is
+/// grouped by room and None for the single ungrouped section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaySheetSection {
+    pub room_id: Option<i64>,
+    pub room_name: Option<String>,
+    pub entries: Vec<DaySheetEntry>,
+}
+
+/// One appointment from a migration file, before it is resolved against
+/// the database. The patient is matched by `patient_id` or, failing that,
+/// `microchip_id`; the room by `room_id` or case-insensitive `room_name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentImportEntry {
+    pub uid: Option<String>,
+    pub patient_id: Option<i64>,
+    pub microchip_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub room_id: Option<i64>,
+    pub room_name: Option<String>,
+}
+
+/// Validation outcome for a single import entry. `index` is the entry's
+/// position in the submitted batch; `conflicting_entries` holds indices of
+/// other entries in the batch it overlaps with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentImportEntryReport {
+    pub index: usize,
+    pub uid: Option<String>,
+    pub patient_id: Option<i64>,
+    pub room_id: Option<i64>,
+    pub errors: Vec<String>,
+    pub duplicate_of: Option<usize>,
+    pub conflicting_entries: Vec<usize>,
+    pub conflicting_appointments: Vec<i64>,
+}
+
+impl AppointmentImportEntryReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+            && self.duplicate_of.is_none()
+            && self.conflicting_entries.is_empty()
+            && self.conflicting_appointments.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentImportReport {
+    pub entries: Vec<AppointmentImportEntryReport>,
+    pub valid_count: usize,
+    pub invalid_count: usize,
+}
+
+/// A free slot found by `find_next_available_slot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailableSlot {
+    pub room_id: i64,
+    pub room_name: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+/// An appointment `reassign_room_appointments` left in its room because the
+/// target room was full at that time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmovedAppointment {
+    pub appointment_id: i64,
+    /// Appointments in the target room it would have overlapped
+    pub conflicting_appointment_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomReassignmentResult {
+    pub moved_appointment_ids: Vec<i64>,
+    pub unmoved: Vec<UnmovedAppointment>,
+    /// Whether the source room was set inactive
+    pub source_deactivated: bool,
+}
+
+/// Breakdown used by `get_appointment_statistics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppointmentStatsGroupBy {
+    Room,
+    Species,
+    Status,
+    DayOfWeek,
+}
+
+/// Appointment counts for one room, species, status or weekday. `key` is
+/// the room/species id, the status, or the ISO weekday (1 = Monday); it is
+/// None for appointments without a room or species. `label` carries the
+/// room or species name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentStatsGroup {
+    pub key: Option<String>,
+    pub label: Option<String>,
+    pub total: i64,
+    pub completed: i64,
+    pub cancelled: i64,
+    /// Over appointments that weren't cancelled
+    pub average_duration_minutes: Option<f64>,
+}
+
+/// Aggregates over the appointments starting in a period. Durations and the
+/// busiest weekday/hour leave cancelled appointments out; weekday and hour
+/// are in practice-local time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentStatistics {
+    pub total: i64,
+    pub completed: i64,
+    pub cancelled: i64,
+    pub average_duration_minutes: Option<f64>,
+    /// ISO weekday, 1 = Monday
+    pub busiest_day_of_week: Option<u32>,
+    /// Hour of day, 0-23
+    pub busiest_hour: Option<u32>,
+    pub group_by: AppointmentStatsGroupBy,
+    pub groups: Vec<AppointmentStatsGroup>,
 }
 
 // Validation helpers
@@ -441,6 +646,7 @@ mod tests {
                 end_time: None,
                 room_id: None,
                 status: None,
+                override_status_check: false,
             };
             assert!(input.validate().is_ok());
         }
@@ -454,6 +660,7 @@ mod tests {
                 end_time: None,
                 room_id: None,
                 status: None,
+                override_status_check: false,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -469,6 +676,7 @@ mod tests {
                 end_time: Some(make_time(15, 30)),
                 room_id: None,
                 status: None,
+                override_status_check: false,
             };
             assert!(input.validate().is_ok());
         }
@@ -482,6 +690,7 @@ mod tests {
                 end_time: Some(make_time(14, 0)),
                 room_id: None,
                 status: None,
+                override_status_check: false,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -498,6 +707,7 @@ mod tests {
                 end_time: None,
                 room_id: None,
                 status: None,
+                override_status_check: false,
             };
             assert!(input.validate().is_ok());
         }
@@ -511,6 +721,7 @@ mod tests {
                 end_time: None,
                 room_id: None,
                 status: Some(AppointmentStatus::Completed),
+                override_status_check: false,
             };
             assert!(input.validate().is_ok());
         }
@@ -532,6 +743,32 @@ mod tests {
             assert_eq!(AppointmentStatus::Scheduled, AppointmentStatus::Scheduled);
             assert_ne!(AppointmentStatus::Scheduled, AppointmentStatus::Cancelled);
         }
+
+        #[test]
+        fn transitions_follow_the_visit() {
+            use AppointmentStatus::*;
+            let all = [Scheduled, InProgress, Completed, Cancelled];
+            let allowed = [
+                (Scheduled, InProgress),
+                (InProgress, Completed),
+                (Scheduled, Cancelled),
+                (InProgress, Cancelled),
+            ];
+            for from in &all {
+                for to in &all {
+                    let expected = from == to || allowed.contains(&(from.clone(), to.clone()));
+                    assert_eq!(from.can_transition_to(to), expected, "{} -> {}", from, to);
+                }
+            }
+            assert!(Completed.is_terminal() && Cancelled.is_terminal());
+            assert!(!Scheduled.is_terminal() && !InProgress.is_terminal());
+        }
+
+        #[test]
+        fn override_flag_defaults_to_off() {
+            let input: UpdateAppointmentInput = serde_json::from_str(r#"{"status":"completed"}"#).unwrap();
+            assert!(!input.override_status_check);
+        }
     }
 
     mod appointment_filter {
@@ -37,6 +37,23 @@ pub struct UpdateBreedInput {
     pub active: Option<bool>,
 }
 
+/// A breed in a `find_duplicate_breeds` cluster, with how many patients
+/// use it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+pub struct DuplicateBreedCandidate {
+    pub breed: Breed,
+    #[ts(type = "number")]
+    pub patient_count: i64,
+}
+
+/// Breeds of one species with similar names, most used first.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+pub struct DuplicateBreedCluster {
+    pub breeds: Vec<DuplicateBreedCandidate>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BreedFilter {
     pub species_id: Option<i64>,
@@ -14,6 +14,13 @@ pub enum DeviceType {
 }
 
 impl DeviceType {
+    pub const ALL: [DeviceType; 4] = [
+        DeviceType::ExigoEosVet,
+        DeviceType::HealvetHvFia3000,
+        DeviceType::MnchipPointcareChemistry,
+        DeviceType::MnchipPcrAnalyzer,
+    ];
+
     pub fn to_db_string(&self) -> &str {
         match self {
             DeviceType::ExigoEosVet => "exigo_eos_vet",
@@ -33,7 +40,6 @@ impl DeviceType {
         }
     }
 
-    #[allow(dead_code)]
     pub fn display_name(&self) -> &str {
         match self {
             DeviceType::ExigoEosVet => "Exigo Eos Vet",
@@ -54,7 +60,7 @@ pub enum ConnectionType {
 }
 
 impl ConnectionType {
-    pub fn to_db_string(&self) -> &str {
+    pub fn to_db_string(&self) -> &'static str {
         match self {
             ConnectionType::FileWatch => "file_watch",
             ConnectionType::SerialPort => "serial_port",
@@ -72,6 +78,121 @@ impl ConnectionType {
     }
 }
 
+/// A single problem with the connection fields of an integration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceIntegrationValidationError {
+    /// A field the connection type needs is absent or blank
+    MissingField { connection_type: &'static str, field: &'static str },
+    /// A field belonging to a different connection type is set
+    ConflictingField { connection_type: &'static str, field: &'static str },
+    /// A field is present but its value can never work
+    InvalidField { field: &'static str, reason: &'static str },
+}
+
+impl std::fmt::Display for DeviceIntegrationValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField { connection_type, field } => {
+                write!(f, "{} integration requires {}", connection_type, field)
+            }
+            Self::ConflictingField { connection_type, field } => {
+                write!(f, "{} integration must not set {}", connection_type, field)
+            }
+            Self::InvalidField { field, reason } => write!(f, "Invalid {}: {}", field, reason),
+        }
+    }
+}
+
+/// The connection-specific fields of an integration, resolved to their final
+/// values (after applying an update), so they can be validated together.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionSettings {
+    pub watch_directory: Option<String>,
+    pub file_pattern: Option<String>,
+    pub serial_port_name: Option<String>,
+    pub serial_baud_rate: Option<i64>,
+    pub tcp_host: Option<String>,
+    pub tcp_port: Option<i64>,
+}
+
+impl ConnectionSettings {
+    /// Fields each connection type uses; anything else set is a conflict.
+    fn fields_for(connection_type: &ConnectionType) -> &'static [&'static str] {
+        match connection_type {
+            ConnectionType::FileWatch => &["watch_directory", "file_pattern"],
+            ConnectionType::SerialPort => &["serial_port_name", "serial_baud_rate"],
+            ConnectionType::Hl7Tcp => &["tcp_host", "tcp_port"],
+        }
+    }
+
+    fn is_set(&self, field: &str) -> bool {
+        let present = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+        match field {
+            "watch_directory" => present(&self.watch_directory),
+            "file_pattern" => present(&self.file_pattern),
+            "serial_port_name" => present(&self.serial_port_name),
+            "serial_baud_rate" => self.serial_baud_rate.is_some(),
+            "tcp_host" => present(&self.tcp_host),
+            "tcp_port" => self.tcp_port.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Clear every field that doesn't belong to `connection_type`.
+    pub fn clear_foreign_fields(&mut self, connection_type: &ConnectionType) {
+        let own = Self::fields_for(connection_type);
+        if !own.contains(&"watch_directory") { self.watch_directory = None; }
+        if !own.contains(&"file_pattern") { self.file_pattern = None; }
+        if !own.contains(&"serial_port_name") { self.serial_port_name = None; }
+        if !own.contains(&"serial_baud_rate") { self.serial_baud_rate = None; }
+        if !own.contains(&"tcp_host") { self.tcp_host = None; }
+        if !own.contains(&"tcp_port") { self.tcp_port = None; }
+    }
+
+    /// Check the fields against what `connection_type` needs. Returns every
+    /// problem found, not just the first, so the form can flag them all.
+    pub fn validate(&self, connection_type: &ConnectionType) -> Result<(), Vec<DeviceIntegrationValidationError>> {
+        let type_name = connection_type.to_db_string();
+        let required: &[&'static str] = match connection_type {
+            ConnectionType::FileWatch => &["watch_directory", "file_pattern"],
+            ConnectionType::SerialPort => &["serial_port_name"],
+            ConnectionType::Hl7Tcp => &["tcp_host", "tcp_port"],
+        };
+        let own = Self::fields_for(connection_type);
+        let all = ["watch_directory", "file_pattern", "serial_port_name", "serial_baud_rate", "tcp_host", "tcp_port"];
+
+        let mut errors = Vec::new();
+        for &field in required {
+            if !self.is_set(field) {
+                errors.push(DeviceIntegrationValidationError::MissingField { connection_type: type_name, field });
+            }
+        }
+        for field in all {
+            if !own.contains(&field) && self.is_set(field) {
+                errors.push(DeviceIntegrationValidationError::ConflictingField { connection_type: type_name, field });
+            }
+        }
+        if let Some(port) = self.tcp_port {
+            if !(1..=65535).contains(&port) {
+                errors.push(DeviceIntegrationValidationError::InvalidField {
+                    field: "tcp_port",
+                    reason: "must be between 1 and 65535",
+                });
+            }
+        }
+        if let Some(baud) = self.serial_baud_rate {
+            if baud <= 0 {
+                errors.push(DeviceIntegrationValidationError::InvalidField {
+                    field: "serial_baud_rate",
+                    reason: "must be positive",
+                });
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
 // Domain model
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -150,3 +271,38 @@ pub struct UpdateDeviceIntegrationInput {
 
     pub enabled: Option<bool>,
 }
+
+/// Where one test result sits in a JSON device payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceResultPath {
+    /// Key in `test_results`
+    pub result_key: String,
+    /// e.g. `sample.results[0].value` or `results[code=WBC].value`
+    pub path: String,
+}
+
+/// How the JSON payload of a device without a dedicated parser maps onto
+/// `DeviceData`. One per device type, in `device_parse_mappings`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceParseMapping {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub device_type: String,
+    pub patient_identifier_path: Option<String>,
+    pub result_paths: Vec<DeviceResultPath>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct SaveDeviceParseMappingInput {
+    pub device_type: String,
+    pub patient_identifier_path: Option<String>,
+    pub result_paths: Vec<DeviceResultPath>,
+}
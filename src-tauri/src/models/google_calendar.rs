@@ -56,6 +56,15 @@ pub struct GoogleCalendar {
     pub id: String,
     pub summary: String,
     pub primary: bool,
+    /// "owner", "writer", "reader" or "freeBusyReader"
+    pub access_role: String,
+}
+
+impl GoogleCalendar {
+    /// Whether events can be created in the calendar.
+    pub fn is_writable(&self) -> bool {
+        matches!(self.access_role.as_str(), "owner" | "writer")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -143,6 +143,47 @@ pub struct HouseholdWithPeople {
     pub pet_count: i32,
 }
 
+/// One of the households a patient is linked to, with how it is linked.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+pub struct LinkedHousehold {
+    pub household: Household,
+    pub relationship_type: String,
+    /// The household the patient's owner details come from
+    pub is_primary: bool,
+}
+
+/// How a household's `household_search` row was written: by the insert
+/// triggers, or directly because the triggers left it missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "lowercase")]
+pub enum SearchIndexSource {
+    Trigger,
+    Fallback,
+}
+
+/// Payload of the household search reindex progress event, sent after each
+/// batch of a full rebuild.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+pub struct HouseholdSearchReindexProgress {
+    #[ts(type = "number")]
+    pub indexed: usize,
+    #[ts(type = "number")]
+    pub total: usize,
+}
+
+// Returned by create_household_with_people. Flattened so the response is a
+// HouseholdWithPeople with an extra `search_index` field.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+pub struct CreateHouseholdWithPeopleResult {
+    #[serde(flatten)]
+    pub created: HouseholdWithPeople,
+    pub search_index: SearchIndexSource,
+}
+
 // For patient creation with household
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -190,17 +231,58 @@ impl CreateHouseholdWithPeopleDto {
 
             // Validate contacts
             for contact in &person_with_contacts.contacts {
-                if !["phone", "email", "mobile", "work_phone"].contains(&contact.contact_type.as_str()) {
-                    return Err(format!("Invalid contact type: {}", contact.contact_type));
-                }
-
-                // Basic email validation
-                if contact.contact_type == "email" && !contact.contact_value.contains('@') {
-                    return Err(format!("Invalid email: {}", contact.contact_value));
-                }
+                validate_contact(&contact.contact_type, &contact.contact_value)?;
             }
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Digits a phone number may have; 15 is the E.164 maximum.
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 6..=15;
+
+/// Check a contact value for its type and return it normalized: emails
+/// trimmed, phone numbers reduced to their digits with an optional leading
+/// `+` (`(070) 340-846` becomes `070340846`).
+pub fn validate_contact(contact_type: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match contact_type {
+        "email" => {
+            let valid = match value.split_once('@') {
+                Some((local, domain)) => {
+                    !local.is_empty()
+                        && !domain.contains('@')
+                        && domain.contains('.')
+                        && domain.split('.').all(|label| !label.is_empty())
+                        && !value.chars().any(char::is_whitespace)
+                }
+                None => false,
+            };
+            if !valid {
+                return Err(format!("Invalid email: {}", value));
+            }
+            Ok(value.to_string())
+        }
+        "phone" | "mobile" | "work_phone" => {
+            let (plus, rest) = match value.strip_prefix('+') {
+                Some(rest) => ("+", rest),
+                None => ("", value),
+            };
+            if let Some(c) = rest.chars().find(|c| !c.is_ascii_digit() && !" -./()".contains(*c)) {
+                return Err(format!("Invalid phone number '{}': unexpected '{}'", value, c));
+            }
+            let digits: String = rest.chars().filter(char::is_ascii_digit).collect();
+            if !PHONE_DIGITS.contains(&digits.len()) {
+                return Err(format!(
+                    "Invalid phone number '{}': expected {} to {} digits",
+                    value,
+                    PHONE_DIGITS.start(),
+                    PHONE_DIGITS.end()
+                ));
+            }
+            Ok(format!("{}{}", plus, digits))
+        }
+        _ => Err(format!("Invalid contact type: {}", contact_type)),
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use ts_rs::TS;
 
 use crate::models::line_item::MedicalRecordLineItem;
@@ -26,6 +26,9 @@ pub struct MedicalRecord {
     pub manual_total: Option<f64>,
     pub invoice_number: Option<String>,
     pub is_archived: bool,
+    #[ts(type = "string | null")]
+    pub follow_up_date: Option<NaiveDate>,
+    pub follow_up_done: bool,
     #[ts(type = "string")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "string")]
@@ -59,6 +62,84 @@ pub struct MedicalAttachment {
     pub device_name: Option<String>,
     pub connection_method: Option<String>,
     pub attachment_type: Option<String>,
+    #[ts(type = "number")]
+    pub sort_order: i64,
+    /// Referenced in place (see `external_path`) rather than copied into app storage
+    #[sqlx(default)]
+    pub is_external: bool,
+    pub external_path: Option<String>,
+}
+
+/// An attachment together with the record it hangs off, for the patient
+/// "files" tab which lists attachments across all of a patient's records.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientAttachment {
+    #[serde(flatten)]
+    pub attachment: MedicalAttachment,
+    pub record_name: String,
+    pub record_type: String,
+    pub record_is_archived: bool,
+    #[ts(type = "string")]
+    pub record_created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientAttachmentFilter {
+    /// Exact mime type, or a family like "image/*"
+    pub mime_type: Option<String>,
+    pub attachment_type: Option<String>,
+    /// Include attachments on archived records (default false)
+    pub include_archived: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientAttachmentsResponse {
+    pub attachments: Vec<PatientAttachment>,
+    #[ts(type = "number")]
+    pub total: i64,
+    pub page: i32,
+    pub page_size: i32,
+}
+
+/// One file in a batch upload (see `upload_medical_attachments`)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UploadFile {
+    pub file_name: String,
+    pub file_data: Vec<u8>,
+    pub mime_type: String,
+    pub device_type: Option<String>,
+    pub device_name: Option<String>,
+    pub connection_method: Option<String>,
+    pub attachment_type: Option<String>,
+}
+
+/// Per-file outcome of a batch upload. `index` is the file's position in
+/// the submitted list so the UI can match results back to its queue.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UploadFileResult {
+    pub index: usize,
+    pub file_name: String,
+    #[ts(type = "number | null")]
+    pub attachment_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUploadResponse {
+    pub attachments: Vec<MedicalAttachment>,
+    pub results: Vec<UploadFileResult>,
 }
 
 // T025: MedicalRecordHistory model
@@ -79,6 +160,20 @@ pub struct MedicalRecordHistory {
     pub changed_at: DateTime<Utc>,
 }
 
+/// A field that differs between a history entry's old and new snapshot.
+/// The create entry (version 1) has no old snapshot, so every field is
+/// reported with a null `old`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    #[ts(type = "unknown")]
+    pub old: serde_json::Value,
+    #[ts(type = "unknown")]
+    pub new: serde_json::Value,
+}
+
 // T026: Currency model
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -166,6 +261,10 @@ use crate::models::dto::MaybeNull;
 #[ts(export, export_to = "../../src/types/generated/")]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateMedicalRecordInput {
+    /// Only set when restoring a history snapshot; the frontend changes a
+    /// record's type through `change_record_type`
+    #[serde(skip)]
+    pub record_type: Option<String>,
     pub name: Option<String>,
     pub procedure_name: Option<String>,
     pub description: Option<String>,
@@ -212,6 +311,100 @@ pub struct MedicalRecordsResponse {
     pub total: i64,
     pub page: i32,
     pub page_size: i32,
+    /// At least 1, even with no records
+    #[ts(type = "number")]
+    pub total_pages: i64,
+    pub has_next: bool,
+    pub has_previous: bool,
+}
+
+/// `(total_pages, has_next, has_previous)` for `total` items split into
+/// pages of `page_size`. An empty list is still one (empty) page.
+pub fn page_metadata(total: i64, page: i32, page_size: i32) -> (i64, bool, bool) {
+    let page_size = i64::from(page_size.max(1));
+    let total_pages = ((total + page_size - 1) / page_size).max(1);
+    (total_pages, i64::from(page) < total_pages, page > 1)
+}
+
+/// Records of one patient created or updated since a poll's `since`.
+/// Pass `checked_at` as the next `since` so nothing falls between polls.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MedicalRecordChanges {
+    pub records: Vec<MedicalRecord>,
+    #[ts(type = "string")]
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Lightweight projection of a medical record for list views. Only `id` and
+/// `record_type` are always present; every other field is filled in only
+/// when requested via `fields` and omitted from the payload otherwise.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MedicalRecordSummary {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub record_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub procedure_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prescription_notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "number")]
+    pub currency_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub discount_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub manual_total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub is_archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MedicalRecordSummariesResponse {
+    pub records: Vec<MedicalRecordSummary>,
+    #[ts(type = "number")]
+    pub total: i64,
+    pub page: i32,
+    pub page_size: i32,
+    #[ts(type = "number")]
+    pub total_pages: i64,
+    pub has_next: bool,
+    pub has_previous: bool,
+}
+
+/// Result of `get_medical_records`: full records by default, summaries when
+/// the caller passed a `fields` selection.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(untagged)]
+pub enum MedicalRecordsResult {
+    Full(MedicalRecordsResponse),
+    Summary(MedicalRecordSummariesResponse),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -270,6 +463,24 @@ pub struct UpdateRecordTemplateInput {
     pub currency_id: Option<i64>,
 }
 
+/// Values that replace a template's defaults when previewing a record.
+/// Only provided fields override the template.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePreviewOverrides {
+    /// Must match the template's record type when given
+    pub record_type: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub prescription_notes: Option<String>,
+    pub price: Option<f64>,
+    #[ts(type = "number | null")]
+    pub currency_id: Option<i64>,
+    pub discount_percent: Option<f64>,
+    pub manual_total: Option<f64>,
+}
+
 /// Patient overrides for configurable PDF report generation
 /// All fields are optional - only provided fields override the DB values
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -281,4 +492,174 @@ pub struct PatientOverrides {
     pub gender: Option<String>,
     pub date_of_birth: Option<String>,
     pub microchip_id: Option<String>,
-}
\ No newline at end of file
+}
+/// A record whose follow-up is due, with enough patient and owner context
+/// to act on it from the worklist.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PendingFollowUp {
+    #[ts(type = "number")]
+    pub record_id: i64,
+    pub record_name: String,
+    pub record_type: String,
+    #[ts(type = "string")]
+    pub follow_up_date: NaiveDate,
+    /// Negative when overdue
+    #[ts(type = "number")]
+    pub days_until_due: i64,
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub patient_name: Option<String>,
+    pub species: Option<String>,
+    pub owner_name: Option<String>,
+    pub owner_phone: Option<String>,
+}
+
+/// What one auto-archive pass did.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct AutoArchiveResult {
+    #[ts(type = "number")]
+    pub archived: usize,
+    /// Old enough to archive but left alone because a follow-up is still open
+    #[ts(type = "number")]
+    pub skipped_pending_follow_up: usize,
+}
+
+/// What `move_records_to_patient` moved.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MoveRecordsResult {
+    #[ts(type = "number")]
+    pub moved_records: usize,
+    /// Attachments of the moved records, which follow them to the new patient
+    #[ts(type = "number")]
+    pub moved_attachments: usize,
+}
+
+/// Charged total for one record_type, in the summary's target currency.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ChargeTypeTotal {
+    pub record_type: String,
+    #[ts(type = "number")]
+    pub count: i64,
+    pub total: f64,
+}
+
+/// A priced record left out of a charge summary because one of its
+/// currencies has no exchange rate to the target currency.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedChargeRecord {
+    #[ts(type = "number")]
+    pub record_id: i64,
+    pub record_name: String,
+    pub record_type: String,
+    #[ts(type = "number")]
+    pub currency_id: i64,
+}
+
+/// A patient's charges grouped by record_type and converted into one
+/// currency. Totals are rounded to two decimals.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientChargeSummary {
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub currency: Currency,
+    pub by_type: Vec<ChargeTypeTotal>,
+    pub grand_total: f64,
+    pub skipped: Vec<SkippedChargeRecord>,
+}
+
+/// One record in a `RecordTypeReport`, with patient and owner context.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecordTypeReportRow {
+    #[ts(type = "number")]
+    pub record_id: i64,
+    pub record_name: String,
+    #[ts(type = "string")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub patient_name: Option<String>,
+    pub species: Option<String>,
+    pub owner_name: Option<String>,
+    /// In the report's currency; null when unpriced or skipped
+    pub charge: Option<f64>,
+}
+
+/// A page of the clinic's records of one type in a date range, with the
+/// charge total of every matching record in one currency.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecordTypeReport {
+    pub record_type: String,
+    #[ts(type = "string")]
+    pub from: NaiveDate,
+    #[ts(type = "string")]
+    pub to: NaiveDate,
+    pub currency: Currency,
+    pub records: Vec<RecordTypeReportRow>,
+    #[ts(type = "number")]
+    pub total: i64,
+    pub page: i32,
+    pub page_size: i32,
+    pub total_charge: f64,
+    pub skipped: Vec<SkippedChargeRecord>,
+}
+
+/// An attachment whose OCR text matched a `search_attachment_text` query
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentTextMatch {
+    #[ts(type = "number")]
+    pub attachment_id: i64,
+    #[ts(type = "number")]
+    pub medical_record_id: i64,
+    pub original_name: String,
+    /// Text around the hits, which are wrapped in `[` `]`
+    pub snippet: String,
+}
+
+/// Payload of the `attachment-thumbnails-progress` event, sent once per
+/// attachment as `prewarm_attachment_thumbnails` works through them.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailPrewarmProgress {
+    #[ts(type = "number")]
+    pub attachment_id: i64,
+    /// Attachments done so far, this one included
+    #[ts(type = "number")]
+    pub completed: usize,
+    #[ts(type = "number")]
+    pub total: usize,
+    /// A preview was already cached; nothing was rendered
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+/// Payload of the `attachment-ocr-complete` event, sent once per attachment
+/// after OCR finishes or fails.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentOcrResult {
+    #[ts(type = "number")]
+    pub attachment_id: i64,
+    /// False when no text was found or OCR failed; see `error`
+    pub indexed: bool,
+    pub error: Option<String>,
+}
@@ -15,10 +15,17 @@ pub mod line_item;
 pub mod managed_hid_scanner;
 pub mod hid_devices;
 pub mod diagnosis;
+pub mod vaccination;
+pub mod user;
 
 // Re-exports for public API - some may be unused internally but available for external use
 #[allow(unused_imports)]
-pub use patient::Patient;
+pub use patient::{
+    DuplicatePatientError, Patient, PatientAge, PatientListFilter, PatientListItem, PatientListResponse, PatientPrimaryContact,
+    PatientMergeResult, PatientProfile, PatientReclassification, PatientSort, PatientSortField, PatientWeightTrend,
+    PatientWriteError, RecallCandidate, WeightPoint,
+    WeightTrendDirection,
+};
 #[allow(unused_imports)]
 pub use dto::{CreatePatientDto, UpdatePatientDto};
 #[allow(unused_imports)]
@@ -27,7 +34,8 @@ pub use household::{
     CreateHouseholdDto, CreatePersonDto, CreateContactDto,
     CreatePersonWithContactsDto, CreateHouseholdWithPeopleDto,
     HouseholdSearchResult, SearchHouseholdsResponse,
-    HouseholdWithPeople, CreatePatientWithHouseholdDto
+    HouseholdWithPeople, LinkedHousehold, CreatePatientWithHouseholdDto,
+    SearchIndexSource, CreateHouseholdWithPeopleResult
 };
 #[allow(unused_imports)]
 pub use medical::{
@@ -47,11 +55,16 @@ pub use appointments::{
     Appointment, AppointmentStatus, AppointmentDetail, PatientInfo,
     CreateAppointmentInput, UpdateAppointmentInput, AppointmentFilter,
     AppointmentListResponse, DuplicateAppointmentInput,
-    ConflictCheckInput, ConflictCheckResponse
+    ConflictCheckInput, ConflictCheckResponse,
+    CalendarViewMode, CalendarEntry, CalendarDay, CalendarView,
+    DaySheetEntry, DaySheetSection,
+    AppointmentImportEntry, AppointmentImportEntryReport, AppointmentImportReport,
+    AvailableSlot, RoomReassignmentResult, UnmovedAppointment,
+    AppointmentStatsGroupBy, AppointmentStatsGroup, AppointmentStatistics
 };
 #[allow(unused_imports)]
 pub use rooms::{
-    Room, CreateRoomInput, UpdateRoomInput, RoomFilter,
+    Room, CreateRoomInput, UpdateRoomInput, RoomFilter, RoomList, RoomSort,
     RoomAvailability, RoomAppointmentSlot
 };
 #[allow(unused_imports)]
@@ -64,23 +77,25 @@ pub use google_calendar::{
 };
 #[allow(unused_imports)]
 pub use sync_log::{
-    AppointmentSyncLog, SyncAction, SyncStatus,
-    CreateSyncLogInput, SyncQueueItem, SyncResult, SyncError
+    AppointmentSyncLog, AppointmentSyncStatus, SyncAction, SyncStatus,
+    CreateSyncLogInput, SyncQueueItem, SyncResult, SyncError, SyncRetrySummary,
+    PendingSyncAppointment
 };
 #[allow(unused_imports)]
 pub use update_models::UpdatePreferences;
 #[allow(unused_imports)]
 pub use species::{
-    Species, CreateSpeciesInput, UpdateSpeciesInput
+    Species, CreateSpeciesInput, UpdateSpeciesInput, SpeciesColor
 };
 #[allow(unused_imports)]
 pub use breed::{
-    Breed, CreateBreedInput, UpdateBreedInput
+    Breed, CreateBreedInput, DuplicateBreedCandidate, DuplicateBreedCluster, UpdateBreedInput
 };
 #[allow(unused_imports)]
 pub use device_integration::{
     DeviceIntegration, DeviceType, ConnectionType,
-    CreateDeviceIntegrationInput, UpdateDeviceIntegrationInput
+    CreateDeviceIntegrationInput, UpdateDeviceIntegrationInput,
+    DeviceResultPath, DeviceParseMapping, SaveDeviceParseMappingInput
 };
 #[allow(unused_imports)]
 pub use line_item::{
@@ -94,4 +109,9 @@ pub use managed_hid_scanner::{
 #[allow(unused_imports)]
 pub use diagnosis::{
     Diagnosis, CreateDiagnosisInput, UpdateDiagnosisInput
+};
+#[allow(unused_imports)]
+pub use vaccination::{
+    VaccineProtocol, CreateVaccineProtocolInput, UpdateVaccineProtocolInput,
+    Vaccination, RecordVaccinationInput, OverdueVaccination
 };
\ No newline at end of file
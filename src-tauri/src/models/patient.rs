@@ -35,4 +35,288 @@ pub struct Patient {
     pub created_at: DateTime<Utc>,
     #[ts(type = "string")]
     pub updated_at: DateTime<Utc>,
-}
\ No newline at end of file
+    /// Whole months since date_of_birth as of today; None without a date
+    /// of birth or with one in the future
+    #[sqlx(skip)]
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub age_months: Option<i64>,
+    /// "3y 2m", "5 months" or "2 weeks"; None when age_months is
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub age_display: Option<String>,
+}
+
+/// Age of a patient today, for `get_patient_age`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientAge {
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    #[ts(type = "string | null")]
+    pub date_of_birth: Option<NaiveDate>,
+    #[ts(type = "number | null")]
+    pub age_months: Option<i64>,
+    pub age_display: Option<String>,
+}
+
+/// Another patient already carries the microchip being saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePatientError {
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub patient_name: Option<String>,
+    pub microchip_id: String,
+}
+
+impl std::fmt::Display for DuplicatePatientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Microchip {} is already used by {} (patient #{})",
+            self.microchip_id,
+            self.patient_name.as_deref().unwrap_or("another patient"),
+            self.patient_id
+        )
+    }
+}
+
+/// Error from creating or updating a patient. A duplicate microchip is sent
+/// to the frontend as `{ message, code: "DUPLICATE_MICROCHIP", details }`
+/// so the form can link to the other patient; anything else stays a plain
+/// message string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatientWriteError {
+    DuplicateMicrochip(DuplicatePatientError),
+    Other(String),
+}
+
+impl std::fmt::Display for PatientWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateMicrochip(duplicate) => duplicate.fmt(f),
+            Self::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for PatientWriteError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<PatientWriteError> for String {
+    fn from(error: PatientWriteError) -> Self {
+        error.to_string()
+    }
+}
+
+impl Serialize for PatientWriteError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        match self {
+            Self::DuplicateMicrochip(duplicate) => {
+                let mut error = serializer.serialize_struct("PatientWriteError", 3)?;
+                error.serialize_field("message", &duplicate.to_string())?;
+                error.serialize_field("code", "DUPLICATE_MICROCHIP")?;
+                error.serialize_field("details", duplicate)?;
+                error.end()
+            }
+            Self::Other(message) => serializer.serialize_str(message),
+        }
+    }
+}
+
+/// A patient matched by an age-bracket recall query, with the household
+/// contact to reach out to.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecallCandidate {
+    pub patient: Patient,
+    /// Whole months since date_of_birth
+    #[ts(type = "number")]
+    pub age_months: i64,
+    /// Primary person of the patient's primary household
+    pub owner_name: Option<String>,
+    pub owner_phone: Option<String>,
+    pub owner_email: Option<String>,
+}
+
+/// Filters of `list_patients`; all optional and ANDed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientListFilter {
+    #[ts(type = "number | null")]
+    pub species_id: Option<i64>,
+    pub is_active: Option<bool>,
+    /// Whether the patient has an appointment starting from now on that
+    /// isn't cancelled or deleted
+    pub has_upcoming_appointment: Option<bool>,
+}
+
+/// Columns the patient list can be sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "snake_case")]
+pub enum PatientSortField {
+    #[default]
+    Name,
+    Species,
+    Breed,
+    Owner,
+    DateOfBirth,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientSort {
+    pub field: PatientSortField,
+    pub descending: bool,
+}
+
+/// A row of the main patient table.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientListItem {
+    pub patient: Patient,
+    /// Whole months since date_of_birth, if known
+    #[ts(type = "number | null")]
+    pub age_months: Option<i64>,
+    /// Primary person of the patient's primary household
+    pub owner_name: Option<String>,
+    /// Non-archived medical records
+    #[ts(type = "number")]
+    pub record_count: i64,
+    /// Attachments across all of the patient's medical records, archived
+    /// ones included
+    #[ts(type = "number")]
+    pub attachment_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientListResponse {
+    pub patients: Vec<PatientListItem>,
+    #[ts(type = "number")]
+    pub total: i64,
+    pub page: i32,
+    pub page_size: i32,
+}
+
+/// Who to contact about a patient: the primary household's primary person
+/// (or the first one added) with their preferred phone and email.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientPrimaryContact {
+    /// Person's full name, or the household name if it has no people
+    pub name: String,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    #[ts(type = "number")]
+    pub household_id: i64,
+}
+
+/// Which way a patient's weight moved over the trend window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "snake_case")]
+pub enum WeightTrendDirection {
+    Gaining,
+    Stable,
+    Losing,
+    /// Fewer than two weights recorded in the window
+    InsufficientData,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct WeightPoint {
+    pub weight: f64,
+    #[ts(type = "string")]
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A patient's weight change over the last `window_days`, oldest point first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientWeightTrend {
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    #[ts(type = "number")]
+    pub window_days: i64,
+    pub direction: WeightTrendDirection,
+    /// First to last point in the window; `None` with insufficient data
+    pub percent_change: Option<f64>,
+    /// Whether the change reaches `threshold_percent` either way
+    pub is_significant: bool,
+    pub threshold_percent: f64,
+    pub points: Vec<WeightPoint>,
+}
+
+/// Everything the patient detail screen shows, in one call.
+///
+/// Not exported to TS: it embeds `Appointment`, which has no generated type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatientProfile {
+    pub patient: Patient,
+    /// Whole months since date_of_birth, if known
+    pub age_months: Option<i64>,
+    /// The patient's primary household, if linked to one
+    pub household: Option<super::household::HouseholdWithPeople>,
+    /// The household's primary person (or the first one added)
+    pub primary_owner: Option<super::household::PersonWithContacts>,
+    /// Non-archived medical records
+    pub medical_record_count: i64,
+    /// Attachments across all of the patient's medical records
+    pub attachment_count: i64,
+    /// Earliest upcoming appointment that isn't cancelled or deleted
+    pub next_appointment: Option<super::appointments::Appointment>,
+}
+
+/// Outcome of `merge_patients`: the surviving patient and what was moved
+/// to it from the merged one.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientMergeResult {
+    pub patient: Patient,
+    #[ts(type = "number")]
+    pub medical_records_moved: u64,
+    #[ts(type = "number")]
+    pub appointments_moved: u64,
+    #[ts(type = "number")]
+    pub vaccinations_moved: u64,
+    #[ts(type = "number")]
+    pub weights_moved: u64,
+    #[ts(type = "number")]
+    pub households_moved: u64,
+    /// Households the target was already linked to; left on the source
+    #[ts(type = "number")]
+    pub households_skipped: u64,
+}
+
+/// Outcome of `reclassify_patients`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientReclassification {
+    #[ts(type = "number")]
+    pub patients_updated: u64,
+    /// Patients that had a breed and were reclassified without one
+    #[ts(type = "number")]
+    pub breeds_cleared: u64,
+}
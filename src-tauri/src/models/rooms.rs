@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use crate::models::dto::MaybeNull;
 
+/// Longest turnover time a room can be given.
+pub const MAX_BUFFER_MINUTES: i32 = 240;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Room {
     pub id: i64,
@@ -11,6 +14,10 @@ pub struct Room {
     pub capacity: i32,
     pub color: String,
     pub is_active: bool,
+    /// Position among the rooms when listed by `RoomSort::DisplayOrder`
+    pub display_order: i32,
+    /// Turnover time kept free before and after each appointment
+    pub buffer_minutes: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -21,6 +28,8 @@ pub struct CreateRoomInput {
     pub description: Option<String>,
     pub capacity: Option<i32>,
     pub color: Option<String>,
+    #[serde(default)]
+    pub buffer_minutes: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,20 +40,49 @@ pub struct UpdateRoomInput {
     #[serde(default)]
     pub color: MaybeNull<String>,
     pub is_active: Option<bool>,
+    #[serde(default)]
+    pub buffer_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomSort {
+    #[default]
+    Name,
+    /// The order set with `reorder_rooms`, then name
+    DisplayOrder,
 }
 
+/// Inactive rooms are left out unless `include_inactive` is set.
+/// `active_only: false` is the older way of asking for them too.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct RoomFilter {
     pub active_only: bool,
+    pub include_inactive: bool,
+    pub sort: RoomSort,
 }
 
 impl Default for RoomFilter {
     fn default() -> Self {
-        Self { active_only: true }
+        Self { active_only: true, include_inactive: false, sort: RoomSort::Name }
     }
 }
 
+impl RoomFilter {
+    pub fn lists_inactive(&self) -> bool {
+        self.include_inactive || !self.active_only
+    }
+}
+
+/// Rooms matching a `RoomFilter`, with counts over all rooms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomList {
+    pub rooms: Vec<Room>,
+    pub active_count: u64,
+    pub inactive_count: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoomAvailability {
     pub room: Room,
@@ -80,6 +118,8 @@ impl CreateRoomInput {
             }
         }
 
+        validate_buffer_minutes(self.buffer_minutes)?;
+
         Ok(())
     }
 }
@@ -101,6 +141,8 @@ impl UpdateRoomInput {
             }
         }
 
+        validate_buffer_minutes(self.buffer_minutes)?;
+
         Ok(())
     }
 
@@ -110,6 +152,17 @@ impl UpdateRoomInput {
             || self.capacity.is_some()
             || !matches!(self.color, MaybeNull::Undefined)
             || self.is_active.is_some()
+            || self.buffer_minutes.is_some()
+    }
+}
+
+fn validate_buffer_minutes(buffer_minutes: Option<i32>) -> Result<(), String> {
+    match buffer_minutes {
+        Some(minutes) if !(0..=MAX_BUFFER_MINUTES).contains(&minutes) => Err(format!(
+            "Room buffer must be between 0 and {} minutes",
+            MAX_BUFFER_MINUTES
+        )),
+        _ => Ok(()),
     }
 }
 
@@ -127,6 +180,7 @@ mod tests {
                 description: Some("Main examination room".to_string()),
                 capacity: Some(2),
                 color: Some("#3498db".to_string()),
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -138,6 +192,7 @@ mod tests {
                 description: None,
                 capacity: None,
                 color: None,
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -149,6 +204,7 @@ mod tests {
                 description: None,
                 capacity: None,
                 color: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -162,6 +218,7 @@ mod tests {
                 description: None,
                 capacity: None,
                 color: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -175,6 +232,7 @@ mod tests {
                 description: None,
                 capacity: None,
                 color: None,
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -186,6 +244,7 @@ mod tests {
                 description: None,
                 capacity: Some(0),
                 color: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -199,12 +258,30 @@ mod tests {
                 description: None,
                 capacity: Some(-5),
                 color: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
             assert_eq!(result.unwrap_err(), "Room capacity must be at least 1");
         }
 
+        #[test]
+        fn buffer_outside_range_fails() {
+            for buffer_minutes in [-1, MAX_BUFFER_MINUTES + 1] {
+                let input = CreateRoomInput {
+                    name: "Surgery".to_string(),
+                    description: None,
+                    capacity: None,
+                    color: None,
+                    buffer_minutes: Some(buffer_minutes),
+                };
+                assert_eq!(
+                    input.validate().unwrap_err(),
+                    "Room buffer must be between 0 and 240 minutes"
+                );
+            }
+        }
+
         #[test]
         fn capacity_one_passes() {
             let input = CreateRoomInput {
@@ -212,6 +289,7 @@ mod tests {
                 description: None,
                 capacity: Some(1),
                 color: None,
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -228,6 +306,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -240,6 +319,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -252,6 +332,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -266,6 +347,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -280,6 +362,7 @@ mod tests {
                 capacity: Some(0),
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             let result = input.validate();
             assert!(result.is_err());
@@ -294,6 +377,7 @@ mod tests {
                 capacity: Some(10),
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -306,6 +390,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: Some(false),
+                buffer_minutes: None,
             };
             assert!(input.validate().is_ok());
         }
@@ -322,6 +407,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(!input.has_updates());
         }
@@ -334,6 +420,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.has_updates());
         }
@@ -346,6 +433,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.has_updates());
         }
@@ -358,6 +446,7 @@ mod tests {
                 capacity: Some(5),
                 color: MaybeNull::Undefined,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.has_updates());
         }
@@ -370,6 +459,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Value("#ff0000".to_string()),
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.has_updates());
         }
@@ -382,6 +472,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Null,
                 is_active: None,
+                buffer_minutes: None,
             };
             assert!(input.has_updates());
         }
@@ -394,6 +485,7 @@ mod tests {
                 capacity: None,
                 color: MaybeNull::Undefined,
                 is_active: Some(true),
+                buffer_minutes: None,
             };
             assert!(input.has_updates());
         }
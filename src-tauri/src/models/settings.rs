@@ -16,6 +16,18 @@ pub struct AppSettings {
     pub currency_id: Option<i64>,
     pub theme: String,
     pub date_format: String,
+    /// Room new appointments default to when none is chosen
+    #[ts(type = "number | null")]
+    pub default_room_id: Option<i64>,
+    /// Tokenizer the household search index is built with ("unicode61" or "porter")
+    pub search_tokenizer: String,
+    /// IANA timezone of the practice; the machine's timezone when unset
+    pub practice_timezone: Option<String>,
+    /// Read text out of scanned attachments after upload so they're searchable
+    pub ocr_enabled: bool,
+    /// Records older than this many days are archived automatically; off when unset
+    #[ts(type = "number | null")]
+    pub auto_archive_after_days: Option<i64>,
     #[ts(type = "string")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "string")]
@@ -12,6 +12,7 @@ pub struct Species {
     pub active: bool,
     #[ts(type = "number")]
     pub display_order: i64,
+    pub color: String,
     #[ts(type = "string")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "string")]
@@ -24,6 +25,8 @@ pub struct CreateSpeciesInput {
     pub name: String,
     #[ts(type = "number | null")]
     pub display_order: Option<i64>,
+    /// `#RRGGBB` or `#RGB`; the column default applies when omitted.
+    pub color: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -33,4 +36,15 @@ pub struct UpdateSpeciesInput {
     pub active: Option<bool>,
     #[ts(type = "number | null")]
     pub display_order: Option<i64>,
+    pub color: Option<String>,
+}
+
+/// Species id → color, for legends and coloring patients by species.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+pub struct SpeciesColor {
+    #[ts(type = "number")]
+    pub species_id: i64,
+    pub name: String,
+    pub color: String,
 }
@@ -62,6 +62,8 @@ impl std::fmt::Display for SyncType {
 #[allow(dead_code)]
 pub type SyncLogCreate = CreateSyncLogInput;
 
+/// One push attempt for an appointment, from `appointment_sync_log`. A
+/// failed attempt is updated in place when it is retried.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct AppointmentSyncLog {
     pub id: i64,
@@ -71,6 +73,8 @@ pub struct AppointmentSyncLog {
     pub sync_status: SyncStatus,
     pub error_message: Option<String>,
     pub synced_at: DateTime<Utc>,
+    pub retry_count: i64,
+    pub next_retry_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
@@ -167,6 +171,38 @@ pub struct SyncError {
     pub action: SyncAction,
 }
 
+/// Google Calendar sync state of a single appointment, for the per-appointment
+/// sync badge. `last_error` is set only while the most recent attempt failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppointmentSyncStatus {
+    pub appointment_id: i64,
+    pub synced: bool,
+    pub event_id: Option<String>,
+    pub calendar_id: Option<String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// An appointment the push sync still has to send to Google: `Create` when
+/// it has no event yet, `Update` when it changed after its last sync, and
+/// `Delete` when it was deleted while its event is still on the calendar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSyncAppointment {
+    pub appointment_id: i64,
+    pub action: SyncAction,
+    pub event_id: Option<String>,
+    pub calendar_id: Option<String>,
+}
+
+/// Outcome of a `retry_failed_syncs` pass. `retried` counts pushes actually
+/// re-attempted, so it equals `succeeded + failed`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncRetrySummary {
+    pub retried: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+}
+
 impl AppointmentSyncLog {
     #[allow(dead_code)]
     pub fn is_success(&self) -> bool {
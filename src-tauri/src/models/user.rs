@@ -0,0 +1,41 @@
+//! Clinic staff who use the app. The user picked with `set_current_user`
+//! is written to `created_by` / `updated_by` and the audit log's actor.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Roles a user can have.
+pub const USER_ROLES: [&str; 4] = ["admin", "veterinarian", "technician", "receptionist"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub name: String,
+    pub role: String,
+    pub active: bool,
+    #[ts(type = "string")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserInput {
+    pub name: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserInput {
+    pub name: Option<String>,
+    pub role: Option<String>,
+    pub active: Option<bool>,
+}
@@ -0,0 +1,101 @@
+//! Vaccinations given to a patient and the protocols that schedule the
+//! next dose.
+//!
+//! A protocol says how long a vaccine lasts for one species (e.g. rabies
+//! for dogs: 365 days). When a vaccination is recorded without an explicit
+//! next-due date, the matching protocol supplies it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct VaccineProtocol {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub vaccine_name: String,
+    #[ts(type = "number")]
+    pub species_id: i64,
+    /// Days from administration until the next dose is due
+    #[ts(type = "number")]
+    pub interval_days: i64,
+    #[ts(type = "string")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVaccineProtocolInput {
+    pub vaccine_name: String,
+    #[ts(type = "number")]
+    pub species_id: i64,
+    #[ts(type = "number")]
+    pub interval_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVaccineProtocolInput {
+    pub vaccine_name: Option<String>,
+    #[ts(type = "number | null")]
+    pub species_id: Option<i64>,
+    #[ts(type = "number | null")]
+    pub interval_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct Vaccination {
+    #[ts(type = "number")]
+    pub id: i64,
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub vaccine_name: String,
+    #[ts(type = "string")]
+    pub administered_at: DateTime<Utc>,
+    #[ts(type = "string | null")]
+    pub next_due_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecordVaccinationInput {
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub vaccine_name: String,
+    #[ts(type = "string")]
+    pub administered_at: DateTime<Utc>,
+    /// Overrides the protocol; when absent the protocol for the vaccine and
+    /// the patient's species decides, and without one there is no next dose
+    #[ts(type = "string | null")]
+    pub next_due_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// A patient's latest dose of a vaccine whose next dose is past due.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct OverdueVaccination {
+    #[ts(type = "number")]
+    pub vaccination_id: i64,
+    #[ts(type = "number")]
+    pub patient_id: i64,
+    pub patient_name: Option<String>,
+    pub vaccine_name: String,
+    #[ts(type = "string")]
+    pub administered_at: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub next_due_at: DateTime<Utc>,
+}
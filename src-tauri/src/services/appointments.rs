@@ -1,13 +1,32 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
 use sea_orm::*;
 use crate::entities::appointment::{self, Entity as AppointmentEntity};
-use crate::entities::room::Entity as RoomEntity;
+use crate::entities::patient::{self, Entity as PatientEntity};
+use crate::entities::room::{self, Entity as RoomEntity};
 use crate::models::{
     Appointment, AppointmentDetail, PatientInfo,
     CreateAppointmentInput, UpdateAppointmentInput, AppointmentFilter,
     AppointmentListResponse, DuplicateAppointmentInput,
-    ConflictCheckInput, ConflictCheckResponse, Room
+    ConflictCheckInput, ConflictCheckResponse, Room,
+    CalendarViewMode, CalendarEntry, CalendarDay, CalendarView,
+    DaySheetEntry, DaySheetSection,
+    AppointmentImportEntry, AppointmentImportEntryReport, AppointmentImportReport,
+    AvailableSlot, RoomReassignmentResult, UnmovedAppointment,
+    AppointmentStatsGroupBy, AppointmentStatsGroup, AppointmentStatistics
 };
+use crate::models::user::User;
+use crate::services::patient::PatientService;
+use crate::services::settings::SettingsService;
+
+/// Local working hours searched by `find_next_available_slot`. There is no
+/// opening-hours setting yet, so every day is searched 08:00-18:00.
+const SLOT_SEARCH_OPEN_HOUR: u32 = 8;
+const SLOT_SEARCH_CLOSE_HOUR: u32 = 18;
+
+/// Length of appointment `a` in whole minutes, for the statistics queries.
+const STATS_DURATION_MINUTES: &str =
+    "CAST(ROUND((julianday(a.end_time) - julianday(a.start_time)) * 1440) AS INTEGER)";
 
 pub struct AppointmentService;
 
@@ -163,6 +182,8 @@ impl AppointmentService {
                     capacity: r.capacity,
                     color: r.color,
                     is_active: r.is_active,
+                    display_order: r.display_order,
+                    buffer_minutes: r.buffer_minutes,
                     created_at: r.created_at,
                     updated_at: r.updated_at,
                 })
@@ -211,11 +232,16 @@ impl AppointmentService {
         Self::get_appointment_simple(db, result.last_insert_id).await
     }
 
+    /// Apply the provided fields. A status change must follow
+    /// `AppointmentStatus::can_transition_to` unless the input sets
+    /// `override_status_check`, which only admins may do: `actor` is the
+    /// current user, and with nobody picked (`None`) the override is refused.
     pub async fn update_appointment(
         db: &DatabaseConnection,
         id: i64,
         input: UpdateAppointmentInput,
         _updated_by: String,
+        actor: Option<&User>,
     ) -> Result<Appointment, String> {
         // Validate input
         input.validate()?;
@@ -231,6 +257,28 @@ impl AppointmentService {
             return Err("Cannot update deleted appointment".to_string());
         }
 
+        if let Some(status) = &input.status {
+            let current = parse_status(&existing.status);
+            if input.override_status_check {
+                match actor {
+                    Some(user) if user.role == "admin" => {}
+                    Some(user) => {
+                        return Err(format!(
+                            "Only admins can override appointment status rules ({} is {})",
+                            user.name, user.role
+                        ));
+                    }
+                    None => {
+                        return Err("Only admins can override appointment status rules (no user is selected)".to_string());
+                    }
+                }
+            } else if current.is_terminal() && current != *status {
+                return Err(format!("Appointment is {}; its status can no longer change", current));
+            } else if !current.can_transition_to(status) {
+                return Err(format!("Cannot change appointment status from {} to {}", current, status));
+            }
+        }
+
         if input.title.is_none() && input.description.is_none()
             && input.start_time.is_none() && input.end_time.is_none()
             && input.room_id.is_none() && input.status.is_none() {
@@ -297,24 +345,308 @@ impl AppointmentService {
         Ok(())
     }
 
+    /// Overlapping appointments for a booking in `[start_time, end_time)`.
+    /// In a room it conflicts only once the overlapping appointments fill the
+    /// room's capacity, the same rule as `auto_assign_room`; appointments
+    /// closer than the room's buffer count as overlapping.
     pub async fn check_conflicts(
         db: &DatabaseConnection,
         input: ConflictCheckInput,
     ) -> Result<ConflictCheckResponse, String> {
-        let conflicts = Self::check_conflicts_internal(
-            db,
-            input.start_time,
-            input.end_time,
-            input.room_id,
-            input.exclude_appointment_id,
-        ).await?;
+        let room = match input.room_id {
+            Some(room_id) => Some(
+                RoomEntity::find_by_id(room_id)
+                    .one(db)
+                    .await
+                    .map_err(|e| format!("Failed to fetch room: {}", e))?
+                    .ok_or("Room not found")?,
+            ),
+            None => None,
+        };
+
+        let conflicts = match &room {
+            Some(room) => Self::overlapping_in_room(
+                db,
+                room,
+                input.start_time,
+                input.end_time,
+                input.exclude_appointment_id,
+            ).await?,
+            None => Self::check_conflicts_internal(
+                db,
+                input.start_time,
+                input.end_time,
+                None,
+                input.exclude_appointment_id,
+            ).await?,
+        };
 
+        let occupancy = conflicts.len();
+        let capacity = room.map(|r| r.capacity);
         Ok(ConflictCheckResponse {
-            has_conflicts: !conflicts.is_empty(),
+            has_conflicts: occupancy as i32 >= capacity.unwrap_or(1),
             conflicts,
+            occupancy,
+            capacity,
         })
     }
 
+    /// Pick a room for a new appointment in `[start_time, end_time)`. Active
+    /// rooms are tried in order, the default room first and the rest by
+    /// name, and the first one whose overlapping appointments are still
+    /// below its capacity wins. Appointments within a room's buffer count as
+    /// overlapping; rooms carry no opening-hours settings yet. When no room
+    /// is free this returns `None` and the appointment is created without a
+    /// room rather than double-booked.
+    pub async fn auto_assign_room(
+        db: &DatabaseConnection,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Option<i64>, String> {
+        for candidate in Self::rooms_by_preference(db).await? {
+            let overlapping = Self::overlapping_in_room(db, &candidate, start_time, end_time, None).await?;
+
+            if (overlapping.len() as i32) < candidate.capacity {
+                return Ok(Some(candidate.id));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Move `from_room_id`'s appointments starting at or after `from_date`
+    /// (skipping cancelled, completed and deleted ones) to `to_room_id`, in
+    /// one transaction. An appointment that would take the target room past
+    /// its capacity is left untouched and reported with what it overlaps.
+    /// With `deactivate_source`, the source room is set inactive only if it
+    /// has no upcoming appointments left afterwards.
+    pub async fn reassign_room_appointments(
+        db: &DatabaseConnection,
+        from_room_id: i64,
+        to_room_id: i64,
+        from_date: DateTime<Utc>,
+        deactivate_source: bool,
+    ) -> Result<RoomReassignmentResult, String> {
+        if from_room_id == to_room_id {
+            return Err("Source and target room must be different".to_string());
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let source = RoomEntity::find_by_id(from_room_id)
+            .one(&txn)
+            .await
+            .map_err(|e| format!("Failed to fetch room: {}", e))?
+            .ok_or_else(|| "Source room not found".to_string())?;
+        let target = RoomEntity::find_by_id(to_room_id)
+            .one(&txn)
+            .await
+            .map_err(|e| format!("Failed to fetch room: {}", e))?
+            .ok_or_else(|| "Target room not found".to_string())?;
+        if !target.is_active {
+            return Err("Target room is inactive".to_string());
+        }
+
+        let candidates = AppointmentEntity::find()
+            .filter(appointment::Column::RoomId.eq(from_room_id))
+            .filter(appointment::Column::DeletedAt.is_null())
+            .filter(appointment::Column::Status.is_not_in(["cancelled", "completed"]))
+            .filter(appointment::Column::StartTime.gte(from_date))
+            .order_by_asc(appointment::Column::StartTime)
+            .order_by_asc(appointment::Column::Id)
+            .all(&txn)
+            .await
+            .map_err(|e| format!("Failed to fetch room appointments: {}", e))?;
+
+        let now = Utc::now();
+        let mut moved_appointment_ids = Vec::new();
+        let mut unmoved = Vec::new();
+        for candidate in candidates {
+            // Earlier moves are visible here, so moved appointments are also
+            // checked against each other
+            let overlapping = Self::overlapping_in_room(
+                &txn,
+                &target,
+                candidate.start_time,
+                candidate.end_time,
+                Some(candidate.id),
+            ).await?;
+
+            if (overlapping.len() as i32) >= target.capacity {
+                unmoved.push(UnmovedAppointment {
+                    appointment_id: candidate.id,
+                    conflicting_appointment_ids: overlapping.iter().map(|a| a.id).collect(),
+                });
+                continue;
+            }
+
+            let id = candidate.id;
+            let mut model: appointment::ActiveModel = candidate.into();
+            model.room_id = Set(Some(to_room_id));
+            model.updated_at = Set(now);
+            model
+                .update(&txn)
+                .await
+                .map_err(|e| format!("Failed to move appointment {}: {}", id, e))?;
+            moved_appointment_ids.push(id);
+        }
+
+        let mut source_deactivated = false;
+        if deactivate_source {
+            let remaining = AppointmentEntity::find()
+                .filter(appointment::Column::RoomId.eq(from_room_id))
+                .filter(appointment::Column::DeletedAt.is_null())
+                .filter(appointment::Column::Status.is_not_in(["cancelled", "completed"]))
+                .filter(appointment::Column::EndTime.gt(from_date.min(now)))
+                .count(&txn)
+                .await
+                .map_err(|e| format!("Failed to count remaining appointments: {}", e))?;
+
+            if remaining == 0 {
+                let mut model: room::ActiveModel = source.into();
+                model.is_active = Set(false);
+                model.updated_at = Set(now);
+                model
+                    .update(&txn)
+                    .await
+                    .map_err(|e| format!("Failed to deactivate room: {}", e))?;
+                source_deactivated = true;
+            }
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit room reassignment: {}", e))?;
+
+        Ok(RoomReassignmentResult {
+            moved_appointment_ids,
+            unmoved,
+            source_deactivated,
+        })
+    }
+
+    /// Active rooms, default room first and the rest by name.
+    async fn rooms_by_preference(db: &DatabaseConnection) -> Result<Vec<room::Model>, String> {
+        let default_room_id = SettingsService::get_default_room_id(db, "default").await?;
+
+        let mut rooms = RoomEntity::find()
+            .filter(room::Column::IsActive.eq(true))
+            .order_by_asc(room::Column::Name)
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to fetch rooms: {}", e))?;
+
+        if let Some(default_id) = default_room_id {
+            if let Some(pos) = rooms.iter().position(|r| r.id == default_id) {
+                let default_room = rooms.remove(pos);
+                rooms.insert(0, default_room);
+            }
+        }
+
+        Ok(rooms)
+    }
+
+    /// Earliest slot of `duration_minutes` starting at or after `after`,
+    /// looking at `search_days` days from `after`'s local date in `tz`.
+    /// Starts are on the 15-minute grid and the whole slot must fit inside
+    /// the day's working hours. A room qualifies while its overlapping
+    /// appointments stay below its capacity, the same rule as
+    /// `auto_assign_room`. Without `room_id` every active room is tried and
+    /// the earliest start wins, ties going to the default room and then by
+    /// name. A slot keeps the room's buffer free on either side of other
+    /// appointments. Returns `None` if nothing fits in the window.
+    pub async fn find_next_available_slot<Tz: TimeZone>(
+        db: &DatabaseConnection,
+        room_id: Option<i64>,
+        duration_minutes: i64,
+        after: DateTime<Utc>,
+        search_days: u32,
+        tz: &Tz,
+    ) -> Result<Option<AvailableSlot>, String> {
+        if duration_minutes < 15 || duration_minutes % 15 != 0 {
+            return Err("Duration must be a multiple of 15 minutes".to_string());
+        }
+        if duration_minutes > 8 * 60 {
+            return Err("Appointment cannot exceed 8 hours".to_string());
+        }
+        if search_days == 0 || search_days > 366 {
+            return Err("Search window must be between 1 and 366 days".to_string());
+        }
+
+        let rooms = match room_id {
+            Some(id) => {
+                let room = RoomEntity::find_by_id(id)
+                    .one(db)
+                    .await
+                    .map_err(|e| format!("Failed to fetch room: {}", e))?
+                    .ok_or("Room not found")?;
+                if !room.is_active {
+                    return Err("Room is not active".to_string());
+                }
+                vec![room]
+            }
+            None => Self::rooms_by_preference(db).await?,
+        };
+        if rooms.is_empty() {
+            return Ok(None);
+        }
+
+        let hour = |h: u32| NaiveTime::from_hms_opt(h, 0, 0).ok_or_else(|| "Invalid working hours".to_string());
+        let (open_time, close_time) = (hour(SLOT_SEARCH_OPEN_HOUR)?, hour(SLOT_SEARCH_CLOSE_HOUR)?);
+        let duration = Duration::minutes(duration_minutes);
+        let max_buffer = Duration::minutes(rooms.iter().map(|r| r.buffer_minutes).max().unwrap_or(0).into());
+        let earliest = Self::round_up_to_quarter_hour(after)?;
+        let first_date = after.with_timezone(tz).date_naive();
+
+        for date in first_date.iter_days().take(search_days as usize) {
+            let open = Self::local_time_utc(date, open_time, tz)?;
+            let close = Self::local_time_utc(date, close_time, tz)?;
+            let mut start = open.max(earliest);
+            if start + duration > close {
+                continue;
+            }
+
+            let booked = Self::check_conflicts_internal(db, open - max_buffer, close + max_buffer, room_id, None).await?;
+            while start + duration <= close {
+                let end = start + duration;
+                for room in &rooms {
+                    let buffer = Duration::minutes(room.buffer_minutes.into());
+                    let overlapping = booked
+                        .iter()
+                        .filter(|a| {
+                            a.room_id == Some(room.id) && a.start_time < end + buffer && a.end_time + buffer > start
+                        })
+                        .count();
+                    if (overlapping as i32) < room.capacity {
+                        return Ok(Some(AvailableSlot {
+                            room_id: room.id,
+                            room_name: room.name.clone(),
+                            start_time: start,
+                            end_time: end,
+                        }));
+                    }
+                }
+                start += Duration::minutes(15);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn round_up_to_quarter_hour(time: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+        const QUARTER: i64 = 15 * 60;
+        let secs = time.timestamp();
+        let rounded = if secs % QUARTER == 0 && time.timestamp_subsec_nanos() == 0 {
+            secs
+        } else {
+            (secs.div_euclid(QUARTER) + 1) * QUARTER
+        };
+        DateTime::from_timestamp(rounded, 0).ok_or_else(|| "Search start is out of range".to_string())
+    }
+
     pub async fn duplicate_appointment(
         db: &DatabaseConnection,
         input: DuplicateAppointmentInput,
@@ -346,9 +678,658 @@ impl AppointmentService {
         Self::create_appointment(db, create_input, created_by).await
     }
 
-    // Internal helper to check conflicts
-    async fn check_conflicts_internal(
+    /// Appointments for the day/week/month around `anchor_date`, bucketed by
+    /// their start date in `tz` and laid out into lanes. Soft-deleted and
+    /// cancelled appointments are left out, as in the default list filter;
+    /// an empty `room_ids` means every room.
+    pub async fn get_calendar_view<Tz: TimeZone>(
+        db: &DatabaseConnection,
+        view: CalendarViewMode,
+        anchor_date: NaiveDate,
+        room_ids: &[i64],
+        tz: &Tz,
+    ) -> Result<CalendarView, String> {
+        let (start_date, end_date) = Self::calendar_range(view, anchor_date)?;
+        let range_start = Self::local_midnight_utc(start_date, tz)?;
+        let range_end = Self::local_midnight_utc(
+            end_date.succ_opt().ok_or("Calendar range is out of bounds")?,
+            tz,
+        )?;
+
+        let mut sql = String::from(
+            "SELECT a.*, p.name as patient_name, s.name as species, b.name as breed, p.microchip_id
+             FROM appointments a
+             JOIN patients p ON a.patient_id = p.id
+             LEFT JOIN species s ON p.species_id = s.id
+             LEFT JOIN breeds b ON p.breed_id = b.id
+             WHERE a.deleted_at IS NULL
+             AND a.status != 'cancelled'
+             AND a.start_time >= ? AND a.start_time < ?"
+        );
+        let mut params: Vec<Value> = vec![
+            range_start.to_rfc3339().into(),
+            range_end.to_rfc3339().into(),
+        ];
+
+        if !room_ids.is_empty() {
+            let placeholders = vec!["?"; room_ids.len()].join(", ");
+            sql.push_str(&format!(" AND a.room_id IN ({})", placeholders));
+            params.extend(room_ids.iter().map(|id| Value::from(*id)));
+        }
+
+        sql.push_str(" ORDER BY a.start_time ASC, a.end_time ASC, a.id ASC");
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &sql,
+                params,
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+        let mut days: Vec<CalendarDay> = start_date
+            .iter_days()
+            .take_while(|date| *date <= end_date)
+            .map(|date| CalendarDay { date, entries: Vec::new() })
+            .collect();
+
+        let mut by_day: Vec<Vec<Appointment>> = vec![Vec::new(); days.len()];
+        for row in rows {
+            let appointment = Self::row_to_appointment(&row)?;
+            let date = appointment.start_time.with_timezone(tz).date_naive();
+            let index = (date - start_date).num_days();
+            if let Some(bucket) = usize::try_from(index).ok().and_then(|i| by_day.get_mut(i)) {
+                bucket.push(appointment);
+            }
+        }
+
+        for (day, appointments) in days.iter_mut().zip(by_day) {
+            day.entries = Self::assign_lanes(appointments);
+        }
+
+        Ok(CalendarView {
+            view,
+            start_date,
+            end_date,
+            days,
+        })
+    }
+
+    /// Appointments on `date` (in `tz`) for the printed day sheet, in time
+    /// order. Cancelled appointments are left out unless `include_cancelled`.
+    /// With more than one room in `room_ids` the rows are split into one
+    /// section per room, in the order given; otherwise there is a single
+    /// ungrouped section.
+    pub async fn get_day_sheet<Tz: TimeZone>(
+        db: &DatabaseConnection,
+        date: NaiveDate,
+        room_ids: &[i64],
+        include_cancelled: bool,
+        tz: &Tz,
+    ) -> Result<Vec<DaySheetSection>, String> {
+        let range_start = Self::local_midnight_utc(date, tz)?;
+        let range_end = Self::local_midnight_utc(
+            date.succ_opt().ok_or("Day sheet date is out of bounds")?,
+            tz,
+        )?;
+
+        let mut sql = String::from(
+            "SELECT a.*, p.name as patient_name, s.name as species, b.name as breed, p.microchip_id,
+                    r.name as room_name
+             FROM appointments a
+             JOIN patients p ON a.patient_id = p.id
+             LEFT JOIN species s ON p.species_id = s.id
+             LEFT JOIN breeds b ON p.breed_id = b.id
+             LEFT JOIN rooms r ON a.room_id = r.id
+             WHERE a.deleted_at IS NULL
+             AND a.start_time >= ? AND a.start_time < ?"
+        );
+        let mut params: Vec<Value> = vec![
+            range_start.to_rfc3339().into(),
+            range_end.to_rfc3339().into(),
+        ];
+
+        if !include_cancelled {
+            sql.push_str(" AND a.status != 'cancelled'");
+        }
+
+        if !room_ids.is_empty() {
+            let placeholders = vec!["?"; room_ids.len()].join(", ");
+            sql.push_str(&format!(" AND a.room_id IN ({})", placeholders));
+            params.extend(room_ids.iter().map(|id| Value::from(*id)));
+        }
+
+        sql.push_str(" ORDER BY a.start_time ASC, a.end_time ASC, a.id ASC");
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &sql,
+                params,
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+        // Owners as the patient screen shows them, looked up once per patient
+        let mut owners: HashMap<i64, Option<String>> = HashMap::new();
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let appointment = Self::row_to_appointment(&row)?;
+            let owner = match owners.get(&appointment.patient_id) {
+                Some(owner) => owner.clone(),
+                None => {
+                    let owner = PatientService::get_primary_contact(db, appointment.patient_id)
+                        .await?
+                        .map(|c| c.name);
+                    owners.insert(appointment.patient_id, owner.clone());
+                    owner
+                }
+            };
+            let time = format!(
+                "{}–{}",
+                appointment.start_time.with_timezone(tz).naive_local().format("%H:%M"),
+                appointment.end_time.with_timezone(tz).naive_local().format("%H:%M"),
+            );
+            entries.push(DaySheetEntry {
+                appointment_id: appointment.id,
+                start_time: appointment.start_time,
+                time,
+                patient_name: appointment.patient_name.unwrap_or_default(),
+                owner,
+                room_id: appointment.room_id,
+                room_name: row.try_get("", "room_name").ok(),
+                status: appointment.status,
+                title: appointment.title,
+                notes: appointment.description.filter(|d| !d.trim().is_empty()),
+            });
+        }
+
+        if room_ids.len() < 2 {
+            return Ok(vec![DaySheetSection {
+                room_id: None,
+                room_name: None,
+                entries,
+            }]);
+        }
+
+        let room_names: HashMap<i64, String> = RoomEntity::find()
+            .filter(room::Column::Id.is_in(room_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to fetch rooms: {}", e))?
+            .into_iter()
+            .map(|r| (r.id, r.name))
+            .collect();
+
+        Ok(room_ids
+            .iter()
+            .map(|room_id| DaySheetSection {
+                room_id: Some(*room_id),
+                room_name: room_names.get(room_id).cloned(),
+                entries: entries
+                    .iter()
+                    .filter(|entry| entry.room_id == Some(*room_id))
+                    .cloned()
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Counts, average duration and the busiest weekday and hour of the
+    /// appointments starting in `[from, to)`, plus a breakdown by
+    /// `group_by`. Weekdays and hours are read in `tz`.
+    pub async fn get_appointment_statistics<Tz: TimeZone>(
+        db: &DatabaseConnection,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        group_by: AppointmentStatsGroupBy,
+        tz: &Tz,
+    ) -> Result<AppointmentStatistics, String> {
+        if to <= from {
+            return Err("Statistics period must end after it starts".to_string());
+        }
+
+        let overall = Self::appointment_stats_groups(db, from, to, "'all'", "NULL")
+            .await?
+            .into_iter()
+            .next();
+
+        // Start minutes are grouped in SQL and folded into local weekdays
+        // and hours here; SQLite has no notion of the practice timezone.
+        let slot_rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT strftime('%Y-%m-%dT%H:%M:00Z', a.start_time) AS slot,
+                            COUNT(*) AS total,
+                            SUM(a.status = 'completed') AS completed,
+                            SUM(a.status = 'cancelled') AS cancelled,
+                            SUM(CASE WHEN a.status != 'cancelled' THEN {} END) AS active_minutes
+                     FROM appointments a
+                     WHERE a.deleted_at IS NULL AND a.start_time >= ? AND a.start_time < ?
+                     GROUP BY slot",
+                    STATS_DURATION_MINUTES
+                ),
+                [from.to_rfc3339().into(), to.to_rfc3339().into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to compute appointment statistics: {}", e))?;
+
+        // (total, completed, cancelled, minutes of non-cancelled)
+        let mut by_weekday: HashMap<u32, (i64, i64, i64, i64)> = HashMap::new();
+        let mut active_by_hour: HashMap<u32, i64> = HashMap::new();
+        for row in &slot_rows {
+            let slot: String = row.try_get("", "slot").map_err(|e| e.to_string())?;
+            let start = DateTime::parse_from_rfc3339(&slot)
+                .map_err(|e| format!("Invalid appointment start {}: {}", slot, e))?
+                .with_timezone(tz);
+            let total: i64 = row.try_get("", "total").unwrap_or(0);
+            let completed: i64 = row.try_get("", "completed").unwrap_or(0);
+            let cancelled: i64 = row.try_get("", "cancelled").unwrap_or(0);
+            let minutes: i64 = row.try_get("", "active_minutes").unwrap_or(0);
+
+            let day = by_weekday.entry(start.weekday().number_from_monday()).or_default();
+            day.0 += total;
+            day.1 += completed;
+            day.2 += cancelled;
+            day.3 += minutes;
+            *active_by_hour.entry(start.hour()).or_default() += total - cancelled;
+        }
+
+        let busiest_day_of_week =
+            Self::busiest_key(by_weekday.iter().map(|(day, c)| (*day, c.0 - c.2)));
+        let busiest_hour = Self::busiest_key(active_by_hour.into_iter());
+
+        let groups = match group_by {
+            AppointmentStatsGroupBy::Room => {
+                Self::appointment_stats_groups(db, from, to, "CAST(a.room_id AS TEXT)", "r.name").await?
+            }
+            AppointmentStatsGroupBy::Species => {
+                Self::appointment_stats_groups(db, from, to, "CAST(p.species_id AS TEXT)", "s.name").await?
+            }
+            AppointmentStatsGroupBy::Status => {
+                Self::appointment_stats_groups(db, from, to, "a.status", "NULL").await?
+            }
+            AppointmentStatsGroupBy::DayOfWeek => {
+                let mut days: Vec<_> = by_weekday.into_iter().collect();
+                days.sort_by_key(|(day, _)| *day);
+                days.into_iter()
+                    .map(|(day, (total, completed, cancelled, minutes))| AppointmentStatsGroup {
+                        key: Some(day.to_string()),
+                        label: None,
+                        total,
+                        completed,
+                        cancelled,
+                        average_duration_minutes: (total > cancelled)
+                            .then(|| minutes as f64 / (total - cancelled) as f64),
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(AppointmentStatistics {
+            total: overall.as_ref().map_or(0, |o| o.total),
+            completed: overall.as_ref().map_or(0, |o| o.completed),
+            cancelled: overall.as_ref().map_or(0, |o| o.cancelled),
+            average_duration_minutes: overall.and_then(|o| o.average_duration_minutes),
+            busiest_day_of_week,
+            busiest_hour,
+            group_by,
+            groups,
+        })
+    }
+
+    /// Key with the most appointments; the lowest key wins a tie.
+    fn busiest_key(counts: impl Iterator<Item = (u32, i64)>) -> Option<u32> {
+        counts
+            .filter(|(_, count)| *count > 0)
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(key, _)| key)
+    }
+
+    /// One `AppointmentStatsGroup` per distinct `key_sql` over the period,
+    /// largest first.
+    async fn appointment_stats_groups(
+        db: &DatabaseConnection,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        key_sql: &str,
+        label_sql: &str,
+    ) -> Result<Vec<AppointmentStatsGroup>, String> {
+        let sql = format!(
+            "SELECT {key} AS group_key, {label} AS label,
+                    COUNT(*) AS total,
+                    SUM(a.status = 'completed') AS completed,
+                    SUM(a.status = 'cancelled') AS cancelled,
+                    AVG(CASE WHEN a.status != 'cancelled' THEN {minutes} END) AS average_minutes
+             FROM appointments a
+             JOIN patients p ON p.id = a.patient_id
+             LEFT JOIN species s ON s.id = p.species_id
+             LEFT JOIN rooms r ON r.id = a.room_id
+             WHERE a.deleted_at IS NULL AND a.start_time >= ? AND a.start_time < ?
+             GROUP BY {key}
+             ORDER BY total DESC, group_key",
+            key = key_sql,
+            label = label_sql,
+            minutes = STATS_DURATION_MINUTES,
+        );
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &sql,
+                [from.to_rfc3339().into(), to.to_rfc3339().into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to compute appointment statistics: {}", e))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AppointmentStatsGroup {
+                key: row.try_get("", "group_key").ok(),
+                label: row.try_get("", "label").ok(),
+                total: row.try_get("", "total").unwrap_or(0),
+                completed: row.try_get("", "completed").unwrap_or(0),
+                cancelled: row.try_get("", "cancelled").unwrap_or(0),
+                average_duration_minutes: row.try_get("", "average_minutes").ok(),
+            })
+            .collect())
+    }
+
+    /// First and last date (inclusive) shown by `view` for `anchor_date`.
+    fn calendar_range(view: CalendarViewMode, anchor_date: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let out_of_range = || "Calendar range is out of bounds".to_string();
+        match view {
+            CalendarViewMode::Day => Ok((anchor_date, anchor_date)),
+            CalendarViewMode::Week => {
+                let offset = anchor_date.weekday().num_days_from_monday() as i64;
+                let start = anchor_date - Duration::days(offset);
+                Ok((start, start + Duration::days(6)))
+            }
+            CalendarViewMode::Month => {
+                let start = anchor_date.with_day(1).ok_or_else(out_of_range)?;
+                let next_month = start
+                    .checked_add_months(Months::new(1))
+                    .ok_or_else(out_of_range)?;
+                Ok((start, next_month.pred_opt().ok_or_else(out_of_range)?))
+            }
+        }
+    }
+
+    pub(crate) fn local_midnight_utc<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> Result<DateTime<Utc>, String> {
+        Self::local_time_utc(date, NaiveTime::MIN, tz)
+    }
+
+    fn local_time_utc<Tz: TimeZone>(date: NaiveDate, time: NaiveTime, tz: &Tz) -> Result<DateTime<Utc>, String> {
+        Ok(tz
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .ok_or_else(|| format!("Could not resolve local time {} {}", date, time))?
+            .with_timezone(&Utc))
+    }
+
+    /// Greedy column layout for one day's appointments (sorted by start).
+    /// Appointments are grouped into clusters of transitively overlapping
+    /// ones; each takes the first lane whose previous occupant has ended, and
+    /// every member of a cluster reports the cluster's lane count.
+    fn assign_lanes(appointments: Vec<Appointment>) -> Vec<CalendarEntry> {
+        let mut entries: Vec<CalendarEntry> = Vec::with_capacity(appointments.len());
+        let mut cluster_start = 0;
+        let mut cluster_end: Option<DateTime<Utc>> = None;
+        let mut lane_ends: Vec<DateTime<Utc>> = Vec::new();
+
+        for appointment in appointments {
+            if cluster_end.is_some_and(|end| appointment.start_time >= end) {
+                let lane_count = lane_ends.len() as u32;
+                for entry in &mut entries[cluster_start..] {
+                    entry.lane_count = lane_count;
+                }
+                cluster_start = entries.len();
+                cluster_end = None;
+                lane_ends.clear();
+            }
+
+            let lane = match lane_ends.iter().position(|end| *end <= appointment.start_time) {
+                Some(free) => {
+                    lane_ends[free] = appointment.end_time;
+                    free
+                }
+                None => {
+                    lane_ends.push(appointment.end_time);
+                    lane_ends.len() - 1
+                }
+            };
+
+            cluster_end = Some(cluster_end.map_or(appointment.end_time, |end| end.max(appointment.end_time)));
+            entries.push(CalendarEntry {
+                appointment,
+                lane: lane as u32,
+                lane_count: 0,
+            });
+        }
+
+        let lane_count = lane_ends.len() as u32;
+        for entry in &mut entries[cluster_start..] {
+            entry.lane_count = lane_count;
+        }
+
+        entries
+    }
+
+    /// Dry run over a batch of appointments to import. Every entry has its
+    /// patient and room resolved, its times checked against the same rules
+    /// as `create_appointment`, and its room checked for overlaps with both
+    /// existing appointments and the other entries in the batch; overlaps
+    /// (widened by the room's buffer) are only reported once together they
+    /// reach the room's capacity, as when booking. A repeated
+    /// `uid` marks the later entry as a duplicate of the first. Nothing is
+    /// written, so a migration file can be fixed and re-checked until every
+    /// entry comes back valid. Entries without a room are not checked for
+    /// overlaps.
+    pub async fn validate_appointment_import(
         db: &DatabaseConnection,
+        entries: &[AppointmentImportEntry],
+    ) -> Result<AppointmentImportReport, String> {
+        let rooms = RoomEntity::find()
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to fetch rooms: {}", e))?;
+
+        let mut reports: Vec<AppointmentImportEntryReport> = Vec::with_capacity(entries.len());
+        let mut first_by_uid: HashMap<&str, usize> = HashMap::new();
+
+        for (index, entry) in entries.iter().enumerate() {
+            let mut errors = Vec::new();
+            let patient_id = Self::resolve_import_patient(db, entry, &mut errors).await?;
+            let room_id = Self::resolve_import_room(&rooms, entry, &mut errors);
+
+            let input = CreateAppointmentInput {
+                patient_id: patient_id.unwrap_or_default(),
+                title: entry.title.clone(),
+                description: entry.description.clone(),
+                start_time: entry.start_time,
+                end_time: entry.end_time,
+                room_id,
+            };
+            if let Err(e) = input.validate() {
+                errors.push(e);
+            }
+
+            let uid = entry.uid.as_deref().map(str::trim).filter(|uid| !uid.is_empty());
+            let duplicate_of = match uid {
+                Some(uid) => match first_by_uid.get(uid) {
+                    Some(first) => Some(*first),
+                    None => {
+                        first_by_uid.insert(uid, index);
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            // Every overlap for now; whether they fill the room is decided
+            // once the overlaps inside the batch are known too
+            let room = room_id.and_then(|id| rooms.iter().find(|r| r.id == id));
+            let conflicting_appointments = match room {
+                Some(room) if entry.end_time > entry.start_time => {
+                    Self::overlapping_in_room(db, room, entry.start_time, entry.end_time, None)
+                        .await?
+                        .into_iter()
+                        .map(|a| a.id)
+                        .collect()
+                }
+                _ => Vec::new(),
+            };
+
+            reports.push(AppointmentImportEntryReport {
+                index,
+                uid: uid.map(str::to_string),
+                patient_id,
+                room_id,
+                errors,
+                duplicate_of,
+                conflicting_entries: Vec::new(),
+                conflicting_appointments,
+            });
+        }
+
+        // Overlaps inside the batch; a duplicate is already reported as such
+        // and would otherwise also "conflict" with the entry it repeats.
+        for i in 0..entries.len() {
+            for j in (i + 1)..entries.len() {
+                let (a, b) = (&entries[i], &entries[j]);
+                let same_room = reports[i].room_id.is_some() && reports[i].room_id == reports[j].room_id;
+                if !same_room
+                    || reports[i].duplicate_of.is_some()
+                    || reports[j].duplicate_of.is_some()
+                    || a.end_time <= a.start_time
+                    || b.end_time <= b.start_time
+                {
+                    continue;
+                }
+                // The room's buffer is kept free between entries too
+                let buffer = reports[i]
+                    .room_id
+                    .and_then(|id| rooms.iter().find(|r| r.id == id))
+                    .map_or(Duration::zero(), |room| Duration::minutes(room.buffer_minutes.into()));
+                if a.start_time < b.end_time + buffer && b.start_time < a.end_time + buffer {
+                    reports[i].conflicting_entries.push(j);
+                    reports[j].conflicting_entries.push(i);
+                }
+            }
+        }
+
+        // Like a booking, an entry only conflicts once the appointments and
+        // entries it overlaps fill its room
+        for report in &mut reports {
+            let capacity = report
+                .room_id
+                .and_then(|id| rooms.iter().find(|r| r.id == id))
+                .map_or(1, |room| room.capacity);
+            let occupancy = report.conflicting_appointments.len() + report.conflicting_entries.len();
+            if (occupancy as i32) < capacity {
+                report.conflicting_appointments.clear();
+                report.conflicting_entries.clear();
+            }
+        }
+
+        let valid_count = reports.iter().filter(|r| r.is_valid()).count();
+        Ok(AppointmentImportReport {
+            invalid_count: reports.len() - valid_count,
+            valid_count,
+            entries: reports,
+        })
+    }
+
+    /// Patient for an import entry: by id if given, otherwise by microchip.
+    async fn resolve_import_patient(
+        db: &DatabaseConnection,
+        entry: &AppointmentImportEntry,
+        errors: &mut Vec<String>,
+    ) -> Result<Option<i64>, String> {
+        let microchip = entry
+            .microchip_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|chip| !chip.is_empty());
+
+        let patient = if let Some(id) = entry.patient_id {
+            PatientEntity::find_by_id(id).one(db).await
+        } else if let Some(chip) = microchip {
+            PatientEntity::find()
+                .filter(patient::Column::MicrochipId.eq(chip))
+                .one(db)
+                .await
+        } else {
+            errors.push("Patient id or microchip is required".to_string());
+            return Ok(None);
+        }
+        .map_err(|e| format!("Failed to fetch patient: {}", e))?;
+
+        match patient {
+            Some(p) if p.is_active => Ok(Some(p.id)),
+            Some(p) => {
+                errors.push(format!("Patient {} is inactive", p.id));
+                Ok(None)
+            }
+            None => {
+                errors.push("Patient not found".to_string());
+                Ok(None)
+            }
+        }
+    }
+
+    /// Room for an import entry: by id if given, otherwise by name. An
+    /// entry naming neither stays unassigned.
+    fn resolve_import_room(
+        rooms: &[room::Model],
+        entry: &AppointmentImportEntry,
+        errors: &mut Vec<String>,
+    ) -> Option<i64> {
+        let name = entry
+            .room_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+
+        let found = if let Some(id) = entry.room_id {
+            rooms.iter().find(|r| r.id == id)
+        } else if let Some(name) = name {
+            rooms.iter().find(|r| r.name.to_lowercase() == name.to_lowercase())
+        } else {
+            return None;
+        };
+
+        match found {
+            Some(r) if r.is_active => Some(r.id),
+            Some(r) => {
+                errors.push(format!("Room '{}' is inactive", r.name));
+                None
+            }
+            None => {
+                errors.push("Room not found".to_string());
+                None
+            }
+        }
+    }
+
+    /// Appointments in `room` overlapping `[start_time, end_time)` once the
+    /// room's buffer is kept free on both sides. A buffer of 0 lets
+    /// appointments touch end to start.
+    async fn overlapping_in_room<C: ConnectionTrait>(
+        db: &C,
+        room: &room::Model,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        exclude_id: Option<i64>,
+    ) -> Result<Vec<Appointment>, String> {
+        let buffer = Duration::minutes(room.buffer_minutes.into());
+        Self::check_conflicts_internal(db, start_time - buffer, end_time + buffer, Some(room.id), exclude_id).await
+    }
+
+    // Internal helper to check conflicts
+    async fn check_conflicts_internal<C: ConnectionTrait>(
+        db: &C,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
         room_id: Option<i64>,
@@ -434,13 +1415,7 @@ impl AppointmentService {
     fn row_to_appointment(row: &QueryResult) -> Result<Appointment, String> {
         // Read status as String and parse to enum
         let status_str: String = row.try_get("", "status").map_err(|e| e.to_string())?;
-        let status = match status_str.as_str() {
-            "scheduled" => crate::models::AppointmentStatus::Scheduled,
-            "in_progress" => crate::models::AppointmentStatus::InProgress,
-            "completed" => crate::models::AppointmentStatus::Completed,
-            "cancelled" => crate::models::AppointmentStatus::Cancelled,
-            _ => crate::models::AppointmentStatus::Scheduled, // Default fallback
-        };
+        let status = parse_status(&status_str);
 
         Ok(Appointment {
             id: row.try_get("", "id").map_err(|e| e.to_string())?,
@@ -463,6 +1438,16 @@ impl AppointmentService {
     }
 }
 
+/// An `appointments.status` value; anything unknown reads as scheduled.
+fn parse_status(status: &str) -> crate::models::AppointmentStatus {
+    match status {
+        "in_progress" => crate::models::AppointmentStatus::InProgress,
+        "completed" => crate::models::AppointmentStatus::Completed,
+        "cancelled" => crate::models::AppointmentStatus::Cancelled,
+        _ => crate::models::AppointmentStatus::Scheduled,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -734,6 +1719,7 @@ mod tests {
             end_time: None,
             room_id: None,
             status: None,
+            override_status_check: false,
         };
 
         let result = AppointmentService::update_appointment(
@@ -741,6 +1727,7 @@ mod tests {
             created.id,
             update_input,
             "test_user".to_string(),
+            None,
         ).await;
 
         assert!(result.is_ok());
@@ -768,6 +1755,7 @@ mod tests {
             end_time: None,
             room_id: None,
             status: Some(AppointmentStatus::InProgress),
+            override_status_check: false,
         };
 
         let updated = AppointmentService::update_appointment(
@@ -775,6 +1763,7 @@ mod tests {
             created.id,
             update_input,
             "test_user".to_string(),
+            None,
         ).await.unwrap();
 
         assert_eq!(updated.status, AppointmentStatus::InProgress);
@@ -794,12 +1783,144 @@ mod tests {
             99999,
             update_input,
             "test_user".to_string(),
+            None,
         ).await;
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("not found"));
     }
 
+    // ==================== STATUS TRANSITION TESTS ====================
+
+    fn status_input(status: AppointmentStatus, override_status_check: bool) -> UpdateAppointmentInput {
+        UpdateAppointmentInput {
+            status: Some(status),
+            override_status_check,
+            ..Default::default()
+        }
+    }
+
+    fn test_user(role: &str) -> User {
+        User {
+            id: 1,
+            name: "Ana".to_string(),
+            role: role.to_string(),
+            active: true,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    /// A scheduled appointment moved through `path`, one update per status.
+    async fn appointment_through(db: &DatabaseConnection, path: &[AppointmentStatus]) -> i64 {
+        let species_id = create_test_species(db, "Dog").await;
+        let patient_id = create_test_patient(db, "Max", species_id, None).await;
+        let created = AppointmentService::create_appointment(
+            db,
+            valid_appointment_input(patient_id, None),
+            "test_user".to_string(),
+        ).await.unwrap();
+        for status in path {
+            AppointmentService::update_appointment(
+                db,
+                created.id,
+                status_input(status.clone(), false),
+                "test_user".to_string(),
+                None,
+            ).await.unwrap();
+        }
+        created.id
+    }
+
+    #[tokio::test]
+    async fn test_update_appointment_allows_legal_status_transitions() {
+        use AppointmentStatus::*;
+        let legal = [
+            (vec![], Scheduled),
+            (vec![], InProgress),
+            (vec![], Cancelled),
+            (vec![InProgress], InProgress),
+            (vec![InProgress], Completed),
+            (vec![InProgress], Cancelled),
+            (vec![InProgress, Completed], Completed),
+            (vec![Cancelled], Cancelled),
+        ];
+        for (path, next) in legal {
+            let db = create_test_db().await;
+            let id = appointment_through(&db, &path).await;
+            let updated = AppointmentService::update_appointment(
+                &db,
+                id,
+                status_input(next.clone(), false),
+                "test_user".to_string(),
+                None,
+            ).await.unwrap_or_else(|e| panic!("{:?} -> {} should be allowed: {}", path, next, e));
+            assert_eq!(updated.status, next);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_appointment_rejects_illegal_status_transitions() {
+        use AppointmentStatus::*;
+        let illegal = [
+            (vec![], Completed, "Cannot change appointment status from scheduled to completed"),
+            (vec![InProgress], Scheduled, "Cannot change appointment status from in_progress to scheduled"),
+            (vec![InProgress, Completed], Scheduled, "Appointment is completed"),
+            (vec![Cancelled], InProgress, "Appointment is cancelled"),
+        ];
+        for (path, next, message) in illegal {
+            let db = create_test_db().await;
+            let id = appointment_through(&db, &path).await;
+            let err = AppointmentService::update_appointment(
+                &db,
+                id,
+                status_input(next.clone(), false),
+                "test_user".to_string(),
+                None,
+            ).await.unwrap_err();
+            assert!(err.contains(message), "{:?} -> {}: {}", path, next, err);
+            let unchanged = AppointmentService::get_appointment_simple(&db, id).await.unwrap();
+            assert_eq!(unchanged.status, path.last().cloned().unwrap_or(Scheduled));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_appointment_status_override_is_admin_only() {
+        let db = create_test_db().await;
+        let id = appointment_through(&db, &[AppointmentStatus::Cancelled]).await;
+
+        let err = AppointmentService::update_appointment(
+            &db,
+            id,
+            status_input(AppointmentStatus::Scheduled, true),
+            "test_user".to_string(),
+            Some(&test_user("receptionist")),
+        ).await.unwrap_err();
+        assert!(err.contains("Only admins"), "{}", err);
+
+        let admin = test_user("admin");
+        let reopened = AppointmentService::update_appointment(
+            &db,
+            id,
+            status_input(AppointmentStatus::Scheduled, true),
+            "test_user".to_string(),
+            Some(&admin),
+        ).await.unwrap();
+        assert_eq!(reopened.status, AppointmentStatus::Scheduled);
+
+        // With nobody picked the override is refused too
+        let err = AppointmentService::update_appointment(
+            &db,
+            id,
+            status_input(AppointmentStatus::Completed, true),
+            "test_user".to_string(),
+            None,
+        ).await.unwrap_err();
+        assert!(err.contains("no user is selected"), "{}", err);
+        let unchanged = AppointmentService::get_appointment_simple(&db, id).await.unwrap();
+        assert_eq!(unchanged.status, AppointmentStatus::Scheduled);
+    }
+
     // ==================== DELETE TESTS ====================
 
     #[tokio::test]
@@ -988,6 +2109,91 @@ mod tests {
         assert!(!result.has_conflicts, "Should not conflict with itself");
     }
 
+    #[tokio::test]
+    async fn test_check_conflicts_allows_overlaps_up_to_room_capacity() {
+        let db = create_test_db().await;
+        let species_id = create_test_species(&db, "Dog").await;
+        let patient_id = create_test_patient(&db, "Max", species_id, None).await;
+        let room_id = create_test_room(&db, "Surgery").await;
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE rooms SET capacity = 2 WHERE id = ?",
+            [room_id.into()],
+        )).await.unwrap();
+
+        let slot = ConflictCheckInput {
+            start_time: test_time_slot(10, 1), // 10:15
+            end_time: test_time_slot(10, 3),   // 10:45
+            room_id: Some(room_id),
+            exclude_appointment_id: None,
+        };
+
+        for booked in 0..2 {
+            let result = AppointmentService::check_conflicts(&db, slot.clone()).await.unwrap();
+            assert!(!result.has_conflicts, "{} booked, room takes 2", booked);
+            assert_eq!((result.occupancy, result.capacity), (booked, Some(2)));
+
+            AppointmentService::create_appointment(
+                &db,
+                valid_appointment_input(patient_id, Some(room_id)),
+                "test_user".to_string(),
+            ).await.unwrap();
+        }
+
+        // A third overlapping appointment would overbook the room
+        let result = AppointmentService::check_conflicts(&db, slot).await.unwrap();
+        assert!(result.has_conflicts);
+        assert_eq!((result.occupancy, result.capacity), (2, Some(2)));
+        assert_eq!(result.conflicts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_check_conflicts_keeps_room_buffer_between_appointments() {
+        let db = create_test_db().await;
+        let species_id = create_test_species(&db, "Dog").await;
+        let patient_id = create_test_patient(&db, "Max", species_id, None).await;
+        let room_id = create_test_room(&db, "Surgery").await;
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE rooms SET buffer_minutes = 15 WHERE id = ?",
+            [room_id.into()],
+        )).await.unwrap();
+
+        // 10:00-10:30
+        AppointmentService::create_appointment(
+            &db,
+            valid_appointment_input(patient_id, Some(room_id)),
+            "test_user".to_string(),
+        ).await.unwrap();
+
+        let check = |start: DateTime<Utc>| ConflictCheckInput {
+            start_time: start,
+            end_time: start + Duration::minutes(30),
+            room_id: Some(room_id),
+            exclude_appointment_id: None,
+        };
+
+        let ten_past = test_time_slot(10, 2) + Duration::minutes(10);
+        let result = AppointmentService::check_conflicts(&db, check(ten_past)).await.unwrap();
+        assert!(result.has_conflicts, "starts 10 minutes after the last one ends, inside the 15-minute buffer");
+        assert_eq!(result.occupancy, 1);
+
+        let result = AppointmentService::check_conflicts(&db, check(test_time_slot(10, 3))).await.unwrap();
+        assert!(!result.has_conflicts, "starts exactly when the buffer ends");
+
+        // Before an appointment too: 9:20-9:50 ends 10 minutes before 10:00
+        let result = AppointmentService::check_conflicts(&db, check(test_time_slot(9, 0) + Duration::minutes(20))).await.unwrap();
+        assert!(result.has_conflicts);
+
+        db.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "UPDATE appointments SET status = 'cancelled'".to_string(),
+        )).await.unwrap();
+        let result = AppointmentService::check_conflicts(&db, check(ten_past)).await.unwrap();
+        assert!(!result.has_conflicts, "cancelled appointments don't block the room");
+        assert_eq!(result.occupancy, 0);
+    }
+
     // ==================== DUPLICATE TESTS ====================
 
     #[tokio::test]
@@ -0,0 +1,251 @@
+//! Optional OCR of scanned attachments (lab printouts, referral letters) so
+//! their text can be searched.
+//!
+//! Text is read with the Tesseract command-line tool: from the image itself,
+//! or from each PDF page rendered through PDFium. Tesseract is looked up on
+//! PATH unless `TESSERACT_PATH` points at a bundled binary. OCR runs in the
+//! background after the upload has returned; when it fails the attachment
+//! simply has no `attachment_text` row and isn't searchable.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use tauri::{AppHandle, Manager};
+
+use crate::database::queries::household_search::sanitize_fts5_query;
+use crate::models::medical::{AttachmentOcrResult, AttachmentTextMatch, MedicalAttachment};
+use crate::services::events;
+use crate::services::file_storage::FileStorageService;
+use crate::services::pdf_jobs::PdfCancelToken;
+use crate::services::pdf_render::PdfRenderService;
+use crate::services::settings::SettingsService;
+
+/// Pages of a PDF read by OCR; longer documents are indexed up to here.
+const OCR_MAX_PDF_PAGES: u16 = 20;
+
+/// Width PDF pages are rendered at before OCR (about 240 dpi for A4).
+const OCR_RENDER_WIDTH: u32 = 2000;
+
+pub struct AttachmentOcrService;
+
+impl AttachmentOcrService {
+    /// Images and PDFs are OCR'd; everything else is skipped.
+    pub fn is_ocr_candidate(mime_type: &str) -> bool {
+        mime_type.starts_with("image/") || mime_type == "application/pdf"
+    }
+
+    /// OCR newly stored attachments in the background if OCR is enabled.
+    /// Each attachment gets an `events::ATTACHMENT_OCR_COMPLETE` when it is done.
+    pub fn spawn_for_attachments(
+        app_handle: AppHandle,
+        db: Arc<DatabaseConnection>,
+        attachments: Vec<MedicalAttachment>,
+    ) {
+        tokio::spawn(async move {
+            match SettingsService::is_ocr_enabled(&db, "default").await {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => {
+                    log::warn!("Skipping OCR, could not read settings: {}", e);
+                    return;
+                }
+            }
+
+            for attachment in attachments {
+                let mime_type = attachment.mime_type.clone().unwrap_or_default();
+                if !Self::is_ocr_candidate(&mime_type) {
+                    continue;
+                }
+
+                let render_handle = app_handle.clone();
+                let outcome = FileStorageService::with_materialized_attachment(
+                    &app_handle,
+                    &db,
+                    attachment.id,
+                    move |path| Self::extract_text(&render_handle, &path, &mime_type),
+                )
+                .await;
+
+                let result = Self::record_ocr_outcome(&db, attachment.id, outcome).await;
+                let _ = app_handle.emit_all(events::ATTACHMENT_OCR_COMPLETE, &result);
+            }
+        });
+    }
+
+    /// Index the text OCR produced for `attachment_id`, or log why it
+    /// failed. Never returns an error: a failed OCR only means the
+    /// attachment isn't searchable.
+    pub async fn record_ocr_outcome(
+        db: &DatabaseConnection,
+        attachment_id: i64,
+        outcome: Result<String, String>,
+    ) -> AttachmentOcrResult {
+        let indexed = match outcome {
+            Ok(text) if text.trim().is_empty() => Ok(false),
+            Ok(text) => Self::index_text(db, attachment_id, &text).await.map(|_| true),
+            Err(e) => Err(e),
+        };
+
+        match indexed {
+            Ok(indexed) => {
+                log::info!("OCR of attachment {} done, text found: {}", attachment_id, indexed);
+                AttachmentOcrResult { attachment_id, indexed, error: None }
+            }
+            Err(e) => {
+                log::warn!("OCR of attachment {} failed: {}", attachment_id, e);
+                AttachmentOcrResult { attachment_id, indexed: false, error: Some(e) }
+            }
+        }
+    }
+
+    /// Replace the indexed text of `attachment_id`.
+    pub async fn index_text(
+        db: &DatabaseConnection,
+        attachment_id: i64,
+        text: &str,
+    ) -> Result<(), String> {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM attachment_text WHERE attachment_id = ?",
+            [attachment_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to clear attachment text: {}", e))?;
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO attachment_text (attachment_id, content) VALUES (?, ?)",
+            [attachment_id.into(), text.trim().into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to index attachment text: {}", e))?;
+        Ok(())
+    }
+
+    /// Attachments whose OCR text matches `query`, best match first.
+    pub async fn search(
+        db: &DatabaseConnection,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<AttachmentTextMatch>, String> {
+        let fts_query = sanitize_fts5_query(query);
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                r#"
+                SELECT
+                    ma.id,
+                    ma.medical_record_id,
+                    ma.original_name,
+                    snippet(attachment_text, 1, '[', ']', '…', 12) AS snippet
+                FROM attachment_text
+                JOIN medical_attachments ma ON ma.id = attachment_text.attachment_id
+                WHERE attachment_text MATCH ?
+                ORDER BY bm25(attachment_text)
+                LIMIT ?
+                "#,
+                [fts_query.into(), limit.clamp(1, 100).into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to search attachment text: {}", e))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(AttachmentTextMatch {
+                    attachment_id: row.try_get("", "id").map_err(|e| e.to_string())?,
+                    medical_record_id: row.try_get("", "medical_record_id").map_err(|e| e.to_string())?,
+                    original_name: row.try_get("", "original_name").map_err(|e| e.to_string())?,
+                    snippet: row.try_get("", "snippet").unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Run OCR on a materialized attachment. Blocking; call it off the
+    /// async runtime.
+    pub fn extract_text(app_handle: &AppHandle, path: &Path, mime_type: &str) -> Result<String, String> {
+        if mime_type != "application/pdf" {
+            return Self::ocr_image_file(path);
+        }
+
+        let pdf_path = path.to_string_lossy();
+        let page_count = PdfRenderService::get_page_count(app_handle, &pdf_path)?;
+        let mut pages = Vec::new();
+        for index in 0..page_count.min(OCR_MAX_PDF_PAGES) {
+            let png = PdfRenderService::render_page_to_png_bytes(
+                app_handle,
+                &pdf_path,
+                index as u32,
+                OCR_RENDER_WIDTH,
+                &PdfCancelToken::none(),
+            )?;
+            pages.push(Self::ocr_image_bytes(&png)?);
+        }
+        Ok(pages.join("\n"))
+    }
+
+    /// OCR an image file with Tesseract.
+    pub fn ocr_image_file(path: &Path) -> Result<String, String> {
+        let output = Self::tesseract_command()
+            .arg(path)
+            .arg("stdout")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run Tesseract: {}", e))?;
+        Self::read_output(output)
+    }
+
+    fn ocr_image_bytes(image: &[u8]) -> Result<String, String> {
+        let mut child = Self::tesseract_command()
+            .arg("stdin")
+            .arg("stdout")
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run Tesseract: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(image)
+                .map_err(|e| format!("Failed to pass page to Tesseract: {}", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Tesseract did not finish: {}", e))?;
+        Self::read_output(output)
+    }
+
+    fn read_output(output: std::process::Output) -> Result<String, String> {
+        if !output.status.success() {
+            return Err(format!(
+                "Tesseract exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn tesseract_command() -> Command {
+        let program = std::env::var("TESSERACT_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("tesseract"));
+        let mut cmd = Command::new(program);
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        // On Windows, prevent a console window from flashing
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        cmd
+    }
+}
@@ -0,0 +1,179 @@
+//! PNG previews of PDF attachment pages, cached on disk so the files view
+//! doesn't render the same page twice.
+//!
+//! Previews live in `<cache dir>/previews`, one file per attachment, page
+//! and width. `render_page` serves a cached preview or renders it on
+//! demand; `prewarm` renders the first page of a batch of PDFs ahead of
+//! time, a few at once, so opening a record with many scans doesn't stall.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::models::medical::ThumbnailPrewarmProgress;
+use crate::services::file_storage::FileStorageService;
+
+/// Renders a 0-based page of the PDF at a path to PNG bytes at a width.
+pub type PageRenderer = Arc<dyn Fn(&Path, u32, u32) -> Result<Vec<u8>, String> + Send + Sync>;
+
+/// Width of a preview when the caller doesn't ask for one.
+pub const DEFAULT_THUMBNAIL_WIDTH: u32 = 900;
+
+/// PDFs rendered at the same time by a prewarm; renders are CPU-heavy and
+/// the files view may be rendering on demand meanwhile.
+pub const PREWARM_CONCURRENCY: usize = 2;
+
+pub struct AttachmentThumbnailService;
+
+impl AttachmentThumbnailService {
+    pub fn preview_path(cache_dir: &Path, attachment_id: i64, page_index: u32, width: u32) -> PathBuf {
+        cache_dir
+            .join("previews")
+            .join(format!("attachment_{}_p{}_w{}.png", attachment_id, page_index + 1, width))
+    }
+
+    /// The preview at `path` if one was written completely. An empty file
+    /// left by an interrupted write is removed.
+    pub fn cached_preview(path: &Path) -> Option<String> {
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.len() > 0 => Some(path.display().to_string()),
+            Ok(_) => {
+                log::debug!("Cached preview {} is 0 bytes, regenerating", path.display());
+                let _ = std::fs::remove_file(path);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Path of the page's preview, rendering it unless a cached one exists
+    /// (or `force_regenerate`). A cache hit never touches the database or
+    /// the stored file.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn render_page(
+        storage_dir: &Path,
+        cache_dir: &Path,
+        db: &DatabaseConnection,
+        attachment_id: i64,
+        page_index: u32,
+        width: u32,
+        force_regenerate: bool,
+        render: PageRenderer,
+    ) -> Result<String, String> {
+        let out_path = Self::preview_path(cache_dir, attachment_id, page_index, width);
+        if force_regenerate {
+            let _ = std::fs::remove_file(&out_path);
+        } else if let Some(cached) = Self::cached_preview(&out_path) {
+            log::debug!("Using cached preview: {}", cached);
+            return Ok(cached);
+        }
+
+        // Render and write the preview off the async runtime; the attachment
+        // row has already been read, so no database connection is held meanwhile
+        FileStorageService::with_materialized_attachment_in_dir(storage_dir, cache_dir, db, attachment_id, move |pdf_path| {
+            let png_bytes = render(&pdf_path, page_index, width)?;
+            if let Some(dir) = out_path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create preview dir: {}", e))?;
+            }
+            std::fs::write(&out_path, &png_bytes).map_err(|e| format!("Failed to write preview: {}", e))?;
+            log::debug!("Wrote {} bytes to {:?}", png_bytes.len(), out_path);
+            Ok(out_path.display().to_string())
+        })
+        .await
+    }
+
+    /// PDF attachments of one medical record, or of every record of a
+    /// patient, in the order the files view lists them.
+    pub async fn pdf_attachment_ids(
+        db: &DatabaseConnection,
+        record_id: Option<i64>,
+        patient_id: Option<i64>,
+    ) -> Result<Vec<i64>, String> {
+        let (filter, id) = match (record_id, patient_id) {
+            (Some(record_id), None) => ("a.medical_record_id = ?", record_id),
+            (None, Some(patient_id)) => ("r.patient_id = ?", patient_id),
+            _ => return Err("Pass either a record id or a patient id".to_string()),
+        };
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT a.id FROM medical_attachments a \
+                     JOIN medical_records r ON r.id = a.medical_record_id \
+                     WHERE {} AND (a.mime_type = 'application/pdf' OR lower(a.original_name) LIKE '%.pdf') \
+                     ORDER BY a.medical_record_id, a.sort_order, a.id",
+                    filter
+                ),
+                [id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to list PDF attachments: {}", e))?;
+        Ok(rows.iter().filter_map(|row| row.try_get::<i64>("", "id").ok()).collect())
+    }
+
+    /// Make sure the first page of each attachment has a cached preview at
+    /// `width`, rendering at most `PREWARM_CONCURRENCY` at once.
+    /// `on_progress` is called as each attachment finishes; a failed
+    /// render is reported there and doesn't stop the others.
+    pub async fn prewarm(
+        storage_dir: PathBuf,
+        cache_dir: PathBuf,
+        db: Arc<DatabaseConnection>,
+        attachment_ids: Vec<i64>,
+        width: u32,
+        render: PageRenderer,
+        on_progress: impl Fn(&ThumbnailPrewarmProgress),
+    ) -> Vec<ThumbnailPrewarmProgress> {
+        let total = attachment_ids.len();
+        let permits = Arc::new(Semaphore::new(PREWARM_CONCURRENCY));
+        let mut renders = JoinSet::new();
+        let mut finished = Vec::with_capacity(total);
+
+        for attachment_id in attachment_ids {
+            let mut progress = ThumbnailPrewarmProgress {
+                attachment_id,
+                completed: 0,
+                total,
+                cached: false,
+                error: None,
+            };
+            if Self::cached_preview(&Self::preview_path(&cache_dir, attachment_id, 0, width)).is_some() {
+                progress.cached = true;
+                progress.completed = finished.len() + 1;
+                on_progress(&progress);
+                finished.push(progress);
+                continue;
+            }
+
+            let (storage_dir, cache_dir, db, render) = (storage_dir.clone(), cache_dir.clone(), db.clone(), render.clone());
+            let permits = permits.clone();
+            renders.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                progress.error = Self::render_page(&storage_dir, &cache_dir, &db, attachment_id, 0, width, false, render)
+                    .await
+                    .err();
+                progress
+            });
+        }
+
+        while let Some(joined) = renders.join_next().await {
+            let mut progress = match joined {
+                Ok(progress) => progress,
+                Err(e) => {
+                    log::warn!("Thumbnail prewarm task failed: {}", e);
+                    continue;
+                }
+            };
+            if let Some(e) = &progress.error {
+                log::warn!("Failed to prewarm thumbnail for attachment {}: {}", progress.attachment_id, e);
+            }
+            progress.completed = finished.len() + 1;
+            on_progress(&progress);
+            finished.push(progress);
+        }
+        finished
+    }
+}
@@ -4,9 +4,15 @@
 //! Persists its own config as JSON in <app_data_dir>/backup_config.json rather
 //! than in app_settings, because the config is system-level and should be
 //! readable before the DB pool is ready (e.g. at startup before migrations).
+//!
+//! Automatic backups are separate from the daily mirror: when enabled, a
+//! timestamped snapshot is written to <backup_dir>/auto on an interval, the
+//! oldest are rotated out, and each one is recorded in `backup_history` so
+//! it can be restored from the UI.
 
 use std::path::{Path, PathBuf};
-use chrono::Utc;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
 use sea_orm::{ConnectionTrait, DatabaseConnection, Statement, DbBackend};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -18,11 +24,37 @@ pub struct BackupConfig {
     pub directory: Option<String>,
     pub last_backup_at: Option<String>,
     pub last_error: Option<String>,
+    #[serde(default)]
+    pub auto_backup_enabled: bool,
+    /// Hours between automatic snapshots; `None` means the default (24).
+    pub auto_backup_interval_hours: Option<u32>,
+    /// Number of automatic snapshots to keep; `None` means the default (7).
+    pub auto_backup_keep: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct BackupHistoryEntry {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub file_path: String,
+    #[ts(type = "number")]
+    pub file_size: i64,
+    pub created_at: String,
 }
 
 pub struct BackupService;
 
 const DB_SNAPSHOT_RETENTION: usize = 30;
+const DEFAULT_AUTO_BACKUP_INTERVAL_HOURS: u32 = 24;
+const DEFAULT_AUTO_BACKUP_KEEP: u32 = 7;
+/// How often the scheduler wakes up to check whether a snapshot is due.
+const AUTO_BACKUP_CHECK_SECS: u64 = 10 * 60;
+/// Staged by `stage_restore`, swapped in by `apply_pending_restore` at the
+/// next startup — the live DB can't be replaced while the pool holds it.
+const PENDING_RESTORE_FILE: &str = "pending_restore.db";
+const DB_FILE: &str = "vet_clinic.db";
 
 impl BackupService {
     fn config_path(app_data_dir: &Path) -> PathBuf {
@@ -91,6 +123,256 @@ impl BackupService {
         Ok(cfg)
     }
 
+    pub fn set_auto_backup(
+        app_data_dir: &Path,
+        enabled: bool,
+        interval_hours: Option<u32>,
+        keep: Option<u32>,
+    ) -> Result<BackupConfig, String> {
+        if interval_hours == Some(0) {
+            return Err("Backup interval must be at least 1 hour".to_string());
+        }
+        if keep == Some(0) {
+            return Err("Must keep at least 1 backup".to_string());
+        }
+        let mut cfg = Self::load_config(app_data_dir);
+        if enabled && cfg.directory.is_none() {
+            return Err("No backup directory configured".to_string());
+        }
+        cfg.auto_backup_enabled = enabled;
+        cfg.auto_backup_interval_hours = interval_hours;
+        cfg.auto_backup_keep = keep;
+        Self::save_config(app_data_dir, &cfg)?;
+        Ok(cfg)
+    }
+
+    /// Spawn the periodic auto-backup loop. Config is re-read on every tick so
+    /// toggling the setting takes effect without a restart.
+    pub fn start_scheduler(app_data_dir: PathBuf, db: Arc<DatabaseConnection>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                tokio::time::Duration::from_secs(AUTO_BACKUP_CHECK_SECS),
+            );
+            loop {
+                interval.tick().await;
+
+                let cfg = Self::load_config(&app_data_dir);
+                if !cfg.auto_backup_enabled {
+                    continue;
+                }
+                match Self::auto_backup_due(&db, &cfg).await {
+                    Ok(false) => continue,
+                    Ok(true) => {}
+                    Err(e) => {
+                        log::error!("Auto backup: failed to check history: {}", e);
+                        continue;
+                    }
+                }
+                match Self::run_auto_backup(&app_data_dir, &db).await {
+                    Ok(Some(entry)) => log::info!("Auto backup: wrote {}", entry.file_path),
+                    Ok(None) => {}
+                    Err(e) => log::error!("Auto backup: failed: {}", e),
+                }
+            }
+        });
+    }
+
+    async fn auto_backup_due(db: &DatabaseConnection, cfg: &BackupConfig) -> Result<bool, String> {
+        let hours = cfg.auto_backup_interval_hours.unwrap_or(DEFAULT_AUTO_BACKUP_INTERVAL_HOURS);
+        let row = db
+            .query_one(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT created_at FROM backup_history ORDER BY id DESC LIMIT 1".to_string(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to query backup history: {}", e))?;
+        let Some(row) = row else {
+            return Ok(true);
+        };
+        let created_at: String = row.try_get("", "created_at").unwrap_or_default();
+        Ok(match DateTime::parse_from_rfc3339(&created_at) {
+            Ok(last) => Utc::now() - last.with_timezone(&Utc) >= Duration::hours(hours as i64),
+            Err(_) => true,
+        })
+    }
+
+    /// Write one timestamped snapshot into <backup_dir>/auto, record it in
+    /// `backup_history` and rotate down to the configured keep count.
+    ///
+    /// Returns `Ok(None)` when skipped: auto backup disabled, no directory
+    /// configured, or the directory isn't writable (logged as a warning so a
+    /// disconnected network share doesn't spam errors every tick).
+    pub async fn run_auto_backup(
+        app_data_dir: &Path,
+        db: &DatabaseConnection,
+    ) -> Result<Option<BackupHistoryEntry>, String> {
+        let cfg = Self::load_config(app_data_dir);
+        if !cfg.auto_backup_enabled {
+            return Ok(None);
+        }
+        let Some(backup_dir) = cfg.directory.as_ref().map(PathBuf::from) else {
+            log::warn!("Auto backup: no backup directory configured, skipping");
+            return Ok(None);
+        };
+        let auto_dir = backup_dir.join("auto");
+        if let Err(e) = ensure_writable(&auto_dir) {
+            log::warn!("Auto backup: {} is not writable, skipping: {}", auto_dir.display(), e);
+            return Ok(None);
+        }
+
+        let stamp = Utc::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+        let target = auto_dir.join(format!("vet_clinic_auto_{}.db", stamp));
+        let target_str = target.to_string_lossy().replace('\'', "''");
+        db.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            format!("VACUUM INTO '{}'", target_str),
+        ))
+        .await
+        .map_err(|e| format!("VACUUM INTO failed: {}", e))?;
+
+        let file_size = std::fs::metadata(&target)
+            .map(|m| m.len() as i64)
+            .map_err(|e| format!("Read snapshot size: {}", e))?;
+        let file_path = target.to_string_lossy().to_string();
+        let created_at = Utc::now().to_rfc3339();
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO backup_history (file_path, file_size, created_at) VALUES (?, ?, ?)",
+                [file_path.clone().into(), file_size.into(), created_at.clone().into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to record backup: {}", e))?;
+
+        let keep = cfg.auto_backup_keep.unwrap_or(DEFAULT_AUTO_BACKUP_KEEP) as usize;
+        Self::rotate_auto_backups(db, &auto_dir, keep).await?;
+
+        Ok(Some(BackupHistoryEntry {
+            id: result.last_insert_id() as i64,
+            file_path,
+            file_size,
+            created_at,
+        }))
+    }
+
+    /// Delete all but the newest `keep` auto snapshots and their history rows.
+    /// File names embed a sortable timestamp, so name order is age order.
+    async fn rotate_auto_backups(
+        db: &DatabaseConnection,
+        auto_dir: &Path,
+        keep: usize,
+    ) -> Result<(), String> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(auto_dir)
+            .map_err(|e| format!("Read auto backup dir: {}", e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with("vet_clinic_auto_") && n.ends_with(".db"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        files.sort();
+        files.reverse();
+
+        for path in files.into_iter().skip(keep) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Auto backup: failed to remove {}: {}", path.display(), e);
+                continue;
+            }
+            db.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "DELETE FROM backup_history WHERE file_path = ?",
+                [path.to_string_lossy().to_string().into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to remove backup history: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_history(db: &DatabaseConnection) -> Result<Vec<BackupHistoryEntry>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT id, file_path, file_size, created_at FROM backup_history \
+                 ORDER BY created_at DESC, id DESC".to_string(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch backup history: {}", e))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| BackupHistoryEntry {
+                id: row.try_get("", "id").unwrap_or(0),
+                file_path: row.try_get("", "file_path").unwrap_or_default(),
+                file_size: row.try_get("", "file_size").unwrap_or(0),
+                created_at: row.try_get("", "created_at").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Copy a recorded snapshot next to the live DB so it replaces it on the
+    /// next startup (see `apply_pending_restore`).
+    pub async fn stage_restore(
+        app_data_dir: &Path,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<BackupHistoryEntry, String> {
+        let entry = Self::get_history(db)
+            .await?
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| format!("Backup {} not found", id))?;
+
+        let source = PathBuf::from(&entry.file_path);
+        let mut header = [0u8; 16];
+        std::fs::File::open(&source)
+            .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut header))
+            .map_err(|e| format!("Cannot read backup file {}: {}", entry.file_path, e))?;
+        if &header != b"SQLite format 3\0" {
+            return Err(format!("Backup file is not a SQLite database: {}", entry.file_path));
+        }
+
+        std::fs::copy(&source, app_data_dir.join(PENDING_RESTORE_FILE))
+            .map_err(|e| format!("Stage restore: {}", e))?;
+        Ok(entry)
+    }
+
+    /// Swap a staged restore into place. Must run before the DB pool is
+    /// created. The replaced DB is kept as `vet_clinic.db.pre-restore`, with
+    /// its WAL/SHM moved alongside so transactions not yet checkpointed into
+    /// the main file are kept too. Returns whether a restore was applied.
+    pub fn apply_pending_restore(app_data_dir: &Path) -> Result<bool, String> {
+        let pending = app_data_dir.join(PENDING_RESTORE_FILE);
+        if !pending.exists() {
+            return Ok(false);
+        }
+        let live = app_data_dir.join(DB_FILE);
+        let kept = format!("{}.pre-restore", DB_FILE);
+        let live_exists = live.exists();
+        if live_exists {
+            std::fs::rename(&live, app_data_dir.join(&kept))
+                .map_err(|e| format!("Move current database aside: {}", e))?;
+        }
+        // The old DB's WAL/SHM would be replayed onto the restored one, so they
+        // go with the safety copy (or away, if there is no copy to go with)
+        for suffix in ["-wal", "-shm"] {
+            let journal = app_data_dir.join(format!("{}{}", DB_FILE, suffix));
+            if !journal.exists() {
+                continue;
+            }
+            if live_exists {
+                std::fs::rename(&journal, app_data_dir.join(format!("{}{}", kept, suffix)))
+                    .map_err(|e| format!("Move current database {} aside: {}", suffix, e))?;
+            } else {
+                let _ = std::fs::remove_file(&journal);
+            }
+        }
+        std::fs::rename(&pending, &live).map_err(|e| format!("Apply restore: {}", e))?;
+        Ok(true)
+    }
+
     async fn do_backup(
         app_data_dir: &Path,
         backup_dir: &Path,
@@ -136,6 +418,14 @@ impl BackupService {
     }
 }
 
+/// Create `dir` if needed and prove we can write into it.
+fn ensure_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".write_test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
 /// Recursive copy that only copies files when the source is newer or the
 /// destination doesn't exist. Never deletes from destination.
 fn copy_dir_additive(src: &Path, dest: &Path) -> std::io::Result<()> {
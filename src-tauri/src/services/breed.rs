@@ -1,8 +1,22 @@
 use crate::entities::breed::{self, Entity as BreedEntity};
 use crate::entities::patient::{self, Entity as PatientEntity};
-use crate::models::breed::{Breed, CreateBreedInput, UpdateBreedInput};
+use crate::models::breed::{
+    Breed, CreateBreedInput, DuplicateBreedCandidate, DuplicateBreedCluster, UpdateBreedInput,
+};
 use chrono::Utc;
 use sea_orm::*;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Most suggestions returned by `suggest`.
+const MAX_SUGGESTIONS: usize = 50;
+
+/// Shortest input that gets typo-tolerant matching; shorter inputs would
+/// match almost anything within one edit.
+const FUZZY_MIN_CHARS: usize = 3;
+
+/// Shortest name taken as an abbreviation of a longer one ("Lab" for
+/// "Labrador") by `find_duplicates`.
+const ABBREVIATION_MIN_CHARS: usize = 3;
 
 pub struct BreedService;
 
@@ -41,6 +55,56 @@ impl BreedService {
         Ok(breeds.into_iter().map(Self::to_api_model).collect())
     }
 
+    /// Active breeds of a species for the patient form's breed field. Breeds
+    /// whose name, or a word in it, starts with `prefix` come first in
+    /// display order; matching ignores case and diacritics. When nothing
+    /// starts with it, breeds within a typo or two of it are returned
+    /// instead, closest first.
+    pub async fn suggest(
+        db: &DatabaseConnection,
+        species_id: i64,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Breed>, String> {
+        let limit = limit.clamp(1, MAX_SUGGESTIONS);
+        let breeds = Self::get_all(db, Some(species_id), true).await?;
+        let query = fold_for_match(prefix.trim());
+        if query.is_empty() {
+            return Ok(breeds.into_iter().take(limit).collect());
+        }
+
+        let folded: Vec<String> = breeds.iter().map(|b| fold_for_match(&b.name)).collect();
+        let prefix_matches: Vec<usize> = (0..breeds.len())
+            .filter(|&i| {
+                // The whole name too, for input spanning words ("german sh")
+                folded[i].starts_with(&query)
+                    || folded[i].split_whitespace().any(|word| word.starts_with(&query))
+            })
+            .collect();
+
+        let picked = if !prefix_matches.is_empty() {
+            prefix_matches
+        } else {
+            let query_len = query.chars().count();
+            if query_len < FUZZY_MIN_CHARS {
+                return Ok(Vec::new());
+            }
+            let max_distance = if query_len <= 5 { 1 } else { 2 };
+            let mut scored: Vec<(usize, usize)> = (0..breeds.len())
+                .filter_map(|i| {
+                    let distance = prefix_distance(&query, &folded[i]);
+                    (distance <= max_distance).then_some((distance, i))
+                })
+                .collect();
+            // Stable: equal distances keep display order
+            scored.sort_by_key(|&(distance, _)| distance);
+            scored.into_iter().map(|(_, i)| i).collect()
+        };
+
+        let mut breeds: Vec<Option<Breed>> = breeds.into_iter().map(Some).collect();
+        Ok(picked.into_iter().take(limit).filter_map(|i| breeds[i].take()).collect())
+    }
+
     pub async fn get_by_id(db: &DatabaseConnection, id: i64) -> Result<Breed, String> {
         let breed = BreedEntity::find_by_id(id)
             .one(db)
@@ -164,4 +228,166 @@ impl BreedService {
 
         Ok(())
     }
+
+    /// Clusters of a species' breeds whose names look like the same breed:
+    /// equal apart from case, accents, spacing and punctuation, one a
+    /// leading abbreviation of the other ("Lab" / "Labrador"), or within a
+    /// typo or two. Inactive breeds are included since patients may still
+    /// use them. Only clusters of two or more are returned.
+    pub async fn find_duplicates(db: &DatabaseConnection, species_id: i64) -> Result<Vec<DuplicateBreedCluster>, String> {
+        let breeds = Self::get_all(db, Some(species_id), false).await?;
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT breed_id, COUNT(*) AS n FROM patients \
+                 WHERE breed_id IN (SELECT id FROM breeds WHERE species_id = ?) GROUP BY breed_id",
+                [species_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to count breed usage: {}", e))?;
+        let usage: std::collections::HashMap<i64, i64> = rows
+            .iter()
+            .filter_map(|row| Some((row.try_get("", "breed_id").ok()?, row.try_get("", "n").ok()?)))
+            .collect();
+
+        // Union-find over similar pairs, so "Lab" ~ "Labrador" ~ "Labradr"
+        // end up in one cluster
+        let compact: Vec<String> = breeds
+            .iter()
+            .map(|b| fold_for_match(&b.name).chars().filter(|c| c.is_alphanumeric()).collect())
+            .collect();
+        let mut parent: Vec<usize> = (0..breeds.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for i in 0..breeds.len() {
+            for j in (i + 1)..breeds.len() {
+                if names_look_alike(&compact[i], &compact[j]) {
+                    let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        let mut clusters: Vec<Vec<DuplicateBreedCandidate>> = vec![Vec::new(); breeds.len()];
+        for (i, breed) in breeds.into_iter().enumerate() {
+            let patient_count = usage.get(&breed.id).copied().unwrap_or(0);
+            clusters[root(&mut parent, i)].push(DuplicateBreedCandidate { breed, patient_count });
+        }
+        Ok(clusters
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .map(|mut breeds| {
+                // Stable: equally used breeds keep display order
+                breeds.sort_by(|a, b| b.patient_count.cmp(&a.patient_count));
+                DuplicateBreedCluster { breeds }
+            })
+            .collect())
+    }
+
+    /// Fold `merge_id` into `keep_id`: its patients are re-pointed to
+    /// `keep_id` and the breed is deleted, in one transaction. `keep_id`
+    /// keeps its name, so `UNIQUE(name, species_id)` can't be violated.
+    /// Both breeds must belong to the same species. Returns the number of
+    /// patients moved.
+    pub async fn merge(db: &DatabaseConnection, keep_id: i64, merge_id: i64) -> Result<u64, String> {
+        if keep_id == merge_id {
+            return Err("Can't merge a breed into itself".to_string());
+        }
+        let keep = Self::get_by_id(db, keep_id).await?;
+        let merge = Self::get_by_id(db, merge_id).await?;
+        if keep.species_id != merge.species_id {
+            return Err(format!(
+                "Can't merge breeds of different species ('{}' and '{}')",
+                keep.name, merge.name
+            ));
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let moved = txn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "UPDATE patients SET breed_id = ?, updated_at = ? WHERE breed_id = ?",
+                [keep_id.into(), Utc::now().to_rfc3339().into(), merge_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to move patients to breed: {}", e))?
+            .rows_affected();
+
+        BreedEntity::delete_by_id(merge_id)
+            .exec(&txn)
+            .await
+            .map_err(|e| format!("Failed to delete breed: {}", e))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit breed merge: {}", e))?;
+
+        log::info!("Merged breed '{}' into '{}', moving {} patients", merge.name, keep.name, moved);
+        Ok(moved)
+    }
+}
+
+/// Whether two compacted (folded, alphanumeric-only) breed names look like
+/// the same breed.
+fn names_look_alike(a: &str, b: &str) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if shorter.chars().count() >= ABBREVIATION_MIN_CHARS && longer.starts_with(shorter) {
+        return true;
+    }
+    let max_distance = match shorter.chars().count() {
+        n if n < FUZZY_MIN_CHARS => return false,
+        n if n <= 5 => 1,
+        _ => 2,
+    };
+    levenshtein(a, b) <= max_distance
+}
+
+/// Lowercase and strip diacritics (é -> e, č -> c) so typed input matches
+/// stored names however it was accented.
+fn fold_for_match(s: &str) -> String {
+    s.nfd().filter(|c| !is_combining_mark(*c)).collect::<String>().to_lowercase()
+}
+
+/// Edit distance from `query` to the closest start of `name` or of one of
+/// its words, so a typo in a partially typed breed still counts as close.
+fn prefix_distance(query: &str, name: &str) -> usize {
+    let query_len = query.chars().count();
+    std::iter::once(name)
+        .chain(name.split_whitespace())
+        .map(|candidate| {
+            let start: String = candidate.chars().take(query_len).collect();
+            levenshtein(query, &start).min(levenshtein(query, candidate))
+        })
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
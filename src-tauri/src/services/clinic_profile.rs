@@ -0,0 +1,639 @@
+//! Clinic-wide configuration as a portable JSON profile, for standing up a
+//! new install or keeping branches consistent.
+//!
+//! A profile holds currencies, species with their breeds, rooms, record and
+//! line item templates, and the clinic's default settings. Rows are matched
+//! by natural key (currency code, species name, breed name within its
+//! species, room name, template type and title, line item name), never by
+//! id, and references between them travel as those keys too. Patients,
+//! households, medical records and appointments are never read or written.
+//!
+//! Device protocol defaults are compiled in (see `device_input`), so there
+//! is nothing per-install to carry for them.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement, TransactionTrait, Value,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::services::settings::SettingsService;
+
+/// Bumped when the profile layout changes. Profiles from a newer version
+/// are refused rather than half-imported.
+pub const CLINIC_PROFILE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileCurrency {
+    pub code: String,
+    pub name: String,
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileBreed {
+    pub name: String,
+    pub active: bool,
+    #[ts(type = "number")]
+    pub display_order: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileSpecies {
+    pub name: String,
+    pub active: bool,
+    #[ts(type = "number")]
+    pub display_order: i64,
+    pub color: String,
+    pub breeds: Vec<ClinicProfileBreed>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileRoom {
+    pub name: String,
+    pub description: Option<String>,
+    #[ts(type = "number")]
+    pub capacity: i64,
+    pub is_active: bool,
+    pub color: String,
+    #[ts(type = "number")]
+    pub display_order: i64,
+    /// Missing in profiles exported before rooms had a buffer
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub buffer_minutes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileRecordTemplate {
+    pub record_type: String,
+    pub title: String,
+    pub description: String,
+    pub price: Option<f64>,
+    pub currency_code: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileLineItemTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    pub default_price: f64,
+    pub currency_code: String,
+    #[ts(type = "number")]
+    pub display_order: i64,
+    pub is_active: bool,
+}
+
+/// The clinic's default settings row. Per-user preferences (theme) and the
+/// search tokenizer, which needs an index rebuild, are not part of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileSettings {
+    pub language: String,
+    pub currency_code: Option<String>,
+    pub date_format: String,
+    pub default_room: Option<String>,
+    pub practice_timezone: Option<String>,
+    pub ocr_enabled: bool,
+    #[ts(type = "number | null")]
+    pub auto_archive_after_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfile {
+    pub format_version: u32,
+    #[ts(type = "string")]
+    pub generated_at: DateTime<Utc>,
+    pub settings: Option<ClinicProfileSettings>,
+    pub currencies: Vec<ClinicProfileCurrency>,
+    pub species: Vec<ClinicProfileSpecies>,
+    pub rooms: Vec<ClinicProfileRoom>,
+    pub record_templates: Vec<ClinicProfileRecordTemplate>,
+    pub line_item_templates: Vec<ClinicProfileLineItemTemplate>,
+}
+
+/// How an import treats configuration the profile doesn't mention. `Merge`
+/// leaves it alone; `Replace` retires it: species, breeds, rooms and line
+/// item templates are deactivated (patients and records still point at
+/// them) and record templates are deleted. Currencies are never removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileMergeStrategy {
+    Merge,
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileImportCount {
+    pub created: usize,
+    pub updated: usize,
+    pub retired: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClinicProfileImportReport {
+    pub currencies: ProfileImportCount,
+    pub species: ProfileImportCount,
+    pub breeds: ProfileImportCount,
+    pub rooms: ProfileImportCount,
+    pub record_templates: ProfileImportCount,
+    pub line_item_templates: ProfileImportCount,
+    pub settings_applied: bool,
+}
+
+pub struct ClinicProfileService;
+
+impl ClinicProfileService {
+    /// Read the current configuration into a profile, each list ordered by
+    /// its natural key.
+    pub async fn export_profile(db: &DatabaseConnection) -> Result<ClinicProfile, String> {
+        let currencies = query(db, "SELECT code, name, symbol FROM currencies ORDER BY code", vec![])
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(ClinicProfileCurrency {
+                    code: get(row, "code")?,
+                    name: get(row, "name")?,
+                    symbol: get(row, "symbol")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut breeds_by_species: HashMap<i64, Vec<ClinicProfileBreed>> = HashMap::new();
+        for row in query(db, "SELECT species_id, name, active, display_order FROM breeds ORDER BY name, id", vec![]).await? {
+            breeds_by_species
+                .entry(get(&row, "species_id")?)
+                .or_default()
+                .push(ClinicProfileBreed {
+                    name: get(&row, "name")?,
+                    active: flag(&row, "active")?,
+                    display_order: get(&row, "display_order")?,
+                });
+        }
+        let species = query(db, "SELECT id, name, active, display_order, color FROM species ORDER BY name", vec![])
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(ClinicProfileSpecies {
+                    breeds: breeds_by_species.remove(&get(row, "id")?).unwrap_or_default(),
+                    name: get(row, "name")?,
+                    active: flag(row, "active")?,
+                    display_order: get(row, "display_order")?,
+                    color: get(row, "color")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let rooms = query(
+            db,
+            "SELECT name, description, COALESCE(capacity, 1) AS capacity, COALESCE(is_active, 1) AS is_active, \
+             color, display_order, buffer_minutes FROM rooms ORDER BY name",
+            vec![],
+        )
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(ClinicProfileRoom {
+                name: get(row, "name")?,
+                description: get(row, "description")?,
+                capacity: get(row, "capacity")?,
+                is_active: flag(row, "is_active")?,
+                color: get(row, "color")?,
+                display_order: get(row, "display_order")?,
+                buffer_minutes: get(row, "buffer_minutes")?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+        let record_templates = query(
+            db,
+            "SELECT t.record_type, t.title, t.description, t.price, c.code AS currency_code \
+             FROM record_templates t LEFT JOIN currencies c ON c.id = t.currency_id \
+             ORDER BY t.record_type, t.title, t.id",
+            vec![],
+        )
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(ClinicProfileRecordTemplate {
+                record_type: get(row, "record_type")?,
+                title: get(row, "title")?,
+                description: get(row, "description")?,
+                price: real(row, "price"),
+                currency_code: get(row, "currency_code")?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+        let line_item_templates = query(
+            db,
+            "SELECT l.name, l.description, l.default_price, c.code AS currency_code, l.display_order, l.is_active \
+             FROM line_item_templates l JOIN currencies c ON c.id = l.currency_id \
+             ORDER BY l.name, l.id",
+            vec![],
+        )
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(ClinicProfileLineItemTemplate {
+                name: get(row, "name")?,
+                description: get(row, "description")?,
+                default_price: real(row, "default_price").unwrap_or_default(),
+                currency_code: get(row, "currency_code")?,
+                display_order: get(row, "display_order")?,
+                is_active: flag(row, "is_active")?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+        let settings = query(
+            db,
+            "SELECT s.language, c.code AS currency_code, s.date_format, r.name AS default_room, \
+             s.practice_timezone, s.ocr_enabled, s.auto_archive_after_days \
+             FROM app_settings s \
+             LEFT JOIN currencies c ON c.id = s.currency_id \
+             LEFT JOIN rooms r ON r.id = s.default_room_id \
+             WHERE s.user_id = 'default'",
+            vec![],
+        )
+        .await?
+        .first()
+        .map(|row| {
+            Ok::<_, String>(ClinicProfileSettings {
+                language: get(row, "language")?,
+                currency_code: get(row, "currency_code")?,
+                date_format: get::<Option<String>>(row, "date_format")?
+                    .unwrap_or_else(|| "DD/MM/YYYY".to_string()),
+                default_room: get(row, "default_room")?,
+                practice_timezone: get(row, "practice_timezone")?,
+                ocr_enabled: flag(row, "ocr_enabled")?,
+                auto_archive_after_days: get(row, "auto_archive_after_days")?,
+            })
+        })
+        .transpose()?;
+
+        Ok(ClinicProfile {
+            format_version: CLINIC_PROFILE_FORMAT_VERSION,
+            generated_at: Utc::now(),
+            settings,
+            currencies,
+            species,
+            rooms,
+            record_templates,
+            line_item_templates,
+        })
+    }
+
+    /// Apply a profile exported by `export_profile`, all or nothing.
+    pub async fn import_profile(
+        db: &DatabaseConnection,
+        json: &str,
+        strategy: ProfileMergeStrategy,
+    ) -> Result<ClinicProfileImportReport, String> {
+        let profile: ClinicProfile =
+            serde_json::from_str(json).map_err(|e| format!("Invalid clinic profile: {}", e))?;
+        if profile.format_version == 0 || profile.format_version > CLINIC_PROFILE_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported clinic profile version {} (this build reads up to {})",
+                profile.format_version, CLINIC_PROFILE_FORMAT_VERSION
+            ));
+        }
+        if let Some(settings) = &profile.settings {
+            if settings.language != "en" && settings.language != "mk" {
+                return Err(format!("Invalid language: {}. Must be 'en' or 'mk'", settings.language));
+            }
+            if let Some(tz) = &settings.practice_timezone {
+                SettingsService::parse_timezone(tz)?;
+            }
+        }
+
+        let txn = db.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut report = ClinicProfileImportReport::default();
+
+        let mut currency_ids: HashMap<String, i64> = HashMap::new();
+        for currency in &profile.currencies {
+            let id = upsert(
+                &txn,
+                &mut report.currencies,
+                "SELECT id FROM currencies WHERE code = ?",
+                vec![currency.code.clone().into()],
+                "INSERT INTO currencies (code, name, symbol) VALUES (?, ?, ?)",
+                vec![currency.code.clone().into(), currency.name.clone().into(), currency.symbol.clone().into()],
+                "UPDATE currencies SET name = ?, symbol = ? WHERE id = ?",
+                vec![currency.name.clone().into(), currency.symbol.clone().into()],
+            )
+            .await?;
+            currency_ids.insert(currency.code.clone(), id);
+        }
+
+        let mut kept_species = Vec::new();
+        let mut kept_breeds = Vec::new();
+        for species in &profile.species {
+            let species_id = upsert(
+                &txn,
+                &mut report.species,
+                "SELECT id FROM species WHERE name = ?",
+                vec![species.name.clone().into()],
+                "INSERT INTO species (name, active, display_order, color) VALUES (?, ?, ?, ?)",
+                vec![
+                    species.name.clone().into(),
+                    species.active.into(),
+                    species.display_order.into(),
+                    species.color.clone().into(),
+                ],
+                "UPDATE species SET active = ?, display_order = ?, color = ? WHERE id = ?",
+                vec![species.active.into(), species.display_order.into(), species.color.clone().into()],
+            )
+            .await?;
+            kept_species.push(species_id);
+
+            for breed in &species.breeds {
+                kept_breeds.push(
+                    upsert(
+                        &txn,
+                        &mut report.breeds,
+                        "SELECT id FROM breeds WHERE species_id = ? AND name = ?",
+                        vec![species_id.into(), breed.name.clone().into()],
+                        "INSERT INTO breeds (name, species_id, active, display_order) VALUES (?, ?, ?, ?)",
+                        vec![
+                            breed.name.clone().into(),
+                            species_id.into(),
+                            breed.active.into(),
+                            breed.display_order.into(),
+                        ],
+                        "UPDATE breeds SET active = ?, display_order = ? WHERE id = ?",
+                        vec![breed.active.into(), breed.display_order.into()],
+                    )
+                    .await?,
+                );
+            }
+        }
+
+        let mut room_ids: HashMap<String, i64> = HashMap::new();
+        for room in &profile.rooms {
+            let id = upsert(
+                &txn,
+                &mut report.rooms,
+                "SELECT id FROM rooms WHERE name = ?",
+                vec![room.name.clone().into()],
+                "INSERT INTO rooms (name, description, capacity, is_active, color, display_order, buffer_minutes) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                vec![
+                    room.name.clone().into(),
+                    room.description.clone().into(),
+                    room.capacity.into(),
+                    room.is_active.into(),
+                    room.color.clone().into(),
+                    room.display_order.into(),
+                    room.buffer_minutes.into(),
+                ],
+                "UPDATE rooms SET description = ?, capacity = ?, is_active = ?, color = ?, display_order = ?, \
+                 buffer_minutes = ? WHERE id = ?",
+                vec![
+                    room.description.clone().into(),
+                    room.capacity.into(),
+                    room.is_active.into(),
+                    room.color.clone().into(),
+                    room.display_order.into(),
+                    room.buffer_minutes.into(),
+                ],
+            )
+            .await?;
+            room_ids.insert(room.name.clone(), id);
+        }
+
+        let mut kept_record_templates = Vec::new();
+        for template in &profile.record_templates {
+            let currency_id = match &template.currency_code {
+                Some(code) => Some(currency_id(&txn, &currency_ids, code).await?),
+                None => None,
+            };
+            kept_record_templates.push(
+                upsert(
+                    &txn,
+                    &mut report.record_templates,
+                    "SELECT id FROM record_templates WHERE record_type = ? AND title = ? ORDER BY id LIMIT 1",
+                    vec![template.record_type.clone().into(), template.title.clone().into()],
+                    "INSERT INTO record_templates (record_type, title, description, price, currency_id) VALUES (?, ?, ?, ?, ?)",
+                    vec![
+                        template.record_type.clone().into(),
+                        template.title.clone().into(),
+                        template.description.clone().into(),
+                        template.price.into(),
+                        currency_id.into(),
+                    ],
+                    "UPDATE record_templates SET description = ?, price = ?, currency_id = ? WHERE id = ?",
+                    vec![template.description.clone().into(), template.price.into(), currency_id.into()],
+                )
+                .await?,
+            );
+        }
+
+        let mut kept_line_items = Vec::new();
+        for template in &profile.line_item_templates {
+            let currency_id = currency_id(&txn, &currency_ids, &template.currency_code).await?;
+            kept_line_items.push(
+                upsert(
+                    &txn,
+                    &mut report.line_item_templates,
+                    "SELECT id FROM line_item_templates WHERE name = ? ORDER BY id LIMIT 1",
+                    vec![template.name.clone().into()],
+                    "INSERT INTO line_item_templates (name, description, default_price, currency_id, display_order, is_active) \
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                    vec![
+                        template.name.clone().into(),
+                        template.description.clone().into(),
+                        template.default_price.into(),
+                        currency_id.into(),
+                        template.display_order.into(),
+                        template.is_active.into(),
+                    ],
+                    "UPDATE line_item_templates SET description = ?, default_price = ?, currency_id = ?, \
+                     display_order = ?, is_active = ? WHERE id = ?",
+                    vec![
+                        template.description.clone().into(),
+                        template.default_price.into(),
+                        currency_id.into(),
+                        template.display_order.into(),
+                        template.is_active.into(),
+                    ],
+                )
+                .await?,
+            );
+        }
+
+        if let Some(settings) = &profile.settings {
+            let currency_id = match &settings.currency_code {
+                Some(code) => Some(currency_id(&txn, &currency_ids, code).await?),
+                None => None,
+            };
+            let default_room_id = match &settings.default_room {
+                Some(name) => Some(match room_ids.get(name) {
+                    Some(id) => *id,
+                    None => existing_id(&txn, "SELECT id FROM rooms WHERE name = ?", vec![name.clone().into()])
+                        .await?
+                        .ok_or_else(|| format!("Unknown default room: {}", name))?,
+                }),
+                None => None,
+            };
+            execute(
+                &txn,
+                "INSERT INTO app_settings (user_id, language) \
+                 SELECT 'default', ? WHERE NOT EXISTS (SELECT 1 FROM app_settings WHERE user_id = 'default')",
+                vec![settings.language.clone().into()],
+            )
+            .await?;
+            execute(
+                &txn,
+                "UPDATE app_settings SET language = ?, currency_id = ?, date_format = ?, default_room_id = ?, \
+                 practice_timezone = ?, ocr_enabled = ?, auto_archive_after_days = ?, updated_at = ? \
+                 WHERE user_id = 'default'",
+                vec![
+                    settings.language.clone().into(),
+                    currency_id.into(),
+                    settings.date_format.clone().into(),
+                    default_room_id.into(),
+                    settings.practice_timezone.clone().into(),
+                    settings.ocr_enabled.into(),
+                    settings.auto_archive_after_days.into(),
+                    Utc::now().into(),
+                ],
+            )
+            .await?;
+            report.settings_applied = true;
+        }
+
+        if strategy == ProfileMergeStrategy::Replace {
+            let kept_rooms: Vec<i64> = room_ids.values().copied().collect();
+            report.species.retired =
+                execute(&txn, &retire_sql("UPDATE species SET active = 0 WHERE active = 1", &kept_species), ids(&kept_species)).await?;
+            report.breeds.retired =
+                execute(&txn, &retire_sql("UPDATE breeds SET active = 0 WHERE active = 1", &kept_breeds), ids(&kept_breeds)).await?;
+            report.rooms.retired =
+                execute(&txn, &retire_sql("UPDATE rooms SET is_active = 0 WHERE is_active = 1", &kept_rooms), ids(&kept_rooms)).await?;
+            report.record_templates.retired = execute(
+                &txn,
+                &retire_sql("DELETE FROM record_templates WHERE 1 = 1", &kept_record_templates),
+                ids(&kept_record_templates),
+            )
+            .await?;
+            report.line_item_templates.retired = execute(
+                &txn,
+                &retire_sql("UPDATE line_item_templates SET is_active = 0 WHERE is_active = 1", &kept_line_items),
+                ids(&kept_line_items),
+            )
+            .await?;
+        }
+
+        txn.commit().await.map_err(|e| format!("Failed to commit clinic profile: {}", e))?;
+        Ok(report)
+    }
+}
+
+async fn query<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> Result<Vec<QueryResult>, String> {
+    conn.query_all(Statement::from_sql_and_values(DbBackend::Sqlite, sql, values))
+        .await
+        .map_err(|e| format!("Failed to read clinic configuration: {}", e))
+}
+
+async fn execute<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> Result<usize, String> {
+    conn.execute(Statement::from_sql_and_values(DbBackend::Sqlite, sql, values))
+        .await
+        .map(|result| result.rows_affected() as usize)
+        .map_err(|e| format!("Failed to import clinic profile: {}", e))
+}
+
+async fn existing_id<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> Result<Option<i64>, String> {
+    query(conn, sql, values).await?.first().map(|row| get(row, "id")).transpose()
+}
+
+/// Update the row found by `find_sql` (its id is bound last) or insert a
+/// new one, counting which happened. Returns the row's id.
+#[allow(clippy::too_many_arguments)]
+async fn upsert<C: ConnectionTrait>(
+    conn: &C,
+    count: &mut ProfileImportCount,
+    find_sql: &str,
+    find_values: Vec<Value>,
+    insert_sql: &str,
+    insert_values: Vec<Value>,
+    update_sql: &str,
+    mut update_values: Vec<Value>,
+) -> Result<i64, String> {
+    match existing_id(conn, find_sql, find_values).await? {
+        Some(id) => {
+            update_values.push(id.into());
+            execute(conn, update_sql, update_values).await?;
+            count.updated += 1;
+            Ok(id)
+        }
+        None => {
+            let result = conn
+                .execute(Statement::from_sql_and_values(DbBackend::Sqlite, insert_sql, insert_values))
+                .await
+                .map_err(|e| format!("Failed to import clinic profile: {}", e))?;
+            count.created += 1;
+            Ok(result.last_insert_id() as i64)
+        }
+    }
+}
+
+/// A currency referenced by code, either imported just now or already present.
+async fn currency_id<C: ConnectionTrait>(conn: &C, imported: &HashMap<String, i64>, code: &str) -> Result<i64, String> {
+    if let Some(id) = imported.get(code) {
+        return Ok(*id);
+    }
+    existing_id(conn, "SELECT id FROM currencies WHERE code = ?", vec![code.into()])
+        .await?
+        .ok_or_else(|| format!("Unknown currency: {}", code))
+}
+
+/// `base` restricted to rows whose id isn't in `kept`.
+fn retire_sql(base: &str, kept: &[i64]) -> String {
+    if kept.is_empty() {
+        return base.to_string();
+    }
+    format!("{} AND id NOT IN ({})", base, vec!["?"; kept.len()].join(", "))
+}
+
+fn ids(kept: &[i64]) -> Vec<Value> {
+    kept.iter().map(|id| Value::from(*id)).collect()
+}
+
+fn get<T: sea_orm::TryGetable>(row: &QueryResult, column: &str) -> Result<T, String> {
+    row.try_get("", column).map_err(|e| e.to_string())
+}
+
+fn flag(row: &QueryResult, column: &str) -> Result<bool, String> {
+    get::<i64>(row, column).map(|value| value != 0)
+}
+
+/// REAL columns read back as integers when the value has no fraction.
+fn real(row: &QueryResult, column: &str) -> Option<f64> {
+    row.try_get::<f64>("", column)
+        .ok()
+        .or_else(|| row.try_get::<i64>("", column).ok().map(|value| value as f64))
+}
@@ -0,0 +1,109 @@
+//! Landing-screen overview: the short actionable lists shown on the home
+//! page, gathered in one call so the screen needs a single round-trip.
+//!
+//! Each list reuses the owning service's query and is capped at a small N.
+
+use chrono::{DateTime, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+
+use crate::models::{Appointment, AppointmentFilter, OverdueVaccination, Patient};
+use crate::services::appointments::AppointmentService;
+use crate::services::device_input::{ConnectionState, DeviceConnectionStatus};
+use crate::services::device_integration::DeviceIntegrationService;
+use crate::services::patient::PatientService;
+use crate::services::vaccination::VaccinationService;
+
+/// Default cap on each overview list.
+pub const OVERVIEW_LIST_LIMIT: i64 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceErrorSummary {
+    pub integration_id: i64,
+    pub name: String,
+    pub port_name: String,
+    pub last_error: Option<String>,
+    pub retry_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TodayOverview {
+    pub appointments: Vec<Appointment>,
+    /// Next doses due before the start of the day
+    pub overdue_vaccinations: Vec<OverdueVaccination>,
+    pub recent_patients: Vec<Patient>,
+    pub devices_in_error: Vec<DeviceErrorSummary>,
+}
+
+pub struct DashboardService;
+
+impl DashboardService {
+    /// UTC bounds `[start, end)` of the day `now` falls on in `tz`. The end is
+    /// the next local midnight, since a DST day is 23 or 25 hours long.
+    pub fn day_bounds<Tz: TimeZone>(
+        now: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let today = now.with_timezone(tz).date_naive();
+        let tomorrow = today.succ_opt().ok_or("Date is out of range")?;
+        Ok((
+            AppointmentService::local_midnight_utc(today, tz)?,
+            AppointmentService::local_midnight_utc(tomorrow, tz)?,
+        ))
+    }
+
+    /// Build the overview for the day `[day_start, day_end)`.
+    ///
+    /// `device_statuses` is the live listener state (it lives in memory, not
+    /// the DB); only listeners in `Error` for an enabled, non-deleted
+    /// integration are reported.
+    pub async fn get_today_overview(
+        db: &DatabaseConnection,
+        day_start: DateTime<Utc>,
+        day_end: DateTime<Utc>,
+        device_statuses: Vec<DeviceConnectionStatus>,
+        limit: i64,
+    ) -> Result<TodayOverview, String> {
+        // Soft-deleted and cancelled appointments are excluded by the default filter
+        let filter = AppointmentFilter {
+            start_date: Some(day_start),
+            ..Default::default()
+        };
+        let appointments = AppointmentService::get_appointments(db, filter, limit, 0)
+            .await?
+            .appointments
+            .into_iter()
+            .filter(|a| a.start_time < day_end)
+            .collect();
+
+        let overdue_vaccinations = VaccinationService::get_overdue(db, day_start, limit).await?;
+
+        let recent_patients = PatientService::get_recent(db, limit).await?;
+
+        let integrations = DeviceIntegrationService::get_all(db).await?;
+        let devices_in_error = device_statuses
+            .into_iter()
+            .filter(|s| s.status == ConnectionState::Error)
+            .filter_map(|s| {
+                let integration = integrations
+                    .iter()
+                    .find(|i| i.id == s.integration_id && i.enabled)?;
+                Some(DeviceErrorSummary {
+                    integration_id: s.integration_id,
+                    name: integration.name.clone(),
+                    port_name: s.port_name,
+                    last_error: s.last_error,
+                    retry_count: s.retry_count,
+                })
+            })
+            .take(limit.max(0) as usize)
+            .collect();
+
+        Ok(TodayOverview {
+            appointments,
+            overdue_vaccinations,
+            recent_patients,
+            devices_in_error,
+        })
+    }
+}
@@ -0,0 +1,491 @@
+//! Scan for rows whose references have drifted: patients pointing at
+//! species or breeds that no longer exist, attachments whose file is gone
+//! from storage or no longer matches the hash stored at upload, Google
+//! Calendar mappings for deleted appointments, and people without a household.
+//!
+//! SQLite only enforces foreign keys on connections that ran
+//! `PRAGMA foreign_keys = ON`, so databases that were written without it can
+//! hold any of these. The scan is read-only unless a `repair_*` flag is set
+//! for a category.
+//!
+//! External attachments (files referenced in place, e.g. on a network share)
+//! are not checked: a share that is offline during the scan would otherwise
+//! get their rows deleted. Attachments stored before hashes were recorded
+//! (migration 064) can only be checked for presence.
+//!
+//! `repair_primary_flags` makes every household, person (per contact type)
+//! and patient have exactly one primary person, contact or household. The
+//! triggers only demote other primaries when a primary row is inserted, so
+//! imported rows and updates can leave none or several.
+
+use std::path::{Path, PathBuf};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::services::file_storage::{FileStorageService, STORED_FILE};
+
+/// Ids listed per category; `count` always has the full number.
+pub const INTEGRITY_SAMPLE_SIZE: usize = 10;
+
+/// Which categories to fix while scanning. Everything is off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntegrityRepairOptions {
+    /// Clear invalid species_id / breed_id on patients.
+    pub repair_patient_references: bool,
+    /// Delete attachment rows whose file is missing.
+    pub repair_missing_attachments: bool,
+    /// Delete calendar mappings whose appointment no longer exists.
+    pub repair_orphan_mappings: bool,
+    /// Delete people (and their contacts) whose household no longer exists.
+    pub repair_orphan_people: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntegrityIssue {
+    pub count: usize,
+    pub sample_ids: Vec<i64>,
+    /// Rows fixed by this run; 0 unless the category's repair flag was set.
+    pub repaired: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    /// Whether the connection that ran the scan enforces foreign keys.
+    pub foreign_keys_enabled: bool,
+    pub patients_invalid_species: IntegrityIssue,
+    pub patients_invalid_breed: IntegrityIssue,
+    pub attachments_missing_file: IntegrityIssue,
+    /// Attachment files whose content differs from the hash stored at upload.
+    /// Never repaired: the original bytes are gone.
+    pub attachments_hash_mismatch: IntegrityIssue,
+    pub mappings_without_appointment: IntegrityIssue,
+    pub people_without_household: IntegrityIssue,
+}
+
+impl IntegrityReport {
+    pub fn total_issues(&self) -> usize {
+        self.patients_invalid_species.count
+            + self.patients_invalid_breed.count
+            + self.attachments_missing_file.count
+            + self.attachments_hash_mismatch.count
+            + self.mappings_without_appointment.count
+            + self.people_without_household.count
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentIntegrityProblemKind {
+    /// The file is not in storage.
+    Missing,
+    /// The file's SHA-256 differs from `content_sha256`.
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentIntegrityProblem {
+    pub attachment_id: i64,
+    pub medical_record_id: i64,
+    pub original_name: String,
+    pub kind: AttachmentIntegrityProblemKind,
+    pub expected_sha256: Option<String>,
+    /// Hash of the file as it is now; None when it is missing.
+    pub actual_sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttachmentIntegrityReport {
+    /// Files that were re-hashed and compared.
+    pub checked: usize,
+    /// Files present but stored without a hash, so not compared.
+    pub unhashed: usize,
+    pub problems: Vec<AttachmentIntegrityProblem>,
+}
+
+/// Fixes made in one scope by `repair_primary_flags`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrimaryFlagRepair {
+    /// Groups that had no primary and got their earliest row promoted.
+    pub promoted: usize,
+    /// Rows that lost the flag because an earlier row in the group kept it.
+    pub demoted: usize,
+    /// Owner ids (household, person, patient) of fixed groups.
+    pub sample_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrimaryFlagRepairReport {
+    /// One primary person per household.
+    pub household_people: PrimaryFlagRepair,
+    /// One primary contact per person and contact type.
+    pub person_contacts: PrimaryFlagRepair,
+    /// One primary household per patient.
+    pub patient_households: PrimaryFlagRepair,
+}
+
+const INVALID_SPECIES: &str =
+    "species_id IS NOT NULL AND species_id NOT IN (SELECT id FROM species)";
+const INVALID_BREED: &str =
+    "breed_id IS NOT NULL AND breed_id NOT IN (SELECT id FROM breeds)";
+const ORPHAN_MAPPING: &str = "appointment_id NOT IN (SELECT id FROM appointments)";
+const ORPHAN_PERSON: &str =
+    "household_id IS NULL OR household_id NOT IN (SELECT id FROM households)";
+
+pub struct DataIntegrityService;
+
+impl DataIntegrityService {
+    /// Scan every category and apply the repairs enabled in `repair`.
+    /// Attachment files are looked up in `storage_dir`.
+    pub async fn run_scan(
+        db: &DatabaseConnection,
+        storage_dir: &Path,
+        repair: &IntegrityRepairOptions,
+    ) -> Result<IntegrityReport, String> {
+        let foreign_keys_enabled = db
+            .query_one(Statement::from_string(DbBackend::Sqlite, "PRAGMA foreign_keys".to_string()))
+            .await
+            .map_err(|e| format!("Failed to read foreign_keys pragma: {}", e))?
+            .and_then(|row| row.try_get::<i64>("", "foreign_keys").ok())
+            .is_some_and(|v| v == 1);
+        if !foreign_keys_enabled {
+            log::warn!("Data integrity scan: foreign key enforcement is off on this connection");
+        }
+
+        let mut patients_invalid_species = Self::find_issue(db, "patients", INVALID_SPECIES).await?;
+        let mut patients_invalid_breed = Self::find_issue(db, "patients", INVALID_BREED).await?;
+        if repair.repair_patient_references {
+            patients_invalid_species.repaired = Self::execute(
+                db,
+                &format!("UPDATE patients SET species_id = NULL WHERE {}", INVALID_SPECIES),
+                Vec::new(),
+            )
+            .await?;
+            patients_invalid_breed.repaired = Self::execute(
+                db,
+                &format!("UPDATE patients SET breed_id = NULL WHERE {}", INVALID_BREED),
+                Vec::new(),
+            )
+            .await?;
+        }
+
+        let missing_ids = Self::attachments_missing_file(db, storage_dir).await?;
+        let mut attachments_missing_file = IntegrityIssue {
+            count: missing_ids.len(),
+            sample_ids: missing_ids.iter().take(INTEGRITY_SAMPLE_SIZE).copied().collect(),
+            repaired: 0,
+        };
+        if repair.repair_missing_attachments && !missing_ids.is_empty() {
+            let placeholders = vec!["?"; missing_ids.len()].join(", ");
+            attachments_missing_file.repaired = Self::execute(
+                db,
+                &format!("DELETE FROM medical_attachments WHERE id IN ({})", placeholders),
+                missing_ids.iter().map(|id| Value::from(*id)).collect(),
+            )
+            .await?;
+        }
+
+        let modified_ids: Vec<i64> = Self::verify_attachment_hashes(db, storage_dir, None)
+            .await?
+            .problems
+            .iter()
+            .filter(|p| p.kind == AttachmentIntegrityProblemKind::Modified)
+            .map(|p| p.attachment_id)
+            .collect();
+        let attachments_hash_mismatch = IntegrityIssue {
+            count: modified_ids.len(),
+            sample_ids: modified_ids.iter().take(INTEGRITY_SAMPLE_SIZE).copied().collect(),
+            repaired: 0,
+        };
+
+        let mut mappings_without_appointment =
+            Self::find_issue(db, "calendar_event_mappings", ORPHAN_MAPPING).await?;
+        if repair.repair_orphan_mappings {
+            mappings_without_appointment.repaired = Self::execute(
+                db,
+                &format!("DELETE FROM calendar_event_mappings WHERE {}", ORPHAN_MAPPING),
+                Vec::new(),
+            )
+            .await?;
+        }
+
+        let mut people_without_household = Self::find_issue(db, "people", ORPHAN_PERSON).await?;
+        if repair.repair_orphan_people {
+            // Contacts first: with foreign keys off they would not cascade
+            Self::execute(
+                db,
+                &format!(
+                    "DELETE FROM person_contacts WHERE person_id IN (SELECT id FROM people WHERE {})",
+                    ORPHAN_PERSON
+                ),
+                Vec::new(),
+            )
+            .await?;
+            people_without_household.repaired = Self::execute(
+                db,
+                &format!("DELETE FROM people WHERE {}", ORPHAN_PERSON),
+                Vec::new(),
+            )
+            .await?;
+        }
+
+        let report = IntegrityReport {
+            foreign_keys_enabled,
+            patients_invalid_species,
+            patients_invalid_breed,
+            attachments_missing_file,
+            attachments_hash_mismatch,
+            mappings_without_appointment,
+            people_without_household,
+        };
+        log::info!("Data integrity scan found {} issue(s)", report.total_issues());
+        Ok(report)
+    }
+
+    async fn find_issue(
+        db: &DatabaseConnection,
+        table: &str,
+        condition: &str,
+    ) -> Result<IntegrityIssue, String> {
+        let ids = Self::query_ids(
+            db,
+            &format!("SELECT id FROM {} WHERE {} ORDER BY id", table, condition),
+        )
+        .await?;
+        Ok(IntegrityIssue {
+            count: ids.len(),
+            sample_ids: ids.into_iter().take(INTEGRITY_SAMPLE_SIZE).collect(),
+            repaired: 0,
+        })
+    }
+
+    async fn attachments_missing_file(
+        db: &DatabaseConnection,
+        storage_dir: &Path,
+    ) -> Result<Vec<i64>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!("SELECT id, {} AS file_id FROM medical_attachments WHERE is_external = 0 ORDER BY id", STORED_FILE),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch attachments: {}", e))?;
+
+        let mut missing = Vec::new();
+        for row in rows {
+            let id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            let file_id: String = row.try_get("", "file_id").unwrap_or_default();
+            if file_id.is_empty() || !storage_dir.join(&file_id).is_file() {
+                missing.push(id);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Re-hash the stored attachment files, of one patient or of all, and
+    /// compare them with `content_sha256`. Files in `storage_dir` are read
+    /// off the async runtime.
+    pub async fn verify_attachment_hashes(
+        db: &DatabaseConnection,
+        storage_dir: &Path,
+        patient_id: Option<i64>,
+    ) -> Result<AttachmentIntegrityReport, String> {
+        let mut sql = format!(
+            "SELECT ma.id, ma.medical_record_id, ma.original_name, {} AS file_id, ma.content_sha256 \
+             FROM medical_attachments ma \
+             JOIN medical_records mr ON mr.id = ma.medical_record_id \
+             WHERE ma.is_external = 0",
+            STORED_FILE
+        );
+        let mut values = Vec::new();
+        if let Some(patient_id) = patient_id {
+            sql.push_str(" AND mr.patient_id = ?");
+            values.push(Value::from(patient_id));
+        }
+        sql.push_str(" ORDER BY ma.id");
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, values))
+            .await
+            .map_err(|e| format!("Failed to fetch attachments: {}", e))?;
+
+        let mut attachments = Vec::with_capacity(rows.len());
+        for row in rows {
+            let file_id: String = row.try_get("", "file_id").unwrap_or_default();
+            attachments.push((
+                AttachmentIntegrityProblem {
+                    attachment_id: row.try_get("", "id").map_err(|e| e.to_string())?,
+                    medical_record_id: row.try_get("", "medical_record_id").map_err(|e| e.to_string())?,
+                    original_name: row.try_get("", "original_name").unwrap_or_default(),
+                    kind: AttachmentIntegrityProblemKind::Missing,
+                    expected_sha256: row.try_get("", "content_sha256").ok().flatten(),
+                    actual_sha256: None,
+                },
+                (!file_id.is_empty()).then(|| storage_dir.join(&file_id)),
+            ));
+        }
+
+        let report = tokio::task::spawn_blocking(move || Self::compare_hashes(attachments))
+            .await
+            .map_err(|e| format!("Attachment verification failed: {}", e))?;
+        log::info!(
+            "Attachment verification: {} checked, {} unhashed, {} problem(s)",
+            report.checked,
+            report.unhashed,
+            report.problems.len()
+        );
+        Ok(report)
+    }
+
+    fn compare_hashes(attachments: Vec<(AttachmentIntegrityProblem, Option<PathBuf>)>) -> AttachmentIntegrityReport {
+        let mut report = AttachmentIntegrityReport::default();
+        for (mut entry, path) in attachments {
+            let Some(path) = path.filter(|p| p.is_file()) else {
+                report.problems.push(entry);
+                continue;
+            };
+            let Some(expected) = entry.expected_sha256.as_deref() else {
+                report.unhashed += 1;
+                continue;
+            };
+
+            report.checked += 1;
+            match FileStorageService::file_sha256(&path) {
+                Ok(actual) if actual.eq_ignore_ascii_case(expected) => {}
+                Ok(actual) => {
+                    entry.kind = AttachmentIntegrityProblemKind::Modified;
+                    entry.actual_sha256 = Some(actual);
+                    report.problems.push(entry);
+                }
+                Err(e) => {
+                    log::warn!("Could not read attachment {}: {}", entry.attachment_id, e);
+                    report.problems.push(entry);
+                }
+            }
+        }
+        report
+    }
+
+    async fn query_ids(db: &DatabaseConnection, sql: &str) -> Result<Vec<i64>, String> {
+        let rows = db
+            .query_all(Statement::from_string(DbBackend::Sqlite, sql.to_string()))
+            .await
+            .map_err(|e| format!("Integrity query failed: {}", e))?;
+        rows.iter()
+            .map(|row| row.try_get::<i64>("", "id").map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    async fn execute(db: &DatabaseConnection, sql: &str, values: Vec<Value>) -> Result<usize, String> {
+        let result = db
+            .execute(Statement::from_sql_and_values(DbBackend::Sqlite, sql, values))
+            .await
+            .map_err(|e| format!("Integrity repair failed: {}", e))?;
+        Ok(result.rows_affected() as usize)
+    }
+
+    /// Leave exactly one primary in every group: the earliest-created
+    /// primary is kept, and a group with none gets its earliest row
+    /// promoted. All three scopes are fixed in one transaction.
+    pub async fn repair_primary_flags(db: &DatabaseConnection) -> Result<PrimaryFlagRepairReport, String> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let report = PrimaryFlagRepairReport {
+            household_people: Self::repair_primary_scope(&txn, "people", "household_id", "''").await?,
+            person_contacts: Self::repair_primary_scope(&txn, "person_contacts", "person_id", "contact_type").await?,
+            patient_households: Self::repair_primary_scope(&txn, "patient_households", "patient_id", "''").await?,
+        };
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit primary flag repair: {}", e))?;
+        log::info!(
+            "Primary flag repair: people {}/{}, contacts {}/{}, patient households {}/{} (promoted/demoted)",
+            report.household_people.promoted,
+            report.household_people.demoted,
+            report.person_contacts.promoted,
+            report.person_contacts.demoted,
+            report.patient_households.promoted,
+            report.patient_households.demoted
+        );
+        Ok(report)
+    }
+
+    /// Fix one table, grouping rows by `owner_column` and `kind_expr`.
+    async fn repair_primary_scope<C: ConnectionTrait>(
+        conn: &C,
+        table: &str,
+        owner_column: &str,
+        kind_expr: &str,
+    ) -> Result<PrimaryFlagRepair, String> {
+        let rows = conn
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!(
+                    "SELECT id, {owner} AS owner_id, {kind} AS kind, COALESCE(is_primary, 0) AS is_primary \
+                     FROM {table} ORDER BY {owner}, {kind}, created_at, id",
+                    owner = owner_column,
+                    kind = kind_expr,
+                    table = table
+                ),
+            ))
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+
+        // Rows arrive grouped and oldest first within a group
+        let mut groups: Vec<((i64, String), Vec<(i64, bool)>)> = Vec::new();
+        for row in rows {
+            let key = (
+                row.try_get("", "owner_id").map_err(|e| e.to_string())?,
+                row.try_get("", "kind").map_err(|e| e.to_string())?,
+            );
+            let entry = (
+                row.try_get("", "id").map_err(|e| e.to_string())?,
+                row.try_get("", "is_primary").map_err(|e| e.to_string())?,
+            );
+            match groups.last_mut() {
+                Some((last, members)) if *last == key => members.push(entry),
+                _ => groups.push((key, vec![entry])),
+            }
+        }
+
+        let mut repair = PrimaryFlagRepair::default();
+        let mut promote = Vec::new();
+        let mut demote = Vec::new();
+        for ((owner_id, _), members) in groups {
+            let primaries: Vec<i64> = members.iter().filter(|(_, p)| *p).map(|(id, _)| *id).collect();
+            match primaries.len() {
+                1 => continue,
+                0 => promote.push(members[0].0),
+                _ => demote.extend_from_slice(&primaries[1..]),
+            }
+            if repair.sample_ids.len() < INTEGRITY_SAMPLE_SIZE && !repair.sample_ids.contains(&owner_id) {
+                repair.sample_ids.push(owner_id);
+            }
+        }
+
+        for (ids, flag) in [(&promote, true), (&demote, false)] {
+            if ids.is_empty() {
+                continue;
+            }
+            let placeholders = vec!["?"; ids.len()].join(", ");
+            let mut values = vec![Value::from(flag)];
+            values.extend(ids.iter().map(|id| Value::from(*id)));
+            conn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("UPDATE {} SET is_primary = ? WHERE id IN ({})", table, placeholders),
+                values,
+            ))
+            .await
+            .map_err(|e| format!("Failed to repair {}: {}", table, e))?;
+        }
+
+        repair.promoted = promote.len();
+        repair.demoted = demote.len();
+        Ok(repair)
+    }
+}
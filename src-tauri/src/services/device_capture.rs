@@ -4,6 +4,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 use hidapi::HidApi;
 use tauri::{AppHandle, Manager};
+use crate::services::events;
 
 #[derive(Clone, Debug)]
 struct BarcodeBuffer {
@@ -151,7 +152,7 @@ fn emit_barcode(app: &AppHandle, device_name: &str, code: &str) {
         if is_hidden {
             throttled_show_and_focus(app);
             let _ = app.emit_all(
-                "wake-from-tray",
+                events::WAKE_FROM_TRAY,
                 serde_json::json!({
                     "cause": "scan",
                     "code": normalized,
@@ -162,7 +163,7 @@ fn emit_barcode(app: &AppHandle, device_name: &str, code: &str) {
         }
         // Always emit barcode data so the frontend can process it
         let _ = app.emit_all(
-            "scanner:barcode",
+            events::SCANNER_BARCODE,
             serde_json::json!({
                 "code": normalized,
                 "device": device_name,
@@ -0,0 +1,248 @@
+//! Recently parsed device results, per integration, held in memory.
+//!
+//! `device-data-received` is fire-and-forget: a result that arrives while no
+//! screen is listening is gone. Every emitted result is also pushed here so a
+//! freshly opened screen can backfill via `get_recent_device_data`. Nothing
+//! is persisted, and each integration keeps at most `RECENT_DEVICE_DATA_CAPACITY`
+//! results (they carry the raw file bytes, so the cap matters). The newest
+//! one backs the "last reading" preview (`get_last_device_result`).
+//!
+//! Serial listeners also check `is_duplicate_device_data` before emitting, so
+//! a message the framing split twice (or the device resent) within
+//! `DEVICE_DATA_DEDUP_WINDOW` doesn't create a second record.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::services::device_parser::DeviceData;
+
+/// Results kept per integration.
+pub const RECENT_DEVICE_DATA_CAPACITY: usize = 20;
+
+/// Identical results arriving within this window are treated as one.
+pub const DEVICE_DATA_DEDUP_WINDOW: Duration = Duration::from_secs(10);
+
+static RECENT_DEVICE_DATA: OnceLock<Mutex<DeviceDataBuffer>> = OnceLock::new();
+
+static SEEN_DEVICE_DATA: OnceLock<Mutex<DeviceDataDedup>> = OnceLock::new();
+
+fn get_recent_device_data_buffer() -> &'static Mutex<DeviceDataBuffer> {
+    RECENT_DEVICE_DATA.get_or_init(|| Mutex::new(DeviceDataBuffer::new(RECENT_DEVICE_DATA_CAPACITY)))
+}
+
+fn get_seen_device_data() -> &'static Mutex<DeviceDataDedup> {
+    SEEN_DEVICE_DATA.get_or_init(|| Mutex::new(DeviceDataDedup::new(DEVICE_DATA_DEDUP_WINDOW)))
+}
+
+/// Bounded ring of the latest results for each integration.
+pub struct DeviceDataBuffer {
+    capacity: usize,
+    entries: HashMap<i64, VecDeque<DeviceData>>,
+}
+
+impl DeviceDataBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Append a result, dropping the oldest once the integration is full.
+    pub fn push(&mut self, integration_id: i64, data: DeviceData) {
+        let ring = self.entries.entry(integration_id).or_default();
+        while ring.len() >= self.capacity {
+            ring.pop_front();
+        }
+        ring.push_back(data);
+    }
+
+    /// Up to `limit` results for an integration, newest first.
+    pub fn recent(&self, integration_id: i64, limit: usize) -> Vec<DeviceData> {
+        self.entries
+            .get(&integration_id)
+            .map(|ring| ring.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The integration's newest result.
+    pub fn latest(&self, integration_id: i64) -> Option<DeviceData> {
+        self.entries.get(&integration_id).and_then(|ring| ring.back().cloned())
+    }
+
+    pub fn clear(&mut self, integration_id: i64) {
+        self.entries.remove(&integration_id);
+    }
+}
+
+/// Short-window memory of result fingerprints, used to drop repeats.
+pub struct DeviceDataDedup {
+    window: Duration,
+    seen: HashMap<u64, Instant>,
+}
+
+impl DeviceDataDedup {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Fingerprint of a parsed result: integration, device type, patient
+    /// identifier and the parsed values. The file name and arrival time are
+    /// left out since they differ between two copies of the same message.
+    pub fn fingerprint(integration_id: i64, data: &DeviceData) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        integration_id.hash(&mut hasher);
+        data.device_type.hash(&mut hasher);
+        data.patient_identifier.hash(&mut hasher);
+        data.test_results.to_string().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// True if the same result was seen less than `window` before `now`.
+    /// Either way `now` becomes the time it was last seen.
+    pub fn check(&mut self, integration_id: i64, data: &DeviceData, now: Instant) -> bool {
+        let window = self.window;
+        self.seen.retain(|_, seen_at| now.saturating_duration_since(*seen_at) < window);
+
+        self.seen
+            .insert(Self::fingerprint(integration_id, data), now)
+            .is_some()
+    }
+}
+
+/// Remember a result that was just emitted to the frontend.
+pub fn record_device_data(integration_id: i64, data: &DeviceData) {
+    get_recent_device_data_buffer()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(integration_id, data.clone());
+}
+
+/// Whether this result repeats one received for the integration within
+/// `DEVICE_DATA_DEDUP_WINDOW`.
+pub fn is_duplicate_device_data(integration_id: i64, data: &DeviceData) -> bool {
+    get_seen_device_data()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .check(integration_id, data, Instant::now())
+}
+
+/// Latest results for an integration, newest first.
+pub fn get_recent_device_data(integration_id: i64, limit: usize) -> Vec<DeviceData> {
+    get_recent_device_data_buffer()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .recent(integration_id, limit)
+}
+
+/// An integration's newest result, if it produced any since startup.
+pub fn get_last_device_result(integration_id: i64) -> Option<DeviceData> {
+    get_recent_device_data_buffer()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .latest(integration_id)
+}
+
+/// Forget an integration's results, e.g. when it is deleted.
+pub fn clear_device_data(integration_id: i64) {
+    get_recent_device_data_buffer()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear(integration_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample(file_name: &str) -> DeviceData {
+        DeviceData {
+            device_type: "exigo_eos_vet".to_string(),
+            device_name: "Exigo".to_string(),
+            connection_method: "file_watch".to_string(),
+            patient_identifier: None,
+            test_results: serde_json::json!({}),
+            original_file_name: file_name.to_string(),
+            file_data: Vec::new(),
+            mime_type: "application/xml".to_string(),
+            detected_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn keeps_only_the_latest_results_per_integration() {
+        let mut buffer = DeviceDataBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(1, sample(&format!("result-{}.xml", i)));
+        }
+        buffer.push(2, sample("other.xml"));
+
+        let names: Vec<String> = buffer
+            .recent(1, 10)
+            .into_iter()
+            .map(|d| d.original_file_name)
+            .collect();
+        assert_eq!(names, vec!["result-4.xml", "result-3.xml", "result-2.xml"]);
+        assert_eq!(buffer.recent(1, 2).len(), 2);
+        assert_eq!(buffer.recent(2, 10).len(), 1, "integrations don't share a ring");
+
+        buffer.clear(1);
+        assert!(buffer.recent(1, 10).is_empty());
+    }
+
+    #[test]
+    fn last_result_is_the_newest_for_that_integration() {
+        // The buffer is process-wide; these ids are not used by other tests
+        let (integration, other) = (1994, 1995);
+        assert!(get_last_device_result(integration).is_none());
+
+        let mut first = sample("first.xml");
+        first.patient_identifier = Some("REX".to_string());
+        record_device_data(integration, &first);
+        let mut second = sample("second.xml");
+        second.patient_identifier = Some("BELLA".to_string());
+        record_device_data(integration, &second);
+        record_device_data(other, &sample("other.xml"));
+
+        let last = get_last_device_result(integration).unwrap();
+        assert_eq!(last.original_file_name, "second.xml");
+        assert_eq!(last.patient_identifier.as_deref(), Some("BELLA"));
+        assert_eq!(last.detected_at, second.detected_at);
+
+        clear_device_data(integration);
+        clear_device_data(other);
+        assert!(get_last_device_result(integration).is_none());
+    }
+
+    #[test]
+    fn repeated_payload_within_window_is_dropped() {
+        let mut dedup = DeviceDataDedup::new(Duration::from_secs(10));
+        let start = Instant::now();
+        let mut data = sample("serial_data");
+        data.patient_identifier = Some("REX".to_string());
+        data.test_results = serde_json::json!({ "T4-1": "15.5" });
+
+        // Same message twice in quick succession: only the first goes out
+        let emitted = [start, start + Duration::from_millis(200)]
+            .into_iter()
+            .filter(|now| !dedup.check(1, &data, *now))
+            .count();
+        assert_eq!(emitted, 1);
+
+        // A different patient, or another integration, is not a repeat
+        let mut other = data.clone();
+        other.patient_identifier = Some("BELLA".to_string());
+        assert!(!dedup.check(1, &other, start + Duration::from_secs(1)));
+        assert!(!dedup.check(2, &data, start + Duration::from_secs(1)));
+
+        // Once the window has passed the same result counts again
+        assert!(!dedup.check(1, &data, start + Duration::from_secs(11)));
+    }
+}
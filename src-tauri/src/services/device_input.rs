@@ -12,10 +12,14 @@ use chrono::Utc;
 use rand::Rng;
 use crate::services::device_input::PortType::HIDDevice;
 use crate::services::device_parser::DeviceParserService;
+use crate::services::events::{self, EventSink};
+use crate::services::device_data_buffer::{is_duplicate_device_data, record_device_data};
+use crate::services::device_raw_capture::tee_raw_bytes;
 use crate::services::device_capture::throttled_show_and_focus;
 use crate::services::file_storage::FileStorageService;
 use crate::commands::file_history::record_device_file_access_internal_seaorm;
 use crate::database::SeaOrmPool;
+use crate::models::device_integration::DeviceType;
 
 // Thread handle for managing listener lifecycle
 // Stores JoinHandle, shutdown channel, and cleanup data for graceful thread termination
@@ -204,7 +208,7 @@ fn update_connection_status(app_handle: &AppHandle, integration_id: i64, port_na
     };
 
     if should_emit {
-        match app_handle.emit_all("device-connection-status", &connection_status) {
+        match app_handle.emit_all(events::DEVICE_CONNECTION_STATUS, &connection_status) {
             Ok(_) => log::info!("📡 Emitted device-connection-status event to frontend for {} ({})", device_type, port_name),
             Err(e) => log::error!("❌ Failed to emit device-connection-status event: {}", e),
         }
@@ -218,6 +222,42 @@ pub fn get_all_connection_statuses() -> Vec<DeviceConnectionStatus> {
     statuses.values().cloned().collect()
 }
 
+/// Forget the stored status of every port of `integration_id`, leaving any
+/// listener running. Returns what each cleared port should now show:
+/// disconnected, with the error and retry state dropped.
+pub fn clear_connection_status(integration_id: i64) -> Vec<DeviceConnectionStatus> {
+    let mut statuses = get_connection_status().lock()
+        .expect("CONNECTION_STATUS mutex poisoned - a thread panicked while holding the lock");
+    let keys: Vec<String> = statuses
+        .iter()
+        .filter(|(_, status)| status.integration_id == integration_id)
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    keys.iter()
+        .filter_map(|key| statuses.remove(key))
+        .map(|status| DeviceConnectionStatus {
+            status: ConnectionState::Disconnected,
+            last_error: None,
+            retry_count: 0,
+            next_retry: None,
+            ..status
+        })
+        .collect()
+}
+
+/// Clear the stored statuses of `integration_id` and send each cleared
+/// port's disconnected status to `sink`. Returns how many were cleared.
+pub fn dismiss_connection_status(sink: &impl EventSink, integration_id: i64) -> usize {
+    let cleared = clear_connection_status(integration_id);
+    for status in &cleared {
+        if let Err(e) = sink.emit_event(events::DEVICE_CONNECTION_STATUS, status) {
+            log::error!("❌ Failed to emit device-connection-status event: {}", e);
+        }
+    }
+    cleared.len()
+}
+
 #[derive(Serialize)]
 pub struct UsbInfo {
     pub vid: u16,
@@ -335,6 +375,61 @@ pub fn get_device_protocol(device_type: &str) -> DeviceProtocol {
     }
 }
 
+/// How a device's messages are delimited on the wire, for the setup form.
+fn framing_description(device_type: &str) -> &'static str {
+    match device_type {
+        "healvet_hv_fia_3000" => "Text message starting with #AFS1000 and ending with \"EE\"",
+        "mnchip_pointcare_chemistry" | "mnchip_pcr_analyzer" => {
+            "HL7 over MLLP: VT (0x0B) starts a message, FS (0x1C) followed by CR ends it"
+        }
+        _ => "One message per line, ending with LF (0x0A)",
+    }
+}
+
+/// A supported device type and its name in the UI.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedDeviceType {
+    pub device_type: String,
+    pub display_name: String,
+}
+
+/// Serial defaults of a device type, from `get_device_protocol`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceProtocolDefaults {
+    pub device_type: String,
+    pub display_name: String,
+    pub baud_rate: u32,
+    pub start_symbol: Option<u8>,
+    pub end_symbol: u8,
+    pub framing: String,
+    /// Every device type the setup form can choose from.
+    pub supported_device_types: Vec<SupportedDeviceType>,
+}
+
+/// Protocol defaults of `device_type`, so the setup form can prefill the
+/// baud rate and explain the framing.
+pub fn get_device_protocol_defaults(device_type: &str) -> Result<DeviceProtocolDefaults, String> {
+    let known = DeviceType::from_db_string(device_type)?;
+    let protocol = get_device_protocol(device_type);
+    Ok(DeviceProtocolDefaults {
+        device_type: device_type.to_string(),
+        display_name: known.display_name().to_string(),
+        baud_rate: protocol.baud_rate,
+        start_symbol: protocol.start_symbol,
+        end_symbol: protocol.end_symbol,
+        framing: framing_description(device_type).to_string(),
+        supported_device_types: DeviceType::ALL
+            .iter()
+            .map(|t| SupportedDeviceType {
+                device_type: t.to_db_string().to_string(),
+                display_name: t.display_name().to_string(),
+            })
+            .collect(),
+    })
+}
+
 /// Byte-at-a-time frame assembler for the serial read loop.
 ///
 /// Extracted from the read loop so the (device-specific, easy-to-get-wrong)
@@ -884,6 +979,61 @@ fn calculate_backoff_delay(attempt: u32) -> Duration {
     Duration::from_secs(capped_delay + jitter)
 }
 
+/// Integration ids that currently have a listener thread running
+pub fn active_listener_integration_ids() -> Vec<i64> {
+    let listeners = get_active_listeners().lock()
+        .expect("ACTIVE_LISTENERS mutex poisoned - a thread panicked while holding the lock");
+    listeners.values().map(|l| l.integration_id).collect()
+}
+
+/// Stop every listener running for `integration_id`, whichever port it was
+/// started on. Returns how many were stopped.
+pub fn stop_integration_listeners(integration_id: i64) -> usize {
+    let listener_keys: Vec<String> = {
+        let listeners = get_active_listeners().lock()
+            .expect("ACTIVE_LISTENERS mutex poisoned - a thread panicked while holding the lock");
+        listeners.iter()
+            .filter(|(_, l)| l.integration_id == integration_id)
+            .map(|(key, _)| key.clone())
+            .collect()
+    };
+
+    for key in &listener_keys {
+        // Device types never contain ':', port names might
+        if let Some((port_name, device_type)) = key.rsplit_once(':') {
+            stop_listen(port_name, device_type);
+        }
+    }
+    listener_keys.len()
+}
+
+/// Store a connection status directly, so tests can set up a device state
+/// without a listener.
+#[cfg(test)]
+pub(crate) fn insert_connection_status(status: DeviceConnectionStatus) {
+    let mut statuses = get_connection_status().lock()
+        .expect("CONNECTION_STATUS mutex poisoned - a thread panicked while holding the lock");
+    statuses.insert(format!("{}:{}", status.integration_id, status.port_name), status);
+}
+
+/// Register a listener thread that only waits for its shutdown signal, so
+/// tests can exercise the listener lifecycle without a serial port.
+#[cfg(test)]
+pub(crate) fn register_idle_listener(port_name: &str, device_type: &str, integration_id: i64) {
+    let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>();
+    let handle = thread::spawn(move || {
+        let _ = shutdown_rx.recv();
+    });
+    let mut listeners = get_active_listeners().lock()
+        .expect("ACTIVE_LISTENERS mutex poisoned - a thread panicked while holding the lock");
+    listeners.insert(format!("{}:{}", port_name, device_type), ListenerThread {
+        handle,
+        shutdown_sender: shutdown_tx,
+        integration_id,
+        port_name: port_name.to_string(),
+    });
+}
+
 /// Stop all active listeners - used for graceful shutdown
 /// Returns number of threads that were stopped
 pub fn stop_all_listeners() -> usize {
@@ -1147,7 +1297,7 @@ pub fn stop_listen(port_name: &str, device_type: &str) {
 }
 
 /// Handle incoming device data: parse and emit to frontend
-fn handle_device_data(app_handle: &AppHandle, data: &[u8], device_name: &str, device_type: &str) {
+fn handle_device_data(app_handle: &AppHandle, integration_id: i64, data: &[u8], device_name: &str, device_type: &str) {
     log::info!("📥 Received device data - Device: {} ({}), Data size: {} bytes",
         device_name, device_type, data.len());
 
@@ -1181,8 +1331,19 @@ fn handle_device_data(app_handle: &AppHandle, data: &[u8], device_name: &str, de
                     .or_else(|| device_data.test_results.get("sample_id")),
                 device_data.original_file_name);
 
+            // A double end marker or a device resend delivers the same result
+            // twice; emitting both would create duplicate records
+            if is_duplicate_device_data(integration_id, &device_data) {
+                log::debug!("Suppressed duplicate device data from {} ({}), patient {:?}",
+                    device_name, device_type, device_data.patient_identifier);
+                return;
+            }
+
+            // Keep a copy for screens opened after the event fires
+            record_device_data(integration_id, &device_data);
+
             // Emit to frontend
-            match app_handle.emit_all("device-data-received", &device_data) {
+            match app_handle.emit_all(events::DEVICE_DATA_RECEIVED, &device_data) {
                 Ok(_) => {
                     log::info!("📡 Emitted device-data-received event to frontend for {} ({})",
                         device_name, device_type);
@@ -1209,7 +1370,7 @@ fn handle_device_data(app_handle: &AppHandle, data: &[u8], device_name: &str, de
                 };
 
                 let _ = app_handle.emit_all(
-                    "wake-from-tray",
+                    events::WAKE_FROM_TRAY,
                     serde_json::json!({
                         "cause": cause,
                         "code": device_data.patient_identifier.clone().unwrap_or_default(),
@@ -1364,6 +1525,7 @@ fn handle_listening_serial(
 
                 // Write raw bytes to per-port log file for debugging
                 write_raw_log(app_handle, port_name, &buffer[..bytes_read]);
+                tee_raw_bytes(integration_id, &buffer[..bytes_read]);
 
                 if read_count % 100 == 0 {
                     // Demoted to debug because this fires every ~200ms during
@@ -1381,7 +1543,7 @@ fn handle_listening_serial(
                         log::info!("📦 Complete message received from {} ({}) - Size: {} bytes",
                             device_type, port_name, message.len());
                         // Parse and emit device data
-                        handle_device_data(&app_handle, &message, &port_name, &device_type);
+                        handle_device_data(&app_handle, integration_id, &message, &port_name, &device_type);
                     }
                 }
             }
@@ -1,11 +1,28 @@
 use crate::entities::device_integration::{self, Entity as DeviceIntegrationEntity};
 use crate::models::device_integration::{
     DeviceIntegration, CreateDeviceIntegrationInput,
-    UpdateDeviceIntegrationInput, DeviceType, ConnectionType
+    UpdateDeviceIntegrationInput, DeviceType, ConnectionType, ConnectionSettings,
 };
 use crate::models::dto::MaybeNull;
+use crate::services::device_input::{
+    active_listener_integration_ids, get_all_connection_statuses, stop_integration_listeners,
+    DeviceConnectionStatus,
+};
 use chrono::Utc;
 use sea_orm::*;
+use serde::Serialize;
+
+/// A stored integration together with its in-memory listener state, for the
+/// integrations list screen.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceIntegrationWithStatus {
+    #[serde(flatten)]
+    pub integration: DeviceIntegration,
+    /// Latest listener status; `None` if no listener has run since startup
+    pub connection_status: Option<DeviceConnectionStatus>,
+    /// Whether a listener or file watcher is currently running for it
+    pub listener_active: bool,
+}
 
 pub struct DeviceIntegrationService;
 
@@ -31,6 +48,13 @@ impl DeviceIntegrationService {
         })
     }
 
+    /// Validate connection fields, flattening all problems into one message
+    fn validate_settings(settings: &ConnectionSettings, connection_type: &ConnectionType) -> Result<(), String> {
+        settings.validate(connection_type).map_err(|errors| {
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ")
+        })
+    }
+
     /// Get all device integrations
     pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<DeviceIntegration>, String> {
         let models = DeviceIntegrationEntity::find()
@@ -63,18 +87,28 @@ impl DeviceIntegrationService {
         db: &DatabaseConnection,
         input: CreateDeviceIntegrationInput,
     ) -> Result<DeviceIntegration, String> {
+        let settings = ConnectionSettings {
+            watch_directory: input.watch_directory,
+            file_pattern: input.file_pattern,
+            serial_port_name: input.serial_port_name,
+            serial_baud_rate: input.serial_baud_rate,
+            tcp_host: input.tcp_host,
+            tcp_port: input.tcp_port,
+        };
+        Self::validate_settings(&settings, &input.connection_type)?;
+
         let now = Utc::now();
 
         let new_integration = device_integration::ActiveModel {
             name: Set(input.name),
             device_type: Set(input.device_type.to_db_string().to_string()),
             connection_type: Set(input.connection_type.to_db_string().to_string()),
-            watch_directory: Set(input.watch_directory),
-            file_pattern: Set(input.file_pattern),
-            serial_port_name: Set(input.serial_port_name),
-            serial_baud_rate: Set(input.serial_baud_rate),
-            tcp_host: Set(input.tcp_host),
-            tcp_port: Set(input.tcp_port),
+            watch_directory: Set(settings.watch_directory),
+            file_pattern: Set(settings.file_pattern),
+            serial_port_name: Set(settings.serial_port_name),
+            serial_baud_rate: Set(settings.serial_baud_rate),
+            tcp_host: Set(settings.tcp_host),
+            tcp_port: Set(settings.tcp_port),
             enabled: Set(true),
             last_connected_at: Set(None),
             created_at: Set(now),
@@ -104,37 +138,61 @@ impl DeviceIntegrationService {
         let name = input.name.unwrap_or(current.name);
         let connection_type = input.connection_type.unwrap_or(current.connection_type);
 
+        // Stored fields that don't belong to the (possibly new) connection
+        // type are dropped rather than flagged as conflicts, so switching type
+        // or saving a row from before validation existed clears them. Fields
+        // the caller explicitly set are kept and validated below.
+        let mut existing = ConnectionSettings {
+            watch_directory: current.watch_directory,
+            file_pattern: current.file_pattern,
+            serial_port_name: current.serial_port_name,
+            serial_baud_rate: current.serial_baud_rate,
+            tcp_host: current.tcp_host,
+            tcp_port: current.tcp_port,
+        };
+        existing.clear_foreign_fields(&connection_type);
+
         // Handle MaybeNull fields - only update if not Undefined
         let watch_directory = match input.watch_directory {
-            MaybeNull::Undefined => current.watch_directory,
+            MaybeNull::Undefined => existing.watch_directory,
             MaybeNull::Null => None,
             MaybeNull::Value(v) => Some(v),
         };
         // file_pattern stays as Option (clearable)
-        let file_pattern = input.file_pattern.or(current.file_pattern);
+        let file_pattern = input.file_pattern.or(existing.file_pattern);
 
         let serial_port_name = match input.serial_port_name {
-            MaybeNull::Undefined => current.serial_port_name,
+            MaybeNull::Undefined => existing.serial_port_name,
             MaybeNull::Null => None,
             MaybeNull::Value(v) => Some(v),
         };
         let serial_baud_rate = match input.serial_baud_rate {
-            MaybeNull::Undefined => current.serial_baud_rate,
+            MaybeNull::Undefined => existing.serial_baud_rate,
             MaybeNull::Null => None,
             MaybeNull::Value(v) => Some(v),
         };
         let tcp_host = match input.tcp_host {
-            MaybeNull::Undefined => current.tcp_host,
+            MaybeNull::Undefined => existing.tcp_host,
             MaybeNull::Null => None,
             MaybeNull::Value(v) => Some(v),
         };
         let tcp_port = match input.tcp_port {
-            MaybeNull::Undefined => current.tcp_port,
+            MaybeNull::Undefined => existing.tcp_port,
             MaybeNull::Null => None,
             MaybeNull::Value(v) => Some(v),
         };
         let enabled = input.enabled.unwrap_or(current.enabled);
 
+        let settings = ConnectionSettings {
+            watch_directory,
+            file_pattern,
+            serial_port_name,
+            serial_baud_rate,
+            tcp_host,
+            tcp_port,
+        };
+        Self::validate_settings(&settings, &connection_type)?;
+
         // Fetch the entity model for update
         let entity = DeviceIntegrationEntity::find_by_id(id)
             .one(db)
@@ -147,12 +205,12 @@ impl DeviceIntegrationService {
 
         model.name = Set(name);
         model.connection_type = Set(connection_type.to_db_string().to_string());
-        model.watch_directory = Set(watch_directory);
-        model.file_pattern = Set(file_pattern);
-        model.serial_port_name = Set(serial_port_name);
-        model.serial_baud_rate = Set(serial_baud_rate);
-        model.tcp_host = Set(tcp_host);
-        model.tcp_port = Set(tcp_port);
+        model.watch_directory = Set(settings.watch_directory);
+        model.file_pattern = Set(settings.file_pattern);
+        model.serial_port_name = Set(settings.serial_port_name);
+        model.serial_baud_rate = Set(settings.serial_baud_rate);
+        model.tcp_host = Set(settings.tcp_host);
+        model.tcp_port = Set(settings.tcp_port);
         model.enabled = Set(enabled);
         model.updated_at = Set(now);
 
@@ -187,6 +245,12 @@ impl DeviceIntegrationService {
 
     /// Toggle enabled status
     pub async fn toggle_enabled(db: &DatabaseConnection, id: i64) -> Result<DeviceIntegration, String> {
+        let current = Self::get_by_id(db, id).await?;
+        Self::set_enabled(db, id, !current.enabled).await
+    }
+
+    /// Set enabled status
+    pub async fn set_enabled(db: &DatabaseConnection, id: i64, enabled: bool) -> Result<DeviceIntegration, String> {
         let entity = DeviceIntegrationEntity::find_by_id(id)
             .filter(device_integration::Column::DeletedAt.is_null())
             .one(db)
@@ -194,21 +258,55 @@ impl DeviceIntegrationService {
             .map_err(|e| format!("Failed to fetch device integration: {}", e))?
             .ok_or_else(|| "Device integration not found".to_string())?;
 
-        let new_enabled = !entity.enabled;
-        let now = Utc::now();
-
         let mut model: device_integration::ActiveModel = entity.into();
-        model.enabled = Set(new_enabled);
-        model.updated_at = Set(now);
+        model.enabled = Set(enabled);
+        model.updated_at = Set(Utc::now());
 
         model
             .update(db)
             .await
-            .map_err(|e| format!("Failed to toggle enabled status: {}", e))?;
+            .map_err(|e| format!("Failed to update enabled status: {}", e))?;
 
         Self::get_by_id(db, id).await
     }
 
+    /// Enable or disable an integration and bring its serial listener in
+    /// line: disabling stops every listener of the integration, enabling
+    /// starts one through `start`. If the listener can't be started the
+    /// integration is left disabled, so the row never says enabled while
+    /// nothing listens. Other connection types are only updated in the DB.
+    pub async fn set_enabled_with_listener<F>(
+        db: &DatabaseConnection,
+        id: i64,
+        enabled: bool,
+        start: F,
+    ) -> Result<DeviceIntegrationWithStatus, String>
+    where
+        F: FnOnce(&DeviceIntegration, &str) -> Result<(), String>,
+    {
+        let integration = Self::set_enabled(db, id, enabled).await?;
+
+        if integration.connection_type == ConnectionType::SerialPort {
+            match (enabled, integration.serial_port_name.as_deref()) {
+                (true, Some(port_name)) => {
+                    if let Err(e) = start(&integration, port_name) {
+                        Self::set_enabled(db, id, false).await?;
+                        return Err(format!("Failed to start listener, integration left disabled: {}", e));
+                    }
+                }
+                _ => {
+                    stop_integration_listeners(id);
+                }
+            }
+        }
+
+        Ok(Self::with_status(
+            integration,
+            &get_all_connection_statuses(),
+            &active_listener_integration_ids(),
+        ))
+    }
+
     /// Update last_connected_at timestamp
     #[allow(dead_code)]
     pub async fn update_last_connected(db: &DatabaseConnection, id: i64) -> Result<(), String> {
@@ -229,4 +327,39 @@ impl DeviceIntegrationService {
 
         Ok(())
     }
+
+    /// Every non-deleted integration joined with the live state passed in
+    /// (it lives in memory, not the DB): `statuses` from the listeners and
+    /// the ids of integrations with a running listener or watcher.
+    pub async fn get_all_with_status(
+        db: &DatabaseConnection,
+        statuses: Vec<DeviceConnectionStatus>,
+        active_integration_ids: &[i64],
+    ) -> Result<Vec<DeviceIntegrationWithStatus>, String> {
+        let integrations = Self::get_all(db).await?;
+
+        Ok(integrations
+            .into_iter()
+            .map(|integration| Self::with_status(integration, &statuses, active_integration_ids))
+            .collect())
+    }
+
+    fn with_status(
+        integration: DeviceIntegration,
+        statuses: &[DeviceConnectionStatus],
+        active_integration_ids: &[i64],
+    ) -> DeviceIntegrationWithStatus {
+        // A listener restarted on a new port leaves the old port's status
+        // behind; prefer the one for the configured port
+        let connection_status = statuses
+            .iter()
+            .filter(|s| s.integration_id == integration.id)
+            .max_by_key(|s| integration.serial_port_name.as_deref() == Some(s.port_name.as_str()))
+            .cloned();
+        DeviceIntegrationWithStatus {
+            listener_active: active_integration_ids.contains(&integration.id),
+            connection_status,
+            integration,
+        }
+    }
 }
@@ -0,0 +1,178 @@
+//! Stored JSON-path mappings for devices that send JSON and have no
+//! dedicated parser. A mapping tells `DeviceParserService::parse_json_device_data`
+//! where the patient identifier and each result are in the payload, so a
+//! new JSON device is set up here instead of in a new Rust parser.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement};
+
+use crate::models::device_integration::{
+    DeviceParseMapping, DeviceResultPath, DeviceType, SaveDeviceParseMappingInput,
+};
+use crate::services::device_parser::{DeviceData, DeviceParserService};
+
+const MAPPING_COLUMNS: &str =
+    "id, device_type, patient_identifier_path, result_paths, created_at, updated_at";
+
+pub struct DeviceParseMappingService;
+
+impl DeviceParseMappingService {
+    pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<DeviceParseMapping>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!("SELECT {} FROM device_parse_mappings ORDER BY device_type", MAPPING_COLUMNS),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch device parse mappings: {}", e))?;
+        rows.iter().map(Self::row_to_mapping).collect()
+    }
+
+    pub async fn get_by_device_type(
+        db: &DatabaseConnection,
+        device_type: &str,
+    ) -> Result<Option<DeviceParseMapping>, String> {
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT {} FROM device_parse_mappings WHERE device_type = ?", MAPPING_COLUMNS),
+                [device_type.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch device parse mapping: {}", e))?;
+        row.as_ref().map(Self::row_to_mapping).transpose()
+    }
+
+    /// Create or replace the mapping for `input.device_type`. With
+    /// `sample_payload`, every path must also resolve in that payload.
+    pub async fn save(
+        db: &DatabaseConnection,
+        input: SaveDeviceParseMappingInput,
+        sample_payload: Option<&str>,
+    ) -> Result<DeviceParseMapping, String> {
+        let device_type = input.device_type.trim().to_string();
+        let patient_identifier_path = input
+            .patient_identifier_path
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty());
+        let result_paths: Vec<DeviceResultPath> = input
+            .result_paths
+            .into_iter()
+            .map(|entry| DeviceResultPath {
+                result_key: entry.result_key.trim().to_string(),
+                path: entry.path.trim().to_string(),
+            })
+            .collect();
+
+        Self::validate(&device_type, patient_identifier_path.as_deref(), &result_paths)?;
+
+        if let Some(sample) = sample_payload {
+            let payload: serde_json::Value = serde_json::from_str(sample)
+                .map_err(|e| format!("Sample payload is not valid JSON: {}", e))?;
+            let unresolved = DeviceParserService::unresolved_mapping_paths(
+                patient_identifier_path.as_deref(),
+                &result_paths,
+                &payload,
+            )?;
+            if !unresolved.is_empty() {
+                return Err(format!(
+                    "Paths not found in the sample payload: {}",
+                    unresolved.join(", ")
+                ));
+            }
+        }
+
+        let result_paths_json = serde_json::to_string(&result_paths)
+            .map_err(|e| format!("Failed to serialize result paths: {}", e))?;
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO device_parse_mappings (device_type, patient_identifier_path, result_paths) \
+             VALUES (?, ?, ?) \
+             ON CONFLICT(device_type) DO UPDATE SET \
+             patient_identifier_path = excluded.patient_identifier_path, \
+             result_paths = excluded.result_paths",
+            [
+                device_type.clone().into(),
+                patient_identifier_path.into(),
+                result_paths_json.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to save device parse mapping: {}", e))?;
+
+        Self::get_by_device_type(db, &device_type)
+            .await?
+            .ok_or_else(|| "Device parse mapping not found after save".to_string())
+    }
+
+    pub async fn delete(db: &DatabaseConnection, device_type: &str) -> Result<(), String> {
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "DELETE FROM device_parse_mappings WHERE device_type = ?",
+                [device_type.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to delete device parse mapping: {}", e))?;
+        if result.rows_affected() == 0 {
+            return Err(format!("No parse mapping for device type: {}", device_type));
+        }
+        Ok(())
+    }
+
+    /// Parse `payload` with the stored mapping for `device_type`.
+    pub async fn parse_payload(
+        db: &DatabaseConnection,
+        device_type: &str,
+        payload: &[u8],
+        connection_method: &str,
+    ) -> Result<DeviceData, String> {
+        let mapping = Self::get_by_device_type(db, device_type)
+            .await?
+            .ok_or_else(|| format!("No parse mapping for device type: {}", device_type))?;
+        DeviceParserService::parse_json_device_data(&mapping, device_type, "payload", payload, connection_method)
+    }
+
+    fn validate(
+        device_type: &str,
+        patient_identifier_path: Option<&str>,
+        result_paths: &[DeviceResultPath],
+    ) -> Result<(), String> {
+        if device_type.is_empty() {
+            return Err("Device type is required".to_string());
+        }
+        if DeviceType::from_db_string(device_type).is_ok() {
+            return Err(format!("{} already has a built-in parser", device_type));
+        }
+        if result_paths.is_empty() {
+            return Err("A mapping needs at least one result path".to_string());
+        }
+
+        let mut keys = std::collections::HashSet::new();
+        for entry in result_paths {
+            if entry.result_key.is_empty() {
+                return Err(format!("Result path '{}' has no result key", entry.path));
+            }
+            if !keys.insert(entry.result_key.as_str()) {
+                return Err(format!("Result key '{}' is mapped twice", entry.result_key));
+            }
+            DeviceParserService::validate_json_path(&entry.path)?;
+        }
+        if let Some(path) = patient_identifier_path {
+            DeviceParserService::validate_json_path(path)?;
+        }
+        Ok(())
+    }
+
+    fn row_to_mapping(row: &QueryResult) -> Result<DeviceParseMapping, String> {
+        let result_paths: String = row.try_get("", "result_paths").map_err(|e| e.to_string())?;
+        Ok(DeviceParseMapping {
+            id: row.try_get("", "id").map_err(|e| e.to_string())?,
+            device_type: row.try_get("", "device_type").map_err(|e| e.to_string())?,
+            patient_identifier_path: row.try_get("", "patient_identifier_path").ok(),
+            result_paths: serde_json::from_str(&result_paths)
+                .map_err(|e| format!("Invalid stored result paths: {}", e))?,
+            created_at: row.try_get("", "created_at").unwrap_or_default(),
+            updated_at: row.try_get("", "updated_at").unwrap_or_default(),
+        })
+    }
+}
@@ -4,14 +4,60 @@ use std::collections::HashMap;
 use quick_xml::Reader;
 use quick_xml::events::Event;
 
-/// The Exigo analyte value keys the Java PDF renderer knows how to draw
-/// (mirrors `parameterOrder` in PdfGeneratorCLI.parseExigoSample). A parsed
-/// result containing none of these renders a header-only hematology table.
-pub const EXIGO_PDF_ANALYTE_KEYS: [&str; 19] = [
-    "PLT", "MPV", "HGB", "WBC", "LA", "MA", "GA", "LR", "MR", "GR", "EA",
-    "ER", "RBC", "MCV", "HCT", "MCH", "MCHC", "RDWR", "RDWA",
+use crate::models::device_integration::{DeviceParseMapping, DeviceResultPath};
+
+/// One row of a device's result table: the `test_results` key, and the
+/// name and unit the PDF report prints for it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultParameter {
+    pub code: &'static str,
+    pub display_name: &'static str,
+    pub unit: &'static str,
+}
+
+const fn param(code: &'static str, display_name: &'static str, unit: &'static str) -> ResultParameter {
+    ResultParameter { code, display_name, unit }
+}
+
+/// Exigo hematology parameters in the order the PDF report prints them
+/// (mirrors `ExigoParameterEnum` in the Java PDF generator). Reference
+/// ranges aren't fixed; each sample carries them as `<code>_L` / `<code>_H`.
+pub const EXIGO_RESULT_PARAMETERS: [ResultParameter; 19] = [
+    param("PLT", "PLT - тромбоцити", "10e+9/L"),
+    param("MPV", "MPV", "fL"),
+    param("HGB", "HGB - хемоглобин", "g/dL"),
+    param("WBC", "WBC - леукоцити", "10e+9/L"),
+    param("LA", "LA - лимфоцити", "10e+9/L"),
+    param("MA", "MA - моноцити", "10e+9/L"),
+    param("GA", "NEUT - неутрофили", "10e+9/L"),
+    param("LR", "LR - лимфоцити %", "%"),
+    param("MR", "MR - моноцити %", "%"),
+    param("GR", "GR - гранулоцити %", "%"),
+    param("EA", "EA - еозинофили", "10e+9/L"),
+    param("ER", "ER - еозинофили %", "%"),
+    param("RBC", "RBC - еритроцити", "10e+12/L"),
+    param("MCV", "MCV", "fL"),
+    param("HCT", "HCT - хематокрит", "%"),
+    param("MCH", "MCH", "pg"),
+    param("MCHC", "MCHC", "g/dL"),
+    param("RDWR", "RDW %", "%"),
+    param("RDWA", "RDW", "fL"),
 ];
 
+/// The Exigo analyte value keys the Java PDF renderer knows how to draw. A
+/// parsed result containing none of these renders a header-only
+/// hematology table.
+pub const EXIGO_PDF_ANALYTE_KEYS: [&str; 19] = {
+    let mut keys = [""; 19];
+    let mut i = 0;
+    while i < keys.len() {
+        keys[i] = EXIGO_RESULT_PARAMETERS[i].code;
+        i += 1;
+    }
+    keys
+};
+
 /// Standardized device data structure that will be sent to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +73,21 @@ pub struct DeviceData {
     pub detected_at: DateTime<Utc>,
 }
 
+/// Which of a device's expected parameters a parsed payload carried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicePayloadValidation {
+    pub device_type: String,
+    /// Expected parameters found in the payload, in expected order.
+    pub present: Vec<String>,
+    /// Expected parameters the payload did not carry, in expected order.
+    pub missing: Vec<String>,
+    /// Result keys outside the expected list (sorted). Range and flag keys
+    /// (`<code>_L` / `<code>_H` / `<code>_F`) of expected parameters are
+    /// not counted.
+    pub extra: Vec<String>,
+}
+
 pub struct DeviceParserService;
 
 impl DeviceParserService {
@@ -699,6 +760,231 @@ impl DeviceParserService {
             _ => Err(format!("Unknown device type: {}", device_type)),
         }
     }
+
+    /// Parameters a complete result from `device_type` must contain, or
+    /// None when we have no fixed list for it (the chemistry and PCR
+    /// analyzers report whatever panel was run).
+    pub fn required_parameters(device_type: &str) -> Option<&'static [&'static str]> {
+        match device_type {
+            "exigo_eos_vet" => Some(&EXIGO_PDF_ANALYTE_KEYS),
+            _ => None,
+        }
+    }
+
+    /// The parameters of `device_type`'s results in report order, for
+    /// rendering them as a table. Only devices with a fixed panel have one.
+    pub fn result_schema(device_type: &str) -> Result<&'static [ResultParameter], String> {
+        match device_type {
+            "exigo_eos_vet" => Ok(&EXIGO_RESULT_PARAMETERS),
+            _ => Err(format!(
+                "No result schema for device type '{}'; its results list whatever panel was run",
+                device_type
+            )),
+        }
+    }
+
+    /// Parse `raw_bytes` as `device_type` would send them and compare the
+    /// resulting `test_results` against the device's required parameters.
+    pub fn validate_device_payload(
+        device_type: &str,
+        raw_bytes: &[u8],
+        connection_method: &str,
+    ) -> Result<DevicePayloadValidation, String> {
+        let required = Self::required_parameters(device_type)
+            .ok_or_else(|| format!("No expected parameter list for device type: {}", device_type))?;
+
+        let data = Self::parse_device_data(device_type, device_type, "payload", raw_bytes, connection_method)?;
+        let results = data
+            .test_results
+            .as_object()
+            .ok_or("Parsed test results are not an object")?;
+
+        let (present, missing): (Vec<&str>, Vec<&str>) =
+            required.iter().copied().partition(|code| results.contains_key(*code));
+
+        let is_expected = |key: &str| {
+            let code = key
+                .strip_suffix("_L")
+                .or_else(|| key.strip_suffix("_H"))
+                .or_else(|| key.strip_suffix("_F"))
+                .unwrap_or(key);
+            required.contains(&key) || required.contains(&code)
+        };
+        let mut extra: Vec<String> = results
+            .keys()
+            .filter(|key| !is_expected(key.as_str()))
+            .cloned()
+            .collect();
+        extra.sort();
+
+        Ok(DevicePayloadValidation {
+            device_type: device_type.to_string(),
+            present: present.into_iter().map(String::from).collect(),
+            missing: missing.into_iter().map(String::from).collect(),
+            extra,
+        })
+    }
+
+    /// Parse the JSON payload of a device onboarded through a
+    /// `DeviceParseMapping` instead of a dedicated parser. Mapped paths the
+    /// payload doesn't have are left out of `test_results`, so a partial
+    /// result still comes through.
+    pub fn parse_json_device_data(
+        mapping: &DeviceParseMapping,
+        device_name: &str,
+        file_name: &str,
+        file_data: &[u8],
+        connection_method: &str,
+    ) -> Result<DeviceData, String> {
+        let payload: serde_json::Value = serde_json::from_slice(file_data)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        let patient_identifier = match mapping.patient_identifier_path.as_deref() {
+            Some(path) => lookup_json_path(&payload, path)?.and_then(json_scalar_to_string),
+            None => None,
+        };
+
+        let mut test_results = serde_json::Map::new();
+        for entry in &mapping.result_paths {
+            match lookup_json_path(&payload, &entry.path)? {
+                Some(value) if !value.is_null() => {
+                    test_results.insert(entry.result_key.clone(), value.clone());
+                }
+                _ => log::debug!(
+                    "{}: no value at {} for {}",
+                    mapping.device_type, entry.path, entry.result_key
+                ),
+            }
+        }
+
+        Ok(DeviceData {
+            device_type: mapping.device_type.clone(),
+            device_name: device_name.to_string(),
+            connection_method: connection_method.to_string(),
+            patient_identifier,
+            test_results: serde_json::Value::Object(test_results),
+            original_file_name: file_name.to_string(),
+            file_data: file_data.to_vec(),
+            mime_type: "application/json".to_string(),
+            detected_at: Utc::now(),
+        })
+    }
+
+    /// Check that `path` is a path `parse_json_device_data` can follow:
+    /// dot-separated keys, `[n]` array indexes and `[field=value]` to pick
+    /// the array element whose `field` is `value`. A leading `$.` is allowed.
+    pub fn validate_json_path(path: &str) -> Result<(), String> {
+        parse_json_path(path).map(|_| ())
+    }
+
+    /// Paths of a mapping (patient identifier first) that `payload` has no
+    /// value at.
+    pub fn unresolved_mapping_paths(
+        patient_identifier_path: Option<&str>,
+        result_paths: &[DeviceResultPath],
+        payload: &serde_json::Value,
+    ) -> Result<Vec<String>, String> {
+        let mut unresolved = Vec::new();
+        for path in patient_identifier_path
+            .into_iter()
+            .chain(result_paths.iter().map(|entry| entry.path.as_str()))
+        {
+            if !lookup_json_path(payload, path)?.is_some_and(|v| !v.is_null()) {
+                unresolved.push(path.to_string());
+            }
+        }
+        Ok(unresolved)
+    }
+}
+
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+    /// First array element whose `field` equals `value`
+    Match { field: String, value: String },
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<JsonPathSegment>, String> {
+    let trimmed = path.trim();
+    let mut rest = trimmed.strip_prefix('$').map_or(trimmed, |r| r.strip_prefix('.').unwrap_or(r));
+    if rest.is_empty() {
+        return Err(format!("Empty JSON path: '{}'", path));
+    }
+
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after
+                .find(']')
+                .ok_or_else(|| format!("Unclosed '[' in JSON path: '{}'", path))?;
+            let inner = after[..end].trim();
+            segments.push(match inner.split_once('=') {
+                Some((field, value)) if !field.trim().is_empty() => JsonPathSegment::Match {
+                    field: field.trim().to_string(),
+                    value: value.trim().trim_matches(|c| c == '\'' || c == '"').to_string(),
+                },
+                Some(_) => return Err(format!("Missing field name in JSON path: '{}'", path)),
+                None => JsonPathSegment::Index(
+                    inner
+                        .parse()
+                        .map_err(|_| format!("Invalid array index '{}' in JSON path: '{}'", inner, path))?,
+                ),
+            });
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(format!("Empty key in JSON path: '{}'", path));
+            }
+            segments.push(JsonPathSegment::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            if after_dot.is_empty() || after_dot.starts_with('.') {
+                return Err(format!("Empty key in JSON path: '{}'", path));
+            }
+            rest = after_dot;
+        }
+    }
+    Ok(segments)
+}
+
+/// Value at `path`, or None when the payload doesn't have it. Only a
+/// malformed path is an error.
+fn lookup_json_path<'a>(
+    payload: &'a serde_json::Value,
+    path: &str,
+) -> Result<Option<&'a serde_json::Value>, String> {
+    let mut current = payload;
+    for segment in parse_json_path(path)? {
+        let next = match segment {
+            JsonPathSegment::Key(key) => current.get(key.as_str()),
+            JsonPathSegment::Index(index) => current.get(index),
+            JsonPathSegment::Match { field, value } => current.as_array().and_then(|items| {
+                items.iter().find(|item| {
+                    item.get(field.as_str())
+                        .and_then(json_scalar_to_string)
+                        .is_some_and(|v| v == value)
+                })
+            }),
+        };
+        match next {
+            Some(value) => current = value,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(current))
+}
+
+fn json_scalar_to_string(value: &serde_json::Value) -> Option<String> {
+    let text = match value {
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
 }
 
 #[cfg(test)]
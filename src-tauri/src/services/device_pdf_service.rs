@@ -1,7 +1,42 @@
 use chrono::{DateTime, Utc};
 use serde_json::Value;
+use std::path::PathBuf;
+use crate::services::device_parser::DeviceParserService;
 use crate::services::java_pdf_service::JavaPdfService;
 
+/// Synthetic raw payloads for `DevicePdfService::sample_report_data`, in each
+/// device's on-the-wire format so they go through the real parsers.
+const SAMPLE_EXIGO_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<samples SNO="10001">
+<sample PRDI="BM800" SNO="10001" SEQ="1" DATE="2026-01-15 10:30:00" APNA="DOG" ID2="SAMPLE"
+        RBC="7.10" RBC_L="5.50" RBC_H="8.50" MCV="67.2" MCV_L="60.0" MCV_H="72.0" HCT="46.1" HCT_L="37.0" HCT_H="55.0" MCH="25.2" MCH_L="19.5" MCH_H="24.5" MCHC="37.7" MCHC_L="32.0" MCHC_H="38.5" RDWR="14.2" RDWR_L="12.0" RDWR_H="17.5" RDWA="46.2" RDWA_L="35.0" RDWA_H="55.0"
+        PLT="285" PLT_L="200" PLT_H="500" MPV="5.5" MPV_L="5.5" MPV_H="10.5" HGB="17.4" HGB_L="12.0" HGB_H="18.0"
+        WBC="10.4" WBC_L="6.0" WBC_H="17.0" LA="2.3" LA_L="0.9" LA_H="9.9" MA="0.9" MA_L="0.3" MA_H="9.9"
+        GA="3.5" GA_L="3.5" GA_H="12.0" LR="21.5" LR_L="0.0" LR_H="99.9" MR="7.4" MR_L="0.0" MR_H="99.9"
+        GR="71.1" GR_L="0.0" GR_H="99.9" EA="0.2" EA_L="0.1" EA_H="1.5" ER="1.9" ER_L="0.1" ER_H="99.9" />
+</samples>
+"#;
+
+const SAMPLE_POINTCARE_HL7: &str = "MSH|^~\\&|MNCHIP|LAB|||20260115103000||ORU^R01|1|P|2.5\r\
+PID|1||SAMPLE||DOG|Sample||M\r\
+OBR|1||SAMPLE|||20260115103000\r\
+OBX|1|NM||ALB|32.1|g/L|23-40|N\r\
+OBX|2|NM||ALT|45|U/L|10-118|N\r\
+OBX|3|NM||BUN|6.8|mmol/L|2.5-9.6|N\r\
+OBX|4|NM||CRE|98|umol/L|44-159|N\r\
+OBX|5|NM||GLU|5.6|mmol/L|4.1-7.9|N\r\
+OBX|6|NM||TP|68|g/L|52-82|N\r\r";
+
+const SAMPLE_PCR_HL7: &str = "MSH|^~\\&|1|PointcarePCRV|||20260115103000|2|ORU^R01|1|P|2.3.1\r\
+PID|1||SAMPLE||DOG|Sample||M\r\
+OBR|1||SAMPLE|1^1|||20260115103000\r\
+OBX|1|ST||CPV|NoCt||>36 or NoCt|Negative(-)|||Whole blood\r\
+OBX|2|ST||IC|31.20||>36 or NoCt|Positive(+)|||Whole blood\r\r";
+
+const SAMPLE_HEALVET_SERIAL: &str = "#AFS1000&&15.5&2026-01-15 10:30:00&&T4-1&Sample&&Male&Serum\
+#AFS1000&&0.8&2026-01-15 10:30:00&&TSH-1&Sample&&Male&Serum\
+#AFS1000&&5.2&2026-01-15 10:30:00&&cCRP&Sample&&Male&SerumEE";
+
 /// Patient information for PDF generation
 #[derive(Debug, Clone)]
 pub struct PatientData {
@@ -47,4 +82,87 @@ impl DevicePdfService {
         // - Professional styling with colors
         JavaPdfService::generate_pdf(app_handle, output_path, &patient, &device_data)
     }
+
+    /// Synthetic patient + results for a test render of `device_type`'s report.
+    /// The device results are parsed from canned raw payloads, so the report
+    /// sees the same keys a real capture would. `language` ("en" / "mk")
+    /// picks the patient details; "mk" uses Cyrillic so font embedding is
+    /// exercised.
+    pub fn sample_report_data(
+        device_type: &str,
+        language: &str,
+    ) -> Result<(PatientData, DeviceTestData), String> {
+        let (device_name, file_name, payload) = match device_type {
+            "exigo_eos_vet" => ("Exigo Eos Vet", "sample_report.xml", SAMPLE_EXIGO_XML),
+            "mnchip_pointcare_chemistry" => ("MNCHIP PointCare", "sample_report.hl7", SAMPLE_POINTCARE_HL7),
+            "mnchip_pcr_analyzer" => ("MNCHIP PCR", "sample_report.hl7", SAMPLE_PCR_HL7),
+            "healvet_hv_fia_3000" => ("Healvet HV-FIA 3000", "sample_report.txt", SAMPLE_HEALVET_SERIAL),
+            _ => return Err(format!("Unknown device type: {}", device_type)),
+        };
+
+        let patient = match language {
+            "en" => PatientData {
+                name: "Sample Patient".to_string(),
+                owner: "Sample Owner".to_string(),
+                species: "Dog".to_string(),
+                microchip_id: Some("900000000000001".to_string()),
+                gender: "Male".to_string(),
+                date_of_birth: Some("2020-05-01".to_string()),
+            },
+            "mk" => PatientData {
+                name: "Пробен Пациент".to_string(),
+                owner: "Пробен Сопственик".to_string(),
+                species: "Куче".to_string(),
+                microchip_id: Some("900000000000001".to_string()),
+                gender: "Машко".to_string(),
+                date_of_birth: Some("2020-05-01".to_string()),
+            },
+            _ => return Err(format!("Invalid language: {}. Must be 'en' or 'mk'", language)),
+        };
+
+        let parsed = DeviceParserService::parse_device_data(
+            device_type,
+            device_name,
+            file_name,
+            payload.as_bytes(),
+            "sample",
+        )?;
+
+        let device_data = DeviceTestData {
+            device_type: parsed.device_type,
+            device_name: parsed.device_name,
+            test_results: parsed.test_results,
+            detected_at: Utc::now(),
+            patient_identifier: parsed.patient_identifier,
+        };
+
+        Ok((patient, device_data))
+    }
+
+    /// Render a report for `device_type` from synthetic data into the temp
+    /// directory and return its path. Used to check fonts, the logo and
+    /// layout without needing a real capture.
+    pub fn render_sample_report(
+        app_handle: &tauri::AppHandle,
+        device_type: &str,
+        language: &str,
+    ) -> Result<PathBuf, String> {
+        let (patient, device_data) = Self::sample_report_data(device_type, language)?;
+
+        let output_path = std::env::temp_dir().join(format!(
+            "sample_report_{}_{}_{}.pdf",
+            device_type,
+            language,
+            Utc::now().timestamp_millis()
+        ));
+
+        Self::generate_pdf(
+            app_handle,
+            output_path.to_str().ok_or("Invalid PDF path")?,
+            patient,
+            device_data,
+        )?;
+
+        Ok(output_path)
+    }
 }
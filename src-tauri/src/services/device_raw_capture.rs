@@ -0,0 +1,258 @@
+//! Opt-in capture of the raw bytes a serial listener receives, for
+//! diagnosing a device that won't parse (framing or baud mismatches)
+//! without external tools.
+//!
+//! While a capture runs for an integration, every read from its listener
+//! is teed into a bounded in-memory buffer that keeps the newest
+//! `max_bytes`. `get_raw_capture` returns it as a hex dump. A capture stops
+//! recording by itself after `RAW_CAPTURE_TTL` so a forgotten one doesn't
+//! keep buffering; its bytes stay readable until it is stopped or a new one
+//! starts. Nothing is persisted; the dated `raw_serial_logs` files are the
+//! durable record.
+//!
+//! Only available in debug builds or with `ARKIVET_DEVICE_DEBUG=1` set.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Used when `start_raw_capture` is called without `max_bytes`.
+pub const RAW_CAPTURE_DEFAULT_BYTES: usize = 4 * 1024;
+
+/// Upper bound for `max_bytes`.
+pub const RAW_CAPTURE_MAX_BYTES: usize = 64 * 1024;
+
+/// How long a capture records before it stops by itself.
+pub const RAW_CAPTURE_TTL: Duration = Duration::minutes(10);
+
+/// Bytes per line of the hex dump.
+const HEX_DUMP_WIDTH: usize = 16;
+
+static RAW_CAPTURES: OnceLock<Mutex<RawCaptureBuffer>> = OnceLock::new();
+
+fn get_raw_captures() -> &'static Mutex<RawCaptureBuffer> {
+    RAW_CAPTURES.get_or_init(|| Mutex::new(RawCaptureBuffer::default()))
+}
+
+/// Whether raw capture may be started: debug builds, or release builds
+/// with `ARKIVET_DEVICE_DEBUG` set to `1` / `true`.
+pub fn raw_capture_enabled() -> bool {
+    cfg!(debug_assertions)
+        || std::env::var("ARKIVET_DEVICE_DEBUG")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+}
+
+/// A capture as returned to the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawCapture {
+    pub integration_id: i64,
+    pub max_bytes: usize,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// False once the capture expired; nothing more is recorded
+    pub active: bool,
+    /// Everything received while the capture was active
+    pub bytes_received: u64,
+    /// Oldest bytes dropped to stay within `max_bytes`
+    pub bytes_dropped: u64,
+    /// `offset  hex bytes  |ascii|`, 16 bytes per line
+    pub hex_dump: String,
+}
+
+struct Capture {
+    max_bytes: usize,
+    started_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    data: VecDeque<u8>,
+    bytes_received: u64,
+}
+
+impl Capture {
+    fn snapshot(&self, integration_id: i64, now: DateTime<Utc>) -> RawCapture {
+        let data: Vec<u8> = self.data.iter().copied().collect();
+        RawCapture {
+            integration_id,
+            max_bytes: self.max_bytes,
+            started_at: self.started_at,
+            expires_at: self.expires_at,
+            active: now < self.expires_at,
+            bytes_received: self.bytes_received,
+            bytes_dropped: self.bytes_received - data.len() as u64,
+            hex_dump: hex_dump(&data),
+        }
+    }
+}
+
+/// Running captures, one per integration.
+#[derive(Default)]
+pub struct RawCaptureBuffer {
+    captures: HashMap<i64, Capture>,
+}
+
+impl RawCaptureBuffer {
+    /// Start (or restart, dropping what was captured) a capture keeping the
+    /// newest `max_bytes`, recording until `now + ttl`.
+    pub fn start(&mut self, integration_id: i64, max_bytes: usize, now: DateTime<Utc>, ttl: Duration) -> RawCapture {
+        let max_bytes = max_bytes.clamp(1, RAW_CAPTURE_MAX_BYTES);
+        let capture = Capture {
+            max_bytes,
+            started_at: now,
+            expires_at: now + ttl,
+            data: VecDeque::with_capacity(max_bytes),
+            bytes_received: 0,
+        };
+        let snapshot = capture.snapshot(integration_id, now);
+        self.captures.insert(integration_id, capture);
+        snapshot
+    }
+
+    /// Record bytes read by one of the integration's listeners. A no-op
+    /// without an active capture.
+    pub fn tee(&mut self, integration_id: i64, bytes: &[u8], now: DateTime<Utc>) {
+        let Some(capture) = self.captures.get_mut(&integration_id) else {
+            return;
+        };
+        if now >= capture.expires_at {
+            return;
+        }
+        capture.bytes_received += bytes.len() as u64;
+        // Only the tail of an oversized read can survive
+        let keep = &bytes[bytes.len().saturating_sub(capture.max_bytes)..];
+        let overflow = (capture.data.len() + keep.len()).saturating_sub(capture.max_bytes);
+        capture.data.drain(..overflow);
+        capture.data.extend(keep);
+    }
+
+    pub fn get(&self, integration_id: i64, now: DateTime<Utc>) -> Option<RawCapture> {
+        self.captures
+            .get(&integration_id)
+            .map(|capture| capture.snapshot(integration_id, now))
+    }
+
+    /// Remove the capture, returning what it held.
+    pub fn stop(&mut self, integration_id: i64, now: DateTime<Utc>) -> Option<RawCapture> {
+        self.captures
+            .remove(&integration_id)
+            .map(|capture| capture.snapshot(integration_id, now))
+    }
+}
+
+/// Classic hex dump: offset, up to 16 hex bytes, then the printable ASCII
+/// (`.` for anything else), one line per 16 bytes.
+pub fn hex_dump(data: &[u8]) -> String {
+    data.chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let hex = chunk.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if (0x20..=0x7E).contains(&b) { b as char } else { '.' })
+                .collect();
+            format!("{:08X}  {:<width$}  |{}|", line * HEX_DUMP_WIDTH, hex, ascii, width = HEX_DUMP_WIDTH * 3 - 1)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Start capturing an integration's raw bytes. The caller checks that the
+/// integration has a running listener.
+pub fn start_raw_capture(integration_id: i64, max_bytes: usize) -> Result<RawCapture, String> {
+    if !raw_capture_enabled() {
+        return Err("Raw capture is disabled; set ARKIVET_DEVICE_DEBUG=1 and restart to enable it".to_string());
+    }
+    Ok(get_raw_captures()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .start(integration_id, max_bytes, Utc::now(), RAW_CAPTURE_TTL))
+}
+
+/// Called by the serial read loop with every chunk it reads.
+pub fn tee_raw_bytes(integration_id: i64, bytes: &[u8]) {
+    get_raw_captures()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .tee(integration_id, bytes, Utc::now());
+}
+
+pub fn get_raw_capture(integration_id: i64) -> Option<RawCapture> {
+    get_raw_captures()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(integration_id, Utc::now())
+}
+
+pub fn stop_raw_capture(integration_id: i64) -> Option<RawCapture> {
+    get_raw_captures()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .stop(integration_id, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// Read `port` in `chunk`-sized reads like the serial loop does, teeing
+    /// each read into the buffer.
+    fn pump(buffer: &mut RawCaptureBuffer, integration_id: i64, mut port: impl Read, chunk: usize, now: DateTime<Utc>) {
+        let mut read_buf = vec![0u8; chunk];
+        loop {
+            let n = port.read(&mut read_buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            buffer.tee(integration_id, &read_buf[..n], now);
+        }
+    }
+
+    fn captured_bytes(buffer: &RawCaptureBuffer, integration_id: i64) -> Vec<u8> {
+        buffer.captures[&integration_id].data.iter().copied().collect()
+    }
+
+    #[test]
+    fn captured_bytes_match_what_the_port_received() {
+        let now = Utc::now();
+        let frame: Vec<u8> = [&[0x0B][..], b"MSH|^~\\&|PointCare", &[0x1C, 0x0D]].concat();
+        let mut buffer = RawCaptureBuffer::default();
+        buffer.start(1, 1024, now, RAW_CAPTURE_TTL);
+
+        pump(&mut buffer, 1, std::io::Cursor::new(frame.clone()), 5, now);
+        pump(&mut buffer, 2, std::io::Cursor::new(b"other device".to_vec()), 5, now);
+
+        assert_eq!(captured_bytes(&buffer, 1), frame);
+        let capture = buffer.get(1, now).unwrap();
+        assert!(capture.active);
+        assert_eq!((capture.bytes_received, capture.bytes_dropped), (frame.len() as u64, 0));
+        assert_eq!(
+            capture.hex_dump,
+            "00000000  0B 4D 53 48 7C 5E 7E 5C 26 7C 50 6F 69 6E 74 43  |.MSH|^~\\&|PointC|\n\
+             00000010  61 72 65 1C 0D                                   |are..|"
+        );
+        assert!(buffer.get(2, now).is_none(), "integration 2 wasn't capturing");
+    }
+
+    #[test]
+    fn keeps_the_newest_bytes_and_stops_recording_when_expired() {
+        let now = Utc::now();
+        let mut buffer = RawCaptureBuffer::default();
+        buffer.start(1, 8, now, Duration::seconds(60));
+
+        pump(&mut buffer, 1, std::io::Cursor::new(b"0123456789ABCDEF".to_vec()), 3, now);
+        assert_eq!(captured_bytes(&buffer, 1), b"89ABCDEF");
+        assert_eq!(buffer.get(1, now).unwrap().bytes_dropped, 8);
+
+        let later = now + Duration::seconds(61);
+        buffer.tee(1, b"late", later);
+        let expired = buffer.get(1, later).unwrap();
+        assert!(!expired.active);
+        assert_eq!(expired.bytes_received, 16, "nothing recorded after expiry");
+
+        assert!(buffer.stop(1, later).is_some());
+        assert!(buffer.get(1, later).is_none());
+    }
+}
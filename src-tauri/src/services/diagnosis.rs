@@ -241,6 +241,71 @@ impl DiagnosisService {
         Ok(())
     }
 
+    /// Hard delete even while records link to the diagnosis. With
+    /// `reassign_to` the links move to that diagnosis (a record that
+    /// already has it keeps a single link); otherwise they're removed.
+    /// Returns how many record links were moved or removed. All in one
+    /// transaction, so a failure leaves both the links and the row.
+    pub async fn force_delete(
+        pool: &SeaOrmPool,
+        id: i64,
+        reassign_to: Option<i64>,
+    ) -> Result<u64, String> {
+        Self::get_by_id(pool, id).await?;
+        if let Some(target) = reassign_to {
+            if target == id {
+                return Err("Cannot reassign a diagnosis to itself".into());
+            }
+            Self::get_by_id(pool, target).await?;
+        }
+
+        let txn = pool
+            .begin()
+            .await
+            .map_err(|e| format!("force_delete_diagnosis begin: {}", e))?;
+
+        if let Some(target) = reassign_to {
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT OR IGNORE INTO medical_record_diagnoses (medical_record_id, diagnosis_id) \
+                 SELECT medical_record_id, ? FROM medical_record_diagnoses WHERE diagnosis_id = ?",
+                vec![target.into(), id.into()],
+            ))
+            .await
+            .map_err(|e| format!("force_delete_diagnosis reassign: {}", e))?;
+        }
+
+        let links = txn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "DELETE FROM medical_record_diagnoses WHERE diagnosis_id = ?",
+                vec![id.into()],
+            ))
+            .await
+            .map_err(|e| format!("force_delete_diagnosis unlink: {}", e))?
+            .rows_affected();
+
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM diagnoses WHERE id = ?",
+            vec![id.into()],
+        ))
+        .await
+        .map_err(|e| format!("force_delete_diagnosis: {}", e))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("force_delete_diagnosis commit: {}", e))?;
+
+        log::info!(
+            "force_delete_diagnosis: id={} links={} reassign_to={:?}",
+            id,
+            links,
+            reassign_to,
+        );
+        Ok(links)
+    }
+
     // ---------------------------------------------------------------
     // medical_record_diagnoses junction-table helpers
     // ---------------------------------------------------------------
@@ -350,6 +415,83 @@ impl DiagnosisService {
 
         Ok(())
     }
+
+    /// Apply `diagnosis_id` to every record in `medical_record_ids` in
+    /// one transaction. Records that already have it are left alone;
+    /// returns how many links were added. An unknown record id rolls
+    /// the whole batch back. Inactive diagnoses can't be applied, the
+    /// same as in the picker.
+    pub async fn add_to_records(
+        pool: &SeaOrmPool,
+        diagnosis_id: i64,
+        medical_record_ids: &[i64],
+    ) -> Result<u64, String> {
+        let diagnosis = Self::get_by_id(pool, diagnosis_id).await?;
+        if !diagnosis.is_active {
+            return Err("Cannot apply an inactive diagnosis".into());
+        }
+
+        let txn = pool
+            .begin()
+            .await
+            .map_err(|e| format!("add_diagnosis_to_records begin: {}", e))?;
+
+        let mut added = 0;
+        for medical_record_id in medical_record_ids {
+            // OR IGNORE skips existing links only; a missing record
+            // still fails the FK check
+            added += txn
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "INSERT OR IGNORE INTO medical_record_diagnoses (medical_record_id, diagnosis_id) VALUES (?, ?)",
+                    vec![(*medical_record_id).into(), diagnosis_id.into()],
+                ))
+                .await
+                .map_err(|e| format!(
+                    "add_diagnosis_to_records insert (record={}, diagnosis={}): {}",
+                    medical_record_id, diagnosis_id, e,
+                ))?
+                .rows_affected();
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("add_diagnosis_to_records commit: {}", e))?;
+
+        Ok(added)
+    }
+
+    /// Remove `diagnosis_id` from every record in `medical_record_ids`.
+    /// Returns how many links were removed; records without it are
+    /// skipped.
+    pub async fn remove_from_records(
+        pool: &SeaOrmPool,
+        diagnosis_id: i64,
+        medical_record_ids: &[i64],
+    ) -> Result<u64, String> {
+        if medical_record_ids.is_empty() {
+            return Ok(0);
+        }
+
+        // A single statement, so the batch is applied atomically
+        let placeholders = vec!["?"; medical_record_ids.len()].join(", ");
+        let mut values: Vec<sea_orm::Value> = vec![diagnosis_id.into()];
+        values.extend(medical_record_ids.iter().map(|id| sea_orm::Value::from(*id)));
+
+        let res = pool
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "DELETE FROM medical_record_diagnoses WHERE diagnosis_id = ? AND medical_record_id IN ({})",
+                    placeholders,
+                ),
+                values,
+            ))
+            .await
+            .map_err(|e| format!("remove_diagnosis_from_records: {}", e))?;
+
+        Ok(res.rows_affected())
+    }
 }
 
 fn row_to_model(row: &QueryResult) -> Result<Diagnosis, String> {
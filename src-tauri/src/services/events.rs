@@ -0,0 +1,161 @@
+//! Names of the events the backend emits to the frontend. Every
+//! `emit_all` goes through one of these constants so an emitter and its
+//! `listen(...)` on the frontend can't drift apart, and
+//! `list_event_channels` serves the catalog with an example payload each.
+//! Code that should be testable without a window emits through
+//! `EventSink` instead of calling `emit_all` itself.
+//!
+//! Examples are built from the payload types themselves where there is
+//! one, so a renamed field shows up in the catalog too.
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::models::household::HouseholdSearchReindexProgress;
+use crate::models::medical::{AttachmentOcrResult, ThumbnailPrewarmProgress};
+use crate::services::device_input::{ConnectionState, DeviceConnectionStatus};
+use crate::services::device_parser::DeviceData;
+use crate::services::file_watcher::{FileWatcherState, FileWatcherStatus};
+
+/// A device result was parsed (serial, file watcher or HL7).
+pub const DEVICE_DATA_RECEIVED: &str = "device-data-received";
+/// A serial device connected, disconnected or is retrying.
+pub const DEVICE_CONNECTION_STATUS: &str = "device-connection-status";
+/// A file watcher started, stopped, failed or processed a file.
+pub const FILE_WATCHER_STATUS: &str = "file-watcher-status";
+/// A microchip or barcode was scanned on a HID scanner.
+pub const SCANNER_BARCODE: &str = "scanner:barcode";
+/// The window was brought back from the tray, by the user or by device input.
+pub const WAKE_FROM_TRAY: &str = "wake-from-tray";
+/// OCR of an uploaded attachment finished or failed.
+pub const ATTACHMENT_OCR_COMPLETE: &str = "attachment-ocr-complete";
+/// A PDF attachment's thumbnail was prewarmed, found cached, or failed.
+pub const ATTACHMENT_THUMBNAILS_PROGRESS: &str = "attachment-thumbnails-progress";
+/// A full household search index rebuild finished a batch.
+pub const HOUSEHOLD_SEARCH_REINDEX_PROGRESS: &str = "household-search-reindex-progress";
+
+/// Where an event goes: the frontend through an `AppHandle`, or a recorder
+/// in tests that check what would have been sent.
+pub trait EventSink {
+    fn emit_event<S: Serialize + Clone>(&self, event: &'static str, payload: S) -> tauri::Result<()>;
+}
+
+impl<R: Runtime> EventSink for AppHandle<R> {
+    fn emit_event<S: Serialize + Clone>(&self, event: &'static str, payload: S) -> tauri::Result<()> {
+        // `event` is the constant the caller passed to `emit_event`
+        self.emit_all(event, payload)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventChannel {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub payload_example: Value,
+}
+
+/// Every event the backend emits.
+pub fn event_channels() -> Vec<EventChannel> {
+    let device_data = DeviceData {
+        device_type: "exigo_eos_vet".to_string(),
+        device_name: "Exigo Eos Vet".to_string(),
+        connection_method: "file_watch".to_string(),
+        patient_identifier: Some("941000012345678".to_string()),
+        test_results: json!({ "WBC": "7.2" }),
+        original_file_name: "result.xml".to_string(),
+        file_data: Vec::new(),
+        mime_type: "application/xml".to_string(),
+        detected_at: Utc::now(),
+    };
+    let connection_status = DeviceConnectionStatus {
+        integration_id: 1,
+        port_name: "COM3".to_string(),
+        device_type: "healvet_hv_fia_3000".to_string(),
+        status: ConnectionState::Connected,
+        last_connected: Some(Utc::now().to_rfc3339()),
+        last_error: None,
+        retry_count: 0,
+        next_retry: None,
+    };
+    let watcher_status = FileWatcherStatus {
+        integration_id: 2,
+        name: "Exigo".to_string(),
+        watch_directory: "C:\\Exigo\\Export".to_string(),
+        status: FileWatcherState::Watching,
+        last_error: None,
+        files_processed: 0,
+    };
+    let ocr_result = AttachmentOcrResult {
+        attachment_id: 1,
+        indexed: true,
+        error: None,
+    };
+    let thumbnail_progress = ThumbnailPrewarmProgress {
+        attachment_id: 1,
+        completed: 1,
+        total: 3,
+        cached: false,
+        error: None,
+    };
+    let reindex_progress = HouseholdSearchReindexProgress { indexed: 200, total: 450 };
+
+    vec![
+        EventChannel {
+            name: DEVICE_DATA_RECEIVED,
+            description: "A device result was parsed (serial, file watcher or HL7)",
+            payload_example: to_example(&device_data),
+        },
+        EventChannel {
+            name: DEVICE_CONNECTION_STATUS,
+            description: "A serial device connected, disconnected or is retrying",
+            payload_example: to_example(&connection_status),
+        },
+        EventChannel {
+            name: FILE_WATCHER_STATUS,
+            description: "A file watcher started, stopped, failed or processed a file",
+            payload_example: to_example(&watcher_status),
+        },
+        EventChannel {
+            name: SCANNER_BARCODE,
+            description: "A microchip or barcode was scanned; source is \"hid\" or \"raw_input\"",
+            payload_example: json!({
+                "code": "941000012345678",
+                "device": "managed-hid",
+                "length": 15,
+                "source": "hid",
+            }),
+        },
+        EventChannel {
+            name: WAKE_FROM_TRAY,
+            description: "The window came back from the tray; cause is \"manual\", \"scan\", \"file\" or \"device_data\"",
+            payload_example: json!({
+                "cause": "scan",
+                "code": "941000012345678",
+                "device": "managed-hid",
+                "deviceType": "hid",
+            }),
+        },
+        EventChannel {
+            name: ATTACHMENT_OCR_COMPLETE,
+            description: "OCR of an uploaded attachment finished or failed",
+            payload_example: to_example(&ocr_result),
+        },
+        EventChannel {
+            name: ATTACHMENT_THUMBNAILS_PROGRESS,
+            description: "A PDF attachment's thumbnail was prewarmed, found cached, or failed",
+            payload_example: to_example(&thumbnail_progress),
+        },
+        EventChannel {
+            name: HOUSEHOLD_SEARCH_REINDEX_PROGRESS,
+            description: "A full household search index rebuild finished a batch",
+            payload_example: to_example(&reindex_progress),
+        },
+    ]
+}
+
+fn to_example<T: Serialize>(payload: &T) -> Value {
+    serde_json::to_value(payload).unwrap_or(Value::Null)
+}
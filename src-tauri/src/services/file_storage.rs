@@ -1,13 +1,22 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::fs;
 use uuid::Uuid;
 use sea_orm::*;
-use crate::models::medical::{MedicalAttachment, AttachmentData};
+use crate::models::medical::{
+    MedicalAttachment, AttachmentData, UploadFile, UploadFileResult, BatchUploadResponse,
+};
 use chrono::Utc;
 use tauri::AppHandle;
 use std::io::Write;
 use std::fs::File;
 use std::process::{Command, Stdio};
+use sha2::{Digest, Sha256};
+
+/// SQL for the name an attachment's file has in storage. Attachments with
+/// identical content share one file, named in `stored_file_id`; rows from
+/// before that column existed are stored under their own `file_id`.
+pub const STORED_FILE: &str = "COALESCE(stored_file_id, file_id)";
 
 // T028: FileStorageService for attachment handling
 pub struct FileStorageService;
@@ -31,6 +40,23 @@ impl FileStorageService {
         Ok(files_dir)
     }
 
+    /// Hex SHA-256 of an attachment's bytes, stored as `content_sha256`.
+    pub fn content_sha256(data: &[u8]) -> String {
+        Self::to_hex(&Sha256::digest(data))
+    }
+
+    /// `content_sha256` of a file on disk, read in chunks.
+    pub fn file_sha256(path: &Path) -> std::io::Result<String> {
+        let mut file = File::open(path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(Self::to_hex(&hasher.finalize()))
+    }
+
+    fn to_hex(digest: &[u8]) -> String {
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
     pub async fn upload_attachment(
         app_handle: &AppHandle,
         db: &DatabaseConnection,
@@ -43,32 +69,81 @@ impl FileStorageService {
         connection_method: Option<String>,
         attachment_type: Option<String>,
     ) -> Result<MedicalAttachment, String> {
+        let storage_dir = Self::get_storage_dir(app_handle)?;
+        let file = UploadFile {
+            file_name,
+            file_data,
+            mime_type,
+            device_type,
+            device_name,
+            connection_method,
+            attachment_type,
+        };
+        Self::upload_attachment_to_dir(&storage_dir, db, medical_record_id, file).await
+    }
+
+    /// Store one file under `storage_dir` and add it to the record. Bytes
+    /// already stored for another attachment aren't written again; the new
+    /// attachment shares that file.
+    pub async fn upload_attachment_to_dir(
+        storage_dir: &Path,
+        db: &DatabaseConnection,
+        medical_record_id: i64,
+        file: UploadFile,
+    ) -> Result<MedicalAttachment, String> {
+        let UploadFile {
+            file_name,
+            file_data,
+            mime_type,
+            device_type,
+            device_name,
+            connection_method,
+            attachment_type,
+        } = file;
+
         // Generate unique file ID
         let file_id = Uuid::new_v4().to_string();
+        let file_size = file_data.len() as i64;
+        let content_sha256 = Self::content_sha256(&file_data);
 
-        // Get storage directory
-        let storage_dir = Self::get_storage_dir(app_handle)?;
+        // The lookup and the insert share a transaction, so the row is in
+        // before anything else decides the shared file is unused
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        // Save file to disk
-        let file_path = storage_dir.join(&file_id);
-        if let Err(e) = fs::write(&file_path, &file_data) {
-            log::error!("❌ Failed to write attachment file '{}' (record_id={}): {}", file_name, medical_record_id, e);
-            return Err(format!("Failed to write file: {}", e));
-        }
+        // Save file to disk, unless identical bytes are already stored
+        let stored_file_id = match Self::find_stored_copy(storage_dir, &txn, &content_sha256).await? {
+            Some(existing) => {
+                log::debug!("Attachment '{}' has the same content as stored file {}", file_name, existing);
+                existing
+            }
+            None => {
+                if let Err(e) = fs::write(storage_dir.join(&file_id), &file_data) {
+                    log::error!("❌ Failed to write attachment file '{}' (record_id={}): {}", file_name, medical_record_id, e);
+                    return Err(format!("Failed to write file: {}", e));
+                }
+                file_id.clone()
+            }
+        };
 
-        let file_size = file_data.len() as i64;
         let now = Utc::now();
 
         // Default attachment_type to 'file' if not provided
         let attachment_type = attachment_type.unwrap_or_else(|| "file".to_string());
 
+        // Append after the record's existing attachments
+        let sort_order = Self::next_sort_order(&txn, medical_record_id).await?;
+
         // Insert attachment record into database with device metadata
-        let result = db.execute(Statement::from_sql_and_values(
+        let result = txn.execute(Statement::from_sql_and_values(
             DbBackend::Sqlite,
             "INSERT INTO medical_attachments \
              (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at, \
-              device_type, device_name, connection_method, attachment_type) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+              device_type, device_name, connection_method, attachment_type, sort_order, content_sha256, \
+              stored_file_id) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             [
                 medical_record_id.into(),
                 file_id.clone().into(),
@@ -80,6 +155,9 @@ impl FileStorageService {
                 Value::String(device_name.clone().map(Box::new)),
                 Value::String(connection_method.clone().map(Box::new)),
                 attachment_type.clone().into(),
+                sort_order.into(),
+                content_sha256.into(),
+                stored_file_id.clone().into(),
             ]
         ))
         .await
@@ -89,6 +167,10 @@ impl FileStorageService {
         })?;
 
         let attachment_id = result.last_insert_id() as i64;
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit attachment: {}", e))?;
+        Self::ensure_stored_file(storage_dir, &stored_file_id, &file_data)?;
 
         Ok(MedicalAttachment {
             id: attachment_id,
@@ -102,35 +184,412 @@ impl FileStorageService {
             device_name,
             connection_method,
             attachment_type: Some(attachment_type),
+            sort_order,
+            is_external: false,
+            external_path: None,
         })
     }
 
+    /// Name of a file in `storage_dir` already holding content with this
+    /// hash, if any attachment stored one that is still on disk.
+    async fn find_stored_copy<C: ConnectionTrait>(
+        storage_dir: &Path,
+        conn: &C,
+        content_sha256: &str,
+    ) -> Result<Option<String>, String> {
+        let rows = conn.query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            &format!(
+                "SELECT DISTINCT {} AS stored_file FROM medical_attachments \
+                 WHERE content_sha256 = ? AND is_external = 0",
+                STORED_FILE
+            ),
+            [content_sha256.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to look up stored files: {}", e))?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| row.try_get::<String>("", "stored_file").ok())
+            .find(|stored_file| storage_dir.join(stored_file).is_file()))
+    }
+
+    /// Write `data` back to a shared stored file if it's gone. Deleting the
+    /// last other attachment using it can remove it after the lookup but
+    /// before the new row is committed; once committed the row keeps it.
+    fn ensure_stored_file(storage_dir: &Path, stored_file: &str, data: &[u8]) -> Result<(), String> {
+        let file_path = storage_dir.join(stored_file);
+        if file_path.is_file() {
+            return Ok(());
+        }
+        log::warn!("Stored file {} was removed while being shared, writing it again", stored_file);
+        fs::write(&file_path, data).map_err(|e| format!("Failed to write file: {}", e))
+    }
+
+    /// Next free `sort_order` slot for a record's attachments
+    async fn next_sort_order<C: ConnectionTrait>(
+        conn: &C,
+        medical_record_id: i64,
+    ) -> Result<i64, String> {
+        let row = conn.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT COALESCE(MAX(sort_order) + 1, 0) AS next_order \
+             FROM medical_attachments WHERE medical_record_id = ?",
+            [medical_record_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to read attachment order: {}", e))?;
+
+        Ok(row.and_then(|r| r.try_get("", "next_order").ok()).unwrap_or(0))
+    }
+
+    /// Reference a file where it already lives (e.g. imaging on a network
+    /// share) instead of copying it into storage. The path must be absolute
+    /// and point at an existing file; `mime_type` defaults to
+    /// `application/octet-stream`.
+    pub async fn attach_external_file(
+        db: &DatabaseConnection,
+        medical_record_id: i64,
+        path: &str,
+        mime_type: Option<String>,
+    ) -> Result<MedicalAttachment, String> {
+        let source = Path::new(path.trim());
+        if !source.is_absolute() {
+            return Err(format!("External file path must be absolute: {}", path));
+        }
+        let metadata = fs::metadata(source)
+            .map_err(|e| format!("External file not found '{}': {}", source.display(), e))?;
+        if !metadata.is_file() {
+            return Err(format!("External path is not a file: {}", source.display()));
+        }
+
+        // file_id stays unique but never names a file in storage
+        let file_id = Uuid::new_v4().to_string();
+        let original_name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "attachment".to_string());
+        let external_path = source.display().to_string();
+        let mime_type = mime_type.unwrap_or_else(|| "application/octet-stream".to_string());
+        let file_size = metadata.len() as i64;
+        let now = Utc::now();
+        let sort_order = Self::next_sort_order(db, medical_record_id).await?;
+
+        let result = db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO medical_attachments \
+             (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at, \
+              attachment_type, sort_order, is_external, external_path) \
+             VALUES (?, ?, ?, ?, ?, ?, 'file', ?, 1, ?)",
+            [
+                medical_record_id.into(),
+                file_id.clone().into(),
+                original_name.clone().into(),
+                file_size.into(),
+                mime_type.clone().into(),
+                now.to_rfc3339().into(),
+                sort_order.into(),
+                external_path.clone().into(),
+            ]
+        ))
+        .await
+        .map_err(|e| format!("Failed to save attachment record: {}", e))?;
+
+        Ok(MedicalAttachment {
+            id: result.last_insert_id() as i64,
+            medical_record_id,
+            file_id,
+            original_name,
+            mime_type: Some(mime_type),
+            file_size: Some(file_size),
+            uploaded_at: now,
+            device_type: None,
+            device_name: None,
+            connection_method: None,
+            attachment_type: Some("file".to_string()),
+            sort_order,
+            is_external: true,
+            external_path: Some(external_path),
+        })
+    }
+
+    /// The path an external attachment row points at, or `None` for a file
+    /// kept in storage. An external file that has since gone missing (share
+    /// unmounted, file moved) is reported as such rather than as an I/O error.
+    fn external_file(row: &QueryResult) -> Result<Option<PathBuf>, String> {
+        let is_external = row.try_get::<i64>("", "is_external").map(|v| v != 0).unwrap_or(false);
+        if !is_external {
+            return Ok(None);
+        }
+
+        let external_path: String = row.try_get("", "external_path")
+            .map_err(|_| "External attachment has no path".to_string())?;
+        let path = PathBuf::from(&external_path);
+        if !path.is_file() {
+            return Err(format!("External file is no longer available: {}", external_path));
+        }
+        Ok(Some(path))
+    }
+
+    /// Upload several files to one record (e.g. the pages of a scanned
+    /// document). See `upload_attachments_to_dir` for the batch semantics.
+    pub async fn upload_attachments(
+        app_handle: &AppHandle,
+        db: &DatabaseConnection,
+        medical_record_id: i64,
+        files: Vec<UploadFile>,
+        max_size_mb: usize,
+        skip_invalid: bool,
+    ) -> Result<BatchUploadResponse, String> {
+        let storage_dir = Self::get_storage_dir(app_handle)?;
+        Self::upload_attachments_to_dir(&storage_dir, db, medical_record_id, files, max_size_mb, skip_invalid).await
+    }
+
+    /// Validate and store a batch of files, inserting all attachment rows in
+    /// one transaction with consecutive `sort_order` values in submission
+    /// order.
+    ///
+    /// Every file is validated before anything touches disk. If any file is
+    /// invalid the whole batch is rejected, unless `skip_invalid` is set, in
+    /// which case the invalid files are reported in `results` and the rest
+    /// are stored. If a write or insert fails midway, files already written
+    /// for this batch are removed again so no orphans are left behind.
+    pub async fn upload_attachments_to_dir(
+        storage_dir: &Path,
+        db: &DatabaseConnection,
+        medical_record_id: i64,
+        files: Vec<UploadFile>,
+        max_size_mb: usize,
+        skip_invalid: bool,
+    ) -> Result<BatchUploadResponse, String> {
+        if files.is_empty() {
+            return Err("No files to upload".to_string());
+        }
+        let total = files.len();
+
+        let mut results: Vec<UploadFileResult> = Vec::with_capacity(total);
+        let mut accepted: Vec<(usize, UploadFile)> = Vec::with_capacity(total);
+        for (index, file) in files.into_iter().enumerate() {
+            match Self::validate_file(&file.file_data, &file.file_name, max_size_mb) {
+                Ok(()) => accepted.push((index, file)),
+                Err(e) => results.push(UploadFileResult {
+                    index,
+                    file_name: file.file_name,
+                    attachment_id: None,
+                    error: Some(e),
+                }),
+            }
+        }
+
+        if !results.is_empty() && !skip_invalid {
+            let details: Vec<String> = results
+                .iter()
+                .map(|r| format!("{}: {}", r.file_name, r.error.as_deref().unwrap_or("invalid")))
+                .collect();
+            return Err(format!(
+                "Upload rejected, {} of {} files invalid: {}",
+                results.len(),
+                total,
+                details.join("; ")
+            ));
+        }
+
+        // Write every accepted file first; the DB rows only go in once all
+        // bytes are safely on disk. Content already in storage, or earlier
+        // in this batch, isn't written twice.
+        let mut written: Vec<PathBuf> = Vec::with_capacity(accepted.len());
+        let mut file_ids: Vec<String> = Vec::with_capacity(accepted.len());
+        let mut stored_files: Vec<String> = Vec::with_capacity(accepted.len());
+        let mut batch_files: HashMap<String, String> = HashMap::new();
+        for (_, file) in accepted.iter() {
+            let file_id = Uuid::new_v4().to_string();
+            let content_sha256 = Self::content_sha256(&file.file_data);
+            let existing = match batch_files.get(&content_sha256) {
+                Some(stored_file) => Some(stored_file.clone()),
+                None => match Self::find_stored_copy(storage_dir, db, &content_sha256).await {
+                    Ok(existing) => existing,
+                    Err(e) => {
+                        Self::remove_files(&written);
+                        return Err(e);
+                    }
+                },
+            };
+            let stored_file = match existing {
+                Some(stored_file) => stored_file,
+                None => {
+                    let file_path = storage_dir.join(&file_id);
+                    if let Err(e) = fs::write(&file_path, &file.file_data) {
+                        log::error!("❌ Failed to write attachment file '{}' (record_id={}): {}", file.file_name, medical_record_id, e);
+                        Self::remove_files(&written);
+                        return Err(format!("Failed to write file '{}': {}", file.file_name, e));
+                    }
+                    written.push(file_path);
+                    file_id.clone()
+                }
+            };
+            batch_files.insert(content_sha256, stored_file.clone());
+            file_ids.push(file_id);
+            stored_files.push(stored_file);
+        }
+
+        let inserted = Self::insert_batch_rows(db, medical_record_id, &accepted, &file_ids, &stored_files).await;
+        let attachments = match inserted {
+            Ok(a) => a,
+            Err(e) => {
+                log::error!("❌ Batch upload failed for record {}: {}", medical_record_id, e);
+                Self::remove_files(&written);
+                return Err(e);
+            }
+        };
+        for ((_, file), stored_file) in accepted.iter().zip(stored_files.iter()) {
+            Self::ensure_stored_file(storage_dir, stored_file, &file.file_data)?;
+        }
+
+        for ((index, file), attachment) in accepted.iter().zip(attachments.iter()) {
+            results.push(UploadFileResult {
+                index: *index,
+                file_name: file.file_name.clone(),
+                attachment_id: Some(attachment.id),
+                error: None,
+            });
+        }
+        results.sort_by_key(|r| r.index);
+
+        log::info!(
+            "Uploaded {} of {} files to record {}",
+            attachments.len(),
+            total,
+            medical_record_id
+        );
+
+        Ok(BatchUploadResponse { attachments, results })
+    }
+
+    async fn insert_batch_rows(
+        db: &DatabaseConnection,
+        medical_record_id: i64,
+        files: &[(usize, UploadFile)],
+        file_ids: &[String],
+        stored_files: &[String],
+    ) -> Result<Vec<MedicalAttachment>, String> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let base_order = Self::next_sort_order(&txn, medical_record_id).await?;
+        let now = Utc::now();
+        let mut attachments = Vec::with_capacity(files.len());
+
+        for (offset, (((_, file), file_id), stored_file)) in
+            files.iter().zip(file_ids.iter()).zip(stored_files.iter()).enumerate()
+        {
+            let sort_order = base_order + offset as i64;
+            let file_size = file.file_data.len() as i64;
+            let attachment_type = file.attachment_type.clone().unwrap_or_else(|| "file".to_string());
+
+            let result = txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO medical_attachments \
+                 (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at, \
+                  device_type, device_name, connection_method, attachment_type, sort_order, content_sha256, \
+                  stored_file_id) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                [
+                    medical_record_id.into(),
+                    file_id.clone().into(),
+                    file.file_name.clone().into(),
+                    file_size.into(),
+                    file.mime_type.clone().into(),
+                    now.to_rfc3339().into(),
+                    Value::String(file.device_type.clone().map(Box::new)),
+                    Value::String(file.device_name.clone().map(Box::new)),
+                    Value::String(file.connection_method.clone().map(Box::new)),
+                    attachment_type.clone().into(),
+                    sort_order.into(),
+                    Self::content_sha256(&file.file_data).into(),
+                    stored_file.clone().into(),
+                ]
+            ))
+            .await
+            .map_err(|e| format!("Failed to save attachment record for '{}': {}", file.file_name, e))?;
+
+            attachments.push(MedicalAttachment {
+                id: result.last_insert_id() as i64,
+                medical_record_id,
+                file_id: file_id.clone(),
+                original_name: file.file_name.clone(),
+                mime_type: Some(file.mime_type.clone()),
+                file_size: Some(file_size),
+                uploaded_at: now,
+                device_type: file.device_type.clone(),
+                device_name: file.device_name.clone(),
+                connection_method: file.connection_method.clone(),
+                attachment_type: Some(attachment_type),
+                sort_order,
+                is_external: false,
+                external_path: None,
+            });
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit attachments: {}", e))?;
+
+        Ok(attachments)
+    }
+
+    fn remove_files(paths: &[PathBuf]) {
+        for path in paths {
+            if let Err(e) = fs::remove_file(path) {
+                log::warn!("Failed to remove partially uploaded file {}: {}", path.display(), e);
+            }
+        }
+    }
+
     pub async fn download_attachment(
         app_handle: &AppHandle,
         db: &DatabaseConnection,
         attachment_id: i64,
+    ) -> Result<AttachmentData, String> {
+        let storage_dir = Self::get_storage_dir(app_handle)?;
+        Self::download_attachment_in_dir(&storage_dir, db, attachment_id).await
+    }
+
+    pub async fn download_attachment_in_dir(
+        storage_dir: &Path,
+        db: &DatabaseConnection,
+        attachment_id: i64,
     ) -> Result<AttachmentData, String> {
         // Get attachment record from database
         let row = db.query_one(Statement::from_sql_and_values(
             DbBackend::Sqlite,
-            "SELECT file_id, original_name, mime_type \
-             FROM medical_attachments WHERE id = ?",
+            &format!(
+                "SELECT {} AS stored_file, original_name, mime_type, is_external, external_path \
+                 FROM medical_attachments WHERE id = ?",
+                STORED_FILE
+            ),
             [attachment_id.into()]
         ))
         .await
         .map_err(|e| format!("Failed to fetch attachment record: {}", e))?
         .ok_or("Attachment not found".to_string())?;
 
-        let file_id: String = row.try_get("", "file_id")
-            .map_err(|e| format!("Failed to get file_id: {}", e))?;
         let original_name: String = row.try_get("", "original_name")
             .map_err(|e| format!("Failed to get original_name: {}", e))?;
         let mime_type: String = row.try_get("", "mime_type")
             .map_err(|e| format!("Failed to get mime_type: {}", e))?;
 
-        // Get storage directory and read file
-        let storage_dir = Self::get_storage_dir(app_handle)?;
-        let file_path = storage_dir.join(&file_id);
+        // Read file from storage, or from where an external attachment points
+        let file_path = match Self::external_file(&row)? {
+            Some(path) => path,
+            None => {
+                let stored_file: String = row.try_get("", "stored_file")
+                    .map_err(|e| format!("Failed to get file_id: {}", e))?;
+                storage_dir.join(stored_file)
+            }
+        };
 
         log::debug!("file_storage download id={} path={}", attachment_id, file_path.display());
 
@@ -151,18 +610,30 @@ impl FileStorageService {
         db: &DatabaseConnection,
         attachment_id: i64,
     ) -> Result<(), String> {
-        // Get file_id before deleting from database
+        let storage_dir = Self::get_storage_dir(app_handle)?;
+        Self::delete_attachment_in_dir(&storage_dir, db, attachment_id).await
+    }
+
+    /// Delete an attachment row, and its file under `storage_dir` unless
+    /// another attachment shares it.
+    pub async fn delete_attachment_in_dir(
+        storage_dir: &Path,
+        db: &DatabaseConnection,
+        attachment_id: i64,
+    ) -> Result<(), String> {
+        // Get the stored file before deleting from database
         let row = db.query_one(Statement::from_sql_and_values(
             DbBackend::Sqlite,
-            "SELECT file_id FROM medical_attachments WHERE id = ?",
+            &format!("SELECT {} AS stored_file, is_external FROM medical_attachments WHERE id = ?", STORED_FILE),
             [attachment_id.into()]
         ))
         .await
         .map_err(|e| format!("Failed to fetch attachment: {}", e))?
         .ok_or("Attachment not found".to_string())?;
 
-        let file_id: String = row.try_get("", "file_id")
+        let stored_file: String = row.try_get("", "stored_file")
             .map_err(|e| format!("Failed to get file_id: {}", e))?;
+        let is_external = row.try_get::<i64>("", "is_external").map(|v| v != 0).unwrap_or(false);
 
         // Delete from database
         db.execute(Statement::from_sql_and_values(
@@ -173,10 +644,30 @@ impl FileStorageService {
         .await
         .map_err(|e| format!("Failed to delete attachment record: {}", e))?;
 
-        // Delete file from disk
-        let storage_dir = Self::get_storage_dir(app_handle)?;
-        let file_path = storage_dir.join(&file_id);
+        // An external file isn't ours to remove
+        if is_external {
+            return Ok(());
+        }
+
+        let shared = db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            &format!(
+                "SELECT COUNT(*) AS c FROM medical_attachments WHERE is_external = 0 AND {} = ?",
+                STORED_FILE
+            ),
+            [stored_file.clone().into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to check shared file: {}", e))?
+        .and_then(|r| r.try_get::<i64>("", "c").ok())
+        .unwrap_or(0);
+        if shared > 0 {
+            log::debug!("Keeping stored file {}, still used by {} attachment(s)", stored_file, shared);
+            return Ok(());
+        }
 
+        // Delete file from disk
+        let file_path = storage_dir.join(&stored_file);
         if file_path.exists() {
             fs::remove_file(&file_path)
                 .map_err(|e| format!("Failed to delete file: {}", e))?;
@@ -185,7 +676,27 @@ impl FileStorageService {
         Ok(())
     }
 
-    /// Clean up orphaned files (files in storage but not in database)
+    /// Look up which record an attachment belongs to, so callers can take
+    /// that record's lock before mutating it.
+    pub async fn get_attachment_record_id(
+        db: &DatabaseConnection,
+        attachment_id: i64,
+    ) -> Result<i64, String> {
+        let row = db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT medical_record_id FROM medical_attachments WHERE id = ?",
+            [attachment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch attachment: {}", e))?
+        .ok_or("Attachment not found".to_string())?;
+
+        row.try_get("", "medical_record_id")
+            .map_err(|e| format!("Failed to get medical_record_id: {}", e))
+    }
+
+    /// Clean up orphaned files (files in storage but not in database).
+    /// External attachments have nothing in storage and are never touched.
     pub async fn cleanup_orphaned_files(
         _app_handle: &AppHandle,
         _db: &DatabaseConnection,
@@ -219,22 +730,103 @@ impl FileStorageService {
         db: &DatabaseConnection,
         attachment_id: i64,
     ) -> Result<String, String> {
-        let data = Self::download_attachment(app_handle, db, attachment_id).await?;
+        let storage_dir = Self::get_storage_dir(app_handle)?;
+        let target_path =
+            Self::materialize_attachment_in_dir(&storage_dir, &Self::materialize_dir(), db, attachment_id).await?;
+        Ok(target_path.display().to_string())
+    }
 
-        // Create temp path
-        let mut tmp_dir = std::env::temp_dir();
-        tmp_dir.push("vet-clinic-attachments");
-        if !tmp_dir.exists() {
-            fs::create_dir_all(&tmp_dir)
+    /// Temp directory attachments are materialized into
+    pub fn materialize_dir() -> PathBuf {
+        std::env::temp_dir().join("vet-clinic-attachments")
+    }
+
+    /// Materialize an attachment, then run `work` on the blocking thread pool
+    /// with the local copy's path. Used for PDF rendering, which can take
+    /// seconds on large scans.
+    pub async fn with_materialized_attachment<T, F>(
+        app_handle: &AppHandle,
+        db: &DatabaseConnection,
+        attachment_id: i64,
+        work: F,
+    ) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(PathBuf) -> Result<T, String> + Send + 'static,
+    {
+        let storage_dir = Self::get_storage_dir(app_handle)?;
+        Self::with_materialized_attachment_in_dir(&storage_dir, &Self::materialize_dir(), db, attachment_id, work).await
+    }
+
+    /// The attachment row is read before `work` starts, so no database
+    /// connection is held while it runs and other queries proceed as normal.
+    pub async fn with_materialized_attachment_in_dir<T, F>(
+        storage_dir: &Path,
+        target_dir: &Path,
+        db: &DatabaseConnection,
+        attachment_id: i64,
+        work: F,
+    ) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(PathBuf) -> Result<T, String> + Send + 'static,
+    {
+        let path = Self::materialize_attachment_in_dir(storage_dir, target_dir, db, attachment_id).await?;
+        tokio::task::spawn_blocking(move || work(path))
+            .await
+            .map_err(|e| format!("Spawn failed: {}", e))?
+    }
+
+    /// Copy an attachment out of `storage_dir` into `target_dir` under its
+    /// original (sanitized) name. External attachments are already on disk,
+    /// so their own path is returned without copying.
+    pub async fn materialize_attachment_in_dir(
+        storage_dir: &Path,
+        target_dir: &Path,
+        db: &DatabaseConnection,
+        attachment_id: i64,
+    ) -> Result<PathBuf, String> {
+        let row = db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT is_external, external_path FROM medical_attachments WHERE id = ?",
+            [attachment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch attachment record: {}", e))?
+        .ok_or("Attachment not found".to_string())?;
+        if let Some(path) = Self::external_file(&row)? {
+            return Ok(path);
+        }
+
+        let data = Self::download_attachment_in_dir(storage_dir, db, attachment_id).await?;
+
+        if !target_dir.exists() {
+            fs::create_dir_all(target_dir)
                 .map_err(|e| format!("Failed to create temp dir: {}", e))?;
         }
-        let target_path = tmp_dir.join(&data.file_name);
+        let target_path = target_dir.join(Self::sanitize_file_name(&data.file_name));
 
         // Write file
         fs::write(&target_path, &data.file_data)
             .map_err(|e| format!("Failed to write temp file: {}", e))?;
 
-        Ok(target_path.display().to_string())
+        Ok(target_path)
+    }
+
+    /// Reduce a stored original name to a safe single path component, so a
+    /// name like `../../x` or `C:\x` can't escape the target directory.
+    pub fn sanitize_file_name(name: &str) -> String {
+        let last = name.rsplit(['/', '\\']).next().unwrap_or("");
+        let cleaned: String = last
+            .chars()
+            .map(|c| if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') { '_' } else { c })
+            .collect();
+        let cleaned = cleaned.trim().trim_end_matches('.').to_string();
+        if cleaned.is_empty() || cleaned == "." || cleaned == ".." {
+            "attachment".to_string()
+        } else {
+            cleaned
+        }
     }
 
     /// Write an attachment to a specific path chosen by the user
@@ -326,6 +918,116 @@ impl FileStorageService {
         Err("Unsupported platform".to_string())
     }
 
+    /// Show a file selected in the platform file manager.
+    pub fn reveal_in_file_manager(path: &Path) -> Result<(), String> {
+        Self::reveal_in_file_manager_with(path, |cmd, wait| {
+            if wait {
+                let status = cmd.status()?;
+                if !status.success() {
+                    return Err(std::io::Error::other(format!("exited with {}", status)));
+                }
+            } else {
+                cmd.spawn()?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Try each reveal command for this platform in turn until `run` accepts
+    /// one. `run` gets the command and whether it must be waited on to know
+    /// if it worked (the Linux D-Bus call); tests pass a fake.
+    pub fn reveal_in_file_manager_with<F>(path: &Path, mut run: F) -> Result<(), String>
+    where
+        F: FnMut(&mut Command, bool) -> std::io::Result<()>,
+    {
+        #[cfg(windows)]
+        let path = dunce::canonicalize(path)
+            .map_err(|e| format!("Cannot reveal '{}': {}", path.display(), e))?;
+        #[cfg(not(windows))]
+        let path = fs::canonicalize(path)
+            .map_err(|e| format!("Cannot reveal '{}': {}", path.display(), e))?;
+
+        let mut last_error = String::from("unsupported platform");
+        for (mut cmd, wait) in Self::reveal_commands(&path) {
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            match run(&mut cmd, wait) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    log::debug!("Reveal via {:?} failed: {}", cmd.get_program(), e);
+                    last_error = e.to_string();
+                }
+            }
+        }
+
+        Err(format!("No file manager available to reveal '{}': {}", path.display(), last_error))
+    }
+
+    /// Candidate commands, best first, paired with whether to wait on them.
+    fn reveal_commands(path: &Path) -> Vec<(Command, bool)> {
+        #[allow(unused_mut)]
+        let mut commands = Vec::new();
+
+        #[cfg(target_os = "macos")]
+        {
+            let mut cmd = Command::new("open");
+            cmd.arg("-R").arg(path);
+            commands.push((cmd, false));
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            // explorer parses its own command line; `/select,"path"` must
+            // reach it unescaped
+            let mut cmd = Command::new("explorer");
+            cmd.raw_arg(format!("/select,\"{}\"", path.display()));
+            commands.push((cmd, false));
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // FileManager1 is implemented by Nautilus, Dolphin, Nemo, Caja
+            // and Thunar, and selects the file
+            let mut show_items = Command::new("dbus-send");
+            show_items.args([
+                "--session",
+                "--print-reply",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ]);
+            show_items.arg(format!("array:string:{}", Self::file_uri(path)));
+            show_items.arg("string:");
+            commands.push((show_items, true));
+
+            // Otherwise at least open the containing folder
+            if let Some(parent) = path.parent() {
+                let mut open_dir = Command::new("xdg-open");
+                open_dir.arg(parent);
+                commands.push((open_dir, false));
+            }
+        }
+
+        commands
+    }
+
+    /// `file://` URI for an absolute path, percent-encoding anything outside
+    /// the unreserved set.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn file_uri(path: &Path) -> String {
+        let mut uri = String::from("file://");
+        for byte in path.to_string_lossy().bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => uri.push(byte as char),
+                _ => uri.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        uri
+    }
+
     /// Print a file using the system's native print dialog.
     /// Uses subprocess isolation to minimize focus disruption.
     pub fn print_file(path: &str) -> Result<(), String> {
@@ -9,6 +9,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::Manager;
 use serde::{Serialize, Deserialize};
 use crate::services::device_parser::DeviceParserService;
+use crate::services::events;
+use crate::services::device_data_buffer::record_device_data;
 use crate::services::device_capture::throttled_show_and_focus;
 use crate::services::file_storage::FileStorageService;
 use crate::commands::file_history::record_device_file_access_internal_seaorm;
@@ -99,7 +101,7 @@ fn update_file_watcher_status(
 
     // Emit to frontend
     if let Some(handle) = app_handle {
-        match handle.emit_all("file-watcher-status", &watcher_status) {
+        match handle.emit_all(events::FILE_WATCHER_STATUS, &watcher_status) {
             Ok(_) => log::info!("📡 Emitted file-watcher-status event to frontend for '{}'", name),
             Err(e) => log::error!("❌ Failed to emit file-watcher-status event: {}", e),
         }
@@ -336,9 +338,12 @@ impl FileWatcherService {
                                                     log::info!("   ✅ File parsed successfully - Patient ID: {:?}, File: {}",
                                                         device_data.patient_identifier, file_name_str);
 
+                                                    // Keep a copy for screens opened after the event fires
+                                                    record_device_data(id, &device_data);
+
                                                     // Emit device data to frontend
                                                     if let Some(ref app_handle) = app_handle_clone {
-                                                        match app_handle.emit_all("device-data-received", &device_data) {
+                                                        match app_handle.emit_all(events::DEVICE_DATA_RECEIVED, &device_data) {
                                                             Ok(_) => {
                                                                 log::info!("   📡 Emitted device-data-received event for '{}'", name_clone);
 
@@ -404,7 +409,7 @@ impl FileWatcherService {
                                                                         // Only emit wake-from-tray if window was hidden
                                                                         if is_hidden {
                                                                             let _ = app_handle_track.emit_all(
-                                                                                "wake-from-tray",
+                                                                                events::WAKE_FROM_TRAY,
                                                                                 serde_json::json!({
                                                                                     "cause": "file",
                                                                                     "fileName": file_name_track,
@@ -12,6 +12,8 @@ use sea_orm::{DatabaseConnection, ConnectionTrait, Statement, DbBackend};
 pub struct GoogleCalendarService {
     client: Client,
     access_token: Option<String>,
+    /// IANA timezone attached to pushed events
+    time_zone: String,
 }
 
 #[allow(dead_code)]
@@ -20,6 +22,7 @@ impl GoogleCalendarService {
         Self {
             client: Client::new(),
             access_token: None,
+            time_zone: "UTC".to_string(),
         }
     }
 
@@ -28,6 +31,11 @@ impl GoogleCalendarService {
         self
     }
 
+    pub fn with_time_zone(mut self, time_zone: String) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
     pub async fn sync_appointment_to_calendar(
         &self,
         appointment: &Appointment,
@@ -189,11 +197,11 @@ impl GoogleCalendarService {
             ),
             "start": {
                 "dateTime": appointment.start_time.to_rfc3339(),
-                "timeZone": "UTC"
+                "timeZone": self.time_zone
             },
             "end": {
                 "dateTime": appointment.end_time.to_rfc3339(),
-                "timeZone": "UTC"
+                "timeZone": self.time_zone
             },
             "extendedProperties": {
                 "private": {
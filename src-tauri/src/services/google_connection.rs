@@ -0,0 +1,202 @@
+//! Checking that the stored Google credentials still work, so admins can
+//! confirm access before relying on sync, and choosing the calendar
+//! appointments sync to.
+//!
+//! The check gets a valid token the same way sync does (refreshing an
+//! expired one) and then lists the account's calendars, the cheapest call
+//! that proves the Calendar scope is granted.
+//!
+//! Switching the sync calendar drops the event mappings of the old one:
+//! those events stay where they are, and appointments are pushed to the
+//! new calendar as new events on the next sync.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::models::google_calendar::GoogleCalendar;
+use crate::services::oauth::{valid_access_token_with, AccessTokenError, OAuthService};
+
+/// The Google calls the connection check makes.
+pub(crate) trait GoogleClient {
+    /// Exchange a refresh token for a new access token and its lifetime in seconds.
+    async fn refresh_access_token(&self, refresh_token: String) -> Result<(String, i64), String>;
+    async fn list_calendars(&self, access_token: &str) -> Result<Vec<GoogleCalendar>, String>;
+}
+
+/// Talks to Google over HTTP.
+pub(crate) struct GoogleHttpClient;
+
+impl GoogleClient for GoogleHttpClient {
+    async fn refresh_access_token(&self, refresh_token: String) -> Result<(String, i64), String> {
+        OAuthService::refresh_access_token(refresh_token).await
+    }
+
+    async fn list_calendars(&self, access_token: &str) -> Result<Vec<GoogleCalendar>, String> {
+        let response = reqwest::Client::new()
+            .get("https://www.googleapis.com/calendar/v3/users/me/calendarList")
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list calendars: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Google Calendar API error {}: {}", status, error_text));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse calendar list: {}", e))?;
+
+        Ok(body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| {
+                        Some(GoogleCalendar {
+                            id: item["id"].as_str()?.to_string(),
+                            summary: item["summary"].as_str().unwrap_or_default().to_string(),
+                            primary: item["primary"].as_bool().unwrap_or(false),
+                            access_role: item["accessRole"].as_str().unwrap_or("reader").to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoogleConnectionStatus {
+    /// No stored tokens; Google was never connected or was disconnected.
+    NotConnected,
+    /// The token had expired and could not be refreshed; reconnect.
+    RefreshFailed,
+    /// A token was available but Google rejected the calendar listing.
+    ApiError,
+    Connected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleConnectionTest {
+    pub ok: bool,
+    pub status: GoogleConnectionStatus,
+    pub calendars: Vec<GoogleCalendar>,
+    pub error: Option<String>,
+}
+
+impl GoogleConnectionTest {
+    fn failed(status: GoogleConnectionStatus, error: String) -> Self {
+        Self { ok: false, status, calendars: Vec::new(), error: Some(error) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncCalendarChange {
+    pub calendar: GoogleCalendar,
+    /// Event mappings of the previous calendar that were dropped.
+    pub cleared_mappings: usize,
+}
+
+pub struct GoogleConnectionService;
+
+impl GoogleConnectionService {
+    pub async fn test_connection(db: &DatabaseConnection) -> Result<GoogleConnectionTest, String> {
+        Self::test_connection_with(db, &GoogleHttpClient).await
+    }
+
+    /// Failures of the check itself are reported in the result; only a
+    /// database error is returned as `Err`.
+    pub(crate) async fn test_connection_with<C: GoogleClient>(
+        db: &DatabaseConnection,
+        client: &C,
+    ) -> Result<GoogleConnectionTest, String> {
+        let access_token = match valid_access_token_with(db, client).await {
+            Ok(token) => token,
+            Err(AccessTokenError::NotConnected(e)) => {
+                return Ok(GoogleConnectionTest::failed(GoogleConnectionStatus::NotConnected, e))
+            }
+            Err(AccessTokenError::RefreshFailed(e)) => {
+                log::warn!("Google connection test: token refresh failed: {}", e);
+                return Ok(GoogleConnectionTest::failed(GoogleConnectionStatus::RefreshFailed, e));
+            }
+            Err(AccessTokenError::Database(e)) => return Err(e),
+        };
+
+        match client.list_calendars(&access_token).await {
+            Ok(calendars) => {
+                log::info!("Google connection test: connected, {} calendar(s)", calendars.len());
+                Ok(GoogleConnectionTest {
+                    ok: true,
+                    status: GoogleConnectionStatus::Connected,
+                    calendars,
+                    error: None,
+                })
+            }
+            Err(e) => {
+                log::warn!("Google connection test: listing calendars failed: {}", e);
+                Ok(GoogleConnectionTest::failed(GoogleConnectionStatus::ApiError, e))
+            }
+        }
+    }
+
+    pub async fn list_calendars(db: &DatabaseConnection) -> Result<Vec<GoogleCalendar>, String> {
+        Self::list_calendars_with(db, &GoogleHttpClient).await
+    }
+
+    pub(crate) async fn list_calendars_with<C: GoogleClient>(
+        db: &DatabaseConnection,
+        client: &C,
+    ) -> Result<Vec<GoogleCalendar>, String> {
+        let access_token = valid_access_token_with(db, client).await.map_err(|e| e.to_string())?;
+        client.list_calendars(&access_token).await
+    }
+
+    pub async fn set_sync_calendar(db: &DatabaseConnection, calendar_id: &str) -> Result<SyncCalendarChange, String> {
+        Self::set_sync_calendar_with(db, &GoogleHttpClient, calendar_id).await
+    }
+
+    /// Make `calendar_id` the calendar appointments sync to. It must be one
+    /// of the account's calendars and writable. When it differs from the
+    /// current one, the old calendar's event mappings are dropped.
+    pub(crate) async fn set_sync_calendar_with<C: GoogleClient>(
+        db: &DatabaseConnection,
+        client: &C,
+        calendar_id: &str,
+    ) -> Result<SyncCalendarChange, String> {
+        let calendar = Self::list_calendars_with(db, client)
+            .await?
+            .into_iter()
+            .find(|c| c.id == calendar_id)
+            .ok_or_else(|| format!("Calendar {} not found in the connected account", calendar_id))?;
+        if !calendar.is_writable() {
+            return Err(format!("Calendar \"{}\" is read-only", calendar.summary));
+        }
+
+        let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        let cleared = txn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "DELETE FROM calendar_event_mappings WHERE calendar_id <> ?",
+                [calendar.id.clone().into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to clear calendar mappings: {}", e))?
+            .rows_affected() as usize;
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE google_calendar_settings SET calendar_id = ?, updated_at = CURRENT_TIMESTAMP WHERE user_id = 'default'",
+            [calendar.id.clone().into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to update settings: {}", e))?;
+        txn.commit().await.map_err(|e| format!("Failed to commit calendar change: {}", e))?;
+
+        log::info!("Sync calendar set to {}, {} stale mapping(s) cleared", calendar.id, cleared);
+        Ok(SyncCalendarChange { calendar, cleared_mappings: cleared })
+    }
+}
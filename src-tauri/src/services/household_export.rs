@@ -0,0 +1,287 @@
+//! Everything held about one household, as a single JSON package for an
+//! owner's data-access request.
+//!
+//! The package is composed from the regular read paths (household with
+//! people, patients, medical records, appointments) and lists, in its
+//! manifest, what it contains and which internal fields were left out:
+//! staff names, record versions, storage ids and local file paths.
+//! Soft-deleted data (a deleted household, inactive patients, deleted
+//! appointments) is only included when asked for.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::database::queries::household;
+use crate::models::household::{Household, PersonWithContacts};
+use crate::models::{
+    Appointment, AppointmentFilter, MedicalAttachment, MedicalRecord, MedicalRecordFilter,
+    PaginationParams, Patient,
+};
+use crate::services::appointments::AppointmentService;
+use crate::services::medical_record::MedicalRecordService;
+use crate::services::patient::PatientService;
+
+/// Bumped when the package layout changes.
+pub const DATA_PACKAGE_FORMAT_VERSION: u32 = 1;
+
+/// Fields of the source data that are not exported.
+pub const DATA_PACKAGE_REDACTED_FIELDS: [&str; 8] = [
+    "medicalRecord.createdBy",
+    "medicalRecord.updatedBy",
+    "medicalRecord.version",
+    "attachment.fileId",
+    "attachment.externalPath",
+    "attachment.sortOrder",
+    "appointment.createdBy",
+    "appointment.roomId",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct DataPackageCounts {
+    pub people: usize,
+    pub contacts: usize,
+    pub patients: usize,
+    pub medical_records: usize,
+    pub attachments: usize,
+    pub appointments: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct DataPackageManifest {
+    pub format_version: u32,
+    #[ts(type = "string")]
+    pub generated_at: DateTime<Utc>,
+    pub household_id: i32,
+    pub include_deleted: bool,
+    pub counts: DataPackageCounts,
+    pub redacted_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedAttachment {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub original_name: String,
+    #[ts(type = "number | null")]
+    pub file_size: Option<i64>,
+    pub mime_type: Option<String>,
+    #[ts(type = "string")]
+    pub uploaded_at: DateTime<Utc>,
+    pub attachment_type: Option<String>,
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedMedicalRecord {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub record_type: String,
+    pub name: String,
+    pub procedure_name: Option<String>,
+    pub description: String,
+    pub prescription_notes: Option<String>,
+    pub price: Option<f64>,
+    pub discount_percent: Option<f64>,
+    pub manual_total: Option<f64>,
+    pub invoice_number: Option<String>,
+    pub is_archived: bool,
+    #[ts(type = "string | null")]
+    pub follow_up_date: Option<NaiveDate>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub updated_at: DateTime<Utc>,
+    pub attachments: Vec<ExportedAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedAppointment {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    #[ts(type = "string")]
+    pub start_time: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub end_time: DateTime<Utc>,
+    pub status: String,
+    #[ts(type = "string | null")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedPatient {
+    pub patient: Patient,
+    pub medical_records: Vec<ExportedMedicalRecord>,
+    pub appointments: Vec<ExportedAppointment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct HouseholdDataPackage {
+    pub manifest: DataPackageManifest,
+    pub household: Household,
+    pub people: Vec<PersonWithContacts>,
+    pub patients: Vec<ExportedPatient>,
+}
+
+impl From<MedicalAttachment> for ExportedAttachment {
+    fn from(a: MedicalAttachment) -> Self {
+        ExportedAttachment {
+            id: a.id,
+            original_name: a.original_name,
+            file_size: a.file_size,
+            mime_type: a.mime_type,
+            uploaded_at: a.uploaded_at,
+            attachment_type: a.attachment_type,
+            device_name: a.device_name,
+        }
+    }
+}
+
+impl From<MedicalRecord> for ExportedMedicalRecord {
+    fn from(r: MedicalRecord) -> Self {
+        ExportedMedicalRecord {
+            id: r.id,
+            record_type: r.record_type,
+            name: r.name,
+            procedure_name: r.procedure_name,
+            description: r.description,
+            prescription_notes: r.prescription_notes,
+            price: r.price,
+            discount_percent: r.discount_percent,
+            manual_total: r.manual_total,
+            invoice_number: r.invoice_number,
+            is_archived: r.is_archived,
+            follow_up_date: r.follow_up_date,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+            attachments: r
+                .attachments
+                .unwrap_or_default()
+                .into_iter()
+                .map(ExportedAttachment::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<Appointment> for ExportedAppointment {
+    fn from(a: Appointment) -> Self {
+        ExportedAppointment {
+            id: a.id,
+            title: a.title,
+            description: a.description,
+            start_time: a.start_time,
+            end_time: a.end_time,
+            status: a.status.to_string(),
+            deleted_at: a.deleted_at,
+        }
+    }
+}
+
+pub struct HouseholdExportService;
+
+impl HouseholdExportService {
+    pub async fn export_data_package(
+        db: &DatabaseConnection,
+        household_id: i32,
+        include_deleted: bool,
+    ) -> Result<HouseholdDataPackage, String> {
+        let with_people = household::fetch_household_with_people(db, household_id, include_deleted)
+            .await?
+            .ok_or_else(|| format!("Household {} not found", household_id))?;
+
+        let mut patients = Vec::new();
+        for patient_id in Self::linked_patient_ids(db, household_id).await? {
+            let Some(patient) = PatientService::get_by_id(db, patient_id).await? else {
+                continue;
+            };
+            if !patient.is_active && !include_deleted {
+                continue;
+            }
+
+            // An empty filter (rather than None) keeps archived records
+            let medical_records = MedicalRecordService::get_medical_records(
+                db,
+                patient_id,
+                Some(MedicalRecordFilter { record_type: None, is_archived: None, search_term: None }),
+                Some(PaginationParams { page: Some(1), page_size: Some(i32::MAX) }),
+            )
+            .await?
+            .records;
+
+            let appointment_filter = AppointmentFilter {
+                patient_id: Some(patient_id),
+                include_deleted,
+                include_cancelled: Some(true),
+                ..Default::default()
+            };
+            let appointments = AppointmentService::get_appointments(db, appointment_filter, i64::MAX, 0)
+                .await?
+                .appointments;
+
+            patients.push(ExportedPatient {
+                patient,
+                medical_records: medical_records.into_iter().map(ExportedMedicalRecord::from).collect(),
+                appointments: appointments.into_iter().map(ExportedAppointment::from).collect(),
+            });
+        }
+
+        let counts = DataPackageCounts {
+            people: with_people.people.len(),
+            contacts: with_people.people.iter().map(|p| p.contacts.len()).sum(),
+            patients: patients.len(),
+            medical_records: patients.iter().map(|p| p.medical_records.len()).sum(),
+            attachments: patients
+                .iter()
+                .flat_map(|p| &p.medical_records)
+                .map(|r| r.attachments.len())
+                .sum(),
+            appointments: patients.iter().map(|p| p.appointments.len()).sum(),
+        };
+
+        Ok(HouseholdDataPackage {
+            manifest: DataPackageManifest {
+                format_version: DATA_PACKAGE_FORMAT_VERSION,
+                generated_at: Utc::now(),
+                household_id,
+                include_deleted,
+                counts,
+                redacted_fields: DATA_PACKAGE_REDACTED_FIELDS.iter().map(|f| f.to_string()).collect(),
+            },
+            household: with_people.household,
+            people: with_people.people,
+            patients,
+        })
+    }
+
+    async fn linked_patient_ids(db: &DatabaseConnection, household_id: i32) -> Result<Vec<i64>, String> {
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT patient_id FROM patient_households WHERE household_id = ? ORDER BY patient_id",
+                [household_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch household patients: {}", e))?;
+        rows.iter()
+            .map(|row| row.try_get("", "patient_id").map_err(|e| e.to_string()))
+            .collect()
+    }
+}
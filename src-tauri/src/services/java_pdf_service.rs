@@ -6,6 +6,7 @@ use serde::{Serialize, Deserialize};
 use serde_json::Value;
 use std::path::PathBuf;
 use std::fs;
+use crate::services::pdf_jobs::{run_cancellable, PdfCancelToken, PDF_JOB_CANCELLED};
 
 /// Java PDF service - calls the iText 5 JAR for PDF generation
 /// This provides 100% identical output to the original print-app
@@ -58,7 +59,24 @@ impl JavaPdfService {
         output_path: &str,
         patient: &crate::services::device_pdf_service::PatientData,
         device_data_list: &[crate::services::device_pdf_service::DeviceTestData],
+        cancel: &PdfCancelToken,
     ) -> Result<(), String> {
+        // Get JAR path (in pdf-generator-cli/build/libs/)
+        let jar_path = Self::get_jar_path(app_handle)?;
+        Self::generate_pdf_multi_with_jar(&jar_path, output_path, patient, device_data_list, cancel)
+    }
+
+    /// Same as `generate_pdf_multi`, against an explicit JAR instead of the
+    /// one resolved from the app bundle (lets tests render without an AppHandle).
+    /// The JAR is killed if `cancel` is set while it runs.
+    pub fn generate_pdf_multi_with_jar(
+        jar_path: &PathBuf,
+        output_path: &str,
+        patient: &crate::services::device_pdf_service::PatientData,
+        device_data_list: &[crate::services::device_pdf_service::DeviceTestData],
+        cancel: &PdfCancelToken,
+    ) -> Result<(), String> {
+        cancel.check()?;
         log::info!("☕ Generating PDF using Java JAR (iText 5) with {} samples...", device_data_list.len());
 
         // Sort samples in the order Java expects: Exigo, Pointcare, Healvet, PCR
@@ -145,20 +163,26 @@ impl JavaPdfService {
 
         log::debug!("   📄 Input JSON created: {:?}", input_json_path);
 
-        // Get JAR path (in pdf-generator-cli/build/libs/)
-        let jar_path = Self::get_jar_path(app_handle)?;
-
         log::info!("   ☕ JAR path: {:?}", jar_path);
         log::info!("   📂 Output path: {}", output_path);
 
         // Call Java JAR
-        let output = Self::create_java_command(&jar_path, &input_json_path)
-            .output()
-            .map_err(|e| format!("Failed to execute Java: {}", e))?;
+        let output = run_cancellable(Self::create_java_command(jar_path, &input_json_path), cancel);
 
         // Clean up temp JSON file
         let _ = fs::remove_file(&input_json_path);
 
+        let output = match output {
+            Ok(output) => output,
+            Err(e) if e == PDF_JOB_CANCELLED => {
+                // A killed JAR can leave a partial PDF behind
+                let _ = fs::remove_file(output_path);
+                log::info!("   ⏹️  PDF generation cancelled");
+                return Err(e);
+            }
+            Err(e) => return Err(format!("Failed to execute Java: {}", e)),
+        };
+
         // Check if command was successful
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -454,7 +478,7 @@ impl JavaPdfService {
         patient: &crate::services::device_pdf_service::PatientData,
         device_data: &crate::services::device_pdf_service::DeviceTestData,
     ) -> Result<(), String> {
-        Self::generate_pdf_multi(app_handle, output_path, patient, &[device_data.clone()])
+        Self::generate_pdf_multi(app_handle, output_path, patient, &[device_data.clone()], &PdfCancelToken::none())
     }
 
     /// Map detailed device type names to Java-expected format
@@ -693,6 +717,215 @@ impl JavaPdfService {
         log::info!("✅ Pharmacy note PDF generated successfully");
         Ok(())
     }
+
+    /// Generate the front desk's day sheet: the day's appointments in time
+    /// order, one table per section (per room when grouped)
+    pub fn generate_day_sheet(
+        app_handle: &tauri::AppHandle,
+        output_path: &str,
+        date: &str,
+        sections: &[crate::models::DaySheetSection],
+    ) -> Result<(), String> {
+        let jar_path = Self::get_jar_path(app_handle)?;
+        Self::generate_day_sheet_with_jar(&jar_path, output_path, date, sections)
+    }
+
+    /// Same as `generate_day_sheet`, against an explicit JAR (lets tests
+    /// render without an AppHandle).
+    pub fn generate_day_sheet_with_jar(
+        jar_path: &PathBuf,
+        output_path: &str,
+        date: &str,
+        sections: &[crate::models::DaySheetSection],
+    ) -> Result<(), String> {
+        log::info!("☕ Generating day sheet PDF using Java JAR...");
+
+        let grouped = sections.iter().any(|section| section.room_id.is_some());
+        let sections_json: Vec<serde_json::Value> = sections
+            .iter()
+            .map(|section| {
+                let title = grouped.then(|| {
+                    section
+                        .room_name
+                        .clone()
+                        .unwrap_or_else(|| format!("Room {}", section.room_id.unwrap_or_default()))
+                });
+                let rows: Vec<serde_json::Value> = section
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        let notes = match &entry.notes {
+                            Some(notes) => format!("{} — {}", entry.title, notes),
+                            None => entry.title.clone(),
+                        };
+                        serde_json::json!({
+                            "time": entry.time,
+                            "patient": entry.patient_name,
+                            "owner": entry.owner,
+                            "room": entry.room_name,
+                            "status": entry.status.to_string(),
+                            "notes": notes
+                        })
+                    })
+                    .collect();
+                serde_json::json!({ "title": title, "rows": rows })
+            })
+            .collect();
+
+        let json_obj = serde_json::json!({
+            "report_type": "day_sheet",
+            "date": date,
+            "sections": sections_json,
+            "output_path": output_path
+        });
+
+        let temp_dir = std::env::temp_dir();
+        let input_json_path = temp_dir.join(format!("day_sheet_input_{}.json", Utc::now().timestamp_millis()));
+
+        let json_str = serde_json::to_string_pretty(&json_obj)
+            .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+
+        fs::write(&input_json_path, &json_str)
+            .map_err(|e| format!("Failed to write temp JSON file: {}", e))?;
+
+        log::debug!("   📄 Day sheet input JSON created: {:?}", input_json_path);
+        log::info!("   ☕ JAR path: {:?}", jar_path);
+        log::info!("   📂 Output path: {}", output_path);
+
+        let output = Self::create_java_command(jar_path, &input_json_path)
+            .output()
+            .map_err(|e| format!("Failed to execute Java: {}", e))?;
+
+        let _ = fs::remove_file(&input_json_path);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return Err(format!(
+                "Java JAR failed:\nSTDOUT: {}\nSTDERR: {}",
+                stdout, stderr
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        log::info!("   ✅ Java output: {}", stdout);
+
+        if !PathBuf::from(output_path).exists() {
+            return Err(format!("PDF was not created at: {}", output_path));
+        }
+
+        log::info!("✅ Day sheet PDF generated successfully");
+        Ok(())
+    }
+
+    /// Generate a referral export holding only the given records of one
+    /// patient. Attachments are listed by name under their record; generated
+    /// reports are marked as such rather than inlined.
+    pub fn generate_record_selection(
+        app_handle: &tauri::AppHandle,
+        output_path: &str,
+        patient: &crate::services::device_pdf_service::PatientData,
+        records: &[crate::models::MedicalRecordDetail],
+    ) -> Result<(), String> {
+        let jar_path = Self::get_jar_path(app_handle)?;
+        Self::generate_record_selection_with_jar(&jar_path, output_path, patient, records)
+    }
+
+    /// Same as `generate_record_selection`, against an explicit JAR.
+    pub fn generate_record_selection_with_jar(
+        jar_path: &PathBuf,
+        output_path: &str,
+        patient: &crate::services::device_pdf_service::PatientData,
+        records: &[crate::models::MedicalRecordDetail],
+    ) -> Result<(), String> {
+        log::info!("☕ Generating record selection PDF ({} records) using Java JAR...", records.len());
+
+        let json_obj = Self::record_selection_input(output_path, patient, records);
+
+        let temp_dir = std::env::temp_dir();
+        let input_json_path = temp_dir.join(format!("record_selection_input_{}.json", Utc::now().timestamp_millis()));
+
+        let json_str = serde_json::to_string_pretty(&json_obj)
+            .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+
+        fs::write(&input_json_path, &json_str)
+            .map_err(|e| format!("Failed to write temp JSON file: {}", e))?;
+
+        log::debug!("   📄 Record selection input JSON created: {:?}", input_json_path);
+        log::info!("   ☕ JAR path: {:?}", jar_path);
+        log::info!("   📂 Output path: {}", output_path);
+
+        let output = Self::create_java_command(jar_path, &input_json_path)
+            .output()
+            .map_err(|e| format!("Failed to execute Java: {}", e))?;
+
+        let _ = fs::remove_file(&input_json_path);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return Err(format!(
+                "Java JAR failed:\nSTDOUT: {}\nSTDERR: {}",
+                stdout, stderr
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        log::info!("   ✅ Java output: {}", stdout);
+
+        if !PathBuf::from(output_path).exists() {
+            return Err(format!("PDF was not created at: {}", output_path));
+        }
+
+        log::info!("✅ Record selection PDF generated successfully");
+        Ok(())
+    }
+
+    /// The JSON handed to the JAR for a record selection ("records" report type).
+    pub(crate) fn record_selection_input(
+        output_path: &str,
+        patient: &crate::services::device_pdf_service::PatientData,
+        records: &[crate::models::MedicalRecordDetail],
+    ) -> Value {
+        let records_json: Vec<Value> = records
+            .iter()
+            .map(|detail| {
+                let record = &detail.record;
+                let attachments: Vec<Value> = detail
+                    .attachments
+                    .iter()
+                    .map(|attachment| {
+                        serde_json::json!({
+                            "name": attachment.original_name,
+                            "generated": attachment.attachment_type.as_deref() == Some("generated_pdf")
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "date": record.created_at.format("%d/%m/%Y").to_string(),
+                    "record_type": record.record_type,
+                    "name": record.name,
+                    "description": record.description,
+                    "prescription_notes": record.prescription_notes,
+                    "attachments": attachments
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "report_type": "records",
+            "patient": {
+                "name": patient.name,
+                "owner": patient.owner,
+                "species": patient.species,
+                "microchip_id": patient.microchip_id,
+                "gender": patient.gender,
+                "date_of_birth": patient.date_of_birth
+            },
+            "records": records_json,
+            "output_path": output_path
+        })
+    }
 }
 
 /// Line item data for invoice generation
@@ -0,0 +1,287 @@
+//! CSV exports of the change logs for compliance hand-over.
+//!
+//! The audit log is `medical_record_history` (one row per record version);
+//! the sync log is `sync_logs`. Both exports resolve ids to readable names
+//! and are written row by row to the chosen file with the `csv` writer, so
+//! values like `changed_fields` ("name,description") are quoted and survive
+//! a round-trip through a spreadsheet.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, Value};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Placeholder written in place of anything that looks like a credential.
+pub const REDACTED: &str = "[REDACTED]";
+
+const AUDIT_HEADER: [&str; 8] = [
+    "changed_at",
+    "actor",
+    "patient",
+    "record",
+    "record_type",
+    "version",
+    "action",
+    "changed_fields",
+];
+
+const SYNC_HEADER: [&str; 9] = [
+    "id",
+    "started_at",
+    "completed_at",
+    "direction",
+    "sync_type",
+    "status",
+    "items_synced",
+    "items_failed",
+    "error_message",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogFilter {
+    pub patient_id: Option<i64>,
+    pub medical_record_id: Option<i64>,
+    /// Exact match on the recorded actor
+    pub changed_by: Option<String>,
+}
+
+/// Timestamps in both tables come from `CURRENT_TIMESTAMP` (UTC, no zone).
+fn sqlite_timestamp(ts: &DateTime<Utc>) -> String {
+    ts.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+type CsvFile = csv::Writer<BufWriter<File>>;
+
+fn create_csv(target_path: &Path) -> Result<CsvFile, String> {
+    let file = File::create(target_path)
+        .map_err(|e| format!("Failed to create {}: {}", target_path.display(), e))?;
+    Ok(csv::Writer::from_writer(BufWriter::new(file)))
+}
+
+fn write_csv_record<I, S>(writer: &mut CsvFile, fields: I) -> Result<(), String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[u8]>,
+{
+    writer
+        .write_record(fields)
+        .map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+fn finish_csv(mut writer: CsvFile) -> Result<(), String> {
+    writer.flush().map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+fn secret_patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        vec![
+            // Authorization headers echoed into error messages
+            (Regex::new(r"(?i)(bearer\s+)[A-Za-z0-9\-._~+/]+=*").unwrap(), "${1}[REDACTED]"),
+            // key=value / "key": "value" pairs for OAuth fields
+            (
+                Regex::new(r#"(?i)((?:access_token|refresh_token|id_token|client_secret)["']?\s*[:=]\s*["']?)[^\s"'&,}]+"#).unwrap(),
+                "${1}[REDACTED]",
+            ),
+            // OAuth authorization codes in redirect URLs
+            (Regex::new(r"([?&]code=)[^\s&]+").unwrap(), "${1}[REDACTED]"),
+            // Bare Google access / refresh tokens
+            (Regex::new(r"ya29\.[A-Za-z0-9\-_.]+").unwrap(), REDACTED),
+            (Regex::new(r"1//[A-Za-z0-9\-_]+").unwrap(), REDACTED),
+        ]
+    })
+}
+
+/// Strip OAuth tokens and secrets from free text before it leaves the app.
+pub fn redact_secrets(text: &str) -> String {
+    let mut out = text.to_string();
+    for (pattern, replacement) in secret_patterns() {
+        out = pattern.replace_all(&out, *replacement).into_owned();
+    }
+    out
+}
+
+fn push_range_clauses(
+    column: &str,
+    from: Option<&DateTime<Utc>>,
+    to: Option<&DateTime<Utc>>,
+    clauses: &mut Vec<String>,
+    values: &mut Vec<Value>,
+) {
+    if let Some(from) = from {
+        clauses.push(format!("{} >= ?", column));
+        values.push(sqlite_timestamp(from).into());
+    }
+    if let Some(to) = to {
+        clauses.push(format!("{} < ?", column));
+        values.push(sqlite_timestamp(to).into());
+    }
+}
+
+fn opt_string(row: &sea_orm::QueryResult, col: &str) -> Option<String> {
+    row.try_get::<Option<String>>("", col).ok().flatten()
+}
+
+fn opt_i64(row: &sea_orm::QueryResult, col: &str) -> Option<i64> {
+    row.try_get::<Option<i64>>("", col).ok().flatten()
+}
+
+pub struct LogExportService;
+
+impl LogExportService {
+    /// Write medical record history in `[from, to)` to `target_path` as
+    /// CSV, oldest first. Returns the number of rows written.
+    pub async fn export_audit_log_csv(
+        db: &DatabaseConnection,
+        target_path: &Path,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        filter: &AuditLogFilter,
+    ) -> Result<usize, String> {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        push_range_clauses("h.changed_at", from.as_ref(), to.as_ref(), &mut clauses, &mut values);
+        if let Some(patient_id) = filter.patient_id {
+            clauses.push("mr.patient_id = ?".to_string());
+            values.push(patient_id.into());
+        }
+        if let Some(record_id) = filter.medical_record_id {
+            clauses.push("h.medical_record_id = ?".to_string());
+            values.push(record_id.into());
+        }
+        if let Some(changed_by) = &filter.changed_by {
+            clauses.push("h.changed_by = ?".to_string());
+            values.push(changed_by.clone().into());
+        }
+
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", clauses.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT h.medical_record_id, h.version, h.changed_fields, h.changed_by, \
+             CAST(h.changed_at AS TEXT) AS changed_at, \
+             mr.name AS record_name, mr.record_type, mr.patient_id, p.name AS patient_name, \
+             u.name AS user_name \
+             FROM medical_record_history h \
+             LEFT JOIN medical_records mr ON mr.id = h.medical_record_id \
+             LEFT JOIN patients p ON p.id = mr.patient_id \
+             LEFT JOIN users u ON CAST(u.id AS TEXT) = h.changed_by{} \
+             ORDER BY h.changed_at ASC, h.id ASC",
+            where_sql
+        );
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, values))
+            .await
+            .map_err(|e| format!("Failed to fetch audit log: {}", e))?;
+
+        let mut writer = create_csv(target_path)?;
+        write_csv_record(&mut writer, AUDIT_HEADER)?;
+        for row in &rows {
+            let record_id: i64 = row.try_get("", "medical_record_id").map_err(|e| e.to_string())?;
+            let version = opt_i64(row, "version").map(|v| v.to_string()).unwrap_or_default();
+            let changed_fields = opt_string(row, "changed_fields").unwrap_or_default();
+            // The initial snapshot is tagged "created" rather than listing fields
+            let (action, fields) = if changed_fields == "created" {
+                ("created", String::new())
+            } else {
+                ("updated", changed_fields)
+            };
+            // Staff users are stored by id; older rows hold a free-text name
+            let changed_by = opt_string(row, "changed_by");
+            let actor = match (opt_string(row, "user_name"), changed_by) {
+                (Some(name), Some(id)) => format!("{} (#{})", name, id),
+                (None, Some(changed_by)) => changed_by,
+                _ => "system".to_string(),
+            };
+            let patient = match (opt_string(row, "patient_name"), opt_i64(row, "patient_id")) {
+                (Some(name), Some(id)) => format!("{} (#{})", name, id),
+                (None, Some(id)) => format!("#{}", id),
+                _ => String::new(),
+            };
+            let record = match opt_string(row, "record_name") {
+                Some(name) => format!("{} (#{})", name, record_id),
+                None => format!("#{}", record_id),
+            };
+
+            write_csv_record(
+                &mut writer,
+                [
+                    opt_string(row, "changed_at").unwrap_or_default(),
+                    actor,
+                    patient,
+                    record,
+                    opt_string(row, "record_type").unwrap_or_default(),
+                    version,
+                    action.to_string(),
+                    fields,
+                ],
+            )?;
+        }
+        finish_csv(writer)?;
+        Ok(rows.len())
+    }
+
+    /// Write sync runs started in `[from, to)` to `target_path` as CSV,
+    /// oldest first, with credentials redacted from error messages. Returns
+    /// the number of rows written.
+    pub async fn export_sync_log_csv(
+        db: &DatabaseConnection,
+        target_path: &Path,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<usize, String> {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        push_range_clauses("started_at", from.as_ref(), to.as_ref(), &mut clauses, &mut values);
+
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", clauses.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT id, direction, sync_type, status, items_synced, items_failed, error_message, \
+             CAST(started_at AS TEXT) AS started_at, CAST(completed_at AS TEXT) AS completed_at \
+             FROM sync_logs{} ORDER BY started_at ASC, id ASC",
+            where_sql
+        );
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, values))
+            .await
+            .map_err(|e| format!("Failed to fetch sync logs: {}", e))?;
+
+        let mut writer = create_csv(target_path)?;
+        write_csv_record(&mut writer, SYNC_HEADER)?;
+        for row in &rows {
+            let id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            write_csv_record(
+                &mut writer,
+                [
+                    id.to_string(),
+                    opt_string(row, "started_at").unwrap_or_default(),
+                    opt_string(row, "completed_at").unwrap_or_default(),
+                    opt_string(row, "direction").unwrap_or_default(),
+                    opt_string(row, "sync_type").unwrap_or_default(),
+                    opt_string(row, "status").unwrap_or_default(),
+                    opt_i64(row, "items_synced").unwrap_or(0).to_string(),
+                    opt_i64(row, "items_failed").unwrap_or(0).to_string(),
+                    opt_string(row, "error_message").map(|m| redact_secrets(&m)).unwrap_or_default(),
+                ],
+            )?;
+        }
+        finish_csv(writer)?;
+        Ok(rows.len())
+    }
+}
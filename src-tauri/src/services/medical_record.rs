@@ -1,10 +1,44 @@
 use sea_orm::*;
 use crate::models::medical::*;
 use crate::models::dto::MaybeNull;
-use chrono::{Utc, DateTime};
+use crate::services::patient::{PatientService, PRIMARY_OWNER_JOIN};
+use crate::services::settings::SettingsService;
+use crate::models::user::User;
+use crate::services::users::UserService;
+use chrono::{Utc, DateTime, NaiveDate};
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[cfg(test)]
+thread_local! {
+    /// Attachment queries issued on this thread. Lets tests assert that list
+    /// loading stays at one attachment query per page, whatever the page size.
+    pub(crate) static ATTACHMENT_QUERY_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// `changed_by` on records archived by the auto-archive policy.
+const AUTO_ARCHIVE_ACTOR: &str = "auto_archive";
+/// How often the auto-archive scheduler wakes up.
+const AUTO_ARCHIVE_CHECK_SECS: u64 = 6 * 60 * 60;
+const PENDING_FOLLOW_UP: &str = "follow_up_date IS NOT NULL AND follow_up_done = 0";
 
 // T027: MedicalRecordService with CRUD operations
+/// Map a `record_templates` row to RecordTemplate
+pub fn row_to_record_template(row: &sea_orm::QueryResult) -> Result<RecordTemplate, String> {
+    Ok(RecordTemplate {
+        id: row.try_get("", "id").map_err(|e| format!("Failed to get id: {}", e))?,
+        record_type: row.try_get("", "record_type").map_err(|e| format!("Failed to get record_type: {}", e))?,
+        title: row.try_get("", "title").map_err(|e| format!("Failed to get title: {}", e))?,
+        description: row.try_get("", "description").map_err(|e| format!("Failed to get description: {}", e))?,
+        price: row.try_get("", "price").ok(),
+        currency_id: row.try_get("", "currency_id").ok(),
+        created_at: row.try_get("", "created_at").map_err(|e| format!("Failed to get created_at: {}", e))?,
+        updated_at: row.try_get("", "updated_at").map_err(|e| format!("Failed to get updated_at: {}", e))?,
+    })
+}
+
+
 pub struct MedicalRecordService;
 
 impl MedicalRecordService {
@@ -21,33 +55,12 @@ impl MedicalRecordService {
         // Build the query based on filters
         let mut sql = String::from(
             "SELECT id, patient_id, record_type, name, procedure_name, description, \
-             prescription_notes, price, currency_id, is_archived, version, created_at, updated_at, \
-             created_by, updated_by \
+             prescription_notes, price, currency_id, is_archived, follow_up_date, follow_up_done, \
+             version, created_at, updated_at, created_by, updated_by \
              FROM medical_records WHERE patient_id = ?"
         );
         let mut params: Vec<Value> = vec![patient_id.into()];
-
-        if let Some(ref f) = filter {
-            if let Some(ref record_type) = f.record_type {
-                sql.push_str(" AND record_type = ?");
-                params.push(record_type.clone().into());
-            }
-            if let Some(is_archived) = f.is_archived {
-                sql.push_str(" AND is_archived = ?");
-                params.push((if is_archived { 1i32 } else { 0i32 }).into());
-            }
-            // NOTE: search_term is intentionally NOT applied here. SQLite
-            // LIKE only case-folds ASCII, so a Cyrillic search term wouldn't
-            // match Cyrillic record text differing in case. We fetch the
-            // patient's records (filtered by the exact-match record_type /
-            // is_archived above) and apply the search + pagination in Rust
-            // below with Unicode-aware to_lowercase(). Per-patient record
-            // counts are small, so the full fetch is cheap.
-        } else {
-            // Default to not showing archived records
-            sql.push_str(" AND is_archived = 0");
-        }
-
+        Self::push_filter_clauses(&filter, &mut sql, &mut params);
         sql.push_str(" ORDER BY created_at DESC");
 
         log::debug!("Executing safe parameterized query for patient_id: {}", patient_id);
@@ -62,84 +75,20 @@ impl MedicalRecordService {
 
         log::debug!("Got {} rows from database", rows.len());
 
-        let mut records = Vec::new();
-        for row in rows {
-            log::trace!("Processing row...");
-            let record_id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
-            let is_archived_int: i64 = row.try_get("", "is_archived").unwrap_or(0);
-            let created_at_str: Option<String> = row.try_get("", "created_at").ok();
-            let updated_at_str: Option<String> = row.try_get("", "updated_at").ok();
-
-            let created_at = created_at_str
-                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|| Utc::now());
-
-            let updated_at = updated_at_str
-                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|| Utc::now());
-
-            // Handle price as either integer or float
-            let price: Option<f64> = row.try_get::<i64>("", "price")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "price").ok());
-
-            // Attachments are fetched after search-filtering + pagination
-            // (below) so we only hit the DB for the records actually
-            // returned on this page, not the whole patient history.
-
-            // Handle discount_percent as either integer or float
-            let discount_percent: Option<f64> = row.try_get::<i64>("", "discount_percent")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "discount_percent").ok());
-
-            // Handle manual_total as either integer or float
-            let manual_total: Option<f64> = row.try_get::<i64>("", "manual_total")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "manual_total").ok());
-
-            records.push(MedicalRecord {
-                id: record_id,
-                patient_id: row.try_get("", "patient_id").unwrap_or(0),
-                record_type: row.try_get("", "record_type").unwrap_or_default(),
-                name: row.try_get("", "name").unwrap_or_default(),
-                procedure_name: row.try_get("", "procedure_name").ok(),
-                description: row.try_get("", "description").unwrap_or_default(),
-                prescription_notes: row.try_get("", "prescription_notes").ok(),
-                price,
-                currency_id: row.try_get("", "currency_id").ok(),
-                discount_percent,
-                manual_total,
-                invoice_number: row.try_get("", "invoice_number").ok(),
-                is_archived: is_archived_int != 0,
-                version: row.try_get("", "version").unwrap_or(1),
-                created_at,
-                updated_at,
-                created_by: row.try_get("", "created_by").ok(),
-                updated_by: row.try_get("", "updated_by").ok(),
-                attachments: None, // filled in below, only for the paged slice
-                line_items: None, // Line items are only loaded in get_medical_record detail view
-            });
-        }
+        // Attachments are fetched after search-filtering + pagination
+        // (below) so we only hit the DB for the records actually
+        // returned on this page, not the whole patient history.
+        let mut records: Vec<MedicalRecord> = rows.iter().map(Self::record_from_row).collect::<Result<_, _>>()?;
 
         log::debug!("Fetched {} records before search filter", records.len());
 
         // Cyrillic-safe search filter (SQLite LIKE is ASCII-only). Matches
         // the same two fields the old SQL LIKE covered (name, description).
-        if let Some(ref f) = filter {
-            if let Some(ref search_term) = f.search_term {
-                let needle = search_term.trim().to_lowercase();
-                if !needle.is_empty() {
-                    records.retain(|r| {
-                        r.name.to_lowercase().contains(&needle)
-                            || r.description.to_lowercase().contains(&needle)
-                    });
-                }
-            }
+        if let Some(needle) = Self::search_needle(&filter) {
+            records.retain(|r| {
+                r.name.to_lowercase().contains(&needle)
+                    || r.description.to_lowercase().contains(&needle)
+            });
         }
 
         // Total is the filtered count; paginate the in-memory list.
@@ -151,61 +100,339 @@ impl MedicalRecordService {
             .take(page_size as usize)
             .collect();
 
-        // Fetch attachments only for the records on this page.
+        // Fetch attachments only for the records on this page, in one query.
+        let page_ids: Vec<i64> = page_records.iter().map(|r| r.id).collect();
+        let mut attachments_by_record = Self::fetch_attachments_for_records(db, &page_ids)
+            .await
+            .unwrap_or_default();
         for record in page_records.iter_mut() {
-            let attachments = Self::fetch_attachments(db, record.id).await.unwrap_or_default();
-            record.attachments = if attachments.is_empty() { None } else { Some(attachments) };
+            record.attachments = attachments_by_record.remove(&record.id);
         }
 
         log::debug!("Returning {} of {} records (page {})", page_records.len(), total, page);
 
+        let (total_pages, has_next, has_previous) = page_metadata(total, page, page_size);
         Ok(MedicalRecordsResponse {
             records: page_records,
             total,
             page,
             page_size,
+            total_pages,
+            has_next,
+            has_previous,
+        })
+    }
+
+    /// Columns a caller may request from `get_medical_record_summaries`.
+    /// `id` and `record_type` are always included.
+    pub const SUMMARY_FIELDS: &'static [&'static str] = &[
+        "name",
+        "procedure_name",
+        "description",
+        "prescription_notes",
+        "price",
+        "currency_id",
+        "discount_percent",
+        "manual_total",
+        "is_archived",
+        "created_at",
+        "updated_at",
+    ];
+
+    /// Same filtering, ordering and paging as `get_medical_records`, but only
+    /// the requested columns are read and attachments are never loaded —
+    /// list views that show name/price don't pay for descriptions or the
+    /// per-record attachment query.
+    pub async fn get_medical_record_summaries(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        filter: Option<MedicalRecordFilter>,
+        pagination: Option<PaginationParams>,
+        fields: &[String],
+    ) -> Result<MedicalRecordSummariesResponse, String> {
+        let mut requested: Vec<&str> = Vec::new();
+        for field in fields {
+            let Some(known) = Self::SUMMARY_FIELDS.iter().find(|f| **f == field.as_str()) else {
+                return Err(format!("Unknown field: {}", field));
+            };
+            if !requested.contains(known) {
+                requested.push(*known);
+            }
+        }
+
+        let page = pagination.as_ref().and_then(|p| p.page).unwrap_or(1);
+        let page_size = pagination.as_ref().and_then(|p| p.page_size).unwrap_or(50);
+        let offset = ((page - 1) * page_size) as i64;
+
+        // The search filter runs in Rust over name + description, so those
+        // are read when searching even if they aren't returned.
+        let needle = Self::search_needle(&filter);
+        let mut columns: Vec<&str> = vec!["id", "record_type"];
+        columns.extend(requested.iter().copied());
+        if needle.is_some() {
+            for c in ["name", "description"] {
+                if !columns.contains(&c) {
+                    columns.push(c);
+                }
+            }
+        }
+
+        let mut sql = format!(
+            "SELECT {} FROM medical_records WHERE patient_id = ?",
+            columns.join(", ")
+        );
+        let mut params: Vec<Value> = vec![patient_id.into()];
+        Self::push_filter_clauses(&filter, &mut sql, &mut params);
+        sql.push_str(" ORDER BY created_at DESC");
+
+        let mut rows = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, params))
+            .await
+            .map_err(|e| format!("Failed to fetch medical records: {}", e))?;
+
+        if let Some(needle) = needle {
+            rows.retain(|row| {
+                let name: String = row.try_get("", "name").unwrap_or_default();
+                let description: String = row.try_get("", "description").unwrap_or_default();
+                name.to_lowercase().contains(&needle) || description.to_lowercase().contains(&needle)
+            });
+        }
+
+        let total = rows.len() as i64;
+        let wants = |f: &str| requested.contains(&f);
+
+        let records = rows
+            .iter()
+            .skip(offset.max(0) as usize)
+            .take(page_size as usize)
+            .map(|row| MedicalRecordSummary {
+                id: row.try_get("", "id").unwrap_or(0),
+                record_type: row.try_get("", "record_type").unwrap_or_default(),
+                name: if wants("name") { row.try_get("", "name").ok() } else { None },
+                procedure_name: if wants("procedure_name") { row.try_get("", "procedure_name").ok() } else { None },
+                description: if wants("description") { row.try_get("", "description").ok() } else { None },
+                prescription_notes: if wants("prescription_notes") { row.try_get("", "prescription_notes").ok() } else { None },
+                price: if wants("price") { read_f64(row, "price") } else { None },
+                currency_id: if wants("currency_id") { row.try_get("", "currency_id").ok() } else { None },
+                discount_percent: if wants("discount_percent") { read_f64(row, "discount_percent") } else { None },
+                manual_total: if wants("manual_total") { read_f64(row, "manual_total") } else { None },
+                is_archived: if wants("is_archived") {
+                    row.try_get::<i64>("", "is_archived").ok().map(|v| v != 0)
+                } else {
+                    None
+                },
+                created_at: if wants("created_at") { row.try_get("", "created_at").ok() } else { None },
+                updated_at: if wants("updated_at") { row.try_get("", "updated_at").ok() } else { None },
+            })
+            .collect();
+
+        let (total_pages, has_next, has_previous) = page_metadata(total, page, page_size);
+        Ok(MedicalRecordSummariesResponse {
+            records,
+            total,
+            page,
+            page_size,
+            total_pages,
+            has_next,
+            has_previous,
         })
     }
 
+    /// Append the exact-match filter clauses shared by the record list queries.
+    fn push_filter_clauses(filter: &Option<MedicalRecordFilter>, sql: &mut String, params: &mut Vec<Value>) {
+        if let Some(ref f) = filter {
+            if let Some(ref record_type) = f.record_type {
+                sql.push_str(" AND record_type = ?");
+                params.push(record_type.clone().into());
+            }
+            if let Some(is_archived) = f.is_archived {
+                sql.push_str(" AND is_archived = ?");
+                params.push((if is_archived { 1i32 } else { 0i32 }).into());
+            }
+            // NOTE: search_term is intentionally NOT applied here. SQLite
+            // LIKE only case-folds ASCII, so a Cyrillic search term wouldn't
+            // match Cyrillic record text differing in case. Callers fetch the
+            // patient's records (filtered by the exact-match record_type /
+            // is_archived above) and apply the search + pagination in Rust
+            // with Unicode-aware to_lowercase(). Per-patient record counts
+            // are small, so the full fetch is cheap.
+        } else {
+            // Default to not showing archived records
+            sql.push_str(" AND is_archived = 0");
+        }
+    }
+
+    /// Lowercased, trimmed search term, or None when there's nothing to match.
+    fn search_needle(filter: &Option<MedicalRecordFilter>) -> Option<String> {
+        let needle = filter.as_ref()?.search_term.as_ref()?.trim().to_lowercase();
+        if needle.is_empty() { None } else { Some(needle) }
+    }
+
     /// Helper to fetch attachments for a medical record
     async fn fetch_attachments(db: &DatabaseConnection, record_id: i64) -> Result<Vec<MedicalAttachment>, String> {
+        let mut by_record = Self::fetch_attachments_for_records(db, &[record_id]).await?;
+        Ok(by_record.remove(&record_id).unwrap_or_default())
+    }
+
+    /// Fetch attachments for several records in a single query, grouped by
+    /// record id. Records without attachments have no entry in the map.
+    async fn fetch_attachments_for_records(
+        db: &DatabaseConnection,
+        record_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<MedicalAttachment>>, String> {
+        let mut by_record: HashMap<i64, Vec<MedicalAttachment>> = HashMap::new();
+        if record_ids.is_empty() {
+            return Ok(by_record);
+        }
+
+        #[cfg(test)]
+        ATTACHMENT_QUERY_COUNT.with(|c| c.set(c.get() + 1));
+
+        let placeholders = vec!["?"; record_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT id, medical_record_id, file_id, original_name, mime_type, \
+             file_size, uploaded_at, device_type, device_name, connection_method, attachment_type, \
+             sort_order, is_external, external_path \
+             FROM medical_attachments WHERE medical_record_id IN ({}) \
+             ORDER BY medical_record_id, sort_order, id",
+            placeholders
+        );
+        let params: Vec<Value> = record_ids.iter().map(|id| (*id).into()).collect();
+        let rows = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, params))
+            .await
+            .map_err(|e| format!("Failed to fetch attachments: {}", e))?;
+
+        for attachment in rows.iter().filter_map(Self::attachment_from_row) {
+            by_record
+                .entry(attachment.medical_record_id)
+                .or_default()
+                .push(attachment);
+        }
+
+        Ok(by_record)
+    }
+
+    /// Every attachment on any of the patient's records, newest first, with
+    /// the parent record's name/type/date. Attachments on archived records
+    /// are skipped unless `include_archived` is set.
+    pub async fn get_patient_attachments(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        filter: Option<PatientAttachmentFilter>,
+        pagination: Option<PaginationParams>,
+    ) -> Result<PatientAttachmentsResponse, String> {
+        let filter = filter.unwrap_or_default();
+        let page = pagination.as_ref().and_then(|p| p.page).unwrap_or(1).max(1);
+        let page_size = pagination.as_ref().and_then(|p| p.page_size).unwrap_or(50).max(1);
+        let offset = ((page - 1) * page_size) as i64;
+
+        let mut where_sql = String::from(" WHERE mr.patient_id = ?");
+        let mut params: Vec<Value> = vec![patient_id.into()];
+        if !filter.include_archived.unwrap_or(false) {
+            where_sql.push_str(" AND mr.is_archived = 0");
+        }
+        if let Some(ref mime) = filter.mime_type {
+            match mime.strip_suffix("/*") {
+                Some(family) => {
+                    where_sql.push_str(" AND ma.mime_type LIKE ? ESCAPE '\\'");
+                    params.push(format!("{}/%", escape_like(family)).into());
+                }
+                None => {
+                    where_sql.push_str(" AND ma.mime_type = ?");
+                    params.push(mime.clone().into());
+                }
+            }
+        }
+        if let Some(ref attachment_type) = filter.attachment_type {
+            where_sql.push_str(" AND ma.attachment_type = ?");
+            params.push(attachment_type.clone().into());
+        }
+
+        let from_sql = " FROM medical_attachments ma \
+                        JOIN medical_records mr ON mr.id = ma.medical_record_id";
+
+        let total: i64 = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT COUNT(*) AS total{}{}", from_sql, where_sql),
+                params.clone(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to count attachments: {}", e))?
+            .and_then(|row| row.try_get("", "total").ok())
+            .unwrap_or(0);
+
+        let mut page_params = params;
+        page_params.push((page_size as i64).into());
+        page_params.push(offset.into());
         let rows = db
             .query_all(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
-                "SELECT id, medical_record_id, file_id, original_name, mime_type, \
-                 file_size, uploaded_at, device_type, device_name, connection_method, attachment_type \
-                 FROM medical_attachments WHERE medical_record_id = ?",
-                [record_id.into()],
+                &format!(
+                    "SELECT ma.id, ma.medical_record_id, ma.file_id, ma.original_name, ma.mime_type, \
+                     ma.file_size, ma.uploaded_at, ma.device_type, ma.device_name, ma.connection_method, \
+                     ma.attachment_type, ma.sort_order, ma.is_external, ma.external_path, \
+                     mr.name AS record_name, mr.record_type, mr.is_archived AS record_is_archived, \
+                     mr.created_at AS record_created_at{}{} \
+                     ORDER BY ma.uploaded_at DESC, ma.id DESC LIMIT ? OFFSET ?",
+                    from_sql, where_sql
+                ),
+                page_params,
             ))
             .await
-            .map_err(|e| format!("Failed to fetch attachments: {}", e))?;
+            .map_err(|e| format!("Failed to fetch patient attachments: {}", e))?;
 
-        let attachments: Vec<MedicalAttachment> = rows
+        let attachments = rows
             .iter()
             .filter_map(|row| {
-                let uploaded_at_str: Option<String> = row.try_get("", "uploaded_at").ok();
-                let uploaded_at = uploaded_at_str
-                    .as_deref()
-                    .map(Self::parse_datetime)
-                    .unwrap_or_else(Utc::now);
-
-                Some(MedicalAttachment {
-                    id: row.try_get("", "id").ok()?,
-                    medical_record_id: row.try_get("", "medical_record_id").ok()?,
-                    file_id: row.try_get("", "file_id").ok()?,
-                    original_name: row.try_get("", "original_name").ok()?,
-                    file_size: row.try_get("", "file_size").ok(),
-                    mime_type: row.try_get("", "mime_type").ok(),
-                    uploaded_at,
-                    device_type: row.try_get("", "device_type").ok(),
-                    device_name: row.try_get("", "device_name").ok(),
-                    connection_method: row.try_get("", "connection_method").ok(),
-                    attachment_type: row.try_get("", "attachment_type").ok(),
+                let attachment = Self::attachment_from_row(row)?;
+                let is_archived: i64 = row.try_get("", "record_is_archived").unwrap_or(0);
+                let created_at: Option<String> = row.try_get("", "record_created_at").ok();
+                Some(PatientAttachment {
+                    attachment,
+                    record_name: row.try_get("", "record_name").unwrap_or_default(),
+                    record_type: row.try_get("", "record_type").unwrap_or_default(),
+                    record_is_archived: is_archived != 0,
+                    record_created_at: created_at
+                        .as_deref()
+                        .map(Self::parse_datetime)
+                        .unwrap_or_else(Utc::now),
                 })
             })
             .collect();
 
-        Ok(attachments)
+        Ok(PatientAttachmentsResponse {
+            attachments,
+            total,
+            page,
+            page_size,
+        })
+    }
+
+    fn attachment_from_row(row: &QueryResult) -> Option<MedicalAttachment> {
+        let uploaded_at_str: Option<String> = row.try_get("", "uploaded_at").ok();
+        let uploaded_at = uploaded_at_str
+            .as_deref()
+            .map(Self::parse_datetime)
+            .unwrap_or_else(Utc::now);
+
+        Some(MedicalAttachment {
+            id: row.try_get("", "id").ok()?,
+            medical_record_id: row.try_get("", "medical_record_id").ok()?,
+            file_id: row.try_get("", "file_id").ok()?,
+            original_name: row.try_get("", "original_name").ok()?,
+            file_size: row.try_get("", "file_size").ok(),
+            mime_type: row.try_get("", "mime_type").ok(),
+            uploaded_at,
+            device_type: row.try_get("", "device_type").ok(),
+            device_name: row.try_get("", "device_name").ok(),
+            connection_method: row.try_get("", "connection_method").ok(),
+            attachment_type: row.try_get("", "attachment_type").ok(),
+            sort_order: row.try_get("", "sort_order").unwrap_or(0),
+            is_external: row.try_get::<i64>("", "is_external").map(|v| v != 0).unwrap_or(false),
+            external_path: row.try_get("", "external_path").ok(),
+        })
     }
 
     /// Helper to parse datetime strings
@@ -233,7 +460,7 @@ impl MedicalRecordService {
                 DbBackend::Sqlite,
                 "SELECT id, patient_id, record_type, name, procedure_name, description, \
                  prescription_notes, price, currency_id, discount_percent, manual_total, \
-                 is_archived, version, created_at, updated_at, \
+                 is_archived, follow_up_date, follow_up_done, version, created_at, updated_at, \
                  created_by, updated_by \
                  FROM medical_records WHERE id = ?",
                 [record_id.into()],
@@ -242,65 +469,13 @@ impl MedicalRecordService {
             .map_err(|e| format!("Failed to fetch medical record: {}", e))?
             .ok_or("Medical record not found".to_string())?;
 
-        let is_archived_int: i64 = row.try_get("", "is_archived").unwrap_or(0);
-        let created_at_str: Option<String> = row.try_get("", "created_at").ok();
-        let updated_at_str: Option<String> = row.try_get("", "updated_at").ok();
-
-        let created_at = created_at_str
-            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|| Utc::now());
-
-        let updated_at = updated_at_str
-            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|| Utc::now());
-
-        // Handle price as either integer or float
-        let price: Option<f64> = row.try_get::<i64>("", "price")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| row.try_get::<f64>("", "price").ok());
-
-        // Handle discount_percent as either integer or float
-        let discount_percent: Option<f64> = row.try_get::<i64>("", "discount_percent")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| row.try_get::<f64>("", "discount_percent").ok());
-
-        // Handle manual_total as either integer or float
-        let manual_total: Option<f64> = row.try_get::<i64>("", "manual_total")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| row.try_get::<f64>("", "manual_total").ok());
-
         // Get line items using the LineItemService
         let line_items = crate::services::line_item::LineItemService::get_line_items_for_record(db, record_id)
             .await
             .unwrap_or_default();
 
-        let record = MedicalRecord {
-            id: row.try_get("", "id").unwrap_or(0),
-            patient_id: row.try_get("", "patient_id").unwrap_or(0),
-            record_type: row.try_get("", "record_type").unwrap_or_default(),
-            name: row.try_get("", "name").unwrap_or_default(),
-            procedure_name: row.try_get("", "procedure_name").ok(),
-            description: row.try_get("", "description").unwrap_or_default(),
-            prescription_notes: row.try_get("", "prescription_notes").ok(),
-            price,
-            currency_id: row.try_get("", "currency_id").ok(),
-            discount_percent,
-            manual_total,
-            invoice_number: row.try_get("", "invoice_number").ok(),
-            is_archived: is_archived_int != 0,
-            version: row.try_get("", "version").unwrap_or(1),
-            created_at,
-            updated_at,
-            created_by: row.try_get("", "created_by").ok(),
-            updated_by: row.try_get("", "updated_by").ok(),
-            attachments: None,
-            line_items: if line_items.is_empty() { None } else { Some(line_items) },
-        };
+        let mut record = Self::record_from_row(&row)?;
+        record.line_items = if line_items.is_empty() { None } else { Some(line_items) };
 
         // Get attachments using helper
         let attachments = Self::fetch_attachments(db, record_id).await.unwrap_or_default();
@@ -319,6 +494,61 @@ impl MedicalRecordService {
         })
     }
 
+    /// The chosen records of one patient with their attachments, oldest
+    /// first, for a targeted referral export. Fails without loading anything
+    /// if an id is unknown or belongs to another patient.
+    pub async fn get_records_for_export(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        record_ids: &[i64],
+    ) -> Result<Vec<MedicalRecordDetail>, String> {
+        let mut ids: Vec<i64> = record_ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Err("No records selected for export".to_string());
+        }
+
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let mut values: Vec<Value> = vec![patient_id.into()];
+        values.extend(ids.iter().map(|id| Value::from(*id)));
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT id FROM medical_records WHERE patient_id = ? AND id IN ({})",
+                    placeholders
+                ),
+                values,
+            ))
+            .await
+            .map_err(|e| format!("Failed to check records: {}", e))?;
+        let owned: std::collections::HashSet<i64> = rows
+            .iter()
+            .filter_map(|row| row.try_get::<i64>("", "id").ok())
+            .collect();
+
+        let foreign: Vec<String> = ids
+            .iter()
+            .filter(|id| !owned.contains(id))
+            .map(|id| id.to_string())
+            .collect();
+        if !foreign.is_empty() {
+            return Err(format!(
+                "Records {} do not belong to patient {}",
+                foreign.join(", "),
+                patient_id
+            ));
+        }
+
+        let mut details = Vec::with_capacity(ids.len());
+        for id in ids {
+            details.push(Self::get_medical_record(db, id, false).await?);
+        }
+        details.sort_by_key(|detail| (detail.record.created_at, detail.record.id));
+        Ok(details)
+    }
+
     /// Helper to fetch history for a medical record
     async fn fetch_history(db: &DatabaseConnection, record_id: i64) -> Result<Vec<MedicalRecordHistory>, String> {
         let rows = db
@@ -359,15 +589,16 @@ impl MedicalRecordService {
         Ok(history)
     }
 
-    pub async fn create_medical_record(
-        app_handle: &tauri::AppHandle,
+    /// Insert a record with its line items and initial history snapshot,
+    /// attributed to the current user. Creating a record through
+    /// `create_medical_record` also generates its PDFs.
+    pub async fn insert_medical_record(
         db: &DatabaseConnection,
-        input: CreateMedicalRecordInput,
+        input: &CreateMedicalRecordInput,
+        now: DateTime<Utc>,
+        actor: Option<&User>,
     ) -> Result<MedicalRecord, String> {
-        log::debug!("Creating medical record with input: device_test_data={:?}, device_type={:?}, device_name={:?}",
-            input.device_test_data.is_some(), input.device_type, input.device_name);
-
-        let now = Utc::now();
+        let actor = UserService::actor_id(actor);
 
         // Development: do not populate procedure_name; use name as the single source of truth
         let procedure_name: Option<String> = None;
@@ -378,8 +609,8 @@ impl MedicalRecordService {
                 "INSERT INTO medical_records \
                  (patient_id, record_type, name, procedure_name, description, \
                   prescription_notes, price, currency_id, discount_percent, manual_total, \
-                  is_archived, version, created_at, updated_at) \
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, 1, ?, ?)",
+                  is_archived, version, created_at, updated_at, created_by, updated_by) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, 1, ?, ?, ?, ?)",
                 [
                     input.patient_id.into(),
                     input.record_type.clone().into(),
@@ -393,6 +624,8 @@ impl MedicalRecordService {
                     input.manual_total.into(),
                     now.to_rfc3339().into(),
                     now.to_rfc3339().into(),
+                    actor.clone().into(),
+                    actor.clone().into(),
                 ],
             ))
             .await
@@ -417,22 +650,24 @@ impl MedicalRecordService {
         let record = MedicalRecord {
             id,
             patient_id: input.patient_id,
-            record_type: input.record_type,
-            name: input.name,
+            record_type: input.record_type.clone(),
+            name: input.name.clone(),
             procedure_name,
-            description: input.description,
-            prescription_notes: input.prescription_notes,
+            description: input.description.clone(),
+            prescription_notes: input.prescription_notes.clone(),
             price: input.price,
             currency_id: input.currency_id,
             discount_percent: input.discount_percent,
             manual_total: input.manual_total,
             invoice_number: None,
             is_archived: false,
+            follow_up_date: None,
+            follow_up_done: false,
             version: 1,
             created_at: now,
             updated_at: now,
-            created_by: None,
-            updated_by: None,
+            created_by: actor.clone(),
+            updated_by: actor.clone(),
             attachments: None,
             line_items,
         };
@@ -461,13 +696,30 @@ impl MedicalRecordService {
                     "created".into(),
                     Value::String(None),
                     new_snapshot.to_string().into(),
-                    Value::String(None),
+                    actor.into(),
                 ],
             ))
             .await;
 
         log::debug!("History snapshot insert (create): rec_id={}, version=1, fields=created, new={}", id, new_snapshot);
 
+        Ok(record)
+    }
+
+    pub async fn create_medical_record(
+        app_handle: &tauri::AppHandle,
+        db: &DatabaseConnection,
+        input: CreateMedicalRecordInput,
+        actor: Option<&User>,
+    ) -> Result<MedicalRecord, String> {
+        log::debug!("Creating medical record with input: device_test_data={:?}, device_type={:?}, device_name={:?}",
+            input.device_test_data.is_some(), input.device_type, input.device_name);
+
+        let now = Utc::now();
+
+        let record = Self::insert_medical_record(db, &input, now, actor).await?;
+        let id = record.id;
+
         // Generate PDFs if device test data is present
         log::debug!("[PDF] Checking for device data: device_test_data (legacy)={}, device_data_list={}",
             input.device_test_data.is_some(), input.device_data_list.is_some());
@@ -857,8 +1109,25 @@ impl MedicalRecordService {
         Ok(())
     }
 
+    /// Owner line for generated PDFs, from the patient's primary contact.
+    /// Empty when there is no contact (or the lookup fails), so the PDF
+    /// skips the owner row instead of printing a placeholder.
+    pub(crate) async fn owner_name_for_pdf(db: &DatabaseConnection, patient_id: i64) -> String {
+        match PatientService::get_primary_contact(db, patient_id).await {
+            Ok(Some(contact)) => contact.name,
+            Ok(None) => {
+                log::debug!("No primary contact for patient {}", patient_id);
+                String::new()
+            }
+            Err(e) => {
+                log::warn!("Owner lookup failed for patient {}: {}", patient_id, e);
+                String::new()
+            }
+        }
+    }
+
     /// Helper to get patient data for PDF generation
-    async fn get_patient_for_pdf(
+    pub(crate) async fn get_patient_for_pdf(
         db: &DatabaseConnection,
         patient_id: i64,
     ) -> Result<crate::services::device_pdf_service::PatientData, String> {
@@ -879,42 +1148,7 @@ impl MedicalRecordService {
 
         let birthdate: Option<String> = row.try_get("", "date_of_birth").ok();
 
-        // Try to get owner information - prefer primary contact's full name, fall back to household name
-        log::debug!("Looking up owner for patient_id: {}", patient_id);
-
-        let owner_result = db
-            .query_one(Statement::from_sql_and_values(
-                DbBackend::Sqlite,
-                "SELECT COALESCE( \
-                    (SELECT p.first_name || ' ' || p.last_name \
-                     FROM people p \
-                     WHERE p.household_id = h.id AND p.is_primary = 1 \
-                     LIMIT 1), \
-                    h.household_name \
-                 ) as owner_name \
-                 FROM households h \
-                 JOIN patient_households ph ON h.id = ph.household_id \
-                 WHERE ph.patient_id = ? \
-                 LIMIT 1",
-                [patient_id.into()],
-            ))
-            .await;
-
-        let owner = match owner_result {
-            Ok(Some(r)) => {
-                let name: String = r.try_get("", "owner_name").unwrap_or_default();
-                log::debug!("Found owner: {}", name);
-                name
-            }
-            Ok(None) => {
-                log::debug!("No household link found for patient");
-                String::new() // Empty string - PDF will skip owner row
-            }
-            Err(e) => {
-                log::warn!("Owner query failed: {}", e);
-                String::new() // Empty string - PDF will skip owner row
-            }
-        };
+        let owner = Self::owner_name_for_pdf(db, patient_id).await;
 
         Ok(crate::services::device_pdf_service::PatientData {
             name: row.try_get("", "name").unwrap_or_default(),
@@ -935,6 +1169,7 @@ impl MedicalRecordService {
         device_data_list: &[crate::services::device_pdf_service::DeviceTestData],
     ) -> Result<(), String> {
         use crate::services::java_pdf_service::JavaPdfService;
+        use crate::services::pdf_jobs::PdfCancelToken;
 
         if device_data_list.is_empty() {
             return Ok(());
@@ -977,6 +1212,7 @@ impl MedicalRecordService {
             pdf_path.to_str().ok_or("Invalid PDF path")?,
             patient_data,
             device_data_list,
+            &PdfCancelToken::none(),
         )?;
 
         // Determine device metadata for attachment (use first device for now)
@@ -1035,6 +1271,8 @@ impl MedicalRecordService {
     ) -> Result<(), String> {
         use uuid::Uuid;
 
+        let _record_lock = crate::services::record_lock::lock_record(medical_record_id).await;
+
         // Read PDF file
         let pdf_bytes = std::fs::read(pdf_path)
             .map_err(|e| format!("Failed to read {} PDF: {}", pdf_type, e))?;
@@ -1065,8 +1303,8 @@ impl MedicalRecordService {
         db.execute(Statement::from_sql_and_values(
             DbBackend::Sqlite,
             "INSERT INTO medical_attachments \
-             (medical_record_id, file_id, original_name, mime_type, file_size, uploaded_at, device_type, device_name, connection_method, attachment_type) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+             (medical_record_id, file_id, original_name, mime_type, file_size, uploaded_at, device_type, device_name, connection_method, attachment_type, content_sha256) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             [
                 medical_record_id.into(),
                 file_id.clone().into(),
@@ -1078,6 +1316,7 @@ impl MedicalRecordService {
                 format!("{} Report ({})", device_name, pdf_type).into(),
                 "pdf_generation".into(),
                 attachment_type.into(),
+                crate::services::file_storage::FileStorageService::content_sha256(&pdf_bytes).into(),
             ],
         ))
         .await
@@ -1093,28 +1332,65 @@ impl MedicalRecordService {
         db: &DatabaseConnection,
         record_id: i64,
         updates: UpdateMedicalRecordInput,
+        actor: Option<&User>,
     ) -> Result<MedicalRecord, String> {
-        let now = Utc::now();
+        let updated_record = Self::apply_medical_record_update(db, record_id, updates, actor).await?;
 
-        // Fetch current record for diffing/history
-        let old_row = db
-            .query_one(Statement::from_sql_and_values(
-                DbBackend::Sqlite,
-                "SELECT id, patient_id, record_type, name, procedure_name, description, \
-                 prescription_notes, price, currency_id, discount_percent, manual_total, \
-                 is_archived, version, created_at, updated_at, \
-                 created_by, updated_by \
-                 FROM medical_records WHERE id = ?",
-                [record_id.into()],
-            ))
-            .await
-            .map_err(|e| format!("Failed to fetch existing medical record: {}", e))?
-            .ok_or("Medical record not found".to_string())?;
+        // Regenerate invoice PDF on update (line items or discount may have changed)
+        log::info!("📝 Regenerating invoice PDF for updated record {}", record_id);
+        match Self::generate_invoice_pdf(app_handle, db, record_id, &updated_record).await {
+            Ok(_) => log::info!("✅ Invoice PDF regenerated for record {}", record_id),
+            Err(e) => log::error!("❌ Failed to regenerate invoice PDF: {}", e),
+        }
 
-        // Build dynamic update query with sequential placeholders
+        // Regenerate pharmacy note PDF on update if prescription notes exist
+        if updated_record.prescription_notes.as_ref().map_or(false, |n| !n.trim().is_empty()) {
+            log::info!("📝 Regenerating pharmacy note PDF for updated record {}", record_id);
+            match Self::generate_pharmacy_note_pdf(app_handle, db, record_id, &updated_record).await {
+                Ok(_) => log::info!("✅ Pharmacy note PDF regenerated for record {}", record_id),
+                Err(e) => log::error!("❌ Failed to regenerate pharmacy note PDF: {}", e),
+            }
+        }
+
+        Ok(updated_record)
+    }
+
+    /// Apply the updates as a new version and record its history snapshot.
+    /// Updating through `update_medical_record` also regenerates its PDFs.
+    pub async fn apply_medical_record_update(
+        db: &DatabaseConnection,
+        record_id: i64,
+        updates: UpdateMedicalRecordInput,
+        actor: Option<&User>,
+    ) -> Result<MedicalRecord, String> {
+        let now = Utc::now();
+
+        // Fetch current record for diffing/history
+        let old_row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id, patient_id, record_type, name, procedure_name, description, \
+                 prescription_notes, price, currency_id, discount_percent, manual_total, \
+                 is_archived, follow_up_date, follow_up_done, version, created_at, updated_at, \
+                 created_by, updated_by \
+                 FROM medical_records WHERE id = ?",
+                [record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch existing medical record: {}", e))?
+            .ok_or("Medical record not found".to_string())?;
+
+        // Build dynamic update query with sequential placeholders
         let mut update_parts: Vec<&str> = Vec::new();
         let mut params: Vec<Value> = Vec::new();
 
+        if let Some(ref record_type) = updates.record_type {
+            if !matches!(record_type.as_str(), "procedure" | "note" | "test_result") {
+                return Err(format!("Invalid record type: {}", record_type));
+            }
+            update_parts.push("record_type = ?");
+            params.push(record_type.clone().into());
+        }
         if let Some(ref name) = updates.name {
             update_parts.push("name = ?");
             params.push(name.clone().into());
@@ -1193,6 +1469,9 @@ impl MedicalRecordService {
             update_parts.push("updated_at = ?");
             params.push(now.to_rfc3339().into());
 
+            update_parts.push("updated_by = ?");
+            params.push(UserService::actor_id(actor).into());
+
             update_parts.push("version = version + 1");
 
             // Add record_id at the end for WHERE clause
@@ -1228,7 +1507,7 @@ impl MedicalRecordService {
                 DbBackend::Sqlite,
                 "SELECT id, patient_id, record_type, name, procedure_name, description, \
                  prescription_notes, price, currency_id, discount_percent, manual_total, \
-                 is_archived, version, created_at, updated_at, \
+                 is_archived, follow_up_date, follow_up_done, version, created_at, updated_at, \
                  created_by, updated_by \
                  FROM medical_records WHERE id = ?",
                 [record_id.into()],
@@ -1237,91 +1516,11 @@ impl MedicalRecordService {
             .map_err(|e| format!("Failed to fetch updated medical record: {}", e))?
             .ok_or("Updated record not found".to_string())?;
 
-        let is_archived_int: i64 = row.try_get("", "is_archived").unwrap_or(0);
-        let created_at_str: Option<String> = row.try_get("", "created_at").ok();
-        let updated_at_str: Option<String> = row.try_get("", "updated_at").ok();
-
-        let created_at = created_at_str
-            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|| Utc::now());
-
-        let updated_at = updated_at_str
-            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|| Utc::now());
-
-        // Handle price as either integer or float
-        let price: Option<f64> = row.try_get::<i64>("", "price")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| row.try_get::<f64>("", "price").ok());
-
-        // Handle discount_percent as either integer or float
-        let discount_percent: Option<f64> = row.try_get::<i64>("", "discount_percent")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| row.try_get::<f64>("", "discount_percent").ok());
-
-        // Handle manual_total as either integer or float
-        let manual_total: Option<f64> = row.try_get::<i64>("", "manual_total")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| row.try_get::<f64>("", "manual_total").ok());
-
-        let updated_record = MedicalRecord {
-            id: row.try_get("", "id").unwrap_or(0),
-            patient_id: row.try_get("", "patient_id").unwrap_or(0),
-            record_type: row.try_get("", "record_type").unwrap_or_default(),
-            name: row.try_get("", "name").unwrap_or_default(),
-            procedure_name: row.try_get("", "procedure_name").ok(),
-            description: row.try_get("", "description").unwrap_or_default(),
-            prescription_notes: row.try_get("", "prescription_notes").ok(),
-            price,
-            currency_id: row.try_get("", "currency_id").ok(),
-            discount_percent,
-            manual_total,
-            invoice_number: row.try_get("", "invoice_number").ok(),
-            is_archived: is_archived_int != 0,
-            version: row.try_get("", "version").unwrap_or(1),
-            created_at,
-            updated_at,
-            created_by: row.try_get("", "created_by").ok(),
-            updated_by: row.try_get("", "updated_by").ok(),
-            attachments: None,
-            line_items,
-        };
+        let mut updated_record = Self::record_from_row(&row)?;
+        updated_record.line_items = line_items;
 
         // Build full snapshot history entry
-        // Old snapshot
-        let old_is_archived_int: i64 = old_row.try_get("", "is_archived").unwrap_or(0);
-        let old_is_archived = old_is_archived_int != 0;
-        let old_price: Option<f64> = old_row.try_get::<i64>("", "price")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| old_row.try_get::<f64>("", "price").ok());
-        // Handle old discount_percent and manual_total as either integer or float
-        let old_discount_percent: Option<f64> = old_row.try_get::<i64>("", "discount_percent")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| old_row.try_get::<f64>("", "discount_percent").ok());
-        let old_manual_total: Option<f64> = old_row.try_get::<i64>("", "manual_total")
-            .ok()
-            .map(|i| i as f64)
-            .or_else(|| old_row.try_get::<f64>("", "manual_total").ok());
-
-        let old_snapshot = json!({
-            "record_type": old_row.try_get::<String>("", "record_type").unwrap_or_default(),
-            "name": old_row.try_get::<String>("", "name").ok(),
-            "procedure_name": old_row.try_get::<String>("", "procedure_name").ok(),
-            "description": old_row.try_get::<String>("", "description").ok(),
-            "prescription_notes": old_row.try_get::<String>("", "prescription_notes").ok(),
-            "price": old_price,
-            "currency_id": old_row.try_get::<i64>("", "currency_id").ok(),
-            "discount_percent": old_discount_percent,
-            "manual_total": old_manual_total,
-            "is_archived": old_is_archived
-        });
+        let old_snapshot = record_snapshot(&old_row);
 
         // New snapshot from updated_record
         let new_snapshot = json!({
@@ -1366,22 +1565,6 @@ impl MedicalRecordService {
             ))
             .await;
 
-        // Regenerate invoice PDF on update (line items or discount may have changed)
-        log::info!("📝 Regenerating invoice PDF for updated record {}", record_id);
-        match Self::generate_invoice_pdf(app_handle, db, record_id, &updated_record).await {
-            Ok(_) => log::info!("✅ Invoice PDF regenerated for record {}", record_id),
-            Err(e) => log::error!("❌ Failed to regenerate invoice PDF: {}", e),
-        }
-
-        // Regenerate pharmacy note PDF on update if prescription notes exist
-        if updated_record.prescription_notes.as_ref().map_or(false, |n| !n.trim().is_empty()) {
-            log::info!("📝 Regenerating pharmacy note PDF for updated record {}", record_id);
-            match Self::generate_pharmacy_note_pdf(app_handle, db, record_id, &updated_record).await {
-                Ok(_) => log::info!("✅ Pharmacy note PDF regenerated for record {}", record_id),
-                Err(e) => log::error!("❌ Failed to regenerate pharmacy note PDF: {}", e),
-            }
-        }
-
         Ok(updated_record)
     }
 
@@ -1405,321 +1588,1479 @@ impl MedicalRecordService {
         Ok(())
     }
 
-    pub async fn search_medical_records(
+    /// Schedule a follow-up, or clear it with `None`. Either way the
+    /// follow-up is reopened. Not a clinical edit, so no new version.
+    pub async fn set_follow_up(
         db: &DatabaseConnection,
-        patient_id: i64,
-        search_term: &str,
-        include_archived: bool,
-    ) -> Result<Vec<MedicalRecord>, String> {
-        // Fetch the patient's records, then filter by search_term in Rust
-        // with Unicode-aware to_lowercase(). SQLite LIKE only folds ASCII,
-        // so a Cyrillic search term wouldn't match Cyrillic record text
-        // differing only in case. The candidate set is bounded to one
-        // patient's records (small), so the in-memory filter is cheap.
-        let needle = search_term.trim().to_lowercase();
+        record_id: i64,
+        follow_up_date: Option<NaiveDate>,
+    ) -> Result<(), String> {
+        let result = db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE medical_records SET follow_up_date = ?, follow_up_done = 0, updated_at = ? WHERE id = ?",
+            [
+                Value::String(follow_up_date.map(|d| Box::new(d.format("%Y-%m-%d").to_string()))),
+                Utc::now().to_rfc3339().into(),
+                record_id.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to set follow-up: {}", e))?;
 
-        let mut sql = String::from(
-            "SELECT id, patient_id, record_type, name, procedure_name, description, \
-             prescription_notes, price, currency_id, is_archived, version, created_at, updated_at, \
-             created_by, updated_by \
-             FROM medical_records \
-             WHERE patient_id = ?"
-        );
+        if result.rows_affected() == 0 {
+            return Err("Medical record not found".to_string());
+        }
+        Ok(())
+    }
 
-        if !include_archived {
-            sql.push_str(" AND is_archived = 0");
+    /// Mark a record's follow-up as done (or reopen it).
+    pub async fn set_follow_up_done(
+        db: &DatabaseConnection,
+        record_id: i64,
+        done: bool,
+    ) -> Result<(), String> {
+        let result = db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE medical_records SET follow_up_done = ?, updated_at = ? \
+             WHERE id = ? AND follow_up_date IS NOT NULL",
+            [
+                (if done { 1i32 } else { 0i32 }).into(),
+                Utc::now().to_rfc3339().into(),
+                record_id.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to update follow-up: {}", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err("Medical record has no follow-up scheduled".to_string());
         }
+        Ok(())
+    }
 
-        sql.push_str(" ORDER BY created_at DESC");
+    /// Open follow-ups due on or before `today + within_days`, overdue ones
+    /// included, soonest first. Archived records and inactive patients are
+    /// left out.
+    pub async fn get_pending_followups(
+        db: &DatabaseConnection,
+        within_days: i64,
+        today: NaiveDate,
+    ) -> Result<Vec<PendingFollowUp>, String> {
+        if within_days < 0 {
+            return Err("within_days cannot be negative".to_string());
+        }
+        let horizon = today + chrono::Duration::days(within_days);
 
         let rows = db
             .query_all(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
-                &sql,
-                [
-                    patient_id.into(),
-                ],
+                &format!(
+                    r#"SELECT mr.id, mr.name, mr.record_type, mr.follow_up_date, mr.patient_id,
+                          p.name AS patient_name, s.name AS species,
+                          owner.first_name AS owner_first_name, owner.last_name AS owner_last_name,
+                          (SELECT pc.contact_value FROM person_contacts pc
+                            WHERE pc.person_id = owner.id AND pc.contact_type IN ('phone', 'mobile', 'work_phone')
+                            ORDER BY pc.is_primary DESC, pc.id LIMIT 1) AS owner_phone
+                   FROM medical_records mr
+                   JOIN patients p ON p.id = mr.patient_id
+                   LEFT JOIN species s ON s.id = p.species_id{}
+                   WHERE mr.follow_up_date IS NOT NULL
+                     AND mr.follow_up_done = 0
+                     AND mr.is_archived = 0
+                     AND (p.is_active = 1 OR p.is_active IS NULL)
+                     AND mr.follow_up_date <= ?
+                   ORDER BY mr.follow_up_date ASC, mr.id ASC"#,
+                    PRIMARY_OWNER_JOIN
+                ),
+                [horizon.format("%Y-%m-%d").to_string().into()],
             ))
             .await
-            .map_err(|e| format!("Failed to search medical records: {}", e))?;
-
-        let mut records = Vec::new();
-        for row in rows {
-            let is_archived_int: i64 = row.try_get("", "is_archived").unwrap_or(0);
-            let created_at_str: Option<String> = row.try_get("", "created_at").ok();
-            let updated_at_str: Option<String> = row.try_get("", "updated_at").ok();
-
-            let created_at = created_at_str
-                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|| Utc::now());
-
-            let updated_at = updated_at_str
-                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|| Utc::now());
-
-            // Handle price as either integer or float
-            let price: Option<f64> = row.try_get::<i64>("", "price")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "price").ok());
-
-            // Handle discount_percent as either integer or float
-            let discount_percent: Option<f64> = row.try_get::<i64>("", "discount_percent")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "discount_percent").ok());
-
-            // Handle manual_total as either integer or float
-            let manual_total: Option<f64> = row.try_get::<i64>("", "manual_total")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "manual_total").ok());
-
-            let name: String = row.try_get("", "name").unwrap_or_default();
-            let procedure_name: Option<String> = row.try_get("", "procedure_name").ok();
-            let description: String = row.try_get("", "description").unwrap_or_default();
-
-            // Unicode-aware substring match across the same three fields the
-            // old SQL LIKE covered (name, description, procedure_name). An
-            // empty needle matches everything (acts as "list all").
-            if !needle.is_empty() {
-                let hit = name.to_lowercase().contains(&needle)
-                    || description.to_lowercase().contains(&needle)
-                    || procedure_name
-                        .as_deref()
-                        .is_some_and(|p| p.to_lowercase().contains(&needle));
-                if !hit {
-                    continue;
-                }
-            }
+            .map_err(|e| format!("Failed to fetch pending follow-ups: {}", e))?;
+
+        let mut followups = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let follow_up_date: NaiveDate = row
+                .try_get("", "follow_up_date")
+                .map_err(|e| format!("Invalid follow-up date: {}", e))?;
+            let first: Option<String> = row.try_get("", "owner_first_name").ok();
+            let last: Option<String> = row.try_get("", "owner_last_name").ok();
+            let owner_name = match (first, last) {
+                (Some(f), Some(l)) => Some(format!("{} {}", f, l).trim().to_string()),
+                (Some(n), None) | (None, Some(n)) => Some(n),
+                (None, None) => None,
+            };
 
-            records.push(MedicalRecord {
-                id: row.try_get("", "id").unwrap_or(0),
-                patient_id: row.try_get("", "patient_id").unwrap_or(0),
+            followups.push(PendingFollowUp {
+                record_id: row.try_get("", "id").map_err(|e| e.to_string())?,
+                record_name: row.try_get("", "name").unwrap_or_default(),
                 record_type: row.try_get("", "record_type").unwrap_or_default(),
-                name,
-                procedure_name,
-                description,
-                prescription_notes: row.try_get("", "prescription_notes").ok(),
-                price,
-                currency_id: row.try_get("", "currency_id").ok(),
-                discount_percent,
-                manual_total,
-                invoice_number: row.try_get("", "invoice_number").ok(),
-                is_archived: is_archived_int != 0,
-                version: row.try_get("", "version").unwrap_or(1),
-                created_at,
-                updated_at,
-                created_by: row.try_get("", "created_by").ok(),
-                updated_by: row.try_get("", "updated_by").ok(),
-                attachments: None,
-                line_items: None,
+                follow_up_date,
+                days_until_due: (follow_up_date - today).num_days(),
+                patient_id: row.try_get("", "patient_id").unwrap_or(0),
+                patient_name: row.try_get("", "patient_name").ok(),
+                species: row.try_get("", "species").ok(),
+                owner_name,
+                owner_phone: row.try_get("", "owner_phone").ok(),
             });
         }
-        Ok(records)
-    }
-
-    pub async fn get_currencies(db: &DatabaseConnection) -> Result<Vec<Currency>, String> {
-        let rows = db
-            .query_all(Statement::from_string(
-                DbBackend::Sqlite,
-                "SELECT id, code, name, symbol FROM currencies ORDER BY id".to_string(),
-            ))
-            .await
-            .map_err(|e| format!("Failed to fetch currencies: {}", e))?;
 
-        let currencies: Vec<Currency> = rows
-            .iter()
-            .filter_map(|row| {
-                Some(Currency {
-                    id: row.try_get("", "id").ok()?,
-                    code: row.try_get("", "code").ok()?,
-                    name: row.try_get("", "name").ok()?,
-                    symbol: row.try_get("", "symbol").ok(),
-                })
-            })
-            .collect();
+        Ok(followups)
+    }
 
-        log::debug!("Fetched currencies from DB: {:?}", currencies);
-        Ok(currencies)
+    /// Checks the create command applies before inserting a record.
+    pub fn validate_create_input(input: &CreateMedicalRecordInput) -> Result<(), String> {
+        if input.name.is_empty() {
+            return Err("Name is required".to_string());
+        }
+        if input.description.is_empty() {
+            return Err("Description is required".to_string());
+        }
+        if input.record_type != "procedure" && input.record_type != "note" && input.record_type != "test_result" {
+            return Err("Invalid record type".to_string());
+        }
+        // Note: We use the 'name' field for both procedures and notes
+        // No need to check procedure_name separately
+        Ok(())
     }
 
-    // Get a record snapshot at a specific version using history new_values
-    pub async fn get_record_at_version(
+    /// Compose the `CreateMedicalRecordInput` a template would produce for a
+    /// patient, without writing anything. Overrides win over template values.
+    /// A priced record with no currency on either side falls back to the
+    /// clinic's default currency. Notes stay unpriced.
+    pub async fn preview_record_from_template(
         db: &DatabaseConnection,
-        record_id: i64,
-        version: i32,
-    ) -> Result<MedicalRecord, String> {
-        // Fetch base record
-        let row = db
+        patient_id: i64,
+        template_id: i64,
+        overrides: TemplatePreviewOverrides,
+    ) -> Result<CreateMedicalRecordInput, String> {
+        let template_row = db
             .query_one(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
-                "SELECT id, patient_id, record_type, name, procedure_name, description, \
-                 prescription_notes, price, currency_id, is_archived, version, created_at, updated_at, \
-                 created_by, updated_by \
-                 FROM medical_records WHERE id = ?",
-                [record_id.into()],
+                "SELECT * FROM record_templates WHERE id = ?",
+                [template_id.into()],
             ))
             .await
-            .map_err(|e| format!("Failed to fetch medical record: {}", e))?
-            .ok_or("Medical record not found".to_string())?;
+            .map_err(|e| format!("Failed to fetch record template: {}", e))?
+            .ok_or("Template not found".to_string())?;
+        let template = row_to_record_template(&template_row)?;
 
-        let mut base = MedicalRecord {
-            id: row.try_get("", "id").unwrap_or(0),
-            patient_id: row.try_get("", "patient_id").unwrap_or(0),
-            record_type: row.try_get("", "record_type").unwrap_or_default(),
-            name: row.try_get("", "name").unwrap_or_default(),
-            procedure_name: row.try_get("", "procedure_name").ok(),
-            description: row.try_get("", "description").unwrap_or_default(),
-            prescription_notes: row.try_get("", "prescription_notes").ok(),
-            price: row.try_get::<i64>("", "price")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "price").ok()),
-            currency_id: row.try_get("", "currency_id").ok(),
-            discount_percent: row.try_get::<i64>("", "discount_percent")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "discount_percent").ok()),
-            manual_total: row.try_get::<i64>("", "manual_total")
-                .ok()
-                .map(|i| i as f64)
-                .or_else(|| row.try_get::<f64>("", "manual_total").ok()),
-            invoice_number: row.try_get("", "invoice_number").ok(),
-            is_archived: {
-                let v: i64 = row.try_get("", "is_archived").unwrap_or(0);
-                v != 0
-            },
-            version: row.try_get("", "version").unwrap_or(1),
-            created_at: {
-                let s: Option<String> = row.try_get("", "created_at").ok();
-                s.and_then(|s| DateTime::parse_from_rfc3339(&s).ok()).map(|d| d.with_timezone(&Utc)).unwrap_or_else(|| Utc::now())
-            },
-            updated_at: {
-                let s: Option<String> = row.try_get("", "updated_at").ok();
-                s.and_then(|s| DateTime::parse_from_rfc3339(&s).ok()).map(|d| d.with_timezone(&Utc)).unwrap_or_else(|| Utc::now())
-            },
-            created_by: row.try_get("", "created_by").ok(),
-            updated_by: row.try_get("", "updated_by").ok(),
-            attachments: None,
-            line_items: None,
-        };
+        db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT id FROM patients WHERE id = ?",
+            [patient_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch patient: {}", e))?
+        .ok_or("Patient not found".to_string())?;
+
+        if let Some(requested) = &overrides.record_type {
+            if requested != &template.record_type {
+                return Err(format!(
+                    "Template '{}' is for {} records, not {}",
+                    template.title, template.record_type, requested
+                ));
+            }
+        }
 
-        // Fetch history snapshot
-        let hrow = db
-            .query_one(Statement::from_sql_and_values(
-                DbBackend::Sqlite,
-                "SELECT new_values FROM medical_record_history WHERE medical_record_id = ? AND version = ?",
-                [record_id.into(), version.into()],
-            ))
-            .await
-            .map_err(|e| format!("Failed to fetch history: {}", e))?;
+        let is_note = template.record_type == "note";
+        if is_note
+            && (overrides.price.is_some()
+                || overrides.currency_id.is_some()
+                || overrides.discount_percent.is_some()
+                || overrides.manual_total.is_some())
+        {
+            return Err("A note cannot have a price or currency".to_string());
+        }
 
-        if let Some(hr) = hrow {
-            let json_str: String = hr.try_get("", "new_values").unwrap_or_default();
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                if let Some(rt) = v.get("record_type").and_then(|x| x.as_str()) { base.record_type = rt.to_string(); }
-                if let Some(nm) = v.get("name").and_then(|x| x.as_str()) { base.name = nm.to_string(); }
-                if let Some(pn) = v.get("procedure_name").and_then(|x| x.as_str()) { base.procedure_name = Some(pn.to_string()); }
-                if let Some(desc) = v.get("description").and_then(|x| x.as_str()) { base.description = desc.to_string(); }
-                if let Some(prn) = v.get("prescription_notes").and_then(|x| x.as_str()) { base.prescription_notes = Some(prn.to_string()); }
-                if let Some(pr) = v.get("price").and_then(|x| x.as_f64()) { base.price = Some(pr); }
-                if let Some(cid) = v.get("currency_id").and_then(|x| x.as_i64()) { base.currency_id = Some(cid); }
-                if let Some(ia) = v.get("is_archived").and_then(|x| x.as_bool()) { base.is_archived = ia; }
-                base.version = version;
+        let (price, currency_id) = if is_note {
+            (None, None)
+        } else {
+            let price = overrides.price.or(template.price);
+            let mut currency_id = overrides.currency_id.or(template.currency_id);
+            if price.is_some() && currency_id.is_none() {
+                currency_id = db
+                    .query_one(Statement::from_string(
+                        DbBackend::Sqlite,
+                        "SELECT currency_id FROM app_settings WHERE user_id = 'default'".to_string(),
+                    ))
+                    .await
+                    .map_err(|e| format!("Failed to fetch default currency: {}", e))?
+                    .and_then(|row| row.try_get::<Option<i64>>("", "currency_id").ok().flatten());
             }
-        }
+            (price, currency_id)
+        };
 
-        Ok(base)
+        let input = CreateMedicalRecordInput {
+            patient_id,
+            record_type: template.record_type,
+            name: overrides.name.unwrap_or(template.title),
+            procedure_name: None,
+            description: overrides.description.unwrap_or(template.description),
+            prescription_notes: overrides.prescription_notes,
+            price,
+            currency_id,
+            discount_percent: overrides.discount_percent,
+            manual_total: overrides.manual_total,
+            device_test_data: None,
+            device_type: None,
+            device_name: None,
+            device_data_list: None,
+            line_items: None,
+        };
+
+        Self::validate_create_input(&input)?;
+        Ok(input)
     }
 
-    // Revert a record one step to its previous version using latest history old_values
-    pub async fn revert_one_step(
-        app_handle: &tauri::AppHandle,
+    /// Convert a record to another record_type, fixing up the pricing fields
+    /// the target type requires. A note can't carry a price, so converting to
+    /// a note clears price/currency/discount/manual total; a priced procedure
+    /// or test result must name an existing currency. The update and its
+    /// history snapshot are written in one transaction.
+    pub async fn change_record_type(
         db: &DatabaseConnection,
         record_id: i64,
+        new_type: &str,
+        price: Option<f64>,
+        currency_id: Option<i64>,
+        actor: Option<&User>,
     ) -> Result<MedicalRecord, String> {
-        // Fetch latest history entry
-        let row = db
+        if !matches!(new_type, "procedure" | "note" | "test_result") {
+            return Err(format!("Invalid record type: {}", new_type));
+        }
+        if new_type == "note" && (price.is_some() || currency_id.is_some()) {
+            return Err("A note cannot have a price or currency".to_string());
+        }
+        if let Some(p) = price {
+            if p < 0.0 {
+                return Err("Price cannot be negative".to_string());
+            }
+            if currency_id.is_none() {
+                return Err("A currency is required when a price is set".to_string());
+            }
+        }
+
+        let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let old_row = txn
             .query_one(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
-                "SELECT version, old_values FROM medical_record_history \
-                 WHERE medical_record_id = ? ORDER BY version DESC LIMIT 1",
+                "SELECT record_type, name, procedure_name, description, prescription_notes, price, \
+                 currency_id, discount_percent, manual_total, is_archived, version \
+                 FROM medical_records WHERE id = ?",
                 [record_id.into()],
             ))
             .await
-            .map_err(|e| format!("Failed to fetch history: {}", e))?
-            .ok_or("No history available to revert".to_string())?;
+            .map_err(|e| format!("Failed to fetch existing medical record: {}", e))?
+            .ok_or("Medical record not found".to_string())?;
 
-        let old_values_str: Option<String> = row.try_get("", "old_values").ok();
-        if old_values_str.is_none() {
-            return Err("No previous values recorded to revert".to_string());
+        let old_type: String = old_row.try_get("", "record_type").unwrap_or_default();
+        if old_type == new_type {
+            return Err(format!("Record is already of type {}", new_type));
         }
 
-        let old_vals: serde_json::Value = serde_json::from_str(&old_values_str.unwrap())
-            .map_err(|e| format!("Failed to parse history values: {}", e))?;
+        if let Some(cid) = currency_id {
+            let exists = txn
+                .query_one(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "SELECT id FROM currencies WHERE id = ?",
+                    [cid.into()],
+                ))
+                .await
+                .map_err(|e| format!("Failed to check currency: {}", e))?;
+            if exists.is_none() {
+                return Err(format!("Currency {} not found", cid));
+            }
+        }
 
-        // Build updates from old values (only known fields)
-        let mut updates = UpdateMedicalRecordInput {
-            name: None,
-            procedure_name: None,
-            description: None,
-            prescription_notes: None,
-            price: MaybeNull::Undefined,
-            currency_id: MaybeNull::Undefined,
-            discount_percent: MaybeNull::Undefined,
-            manual_total: MaybeNull::Undefined,
-            is_archived: None,
-            line_items: None,
+        // Full snapshots, like any other edit, so a revert can restore the type
+        let old_snapshot = record_snapshot(&old_row);
+
+        // Discount and manual total only make sense on a priced record
+        let (discount_percent, manual_total) = if new_type == "note" {
+            (None, None)
+        } else {
+            (read_f64(&old_row, "discount_percent"), read_f64(&old_row, "manual_total"))
         };
+        let mut new_snapshot = old_snapshot.clone();
+        for (field, value) in [
+            ("record_type", json!(new_type)),
+            ("price", json!(price)),
+            ("currency_id", json!(currency_id)),
+            ("discount_percent", json!(discount_percent)),
+            ("manual_total", json!(manual_total)),
+        ] {
+            new_snapshot[field] = value;
+        }
+
+        let changed_fields: Vec<&str> = ["record_type", "price", "currency_id", "discount_percent", "manual_total"]
+            .into_iter()
+            .filter(|f| old_snapshot.get(f) != new_snapshot.get(f))
+            .collect();
+
+        let now = Utc::now().to_rfc3339();
+        let actor = UserService::actor_id(actor);
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE medical_records SET record_type = ?, price = ?, currency_id = ?, \
+             discount_percent = ?, manual_total = ?, updated_at = ?, updated_by = ?, version = version + 1 \
+             WHERE id = ?",
+            [
+                new_type.into(),
+                Value::Double(price),
+                Value::BigInt(currency_id),
+                Value::Double(discount_percent),
+                Value::Double(manual_total),
+                now.into(),
+                actor.clone().into(),
+                record_id.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to change record type: {}", e))?;
+
+        let old_version: i32 = old_row.try_get("", "version").unwrap_or(1);
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO medical_record_history (medical_record_id, version, changed_fields, old_values, new_values, changed_by) VALUES (?, ?, ?, ?, ?, ?)",
+            [
+                record_id.into(),
+                (old_version + 1).into(),
+                changed_fields.join(",").into(),
+                old_snapshot.to_string().into(),
+                new_snapshot.to_string().into(),
+                actor.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to write history: {}", e))?;
+
+        txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(Self::get_medical_record(db, record_id, false).await?.record)
+    }
 
-        if let Some(v) = old_vals.get("name") { updates.name = v.as_str().map(|s| s.to_string()); }
-        if let Some(v) = old_vals.get("procedure_name") { updates.procedure_name = v.as_str().map(|s| s.to_string()); }
-        if let Some(v) = old_vals.get("description") { updates.description = v.as_str().map(|s| s.to_string()); }
-        if let Some(v) = old_vals.get("prescription_notes") { updates.prescription_notes = v.as_str().map(|s| s.to_string()); }
-        if let Some(v) = old_vals.get("price") {
-            updates.price = match v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)) {
-                Some(p) => MaybeNull::Value(p),
-                None if v.is_null() => MaybeNull::Null,
-                None => MaybeNull::Undefined,
+    /// Re-file records under another patient, e.g. results that were saved
+    /// to the wrong animal. Attachments belong to the record and move with
+    /// it. The target must exist and be active, and records of a patient of
+    /// another species are refused unless `force` is set. Every record gets
+    /// a history snapshot of the move; all of it is one transaction.
+    pub async fn move_records_to_patient(
+        db: &DatabaseConnection,
+        record_ids: &[i64],
+        target_patient_id: i64,
+        force: bool,
+        actor: Option<&User>,
+    ) -> Result<MoveRecordsResult, String> {
+        if record_ids.is_empty() {
+            return Err("No records to move".to_string());
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(dup) = record_ids.iter().find(|id| !seen.insert(**id)) {
+            return Err(format!("Record {} is listed more than once", dup));
+        }
+
+        let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let target = txn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT species_id, is_active FROM patients WHERE id = ?",
+                [target_patient_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch target patient: {}", e))?
+            .ok_or_else(|| format!("Patient {} not found", target_patient_id))?;
+        if !target.try_get::<bool>("", "is_active").unwrap_or(true) {
+            return Err(format!("Patient {} is inactive", target_patient_id));
+        }
+        let target_species: Option<i64> = target.try_get("", "species_id").ok().flatten();
+
+        let mut result = MoveRecordsResult::default();
+        let now = Utc::now().to_rfc3339();
+        let actor = UserService::actor_id(actor);
+        for &record_id in record_ids {
+            let row = txn
+                .query_one(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "SELECT mr.patient_id, mr.version, p.species_id, \
+                     (SELECT COUNT(*) FROM medical_attachments ma WHERE ma.medical_record_id = mr.id) AS attachment_count \
+                     FROM medical_records mr JOIN patients p ON p.id = mr.patient_id \
+                     WHERE mr.id = ?",
+                    [record_id.into()],
+                ))
+                .await
+                .map_err(|e| format!("Failed to fetch medical record: {}", e))?
+                .ok_or_else(|| format!("Medical record {} not found", record_id))?;
+
+            let source_patient_id: i64 = row.try_get("", "patient_id").map_err(|e| e.to_string())?;
+            if source_patient_id == target_patient_id {
+                return Err(format!("Record {} already belongs to patient {}", record_id, target_patient_id));
+            }
+            let source_species: Option<i64> = row.try_get("", "species_id").ok().flatten();
+            if !force {
+                if let (Some(from), Some(to)) = (source_species, target_species) {
+                    if from != to {
+                        return Err(format!(
+                            "Record {} belongs to a patient of another species; force the move to file it anyway",
+                            record_id
+                        ));
+                    }
+                }
+            }
+
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "UPDATE medical_records SET patient_id = ?, updated_at = ?, updated_by = ?, version = version + 1 WHERE id = ?",
+                [target_patient_id.into(), now.clone().into(), actor.clone().into(), record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to move medical record: {}", e))?;
+
+            let version: i32 = row.try_get("", "version").unwrap_or(1);
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO medical_record_history (medical_record_id, version, changed_fields, old_values, new_values, changed_by) VALUES (?, ?, ?, ?, ?, ?)",
+                [
+                    record_id.into(),
+                    (version + 1).into(),
+                    "patient_id".into(),
+                    json!({ "patient_id": source_patient_id }).to_string().into(),
+                    json!({ "patient_id": target_patient_id }).to_string().into(),
+                    actor.clone().into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to write history: {}", e))?;
+
+            result.moved_records += 1;
+            result.moved_attachments += row.try_get::<i64>("", "attachment_count").unwrap_or(0) as usize;
+        }
+
+        txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        log::info!(
+            "Moved {} record(s) with {} attachment(s) to patient {}",
+            result.moved_records,
+            result.moved_attachments,
+            target_patient_id
+        );
+        Ok(result)
+    }
+
+    /// Archive every unarchived record created more than `after_days` days
+    /// before `now`, bumping its version and writing a history snapshot as
+    /// any edit would. Records with an open follow-up are never archived; they
+    /// are counted in `skipped_pending_follow_up` instead. Already archived
+    /// records are not touched, so running it again archives nothing new.
+    pub async fn apply_auto_archive(
+        db: &DatabaseConnection,
+        after_days: i64,
+        now: DateTime<Utc>,
+    ) -> Result<AutoArchiveResult, String> {
+        if after_days <= 0 {
+            return Err("Auto-archive threshold must be at least one day".to_string());
+        }
+        let cutoff = (now - chrono::Duration::days(after_days)).to_rfc3339();
+
+        let txn = db.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let rows = txn
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT id, version, {} AS pending_follow_up FROM medical_records \
+                     WHERE is_archived = 0 AND julianday(created_at) < julianday(?) \
+                     ORDER BY id",
+                    PENDING_FOLLOW_UP
+                ),
+                [cutoff.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to find records to archive: {}", e))?;
+
+        let mut result = AutoArchiveResult::default();
+        let now_str = now.to_rfc3339();
+        for row in rows {
+            if row.try_get::<i64>("", "pending_follow_up").unwrap_or(0) != 0 {
+                result.skipped_pending_follow_up += 1;
+                continue;
+            }
+            let record_id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            let version: i32 = row.try_get("", "version").unwrap_or(1);
+
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "UPDATE medical_records SET is_archived = 1, updated_at = ?, updated_by = ?, \
+                 version = version + 1 WHERE id = ?",
+                [now_str.clone().into(), AUTO_ARCHIVE_ACTOR.into(), record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to archive medical record: {}", e))?;
+
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO medical_record_history (medical_record_id, version, changed_fields, old_values, new_values, changed_by) VALUES (?, ?, ?, ?, ?, ?)",
+                [
+                    record_id.into(),
+                    (version + 1).into(),
+                    "is_archived".into(),
+                    json!({ "is_archived": false }).to_string().into(),
+                    json!({ "is_archived": true }).to_string().into(),
+                    AUTO_ARCHIVE_ACTOR.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to write history: {}", e))?;
+
+            result.archived += 1;
+        }
+
+        txn.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        if result.archived > 0 {
+            log::info!(
+                "Auto-archive: archived {} record(s) older than {} days, skipped {} with open follow-ups",
+                result.archived,
+                after_days,
+                result.skipped_pending_follow_up
+            );
+        }
+        Ok(result)
+    }
+
+    /// Run `apply_auto_archive` in the background every
+    /// `AUTO_ARCHIVE_CHECK_SECS` while a threshold is configured.
+    pub fn start_auto_archive_scheduler(db: Arc<DatabaseConnection>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(
+                tokio::time::Duration::from_secs(AUTO_ARCHIVE_CHECK_SECS),
+            );
+            loop {
+                interval.tick().await;
+
+                let after_days = match SettingsService::get_auto_archive_after_days(&db, "default").await {
+                    Ok(Some(days)) => days,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        log::error!("Auto-archive: failed to read settings: {}", e);
+                        continue;
+                    }
+                };
+                if let Err(e) = Self::apply_auto_archive(&db, after_days, Utc::now()).await {
+                    log::error!("Auto-archive: failed: {}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn search_medical_records(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        search_term: &str,
+        include_archived: bool,
+    ) -> Result<Vec<MedicalRecord>, String> {
+        // Fetch the patient's records, then filter by search_term in Rust
+        // with Unicode-aware to_lowercase(). SQLite LIKE only folds ASCII,
+        // so a Cyrillic search term wouldn't match Cyrillic record text
+        // differing only in case. The candidate set is bounded to one
+        // patient's records (small), so the in-memory filter is cheap.
+        let needle = search_term.trim().to_lowercase();
+
+        let mut sql = String::from(
+            "SELECT id, patient_id, record_type, name, procedure_name, description, \
+             prescription_notes, price, currency_id, is_archived, follow_up_date, follow_up_done, \
+             version, created_at, updated_at, created_by, updated_by \
+             FROM medical_records \
+             WHERE patient_id = ?"
+        );
+
+        if !include_archived {
+            sql.push_str(" AND is_archived = 0");
+        }
+
+        sql.push_str(" ORDER BY created_at DESC");
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &sql,
+                [
+                    patient_id.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to search medical records: {}", e))?;
+
+        let mut records: Vec<MedicalRecord> = rows.iter().map(Self::record_from_row).collect::<Result<_, _>>()?;
+
+        // Unicode-aware substring match across the same three fields the
+        // old SQL LIKE covered (name, description, procedure_name). An
+        // empty needle matches everything (acts as "list all").
+        if !needle.is_empty() {
+            records.retain(|r| {
+                r.name.to_lowercase().contains(&needle)
+                    || r.description.to_lowercase().contains(&needle)
+                    || r.procedure_name
+                        .as_deref()
+                        .is_some_and(|p| p.to_lowercase().contains(&needle))
+            });
+        }
+        Ok(records)
+    }
+
+    /// Records of `patient_id` created or updated after `since`, for screens
+    /// that poll instead of refetching the whole history. Medical records are
+    /// never deleted, only archived, and an archived record comes back here
+    /// with `is_archived` set.
+    pub async fn get_records_changed_since(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        since: DateTime<Utc>,
+    ) -> Result<MedicalRecordChanges, String> {
+        let checked_at = Utc::now();
+
+        // updated_at holds RFC 3339 strings and, on older rows, SQLite's
+        // "YYYY-MM-DD HH:MM:SS", which don't compare as text. Both start with
+        // the date, so the date bound narrows the index range and julianday
+        // does the exact comparison.
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id, patient_id, record_type, name, procedure_name, description, \
+                 prescription_notes, price, currency_id, discount_percent, manual_total, \
+                 invoice_number, is_archived, follow_up_date, follow_up_done, version, \
+                 created_at, updated_at, created_by, updated_by \
+                 FROM medical_records \
+                 WHERE patient_id = ? AND updated_at >= ? AND julianday(updated_at) > julianday(?) \
+                 ORDER BY updated_at",
+                [
+                    patient_id.into(),
+                    since.format("%Y-%m-%d").to_string().into(),
+                    since.to_rfc3339().into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch changed medical records: {}", e))?;
+
+        let mut records: Vec<MedicalRecord> = rows.iter().map(Self::record_from_row).collect::<Result<_, _>>()?;
+
+        let ids: Vec<i64> = records.iter().map(|r| r.id).collect();
+        let mut attachments_by_record = Self::fetch_attachments_for_records(db, &ids).await?;
+        for record in records.iter_mut() {
+            record.attachments = attachments_by_record.remove(&record.id);
+        }
+
+        Ok(MedicalRecordChanges { records, checked_at })
+    }
+
+    /// A `medical_records` row as a record without attachments or line items
+    fn record_from_row(row: &QueryResult) -> Result<MedicalRecord, String> {
+        let read_datetime = |col: &str| {
+            row.try_get::<String>("", col)
+                .ok()
+                .as_deref()
+                .map(Self::parse_datetime)
+                .unwrap_or_else(Utc::now)
+        };
+
+        Ok(MedicalRecord {
+            id: row.try_get("", "id").map_err(|e| e.to_string())?,
+            patient_id: row.try_get("", "patient_id").unwrap_or(0),
+            record_type: row.try_get("", "record_type").unwrap_or_default(),
+            name: row.try_get("", "name").unwrap_or_default(),
+            procedure_name: row.try_get("", "procedure_name").ok(),
+            description: row.try_get("", "description").unwrap_or_default(),
+            prescription_notes: row.try_get("", "prescription_notes").ok(),
+            price: read_f64(row, "price"),
+            currency_id: row.try_get("", "currency_id").ok(),
+            discount_percent: read_f64(row, "discount_percent"),
+            manual_total: read_f64(row, "manual_total"),
+            invoice_number: row.try_get("", "invoice_number").ok(),
+            is_archived: row.try_get::<i64>("", "is_archived").map(|v| v != 0).unwrap_or(false),
+            follow_up_date: row.try_get("", "follow_up_date").ok(),
+            follow_up_done: row.try_get::<i64>("", "follow_up_done").map(|v| v != 0).unwrap_or(false),
+            version: row.try_get("", "version").unwrap_or(1),
+            created_at: read_datetime("created_at"),
+            updated_at: read_datetime("updated_at"),
+            created_by: row.try_get("", "created_by").ok(),
+            updated_by: row.try_get("", "updated_by").ok(),
+            attachments: None,
+            line_items: None,
+        })
+    }
+
+    pub async fn get_currencies(db: &DatabaseConnection) -> Result<Vec<Currency>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT id, code, name, symbol FROM currencies ORDER BY id".to_string(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch currencies: {}", e))?;
+
+        let currencies: Vec<Currency> = rows
+            .iter()
+            .filter_map(|row| {
+                Some(Currency {
+                    id: row.try_get("", "id").ok()?,
+                    code: row.try_get("", "code").ok()?,
+                    name: row.try_get("", "name").ok()?,
+                    symbol: row.try_get("", "symbol").ok(),
+                })
+            })
+            .collect();
+
+        log::debug!("Fetched currencies from DB: {:?}", currencies);
+        Ok(currencies)
+    }
+
+    /// Store the rate for converting `from_currency_id` into `to_currency_id`,
+    /// replacing any earlier rate for the pair.
+    pub async fn set_exchange_rate(
+        db: &DatabaseConnection,
+        from_currency_id: i64,
+        to_currency_id: i64,
+        rate: f64,
+    ) -> Result<(), String> {
+        if from_currency_id == to_currency_id {
+            return Err("Exchange rate needs two different currencies".to_string());
+        }
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err("Exchange rate must be greater than zero".to_string());
+        }
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO exchange_rates (from_currency_id, to_currency_id, rate, updated_at)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT(from_currency_id, to_currency_id)
+             DO UPDATE SET rate = excluded.rate, updated_at = excluded.updated_at",
+            [
+                from_currency_id.into(),
+                to_currency_id.into(),
+                rate.into(),
+                Utc::now().to_rfc3339().into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to save exchange rate: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Multiplier converting an amount in `from_currency_id` into
+    /// `to_currency_id`: 1 for the same currency, the stored rate, or the
+    /// inverse of the reverse pair's rate. `None` when neither is stored.
+    pub async fn get_exchange_rate(
+        db: &DatabaseConnection,
+        from_currency_id: i64,
+        to_currency_id: i64,
+    ) -> Result<Option<f64>, String> {
+        if from_currency_id == to_currency_id {
+            return Ok(Some(1.0));
+        }
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT from_currency_id, rate FROM exchange_rates
+                 WHERE (from_currency_id = ? AND to_currency_id = ?)
+                    OR (from_currency_id = ? AND to_currency_id = ?)",
+                [
+                    from_currency_id.into(),
+                    to_currency_id.into(),
+                    to_currency_id.into(),
+                    from_currency_id.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch exchange rate: {}", e))?;
+
+        let mut inverse = None;
+        for row in &rows {
+            let from: i64 = row.try_get("", "from_currency_id").map_err(|e| e.to_string())?;
+            let rate: f64 = row.try_get("", "rate").map_err(|e| e.to_string())?;
+            if from == from_currency_id {
+                return Ok(Some(rate));
+            }
+            inverse = Some(1.0 / rate);
+        }
+        Ok(inverse)
+    }
+
+    /// A patient's charges per record_type, converted into
+    /// `target_currency_id`. A record is charged like the record form
+    /// totals it: its line items less the discount, or else its manual
+    /// total or price, in the record's currency (the clinic default when
+    /// unset). Archived and unpriced records are left out; a record with a
+    /// currency that has no rate to the target is listed in `skipped`
+    /// instead of being counted. The optional dates bound the record's
+    /// creation date, inclusive.
+    pub async fn get_patient_charge_summary(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        target_currency_id: i64,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<PatientChargeSummary, String> {
+        let currency = Self::get_currency(db, target_currency_id).await?;
+        let default_currency_id = Self::default_currency_id(db).await?;
+
+        let mut sql = String::from(
+            "SELECT id, name, record_type, price, currency_id, discount_percent, manual_total
+             FROM medical_records
+             WHERE patient_id = ? AND is_archived = 0",
+        );
+        let mut params: Vec<Value> = vec![patient_id.into()];
+        if let Some(start) = start_date {
+            sql.push_str(" AND date(created_at) >= ?");
+            params.push(start.format("%Y-%m-%d").to_string().into());
+        }
+        if let Some(end) = end_date {
+            sql.push_str(" AND date(created_at) <= ?");
+            params.push(end.format("%Y-%m-%d").to_string().into());
+        }
+        sql.push_str(" ORDER BY id");
+
+        let records = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, params))
+            .await
+            .map_err(|e| format!("Failed to fetch medical records: {}", e))?;
+
+        let mut line_items = Self::line_item_charges(db, "mr.patient_id = ?", vec![patient_id.into()]).await?;
+
+        let mut rates: HashMap<i64, Option<f64>> = HashMap::new();
+        let mut by_type: Vec<ChargeTypeTotal> = Vec::new();
+        let mut skipped = Vec::new();
+
+        for row in &records {
+            let record_id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            let record_type: String = row.try_get("", "record_type").unwrap_or_default();
+            let Some(parts) = Self::charge_parts(row, line_items.remove(&record_id), default_currency_id) else {
+                continue;
+            };
+
+            let total = match Self::convert_charge(db, parts, target_currency_id, &mut rates).await? {
+                Ok(total) => total,
+                Err(currency_id) => {
+                    skipped.push(SkippedChargeRecord {
+                        record_id,
+                        record_name: row.try_get("", "name").unwrap_or_default(),
+                        record_type,
+                        currency_id,
+                    });
+                    continue;
+                }
             };
+
+            match by_type.iter_mut().find(|t| t.record_type == record_type) {
+                Some(entry) => {
+                    entry.count += 1;
+                    entry.total += total;
+                }
+                None => by_type.push(ChargeTypeTotal { record_type, count: 1, total }),
+            }
+        }
+
+        let grand_total = round_charge(by_type.iter().map(|t| t.total).sum());
+        for entry in &mut by_type {
+            entry.total = round_charge(entry.total);
         }
-        if let Some(v) = old_vals.get("currency_id") {
-            updates.currency_id = match v.as_i64() {
-                Some(id) => MaybeNull::Value(id),
-                None if v.is_null() => MaybeNull::Null,
-                None => MaybeNull::Undefined,
+        by_type.sort_by(|a, b| a.record_type.cmp(&b.record_type));
+
+        Ok(PatientChargeSummary {
+            patient_id,
+            currency,
+            by_type,
+            grand_total,
+            skipped,
+        })
+    }
+
+    /// One page of the clinic's records of `record_type` created between
+    /// `from` and `to` (inclusive, UTC dates), newest first, with patient
+    /// and owner, for management reports such as "all procedures this
+    /// quarter". Archived records and records of inactive (deleted)
+    /// patients are left out. Each record is charged like in
+    /// `get_patient_charge_summary`; `total_charge` sums every matching
+    /// record, not just the page, and records without a rate to
+    /// `target_currency_id` are listed in `skipped`.
+    pub async fn list_records_by_type(
+        db: &DatabaseConnection,
+        record_type: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        target_currency_id: i64,
+        pagination: Option<PaginationParams>,
+    ) -> Result<RecordTypeReport, String> {
+        if to < from {
+            return Err("The end date is before the start date".to_string());
+        }
+        let currency = Self::get_currency(db, target_currency_id).await?;
+        let default_currency_id = Self::default_currency_id(db).await?;
+        let page = pagination.as_ref().and_then(|p| p.page).unwrap_or(1).max(1);
+        let page_size = pagination.as_ref().and_then(|p| p.page_size).unwrap_or(50).clamp(1, 500);
+        let offset = (page as i64 - 1) * page_size as i64;
+
+        // Plain comparisons on created_at (not date(created_at)) so the
+        // created_at index is used. Both stored forms, RFC 3339 and
+        // SQLite's "YYYY-MM-DD HH:MM:SS", start with the date.
+        let where_sql = "mr.record_type = ? AND mr.is_archived = 0 \
+                         AND mr.created_at >= ? AND mr.created_at < ? \
+                         AND (p.is_active = 1 OR p.is_active IS NULL)";
+        let params: Vec<Value> = vec![
+            record_type.into(),
+            from.format("%Y-%m-%d").to_string().into(),
+            (to + chrono::Duration::days(1)).format("%Y-%m-%d").to_string().into(),
+        ];
+
+        // Charges need every matching record for the total
+        let pricing = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT mr.id, mr.name, mr.record_type, mr.price, mr.currency_id, \
+                     mr.discount_percent, mr.manual_total \
+                     FROM medical_records mr JOIN patients p ON p.id = mr.patient_id \
+                     WHERE {}",
+                    where_sql
+                ),
+                params.clone(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch medical records: {}", e))?;
+        let mut line_items = Self::line_item_charges(
+            db,
+            &format!("mr.id IN (SELECT mr.id FROM medical_records mr JOIN patients p ON p.id = mr.patient_id WHERE {})", where_sql),
+            params.clone(),
+        )
+        .await?;
+
+        let mut rates: HashMap<i64, Option<f64>> = HashMap::new();
+        let mut charges: HashMap<i64, f64> = HashMap::new();
+        let mut skipped = Vec::new();
+        for row in &pricing {
+            let record_id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            let Some(parts) = Self::charge_parts(row, line_items.remove(&record_id), default_currency_id) else {
+                continue;
             };
+            match Self::convert_charge(db, parts, target_currency_id, &mut rates).await? {
+                Ok(charge) => {
+                    charges.insert(record_id, charge);
+                }
+                Err(currency_id) => skipped.push(SkippedChargeRecord {
+                    record_id,
+                    record_name: row.try_get("", "name").unwrap_or_default(),
+                    record_type: row.try_get("", "record_type").unwrap_or_default(),
+                    currency_id,
+                }),
+            }
         }
-        if let Some(v) = old_vals.get("discount_percent") {
-            updates.discount_percent = match v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)) {
-                Some(p) => MaybeNull::Value(p),
-                None if v.is_null() => MaybeNull::Null,
-                None => MaybeNull::Undefined,
+        skipped.sort_by_key(|s| s.record_id);
+
+        let mut page_params = params;
+        page_params.push((page_size as i64).into());
+        page_params.push(offset.into());
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    r#"SELECT mr.id, mr.name, mr.created_at, mr.patient_id,
+                          p.name AS patient_name, s.name AS species
+                   FROM medical_records mr
+                   JOIN patients p ON p.id = mr.patient_id
+                   LEFT JOIN species s ON s.id = p.species_id
+                   WHERE {}
+                   ORDER BY mr.created_at DESC, mr.id DESC
+                   LIMIT ? OFFSET ?"#,
+                    where_sql
+                ),
+                page_params,
+            ))
+            .await
+            .map_err(|e| format!("Failed to list medical records: {}", e))?;
+
+        // Owners as the patient screen shows them, looked up once per patient
+        let mut owners: HashMap<i64, Option<String>> = HashMap::new();
+        let mut records = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let record_id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            let created_at: Option<String> = row.try_get("", "created_at").ok();
+            let patient_id: i64 = row.try_get("", "patient_id").unwrap_or(0);
+            let owner_name = match owners.get(&patient_id) {
+                Some(owner) => owner.clone(),
+                None => {
+                    let owner = PatientService::get_primary_contact(db, patient_id).await?.map(|c| c.name);
+                    owners.insert(patient_id, owner.clone());
+                    owner
+                }
             };
+            records.push(RecordTypeReportRow {
+                record_id,
+                record_name: row.try_get("", "name").unwrap_or_default(),
+                created_at: created_at
+                    .as_deref()
+                    .map(Self::parse_datetime)
+                    .unwrap_or_else(Utc::now),
+                patient_id,
+                patient_name: row.try_get("", "patient_name").ok(),
+                species: row.try_get("", "species").ok(),
+                owner_name,
+                charge: charges.get(&record_id).copied().map(round_charge),
+            });
+        }
+
+        Ok(RecordTypeReport {
+            record_type: record_type.to_string(),
+            from,
+            to,
+            currency,
+            records,
+            total: pricing.len() as i64,
+            page,
+            page_size,
+            total_charge: round_charge(charges.values().sum()),
+            skipped,
+        })
+    }
+
+    async fn get_currency(db: &DatabaseConnection, currency_id: i64) -> Result<Currency, String> {
+        Self::get_currencies(db)
+            .await?
+            .into_iter()
+            .find(|c| c.id == currency_id)
+            .ok_or_else(|| format!("Currency {} not found", currency_id))
+    }
+
+    /// The clinic's default currency, charged for records without one.
+    async fn default_currency_id(db: &DatabaseConnection) -> Result<Option<i64>, String> {
+        Ok(db
+            .query_one(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT currency_id FROM app_settings WHERE user_id = 'default'".to_string(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch default currency: {}", e))?
+            .and_then(|row| row.try_get::<Option<i64>>("", "currency_id").ok().flatten()))
+    }
+
+    /// (amount, currency) of every line item on records matching
+    /// `record_filter` (over `mr`), by record id.
+    async fn line_item_charges(
+        db: &DatabaseConnection,
+        record_filter: &str,
+        params: Vec<Value>,
+    ) -> Result<HashMap<i64, Vec<(f64, i64)>>, String> {
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT li.medical_record_id, li.unit_price, li.quantity, li.currency_id
+                     FROM medical_record_line_items li
+                     JOIN medical_records mr ON mr.id = li.medical_record_id
+                     WHERE {}",
+                    record_filter
+                ),
+                params,
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch line items: {}", e))?;
+
+        let mut line_items: HashMap<i64, Vec<(f64, i64)>> = HashMap::new();
+        for row in &rows {
+            let record_id: i64 = row.try_get("", "medical_record_id").map_err(|e| e.to_string())?;
+            let unit_price: f64 = read_f64(row, "unit_price").unwrap_or(0.0);
+            let quantity: i64 = row.try_get("", "quantity").unwrap_or(1);
+            let currency_id: i64 = row.try_get("", "currency_id").map_err(|e| e.to_string())?;
+            line_items
+                .entry(record_id)
+                .or_default()
+                .push((unit_price * quantity as f64, currency_id));
         }
-        if let Some(v) = old_vals.get("manual_total") {
-            updates.manual_total = match v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)) {
-                Some(p) => MaybeNull::Value(p),
-                None if v.is_null() => MaybeNull::Null,
-                None => MaybeNull::Undefined,
+        Ok(line_items)
+    }
+
+    /// The (amount, currency) parts a record is charged as: its line items
+    /// less the discount, or else its manual total or price in its
+    /// currency. `None` for an unpriced record.
+    fn charge_parts(
+        row: &QueryResult,
+        line_items: Option<Vec<(f64, i64)>>,
+        default_currency_id: Option<i64>,
+    ) -> Option<Vec<(f64, i64)>> {
+        match line_items {
+            Some(items) => {
+                let discount = read_f64(row, "discount_percent").unwrap_or(0.0);
+                Some(
+                    items
+                        .into_iter()
+                        .map(|(amount, currency_id)| (amount * (1.0 - discount / 100.0), currency_id))
+                        .collect(),
+                )
+            }
+            None => {
+                let record_currency = row
+                    .try_get::<Option<i64>>("", "currency_id")
+                    .ok()
+                    .flatten()
+                    .or(default_currency_id);
+                match (read_f64(row, "manual_total").or_else(|| read_f64(row, "price")), record_currency) {
+                    (Some(amount), Some(currency_id)) => Some(vec![(amount, currency_id)]),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Sum `parts` in `target_currency_id`, caching rates in `rates`.
+    /// `Ok(Err(currency_id))` when a part's currency has no rate.
+    async fn convert_charge(
+        db: &DatabaseConnection,
+        parts: Vec<(f64, i64)>,
+        target_currency_id: i64,
+        rates: &mut HashMap<i64, Option<f64>>,
+    ) -> Result<Result<f64, i64>, String> {
+        let mut total = 0.0;
+        for (amount, currency_id) in parts {
+            let rate = match rates.get(&currency_id) {
+                Some(rate) => *rate,
+                None => {
+                    let rate = Self::get_exchange_rate(db, currency_id, target_currency_id).await?;
+                    rates.insert(currency_id, rate);
+                    rate
+                }
             };
+            match rate {
+                Some(rate) => total += amount * rate,
+                None => return Ok(Err(currency_id)),
+            }
+        }
+        Ok(Ok(total))
+    }
+
+    // Get a record snapshot at a specific version using history new_values
+    pub async fn get_record_at_version(
+        db: &DatabaseConnection,
+        record_id: i64,
+        version: i32,
+    ) -> Result<MedicalRecord, String> {
+        // Fetch base record
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id, patient_id, record_type, name, procedure_name, description, \
+                 prescription_notes, price, currency_id, is_archived, follow_up_date, follow_up_done, \
+                 version, created_at, updated_at, created_by, updated_by \
+                 FROM medical_records WHERE id = ?",
+                [record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch medical record: {}", e))?
+            .ok_or("Medical record not found".to_string())?;
+
+        let mut base = Self::record_from_row(&row)?;
+
+        // Fetch history snapshot
+        let hrow = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT new_values FROM medical_record_history WHERE medical_record_id = ? AND version = ?",
+                [record_id.into(), version.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch history: {}", e))?;
+
+        if let Some(hr) = hrow {
+            let json_str: String = hr.try_get("", "new_values").unwrap_or_default();
+            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&json_str) {
+                if let Some(rt) = v.get("record_type").and_then(|x| x.as_str()) { base.record_type = rt.to_string(); }
+                if let Some(nm) = v.get("name").and_then(|x| x.as_str()) { base.name = nm.to_string(); }
+                if let Some(pn) = v.get("procedure_name").and_then(|x| x.as_str()) { base.procedure_name = Some(pn.to_string()); }
+                if let Some(desc) = v.get("description").and_then(|x| x.as_str()) { base.description = desc.to_string(); }
+                if let Some(prn) = v.get("prescription_notes").and_then(|x| x.as_str()) { base.prescription_notes = Some(prn.to_string()); }
+                if let Some(pr) = v.get("price").and_then(|x| x.as_f64()) { base.price = Some(pr); }
+                if let Some(cid) = v.get("currency_id").and_then(|x| x.as_i64()) { base.currency_id = Some(cid); }
+                if let Some(ia) = v.get("is_archived").and_then(|x| x.as_bool()) { base.is_archived = ia; }
+                base.version = version;
+            }
         }
-        if let Some(v) = old_vals.get("is_archived") { updates.is_archived = v.as_bool(); }
+
+        Ok(base)
+    }
+
+    /// The fields changed by one history entry, compared from its stored
+    /// old and new snapshots.
+    pub async fn get_medical_record_diff(
+        db: &DatabaseConnection,
+        record_id: i64,
+        version: i32,
+    ) -> Result<Vec<FieldChange>, String> {
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT old_values, new_values FROM medical_record_history \
+                 WHERE medical_record_id = ? AND version = ?",
+                [record_id.into(), version.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch history: {}", e))?
+            .ok_or(format!("Version {} not found", version))?;
+
+        let parse = |col: &str| -> Result<Option<serde_json::Value>, String> {
+            row.try_get::<Option<String>>("", col)
+                .map_err(|e| format!("Failed to read {}: {}", col, e))?
+                .map(|s| serde_json::from_str(&s).map_err(|e| format!("Failed to parse history values: {}", e)))
+                .transpose()
+        };
+        let old_values = parse("old_values")?;
+        let new_values = parse("new_values")?.unwrap_or(serde_json::Value::Null);
+        Ok(diff_snapshots(old_values.as_ref(), &new_values))
+    }
+
+    // Revert a record one step to its previous version using latest history old_values
+    pub async fn revert_one_step(
+        app_handle: &tauri::AppHandle,
+        db: &DatabaseConnection,
+        record_id: i64,
+        actor: Option<&User>,
+    ) -> Result<MedicalRecord, String> {
+        // Fetch latest history entry
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT version, old_values FROM medical_record_history \
+                 WHERE medical_record_id = ? ORDER BY version DESC LIMIT 1",
+                [record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch history: {}", e))?
+            .ok_or("No history available to revert".to_string())?;
+
+        let old_values_str: Option<String> = row.try_get("", "old_values").ok();
+        if old_values_str.is_none() {
+            return Err("No previous values recorded to revert".to_string());
+        }
+
+        let old_vals: serde_json::Value = serde_json::from_str(&old_values_str.unwrap())
+            .map_err(|e| format!("Failed to parse history values: {}", e))?;
+
+        let updates = updates_from_snapshot(&old_vals);
 
         // If no fields present, abort
-        if updates.name.is_none()
-            && updates.procedure_name.is_none()
-            && updates.description.is_none()
-            && updates.prescription_notes.is_none()
-            && matches!(updates.price, MaybeNull::Undefined)
-            && matches!(updates.currency_id, MaybeNull::Undefined)
-            && updates.is_archived.is_none() {
+        if !has_restorable_fields(&updates) {
             return Err("No revertable fields in previous version".to_string());
         }
 
-        Self::update_medical_record(app_handle, db, record_id, updates).await
+        Self::update_medical_record(app_handle, db, record_id, updates, actor).await
     }
+
+    /// The updates that bring a record back to the snapshot recorded for
+    /// `version`. Text fields that were empty at that version are cleared,
+    /// since an update can't set them back to NULL; line items aren't part
+    /// of the snapshot and stay as they are.
+    pub async fn updates_for_version(
+        db: &DatabaseConnection,
+        record_id: i64,
+        version: i32,
+    ) -> Result<UpdateMedicalRecordInput, String> {
+        let current = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT version FROM medical_records WHERE id = ?",
+                [record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch medical record: {}", e))?
+            .ok_or("Medical record not found".to_string())?;
+        let current_version: i32 = current.try_get("", "version").unwrap_or(1);
+        if version == current_version {
+            return Err(format!("Record is already at version {}", version));
+        }
+
+        // Moves and auto-archiving record only the fields they changed, so the
+        // state at `version` is every snapshot up to it laid over the full one
+        // written when the record was created
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT version, new_values FROM medical_record_history \
+                 WHERE medical_record_id = ? AND version <= ? ORDER BY version",
+                [record_id.into(), version.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch history: {}", e))?;
+        if !rows.iter().any(|row| row.try_get::<i32>("", "version").ok() == Some(version)) {
+            return Err(format!("Version {} not found", version));
+        }
+        let mut fields = serde_json::Map::new();
+        for row in &rows {
+            let Some(new_values) = row.try_get::<Option<String>>("", "new_values").ok().flatten() else {
+                continue;
+            };
+            let values: serde_json::Value = serde_json::from_str(&new_values)
+                .map_err(|e| format!("Failed to parse history values: {}", e))?;
+            if let Some(values) = values.as_object() {
+                fields.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        let snapshot = serde_json::Value::Object(fields);
+
+        let mut updates = updates_from_snapshot(&snapshot);
+        for (field, value) in [
+            ("procedure_name", &mut updates.procedure_name),
+            ("prescription_notes", &mut updates.prescription_notes),
+        ] {
+            if snapshot.get(field).map_or(false, |v| v.is_null()) {
+                *value = Some(String::new());
+            }
+        }
+        if !has_restorable_fields(&updates) {
+            return Err(format!("No revertable fields in version {}", version));
+        }
+        Ok(updates)
+    }
+
+    /// Revert a record to any earlier (or later) version; the result is
+    /// saved as a new version, so the history stays intact.
+    pub async fn revert_medical_record_to_version(
+        app_handle: &tauri::AppHandle,
+        db: &DatabaseConnection,
+        record_id: i64,
+        version: i32,
+        actor: Option<&User>,
+    ) -> Result<MedicalRecord, String> {
+        let updates = Self::updates_for_version(db, record_id, version).await?;
+        Self::update_medical_record(app_handle, db, record_id, updates, actor).await
+    }
+}
+
+/// Fields whose value differs between two snapshots, in field-name order.
+/// Without an old snapshot every field of the new one is an addition.
+pub fn diff_snapshots(old: Option<&serde_json::Value>, new: &serde_json::Value) -> Vec<FieldChange> {
+    let null = serde_json::Value::Null;
+    let empty = serde_json::Map::new();
+    let new_fields = new.as_object().unwrap_or(&empty);
+    let old_fields = old.and_then(|o| o.as_object()).unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = new_fields.keys().chain(old_fields.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old_value = old_fields.get(field).unwrap_or(&null);
+            let new_value = new_fields.get(field).unwrap_or(&null);
+            (old.is_none() || old_value != new_value).then(|| FieldChange {
+                field: field.clone(),
+                old: old_value.clone(),
+                new: new_value.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Updates restoring the fields present in a history snapshot; fields
+/// missing from it are left untouched.
+fn updates_from_snapshot(snapshot: &serde_json::Value) -> UpdateMedicalRecordInput {
+    let mut updates = UpdateMedicalRecordInput {
+        record_type: None,
+        name: None,
+        procedure_name: None,
+        description: None,
+        prescription_notes: None,
+        price: MaybeNull::Undefined,
+        currency_id: MaybeNull::Undefined,
+        discount_percent: MaybeNull::Undefined,
+        manual_total: MaybeNull::Undefined,
+        is_archived: None,
+        line_items: None,
+    };
+
+    if let Some(v) = snapshot.get("record_type") { updates.record_type = v.as_str().map(|s| s.to_string()); }
+    if let Some(v) = snapshot.get("name") { updates.name = v.as_str().map(|s| s.to_string()); }
+    if let Some(v) = snapshot.get("procedure_name") { updates.procedure_name = v.as_str().map(|s| s.to_string()); }
+    if let Some(v) = snapshot.get("description") { updates.description = v.as_str().map(|s| s.to_string()); }
+    if let Some(v) = snapshot.get("prescription_notes") { updates.prescription_notes = v.as_str().map(|s| s.to_string()); }
+    if let Some(v) = snapshot.get("price") {
+        updates.price = match v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)) {
+            Some(p) => MaybeNull::Value(p),
+            None if v.is_null() => MaybeNull::Null,
+            None => MaybeNull::Undefined,
+        };
+    }
+    if let Some(v) = snapshot.get("currency_id") {
+        updates.currency_id = match v.as_i64() {
+            Some(id) => MaybeNull::Value(id),
+            None if v.is_null() => MaybeNull::Null,
+            None => MaybeNull::Undefined,
+        };
+    }
+    if let Some(v) = snapshot.get("discount_percent") {
+        updates.discount_percent = match v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)) {
+            Some(p) => MaybeNull::Value(p),
+            None if v.is_null() => MaybeNull::Null,
+            None => MaybeNull::Undefined,
+        };
+    }
+    if let Some(v) = snapshot.get("manual_total") {
+        updates.manual_total = match v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)) {
+            Some(p) => MaybeNull::Value(p),
+            None if v.is_null() => MaybeNull::Null,
+            None => MaybeNull::Undefined,
+        };
+    }
+    if let Some(v) = snapshot.get("is_archived") { updates.is_archived = v.as_bool(); }
+    updates
+}
+
+/// Whether the updates built from a snapshot would change anything; a
+/// snapshot of a move holds only `patient_id`, which a revert doesn't touch.
+fn has_restorable_fields(updates: &UpdateMedicalRecordInput) -> bool {
+    updates.record_type.is_some()
+        || updates.name.is_some()
+        || updates.procedure_name.is_some()
+        || updates.description.is_some()
+        || updates.prescription_notes.is_some()
+        || !matches!(updates.price, MaybeNull::Undefined)
+        || !matches!(updates.currency_id, MaybeNull::Undefined)
+        || !matches!(updates.discount_percent, MaybeNull::Undefined)
+        || !matches!(updates.manual_total, MaybeNull::Undefined)
+        || updates.is_archived.is_some()
+}
+
+/// The history snapshot of a `medical_records` row: every field a revert
+/// can restore.
+fn record_snapshot(row: &QueryResult) -> serde_json::Value {
+    json!({
+        "record_type": row.try_get::<String>("", "record_type").unwrap_or_default(),
+        "name": row.try_get::<String>("", "name").ok(),
+        "procedure_name": row.try_get::<String>("", "procedure_name").ok(),
+        "description": row.try_get::<String>("", "description").ok(),
+        "prescription_notes": row.try_get::<String>("", "prescription_notes").ok(),
+        "price": read_f64(row, "price"),
+        "currency_id": row.try_get::<i64>("", "currency_id").ok(),
+        "discount_percent": read_f64(row, "discount_percent"),
+        "manual_total": read_f64(row, "manual_total"),
+        "is_archived": row.try_get::<i64>("", "is_archived").unwrap_or(0) != 0
+    })
+}
+
+/// A numeric column stored as either INTEGER or REAL.
+fn read_f64(row: &QueryResult, col: &str) -> Option<f64> {
+    row.try_get::<i64>("", col)
+        .ok()
+        .map(|i| i as f64)
+        .or_else(|| row.try_get::<f64>("", col).ok())
+}
+
+/// Charges are reported to two decimals.
+fn round_charge(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Escape `%`, `_` and the escape character itself so `value` matches
+/// literally in a `LIKE ? ESCAPE '\'` pattern.
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
 }
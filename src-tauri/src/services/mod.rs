@@ -1,10 +1,13 @@
 pub mod medical_record;
 pub mod file_storage;
+pub mod attachment_ocr;
+pub mod record_lock;
 pub mod pdf_render;
 pub mod settings;
 pub mod appointments;
 pub mod rooms;
 pub mod google_calendar;
+pub mod google_connection;
 pub mod sync;
 pub mod oauth;
 pub mod sync_scheduler;
@@ -15,14 +18,29 @@ pub mod device_input;
 pub mod file_watcher;
 pub mod device_integration;
 pub mod device_parser;
+pub mod device_parse_mapping;
+pub mod device_data_buffer;
 pub mod device_pdf_service;
 pub mod java_pdf_service;
+pub mod pdf_jobs;
 pub mod device_capture;
 pub mod line_item;
 pub mod backup;
+pub mod log_export;
+pub mod household_export;
+pub mod dashboard;
+pub mod data_integrity;
+pub mod events;
 pub mod managed_hid_scanner;
 pub mod raw_input_capture;
 pub mod diagnosis;
+pub mod vaccination;
+pub mod users;
+pub mod storage_usage;
 pub mod log_rotation;
 pub mod telemetry;
 pub mod loki_shipper;
+pub mod clinic_profile;
+pub mod device_raw_capture;
+pub mod patient_import;
+pub mod attachment_thumbnails;
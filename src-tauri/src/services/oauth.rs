@@ -362,11 +362,41 @@ impl OAuthService {
 
 // T039: Token refresh middleware
 use chrono::Utc;
+use crate::services::google_connection::{GoogleClient, GoogleHttpClient};
+
+/// Why no usable access token could be produced.
+#[derive(Debug)]
+pub(crate) enum AccessTokenError {
+    /// No settings row or no stored tokens: Google was never connected or
+    /// was disconnected.
+    NotConnected(String),
+    /// The token had expired and Google refused to refresh it.
+    RefreshFailed(String),
+    Database(String),
+}
+
+impl std::fmt::Display for AccessTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConnected(msg) | Self::RefreshFailed(msg) | Self::Database(msg) => f.write_str(msg),
+        }
+    }
+}
 
 /// Get a valid access token, refreshing if necessary (SeaORM version)
 pub async fn get_valid_access_token(
     db: &sea_orm::DatabaseConnection,
 ) -> Result<String, String> {
+    valid_access_token_with(db, &GoogleHttpClient)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `get_valid_access_token` with the refresh done through `client`.
+pub(crate) async fn valid_access_token_with<C: GoogleClient>(
+    db: &sea_orm::DatabaseConnection,
+    client: &C,
+) -> Result<String, AccessTokenError> {
     use sea_orm::{ConnectionTrait, Statement, DbBackend};
 
     // Get current settings
@@ -375,14 +405,14 @@ pub async fn get_valid_access_token(
         "SELECT access_token, refresh_token, token_expires_at FROM google_calendar_settings WHERE user_id = 'default'".to_string()
     ))
     .await
-    .map_err(|e| format!("Failed to query settings: {}", e))?
-    .ok_or("Google Calendar not configured")?;
+    .map_err(|e| AccessTokenError::Database(format!("Failed to query settings: {}", e)))?
+    .ok_or_else(|| AccessTokenError::NotConnected("Google Calendar not configured".to_string()))?;
 
     let access_token: String = row.try_get("", "access_token")
-        .map_err(|_| "No access token available".to_string())?;
+        .map_err(|_| AccessTokenError::NotConnected("No access token available".to_string()))?;
 
     let refresh_token: String = row.try_get("", "refresh_token")
-        .map_err(|_| "No refresh token available".to_string())?;
+        .map_err(|_| AccessTokenError::NotConnected("No refresh token available".to_string()))?;
 
     let token_expires_at: Option<String> = row.try_get("", "token_expires_at").ok();
 
@@ -406,7 +436,10 @@ pub async fn get_valid_access_token(
     // Token is expired or about to expire, refresh it
     log::info!("Access token expired, refreshing...");
 
-    let (new_access_token, expires_in) = OAuthService::refresh_access_token(refresh_token).await?;
+    let (new_access_token, expires_in) = client
+        .refresh_access_token(refresh_token)
+        .await
+        .map_err(AccessTokenError::RefreshFailed)?;
 
     // Calculate new expiration time
     let new_expires_at = Utc::now() + chrono::Duration::seconds(expires_in);
@@ -418,7 +451,7 @@ pub async fn get_valid_access_token(
         [new_access_token.clone().into(), new_expires_at.to_rfc3339().into()]
     ))
     .await
-    .map_err(|e| format!("Failed to update access token: {}", e))?;
+    .map_err(|e| AccessTokenError::Database(format!("Failed to update access token: {}", e)))?;
 
     log::info!("Access token refreshed successfully");
 
@@ -1,14 +1,48 @@
 use crate::entities::patient::{self, Entity as PatientEntity};
-use crate::models::{Patient, CreatePatientDto, UpdatePatientDto};
+use crate::database::queries::household;
+use crate::models::{
+    AppointmentFilter, PaginationParams, Patient, PatientListFilter, PatientListItem,
+    PatientListResponse, PatientPrimaryContact, PatientProfile, PatientReclassification,
+    PatientAge, PatientSort, PatientSortField, PatientWeightTrend, RecallCandidate,
+    CreatePatientDto, DuplicatePatientError, PatientMergeResult, PatientWriteError, UpdatePatientDto, WeightPoint,
+    WeightTrendDirection,
+};
 use crate::models::dto::MaybeNull;
-use chrono::Utc;
+use crate::models::user::User;
+use crate::services::appointments::AppointmentService;
+use crate::services::users::UserService;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, Utc};
 use sea_orm::*;
 
+/// Weight change (either way) flagged as clinically significant when the
+/// caller doesn't pass its own threshold.
+pub const DEFAULT_WEIGHT_ALERT_PERCENT: f64 = 10.0;
+
+/// Largest page `list_patients` returns.
+const MAX_PATIENT_PAGE_SIZE: i32 = 200;
+
+/// Changes smaller than this are reported as stable.
+const WEIGHT_STABLE_PERCENT: f64 = 2.0;
+
+/// Joins patient `p`'s primary household link as `ph` and the owner to
+/// contact as `owner`: the household's primary person, falling back to the
+/// first one added. A soft-deleted household has no owner, as in
+/// `get_primary_contact`.
+pub(crate) const PRIMARY_OWNER_JOIN: &str = "
+             LEFT JOIN patient_households ph ON ph.patient_id = p.id AND ph.is_primary = 1
+             LEFT JOIN households owner_household
+                 ON owner_household.id = ph.household_id AND owner_household.deleted_at IS NULL
+             LEFT JOIN people owner ON owner.id = (
+                 SELECT pe.id FROM people pe WHERE pe.household_id = owner_household.id
+                 ORDER BY pe.is_primary DESC, pe.id LIMIT 1)";
+
 pub struct PatientService;
 
 impl PatientService {
-    /// Helper to convert a raw query row to a Patient model
-    fn row_to_patient(row: &QueryResult) -> Result<Patient, String> {
+    /// Helper to convert a raw query row to a Patient model, aged on `today`
+    fn row_to_patient(row: &QueryResult, today: NaiveDate) -> Result<Patient, String> {
+        let date_of_birth: Option<NaiveDate> = row.try_get("", "date_of_birth").ok();
+        let age = Self::age_on(date_of_birth, today);
         Ok(Patient {
             id: row.try_get("", "id").map_err(|e| e.to_string())?,
             name: row.try_get("", "name").ok(),
@@ -17,7 +51,7 @@ impl PatientService {
             species: row.try_get("", "species").ok(),
             breed: row.try_get("", "breed").ok(),
             gender: row.try_get("", "gender").ok(),
-            date_of_birth: row.try_get("", "date_of_birth").ok(),
+            date_of_birth,
             color: row.try_get("", "color").ok(),
             weight: row.try_get("", "weight").ok(),
             microchip_id: row.try_get("", "microchip_id").ok(),
@@ -26,12 +60,54 @@ impl PatientService {
             household_id: row.try_get("", "household_id").ok(),
             created_at: row.try_get("", "created_at").map_err(|e| e.to_string())?,
             updated_at: row.try_get("", "updated_at").map_err(|e| e.to_string())?,
+            age_months: age.as_ref().map(|(months, _)| *months),
+            age_display: age.map(|(_, display)| display),
         })
     }
 
-    pub async fn get_all(db: &DatabaseConnection) -> Result<Vec<Patient>, String> {
+    /// Every patient, newest first. Deleted (inactive) patients are left
+    /// out unless `include_inactive` is set.
+    pub async fn get_all(db: &DatabaseConnection, include_inactive: bool) -> Result<Vec<Patient>, String> {
+        let filter = if include_inactive { "" } else { " WHERE p.is_active = 1 OR p.is_active IS NULL" };
         let rows = db
             .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!(r#"SELECT
+                    p.id,
+                    p.name,
+                    p.species_id,
+                    p.breed_id,
+                    s.name as species,
+                    b.name as breed,
+                    p.gender,
+                    p.date_of_birth,
+                    p.color,
+                    CAST(p.weight AS REAL) as weight,
+                    p.microchip_id,
+                    p.medical_notes,
+                    p.is_active,
+                    ph.household_id,
+                    p.created_at,
+                    p.updated_at
+                 FROM patients p
+                 LEFT JOIN species s ON p.species_id = s.id
+                 LEFT JOIN breeds b ON p.breed_id = b.id
+                 LEFT JOIN patient_households ph ON p.id = ph.patient_id AND ph.is_primary = 1{}
+                 ORDER BY p.created_at DESC"#, filter),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch patients: {}", e))?;
+
+        let today = Local::now().date_naive();
+        rows.iter()
+            .map(|r| Self::row_to_patient(r, today))
+            .collect()
+    }
+
+    /// Most recently added active patients, newest first
+    pub async fn get_recent(db: &DatabaseConnection, limit: i64) -> Result<Vec<Patient>, String> {
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
                 r#"SELECT
                     p.id,
@@ -54,13 +130,17 @@ impl PatientService {
                  LEFT JOIN species s ON p.species_id = s.id
                  LEFT JOIN breeds b ON p.breed_id = b.id
                  LEFT JOIN patient_households ph ON p.id = ph.patient_id AND ph.is_primary = 1
-                 ORDER BY p.created_at DESC"#.to_string(),
+                 WHERE p.is_active = 1 OR p.is_active IS NULL
+                 ORDER BY p.created_at DESC, p.id DESC
+                 LIMIT ?"#,
+                [limit.into()],
             ))
             .await
-            .map_err(|e| format!("Failed to fetch patients: {}", e))?;
+            .map_err(|e| format!("Failed to fetch recent patients: {}", e))?;
 
+        let today = Local::now().date_naive();
         rows.iter()
-            .map(Self::row_to_patient)
+            .map(|r| Self::row_to_patient(r, today))
             .collect()
     }
 
@@ -96,12 +176,22 @@ impl PatientService {
             .map_err(|e| format!("Failed to fetch patient: {}", e))?;
 
         match row {
-            Some(r) => Ok(Some(Self::row_to_patient(&r)?)),
+            Some(r) => Ok(Some(Self::row_to_patient(&r, Local::now().date_naive())?)),
             None => Ok(None),
         }
     }
 
     pub async fn create(db: &DatabaseConnection, dto: CreatePatientDto) -> Result<Patient, String> {
+        Self::create_with_options(db, dto, false).await.map_err(String::from)
+    }
+
+    /// `create`, refusing a microchip another patient already has unless
+    /// `allow_duplicate_microchip` is set.
+    pub async fn create_with_options(
+        db: &DatabaseConnection,
+        dto: CreatePatientDto,
+        allow_duplicate_microchip: bool,
+    ) -> Result<Patient, PatientWriteError> {
         // Normalize empty strings to None so the DB stores NULL rather than ""
         let name = dto.name.as_ref()
             .map(|s| s.trim())
@@ -115,11 +205,19 @@ impl PatientService {
         // Require at least some way to identify the patient. The frontend
         // enforces this too, but defend against API misuse.
         if name.is_none() && microchip_id.is_none() {
-            return Err("Patient must have either a name or a microchip ID".to_string());
+            return Err("Patient must have either a name or a microchip ID".to_string().into());
+        }
+        if let (Some(chip), false) = (&microchip_id, allow_duplicate_microchip) {
+            if let Some(duplicate) = Self::find_microchip_duplicate(db, chip, None).await? {
+                return Err(PatientWriteError::DuplicateMicrochip(duplicate));
+            }
         }
 
         log::info!("🐾 Creating patient: name={:?}, species_id={:?}, breed_id={:?}, gender={:?}",
             name, dto.species_id, dto.breed_id, dto.gender);
+        if let Some(date_of_birth) = dto.date_of_birth {
+            Self::warn_if_unborn(date_of_birth);
+        }
 
         let now = Utc::now();
 
@@ -178,12 +276,23 @@ impl PatientService {
             .await
             .map_err(|e| format!("Failed to commit patient create: {}", e))?;
 
-        Self::get_by_id(db, patient_id)
+        Ok(Self::get_by_id(db, patient_id)
             .await?
-            .ok_or_else(|| "Failed to fetch created patient".to_string())
+            .ok_or_else(|| "Failed to fetch created patient".to_string())?)
     }
 
     pub async fn update(db: &DatabaseConnection, id: i64, dto: UpdatePatientDto) -> Result<Option<Patient>, String> {
+        Self::update_with_options(db, id, dto, false).await.map_err(String::from)
+    }
+
+    /// `update`, refusing a microchip another patient already has unless
+    /// `allow_duplicate_microchip` is set.
+    pub async fn update_with_options(
+        db: &DatabaseConnection,
+        id: i64,
+        dto: UpdatePatientDto,
+        allow_duplicate_microchip: bool,
+    ) -> Result<Option<Patient>, PatientWriteError> {
         // Check if patient exists
         let exists = db
             .query_one(Statement::from_sql_and_values(
@@ -248,6 +357,7 @@ impl PatientService {
                 set_clauses.push("date_of_birth = NULL".to_string());
             },
             MaybeNull::Value(v) => {
+                Self::warn_if_unborn(v);
                 set_clauses.push("date_of_birth = ?".to_string());
                 params.push(v.into());
             },
@@ -293,6 +403,11 @@ impl PatientService {
                 set_clauses.push("microchip_id = NULL".to_string());
             },
             MaybeNull::Value(v) => {
+                if !allow_duplicate_microchip {
+                    if let Some(duplicate) = Self::find_microchip_duplicate(db, &v, Some(id)).await? {
+                        return Err(PatientWriteError::DuplicateMicrochip(duplicate));
+                    }
+                }
                 set_clauses.push("microchip_id = ?".to_string());
                 params.push(v.into());
             },
@@ -304,7 +419,7 @@ impl PatientService {
         }
 
         if set_clauses.is_empty() {
-            return Self::get_by_id(db, id).await;
+            return Ok(Self::get_by_id(db, id).await?);
         }
 
         // Always update updated_at
@@ -323,10 +438,74 @@ impl PatientService {
             .await
             .map_err(|e| format!("Failed to update patient: {}", e))?;
 
-        Self::get_by_id(db, id).await
+        Ok(Self::get_by_id(db, id).await?)
+    }
+
+    /// The patient (active or not) other than `exclude_id` that already
+    /// has `microchip_id`, compared without surrounding whitespace.
+    pub async fn find_microchip_duplicate(
+        db: &DatabaseConnection,
+        microchip_id: &str,
+        exclude_id: Option<i64>,
+    ) -> Result<Option<DuplicatePatientError>, String> {
+        let microchip_id = microchip_id.trim();
+        if microchip_id.is_empty() {
+            return Ok(None);
+        }
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id, name FROM patients WHERE TRIM(microchip_id) = ? AND id != ? ORDER BY id LIMIT 1",
+                [microchip_id.into(), exclude_id.unwrap_or(0).into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to check microchip: {}", e))?;
+        Ok(row.map(|row| DuplicatePatientError {
+            patient_id: row.try_get("", "id").unwrap_or(0),
+            patient_name: row.try_get("", "name").ok(),
+            microchip_id: microchip_id.to_string(),
+        }))
     }
 
+    /// Soft delete: mark the patient inactive so it drops out of lists and
+    /// search. Appointments, medical records and attachments are kept and
+    /// come back with `restore`. Returns false if the patient doesn't exist.
     pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<bool, String> {
+        Self::set_active(db, id, false).await
+    }
+
+    /// Undo `delete`. Returns the patient, or `None` if it doesn't exist.
+    pub async fn restore(db: &DatabaseConnection, id: i64) -> Result<Option<Patient>, String> {
+        if !Self::set_active(db, id, true).await? {
+            return Ok(None);
+        }
+        Self::get_by_id(db, id).await
+    }
+
+    async fn set_active(db: &DatabaseConnection, id: i64, active: bool) -> Result<bool, String> {
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "UPDATE patients SET is_active = ? WHERE id = ?",
+                [active.into(), id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to update patient: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the patient permanently. This cascades to its appointments,
+    /// medical records and attachments, so it is for admins only: `actor`
+    /// is the current user, and with nobody picked (`None`) it is refused.
+    pub async fn hard_delete(db: &DatabaseConnection, id: i64, actor: Option<&User>) -> Result<bool, String> {
+        match actor {
+            Some(user) if user.role == "admin" => {}
+            Some(user) => {
+                return Err(format!("Only admins can permanently delete patients ({} is {})", user.name, user.role));
+            }
+            None => return Err("Only admins can permanently delete patients (no user is selected)".to_string()),
+        }
+
         let result = PatientEntity::delete_by_id(id)
             .exec(db)
             .await
@@ -335,6 +514,265 @@ impl PatientService {
         Ok(result.rows_affected > 0)
     }
 
+    /// Fold duplicate patient `source_id` into `target_id`: its medical
+    /// records, appointments, vaccinations, weight history and household
+    /// links move to the target, and the source is soft-deleted. Moved
+    /// records get a version and history entry like any other move. A
+    /// household the target is already linked to is skipped, and a moved
+    /// link only stays primary if the target had no primary household. All
+    /// of it happens in one transaction: on any error nothing changes.
+    pub async fn merge(
+        db: &DatabaseConnection,
+        source_id: i64,
+        target_id: i64,
+        actor: Option<&User>,
+    ) -> Result<PatientMergeResult, String> {
+        if source_id == target_id {
+            return Err("Can't merge a patient into itself".to_string());
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let exists = |id: i64| {
+            Statement::from_sql_and_values(DbBackend::Sqlite, "SELECT is_active FROM patients WHERE id = ?", [id.into()])
+        };
+        txn.query_one(exists(source_id))
+            .await
+            .map_err(|e| format!("Failed to fetch patient: {}", e))?
+            .ok_or_else(|| format!("Patient {} not found", source_id))?;
+        let target = txn
+            .query_one(exists(target_id))
+            .await
+            .map_err(|e| format!("Failed to fetch patient: {}", e))?
+            .ok_or_else(|| format!("Patient {} not found", target_id))?;
+        if !target.try_get::<bool>("", "is_active").unwrap_or(true) {
+            return Err(format!("Patient {} is inactive", target_id));
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let actor = UserService::actor_id(actor);
+        let execute = |sql: &'static str, values: Vec<Value>, what: &'static str| {
+            let txn = &txn;
+            async move {
+                txn.execute(Statement::from_sql_and_values(DbBackend::Sqlite, sql, values))
+                    .await
+                    .map(|result| result.rows_affected())
+                    .map_err(|e| format!("Failed to move {}: {}", what, e))
+            }
+        };
+
+        execute(
+            "INSERT INTO medical_record_history \
+                 (medical_record_id, version, changed_fields, old_values, new_values, changed_by) \
+             SELECT id, version + 1, 'patient_id', json_object('patient_id', patient_id), \
+                 json_object('patient_id', ?), ? \
+             FROM medical_records WHERE patient_id = ?",
+            vec![target_id.into(), actor.clone().into(), source_id.into()],
+            "medical record history",
+        )
+        .await?;
+        let medical_records_moved = execute(
+            "UPDATE medical_records SET patient_id = ?, updated_at = ?, updated_by = ?, version = version + 1 \
+             WHERE patient_id = ?",
+            vec![target_id.into(), now.clone().into(), actor.into(), source_id.into()],
+            "medical records",
+        )
+        .await?;
+        // updated_at makes the push sync rewrite the events
+        let appointments_moved = execute(
+            "UPDATE appointments SET patient_id = ?, updated_at = ? WHERE patient_id = ?",
+            vec![target_id.into(), now.clone().into(), source_id.into()],
+            "appointments",
+        )
+        .await?;
+        let vaccinations_moved = execute(
+            "UPDATE vaccinations SET patient_id = ? WHERE patient_id = ?",
+            vec![target_id.into(), source_id.into()],
+            "vaccinations",
+        )
+        .await?;
+        let weights_moved = execute(
+            "UPDATE patient_weights SET patient_id = ? WHERE patient_id = ?",
+            vec![target_id.into(), source_id.into()],
+            "weight history",
+        )
+        .await?;
+        // OR IGNORE skips links that would break UNIQUE(patient_id, household_id)
+        let households_moved = execute(
+            "UPDATE OR IGNORE patient_households SET patient_id = ?1, \
+                 is_primary = CASE WHEN EXISTS (SELECT 1 FROM patient_households \
+                     WHERE patient_id = ?1 AND is_primary = 1) THEN 0 ELSE is_primary END \
+             WHERE patient_id = ?2",
+            vec![target_id.into(), source_id.into()],
+            "household links",
+        )
+        .await?;
+        let households_skipped = txn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT COUNT(*) AS count FROM patient_households WHERE patient_id = ?",
+                [source_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to count household links: {}", e))?
+            .and_then(|row| row.try_get::<i64>("", "count").ok())
+            .unwrap_or(0) as u64;
+
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE patients SET is_active = 0, updated_at = ? WHERE id = ?",
+            [now.into(), source_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to delete merged patient: {}", e))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit patient merge: {}", e))?;
+
+        log::info!(
+            "Merged patient {} into {}: {} records, {} appointments",
+            source_id, target_id, medical_records_moved, appointments_moved
+        );
+        let patient = Self::get_by_id(db, target_id)
+            .await?
+            .ok_or_else(|| format!("Patient {} not found", target_id))?;
+        Ok(PatientMergeResult {
+            patient,
+            medical_records_moved,
+            appointments_moved,
+            vaccinations_moved,
+            weights_moved,
+            households_moved,
+            households_skipped,
+        })
+    }
+
+    /// Move `patient_ids` to `species_id`, with `breed_id` or no breed.
+    /// Everything is checked before the first write: every patient must
+    /// exist and the breed must belong to the species, otherwise nothing
+    /// changes.
+    pub async fn reclassify(
+        db: &DatabaseConnection,
+        patient_ids: Vec<i64>,
+        species_id: i64,
+        breed_id: Option<i64>,
+    ) -> Result<PatientReclassification, String> {
+        let mut patient_ids = patient_ids;
+        patient_ids.sort_unstable();
+        patient_ids.dedup();
+        if patient_ids.is_empty() {
+            return Err("No patients to reclassify".to_string());
+        }
+
+        let species = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id FROM species WHERE id = ?",
+                [species_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch species: {}", e))?;
+        if species.is_none() {
+            return Err(format!("Species {} not found", species_id));
+        }
+
+        if let Some(breed_id) = breed_id {
+            let breed_species: i64 = db
+                .query_one(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "SELECT species_id FROM breeds WHERE id = ?",
+                    [breed_id.into()],
+                ))
+                .await
+                .map_err(|e| format!("Failed to fetch breed: {}", e))?
+                .ok_or_else(|| format!("Breed {} not found", breed_id))?
+                .try_get("", "species_id")
+                .map_err(|e| e.to_string())?;
+            if breed_species != species_id {
+                return Err(format!(
+                    "Breed {} does not belong to species {}",
+                    breed_id, species_id
+                ));
+            }
+        }
+
+        let placeholders = vec!["?"; patient_ids.len()].join(", ");
+        let id_values: Vec<Value> = patient_ids.iter().map(|id| (*id).into()).collect();
+
+        let found: Vec<i64> = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT id FROM patients WHERE id IN ({})", placeholders),
+                id_values.clone(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch patients: {}", e))?
+            .iter()
+            .filter_map(|row| row.try_get("", "id").ok())
+            .collect();
+        let missing: Vec<String> = patient_ids
+            .iter()
+            .filter(|id| !found.contains(id))
+            .map(|id| id.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Patients not found: {}", missing.join(", ")));
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let breeds_cleared: i64 = if breed_id.is_none() {
+            txn.query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT COUNT(*) AS count FROM patients WHERE id IN ({}) AND breed_id IS NOT NULL",
+                    placeholders
+                ),
+                id_values.clone(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to count breeds: {}", e))?
+            .and_then(|row| row.try_get("", "count").ok())
+            .unwrap_or(0)
+        } else {
+            0
+        };
+
+        let mut params: Vec<Value> = vec![
+            species_id.into(),
+            breed_id.into(),
+            Utc::now().to_rfc3339().into(),
+        ];
+        params.extend(id_values);
+        let result = txn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "UPDATE patients SET species_id = ?, breed_id = ?, updated_at = ? WHERE id IN ({})",
+                    placeholders
+                ),
+                params,
+            ))
+            .await
+            .map_err(|e| format!("Failed to reclassify patients: {}", e))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit reclassification: {}", e))?;
+
+        Ok(PatientReclassification {
+            patients_updated: result.rows_affected(),
+            breeds_cleared: breeds_cleared as u64,
+        })
+    }
+
     pub async fn get_by_species(db: &DatabaseConnection, species: &str) -> Result<Vec<Patient>, String> {
         let rows = db
             .query_all(Statement::from_sql_and_values(
@@ -367,12 +805,15 @@ impl PatientService {
             .await
             .map_err(|e| format!("Failed to fetch patients by species: {}", e))?;
 
+        let today = Local::now().date_naive();
         rows.iter()
-            .map(Self::row_to_patient)
+            .map(|r| Self::row_to_patient(r, today))
             .collect()
     }
 
-    pub async fn search(db: &DatabaseConnection, query: &str) -> Result<Vec<Patient>, String> {
+    /// Patients whose name or microchip contains `query`, by name.
+    /// Deleted (inactive) patients are left out unless `include_inactive`.
+    pub async fn search(db: &DatabaseConnection, query: &str, include_inactive: bool) -> Result<Vec<Patient>, String> {
         // SQLite's LIKE (and LOWER()) only case-fold ASCII a-z/A-Z, so a
         // search for "ана" would NOT match the stored "Ана" for Macedonian
         // Cyrillic names. Fetch the candidate set and filter in Rust with
@@ -414,9 +855,11 @@ impl PatientService {
             .await
             .map_err(|e| format!("Failed to search patients: {}", e))?;
 
+        let today = Local::now().date_naive();
         let mut matched: Vec<Patient> = rows
             .iter()
-            .filter_map(|r| Self::row_to_patient(r).ok())
+            .filter_map(|r| Self::row_to_patient(r, today).ok())
+            .filter(|p| include_inactive || p.is_active)
             .filter(|p| {
                 if needle.is_empty() {
                     return true;
@@ -489,9 +932,10 @@ impl PatientService {
             .await
             .map_err(|e| format!("Failed to search patients: {}", e))?;
 
+        let today = Local::now().date_naive();
         let mut matched: Vec<Patient> = rows
             .iter()
-            .filter_map(|r| Self::row_to_patient(r).ok())
+            .filter_map(|r| Self::row_to_patient(r, today).ok())
             .filter(|p| {
                 let name_ok = match &name_needle {
                     Some(n) => p
@@ -521,4 +965,565 @@ impl PatientService {
         matched.truncate(100);
         Ok(matched)
     }
+
+    /// One page of patients for the main patient table, with species and
+    /// breed names, the primary owner, the age on `today` and the cached
+    /// record counts. Upcoming appointments are those starting at or after
+    /// `now`. Ties in the sort are broken by id so pages don't overlap.
+    pub async fn list_patients(
+        db: &DatabaseConnection,
+        filter: Option<PatientListFilter>,
+        pagination: Option<PaginationParams>,
+        sort: Option<PatientSort>,
+        today: NaiveDate,
+        now: DateTime<Utc>,
+    ) -> Result<PatientListResponse, String> {
+        let filter = filter.unwrap_or_default();
+        let sort = sort.unwrap_or_default();
+        let page = pagination.as_ref().and_then(|p| p.page).unwrap_or(1).max(1);
+        let page_size = pagination
+            .as_ref()
+            .and_then(|p| p.page_size)
+            .unwrap_or(50)
+            .clamp(1, MAX_PATIENT_PAGE_SIZE);
+        let offset = (page as i64 - 1) * page_size as i64;
+
+        let mut clauses: Vec<&str> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(species_id) = filter.species_id {
+            clauses.push("p.species_id = ?");
+            values.push(species_id.into());
+        }
+        match filter.is_active {
+            Some(true) => clauses.push("(p.is_active = 1 OR p.is_active IS NULL)"),
+            Some(false) => clauses.push("p.is_active = 0"),
+            None => {}
+        }
+        if let Some(has_upcoming) = filter.has_upcoming_appointment {
+            clauses.push(if has_upcoming {
+                "EXISTS (SELECT 1 FROM appointments a WHERE a.patient_id = p.id \
+                 AND a.start_time >= ? AND a.deleted_at IS NULL AND a.status != 'cancelled')"
+            } else {
+                "NOT EXISTS (SELECT 1 FROM appointments a WHERE a.patient_id = p.id \
+                 AND a.start_time >= ? AND a.deleted_at IS NULL AND a.status != 'cancelled')"
+            });
+            values.push(now.to_rfc3339().into());
+        }
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", clauses.join(" AND "))
+        };
+
+        let from_sql = format!(
+            r#"
+             FROM patients p
+             LEFT JOIN species s ON p.species_id = s.id
+             LEFT JOIN breeds b ON p.breed_id = b.id{}
+             LEFT JOIN patient_record_counts rc ON rc.patient_id = p.id"#,
+            PRIMARY_OWNER_JOIN
+        );
+
+        let total: i64 = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT COUNT(*) AS total{}{}", from_sql, where_sql),
+                values.clone(),
+            ))
+            .await
+            .map_err(|e| format!("Failed to count patients: {}", e))?
+            .and_then(|row| row.try_get("", "total").ok())
+            .unwrap_or(0);
+
+        // Only these fixed expressions reach ORDER BY
+        let sort_columns: &[&str] = match sort.field {
+            PatientSortField::Name => &["p.name COLLATE NOCASE"],
+            PatientSortField::Species => &["s.name COLLATE NOCASE"],
+            PatientSortField::Breed => &["b.name COLLATE NOCASE"],
+            PatientSortField::Owner => &["owner.last_name COLLATE NOCASE", "owner.first_name COLLATE NOCASE"],
+            PatientSortField::DateOfBirth => &["p.date_of_birth"],
+            PatientSortField::CreatedAt => &["p.created_at"],
+        };
+        let direction = if sort.descending { "DESC" } else { "ASC" };
+        let order_sql = sort_columns
+            .iter()
+            .map(|column| format!("{} {}", column, direction))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut page_values = values;
+        page_values.push((page_size as i64).into());
+        page_values.push(offset.into());
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    r#"SELECT
+                        p.id,
+                        p.name,
+                        p.species_id,
+                        p.breed_id,
+                        s.name as species,
+                        b.name as breed,
+                        p.gender,
+                        p.date_of_birth,
+                        p.color,
+                        CAST(p.weight AS REAL) as weight,
+                        p.microchip_id,
+                        p.medical_notes,
+                        p.is_active,
+                        ph.household_id,
+                        p.created_at,
+                        p.updated_at,
+                        owner.first_name as owner_first_name,
+                        owner.last_name as owner_last_name,
+                        COALESCE(rc.record_count, 0) as record_count,
+                        COALESCE(rc.attachment_count, 0) as attachment_count{}{}
+                     ORDER BY {}, p.id {}
+                     LIMIT ? OFFSET ?"#,
+                    from_sql, where_sql, order_sql, direction
+                ),
+                page_values,
+            ))
+            .await
+            .map_err(|e| format!("Failed to list patients: {}", e))?;
+
+        let mut patients = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let patient = Self::row_to_patient(row, today)?;
+            let first: Option<String> = row.try_get("", "owner_first_name").ok();
+            let last: Option<String> = row.try_get("", "owner_last_name").ok();
+            let owner_name = match (first, last) {
+                (Some(f), Some(l)) => Some(format!("{} {}", f, l).trim().to_string()),
+                (Some(n), None) | (None, Some(n)) => Some(n),
+                (None, None) => None,
+            };
+            patients.push(PatientListItem {
+                age_months: patient.age_months,
+                owner_name,
+                record_count: row.try_get("", "record_count").unwrap_or(0),
+                attachment_count: row.try_get("", "attachment_count").unwrap_or(0),
+                patient,
+            });
+        }
+
+        Ok(PatientListResponse { patients, total, page, page_size })
+    }
+
+    /// Recompute the cached record and attachment counts for every patient.
+    ///
+    /// The triggers from migration 066 keep `patient_record_counts` current,
+    /// so this is only needed after the tables were written with the
+    /// triggers missing (e.g. a restored backup). Returns the patients that
+    /// have records.
+    pub async fn rebuild_record_counts(db: &DatabaseConnection) -> Result<u64, String> {
+        let txn = db.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+        txn.execute(Statement::from_string(DbBackend::Sqlite, "DELETE FROM patient_record_counts"))
+            .await
+            .map_err(|e| format!("Failed to clear record counts: {}", e))?;
+        let rebuilt = txn
+            .execute(Statement::from_string(
+                DbBackend::Sqlite,
+                r#"INSERT INTO patient_record_counts (patient_id, record_count, attachment_count)
+                   SELECT mr.patient_id,
+                       SUM(CASE WHEN mr.is_archived = 0 THEN 1 ELSE 0 END),
+                       (SELECT COUNT(*) FROM medical_attachments ma
+                         JOIN medical_records r ON ma.medical_record_id = r.id
+                         WHERE r.patient_id = mr.patient_id)
+                   FROM medical_records mr
+                   JOIN patients p ON p.id = mr.patient_id
+                   GROUP BY mr.patient_id"#,
+            ))
+            .await
+            .map_err(|e| format!("Failed to rebuild record counts: {}", e))?
+            .rows_affected();
+        txn.commit().await.map_err(|e| format!("Failed to commit record counts: {}", e))?;
+        Ok(rebuilt)
+    }
+
+    /// Patients whose age on `today` is within `[min_months, max_months]`,
+    /// with the primary household contact, for recall campaigns.
+    ///
+    /// Patients without a date of birth are skipped. There is no separate
+    /// deceased flag; deceased pets are marked inactive, so recalls should
+    /// pass `is_active = Some(true)`. `None` includes both.
+    pub async fn get_by_age_bracket(
+        db: &DatabaseConnection,
+        species_id: Option<i64>,
+        min_months: i64,
+        max_months: i64,
+        is_active: Option<bool>,
+        today: NaiveDate,
+    ) -> Result<Vec<RecallCandidate>, String> {
+        if min_months < 0 || max_months < min_months {
+            return Err(format!("Invalid age bracket: {} to {} months", min_months, max_months));
+        }
+
+        let mut clauses = vec!["p.date_of_birth IS NOT NULL".to_string()];
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(species_id) = species_id {
+            clauses.push("p.species_id = ?".to_string());
+            values.push(species_id.into());
+        }
+        match is_active {
+            Some(true) => clauses.push("(p.is_active = 1 OR p.is_active IS NULL)".to_string()),
+            Some(false) => clauses.push("p.is_active = 0".to_string()),
+            None => {}
+        }
+
+        // Phones prefer the primary entry, then mobile.
+        let sql = format!(
+            r#"SELECT
+                p.id,
+                p.name,
+                p.species_id,
+                p.breed_id,
+                s.name as species,
+                b.name as breed,
+                p.gender,
+                p.date_of_birth,
+                p.color,
+                CAST(p.weight AS REAL) as weight,
+                p.microchip_id,
+                p.medical_notes,
+                p.is_active,
+                ph.household_id,
+                p.created_at,
+                p.updated_at,
+                owner.first_name as owner_first_name,
+                owner.last_name as owner_last_name,
+                (SELECT pc.contact_value FROM person_contacts pc
+                  WHERE pc.person_id = owner.id AND pc.contact_type IN ('phone', 'mobile', 'work_phone')
+                  ORDER BY pc.is_primary DESC,
+                           CASE pc.contact_type WHEN 'mobile' THEN 0 WHEN 'phone' THEN 1 ELSE 2 END,
+                           pc.id
+                  LIMIT 1) as owner_phone,
+                (SELECT pc.contact_value FROM person_contacts pc
+                  WHERE pc.person_id = owner.id AND pc.contact_type = 'email'
+                  ORDER BY pc.is_primary DESC, pc.id
+                  LIMIT 1) as owner_email
+             FROM patients p
+             LEFT JOIN species s ON p.species_id = s.id
+             LEFT JOIN breeds b ON p.breed_id = b.id{}
+             WHERE {}
+             ORDER BY p.name COLLATE NOCASE, p.id"#,
+            PRIMARY_OWNER_JOIN,
+            clauses.join(" AND ")
+        );
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(DbBackend::Sqlite, &sql, values))
+            .await
+            .map_err(|e| format!("Failed to fetch patients by age: {}", e))?;
+
+        let mut candidates = Vec::new();
+        for row in &rows {
+            let patient = Self::row_to_patient(row, today)?;
+            let age_months = match patient.date_of_birth {
+                Some(dob) => Self::age_in_months(dob, today),
+                None => continue,
+            };
+            if age_months < min_months || age_months > max_months {
+                continue;
+            }
+
+            let first: Option<String> = row.try_get("", "owner_first_name").ok();
+            let last: Option<String> = row.try_get("", "owner_last_name").ok();
+            let owner_name = match (first, last) {
+                (Some(f), Some(l)) => Some(format!("{} {}", f, l).trim().to_string()),
+                (Some(n), None) | (None, Some(n)) => Some(n),
+                (None, None) => None,
+            };
+
+            candidates.push(RecallCandidate {
+                patient,
+                age_months,
+                owner_name,
+                owner_phone: row.try_get("", "owner_phone").ok(),
+                owner_email: row.try_get("", "owner_email").ok(),
+            });
+        }
+
+        Ok(candidates)
+    }
+
+    /// The patient with everything the detail screen needs: age on `today`,
+    /// primary household and owner, record/attachment counts and the first
+    /// appointment starting at or after `now`. `None` if the patient doesn't
+    /// exist; a patient without a household just has no household or owner.
+    pub async fn get_profile(
+        db: &DatabaseConnection,
+        id: i64,
+        today: NaiveDate,
+        now: DateTime<Utc>,
+    ) -> Result<Option<PatientProfile>, String> {
+        let patient = match Self::get_by_id(db, id).await? {
+            Some(patient) => patient,
+            None => return Ok(None),
+        };
+
+        let age_months = patient.date_of_birth.map(|dob| Self::age_in_months(dob, today));
+
+        let household = match patient.household_id {
+            Some(household_id) => {
+                let household_id = i32::try_from(household_id)
+                    .map_err(|_| format!("Invalid household id: {}", household_id))?;
+                household::get_household_with_people(db, household_id).await?
+            }
+            None => None,
+        };
+        let primary_owner = household.as_ref().and_then(|h| {
+            h.people
+                .iter()
+                .find(|person| person.is_primary)
+                .or_else(|| h.people.iter().min_by_key(|person| person.id))
+                .cloned()
+        });
+
+        let counts = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                r#"SELECT
+                    (SELECT COUNT(*) FROM medical_records mr
+                      WHERE mr.patient_id = ? AND mr.is_archived = 0) as medical_record_count,
+                    (SELECT COUNT(*) FROM medical_attachments ma
+                      JOIN medical_records mr ON ma.medical_record_id = mr.id
+                      WHERE mr.patient_id = ?) as attachment_count"#,
+                [id.into(), id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to count patient records: {}", e))?;
+        let (medical_record_count, attachment_count) = counts
+            .map(|row| {
+                (
+                    row.try_get::<i64>("", "medical_record_count").unwrap_or(0),
+                    row.try_get::<i64>("", "attachment_count").unwrap_or(0),
+                )
+            })
+            .unwrap_or((0, 0));
+
+        let upcoming = AppointmentService::get_appointments(
+            db,
+            AppointmentFilter {
+                patient_id: Some(id),
+                start_date: Some(now),
+                ..Default::default()
+            },
+            1,
+            0,
+        )
+        .await?;
+
+        Ok(Some(PatientProfile {
+            patient,
+            age_months,
+            household,
+            primary_owner,
+            medical_record_count,
+            attachment_count,
+            next_appointment: upcoming.appointments.into_iter().next(),
+        }))
+    }
+
+    /// The owner to contact about a patient, from its primary household.
+    /// Falls back to the household name when the household has no people;
+    /// `None` when the patient isn't linked to a household at all.
+    pub async fn get_primary_contact(
+        db: &DatabaseConnection,
+        patient_id: i64,
+    ) -> Result<Option<PatientPrimaryContact>, String> {
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                r#"SELECT
+                    ph.household_id,
+                    h.household_name,
+                    owner.first_name as owner_first_name,
+                    owner.last_name as owner_last_name,
+                    (SELECT pc.contact_value FROM person_contacts pc
+                      WHERE pc.person_id = owner.id AND pc.contact_type IN ('phone', 'mobile', 'work_phone')
+                      ORDER BY pc.is_primary DESC,
+                               CASE pc.contact_type WHEN 'mobile' THEN 0 WHEN 'phone' THEN 1 ELSE 2 END,
+                               pc.id
+                      LIMIT 1) as owner_phone,
+                    (SELECT pc.contact_value FROM person_contacts pc
+                      WHERE pc.person_id = owner.id AND pc.contact_type = 'email'
+                      ORDER BY pc.is_primary DESC, pc.id
+                      LIMIT 1) as owner_email
+                 FROM patient_households ph
+                 JOIN households h ON h.id = ph.household_id AND h.deleted_at IS NULL
+                 LEFT JOIN people owner ON owner.id = (
+                     SELECT pe.id FROM people pe WHERE pe.household_id = ph.household_id
+                     ORDER BY pe.is_primary DESC, pe.id LIMIT 1)
+                 WHERE ph.patient_id = ?
+                 ORDER BY ph.is_primary DESC, ph.household_id
+                 LIMIT 1"#,
+                [patient_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch primary contact: {}", e))?;
+        let row = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        let first: Option<String> = row.try_get("", "owner_first_name").ok();
+        let last: Option<String> = row.try_get("", "owner_last_name").ok();
+        let person_name = format!("{} {}", first.unwrap_or_default(), last.unwrap_or_default())
+            .trim()
+            .to_string();
+        let name = if person_name.is_empty() {
+            row.try_get::<String>("", "household_name")
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        } else {
+            person_name
+        };
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(PatientPrimaryContact {
+            name,
+            phone: row.try_get("", "owner_phone").ok(),
+            email: row.try_get("", "owner_email").ok(),
+            household_id: row.try_get("", "household_id").map_err(|e| e.to_string())?,
+        }))
+    }
+
+    /// Weight trend over the `window_days` before `now`, from the recorded
+    /// weight history. Needs at least two points in the window; with fewer
+    /// the direction is `InsufficientData` and nothing is flagged.
+    pub async fn get_weight_trend(
+        db: &DatabaseConnection,
+        patient_id: i64,
+        window_days: i64,
+        threshold_percent: f64,
+        now: DateTime<Utc>,
+    ) -> Result<PatientWeightTrend, String> {
+        if window_days <= 0 {
+            return Err("window_days must be positive".to_string());
+        }
+        if !threshold_percent.is_finite() || threshold_percent <= 0.0 {
+            return Err("threshold_percent must be positive".to_string());
+        }
+        if Self::get_by_id(db, patient_id).await?.is_none() {
+            return Err("Patient not found".to_string());
+        }
+
+        let since = now - chrono::Duration::days(window_days);
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT weight, recorded_at FROM patient_weights \
+                 WHERE patient_id = ? AND recorded_at >= ? AND recorded_at <= ? \
+                 ORDER BY recorded_at, id",
+                [
+                    patient_id.into(),
+                    since.format("%Y-%m-%d %H:%M:%S").to_string().into(),
+                    now.format("%Y-%m-%d %H:%M:%S").to_string().into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch weight history: {}", e))?;
+
+        let mut points = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let recorded_at: String = row.try_get("", "recorded_at").map_err(|e| e.to_string())?;
+            let recorded_at = NaiveDateTime::parse_from_str(&recorded_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| format!("Invalid weight timestamp '{}': {}", recorded_at, e))?
+                .and_utc();
+            points.push(WeightPoint {
+                weight: row.try_get("", "weight").map_err(|e| e.to_string())?,
+                recorded_at,
+            });
+        }
+
+        let percent_change = match (points.first(), points.last()) {
+            (Some(first), Some(last)) if points.len() >= 2 => {
+                Some((last.weight - first.weight) / first.weight * 100.0)
+            }
+            _ => None,
+        };
+        let direction = match percent_change {
+            None => WeightTrendDirection::InsufficientData,
+            Some(pct) if pct >= WEIGHT_STABLE_PERCENT => WeightTrendDirection::Gaining,
+            Some(pct) if pct <= -WEIGHT_STABLE_PERCENT => WeightTrendDirection::Losing,
+            Some(_) => WeightTrendDirection::Stable,
+        };
+
+        Ok(PatientWeightTrend {
+            patient_id,
+            window_days,
+            direction,
+            percent_change,
+            is_significant: percent_change.is_some_and(|pct| pct.abs() >= threshold_percent),
+            threshold_percent,
+            points,
+        })
+    }
+
+    /// A patient's age on `today`: `Err` if the patient doesn't exist,
+    /// `None` ages without a date of birth or with one in the future.
+    pub async fn get_age(db: &DatabaseConnection, id: i64, today: NaiveDate) -> Result<PatientAge, String> {
+        let patient = Self::get_by_id(db, id)
+            .await?
+            .ok_or_else(|| format!("Patient {} not found", id))?;
+        let age = Self::age_on(patient.date_of_birth, today);
+        Ok(PatientAge {
+            patient_id: patient.id,
+            date_of_birth: patient.date_of_birth,
+            age_months: age.as_ref().map(|(months, _)| *months),
+            age_display: age.map(|(_, display)| display),
+        })
+    }
+
+    /// Whole months since `date_of_birth` on `today`, with its display
+    /// form: "3y 2m" from a year on, "5 months" under a year, "2 weeks" (or
+    /// days) under a month. `None` without a date of birth, and for one in
+    /// the future (logged when it is saved, see `warn_if_unborn`).
+    pub fn age_on(date_of_birth: Option<NaiveDate>, today: NaiveDate) -> Option<(i64, String)> {
+        let dob = date_of_birth?;
+        if dob > today {
+            return None;
+        }
+        let months = Self::age_in_months(dob, today);
+        let plural = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+        let display = if months >= 12 {
+            match months % 12 {
+                0 => format!("{}y", months / 12),
+                rest => format!("{}y {}m", months / 12, rest),
+            }
+        } else if months >= 1 {
+            plural(months, "month")
+        } else {
+            let days = (today - dob).num_days();
+            if days >= 7 {
+                plural(days / 7, "week")
+            } else {
+                plural(days, "day")
+            }
+        };
+        Some((months, display))
+    }
+
+    /// A date of birth in the future is a data entry mistake; it's saved
+    /// anyway, but logged so it can be found.
+    fn warn_if_unborn(date_of_birth: NaiveDate) {
+        let today = Local::now().date_naive();
+        if date_of_birth > today {
+            log::warn!("Date of birth {} is in the future (today is {})", date_of_birth, today);
+        }
+    }
+
+    /// Whole months between `dob` and `today`; negative if born after today.
+    fn age_in_months(dob: NaiveDate, today: NaiveDate) -> i64 {
+        let mut months = (today.year() - dob.year()) as i64 * 12
+            + today.month() as i64 - dob.month() as i64;
+        if today.day() < dob.day() {
+            months -= 1;
+        }
+        months
+    }
 }
@@ -0,0 +1,382 @@
+//! Bulk import of patients from CSV, for clinics moving over from a
+//! spreadsheet.
+//!
+//! The first line is a header naming the columns, in any order and any
+//! case: `name`, `species`, `breed`, `date_of_birth`, `gender`, `weight`,
+//! `microchip_id`. Only `species` is required. Species and breeds are
+//! matched by name, case-insensitively; a breed that doesn't exist under
+//! the row's species is created only when asked for.
+//!
+//! Every row gets its own result, keyed by its line number in the file.
+//! Rows that fail validation are reported and skipped; the others are
+//! imported. A dry run validates and resolves everything the same way
+//! but writes nothing.
+
+use std::collections::HashSet;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::models::breed::CreateBreedInput;
+use crate::models::dto::CreatePatientDto;
+use crate::services::breed::BreedService;
+use crate::services::patient::PatientService;
+
+const IMPORT_COLUMNS: [&str; 7] = [
+    "name",
+    "species",
+    "breed",
+    "date_of_birth",
+    "gender",
+    "weight",
+    "microchip_id",
+];
+
+/// What happened to one CSV row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PatientImportRowResult {
+    /// 1-based line in the CSV where the row starts (the header is line 1)
+    #[ts(type = "number")]
+    pub line: usize,
+    pub name: Option<String>,
+    /// Valid; on a dry run, would be imported
+    pub success: bool,
+    /// The created patient; always null on a dry run
+    #[ts(type = "number | null")]
+    pub patient_id: Option<i64>,
+    /// Breed created (or, on a dry run, to be created) for this row
+    pub created_breed: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A validated row, ready to insert.
+struct ImportRow {
+    name: Option<String>,
+    species_id: i64,
+    breed: Option<ResolvedBreed>,
+    date_of_birth: Option<chrono::NaiveDate>,
+    gender: Option<String>,
+    weight: Option<f64>,
+    microchip_id: Option<String>,
+}
+
+enum ResolvedBreed {
+    Existing(i64),
+    /// Missing under the species, to be created
+    New(String),
+}
+
+pub struct PatientImportService;
+
+impl PatientImportService {
+    /// Import the patients in `csv_data`, or only validate them with
+    /// `dry_run`. Errs only when the file as a whole is unusable (empty, or
+    /// a header without `species` or with an unknown column); row problems
+    /// are reported in that row's result.
+    pub async fn import_csv(
+        db: &DatabaseConnection,
+        csv_data: &str,
+        dry_run: bool,
+        create_missing_breeds: bool,
+    ) -> Result<Vec<PatientImportRowResult>, String> {
+        let mut records = parse_csv(csv_data)?.into_iter();
+        let (_, header) = records.next().ok_or_else(|| "The CSV file is empty".to_string())?;
+        let columns = Self::header_columns(&header)?;
+
+        // Breeds a dry run would create, so a breed named by several rows is
+        // reported once, on the first, like a real import reports it
+        let mut planned_breeds: HashSet<(i64, String)> = HashSet::new();
+        let mut results = Vec::new();
+
+        for (line, fields) in records {
+            if fields.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+            let field = |column: &str| -> Option<String> {
+                columns
+                    .iter()
+                    .position(|c| c == column)
+                    .and_then(|i| fields.get(i))
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+            };
+            let name = field("name");
+            let mut result = PatientImportRowResult {
+                line,
+                name: name.clone(),
+                success: false,
+                patient_id: None,
+                created_breed: None,
+                error: None,
+            };
+
+            if fields.len() != columns.len() {
+                result.error = Some(format!(
+                    "Expected {} columns, found {}",
+                    columns.len(),
+                    fields.len()
+                ));
+                results.push(result);
+                continue;
+            }
+
+            let row = match Self::validate_row(db, &field, create_missing_breeds).await {
+                Ok(row) => row,
+                Err(e) => {
+                    result.error = Some(e);
+                    results.push(result);
+                    continue;
+                }
+            };
+
+            if dry_run {
+                if let Some(ResolvedBreed::New(breed_name)) = &row.breed {
+                    if planned_breeds.insert((row.species_id, breed_name.to_lowercase())) {
+                        result.created_breed = Some(breed_name.clone());
+                    }
+                }
+                result.success = true;
+                results.push(result);
+                continue;
+            }
+
+            match Self::insert_row(db, row).await {
+                Ok((patient_id, created_breed)) => {
+                    result.success = true;
+                    result.patient_id = Some(patient_id);
+                    result.created_breed = created_breed;
+                }
+                Err(e) => result.error = Some(e),
+            }
+            results.push(result);
+        }
+
+        if !dry_run {
+            let imported = results.iter().filter(|r| r.success).count();
+            log::info!("Imported {} of {} patients from CSV", imported, results.len());
+        }
+        Ok(results)
+    }
+
+    /// Lower-cased column names, checked against the known columns.
+    fn header_columns(header: &[String]) -> Result<Vec<String>, String> {
+        let columns: Vec<String> = header.iter().map(|c| c.trim().to_lowercase()).collect();
+        for (i, column) in columns.iter().enumerate() {
+            if !IMPORT_COLUMNS.contains(&column.as_str()) {
+                return Err(format!(
+                    "Unknown column '{}'; expected {}",
+                    header[i].trim(),
+                    IMPORT_COLUMNS.join(", ")
+                ));
+            }
+            if columns[..i].contains(column) {
+                return Err(format!("Column '{}' appears twice", column));
+            }
+        }
+        if !columns.iter().any(|c| c == "species") {
+            return Err("The CSV header has no 'species' column".to_string());
+        }
+        Ok(columns)
+    }
+
+    async fn validate_row(
+        db: &DatabaseConnection,
+        field: &impl Fn(&str) -> Option<String>,
+        create_missing_breeds: bool,
+    ) -> Result<ImportRow, String> {
+        let name = field("name");
+        let microchip_id = field("microchip_id");
+        if name.is_none() && microchip_id.is_none() {
+            return Err("Patient must have either a name or a microchip ID".to_string());
+        }
+
+        if let Some(chip) = &microchip_id {
+            if let Some(duplicate) = PatientService::find_microchip_duplicate(db, chip, None).await? {
+                return Err(duplicate.to_string());
+            }
+        }
+
+        let species_name = field("species").ok_or_else(|| "Species is missing".to_string())?;
+        let species_id = Self::find_species(db, &species_name)
+            .await?
+            .ok_or_else(|| format!("Unknown species '{}'", species_name))?;
+
+        let breed = match field("breed") {
+            None => None,
+            Some(breed_name) => match Self::find_breed(db, species_id, &breed_name).await? {
+                Some(id) => Some(ResolvedBreed::Existing(id)),
+                None if create_missing_breeds => Some(ResolvedBreed::New(breed_name)),
+                None => return Err(format!("Unknown breed '{}' for species '{}'", breed_name, species_name)),
+            },
+        };
+
+        let date_of_birth = match field("date_of_birth") {
+            None => None,
+            Some(dob) => Some(
+                chrono::NaiveDate::parse_from_str(&dob, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid date_of_birth '{}'; expected YYYY-MM-DD", dob))?,
+            ),
+        };
+
+        let gender = match field("gender") {
+            None => None,
+            Some(gender) => Some(
+                ["Male", "Female", "Unknown"]
+                    .into_iter()
+                    .find(|g| g.eq_ignore_ascii_case(&gender))
+                    .ok_or_else(|| format!("Invalid gender '{}'; expected Male, Female or Unknown", gender))?
+                    .to_string(),
+            ),
+        };
+
+        let weight = match field("weight") {
+            None => None,
+            Some(weight) => match weight.parse::<f64>() {
+                Ok(w) if w.is_finite() && w > 0.0 => Some(w),
+                _ => return Err(format!("Invalid weight '{}'; expected a positive number", weight)),
+            },
+        };
+
+        Ok(ImportRow {
+            name,
+            species_id,
+            breed,
+            date_of_birth,
+            gender,
+            weight,
+            microchip_id,
+        })
+    }
+
+    /// Insert the patient, creating its breed first if needed. Returns the
+    /// patient id and the name of a breed it created.
+    async fn insert_row(db: &DatabaseConnection, row: ImportRow) -> Result<(i64, Option<String>), String> {
+        let mut created_breed = None;
+        let breed_id = match row.breed {
+            None => None,
+            Some(ResolvedBreed::Existing(id)) => Some(id),
+            // An earlier row may have created it already
+            Some(ResolvedBreed::New(name)) => match Self::find_breed(db, row.species_id, &name).await? {
+                Some(id) => Some(id),
+                None => {
+                    let breed = BreedService::create(
+                        db,
+                        CreateBreedInput { name: name.clone(), species_id: row.species_id },
+                    )
+                    .await?;
+                    created_breed = Some(name);
+                    Some(breed.id)
+                }
+            },
+        };
+
+        let patient = PatientService::create(
+            db,
+            CreatePatientDto {
+                name: row.name,
+                species_id: Some(row.species_id),
+                breed_id,
+                gender: row.gender,
+                date_of_birth: row.date_of_birth,
+                color: None,
+                weight: row.weight,
+                microchip_id: row.microchip_id,
+                medical_notes: None,
+                household_id: None,
+            },
+        )
+        .await?;
+        Ok((patient.id, created_breed))
+    }
+
+    async fn find_species(db: &DatabaseConnection, name: &str) -> Result<Option<i64>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT id, name FROM species ORDER BY active DESC, id",
+            ))
+            .await
+            .map_err(|e| format!("Failed to look up species: {}", e))?;
+        Ok(first_named(&rows, name))
+    }
+
+    async fn find_breed(db: &DatabaseConnection, species_id: i64, name: &str) -> Result<Option<i64>, String> {
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id, name FROM breeds WHERE species_id = ? ORDER BY active DESC, id",
+                [species_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to look up breed: {}", e))?;
+        Ok(first_named(&rows, name))
+    }
+}
+
+/// Id of the first `(id, name)` row named `name`, ignoring case. Compared
+/// with Unicode-aware `to_lowercase()` in Rust, since SQLite's NOCASE only
+/// folds ASCII and would miss Cyrillic names.
+fn first_named(rows: &[QueryResult], name: &str) -> Option<i64> {
+    let needle = name.to_lowercase();
+    rows.iter()
+        .find(|r| r.try_get::<String>("", "name").is_ok_and(|n| n.to_lowercase() == needle))
+        .and_then(|r| r.try_get::<i64>("", "id").ok())
+}
+
+/// Split CSV text into records of fields, each with the 1-based line it
+/// starts on. Handles quoted fields (with `""` escapes and embedded
+/// newlines), CRLF line ends and a leading byte-order mark.
+fn parse_csv(data: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!("Unterminated quoted field starting on line {}", record_line));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+    Ok(records)
+}
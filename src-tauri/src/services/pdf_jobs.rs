@@ -0,0 +1,198 @@
+//! Cancellation of long-running PDF work (multi-device reports through the
+//! Java JAR, high-resolution page renders).
+//!
+//! The UI asks for a job id with `create_pdf_job` before it starts the
+//! generation or render command and passes that id along; `cancel_pdf_job`
+//! then flips the job's token. The blocking work checks the token between
+//! steps and, for the JAR, while waiting on the child process, which is
+//! killed on cancellation. A cancelled command fails with
+//! `PDF_JOB_CANCELLED`.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Error returned by PDF work that was cancelled.
+pub const PDF_JOB_CANCELLED: &str = "Cancelled";
+
+/// How often a running child process is checked for exit or cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a created job waits for its command before it is forgotten.
+const UNSTARTED_JOB_TTL: Duration = Duration::from_secs(10 * 60);
+
+// job_id -> its entry; removed when the job's command finishes, or by
+// prune_unstarted when no command ever picks it up
+static PDF_JOBS: OnceLock<Mutex<HashMap<String, JobEntry>>> = OnceLock::new();
+
+fn get_pdf_jobs() -> &'static Mutex<HashMap<String, JobEntry>> {
+    PDF_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) struct JobEntry {
+    cancelled: Arc<AtomicBool>,
+    created_at: Instant,
+    started: bool,
+}
+
+impl JobEntry {
+    pub(crate) fn new(created_at: Instant) -> Self {
+        JobEntry { cancelled: Arc::new(AtomicBool::new(false)), created_at, started: false }
+    }
+
+    pub(crate) fn mark_started(&mut self) {
+        self.started = true;
+    }
+}
+
+/// Drop jobs that were created but never started, e.g. when the UI gave up
+/// before calling the command. Started jobs unregister themselves.
+pub(crate) fn prune_unstarted(jobs: &mut HashMap<String, JobEntry>, now: Instant) {
+    jobs.retain(|_, job| job.started || now.duration_since(job.created_at) < UNSTARTED_JOB_TTL);
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PdfJobHandle {
+    pub job_id: String,
+}
+
+/// Checked by blocking PDF work to stop early.
+#[derive(Debug, Clone, Default)]
+pub struct PdfCancelToken(Arc<AtomicBool>);
+
+impl PdfCancelToken {
+    /// A token for work started without a job id; it is never cancelled.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// `Err(PDF_JOB_CANCELLED)` once the job was cancelled.
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(PDF_JOB_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A job's token for the duration of a command; unregisters on drop.
+pub struct PdfJob {
+    job_id: Option<String>,
+    token: PdfCancelToken,
+}
+
+impl PdfJob {
+    /// Pick up the job created for `job_id`. Without an id, or with one
+    /// that was never created, the work just can't be cancelled.
+    pub fn start(job_id: Option<String>) -> Self {
+        let flag = job_id.as_ref().and_then(|id| {
+            let mut jobs = get_pdf_jobs().lock().unwrap_or_else(|e| e.into_inner());
+            jobs.get_mut(id).map(|job| {
+                job.mark_started();
+                job.cancelled.clone()
+            })
+        });
+        match flag {
+            Some(flag) => PdfJob { job_id, token: PdfCancelToken(flag) },
+            None => PdfJob { job_id: None, token: PdfCancelToken::none() },
+        }
+    }
+
+    pub fn token(&self) -> &PdfCancelToken {
+        &self.token
+    }
+}
+
+impl Drop for PdfJob {
+    fn drop(&mut self) {
+        if let Some(job_id) = &self.job_id {
+            let mut jobs = get_pdf_jobs().lock().unwrap_or_else(|e| e.into_inner());
+            jobs.remove(job_id);
+        }
+    }
+}
+
+/// Register a new job. A cancel that arrives before the work starts is
+/// kept, so the work stops at its first check. A job no command starts
+/// within `UNSTARTED_JOB_TTL` is dropped by a later `create_job`.
+pub fn create_job() -> PdfJobHandle {
+    let job_id = Uuid::new_v4().to_string();
+    let now = Instant::now();
+    let mut jobs = get_pdf_jobs().lock().unwrap_or_else(|e| e.into_inner());
+    prune_unstarted(&mut jobs, now);
+    jobs.insert(job_id.clone(), JobEntry::new(now));
+    PdfJobHandle { job_id }
+}
+
+/// Cancel `job_id`. Returns false when no such job is running.
+pub fn cancel_job(job_id: &str) -> bool {
+    let jobs = get_pdf_jobs().lock().unwrap_or_else(|e| e.into_inner());
+    match jobs.get(job_id) {
+        Some(job) => {
+            job.cancelled.store(true, Ordering::SeqCst);
+            log::info!("PDF job {} cancelled", job_id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Run `cmd` to completion like `Command::output`, killing the child as
+/// soon as `cancel` is set. stdout and stderr must be piped.
+pub fn run_cancellable(mut cmd: Command, cancel: &PdfCancelToken) -> Result<Output, String> {
+    cancel.check()?;
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start process: {}", e))?;
+
+    // Drain the pipes while waiting so a chatty child can't block on a full pipe
+    let stdout = child.stdout.take().map(drain_pipe);
+    let stderr = child.stderr.take().map(drain_pipe);
+
+    let status = loop {
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(PDF_JOB_CANCELLED.to_string());
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => thread::sleep(CANCEL_POLL_INTERVAL),
+            Err(e) => {
+                // Don't leave the child running unsupervised
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Failed to wait for process: {}", e));
+            }
+        }
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.and_then(|h| h.join().ok()).unwrap_or_default(),
+        stderr: stderr.and_then(|h| h.join().ok()).unwrap_or_default(),
+    })
+}
+
+fn drain_pipe<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
@@ -4,6 +4,7 @@ use pdfium_render::prelude::*;
 use tauri::AppHandle;
 use std::io::Cursor;
 use image::{ImageFormat, GenericImageView};
+use crate::services::pdf_jobs::PdfCancelToken;
 
 pub struct PdfRenderService;
 
@@ -78,13 +79,16 @@ impl PdfRenderService {
         Ok(out_path.to_string())
     }
 
-    /// Render a page to PNG bytes (avoids filesystem issues)
+    /// Render a page to PNG bytes (avoids filesystem issues). `cancel` is
+    /// checked before each of the slow steps: load, render and encode.
     pub fn render_page_to_png_bytes(
         app_handle: &AppHandle,
         pdf_path: &str,
         page_index: u32,
         target_width: u32,
+        cancel: &PdfCancelToken,
     ) -> Result<Vec<u8>, String> {
+        cancel.check()?;
         let pdfium = Self::load_pdfium(app_handle)?;
         let document = pdfium
             .load_pdf_from_file(pdf_path, None)
@@ -98,7 +102,9 @@ impl PdfRenderService {
             .set_target_width(target_width as i32)
             .render_form_data(true)
             .use_lcd_text_rendering(true);
+        cancel.check()?;
         let bitmap = page.render_with_config(&cfg).map_err(|e| format!("Failed to render page: {}", e))?;
+        cancel.check()?;
         let image = bitmap.as_image();
         let mut buf = Cursor::new(Vec::new());
         image.write_to(&mut buf, ImageFormat::Png)
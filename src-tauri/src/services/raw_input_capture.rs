@@ -47,6 +47,7 @@ pub fn start_raw_input_capture(_app: tauri::AppHandle, _pool: crate::database::S
 #[cfg(target_os = "windows")]
 mod windows_impl {
     use crate::database::SeaOrmPool;
+    use crate::services::events;
     use crate::services::managed_hid_scanner::ManagedHidScannerService;
     use std::collections::HashMap;
     use std::sync::{Arc, Mutex, OnceLock};
@@ -890,7 +891,7 @@ mod windows_impl {
         if let Some(app) = APP_HANDLE.get() {
             let normalized = crate::services::device_capture::normalize_microchip_public(code);
             let _ = app.emit_all(
-                "scanner:barcode",
+                events::SCANNER_BARCODE,
                 serde_json::json!({
                     "code": normalized,
                     "device": "managed-hid",
@@ -907,7 +908,7 @@ mod windows_impl {
             if is_hidden {
                 let _ = crate::services::device_capture::throttled_show_and_focus(app);
                 let _ = app.emit_all(
-                    "wake-from-tray",
+                    events::WAKE_FROM_TRAY,
                     serde_json::json!({
                         "cause": "scan",
                         "code": normalized,
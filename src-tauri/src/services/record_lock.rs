@@ -0,0 +1,67 @@
+//! Per-record async locks for attachment mutations.
+//!
+//! Uploads, deletes and PDF (re)generation all change a record's attachment
+//! set. Holding the record's lock for the whole operation means a regenerate
+//! reads a consistent set and its `generated_pdf` insert can't interleave
+//! with a user deleting attachments on the same record.
+//!
+//! Lock ordering: take the record lock first, before opening a transaction
+//! or touching the legacy `DatabasePool` mutex, and never take two record
+//! locks at once. The locks are not reentrant, so code that already holds
+//! one must call the unlocked service functions directly.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+// record_id -> lock; entries are pruned once nobody holds or waits on them
+static RECORD_LOCKS: OnceLock<Mutex<HashMap<i64, Arc<AsyncMutex<()>>>>> = OnceLock::new();
+
+fn get_record_locks() -> &'static Mutex<HashMap<i64, Arc<AsyncMutex<()>>>> {
+    RECORD_LOCKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Held for the duration of an attachment mutation; releases on drop.
+pub struct RecordLockGuard {
+    record_id: i64,
+    lock: Arc<AsyncMutex<()>>,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for RecordLockGuard {
+    fn drop(&mut self) {
+        let mut locks = get_record_locks().lock().unwrap_or_else(|e| e.into_inner());
+        // One reference in the map, one here and one inside `_guard`:
+        // anything more is a waiter that still needs the entry.
+        if Arc::strong_count(&self.lock) <= 3 {
+            locks.remove(&self.record_id);
+        }
+    }
+}
+
+/// Wait for exclusive access to `record_id`'s attachments.
+pub async fn lock_record(record_id: i64) -> RecordLockGuard {
+    let lock = {
+        let mut locks = get_record_locks().lock().unwrap_or_else(|e| e.into_inner());
+        locks
+            .entry(record_id)
+            .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+            .clone()
+    };
+    let guard = lock.clone().lock_owned().await;
+    RecordLockGuard {
+        record_id,
+        lock,
+        _guard: guard,
+    }
+}
+
+/// Whether `record_id` still has an entry in the lock map.
+#[cfg(test)]
+pub(crate) fn is_tracked(record_id: i64) -> bool {
+    get_record_locks()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(&record_id)
+}
@@ -1,11 +1,11 @@
 use crate::entities::room::{self, Entity as RoomEntity};
 use crate::entities::appointment::{self, Entity as AppointmentEntity};
 use crate::models::{
-    Room, CreateRoomInput, UpdateRoomInput, RoomFilter,
+    Room, CreateRoomInput, UpdateRoomInput, RoomFilter, RoomList, RoomSort,
     RoomAvailability, RoomAppointmentSlot
 };
 use crate::models::dto::MaybeNull;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sea_orm::*;
 
 pub struct RoomService;
@@ -20,6 +20,8 @@ impl RoomService {
             capacity: model.capacity,
             color: model.color,
             is_active: model.is_active,
+            display_order: model.display_order,
+            buffer_minutes: model.buffer_minutes,
             created_at: model.created_at,
             updated_at: model.updated_at,
         }
@@ -31,10 +33,14 @@ impl RoomService {
     ) -> Result<Vec<Room>, String> {
         let mut query = RoomEntity::find();
 
-        if filter.active_only {
+        if !filter.lists_inactive() {
             query = query.filter(room::Column::IsActive.eq(true));
         }
 
+        if filter.sort == RoomSort::DisplayOrder {
+            query = query.order_by_asc(room::Column::DisplayOrder);
+        }
+
         let rooms = query
             .order_by_asc(room::Column::Name)
             .all(db)
@@ -44,6 +50,93 @@ impl RoomService {
         Ok(rooms.into_iter().map(Self::to_api_model).collect())
     }
 
+    /// `get_rooms` plus how many rooms are active and inactive overall,
+    /// for the room settings list.
+    pub async fn get_room_list(
+        db: &DatabaseConnection,
+        filter: RoomFilter,
+    ) -> Result<RoomList, String> {
+        let rooms = Self::get_rooms(db, filter).await?;
+
+        let active_count = RoomEntity::find()
+            .filter(room::Column::IsActive.eq(true))
+            .count(db)
+            .await
+            .map_err(|e| format!("Failed to count rooms: {}", e))?;
+        let inactive_count = RoomEntity::find()
+            .filter(room::Column::IsActive.eq(false))
+            .count(db)
+            .await
+            .map_err(|e| format!("Failed to count rooms: {}", e))?;
+
+        Ok(RoomList { rooms, active_count, inactive_count })
+    }
+
+    /// Put `room_ids` first, in that order. Rooms not listed keep their
+    /// relative order after them.
+    pub async fn reorder_rooms(
+        db: &DatabaseConnection,
+        room_ids: Vec<i64>,
+    ) -> Result<Vec<Room>, String> {
+        let mut seen = std::collections::HashSet::new();
+        if let Some(id) = room_ids.iter().find(|id| !seen.insert(**id)) {
+            return Err(format!("Room {} is listed twice", id));
+        }
+
+        let current = RoomEntity::find()
+            .order_by_asc(room::Column::DisplayOrder)
+            .order_by_asc(room::Column::Name)
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to fetch rooms: {}", e))?;
+
+        if let Some(id) = room_ids.iter().find(|id| !current.iter().any(|r| r.id == **id)) {
+            return Err(format!("Room {} not found", id));
+        }
+
+        let ordered_ids: Vec<i64> = room_ids
+            .iter()
+            .copied()
+            .chain(current.iter().map(|r| r.id).filter(|id| !seen.contains(id)))
+            .collect();
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let now = Utc::now();
+        for (position, id) in ordered_ids.into_iter().enumerate() {
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "UPDATE rooms SET display_order = ?, updated_at = ? WHERE id = ?",
+                [(position as i32).into(), now.into(), id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to reorder rooms: {}", e))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Self::get_rooms(db, RoomFilter {
+            include_inactive: true,
+            sort: RoomSort::DisplayOrder,
+            ..Default::default()
+        }).await
+    }
+
+    /// Display order for a new room: after every existing room.
+    async fn next_display_order(db: &DatabaseConnection) -> Result<i32, String> {
+        let last = RoomEntity::find()
+            .order_by_desc(room::Column::DisplayOrder)
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to fetch rooms: {}", e))?;
+        Ok(last.map_or(0, |r| r.display_order + 1))
+    }
+
     pub async fn get_room_by_id(
         db: &DatabaseConnection,
         id: i64,
@@ -76,6 +169,7 @@ impl RoomService {
         }
 
         let now = Utc::now();
+        let display_order = Self::next_display_order(db).await?;
 
         // Insert room
         let new_room = room::ActiveModel {
@@ -84,6 +178,8 @@ impl RoomService {
             capacity: Set(input.capacity.unwrap_or(1)),
             color: Set(input.color.unwrap_or_else(|| "#1890ff".to_string())),
             is_active: Set(true),
+            display_order: Set(display_order),
+            buffer_minutes: Set(input.buffer_minutes.unwrap_or(0)),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -150,6 +246,9 @@ impl RoomService {
         if let Some(is_active) = input.is_active {
             room_model.is_active = Set(is_active);
         }
+        if let Some(buffer_minutes) = input.buffer_minutes {
+            room_model.buffer_minutes = Set(buffer_minutes);
+        }
         room_model.updated_at = Set(now);
 
         room_model
@@ -160,6 +259,63 @@ impl RoomService {
         Self::get_room_by_id(db, id).await
     }
 
+    /// Create a room named `new_name` with the configuration of `room_id`
+    /// (description, capacity, color, buffer, active flag). Appointments
+    /// stay with the original room.
+    pub async fn duplicate_room(
+        db: &DatabaseConnection,
+        room_id: i64,
+        new_name: String,
+    ) -> Result<Room, String> {
+        let source = RoomEntity::find_by_id(room_id)
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to fetch room: {}", e))?
+            .ok_or_else(|| "Room not found".to_string())?;
+
+        let name = new_name.trim().to_string();
+        CreateRoomInput {
+            name: name.clone(),
+            description: None,
+            capacity: Some(source.capacity),
+            color: None,
+            buffer_minutes: None,
+        }
+        .validate()?;
+
+        let existing = RoomEntity::find()
+            .filter(room::Column::Name.eq(&name))
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to check room name: {}", e))?;
+
+        if existing.is_some() {
+            return Err("Room with this name already exists".to_string());
+        }
+
+        let now = Utc::now();
+        let display_order = Self::next_display_order(db).await?;
+        let new_room = room::ActiveModel {
+            name: Set(name),
+            description: Set(source.description),
+            capacity: Set(source.capacity),
+            color: Set(source.color),
+            is_active: Set(source.is_active),
+            display_order: Set(display_order),
+            buffer_minutes: Set(source.buffer_minutes),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        let result = RoomEntity::insert(new_room)
+            .exec(db)
+            .await
+            .map_err(|e| format!("Failed to duplicate room: {}", e))?;
+
+        Self::get_room_by_id(db, result.last_insert_id).await
+    }
+
     pub async fn get_room_availability(
         db: &DatabaseConnection,
         room_id: i64,
@@ -205,13 +361,15 @@ impl RoomService {
             })
             .collect();
 
-        // Check if room is available at the specific time
+        // Check if room is available at the specific time; an appointment
+        // keeps the room busy until its buffer after it has passed
+        let buffer = Duration::minutes(room.buffer_minutes.into());
         let occupied_count = AppointmentEntity::find()
             .filter(appointment::Column::RoomId.eq(room_id))
             .filter(appointment::Column::DeletedAt.is_null())
             .filter(appointment::Column::Status.is_in(["scheduled", "in_progress"]))
             .filter(appointment::Column::StartTime.lte(check_time))
-            .filter(appointment::Column::EndTime.gt(check_time))
+            .filter(appointment::Column::EndTime.gt(check_time - buffer))
             .count(db)
             .await
             .map_err(|e| format!("Failed to check availability: {}", e))?;
@@ -224,13 +382,13 @@ impl RoomService {
                 .filter(appointment::Column::RoomId.eq(room_id))
                 .filter(appointment::Column::DeletedAt.is_null())
                 .filter(appointment::Column::Status.is_in(["scheduled", "in_progress"]))
-                .filter(appointment::Column::EndTime.gt(check_time))
+                .filter(appointment::Column::EndTime.gt(check_time - buffer))
                 .order_by_asc(appointment::Column::EndTime)
                 .one(db)
                 .await
                 .ok()
                 .flatten()
-                .map(|a| a.end_time)
+                .map(|a| a.end_time + buffer)
         } else {
             None
         };
@@ -287,6 +445,7 @@ mod tests {
             description: Some("Main exam room".to_string()),
             capacity: Some(2),
             color: Some("#3498db".to_string()),
+            buffer_minutes: None,
         };
 
         let result = RoomService::create_room(&db, input).await;
@@ -309,6 +468,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         };
 
         let result = RoomService::create_room(&db, input).await;
@@ -328,6 +488,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         };
 
         // Create first room
@@ -348,6 +509,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         };
 
         let result = RoomService::create_room(&db, input).await;
@@ -378,6 +540,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         RoomService::create_room(&db, CreateRoomInput {
@@ -385,6 +548,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         let filter = RoomFilter::default();
@@ -406,6 +570,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Deactivate it
@@ -415,6 +580,7 @@ mod tests {
             capacity: None,
             color: MaybeNull::Undefined,
             is_active: Some(false),
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Create another active room
@@ -423,18 +589,88 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Get only active rooms (default)
-        let active_rooms = RoomService::get_rooms(&db, RoomFilter { active_only: true }).await.unwrap();
+        let active_rooms = RoomService::get_rooms(&db, RoomFilter::default()).await.unwrap();
         assert_eq!(active_rooms.len(), 1);
         assert_eq!(active_rooms[0].name, "Still Active");
 
         // Get all rooms
-        let all_rooms = RoomService::get_rooms(&db, RoomFilter { active_only: false }).await.unwrap();
+        let all_rooms = RoomService::get_rooms(&db, RoomFilter { active_only: false, ..Default::default() }).await.unwrap();
         assert_eq!(all_rooms.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_get_rooms_include_inactive() {
+        let db = create_test_db().await;
+
+        let retired = RoomService::create_room(&db, CreateRoomInput {
+            name: "Old X-Ray".to_string(),
+            description: None,
+            capacity: None,
+            color: None,
+            buffer_minutes: None,
+        }).await.unwrap();
+        RoomService::update_room(&db, retired.id, UpdateRoomInput {
+            name: None,
+            description: None,
+            capacity: None,
+            color: MaybeNull::Undefined,
+            is_active: Some(false),
+            buffer_minutes: None,
+        }).await.unwrap();
+        RoomService::create_room(&db, CreateRoomInput {
+            name: "Exam Room".to_string(),
+            description: None,
+            capacity: None,
+            color: None,
+            buffer_minutes: None,
+        }).await.unwrap();
+
+        let filter: RoomFilter = serde_json::from_str("{}").unwrap();
+        let rooms = RoomService::get_rooms(&db, filter).await.unwrap();
+        assert_eq!(rooms.len(), 1);
+        assert_eq!(rooms[0].name, "Exam Room");
+
+        let list = RoomService::get_room_list(&db, RoomFilter {
+            include_inactive: true,
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(list.rooms.len(), 2);
+        assert_eq!(list.active_count, 1);
+        assert_eq!(list.inactive_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_rooms() {
+        let db = create_test_db().await;
+
+        let mut ids = Vec::new();
+        for name in ["Room A", "Room B", "Room C"] {
+            let room = RoomService::create_room(&db, CreateRoomInput {
+                name: name.to_string(),
+                description: None,
+                capacity: None,
+                color: None,
+                buffer_minutes: None,
+            }).await.unwrap();
+            ids.push(room.id);
+        }
+
+        // Only C is moved; A and B follow in their previous order
+        let rooms = RoomService::reorder_rooms(&db, vec![ids[2]]).await.unwrap();
+        let names: Vec<&str> = rooms.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Room C", "Room A", "Room B"]);
+
+        let by_name = RoomService::get_rooms(&db, RoomFilter::default()).await.unwrap();
+        assert_eq!(by_name[0].name, "Room A");
+
+        assert!(RoomService::reorder_rooms(&db, vec![ids[0], ids[0]]).await.is_err());
+        assert!(RoomService::reorder_rooms(&db, vec![99999]).await.unwrap_err().contains("not found"));
+    }
+
     #[tokio::test]
     async fn test_get_room_by_id() {
         let db = create_test_db().await;
@@ -444,6 +680,7 @@ mod tests {
             description: Some("Test description".to_string()),
             capacity: Some(3),
             color: Some("#e74c3c".to_string()),
+            buffer_minutes: None,
         }).await.unwrap();
 
         let fetched = RoomService::get_room_by_id(&db, created.id).await.unwrap();
@@ -474,6 +711,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         let updated = RoomService::update_room(&db, created.id, UpdateRoomInput {
@@ -482,6 +720,7 @@ mod tests {
             capacity: None,
             color: MaybeNull::Undefined,
             is_active: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         assert_eq!(updated.name, "New Name");
@@ -496,6 +735,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         let room_b = RoomService::create_room(&db, CreateRoomInput {
@@ -503,6 +743,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Try to rename Room B to Room A
@@ -512,6 +753,7 @@ mod tests {
             capacity: None,
             color: MaybeNull::Undefined,
             is_active: None,
+            buffer_minutes: None,
         }).await;
 
         assert!(result.is_err());
@@ -527,6 +769,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         assert!(created.is_active);
@@ -537,6 +780,7 @@ mod tests {
             capacity: None,
             color: MaybeNull::Undefined,
             is_active: Some(false),
+            buffer_minutes: None,
         }).await.unwrap();
 
         assert!(!updated.is_active);
@@ -551,6 +795,7 @@ mod tests {
             description: None,
             capacity: None,
             color: Some("#e74c3c".to_string()),
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Reset color to default
@@ -560,6 +805,7 @@ mod tests {
             capacity: None,
             color: MaybeNull::Null, // Reset
             is_active: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         assert_eq!(updated.color, "#1890ff"); // Default color
@@ -575,6 +821,7 @@ mod tests {
             capacity: None,
             color: MaybeNull::Undefined,
             is_active: None,
+            buffer_minutes: None,
         }).await;
 
         assert!(result.is_err());
@@ -592,6 +839,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         let result = RoomService::delete_room(&db, created.id).await;
@@ -612,6 +860,7 @@ mod tests {
             description: None,
             capacity: None,
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Create patient and appointment for this room
@@ -639,6 +888,47 @@ mod tests {
         assert!(result.unwrap_err().contains("existing appointments"));
     }
 
+    // ==================== DUPLICATE TESTS ====================
+
+    #[tokio::test]
+    async fn test_duplicate_room_copies_configuration() {
+        let db = create_test_db().await;
+
+        let original = RoomService::create_room(&db, CreateRoomInput {
+            name: "Surgery 1".to_string(),
+            description: Some("Sterile surgery suite".to_string()),
+            capacity: Some(3),
+            color: Some("#9b59b6".to_string()),
+            buffer_minutes: None,
+        }).await.unwrap();
+
+        let copy = RoomService::duplicate_room(&db, original.id, "Surgery 2".to_string()).await.unwrap();
+
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.name, "Surgery 2");
+        assert_eq!(copy.description, original.description);
+        assert_eq!(copy.capacity, original.capacity);
+        assert_eq!(copy.color, original.color);
+        assert_eq!(copy.is_active, original.is_active);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_room_name_taken_fails() {
+        let db = create_test_db().await;
+
+        let original = RoomService::create_room(&db, CreateRoomInput {
+            name: "Exam Room".to_string(),
+            description: None,
+            capacity: None,
+            color: None,
+            buffer_minutes: None,
+        }).await.unwrap();
+
+        let result = RoomService::duplicate_room(&db, original.id, "Exam Room".to_string()).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("already exists"));
+    }
+
     // ==================== AVAILABILITY TESTS ====================
 
     #[tokio::test]
@@ -650,6 +940,7 @@ mod tests {
             description: None,
             capacity: Some(2),
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         let availability = RoomService::get_room_availability(&db, room.id, test_time_slot(10, 0)).await.unwrap();
@@ -668,6 +959,7 @@ mod tests {
             description: None,
             capacity: Some(1), // Only 1 capacity
             color: None,
+            buffer_minutes: None,
         }).await.unwrap();
 
         // Create patient and appointment
@@ -698,4 +990,60 @@ mod tests {
         let availability_later = RoomService::get_room_availability(&db, room.id, test_time_slot(11, 0)).await.unwrap();
         assert!(availability_later.is_available);
     }
+
+    #[tokio::test]
+    async fn test_room_availability_keeps_buffer_free_after_appointment() {
+        let db = create_test_db().await;
+
+        let room = RoomService::create_room(&db, CreateRoomInput {
+            name: "Surgery".to_string(),
+            description: None,
+            capacity: Some(1),
+            color: None,
+            buffer_minutes: Some(15),
+        }).await.unwrap();
+        assert_eq!(room.buffer_minutes, 15);
+
+        let species_id = create_test_species(&db, "Dog").await;
+        let patient_id = create_test_patient(&db, "Max", species_id, None).await;
+
+        // 10:00-10:30, and a cancelled one right after that doesn't count
+        for (start, end, status) in [(test_time_slot(10, 0), test_time_slot(10, 2), "scheduled"),
+                                     (test_time_slot(10, 2), test_time_slot(11, 0), "cancelled")] {
+            db.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO appointments (patient_id, title, start_time, end_time, room_id, status, created_by) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                [
+                    patient_id.into(),
+                    "Surgery".into(),
+                    start.to_rfc3339().into(),
+                    end.to_rfc3339().into(),
+                    room.id.into(),
+                    status.into(),
+                    "test".into(),
+                ],
+            )).await.unwrap();
+        }
+
+        // 10 minutes after it ends, the room is still being turned over
+        let ten_past = test_time_slot(10, 2) + Duration::minutes(10);
+        let availability = RoomService::get_room_availability(&db, room.id, ten_past).await.unwrap();
+        assert!(!availability.is_available);
+        assert_eq!(availability.next_available, Some(test_time_slot(10, 3)));
+
+        let availability = RoomService::get_room_availability(&db, room.id, test_time_slot(10, 3)).await.unwrap();
+        assert!(availability.is_available);
+
+        // A buffer of 0 frees the room as soon as the appointment ends
+        RoomService::update_room(&db, room.id, UpdateRoomInput {
+            name: None,
+            description: None,
+            capacity: None,
+            color: MaybeNull::Undefined,
+            is_active: None,
+            buffer_minutes: Some(0),
+        }).await.unwrap();
+        let availability = RoomService::get_room_availability(&db, room.id, ten_past).await.unwrap();
+        assert!(availability.is_available);
+    }
 }
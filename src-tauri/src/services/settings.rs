@@ -1,7 +1,10 @@
 use crate::entities::app_settings::{self, Entity as AppSettingsEntity};
 use crate::entities::currency::{self, Entity as CurrencyEntity};
+use crate::entities::room::Entity as RoomEntity;
+use crate::database::queries::household_search::{validate_search_tokenizer, DEFAULT_SEARCH_TOKENIZER};
 use crate::models::{Currency, SettingsResponse, UpdateSettingsRequest};
 use chrono::Utc;
+use chrono_tz::Tz;
 use sea_orm::*;
 
 pub struct SettingsService;
@@ -26,6 +29,11 @@ impl SettingsService {
             currency_id: model.currency_id,
             theme: model.theme,
             date_format: model.date_format,
+            default_room_id: model.default_room_id,
+            search_tokenizer: model.search_tokenizer,
+            practice_timezone: model.practice_timezone,
+            ocr_enabled: model.ocr_enabled,
+            auto_archive_after_days: model.auto_archive_after_days,
             created_at: model.created_at,
             updated_at: model.updated_at,
         }
@@ -51,6 +59,11 @@ impl SettingsService {
                     currency_id: Set(None),
                     theme: Set("light".to_string()),
                     date_format: Set("MM/DD/YYYY".to_string()),
+                    default_room_id: Set(None),
+                    search_tokenizer: Set(DEFAULT_SEARCH_TOKENIZER.to_string()),
+                    practice_timezone: Set(None),
+                    ocr_enabled: Set(false),
+                    auto_archive_after_days: Set(None),
                     created_at: Set(now),
                     updated_at: Set(now),
                     ..Default::default()
@@ -134,6 +147,11 @@ impl SettingsService {
                     currency_id: Set(None),
                     theme: Set("light".to_string()),
                     date_format: Set("MM/DD/YYYY".to_string()),
+                    default_room_id: Set(None),
+                    search_tokenizer: Set(DEFAULT_SEARCH_TOKENIZER.to_string()),
+                    practice_timezone: Set(None),
+                    ocr_enabled: Set(false),
+                    auto_archive_after_days: Set(None),
                     created_at: Set(now),
                     updated_at: Set(now),
                     ..Default::default()
@@ -184,6 +202,173 @@ impl SettingsService {
         Self::get_settings(db, user_id).await
     }
 
+    /// Change one field of the user's settings row, which is created first
+    /// if missing, and return the settings as saved.
+    async fn update_setting(
+        db: &DatabaseConnection,
+        user_id: &str,
+        apply: impl FnOnce(&mut app_settings::ActiveModel),
+    ) -> Result<SettingsResponse, String> {
+        // Make sure the row exists before updating it
+        let current = Self::get_settings(db, user_id).await?;
+
+        let mut settings_model: app_settings::ActiveModel = AppSettingsEntity::find_by_id(current.settings.id)
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to get current settings: {}", e))?
+            .ok_or_else(|| "Settings not found".to_string())?
+            .into();
+        apply(&mut settings_model);
+        settings_model.updated_at = Set(Utc::now());
+
+        settings_model
+            .update(db)
+            .await
+            .map_err(|e| format!("Failed to update settings: {}", e))?;
+
+        Self::get_settings(db, user_id).await
+    }
+
+    /// Set or clear the room new appointments default to.
+    pub async fn set_default_room(
+        db: &DatabaseConnection,
+        user_id: &str,
+        room_id: Option<i64>,
+    ) -> Result<SettingsResponse, String> {
+        if let Some(room_id) = room_id {
+            let room = RoomEntity::find_by_id(room_id)
+                .one(db)
+                .await
+                .map_err(|e| format!("Failed to validate room: {}", e))?
+                .ok_or_else(|| format!("Invalid room_id: {}", room_id))?;
+
+            if !room.is_active {
+                return Err(format!("Room {} is inactive", room.name));
+            }
+        }
+
+        Self::update_setting(db, user_id, |settings| settings.default_room_id = Set(room_id)).await
+    }
+
+    /// The configured default room, if any.
+    pub async fn get_default_room_id(
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<i64>, String> {
+        let settings = AppSettingsEntity::find()
+            .filter(app_settings::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to get settings: {}", e))?;
+
+        Ok(settings.and_then(|s| s.default_room_id))
+    }
+
+    /// Record the tokenizer the household search index was rebuilt with.
+    pub async fn set_search_tokenizer(
+        db: &DatabaseConnection,
+        user_id: &str,
+        tokenizer: &str,
+    ) -> Result<SettingsResponse, String> {
+        validate_search_tokenizer(tokenizer)?;
+
+        Self::update_setting(db, user_id, |settings| settings.search_tokenizer = Set(tokenizer.to_string())).await
+    }
+
+    /// Set or clear the practice timezone. `timezone` must be an IANA name
+    /// such as "Europe/Skopje".
+    pub async fn set_practice_timezone(
+        db: &DatabaseConnection,
+        user_id: &str,
+        timezone: Option<&str>,
+    ) -> Result<SettingsResponse, String> {
+        let timezone = timezone
+            .map(str::trim)
+            .filter(|tz| !tz.is_empty())
+            .map(|tz| Self::parse_timezone(tz).map(|tz| tz.name().to_string()))
+            .transpose()?;
+
+        Self::update_setting(db, user_id, |settings| settings.practice_timezone = Set(timezone)).await
+    }
+
+    /// The configured practice timezone; `None` means use the machine's.
+    pub async fn get_practice_timezone(
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<Tz>, String> {
+        let settings = AppSettingsEntity::find()
+            .filter(app_settings::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to get settings: {}", e))?;
+
+        settings
+            .and_then(|s| s.practice_timezone)
+            .map(|name| Self::parse_timezone(&name))
+            .transpose()
+    }
+
+    /// IANA name to send to Google Calendar. Falls back to "UTC" when no
+    /// practice timezone is set; event times carry their offset either way.
+    pub async fn practice_timezone_name(db: &DatabaseConnection, user_id: &str) -> String {
+        match Self::get_practice_timezone(db, user_id).await {
+            Ok(Some(tz)) => tz.name().to_string(),
+            Ok(None) => "UTC".to_string(),
+            Err(e) => {
+                log::warn!("Ignoring practice timezone setting: {}", e);
+                "UTC".to_string()
+            }
+        }
+    }
+
+    pub async fn set_ocr_enabled(
+        db: &DatabaseConnection,
+        user_id: &str,
+        enabled: bool,
+    ) -> Result<SettingsResponse, String> {
+        Self::update_setting(db, user_id, |settings| settings.ocr_enabled = Set(enabled)).await
+    }
+
+    /// Whether uploads are sent through OCR; false when no settings row exists yet.
+    pub async fn is_ocr_enabled(db: &DatabaseConnection, user_id: &str) -> Result<bool, String> {
+        let settings = AppSettingsEntity::find()
+            .filter(app_settings::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to get settings: {}", e))?;
+        Ok(settings.is_some_and(|s| s.ocr_enabled))
+    }
+
+    /// Set how old a record must be before it is archived automatically;
+    /// `None` turns auto-archiving off.
+    pub async fn set_auto_archive_after_days(
+        db: &DatabaseConnection,
+        user_id: &str,
+        days: Option<i64>,
+    ) -> Result<SettingsResponse, String> {
+        if days.is_some_and(|d| d <= 0) {
+            return Err("Auto-archive threshold must be at least one day".to_string());
+        }
+
+        Self::update_setting(db, user_id, |settings| settings.auto_archive_after_days = Set(days)).await
+    }
+
+    /// The auto-archive threshold in days; `None` when it is off or no
+    /// settings row exists yet.
+    pub async fn get_auto_archive_after_days(db: &DatabaseConnection, user_id: &str) -> Result<Option<i64>, String> {
+        let settings = AppSettingsEntity::find()
+            .filter(app_settings::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to get settings: {}", e))?;
+        Ok(settings.and_then(|s| s.auto_archive_after_days))
+    }
+
+    pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+        name.parse::<Tz>()
+            .map_err(|_| format!("Unknown timezone: {}", name))
+    }
+
     #[allow(dead_code)]
     pub async fn get_currencies(db: &DatabaseConnection) -> Result<Vec<Currency>, String> {
         let currencies = CurrencyEntity::find()
@@ -1,6 +1,6 @@
 use crate::entities::species::{self, Entity as SpeciesEntity};
 use crate::entities::patient::{self, Entity as PatientEntity};
-use crate::models::{Species, CreateSpeciesInput, UpdateSpeciesInput};
+use crate::models::{Species, CreateSpeciesInput, UpdateSpeciesInput, SpeciesColor};
 use chrono::Utc;
 use sea_orm::*;
 
@@ -14,6 +14,7 @@ impl SpeciesService {
             name: model.name,
             active: model.active,
             display_order: model.display_order,
+            color: model.color,
             created_at: model.created_at.and_utc(),
             updated_at: model.updated_at.and_utc(),
         }
@@ -37,6 +38,33 @@ impl SpeciesService {
         Ok(species_list.into_iter().map(Self::to_api_model).collect())
     }
 
+    /// Colors of the active species, in display order
+    pub async fn get_colors(db: &DatabaseConnection) -> Result<Vec<SpeciesColor>, String> {
+        let species_list = Self::get_all(db, true).await?;
+
+        Ok(species_list
+            .into_iter()
+            .map(|s| SpeciesColor {
+                species_id: s.id,
+                name: s.name,
+                color: s.color,
+            })
+            .collect())
+    }
+
+    /// Accept `#RRGGBB` or `#RGB`, surrounding whitespace trimmed
+    fn validate_color(color: &str) -> Result<String, String> {
+        let color = color.trim();
+        let valid = color
+            .strip_prefix('#')
+            .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        if valid {
+            Ok(color.to_string())
+        } else {
+            Err(format!("Invalid color '{}': expected a hex color like #1890ff", color))
+        }
+    }
+
     /// Get a single species by ID
     pub async fn get_by_id(db: &DatabaseConnection, id: i64) -> Result<Species, String> {
         let species = SpeciesEntity::find_by_id(id)
@@ -51,6 +79,7 @@ impl SpeciesService {
     /// Create a new species
     pub async fn create(db: &DatabaseConnection, input: CreateSpeciesInput) -> Result<Species, String> {
         let now = Utc::now().naive_utc();
+        let color = input.color.as_deref().map(Self::validate_color).transpose()?;
 
         // Get the next display_order if not provided
         let display_order = if let Some(order) = input.display_order {
@@ -70,6 +99,7 @@ impl SpeciesService {
             name: Set(input.name),
             active: Set(true),
             display_order: Set(display_order),
+            color: color.map_or(NotSet, Set),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -86,6 +116,7 @@ impl SpeciesService {
     /// Update a species
     pub async fn update(db: &DatabaseConnection, id: i64, input: UpdateSpeciesInput) -> Result<Species, String> {
         let now = Utc::now().naive_utc();
+        let color = input.color.as_deref().map(Self::validate_color).transpose()?;
 
         // Get existing species
         let existing = SpeciesEntity::find_by_id(id)
@@ -95,7 +126,7 @@ impl SpeciesService {
             .ok_or_else(|| "Species not found".to_string())?;
 
         // If nothing to update, return existing
-        if input.name.is_none() && input.active.is_none() && input.display_order.is_none() {
+        if input.name.is_none() && input.active.is_none() && input.display_order.is_none() && color.is_none() {
             return Ok(Self::to_api_model(existing));
         }
 
@@ -110,6 +141,9 @@ impl SpeciesService {
         if let Some(display_order) = input.display_order {
             species_model.display_order = Set(display_order);
         }
+        if let Some(color) = color {
+            species_model.color = Set(color);
+        }
         species_model.updated_at = Set(now);
 
         species_model
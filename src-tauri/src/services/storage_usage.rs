@@ -0,0 +1,213 @@
+//! How much attachment storage is used, and by what: generated PDFs,
+//! uploaded scans, device files. Sizes come from `medical_attachments.file_size`
+//! as recorded at upload, counting a stored file that several attachments
+//! share once; files in storage that no row refers to (neither
+//! an attachment nor a tracked device file) are counted from disk as orphaned.
+//!
+//! External attachments live outside app storage and are left out.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::services::file_storage::STORED_FILE;
+
+/// Most attachments `get_breakdown` will list as largest.
+pub const MAX_LARGEST_ATTACHMENTS: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsageGroup {
+    pub attachment_type: String,
+    pub mime_type: String,
+    #[ts(type = "number")]
+    pub count: i64,
+    #[ts(type = "number")]
+    pub bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct LargestAttachment {
+    #[ts(type = "number")]
+    pub attachment_id: i64,
+    pub original_name: String,
+    pub attachment_type: String,
+    pub mime_type: String,
+    #[ts(type = "number")]
+    pub file_size: i64,
+    #[ts(type = "number")]
+    pub medical_record_id: i64,
+    pub record_name: Option<String>,
+    #[ts(type = "number | null")]
+    pub patient_id: Option<i64>,
+    pub patient_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct StorageBreakdown {
+    #[ts(type = "number")]
+    pub total_bytes: i64,
+    #[ts(type = "number")]
+    pub total_count: i64,
+    /// Largest first
+    pub groups: Vec<StorageUsageGroup>,
+    pub largest: Vec<LargestAttachment>,
+    #[ts(type = "number")]
+    pub orphaned_bytes: i64,
+    #[ts(type = "number")]
+    pub orphaned_files: i64,
+}
+
+pub struct StorageUsageService;
+
+impl StorageUsageService {
+    /// Usage grouped by attachment and MIME type, the `top_n` largest
+    /// attachments, and orphaned files in `storage_dir`.
+    pub async fn get_breakdown(
+        db: &DatabaseConnection,
+        storage_dir: &Path,
+        top_n: i64,
+    ) -> Result<StorageBreakdown, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                // Attachments sharing a stored file take its bytes once, on
+                // the first of them
+                format!(
+                    "WITH stored AS ( \
+                         SELECT MIN(id) AS id FROM medical_attachments \
+                         WHERE is_external = 0 GROUP BY {} \
+                     ) \
+                     SELECT COALESCE(a.attachment_type, 'file') AS attachment_type, \
+                            COALESCE(a.mime_type, 'application/octet-stream') AS mime_type, \
+                            COUNT(*) AS count, \
+                            COALESCE(SUM(CASE WHEN s.id IS NOT NULL THEN a.file_size END), 0) AS bytes \
+                     FROM medical_attachments a LEFT JOIN stored s ON s.id = a.id \
+                     WHERE a.is_external = 0 \
+                     GROUP BY 1, 2 ORDER BY bytes DESC, attachment_type, mime_type",
+                    STORED_FILE
+                ),
+            ))
+            .await
+            .map_err(|e| format!("Failed to sum attachment sizes: {}", e))?;
+
+        let mut breakdown = StorageBreakdown::default();
+        for row in rows {
+            let group = StorageUsageGroup {
+                attachment_type: row.try_get("", "attachment_type").map_err(|e| e.to_string())?,
+                mime_type: row.try_get("", "mime_type").map_err(|e| e.to_string())?,
+                count: row.try_get("", "count").map_err(|e| e.to_string())?,
+                bytes: row.try_get("", "bytes").map_err(|e| e.to_string())?,
+            };
+            breakdown.total_count += group.count;
+            breakdown.total_bytes += group.bytes;
+            breakdown.groups.push(group);
+        }
+
+        breakdown.largest = Self::largest_attachments(db, top_n.clamp(0, MAX_LARGEST_ATTACHMENTS)).await?;
+
+        let known = Self::known_file_ids(db).await?;
+        let dir = storage_dir.to_path_buf();
+        let (orphaned_files, orphaned_bytes) =
+            tokio::task::spawn_blocking(move || Self::measure_orphans(&dir, &known))
+                .await
+                .map_err(|e| format!("Storage scan failed: {}", e))??;
+        breakdown.orphaned_files = orphaned_files;
+        breakdown.orphaned_bytes = orphaned_bytes;
+
+        log::info!(
+            "Storage breakdown: {} attachment(s), {} bytes, {} orphaned file(s) ({} bytes)",
+            breakdown.total_count,
+            breakdown.total_bytes,
+            breakdown.orphaned_files,
+            breakdown.orphaned_bytes
+        );
+        Ok(breakdown)
+    }
+
+    async fn largest_attachments(db: &DatabaseConnection, limit: i64) -> Result<Vec<LargestAttachment>, String> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT ma.id, ma.original_name, COALESCE(ma.attachment_type, 'file') AS attachment_type, \
+                        COALESCE(ma.mime_type, 'application/octet-stream') AS mime_type, \
+                        COALESCE(ma.file_size, 0) AS file_size, ma.medical_record_id, \
+                        mr.name AS record_name, mr.patient_id, p.name AS patient_name \
+                 FROM medical_attachments ma \
+                 LEFT JOIN medical_records mr ON mr.id = ma.medical_record_id \
+                 LEFT JOIN patients p ON p.id = mr.patient_id \
+                 WHERE ma.is_external = 0 \
+                 ORDER BY file_size DESC, ma.id \
+                 LIMIT ?",
+                [limit.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch largest attachments: {}", e))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(LargestAttachment {
+                    attachment_id: row.try_get("", "id").map_err(|e| e.to_string())?,
+                    original_name: row.try_get("", "original_name").unwrap_or_default(),
+                    attachment_type: row.try_get("", "attachment_type").map_err(|e| e.to_string())?,
+                    mime_type: row.try_get("", "mime_type").map_err(|e| e.to_string())?,
+                    file_size: row.try_get("", "file_size").map_err(|e| e.to_string())?,
+                    medical_record_id: row.try_get("", "medical_record_id").map_err(|e| e.to_string())?,
+                    record_name: row.try_get("", "record_name").ok().flatten(),
+                    patient_id: row.try_get("", "patient_id").ok().flatten(),
+                    patient_name: row.try_get("", "patient_name").ok().flatten(),
+                })
+            })
+            .collect()
+    }
+
+    /// File ids stored files may be named after: attachments and device
+    /// files kept in file history.
+    async fn known_file_ids(db: &DatabaseConnection) -> Result<HashSet<String>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!(
+                    "SELECT {} AS file_id FROM medical_attachments WHERE is_external = 0 \
+                     UNION SELECT file_id FROM file_access_history",
+                    STORED_FILE
+                ),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch file ids: {}", e))?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| row.try_get::<Option<String>>("", "file_id").ok().flatten())
+            .collect())
+    }
+
+    fn measure_orphans(storage_dir: &Path, known: &HashSet<String>) -> Result<(i64, i64), String> {
+        let entries = match std::fs::read_dir(storage_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, 0)),
+            Err(e) => return Err(format!("Failed to read storage directory: {}", e)),
+        };
+
+        let (mut files, mut bytes) = (0, 0);
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else { continue };
+            if !metadata.is_file() || known.contains(entry.file_name().to_string_lossy().as_ref()) {
+                continue;
+            }
+            files += 1;
+            bytes += metadata.len() as i64;
+        }
+        Ok((files, bytes))
+    }
+}
@@ -1,13 +1,51 @@
-use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use sea_orm::{DatabaseConnection, ConnectionTrait, Statement, DbBackend};
 #[allow(unused_imports)]
 use crate::models::{
-    sync_log::{SyncLog, SyncLogCreate, SyncStatus, SyncDirection, SyncType},
+    sync_log::{
+        AppointmentSyncLog, AppointmentSyncStatus, PendingSyncAppointment, SyncAction, SyncLog, SyncLogCreate,
+        SyncRetrySummary, SyncStatus, SyncDirection, SyncType,
+    },
     google_calendar::CalendarEventMapping,
     AppointmentStatus,
 };
 #[allow(unused_imports)]
 use crate::services::google_calendar::GoogleCalendarService;
+use crate::services::settings::SettingsService;
+
+/// Attempts per failed `appointment_sync_log` row before it is left alone.
+pub const MAX_SYNC_RETRIES: i64 = 5;
+
+/// Delay before the first retry; doubles after every further failure.
+const RETRY_BASE_DELAY_SECS: i64 = 60;
+
+/// Pushes one appointment change to Google Calendar. Returns the event id
+/// that was written, or `None` when there was nothing to push (sync disabled,
+/// or no event left to delete).
+pub(crate) trait AppointmentPusher {
+    async fn push(
+        &self,
+        db: &Arc<DatabaseConnection>,
+        appointment_id: i64,
+        action: SyncAction,
+    ) -> Result<Option<String>, String>;
+}
+
+fn parse_sync_action(value: &str) -> Option<SyncAction> {
+    match value {
+        "create" => Some(SyncAction::Create),
+        "update" => Some(SyncAction::Update),
+        "delete" => Some(SyncAction::Delete),
+        _ => None,
+    }
+}
+
+/// Backoff after the `attempts`-th failed retry: 1, 2, 4, 8... minutes.
+fn retry_delay(attempts: i64) -> chrono::Duration {
+    let exponent = (attempts - 1).clamp(0, 10) as u32;
+    chrono::Duration::seconds(RETRY_BASE_DELAY_SECS * 2_i64.pow(exponent))
+}
 
 #[allow(dead_code)]
 pub struct SyncService;
@@ -53,7 +91,8 @@ impl SyncService {
         .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
 
         let google_service = GoogleCalendarService::new()
-            .with_token(access_token);
+            .with_token(access_token)
+            .with_time_zone(SettingsService::practice_timezone_name(db, "default").await);
 
         for row in rows {
             let appointment_id: i64 = row.try_get("", "id").unwrap_or(0);
@@ -282,6 +321,310 @@ impl SyncService {
         }
     }
 
+    /// Append one push attempt for an appointment to `appointment_sync_log`.
+    pub async fn record_appointment_sync(
+        db: &DatabaseConnection,
+        appointment_id: i64,
+        action: SyncAction,
+        status: SyncStatus,
+        external_id: Option<String>,
+        error_message: Option<String>,
+    ) -> Result<i64, String> {
+        let result = db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO appointment_sync_log (appointment_id, external_id, sync_action, sync_status, error_message, synced_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+            [
+                appointment_id.into(),
+                sea_orm::Value::String(external_id.map(Box::new)),
+                action.to_string().into(),
+                status.to_string().into(),
+                sea_orm::Value::String(error_message.map(Box::new)),
+                Utc::now().to_rfc3339().into(),
+            ]
+        ))
+        .await
+        .map_err(|e| format!("Failed to record appointment sync: {}", e))?;
+
+        Ok(result.last_insert_id() as i64)
+    }
+
+    /// Whether an appointment has a Google event, and the error from its
+    /// latest sync attempt if that attempt failed.
+    pub async fn get_appointment_sync_status(
+        db: &DatabaseConnection,
+        appointment_id: i64,
+    ) -> Result<AppointmentSyncStatus, String> {
+        db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT id FROM appointments WHERE id = ?",
+            [appointment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch appointment: {}", e))?
+        .ok_or_else(|| "Appointment not found".to_string())?;
+
+        let mapping = db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT event_id, calendar_id, CAST(last_synced_at AS TEXT) AS last_synced_at \
+             FROM calendar_event_mappings WHERE appointment_id = ?",
+            [appointment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch event mapping: {}", e))?;
+
+        let latest_attempt = db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT sync_status, error_message FROM appointment_sync_log \
+             WHERE appointment_id = ? ORDER BY id DESC LIMIT 1",
+            [appointment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch appointment sync log: {}", e))?;
+
+        let last_error = latest_attempt.and_then(|row| {
+            let status: String = row.try_get("", "sync_status").unwrap_or_default();
+            if status == "failed" {
+                row.try_get::<Option<String>>("", "error_message").unwrap_or(None)
+            } else {
+                None
+            }
+        });
+
+        Ok(match mapping {
+            Some(row) => AppointmentSyncStatus {
+                appointment_id,
+                synced: true,
+                event_id: row.try_get("", "event_id").ok(),
+                calendar_id: row.try_get("", "calendar_id").ok(),
+                last_synced_at: row.try_get::<Option<String>>("", "last_synced_at")
+                    .unwrap_or(None)
+                    .and_then(|s| Self::parse_sync_timestamp(&s)),
+                last_error,
+            },
+            None => AppointmentSyncStatus {
+                appointment_id,
+                synced: false,
+                event_id: None,
+                calendar_id: None,
+                last_synced_at: None,
+                last_error,
+            },
+        })
+    }
+
+    /// Every push attempt recorded for an appointment, oldest first, for
+    /// troubleshooting why it didn't reach Google.
+    pub async fn get_appointment_sync_log(
+        db: &DatabaseConnection,
+        appointment_id: i64,
+    ) -> Result<Vec<AppointmentSyncLog>, String> {
+        db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT id FROM appointments WHERE id = ?",
+            [appointment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch appointment: {}", e))?
+        .ok_or_else(|| "Appointment not found".to_string())?;
+
+        let rows = db.query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT id, external_id, sync_action, sync_status, error_message, \
+             CAST(synced_at AS TEXT) AS synced_at, retry_count, next_retry_at \
+             FROM appointment_sync_log WHERE appointment_id = ? ORDER BY id",
+            [appointment_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch appointment sync log: {}", e))?;
+
+        rows.iter()
+            .map(|row| {
+                let action: String = row.try_get("", "sync_action").map_err(|e| e.to_string())?;
+                let status: String = row.try_get("", "sync_status").map_err(|e| e.to_string())?;
+                Ok(AppointmentSyncLog {
+                    id: row.try_get("", "id").map_err(|e| e.to_string())?,
+                    appointment_id,
+                    external_id: row.try_get("", "external_id").unwrap_or(None),
+                    sync_action: match action.as_str() {
+                        "create" => SyncAction::Create,
+                        "delete" => SyncAction::Delete,
+                        _ => SyncAction::Update,
+                    },
+                    sync_status: match status.as_str() {
+                        "success" => SyncStatus::Success,
+                        "failed" => SyncStatus::Failed,
+                        _ => SyncStatus::Pending,
+                    },
+                    error_message: row.try_get("", "error_message").unwrap_or(None),
+                    synced_at: row.try_get::<Option<String>>("", "synced_at")
+                        .unwrap_or(None)
+                        .and_then(|s| Self::parse_sync_timestamp(&s))
+                        .unwrap_or_else(Utc::now),
+                    retry_count: row.try_get("", "retry_count").unwrap_or(0),
+                    next_retry_at: row.try_get::<Option<String>>("", "next_retry_at")
+                        .unwrap_or(None)
+                        .and_then(|s| Self::parse_sync_timestamp(&s)),
+                })
+            })
+            .collect()
+    }
+
+    /// The push sync's work list, least recently changed first: appointments
+    /// without an event, appointments edited after their event was last
+    /// written, and deleted appointments whose event still exists. Empty
+    /// while sync is switched off.
+    ///
+    /// Timestamps are compared with `julianday` because mappings may hold
+    /// either RFC 3339 or SQLite's `CURRENT_TIMESTAMP` format. A mapping
+    /// without `last_synced_at` counts as stale.
+    pub async fn get_appointments_pending_sync(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<PendingSyncAppointment>, String> {
+        let enabled = db.query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT 1 FROM google_calendar_settings WHERE user_id = 'default' AND sync_enabled = 1".to_string()
+        ))
+        .await
+        .map_err(|e| format!("Failed to check sync settings: {}", e))?;
+        if enabled.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let rows = db.query_all(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT a.id, a.deleted_at IS NOT NULL AS deleted, m.event_id, m.calendar_id \
+             FROM appointments a \
+             LEFT JOIN calendar_event_mappings m ON m.appointment_id = a.id \
+             WHERE (a.deleted_at IS NULL AND (m.id IS NULL OR m.last_synced_at IS NULL \
+                    OR julianday(a.updated_at) > julianday(m.last_synced_at))) \
+                OR (a.deleted_at IS NOT NULL AND m.id IS NOT NULL) \
+             ORDER BY julianday(a.updated_at) ASC, a.id ASC".to_string()
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch appointments pending sync: {}", e))?;
+
+        rows.iter()
+            .map(|row| {
+                let event_id: Option<String> = row.try_get("", "event_id").map_err(|e| e.to_string())?;
+                let deleted: i64 = row.try_get("", "deleted").map_err(|e| e.to_string())?;
+                let action = match (deleted != 0, &event_id) {
+                    (true, _) => SyncAction::Delete,
+                    (false, Some(_)) => SyncAction::Update,
+                    (false, None) => SyncAction::Create,
+                };
+                Ok(PendingSyncAppointment {
+                    appointment_id: row.try_get("", "id").map_err(|e| e.to_string())?,
+                    action,
+                    event_id,
+                    calendar_id: row.try_get("", "calendar_id").map_err(|e| e.to_string())?,
+                })
+            })
+            .collect()
+    }
+
+    /// Re-push appointments whose latest sync attempt failed, oldest first.
+    ///
+    /// Only the newest log row per appointment is considered, so a failure
+    /// that a later push already fixed is not replayed. Each row is retried
+    /// at most `MAX_SYNC_RETRIES` times, backing off between attempts, and is
+    /// updated in place with the outcome. Failed `sync_logs` runs aren't
+    /// handled here: they are whole pulls from Google that the scheduler
+    /// re-runs every minute anyway.
+    pub(crate) async fn retry_failed_syncs<P: AppointmentPusher>(
+        db: &Arc<DatabaseConnection>,
+        pusher: &P,
+        limit: i64,
+    ) -> Result<SyncRetrySummary, String> {
+        let now = Utc::now();
+        let rows = db.query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT l.id, l.appointment_id, l.sync_action, l.retry_count FROM appointment_sync_log l \
+             WHERE l.sync_status = 'failed' AND l.retry_count < ? \
+             AND (l.next_retry_at IS NULL OR l.next_retry_at <= ?) \
+             AND NOT EXISTS (SELECT 1 FROM appointment_sync_log newer \
+                             WHERE newer.appointment_id = l.appointment_id AND newer.id > l.id) \
+             ORDER BY l.id ASC LIMIT ?",
+            [
+                MAX_SYNC_RETRIES.into(),
+                now.to_rfc3339_opts(SecondsFormat::Secs, true).into(),
+                limit.into(),
+            ]
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch failed syncs: {}", e))?;
+
+        let mut summary = SyncRetrySummary::default();
+
+        for row in rows {
+            let log_id: i64 = row.try_get("", "id").map_err(|e| e.to_string())?;
+            let appointment_id: i64 = row.try_get("", "appointment_id").map_err(|e| e.to_string())?;
+            let action_str: String = row.try_get("", "sync_action").map_err(|e| e.to_string())?;
+            let attempts = row.try_get::<i64>("", "retry_count").unwrap_or(0) + 1;
+
+            let action = match parse_sync_action(&action_str) {
+                Some(action) => action,
+                None => {
+                    log::warn!("Skipping sync log {} with unknown action '{}'", log_id, action_str);
+                    continue;
+                }
+            };
+
+            match pusher.push(db, appointment_id, action).await {
+                Ok(Some(event_id)) => {
+                    db.execute(Statement::from_sql_and_values(
+                        DbBackend::Sqlite,
+                        "UPDATE appointment_sync_log SET sync_status = 'success', external_id = ?, \
+                         error_message = NULL, retry_count = ?, next_retry_at = NULL, synced_at = ? WHERE id = ?",
+                        [event_id.into(), attempts.into(), Utc::now().to_rfc3339().into(), log_id.into()]
+                    ))
+                    .await
+                    .map_err(|e| format!("Failed to update sync log: {}", e))?;
+                    summary.retried += 1;
+                    summary.succeeded += 1;
+                }
+                // Sync was switched off or the event is already gone; leave
+                // the row for when there is something to push again
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("Retry {} of sync log {} failed: {}", attempts, log_id, e);
+                    let next_retry_at = Utc::now() + retry_delay(attempts);
+                    db.execute(Statement::from_sql_and_values(
+                        DbBackend::Sqlite,
+                        "UPDATE appointment_sync_log SET error_message = ?, retry_count = ?, \
+                         next_retry_at = ?, synced_at = ? WHERE id = ?",
+                        [
+                            e.into(),
+                            attempts.into(),
+                            next_retry_at.to_rfc3339_opts(SecondsFormat::Secs, true).into(),
+                            Utc::now().to_rfc3339().into(),
+                            log_id.into(),
+                        ]
+                    ))
+                    .await
+                    .map_err(|e| format!("Failed to update sync log: {}", e))?;
+                    summary.retried += 1;
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Sync timestamps are written as RFC 3339 by the app, but rows inserted
+    /// with `CURRENT_TIMESTAMP` use SQLite's `YYYY-MM-DD HH:MM:SS` (UTC).
+    fn parse_sync_timestamp(value: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(value)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                    .ok()
+                    .map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc))
+            })
+    }
+
     // Helper methods
     async fn create_sync_log(
         db: &DatabaseConnection,
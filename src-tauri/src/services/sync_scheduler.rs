@@ -1,9 +1,120 @@
 // T038: Periodic sync scheduler - checks Google Calendar for cancellations every 1 minute (with immediate check on startup)
+use crate::models::sync_log::SyncType;
 use crate::services::oauth::get_valid_access_token;
 use chrono::{Utc, Duration};
 use std::sync::Arc;
 use sea_orm::{DatabaseConnection, ConnectionTrait, Statement, DbBackend};
 
+/// How far back a full (token-less) pull lists events.
+const FULL_SYNC_LOOKBACK_DAYS: i64 = 7;
+
+/// One page of an `events.list` response.
+pub(crate) struct CalendarEventPage {
+    pub events: Vec<ChangedEvent>,
+    pub next_page_token: Option<String>,
+    /// Only set on the last page; used for the next incremental pull.
+    pub next_sync_token: Option<String>,
+}
+
+pub(crate) struct ChangedEvent {
+    pub id: String,
+    /// "confirmed", "tentative" or "cancelled"
+    pub status: String,
+}
+
+pub(crate) enum EventListError {
+    /// 410 Gone: the sync token is no longer valid, a full pull is needed.
+    SyncTokenExpired,
+    Failed(String),
+}
+
+/// Lists events of a calendar. With `sync_token` only events changed since
+/// the token was issued come back (cancelled ones included); without it,
+/// every event in the full-sync window.
+pub(crate) trait CalendarEventSource {
+    async fn list_events(
+        &self,
+        calendar_id: &str,
+        sync_token: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<CalendarEventPage, EventListError>;
+}
+
+/// Reads events through the Google Calendar API.
+struct GoogleEventSource {
+    access_token: String,
+}
+
+impl CalendarEventSource for GoogleEventSource {
+    async fn list_events(
+        &self,
+        calendar_id: &str,
+        sync_token: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<CalendarEventPage, EventListError> {
+        let mut query: Vec<(&str, String)> = vec![
+            ("showDeleted", "true".to_string()),
+            ("singleEvents", "true".to_string()),
+            ("maxResults", "250".to_string()),
+        ];
+        match sync_token {
+            Some(token) => query.push(("syncToken", token.to_string())),
+            // Google rejects timeMin together with syncToken; later
+            // incremental pulls report every change after this listing
+            None => query.push((
+                "timeMin",
+                (Utc::now() - Duration::days(FULL_SYNC_LOOKBACK_DAYS)).to_rfc3339(),
+            )),
+        }
+        if let Some(page_token) = page_token {
+            query.push(("pageToken", page_token.to_string()));
+        }
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("https://www.googleapis.com/calendar/v3/calendars/{}/events", calendar_id))
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| EventListError::Failed(format!("Failed to fetch Google Calendar events: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::GONE {
+            return Err(EventListError::SyncTokenExpired);
+        }
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(EventListError::Failed(format!("Google Calendar API error: {}", error_text)));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| EventListError::Failed(format!("Failed to parse events: {}", e)))?;
+
+        let events = body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|event| {
+                        Some(ChangedEvent {
+                            id: event["id"].as_str()?.to_string(),
+                            status: event["status"].as_str().unwrap_or("confirmed").to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(CalendarEventPage {
+            events,
+            next_page_token: body["nextPageToken"].as_str().map(String::from),
+            next_sync_token: body["nextSyncToken"].as_str().map(String::from),
+        })
+    }
+}
+
 pub struct SyncScheduler;
 
 impl SyncScheduler {
@@ -61,85 +172,129 @@ impl SyncScheduler {
         // Get valid access token (will refresh if needed)
         let access_token = get_valid_access_token(&db).await?;
 
+        Self::pull_changes(&db, &GoogleEventSource { access_token }, &calendar_id).await?;
+
+        // Update last_sync timestamp
+        db.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "UPDATE google_calendar_settings SET last_sync = CURRENT_TIMESTAMP WHERE user_id = 'default'".to_string()
+        ))
+        .await
+        .map_err(|e| format!("Failed to update last sync: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Pull event changes for `calendar_id` and apply them. With a stored
+    /// sync token only changed events are requested (`incremental` in
+    /// `sync_logs`); without one, or once Google answers 410 Gone for it,
+    /// the whole window is listed (`initial`). The token from the last page
+    /// is kept for the next run. Returns the `sync_logs` id of the run.
+    pub(crate) async fn pull_changes<S: CalendarEventSource>(
+        db: &DatabaseConnection,
+        source: &S,
+        calendar_id: &str,
+    ) -> Result<i64, String> {
+        let mut sync_token = Self::load_sync_token(db, calendar_id).await?;
+        let mut sync_type = if sync_token.is_some() { SyncType::Incremental } else { SyncType::Initial };
+
         // Create sync log
-        let sync_log_result = db.execute(Statement::from_string(
+        let sync_log_result = db.execute(Statement::from_sql_and_values(
             DbBackend::Sqlite,
-            "INSERT INTO sync_logs (direction, sync_type, status, started_at) VALUES ('from_google', 'incremental', 'in_progress', CURRENT_TIMESTAMP)".to_string()
+            "INSERT INTO sync_logs (direction, sync_type, status, started_at) VALUES ('from_google', ?, 'in_progress', CURRENT_TIMESTAMP)",
+            [sync_type.to_string().into()]
         ))
         .await
         .map_err(|e| format!("Failed to create sync log: {}", e))?;
 
         let sync_log_id = sync_log_result.last_insert_id() as i64;
 
-        // Get events from last 7 days to now
-        let time_min = (Utc::now() - Duration::days(7)).to_rfc3339();
-        let time_max = Utc::now().to_rfc3339();
-
-        // Fetch events from Google Calendar
-        let client = reqwest::Client::new();
-        let response = client
-            .get(format!("https://www.googleapis.com/calendar/v3/calendars/{}/events", calendar_id))
-            .header("Authorization", format!("Bearer {}", access_token))
-            .query(&[
-                ("timeMin", time_min.as_str()),
-                ("timeMax", time_max.as_str()),
-                ("showDeleted", "true"),
-                ("singleEvents", "true"),
-            ])
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch Google Calendar events: {}", e))?;
+        let mut items_synced = 0;
+        let mut items_failed = 0;
+        let mut page_token: Option<String> = None;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        let next_sync_token = loop {
+            let page = match source.list_events(calendar_id, sync_token.as_deref(), page_token.as_deref()).await {
+                Ok(page) => page,
+                Err(EventListError::SyncTokenExpired) if sync_token.is_some() => {
+                    log::warn!("Sync token for calendar {} expired, falling back to a full sync", calendar_id);
+                    Self::clear_sync_token(db, calendar_id).await?;
+                    sync_token = None;
+                    page_token = None;
+                    sync_type = SyncType::Initial;
+                    db.execute(Statement::from_sql_and_values(
+                        DbBackend::Sqlite,
+                        "UPDATE sync_logs SET sync_type = ? WHERE id = ?",
+                        [sync_type.to_string().into(), sync_log_id.into()]
+                    ))
+                    .await
+                    .map_err(|e| format!("Failed to update sync log: {}", e))?;
+                    continue;
+                }
+                Err(e) => {
+                    let error_text = match e {
+                        EventListError::SyncTokenExpired => "Google Calendar returned 410 Gone for a full sync".to_string(),
+                        EventListError::Failed(message) => message,
+                    };
 
-            // Mark sync as failed
-            let _ = db.execute(Statement::from_sql_and_values(
-                DbBackend::Sqlite,
-                "UPDATE sync_logs SET status = 'failed', error_message = ?, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
-                [error_text.clone().into(), sync_log_id.into()]
-            ))
-            .await;
+                    // Mark sync as failed
+                    let _ = db.execute(Statement::from_sql_and_values(
+                        DbBackend::Sqlite,
+                        "UPDATE sync_logs SET status = 'failed', error_message = ?, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
+                        [error_text.clone().into(), sync_log_id.into()]
+                    ))
+                    .await;
 
-            return Err(format!("Google Calendar API error: {}", error_text));
-        }
+                    return Err(error_text);
+                }
+            };
 
-        let events: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse events: {}", e))?;
+            let (synced, failed) = Self::apply_event_changes(db, &page.events).await?;
+            items_synced += synced;
+            items_failed += failed;
 
-        let items = match events["items"].as_array() {
-            Some(items) => items,
-            None => {
-                // No events, mark sync as success
-                let _ = db.execute(Statement::from_sql_and_values(
-                    DbBackend::Sqlite,
-                    "UPDATE sync_logs SET status = 'success', items_synced = 0, items_failed = 0, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
-                    [sync_log_id.into()]
-                ))
-                .await;
-                return Ok(());
+            match page.next_page_token {
+                Some(next) => page_token = Some(next),
+                None => break page.next_sync_token,
             }
         };
 
+        match next_sync_token {
+            Some(token) => Self::save_sync_token(db, calendar_id, &token).await?,
+            None => log::warn!("Google Calendar returned no sync token for {}; next sync will be a full one", calendar_id),
+        }
+
+        // Mark sync as complete
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE sync_logs SET status = 'success', items_synced = ?, items_failed = ?, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
+            [items_synced.into(), items_failed.into(), sync_log_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to update sync log: {}", e))?;
+
+        log::info!("Periodic {} sync completed: {} synced, {} failed", sync_type, items_synced, items_failed);
+
+        Ok(sync_log_id)
+    }
+
+    /// Cancel the appointments whose mapped events were cancelled in
+    /// Google Calendar. Returns (synced, failed) counts.
+    async fn apply_event_changes(db: &DatabaseConnection, events: &[ChangedEvent]) -> Result<(i32, i32), String> {
         let mut items_synced = 0;
         let mut items_failed = 0;
 
-        // Process each event
-        for event in items {
-            let event_id = match event["id"].as_str() {
-                Some(id) => id,
-                None => continue,
-            };
-
-            let status = event["status"].as_str().unwrap_or("confirmed");
+        for event in events {
+            // Only cancellations are pulled back into the app
+            if event.status != "cancelled" {
+                continue;
+            }
 
             // Check if we have a mapping for this event
             let mapping = db.query_one(Statement::from_sql_and_values(
                 DbBackend::Sqlite,
                 "SELECT appointment_id FROM calendar_event_mappings WHERE event_id = ?",
-                [event_id.to_string().into()]
+                [event.id.clone().into()]
             ))
             .await
             .map_err(|e| format!("Failed to check mapping: {}", e))?;
@@ -148,51 +303,64 @@ impl SyncScheduler {
                 let appointment_id: i64 = row.try_get("", "appointment_id")
                     .map_err(|e| format!("Failed to get appointment_id: {}", e))?;
 
-                // Check if event is cancelled in Google Calendar
-                if status == "cancelled" {
-                    // Update appointment status to cancelled
-                    let update_result = db.execute(Statement::from_sql_and_values(
-                        DbBackend::Sqlite,
-                        "UPDATE appointments SET status = 'cancelled', updated_at = CURRENT_TIMESTAMP WHERE id = ? AND status != 'cancelled'",
-                        [appointment_id.into()]
-                    ))
-                    .await;
+                // Update appointment status to cancelled
+                let update_result = db.execute(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "UPDATE appointments SET status = 'cancelled', updated_at = CURRENT_TIMESTAMP WHERE id = ? AND status != 'cancelled'",
+                    [appointment_id.into()]
+                ))
+                .await;
 
-                    match update_result {
-                        Ok(result) => {
-                            if result.rows_affected() > 0 {
-                                items_synced += 1;
-                                log::info!("Synced cancellation for appointment {} from Google Calendar", appointment_id);
-                            }
-                        }
-                        Err(e) => {
-                            items_failed += 1;
-                            log::error!("Failed to update appointment {}: {}", appointment_id, e);
+                match update_result {
+                    Ok(result) => {
+                        if result.rows_affected() > 0 {
+                            items_synced += 1;
+                            log::info!("Synced cancellation for appointment {} from Google Calendar", appointment_id);
                         }
                     }
+                    Err(e) => {
+                        items_failed += 1;
+                        log::error!("Failed to update appointment {}: {}", appointment_id, e);
+                    }
                 }
             }
         }
 
-        // Mark sync as complete
-        db.execute(Statement::from_sql_and_values(
+        Ok((items_synced, items_failed))
+    }
+
+    async fn load_sync_token(db: &DatabaseConnection, calendar_id: &str) -> Result<Option<String>, String> {
+        let row = db.query_one(Statement::from_sql_and_values(
             DbBackend::Sqlite,
-            "UPDATE sync_logs SET status = 'success', items_synced = ?, items_failed = ?, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
-            [items_synced.into(), items_failed.into(), sync_log_id.into()]
+            "SELECT sync_token FROM calendar_sync_tokens WHERE calendar_id = ?",
+            [calendar_id.into()]
         ))
         .await
-        .map_err(|e| format!("Failed to update sync log: {}", e))?;
+        .map_err(|e| format!("Failed to load sync token: {}", e))?;
 
-        // Update last_sync timestamp
-        db.execute(Statement::from_string(
+        Ok(row.and_then(|r| r.try_get("", "sync_token").ok()))
+    }
+
+    async fn save_sync_token(db: &DatabaseConnection, calendar_id: &str, sync_token: &str) -> Result<(), String> {
+        db.execute(Statement::from_sql_and_values(
             DbBackend::Sqlite,
-            "UPDATE google_calendar_settings SET last_sync = CURRENT_TIMESTAMP WHERE user_id = 'default'".to_string()
+            "INSERT INTO calendar_sync_tokens (calendar_id, sync_token, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(calendar_id) DO UPDATE SET sync_token = excluded.sync_token, updated_at = CURRENT_TIMESTAMP",
+            [calendar_id.into(), sync_token.into()]
         ))
         .await
-        .map_err(|e| format!("Failed to update last sync: {}", e))?;
-
-        log::info!("Periodic sync completed: {} synced, {} failed", items_synced, items_failed);
+        .map_err(|e| format!("Failed to save sync token: {}", e))?;
+        Ok(())
+    }
 
+    async fn clear_sync_token(db: &DatabaseConnection, calendar_id: &str) -> Result<(), String> {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM calendar_sync_tokens WHERE calendar_id = ?",
+            [calendar_id.into()]
+        ))
+        .await
+        .map_err(|e| format!("Failed to clear sync token: {}", e))?;
         Ok(())
     }
 }
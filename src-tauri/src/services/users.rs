@@ -0,0 +1,208 @@
+//! Clinic staff and the user currently working in the app.
+//!
+//! The current user lives in Tauri managed state (`CurrentUser`) for the
+//! session. Commands read it from there and pass it to services as
+//! `actor`, which they write to `created_by` / `updated_by` or a history
+//! row. Actors are stored as the user id.
+
+use std::sync::Mutex;
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement};
+
+use crate::models::user::{CreateUserInput, UpdateUserInput, User, USER_ROLES};
+
+const USER_COLUMNS: &str = "id, name, role, active, created_at, updated_at";
+
+/// The user picked with `set_current_user`, managed by Tauri for the session.
+#[derive(Default)]
+pub struct CurrentUser(Mutex<Option<User>>);
+
+impl CurrentUser {
+    pub fn get(&self) -> Option<User> {
+        self.0.lock().expect("current user mutex poisoned").clone()
+    }
+
+    /// The current user's id as written to `created_by` / `updated_by`.
+    pub fn actor(&self) -> Option<String> {
+        UserService::actor_id(self.get().as_ref())
+    }
+
+    fn set(&self, user: Option<User>) {
+        *self.0.lock().expect("current user mutex poisoned") = user;
+    }
+
+    /// Replace the current user with `user` if it is the same user; an
+    /// inactive or deleted (`None`) user is cleared.
+    fn refresh(&self, id: i64, user: Option<&User>) {
+        let mut current = self.0.lock().expect("current user mutex poisoned");
+        if current.as_ref().is_some_and(|c| c.id == id) {
+            *current = user.filter(|u| u.active).cloned();
+        }
+    }
+}
+
+pub struct UserService;
+
+impl UserService {
+    pub async fn get_users(db: &DatabaseConnection, include_inactive: bool) -> Result<Vec<User>, String> {
+        let filter = if include_inactive { "" } else { " WHERE active = 1" };
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!("SELECT {} FROM users{} ORDER BY name, id", USER_COLUMNS, filter),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch users: {}", e))?;
+        rows.iter().map(row_to_user).collect()
+    }
+
+    pub async fn get_user(db: &DatabaseConnection, id: i64) -> Result<User, String> {
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS),
+                [id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch user: {}", e))?
+            .ok_or_else(|| format!("User {} not found", id))?;
+        row_to_user(&row)
+    }
+
+    pub async fn create_user(db: &DatabaseConnection, input: CreateUserInput) -> Result<User, String> {
+        let name = validate_name(&input.name)?;
+        validate_role(&input.role)?;
+
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO users (name, role) VALUES (?, ?)",
+                [name.into(), input.role.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to create user: {}", e))?;
+
+        Self::get_user(db, result.last_insert_id() as i64).await
+    }
+
+    /// Deactivating the current user also clears the current user.
+    pub async fn update_user(
+        db: &DatabaseConnection,
+        current: &CurrentUser,
+        id: i64,
+        input: UpdateUserInput,
+    ) -> Result<User, String> {
+        let existing = Self::get_user(db, id).await?;
+        let name = match input.name {
+            Some(name) => validate_name(&name)?,
+            None => existing.name,
+        };
+        let role = input.role.unwrap_or(existing.role);
+        validate_role(&role)?;
+        let active = input.active.unwrap_or(existing.active);
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE users SET name = ?, role = ?, active = ?, updated_at = ? WHERE id = ?",
+            [name.into(), role.into(), active.into(), Utc::now().to_rfc3339().into(), id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to update user: {}", e))?;
+
+        let user = Self::get_user(db, id).await?;
+        current.refresh(id, Some(&user));
+        Ok(user)
+    }
+
+    /// Delete a user. A user already named in records, appointments or
+    /// history is deactivated instead, so those references keep resolving.
+    /// Returns whether the row was actually deleted.
+    pub async fn delete_user(db: &DatabaseConnection, current: &CurrentUser, id: i64) -> Result<bool, String> {
+        Self::get_user(db, id).await?;
+
+        let references: i64 = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT \
+                   (SELECT COUNT(*) FROM medical_records WHERE created_by = ?1 OR updated_by = ?1) + \
+                   (SELECT COUNT(*) FROM medical_record_history WHERE changed_by = ?1) + \
+                   (SELECT COUNT(*) FROM appointments WHERE created_by = ?1) AS refs",
+                [id.to_string().into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to check user references: {}", e))?
+            .and_then(|row| row.try_get("", "refs").ok())
+            .unwrap_or(0);
+
+        if references > 0 {
+            Self::update_user(db, current, id, UpdateUserInput { name: None, role: None, active: Some(false) }).await?;
+            return Ok(false);
+        }
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM users WHERE id = ?",
+            [id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to delete user: {}", e))?;
+
+        current.refresh(id, None);
+        Ok(true)
+    }
+
+    /// Make `user_id` the user that new changes are attributed to, or clear
+    /// it with `None`. Only active users can be picked.
+    pub async fn set_current_user(
+        db: &DatabaseConnection,
+        current: &CurrentUser,
+        user_id: Option<i64>,
+    ) -> Result<Option<User>, String> {
+        let user = match user_id {
+            Some(id) => {
+                let user = Self::get_user(db, id).await?;
+                if !user.active {
+                    return Err(format!("User {} is inactive", id));
+                }
+                Some(user)
+            }
+            None => None,
+        };
+        current.set(user.clone());
+        log::info!("Current user set to {:?}", user.as_ref().map(|u| u.id));
+        Ok(user)
+    }
+
+    /// What `created_by` / `updated_by` / `changed_by` get for a change by
+    /// `actor`: the user's id, or `None` when nobody is picked.
+    pub fn actor_id(actor: Option<&User>) -> Option<String> {
+        actor.map(|user| user.id.to_string())
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("User name is required".to_string());
+    }
+    Ok(name.to_string())
+}
+
+fn validate_role(role: &str) -> Result<(), String> {
+    if !USER_ROLES.contains(&role) {
+        return Err(format!("Invalid role '{}', expected one of: {}", role, USER_ROLES.join(", ")));
+    }
+    Ok(())
+}
+
+fn row_to_user(row: &QueryResult) -> Result<User, String> {
+    Ok(User {
+        id: row.try_get("", "id").map_err(|e| e.to_string())?,
+        name: row.try_get("", "name").map_err(|e| e.to_string())?,
+        role: row.try_get("", "role").map_err(|e| e.to_string())?,
+        active: row.try_get("", "active").map_err(|e| e.to_string())?,
+        created_at: row.try_get("", "created_at").unwrap_or_else(|_| Utc::now()),
+        updated_at: row.try_get("", "updated_at").unwrap_or_else(|_| Utc::now()),
+    })
+}
@@ -0,0 +1,313 @@
+//! Vaccine protocols and recorded vaccinations.
+//!
+//! Raw SQL on flat tables like `DiagnosisService`. Vaccine names are
+//! matched case-insensitively in Rust, since SQLite's NOCASE only folds
+//! ASCII and names are often Cyrillic.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement};
+
+use crate::models::vaccination::{
+    CreateVaccineProtocolInput, OverdueVaccination, RecordVaccinationInput, UpdateVaccineProtocolInput,
+    Vaccination, VaccineProtocol,
+};
+
+const PROTOCOL_COLUMNS: &str = "id, vaccine_name, species_id, interval_days, created_at, updated_at";
+const VACCINATION_COLUMNS: &str =
+    "id, patient_id, vaccine_name, administered_at, next_due_at, notes, created_at";
+
+/// Longest protocol interval accepted (ten years).
+const MAX_INTERVAL_DAYS: i64 = 3650;
+
+pub struct VaccinationService;
+
+impl VaccinationService {
+    pub async fn get_protocols(db: &DatabaseConnection) -> Result<Vec<VaccineProtocol>, String> {
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!("SELECT {} FROM vaccine_protocols ORDER BY vaccine_name, species_id", PROTOCOL_COLUMNS),
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch vaccine protocols: {}", e))?;
+        rows.iter().map(row_to_protocol).collect()
+    }
+
+    pub async fn get_protocol(db: &DatabaseConnection, id: i64) -> Result<VaccineProtocol, String> {
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT {} FROM vaccine_protocols WHERE id = ?", PROTOCOL_COLUMNS),
+                [id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch vaccine protocol: {}", e))?
+            .ok_or_else(|| format!("Vaccine protocol {} not found", id))?;
+        row_to_protocol(&row)
+    }
+
+    pub async fn create_protocol(
+        db: &DatabaseConnection,
+        input: CreateVaccineProtocolInput,
+    ) -> Result<VaccineProtocol, String> {
+        let vaccine_name = input.vaccine_name.trim().to_string();
+        Self::validate_protocol(db, &vaccine_name, input.species_id, input.interval_days, None).await?;
+
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO vaccine_protocols (vaccine_name, species_id, interval_days) VALUES (?, ?, ?)",
+                [vaccine_name.into(), input.species_id.into(), input.interval_days.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to create vaccine protocol: {}", e))?;
+
+        Self::get_protocol(db, result.last_insert_id() as i64).await
+    }
+
+    pub async fn update_protocol(
+        db: &DatabaseConnection,
+        id: i64,
+        input: UpdateVaccineProtocolInput,
+    ) -> Result<VaccineProtocol, String> {
+        let existing = Self::get_protocol(db, id).await?;
+        let vaccine_name = input
+            .vaccine_name
+            .map(|n| n.trim().to_string())
+            .unwrap_or(existing.vaccine_name);
+        let species_id = input.species_id.unwrap_or(existing.species_id);
+        let interval_days = input.interval_days.unwrap_or(existing.interval_days);
+        Self::validate_protocol(db, &vaccine_name, species_id, interval_days, Some(id)).await?;
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE vaccine_protocols SET vaccine_name = ?, species_id = ?, interval_days = ? WHERE id = ?",
+            [vaccine_name.into(), species_id.into(), interval_days.into(), id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to update vaccine protocol: {}", e))?;
+
+        Self::get_protocol(db, id).await
+    }
+
+    /// Vaccinations already recorded keep the next-due date they got.
+    pub async fn delete_protocol(db: &DatabaseConnection, id: i64) -> Result<(), String> {
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "DELETE FROM vaccine_protocols WHERE id = ?",
+                [id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to delete vaccine protocol: {}", e))?;
+        if result.rows_affected() == 0 {
+            return Err(format!("Vaccine protocol {} not found", id));
+        }
+        Ok(())
+    }
+
+    /// Record a vaccination. Without `next_due_at` the next dose is
+    /// scheduled from the protocol for the vaccine and the patient's
+    /// species; with no such protocol no next dose is set.
+    pub async fn record_vaccination(
+        db: &DatabaseConnection,
+        input: RecordVaccinationInput,
+    ) -> Result<Vaccination, String> {
+        let vaccine_name = input.vaccine_name.trim().to_string();
+        if vaccine_name.is_empty() {
+            return Err("Vaccine name is required".to_string());
+        }
+
+        // A patient without a species has no protocol to follow
+        let species_id: Option<i64> = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT species_id FROM patients WHERE id = ?",
+                [input.patient_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch patient: {}", e))?
+            .ok_or_else(|| format!("Patient {} not found", input.patient_id))?
+            .try_get("", "species_id")
+            .map_err(|e| e.to_string())?;
+
+        let next_due_at = match (input.next_due_at, species_id) {
+            (Some(due), _) => Some(due),
+            (None, Some(species_id)) => Self::find_protocol(db, &vaccine_name, species_id)
+                .await?
+                .map(|protocol| input.administered_at + Duration::days(protocol.interval_days)),
+            (None, None) => None,
+        };
+        if next_due_at.is_some_and(|due| due <= input.administered_at) {
+            return Err("Next due date must be after the administration date".to_string());
+        }
+
+        let notes = input.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO vaccinations (patient_id, vaccine_name, administered_at, next_due_at, notes) \
+                 VALUES (?, ?, ?, ?, ?)",
+                [
+                    input.patient_id.into(),
+                    vaccine_name.into(),
+                    input.administered_at.into(),
+                    next_due_at.into(),
+                    notes.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("Failed to record vaccination: {}", e))?;
+
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!("SELECT {} FROM vaccinations WHERE id = ?", VACCINATION_COLUMNS),
+                [(result.last_insert_id() as i64).into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch vaccination: {}", e))?
+            .ok_or_else(|| "Vaccination not found after insert".to_string())?;
+        row_to_vaccination(&row)
+    }
+
+    /// A patient's vaccinations, most recent first.
+    pub async fn get_patient_vaccinations(
+        db: &DatabaseConnection,
+        patient_id: i64,
+    ) -> Result<Vec<Vaccination>, String> {
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                &format!(
+                    "SELECT {} FROM vaccinations WHERE patient_id = ? ORDER BY administered_at DESC, id DESC",
+                    VACCINATION_COLUMNS
+                ),
+                [patient_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch vaccinations: {}", e))?;
+        rows.iter().map(row_to_vaccination).collect()
+    }
+
+    /// Vaccines whose next dose was due before `as_of`, most overdue first,
+    /// for active patients. Only a patient's latest dose of each vaccine
+    /// counts, so a booster given since clears the earlier dose.
+    pub async fn get_overdue(
+        db: &DatabaseConnection,
+        as_of: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<OverdueVaccination>, String> {
+        // Every dose of the patients with something overdue; the latest dose
+        // per vaccine is picked in Rust since names compare case-insensitively
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT v.id, v.patient_id, p.name AS patient_name, v.vaccine_name, v.administered_at, v.next_due_at \
+                 FROM vaccinations v \
+                 JOIN patients p ON p.id = v.patient_id \
+                 WHERE (p.is_active = 1 OR p.is_active IS NULL) \
+                 AND v.patient_id IN (SELECT patient_id FROM vaccinations WHERE next_due_at < ?) \
+                 ORDER BY v.administered_at DESC, v.id DESC",
+                [as_of.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch overdue vaccinations: {}", e))?;
+
+        let mut seen: HashSet<(i64, String)> = HashSet::new();
+        let mut overdue = Vec::new();
+        for row in &rows {
+            let vaccination = row_to_vaccination(row)?;
+            if !seen.insert((vaccination.patient_id, vaccination.vaccine_name.to_lowercase())) {
+                continue;
+            }
+            let Some(next_due_at) = vaccination.next_due_at.filter(|due| *due < as_of) else {
+                continue;
+            };
+            overdue.push(OverdueVaccination {
+                vaccination_id: vaccination.id,
+                patient_id: vaccination.patient_id,
+                patient_name: row.try_get("", "patient_name").ok().flatten(),
+                vaccine_name: vaccination.vaccine_name,
+                administered_at: vaccination.administered_at,
+                next_due_at,
+            });
+        }
+        overdue.sort_by_key(|v| (v.next_due_at, v.vaccination_id));
+        overdue.truncate(limit.max(0) as usize);
+        Ok(overdue)
+    }
+
+    async fn find_protocol(
+        db: &DatabaseConnection,
+        vaccine_name: &str,
+        species_id: i64,
+    ) -> Result<Option<VaccineProtocol>, String> {
+        let target = vaccine_name.to_lowercase();
+        Ok(Self::get_protocols(db)
+            .await?
+            .into_iter()
+            .find(|p| p.species_id == species_id && p.vaccine_name.to_lowercase() == target))
+    }
+
+    async fn validate_protocol(
+        db: &DatabaseConnection,
+        vaccine_name: &str,
+        species_id: i64,
+        interval_days: i64,
+        exclude_id: Option<i64>,
+    ) -> Result<(), String> {
+        if vaccine_name.is_empty() {
+            return Err("Vaccine name is required".to_string());
+        }
+        if !(1..=MAX_INTERVAL_DAYS).contains(&interval_days) {
+            return Err(format!("Interval must be between 1 and {} days", MAX_INTERVAL_DAYS));
+        }
+
+        let species = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT id FROM species WHERE id = ?",
+                [species_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("Failed to fetch species: {}", e))?;
+        if species.is_none() {
+            return Err(format!("Species {} not found", species_id));
+        }
+
+        if let Some(existing) = Self::find_protocol(db, vaccine_name, species_id).await? {
+            if Some(existing.id) != exclude_id {
+                return Err("A protocol for this vaccine and species already exists".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn row_to_protocol(row: &QueryResult) -> Result<VaccineProtocol, String> {
+    Ok(VaccineProtocol {
+        id: row.try_get("", "id").map_err(|e| e.to_string())?,
+        vaccine_name: row.try_get("", "vaccine_name").map_err(|e| e.to_string())?,
+        species_id: row.try_get("", "species_id").map_err(|e| e.to_string())?,
+        interval_days: row.try_get("", "interval_days").map_err(|e| e.to_string())?,
+        created_at: row.try_get("", "created_at").unwrap_or_else(|_| Utc::now()),
+        updated_at: row.try_get("", "updated_at").unwrap_or_else(|_| Utc::now()),
+    })
+}
+
+fn row_to_vaccination(row: &QueryResult) -> Result<Vaccination, String> {
+    Ok(Vaccination {
+        id: row.try_get("", "id").map_err(|e| e.to_string())?,
+        patient_id: row.try_get("", "patient_id").map_err(|e| e.to_string())?,
+        vaccine_name: row.try_get("", "vaccine_name").map_err(|e| e.to_string())?,
+        administered_at: row
+            .try_get::<DateTime<Utc>>("", "administered_at")
+            .map_err(|e| e.to_string())?,
+        next_due_at: row.try_get("", "next_due_at").ok().flatten(),
+        notes: row.try_get("", "notes").ok().flatten(),
+        created_at: row.try_get("", "created_at").unwrap_or_else(|_| Utc::now()),
+    })
+}
@@ -131,6 +131,8 @@ async fn create_test_schema(db: &DatabaseConnection) {
             capacity INTEGER DEFAULT 1,
             color TEXT DEFAULT '#3498db',
             is_active INTEGER DEFAULT 1,
+            display_order INTEGER NOT NULL DEFAULT 0,
+            buffer_minutes INTEGER NOT NULL DEFAULT 0,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
             updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )
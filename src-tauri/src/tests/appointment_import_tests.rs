@@ -0,0 +1,215 @@
+//! Tests for `AppointmentService::validate_appointment_import`.
+
+use crate::models::{AppointmentImportEntry, CreateAppointmentInput, CreateRoomInput};
+use crate::services::appointments::AppointmentService;
+use crate::services::rooms::RoomService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient, create_test_room};
+use chrono::{DateTime, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+async fn seed_patient(db: &DatabaseConnection, name: &str, microchip_id: &str) -> i64 {
+    let id = create_test_patient(db, name, 1, None).await;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE patients SET microchip_id = ? WHERE id = ?",
+        [microchip_id.into(), id.into()],
+    ))
+    .await
+    .unwrap();
+    id
+}
+
+async fn seed_room_with(
+    db: &DatabaseConnection,
+    name: &str,
+    capacity: Option<i32>,
+    buffer_minutes: Option<i32>,
+) -> i64 {
+    RoomService::create_room(
+        db,
+        CreateRoomInput {
+            name: name.to_string(),
+            description: None,
+            capacity,
+            color: None,
+            buffer_minutes,
+        },
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 3, 12, hour, minute, 0).unwrap()
+}
+
+fn entry(uid: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> AppointmentImportEntry {
+    AppointmentImportEntry {
+        uid: Some(uid.to_string()),
+        patient_id: None,
+        microchip_id: Some("900000000000001".to_string()),
+        title: "Vaccination".to_string(),
+        description: None,
+        start_time: start,
+        end_time: end,
+        room_id: None,
+        room_name: Some("surgery".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn entries_conflicting_with_each_other_are_both_flagged() {
+    let db = create_test_db_with_migrations().await;
+    let patient = seed_patient(&db, "Rex", "900000000000001").await;
+    let room = create_test_room(&db, "Surgery").await;
+
+    let entries = vec![
+        entry("a", at(9, 0), at(10, 0)),
+        entry("b", at(9, 30), at(10, 30)),
+        entry("c", at(11, 0), at(11, 30)),
+    ];
+    let report = AppointmentService::validate_appointment_import(&db, &entries)
+        .await
+        .unwrap();
+
+    assert_eq!(report.entries[0].patient_id, Some(patient), "resolved by microchip");
+    assert_eq!(report.entries[0].room_id, Some(room), "resolved by name, ignoring case");
+    assert_eq!(report.entries[0].conflicting_entries, vec![1]);
+    assert_eq!(report.entries[1].conflicting_entries, vec![0]);
+    assert!(report.entries[2].is_valid());
+    assert_eq!((report.valid_count, report.invalid_count), (1, 2));
+}
+
+#[tokio::test]
+async fn reports_unresolved_invalid_existing_conflicts_and_duplicates() {
+    let db = create_test_db_with_migrations().await;
+    let patient = seed_patient(&db, "Rex", "900000000000001").await;
+    let room = create_test_room(&db, "Surgery").await;
+    let existing = AppointmentService::create_appointment(
+        &db,
+        CreateAppointmentInput {
+            patient_id: patient,
+            title: "Booked".to_string(),
+            description: None,
+            start_time: at(14, 0),
+            end_time: at(15, 0),
+            room_id: Some(room),
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap();
+
+    let mut unknown_patient = entry("p", at(8, 0), at(8, 30));
+    unknown_patient.microchip_id = Some("123".to_string());
+    let mut unknown_room = entry("r", at(8, 0), at(8, 30));
+    unknown_room.room_name = Some("Garden".to_string());
+    let entries = vec![
+        unknown_patient,
+        unknown_room,
+        entry("t", at(9, 10), at(9, 40)),
+        entry("e", at(14, 30), at(15, 0)),
+        entry("d", at(16, 0), at(16, 30)),
+        entry("d", at(16, 0), at(16, 30)),
+    ];
+    let report = AppointmentService::validate_appointment_import(&db, &entries)
+        .await
+        .unwrap();
+
+    assert_eq!(report.entries[0].errors, vec!["Patient not found"]);
+    assert_eq!(report.entries[1].errors, vec!["Room not found"]);
+    assert_eq!(report.entries[2].errors, vec!["Start time must be on a 15-minute interval"]);
+    assert_eq!(report.entries[3].conflicting_appointments, vec![existing.id]);
+    assert!(report.entries[4].is_valid());
+    assert_eq!(report.entries[5].duplicate_of, Some(4));
+    assert!(
+        report.entries[5].conflicting_entries.is_empty(),
+        "a duplicate is not also reported as overlapping the entry it repeats"
+    );
+    assert_eq!((report.valid_count, report.invalid_count), (1, 5));
+}
+
+#[tokio::test]
+async fn overlaps_only_conflict_once_they_fill_the_room() {
+    let db = create_test_db_with_migrations().await;
+    let patient = seed_patient(&db, "Rex", "900000000000001").await;
+    let room = seed_room_with(&db, "Surgery", Some(2), None).await;
+    let existing = AppointmentService::create_appointment(
+        &db,
+        CreateAppointmentInput {
+            patient_id: patient,
+            title: "Booked".to_string(),
+            description: None,
+            start_time: at(14, 0),
+            end_time: at(15, 0),
+            room_id: Some(room),
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap();
+
+    let entries = vec![
+        // Two at once fit
+        entry("a", at(9, 0), at(10, 0)),
+        entry("b", at(9, 30), at(10, 30)),
+        // Shares the room with the booked appointment
+        entry("c", at(14, 45), at(15, 15)),
+        // Together with the booked appointment these two are three at once
+        entry("d", at(14, 0), at(14, 30)),
+        entry("e", at(14, 15), at(14, 45)),
+    ];
+    let report = AppointmentService::validate_appointment_import(&db, &entries)
+        .await
+        .unwrap();
+
+    assert!(report.entries[0].is_valid());
+    assert!(report.entries[1].is_valid());
+    assert!(report.entries[2].is_valid(), "{:?}", report.entries[2]);
+    assert_eq!(report.entries[3].conflicting_appointments, vec![existing.id]);
+    assert_eq!(report.entries[3].conflicting_entries, vec![4]);
+    assert_eq!(report.entries[4].conflicting_entries, vec![3]);
+    assert_eq!((report.valid_count, report.invalid_count), (3, 2));
+}
+
+#[tokio::test]
+async fn entries_keep_the_room_buffer_free() {
+    let db = create_test_db_with_migrations().await;
+    let patient = seed_patient(&db, "Rex", "900000000000001").await;
+    let room = seed_room_with(&db, "Surgery", None, Some(15)).await;
+    let existing = AppointmentService::create_appointment(
+        &db,
+        CreateAppointmentInput {
+            patient_id: patient,
+            title: "Booked".to_string(),
+            description: None,
+            start_time: at(14, 0),
+            end_time: at(15, 0),
+            room_id: Some(room),
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap();
+
+    let entries = vec![
+        // Back to back: inside each other's buffer
+        entry("a", at(9, 0), at(10, 0)),
+        entry("b", at(10, 0), at(10, 30)),
+        // A buffer apart
+        entry("c", at(10, 45), at(11, 0)),
+        // Starts as the booked appointment ends
+        entry("d", at(15, 0), at(15, 30)),
+        entry("e", at(15, 45), at(16, 0)),
+    ];
+    let report = AppointmentService::validate_appointment_import(&db, &entries)
+        .await
+        .unwrap();
+
+    assert_eq!(report.entries[0].conflicting_entries, vec![1]);
+    assert_eq!(report.entries[1].conflicting_entries, vec![0]);
+    assert!(report.entries[2].is_valid(), "{:?}", report.entries[2]);
+    assert_eq!(report.entries[3].conflicting_appointments, vec![existing.id]);
+    assert!(report.entries[4].is_valid(), "{:?}", report.entries[4]);
+}
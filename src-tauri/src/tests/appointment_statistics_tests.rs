@@ -0,0 +1,142 @@
+//! Tests for `AppointmentService::get_appointment_statistics`.
+//!
+//! Weekdays and hours are read in UTC so the results don't depend on the
+//! machine's timezone.
+
+use crate::models::AppointmentStatsGroupBy;
+use crate::services::appointments::AppointmentService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient};
+use chrono::{DateTime, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 4, day, hour, minute, 0).unwrap()
+}
+
+async fn insert_appointment(
+    db: &DatabaseConnection,
+    patient_id: i64,
+    start: DateTime<Utc>,
+    minutes: i64,
+    status: &str,
+    deleted: bool,
+) {
+    let end = start + chrono::Duration::minutes(minutes);
+    let deleted_at = deleted.then(|| end.to_rfc3339());
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO appointments (patient_id, title, start_time, end_time, status, deleted_at, created_by) \
+         VALUES (?, 'Visit', ?, ?, ?, ?, 'test')",
+        [
+            patient_id.into(),
+            start.to_rfc3339().into(),
+            end.to_rfc3339().into(),
+            status.into(),
+            deleted_at.into(),
+        ],
+    ))
+    .await
+    .unwrap();
+}
+
+/// 2030-04-08 is a Monday. Two completed morning visits on Monday, one
+/// scheduled and two cancelled afternoon visits, a deleted visit and one
+/// outside the period.
+async fn seed_week(db: &DatabaseConnection) {
+    let patient = create_test_patient(db, "Rex", 1, None).await;
+    insert_appointment(db, patient, at(8, 9, 0), 30, "completed", false).await;
+    insert_appointment(db, patient, at(8, 9, 30), 30, "completed", false).await;
+    insert_appointment(db, patient, at(9, 14, 0), 60, "scheduled", false).await;
+    insert_appointment(db, patient, at(9, 14, 0), 30, "cancelled", false).await;
+    insert_appointment(db, patient, at(10, 14, 0), 30, "cancelled", false).await;
+    insert_appointment(db, patient, at(10, 14, 0), 30, "completed", true).await;
+    insert_appointment(db, patient, at(20, 14, 0), 30, "completed", false).await;
+}
+
+#[tokio::test]
+async fn statistics_count_statuses_and_find_the_busiest_hour() {
+    let db = create_test_db_with_migrations().await;
+    seed_week(&db).await;
+
+    let stats = AppointmentService::get_appointment_statistics(
+        &db,
+        at(8, 0, 0),
+        at(15, 0, 0),
+        AppointmentStatsGroupBy::Status,
+        &Utc,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(stats.total, 5);
+    assert_eq!(stats.completed, 2);
+    assert_eq!(stats.cancelled, 2);
+    // 30, 30 and 60 minutes; cancelled visits don't count
+    assert_eq!(stats.average_duration_minutes, Some(40.0));
+    // Three afternoon bookings at 14:00, but two were cancelled
+    assert_eq!(stats.busiest_hour, Some(9));
+    assert_eq!(stats.busiest_day_of_week, Some(1));
+
+    let by_status: Vec<(Option<String>, i64)> =
+        stats.groups.iter().map(|g| (g.key.clone(), g.total)).collect();
+    assert_eq!(
+        by_status,
+        vec![
+            (Some("cancelled".to_string()), 2),
+            (Some("completed".to_string()), 2),
+            (Some("scheduled".to_string()), 1),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn statistics_group_by_weekday_and_species() {
+    let db = create_test_db_with_migrations().await;
+    seed_week(&db).await;
+
+    let by_day = AppointmentService::get_appointment_statistics(
+        &db,
+        at(8, 0, 0),
+        at(15, 0, 0),
+        AppointmentStatsGroupBy::DayOfWeek,
+        &Utc,
+    )
+    .await
+    .unwrap();
+    let days: Vec<(Option<String>, i64, i64)> =
+        by_day.groups.iter().map(|g| (g.key.clone(), g.total, g.cancelled)).collect();
+    assert_eq!(
+        days,
+        vec![
+            (Some("1".to_string()), 2, 0),
+            (Some("2".to_string()), 2, 1),
+            (Some("3".to_string()), 1, 1),
+        ]
+    );
+    assert_eq!(by_day.groups[2].average_duration_minutes, None);
+
+    let by_species = AppointmentService::get_appointment_statistics(
+        &db,
+        at(8, 0, 0),
+        at(15, 0, 0),
+        AppointmentStatsGroupBy::Species,
+        &Utc,
+    )
+    .await
+    .unwrap();
+    assert_eq!(by_species.groups.len(), 1);
+    assert_eq!(by_species.groups[0].key.as_deref(), Some("1"));
+    assert_eq!(by_species.groups[0].label.as_deref(), Some("Dog"));
+    assert_eq!(by_species.groups[0].total, 5);
+
+    let err = AppointmentService::get_appointment_statistics(
+        &db,
+        at(15, 0, 0),
+        at(8, 0, 0),
+        AppointmentStatsGroupBy::Room,
+        &Utc,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("must end after"));
+}
@@ -0,0 +1,258 @@
+//! Per-appointment Google Calendar sync status and retries (`SyncService`).
+
+use crate::models::dto::CreatePatientDto;
+use crate::models::{CreateAppointmentInput, SyncAction, SyncStatus};
+use crate::services::appointments::AppointmentService;
+use crate::services::patient::PatientService;
+use crate::services::sync::{AppointmentPusher, SyncService, MAX_SYNC_RETRIES};
+use crate::test_utils::create_test_db_with_migrations;
+use chrono::{TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use std::sync::Arc;
+
+/// Stands in for Google: either succeeds with a fixed event id or fails.
+struct FakePusher {
+    result: Result<Option<String>, String>,
+}
+
+impl AppointmentPusher for FakePusher {
+    async fn push(
+        &self,
+        _db: &Arc<DatabaseConnection>,
+        _appointment_id: i64,
+        _action: SyncAction,
+    ) -> Result<Option<String>, String> {
+        self.result.clone()
+    }
+}
+
+async fn fetch_log_row(db: &DatabaseConnection, log_id: i64) -> (String, Option<String>, Option<String>, i64) {
+    let row = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT sync_status, external_id, error_message, retry_count FROM appointment_sync_log WHERE id = ?",
+        [log_id.into()],
+    )).await.unwrap().unwrap();
+    (
+        row.try_get("", "sync_status").unwrap(),
+        row.try_get("", "external_id").unwrap(),
+        row.try_get("", "error_message").unwrap(),
+        row.try_get("", "retry_count").unwrap(),
+    )
+}
+
+async fn seed_appointment(db: &DatabaseConnection) -> i64 {
+    let patient = PatientService::create(db, CreatePatientDto {
+        name: Some("Rex".to_string()), species_id: Some(1),
+        breed_id: None, gender: None, date_of_birth: None,
+        color: None, weight: None, microchip_id: None,
+        medical_notes: None, household_id: None,
+    }).await.unwrap();
+    AppointmentService::create_appointment(db, CreateAppointmentInput {
+        patient_id: patient.id,
+        title: "Checkup".to_string(),
+        description: None,
+        start_time: Utc.with_ymd_and_hms(2026, 5, 4, 10, 0, 0).unwrap(),
+        end_time: Utc.with_ymd_and_hms(2026, 5, 4, 10, 30, 0).unwrap(),
+        room_id: None,
+    }, "test".to_string()).await.unwrap().id
+}
+
+#[tokio::test]
+async fn synced_appointment_reports_event_and_timestamp() {
+    let db = create_test_db_with_migrations().await;
+    let appointment_id = seed_appointment(&db).await;
+    let synced_at = Utc.with_ymd_and_hms(2026, 5, 1, 8, 15, 0).unwrap();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO calendar_event_mappings (appointment_id, event_id, calendar_id, last_synced_at) VALUES (?, ?, ?, ?)",
+        [appointment_id.into(), "evt_123".into(), "primary".into(), synced_at.to_rfc3339().into()],
+    )).await.unwrap();
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Update, SyncStatus::Failed, None, Some("503 Backend Error".to_string()),
+    ).await.unwrap();
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Update, SyncStatus::Success, Some("evt_123".to_string()), None,
+    ).await.unwrap();
+
+    let status = SyncService::get_appointment_sync_status(&db, appointment_id).await.unwrap();
+
+    assert!(status.synced);
+    assert_eq!(status.event_id.as_deref(), Some("evt_123"));
+    assert_eq!(status.calendar_id.as_deref(), Some("primary"));
+    assert_eq!(status.last_synced_at, Some(synced_at));
+    assert_eq!(status.last_error, None, "a later success clears the earlier failure");
+}
+
+#[tokio::test]
+async fn unsynced_appointment_reports_not_synced_with_last_error() {
+    let db = create_test_db_with_migrations().await;
+    let appointment_id = seed_appointment(&db).await;
+
+    let status = SyncService::get_appointment_sync_status(&db, appointment_id).await.unwrap();
+    assert!(!status.synced);
+    assert_eq!(status.event_id, None);
+    assert_eq!(status.last_synced_at, None);
+    assert_eq!(status.last_error, None);
+
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Create, SyncStatus::Failed, None, Some("401 Invalid Credentials".to_string()),
+    ).await.unwrap();
+
+    let status = SyncService::get_appointment_sync_status(&db, appointment_id).await.unwrap();
+    assert!(!status.synced);
+    assert_eq!(status.last_error.as_deref(), Some("401 Invalid Credentials"));
+}
+
+#[tokio::test]
+async fn sync_log_lists_attempts_in_order_with_the_failure_message() {
+    let db = create_test_db_with_migrations().await;
+    let appointment_id = seed_appointment(&db).await;
+    let other = seed_appointment(&db).await;
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Create, SyncStatus::Success, Some("evt_1".to_string()), None,
+    ).await.unwrap();
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Update, SyncStatus::Failed, Some("evt_1".to_string()),
+        Some("403 Rate Limit Exceeded".to_string()),
+    ).await.unwrap();
+    SyncService::record_appointment_sync(
+        &db, other, SyncAction::Create, SyncStatus::Failed, None, Some("unrelated".to_string()),
+    ).await.unwrap();
+
+    let log = SyncService::get_appointment_sync_log(&db, appointment_id).await.unwrap();
+
+    assert_eq!(log.len(), 2);
+    assert!(log[0].is_success());
+    assert_eq!(log[0].external_id.as_deref(), Some("evt_1"));
+    assert!(matches!(log[1].sync_action, SyncAction::Update));
+    assert!(log[1].is_failed());
+    assert_eq!(log[1].error_message.as_deref(), Some("403 Rate Limit Exceeded"));
+    assert_eq!(log[1].retry_count, 0);
+    assert!(log[0].synced_at <= log[1].synced_at);
+
+    let err = SyncService::get_appointment_sync_log(&db, 424_242).await.unwrap_err();
+    assert_eq!(err, "Appointment not found");
+}
+
+#[tokio::test]
+async fn sync_status_for_missing_appointment_is_an_error() {
+    let db = create_test_db_with_migrations().await;
+    let err = SyncService::get_appointment_sync_status(&db, 424_242).await.unwrap_err();
+    assert_eq!(err, "Appointment not found");
+}
+
+#[tokio::test]
+async fn retried_failed_create_becomes_success() {
+    let test_db = create_test_db_with_migrations().await;
+    let db = Arc::new(test_db.db.clone());
+    let appointment_id = seed_appointment(&db).await;
+    let log_id = SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Create, SyncStatus::Failed, None, Some("503 Backend Error".to_string()),
+    ).await.unwrap();
+
+    let pusher = FakePusher { result: Ok(Some("evt_retry".to_string())) };
+    let summary = SyncService::retry_failed_syncs(&db, &pusher, 10).await.unwrap();
+
+    assert_eq!((summary.retried, summary.succeeded, summary.failed), (1, 1, 0));
+    let (status, external_id, error, retries) = fetch_log_row(&db, log_id).await;
+    assert_eq!(status, "success");
+    assert_eq!(external_id.as_deref(), Some("evt_retry"));
+    assert_eq!(error, None);
+    assert_eq!(retries, 1);
+
+    let again = SyncService::retry_failed_syncs(&db, &pusher, 10).await.unwrap();
+    assert_eq!(again.retried, 0, "a fixed row is not retried again");
+}
+
+#[tokio::test]
+async fn failed_retry_backs_off_and_stops_at_the_cap() {
+    let test_db = create_test_db_with_migrations().await;
+    let db = Arc::new(test_db.db.clone());
+    let appointment_id = seed_appointment(&db).await;
+    let log_id = SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Update, SyncStatus::Failed, None, Some("503 Backend Error".to_string()),
+    ).await.unwrap();
+    let pusher = FakePusher { result: Err("429 Rate Limit Exceeded".to_string()) };
+
+    let summary = SyncService::retry_failed_syncs(&db, &pusher, 10).await.unwrap();
+    assert_eq!((summary.retried, summary.succeeded, summary.failed), (1, 0, 1));
+    let (status, _, error, retries) = fetch_log_row(&db, log_id).await;
+    assert_eq!(status, "failed");
+    assert_eq!(error.as_deref(), Some("429 Rate Limit Exceeded"));
+    assert_eq!(retries, 1);
+
+    let summary = SyncService::retry_failed_syncs(&db, &pusher, 10).await.unwrap();
+    assert_eq!(summary.retried, 0, "backoff holds off the next attempt");
+
+    // Exhaust the remaining attempts with the backoff already elapsed
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE appointment_sync_log SET retry_count = ?, next_retry_at = NULL WHERE id = ?",
+        [MAX_SYNC_RETRIES.into(), log_id.into()],
+    )).await.unwrap();
+    let summary = SyncService::retry_failed_syncs(&db, &pusher, 10).await.unwrap();
+    assert_eq!(summary.retried, 0, "rows at the cap are left alone");
+}
+
+#[tokio::test]
+async fn superseded_failures_are_not_retried() {
+    let test_db = create_test_db_with_migrations().await;
+    let db = Arc::new(test_db.db.clone());
+    let appointment_id = seed_appointment(&db).await;
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Create, SyncStatus::Failed, None, Some("503 Backend Error".to_string()),
+    ).await.unwrap();
+    SyncService::record_appointment_sync(
+        &db, appointment_id, SyncAction::Update, SyncStatus::Success, Some("evt_123".to_string()), None,
+    ).await.unwrap();
+
+    let pusher = FakePusher { result: Ok(Some("evt_dup".to_string())) };
+    let summary = SyncService::retry_failed_syncs(&db, &pusher, 10).await.unwrap();
+
+    assert_eq!(summary.retried, 0);
+}
+
+async fn map_event(db: &DatabaseConnection, appointment_id: i64, event_id: &str, synced_at: chrono::DateTime<Utc>) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO calendar_event_mappings (appointment_id, event_id, calendar_id, last_synced_at) VALUES (?, ?, ?, ?)",
+        [appointment_id.into(), event_id.into(), "primary".into(), synced_at.to_rfc3339().into()],
+    )).await.unwrap();
+}
+
+#[tokio::test]
+async fn pending_sync_lists_edited_new_and_deleted_appointments() {
+    let db = create_test_db_with_migrations().await;
+    let fresh = seed_appointment(&db).await;
+    let edited = seed_appointment(&db).await;
+    let unsynced = seed_appointment(&db).await;
+    let deleted = seed_appointment(&db).await;
+
+    map_event(&db, fresh, "evt_fresh", Utc::now() + chrono::Duration::minutes(5)).await;
+    map_event(&db, edited, "evt_edited", Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap()).await;
+    map_event(&db, deleted, "evt_deleted", Utc::now() + chrono::Duration::minutes(5)).await;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE appointments SET deleted_at = ? WHERE id = ?",
+        [Utc::now().to_rfc3339().into(), deleted.into()],
+    )).await.unwrap();
+
+    // Sync is off in a fresh database
+    assert!(SyncService::get_appointments_pending_sync(&db).await.unwrap().is_empty());
+
+    db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "UPDATE google_calendar_settings SET sync_enabled = 1 WHERE user_id = 'default'".to_string(),
+    )).await.unwrap();
+
+    let pending = SyncService::get_appointments_pending_sync(&db).await.unwrap();
+    let action_of = |id: i64| pending.iter().find(|p| p.appointment_id == id).map(|p| p.action.to_string());
+
+    assert_eq!(action_of(fresh), None, "a freshly synced appointment is not dirty");
+    assert_eq!(action_of(edited).as_deref(), Some("update"));
+    assert_eq!(action_of(unsynced).as_deref(), Some("create"));
+    assert_eq!(action_of(deleted).as_deref(), Some("delete"));
+    let edited_entry = pending.iter().find(|p| p.appointment_id == edited).unwrap();
+    assert_eq!(edited_entry.event_id.as_deref(), Some("evt_edited"));
+    assert_eq!(pending.len(), 3);
+}
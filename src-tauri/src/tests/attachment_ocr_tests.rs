@@ -0,0 +1,87 @@
+//! Tests for the OCR text index behind `search_attachment_text`.
+//!
+//! Most tests pass the OCR output to `record_ocr_outcome` the way the
+//! background task does after extraction. `tesseract_reads_the_lab_report_fixture`
+//! runs Tesseract on a fixture image and is skipped when it isn't installed.
+
+use crate::services::attachment_ocr::AttachmentOcrService;
+use crate::test_utils::create_test_db_with_migrations;
+use sea_orm::ConnectionTrait;
+
+const LAB_SCAN_TEXT: &str = "IDEXX Reference Laboratories\nCreatinine 2.4 mg/dL HIGH\nUrea 15 mmol/L";
+
+async fn seed_attachments(db: &sea_orm::DatabaseConnection) {
+    for sql in [
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, is_archived, version) \
+         VALUES (1, 1, 'test_result', 'Bloods', '', 0, 1)",
+        "INSERT INTO medical_attachments (id, medical_record_id, file_id, original_name, mime_type) \
+         VALUES (1, 1, 'scan-1', 'lab.png', 'image/png')",
+        "INSERT INTO medical_attachments (id, medical_record_id, file_id, original_name, mime_type) \
+         VALUES (2, 1, 'scan-2', 'blurry.png', 'image/png')",
+    ] {
+        db.execute_unprepared(sql).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn ocr_text_is_searchable_and_failures_are_not() {
+    let db = create_test_db_with_migrations().await;
+    seed_attachments(&db).await;
+
+    let indexed = AttachmentOcrService::record_ocr_outcome(&db, 1, Ok(LAB_SCAN_TEXT.to_string())).await;
+    assert!(indexed.indexed);
+    assert_eq!(indexed.error, None);
+
+    let failed = AttachmentOcrService::record_ocr_outcome(&db, 2, Err("Tesseract exited with 1".to_string())).await;
+    assert!(!failed.indexed);
+    assert_eq!(failed.error.as_deref(), Some("Tesseract exited with 1"));
+
+    let matches = AttachmentOcrService::search(&db, "creatinine", 20).await.unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].attachment_id, matches[0].medical_record_id), (1, 1));
+    assert_eq!(matches[0].original_name, "lab.png");
+    assert!(matches[0].snippet.contains("[Creatinine]"), "snippet: {}", matches[0].snippet);
+
+    assert!(AttachmentOcrService::search(&db, "glucose", 20).await.unwrap().is_empty());
+    assert!(AttachmentOcrService::search(&db, "  ", 20).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn deleting_an_attachment_removes_its_text() {
+    let db = create_test_db_with_migrations().await;
+    seed_attachments(&db).await;
+    AttachmentOcrService::record_ocr_outcome(&db, 1, Ok(LAB_SCAN_TEXT.to_string())).await;
+
+    db.execute_unprepared("DELETE FROM medical_attachments WHERE id = 1").await.unwrap();
+    assert!(AttachmentOcrService::search(&db, "creatinine", 20).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn tesseract_reads_the_lab_report_fixture() {
+    let tesseract = std::env::var("TESSERACT_PATH").unwrap_or_else(|_| "tesseract".to_string());
+    if std::process::Command::new(&tesseract).arg("--version").output().is_err() {
+        eprintln!("SKIP: no `{}` on PATH (set TESSERACT_PATH to point at it)", tesseract);
+        return;
+    }
+
+    let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ocr_lab_report.png");
+    let text = AttachmentOcrService::ocr_image_file(&fixture).expect("Tesseract failed on the fixture");
+    assert!(text.contains("Creatinine"), "OCR output: {}", text);
+
+    let db = create_test_db_with_migrations().await;
+    seed_attachments(&db).await;
+    let outcome = AttachmentOcrService::record_ocr_outcome(&db, 1, Ok(text)).await;
+    assert!(outcome.indexed);
+
+    let matches = AttachmentOcrService::search(&db, "creatinine", 20).await.unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].attachment_id, 1);
+}
+
+#[test]
+fn only_images_and_pdfs_are_ocr_candidates() {
+    assert!(AttachmentOcrService::is_ocr_candidate("image/jpeg"));
+    assert!(AttachmentOcrService::is_ocr_candidate("application/pdf"));
+    assert!(!AttachmentOcrService::is_ocr_candidate("application/xml"));
+}
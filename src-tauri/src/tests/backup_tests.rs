@@ -52,12 +52,27 @@ fn save_then_load_roundtrips_config() {
         directory: Some("/tmp/foo".to_string()),
         last_backup_at: Some("2026-05-18T10:00:00Z".to_string()),
         last_error: Some("boom".to_string()),
+        auto_backup_enabled: true,
+        auto_backup_interval_hours: Some(6),
+        auto_backup_keep: Some(3),
     };
     BackupService::save_config(dir.path(), &cfg).unwrap();
     let loaded = BackupService::load_config(dir.path());
     assert_eq!(loaded.directory, cfg.directory);
     assert_eq!(loaded.last_backup_at, cfg.last_backup_at);
     assert_eq!(loaded.last_error, cfg.last_error);
+    assert!(loaded.auto_backup_enabled);
+    assert_eq!(loaded.auto_backup_interval_hours, Some(6));
+    assert_eq!(loaded.auto_backup_keep, Some(3));
+}
+
+#[test]
+fn load_config_without_auto_fields_defaults_to_disabled() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("backup_config.json"), r#"{"directory":"/tmp/foo"}"#).unwrap();
+    let cfg = BackupService::load_config(dir.path());
+    assert_eq!(cfg.directory.as_deref(), Some("/tmp/foo"));
+    assert!(!cfg.auto_backup_enabled);
 }
 
 #[test]
@@ -315,3 +330,128 @@ async fn run_backup_prune_ignores_non_db_files() {
     assert!(db_dir.join("README.txt").exists());
     assert!(db_dir.join("notes.md").exists());
 }
+
+// ---------------------------------------------------------------------------
+// automatic backups
+// ---------------------------------------------------------------------------
+
+fn auto_files(dest: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dest.join("auto"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn run_auto_backup_is_noop_when_disabled() {
+    let fx = setup().await;
+    BackupService::set_directory(fx.app_dir.path(), fx.dest_dir.path().to_str().unwrap()).unwrap();
+
+    let result = BackupService::run_auto_backup(fx.app_dir.path(), &fx.test_db).await.unwrap();
+    assert!(result.is_none());
+    assert!(!fx.dest_dir.path().join("auto").exists());
+}
+
+#[test]
+fn set_auto_backup_requires_directory() {
+    let app_dir = tempfile::tempdir().unwrap();
+    let err = BackupService::set_auto_backup(app_dir.path(), true, None, None).unwrap_err();
+    assert!(err.contains("No backup directory"), "got: {}", err);
+}
+
+#[test]
+fn set_auto_backup_rejects_zero_keep() {
+    let app_dir = tempfile::tempdir().unwrap();
+    let dest = tempfile::tempdir().unwrap();
+    BackupService::set_directory(app_dir.path(), dest.path().to_str().unwrap()).unwrap();
+    assert!(BackupService::set_auto_backup(app_dir.path(), true, None, Some(0)).is_err());
+}
+
+#[tokio::test]
+async fn run_auto_backup_records_history() {
+    let fx = setup().await;
+    BackupService::set_directory(fx.app_dir.path(), fx.dest_dir.path().to_str().unwrap()).unwrap();
+    BackupService::set_auto_backup(fx.app_dir.path(), true, None, Some(5)).unwrap();
+
+    let entry = BackupService::run_auto_backup(fx.app_dir.path(), &fx.test_db)
+        .await
+        .unwrap()
+        .expect("auto backup should run");
+    assert!(Path::new(&entry.file_path).is_file());
+    assert!(entry.file_size > 0);
+
+    let history = BackupService::get_history(&fx.test_db).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, entry.id);
+}
+
+#[tokio::test]
+async fn run_auto_backup_rotation_keeps_exactly_n() {
+    let fx = setup().await;
+    BackupService::set_directory(fx.app_dir.path(), fx.dest_dir.path().to_str().unwrap()).unwrap();
+    BackupService::set_auto_backup(fx.app_dir.path(), true, None, Some(3)).unwrap();
+
+    let mut written = Vec::new();
+    for _ in 0..5 {
+        let entry = BackupService::run_auto_backup(fx.app_dir.path(), &fx.test_db)
+            .await
+            .unwrap()
+            .unwrap();
+        written.push(entry);
+        // File names carry millisecond timestamps; keep them distinct.
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let files = auto_files(fx.dest_dir.path());
+    assert_eq!(files.len(), 3, "rotation should keep exactly 3, got {:?}", files);
+
+    // The newest three survive, in both the folder and the history table
+    let history = BackupService::get_history(&fx.test_db).await.unwrap();
+    assert_eq!(history.len(), 3);
+    let kept: Vec<i64> = history.iter().map(|h| h.id).collect();
+    let newest: Vec<i64> = written.iter().rev().take(3).map(|e| e.id).collect();
+    assert_eq!(kept, newest);
+}
+
+#[tokio::test]
+async fn stage_and_apply_restore_swaps_database_file() {
+    let fx = setup().await;
+    BackupService::set_directory(fx.app_dir.path(), fx.dest_dir.path().to_str().unwrap()).unwrap();
+    BackupService::set_auto_backup(fx.app_dir.path(), true, None, None).unwrap();
+    let entry = BackupService::run_auto_backup(fx.app_dir.path(), &fx.test_db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    write(&fx.app_dir.path().join("vet_clinic.db"), "current");
+    write(&fx.app_dir.path().join("vet_clinic.db-wal"), "uncheckpointed");
+    write(&fx.app_dir.path().join("vet_clinic.db-shm"), "shm");
+    BackupService::stage_restore(fx.app_dir.path(), &fx.test_db, entry.id).await.unwrap();
+    assert!(BackupService::apply_pending_restore(fx.app_dir.path()).unwrap());
+
+    let restored = fs::read(fx.app_dir.path().join("vet_clinic.db")).unwrap();
+    assert_eq!(restored, fs::read(&entry.file_path).unwrap());
+    assert_eq!(
+        fs::read_to_string(fx.app_dir.path().join("vet_clinic.db.pre-restore")).unwrap(),
+        "current"
+    );
+    // The old WAL stays with the safety copy instead of being dropped or
+    // replayed onto the restored database
+    assert!(!fx.app_dir.path().join("vet_clinic.db-wal").exists());
+    assert!(!fx.app_dir.path().join("vet_clinic.db-shm").exists());
+    assert_eq!(
+        fs::read_to_string(fx.app_dir.path().join("vet_clinic.db.pre-restore-wal")).unwrap(),
+        "uncheckpointed"
+    );
+    // Nothing left staged
+    assert!(!BackupService::apply_pending_restore(fx.app_dir.path()).unwrap());
+}
+
+#[tokio::test]
+async fn stage_restore_unknown_id_fails() {
+    let fx = setup().await;
+    let err = BackupService::stage_restore(fx.app_dir.path(), &fx.test_db, 999).await.unwrap_err();
+    assert!(err.contains("not found"), "got: {}", err);
+}
@@ -0,0 +1,181 @@
+//! Pulling Google Calendar changes (`SyncScheduler::pull_changes`) with a
+//! stored sync token, and the full resync when the token has expired.
+
+use crate::models::dto::CreatePatientDto;
+use crate::models::CreateAppointmentInput;
+use crate::services::appointments::AppointmentService;
+use crate::services::patient::PatientService;
+use crate::services::sync_scheduler::{
+    CalendarEventPage, CalendarEventSource, ChangedEvent, EventListError, SyncScheduler,
+};
+use crate::test_utils::create_test_db_with_migrations;
+use chrono::{Duration, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const CALENDAR: &str = "clinic@example.com";
+
+fn event(id: &str, status: &str) -> ChangedEvent {
+    ChangedEvent { id: id.to_string(), status: status.to_string() }
+}
+
+/// Stands in for Google. A full listing spans two pages; each known sync
+/// token returns the changes made since it was issued.
+#[derive(Default)]
+struct FakeCalendar {
+    full_listing: Vec<(&'static str, &'static str)>,
+    changes: HashMap<&'static str, Vec<(&'static str, &'static str)>>,
+    expired: Vec<&'static str>,
+    /// (sync_token, page_token) of every request
+    calls: Mutex<Vec<(Option<String>, Option<String>)>>,
+}
+
+impl CalendarEventSource for FakeCalendar {
+    async fn list_events(
+        &self,
+        _calendar_id: &str,
+        sync_token: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<CalendarEventPage, EventListError> {
+        self.calls.lock().unwrap().push((sync_token.map(String::from), page_token.map(String::from)));
+        let to_events = |items: &[(&str, &str)]| items.iter().map(|(id, status)| event(id, status)).collect();
+
+        match (sync_token, page_token) {
+            (Some(token), _) if self.expired.iter().any(|expired| *expired == token) => Err(EventListError::SyncTokenExpired),
+            (Some(token), _) => Ok(CalendarEventPage {
+                events: to_events(self.changes.get(token).map(Vec::as_slice).unwrap_or_default()),
+                next_page_token: None,
+                next_sync_token: Some(format!("{}+", token)),
+            }),
+            (None, None) => Ok(CalendarEventPage {
+                events: to_events(&self.full_listing[..1]),
+                next_page_token: Some("page-2".to_string()),
+                next_sync_token: None,
+            }),
+            (None, Some(_)) => Ok(CalendarEventPage {
+                events: to_events(&self.full_listing[1..]),
+                next_page_token: None,
+                next_sync_token: Some("full".to_string()),
+            }),
+        }
+    }
+}
+
+/// Three mapped appointments, for events evt-1..evt-3.
+async fn seed_mapped_appointments(db: &DatabaseConnection) -> Vec<i64> {
+    let patient = PatientService::create(db, CreatePatientDto {
+        name: Some("Rex".to_string()), species_id: Some(1),
+        breed_id: None, gender: None, date_of_birth: None,
+        color: None, weight: None, microchip_id: None,
+        medical_notes: None, household_id: None,
+    }).await.unwrap();
+
+    let start = Utc.with_ymd_and_hms(2026, 5, 4, 10, 0, 0).unwrap();
+    let mut ids = Vec::new();
+    for n in 1..=3 {
+        let appointment = AppointmentService::create_appointment(db, CreateAppointmentInput {
+            patient_id: patient.id,
+            title: format!("Visit {}", n),
+            description: None,
+            start_time: start + Duration::hours(n),
+            end_time: start + Duration::hours(n) + Duration::minutes(30),
+            room_id: None,
+        }, "test".to_string()).await.unwrap();
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO calendar_event_mappings (appointment_id, event_id, calendar_id) VALUES (?, ?, ?)",
+            [appointment.id.into(), format!("evt-{}", n).into(), CALENDAR.into()],
+        )).await.unwrap();
+        ids.push(appointment.id);
+    }
+    ids
+}
+
+async fn statuses(db: &DatabaseConnection, ids: &[i64]) -> Vec<String> {
+    let mut out = Vec::new();
+    for id in ids {
+        let row = db.query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT status FROM appointments WHERE id = ?",
+            [(*id).into()],
+        )).await.unwrap().unwrap();
+        out.push(row.try_get("", "status").unwrap());
+    }
+    out
+}
+
+async fn sync_log(db: &DatabaseConnection, id: i64) -> (String, String, i64) {
+    let row = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT sync_type, status, items_synced FROM sync_logs WHERE id = ?",
+        [id.into()],
+    )).await.unwrap().unwrap();
+    (
+        row.try_get("", "sync_type").unwrap(),
+        row.try_get("", "status").unwrap(),
+        row.try_get("", "items_synced").unwrap(),
+    )
+}
+
+async fn stored_token(db: &DatabaseConnection) -> Option<String> {
+    db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT sync_token FROM calendar_sync_tokens WHERE calendar_id = ?",
+        [CALENDAR.into()],
+    )).await.unwrap().map(|row| row.try_get("", "sync_token").unwrap())
+}
+
+#[tokio::test]
+async fn incremental_pull_applies_only_changed_events() {
+    let db = create_test_db_with_migrations().await;
+    let ids = seed_mapped_appointments(&db).await;
+
+    let calendar = FakeCalendar {
+        full_listing: vec![("evt-1", "confirmed"), ("evt-2", "confirmed"), ("evt-3", "confirmed")],
+        changes: HashMap::from([("full", vec![("evt-2", "cancelled")])]),
+        ..Default::default()
+    };
+
+    // First run has no token: full listing across both pages
+    let first = SyncScheduler::pull_changes(&db, &calendar, CALENDAR).await.unwrap();
+    assert_eq!(sync_log(&db, first).await, ("initial".to_string(), "success".to_string(), 0));
+    assert_eq!(stored_token(&db).await.as_deref(), Some("full"));
+
+    // Second run sends the token and only sees the cancelled evt-2
+    let second = SyncScheduler::pull_changes(&db, &calendar, CALENDAR).await.unwrap();
+    assert_eq!(sync_log(&db, second).await, ("incremental".to_string(), "success".to_string(), 1));
+    assert_eq!(statuses(&db, &ids).await, vec!["scheduled", "cancelled", "scheduled"]);
+    assert_eq!(stored_token(&db).await.as_deref(), Some("full+"));
+
+    let calls = calendar.calls.lock().unwrap().clone();
+    assert_eq!(calls, vec![
+        (None, None),
+        (None, Some("page-2".to_string())),
+        (Some("full".to_string()), None),
+    ]);
+}
+
+#[tokio::test]
+async fn expired_token_falls_back_to_full_sync() {
+    let db = create_test_db_with_migrations().await;
+    let ids = seed_mapped_appointments(&db).await;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO calendar_sync_tokens (calendar_id, sync_token) VALUES (?, 'stale')",
+        [CALENDAR.into()],
+    )).await.unwrap();
+
+    let calendar = FakeCalendar {
+        full_listing: vec![("evt-1", "confirmed"), ("evt-3", "cancelled")],
+        expired: vec!["stale"],
+        ..Default::default()
+    };
+
+    let log_id = SyncScheduler::pull_changes(&db, &calendar, CALENDAR).await.unwrap();
+
+    assert_eq!(sync_log(&db, log_id).await, ("initial".to_string(), "success".to_string(), 1));
+    assert_eq!(statuses(&db, &ids).await, vec!["scheduled", "scheduled", "cancelled"]);
+    assert_eq!(stored_token(&db).await.as_deref(), Some("full"), "token refreshed from the full listing");
+    assert_eq!(calendar.calls.lock().unwrap().first(), Some(&(Some("stale".to_string()), None)));
+}
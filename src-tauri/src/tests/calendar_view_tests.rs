@@ -0,0 +1,107 @@
+//! Tests for `AppointmentService::get_calendar_view`.
+//!
+//! Days are bucketed in UTC here so the results don't depend on the
+//! machine's timezone; the command passes `Local`.
+
+use crate::models::{CalendarViewMode, CreateAppointmentInput};
+use crate::services::appointments::AppointmentService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+
+async fn seed_appointment(
+    db: &DatabaseConnection,
+    patient_id: i64,
+    title: &str,
+    start: DateTime<Utc>,
+    minutes: i64,
+) -> i64 {
+    AppointmentService::create_appointment(
+        db,
+        CreateAppointmentInput {
+            patient_id,
+            title: title.to_string(),
+            description: None,
+            start_time: start,
+            end_time: start + Duration::minutes(minutes),
+            room_id: None,
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 1, day, hour, minute, 0).unwrap()
+}
+
+#[tokio::test]
+async fn overlapping_appointments_get_distinct_lanes() {
+    let db = create_test_db_with_migrations().await;
+    let patient = create_test_patient(&db, "Rex", 1, None).await;
+
+    // 2030-01-16 is a Wednesday
+    let first = seed_appointment(&db, patient, "First", at(16, 10, 0), 60).await;
+    let second = seed_appointment(&db, patient, "Second", at(16, 10, 15), 30).await;
+    let third = seed_appointment(&db, patient, "Third", at(16, 10, 30), 60).await;
+    let later = seed_appointment(&db, patient, "Later", at(16, 14, 0), 30).await;
+    let other_day = seed_appointment(&db, patient, "Friday", at(18, 9, 0), 30).await;
+    let deleted = seed_appointment(&db, patient, "Deleted", at(16, 10, 0), 30).await;
+    AppointmentService::delete_appointment(&db, deleted).await.unwrap();
+
+    let anchor = NaiveDate::from_ymd_opt(2030, 1, 16).unwrap();
+    let view = AppointmentService::get_calendar_view(&db, CalendarViewMode::Week, anchor, &[], &Utc)
+        .await
+        .unwrap();
+
+    assert_eq!(view.start_date, NaiveDate::from_ymd_opt(2030, 1, 14).unwrap());
+    assert_eq!(view.end_date, NaiveDate::from_ymd_opt(2030, 1, 20).unwrap());
+    assert_eq!(view.days.len(), 7);
+
+    let wednesday = &view.days[2];
+    assert_eq!(wednesday.date, anchor);
+    let layout: Vec<(i64, u32, u32)> = wednesday
+        .entries
+        .iter()
+        .map(|e| (e.appointment.id, e.lane, e.lane_count))
+        .collect();
+    assert_eq!(
+        layout,
+        vec![(first, 0, 3), (second, 1, 3), (third, 2, 3), (later, 0, 1)],
+        "overlaps share a lane count; the soft-deleted appointment is left out"
+    );
+
+    let friday: Vec<i64> = view.days[4].entries.iter().map(|e| e.appointment.id).collect();
+    assert_eq!(friday, vec![other_day]);
+    assert!(view.days[0].entries.is_empty());
+}
+
+#[tokio::test]
+async fn calendar_view_ranges_and_room_filter() {
+    let db = create_test_db_with_migrations().await;
+    let patient = create_test_patient(&db, "Rex", 1, None).await;
+    seed_appointment(&db, patient, "Unassigned", at(31, 9, 0), 30).await;
+
+    let anchor = NaiveDate::from_ymd_opt(2030, 1, 16).unwrap();
+    let month = AppointmentService::get_calendar_view(&db, CalendarViewMode::Month, anchor, &[], &Utc)
+        .await
+        .unwrap();
+    assert_eq!(month.start_date, NaiveDate::from_ymd_opt(2030, 1, 1).unwrap());
+    assert_eq!(month.end_date, NaiveDate::from_ymd_opt(2030, 1, 31).unwrap());
+    assert_eq!(month.days.len(), 31);
+    assert_eq!(month.days[30].entries.len(), 1);
+
+    let day = AppointmentService::get_calendar_view(&db, CalendarViewMode::Day, anchor, &[], &Utc)
+        .await
+        .unwrap();
+    assert_eq!(day.days.len(), 1);
+    assert!(day.days[0].entries.is_empty());
+
+    // The appointment has no room, so filtering by any room hides it
+    let filtered = AppointmentService::get_calendar_view(&db, CalendarViewMode::Month, anchor, &[999], &Utc)
+        .await
+        .unwrap();
+    assert!(filtered.days.iter().all(|d| d.entries.is_empty()));
+}
@@ -0,0 +1,112 @@
+//! Exporting a clinic's configuration and loading it into another install.
+
+use crate::models::dto::CreatePatientDto;
+use crate::services::clinic_profile::{ClinicProfile, ClinicProfileService, ProfileMergeStrategy};
+use crate::services::patient::PatientService;
+use crate::test_utils::create_test_db_with_migrations;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+async fn exec(db: &DatabaseConnection, sql: &str) {
+    db.execute(Statement::from_string(DbBackend::Sqlite, sql.to_string()))
+        .await
+        .unwrap();
+}
+
+/// A clinic with one of everything a profile carries, plus a patient that
+/// must not travel with it.
+async fn configure_clinic(db: &DatabaseConnection) {
+    exec(db, "INSERT INTO currencies (code, name, symbol) VALUES ('CHF', 'Swiss Franc', 'Fr')").await;
+    exec(db, "INSERT INTO species (name, active, display_order, color) VALUES ('Ferret', 1, 20, '#aa5500')").await;
+    exec(db, "INSERT INTO breeds (name, species_id, active, display_order) VALUES ('Angora', (SELECT id FROM species WHERE name = 'Ferret'), 1, 1)").await;
+    exec(db, "INSERT INTO breeds (name, species_id, active, display_order) VALUES ('Beagle', 1, 1, 3)").await;
+    exec(db, "INSERT INTO rooms (name, description, capacity, is_active, color, display_order) VALUES ('Surgery', 'Theatre', 2, 1, '#ff0000', 1)").await;
+    exec(db, "INSERT INTO record_templates (record_type, title, description, price, currency_id) VALUES ('procedure', 'Spay', 'Routine spay', 120.5, (SELECT id FROM currencies WHERE code = 'CHF'))").await;
+    exec(db, "INSERT INTO line_item_templates (name, description, default_price, currency_id, display_order, is_active) VALUES ('Suture kit', NULL, 15, (SELECT id FROM currencies WHERE code = 'CHF'), 1, 1)").await;
+    exec(db, "UPDATE app_settings SET language = 'mk', currency_id = (SELECT id FROM currencies WHERE code = 'CHF'), \
+              default_room_id = (SELECT id FROM rooms WHERE name = 'Surgery'), practice_timezone = 'Europe/Skopje', \
+              auto_archive_after_days = 365 WHERE user_id = 'default'").await;
+    PatientService::create(db, CreatePatientDto {
+        name: Some("Rex".to_string()), species_id: Some(1),
+        breed_id: None, gender: None, date_of_birth: None,
+        color: None, weight: None, microchip_id: None,
+        medical_notes: None, household_id: None,
+    }).await.unwrap();
+}
+
+fn assert_same_configuration(left: &ClinicProfile, right: &ClinicProfile) {
+    assert_eq!(left.settings, right.settings);
+    assert_eq!(left.currencies, right.currencies);
+    assert_eq!(left.species, right.species);
+    assert_eq!(left.rooms, right.rooms);
+    assert_eq!(left.record_templates, right.record_templates);
+    assert_eq!(left.line_item_templates, right.line_item_templates);
+}
+
+#[tokio::test]
+async fn profile_round_trips_into_an_empty_clinic() {
+    let source = create_test_db_with_migrations().await;
+    configure_clinic(&source).await;
+    let profile = ClinicProfileService::export_profile(&source).await.unwrap();
+    assert!(profile.species.iter().any(|s| s.name == "Ferret" && s.breeds[0].name == "Angora"));
+    assert_eq!(profile.settings.as_ref().unwrap().default_room.as_deref(), Some("Surgery"));
+
+    let target = create_test_db_with_migrations().await;
+    let json = serde_json::to_string(&profile).unwrap();
+    let report = ClinicProfileService::import_profile(&target, &json, ProfileMergeStrategy::Merge)
+        .await
+        .unwrap();
+    assert_eq!(report.currencies.created, 1);
+    assert_eq!(report.breeds.created, 2);
+    assert_eq!(report.record_templates.created, 1);
+    assert!(report.settings_applied);
+
+    let imported = ClinicProfileService::export_profile(&target).await.unwrap();
+    assert_same_configuration(&profile, &imported);
+
+    let patients = target
+        .query_one(Statement::from_string(DbBackend::Sqlite, "SELECT COUNT(*) AS n FROM patients".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(patients.try_get::<i64>("", "n").unwrap(), 0, "patients must not be imported");
+
+    // Importing again only updates
+    let again = ClinicProfileService::import_profile(&target, &json, ProfileMergeStrategy::Merge)
+        .await
+        .unwrap();
+    assert_eq!(again.rooms.created, 0);
+    assert_eq!(again.record_templates.created, 0);
+}
+
+#[tokio::test]
+async fn replace_retires_configuration_missing_from_the_profile() {
+    let source = create_test_db_with_migrations().await;
+    configure_clinic(&source).await;
+    let json = serde_json::to_string(&ClinicProfileService::export_profile(&source).await.unwrap()).unwrap();
+
+    let target = create_test_db_with_migrations().await;
+    exec(&target, "INSERT INTO rooms (name, is_active) VALUES ('Old annex', 1)").await;
+    exec(&target, "INSERT INTO record_templates (record_type, title, description) VALUES ('note', 'Stale', 'x')").await;
+
+    let merged = ClinicProfileService::import_profile(&target, &json, ProfileMergeStrategy::Merge)
+        .await
+        .unwrap();
+    assert_eq!(merged.rooms.retired, 0);
+
+    let replaced = ClinicProfileService::import_profile(&target, &json, ProfileMergeStrategy::Replace)
+        .await
+        .unwrap();
+    assert_eq!(replaced.rooms.retired, 1);
+    assert_eq!(replaced.record_templates.retired, 1);
+
+    let profile = ClinicProfileService::export_profile(&target).await.unwrap();
+    let annex = profile.rooms.iter().find(|r| r.name == "Old annex").unwrap();
+    assert!(!annex.is_active, "rooms are deactivated, not deleted");
+    assert!(profile.record_templates.iter().all(|t| t.title != "Stale"));
+
+    let newer = json.replacen("\"formatVersion\":1", "\"formatVersion\":99", 1);
+    let err = ClinicProfileService::import_profile(&target, &newer, ProfileMergeStrategy::Merge)
+        .await
+        .unwrap_err();
+    assert!(err.contains("Unsupported"), "got: {}", err);
+}
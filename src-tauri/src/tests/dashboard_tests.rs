@@ -0,0 +1,239 @@
+//! Tests for `DashboardService::get_today_overview`.
+//!
+//! The day window is passed in explicitly so the tests don't depend on the
+//! wall clock; device listener state is passed as plain structs since the
+//! live status map is only populated by running serial listeners.
+
+use crate::models::device_integration::{ConnectionType, CreateDeviceIntegrationInput, DeviceType};
+use crate::models::vaccination::RecordVaccinationInput;
+use crate::models::CreateAppointmentInput;
+use crate::services::appointments::AppointmentService;
+use crate::services::dashboard::DashboardService;
+use crate::services::device_input::{ConnectionState, DeviceConnectionStatus};
+use crate::services::device_integration::DeviceIntegrationService;
+use crate::services::vaccination::VaccinationService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+fn day_start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 1, 15, 0, 0, 0).unwrap()
+}
+
+async fn seed_appointment(db: &DatabaseConnection, patient_id: i64, title: &str, start: DateTime<Utc>) -> i64 {
+    AppointmentService::create_appointment(
+        db,
+        CreateAppointmentInput {
+            patient_id,
+            title: title.to_string(),
+            description: None,
+            start_time: start,
+            end_time: start + Duration::minutes(30),
+            room_id: None,
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+async fn seed_vaccination(
+    db: &DatabaseConnection,
+    patient_id: i64,
+    vaccine_name: &str,
+    administered_at: DateTime<Utc>,
+    next_due_at: DateTime<Utc>,
+) -> i64 {
+    VaccinationService::record_vaccination(
+        db,
+        RecordVaccinationInput {
+            patient_id,
+            vaccine_name: vaccine_name.to_string(),
+            administered_at,
+            next_due_at: Some(next_due_at),
+            notes: None,
+        },
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+fn status(integration_id: i64, state: ConnectionState) -> DeviceConnectionStatus {
+    DeviceConnectionStatus {
+        integration_id,
+        port_name: "/dev/ttyUSB0".to_string(),
+        device_type: "healvet_hv_fia_3000".to_string(),
+        status: state,
+        last_connected: None,
+        last_error: Some("Port busy".to_string()),
+        retry_count: 3,
+        next_retry: None,
+    }
+}
+
+#[tokio::test]
+async fn overview_lists_todays_appointments_recent_patients_and_device_errors() {
+    let db = create_test_db_with_migrations().await;
+    let patient = create_test_patient(&db, "Rex", 1, None).await;
+    let today = seed_appointment(&db, patient, "Checkup", day_start() + Duration::hours(9)).await;
+    seed_appointment(&db, patient, "Tomorrow", day_start() + Duration::hours(33)).await;
+    seed_appointment(&db, patient, "Yesterday", day_start() - Duration::hours(15)).await;
+
+    let integration = DeviceIntegrationService::create(
+        &db,
+        CreateDeviceIntegrationInput {
+            name: "Healvet".to_string(),
+            device_type: DeviceType::HealvetHvFia3000,
+            connection_type: ConnectionType::SerialPort,
+            watch_directory: None,
+            file_pattern: None,
+            serial_port_name: Some("/dev/ttyUSB0".to_string()),
+            serial_baud_rate: Some(9600),
+            tcp_host: None,
+            tcp_port: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let statuses = vec![
+        status(integration.id, ConnectionState::Error),
+        // Unknown / deleted integrations aren't reported
+        status(9999, ConnectionState::Error),
+    ];
+    let overview = DashboardService::get_today_overview(
+        &db,
+        day_start(),
+        day_start() + Duration::days(1),
+        statuses,
+        10,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(overview.appointments.len(), 1);
+    assert_eq!(overview.appointments[0].id, today);
+    assert_eq!(overview.recent_patients.len(), 1);
+    assert_eq!(overview.recent_patients[0].id, patient);
+    assert_eq!(overview.devices_in_error.len(), 1);
+    assert_eq!(overview.devices_in_error[0].name, "Healvet");
+    assert_eq!(overview.devices_in_error[0].last_error.as_deref(), Some("Port busy"));
+}
+
+#[tokio::test]
+async fn overview_skips_inactive_deleted_and_healthy_entries() {
+    let db = create_test_db_with_migrations().await;
+    let active = create_test_patient(&db, "Active", 1, None).await;
+    let inactive = create_test_patient(&db, "Inactive", 1, None).await;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE patients SET is_active = 0 WHERE id = ?",
+        [inactive.into()],
+    ))
+    .await
+    .unwrap();
+
+    let deleted = seed_appointment(&db, active, "Deleted", day_start() + Duration::hours(10)).await;
+    AppointmentService::delete_appointment(&db, deleted).await.unwrap();
+
+    let overview = DashboardService::get_today_overview(
+        &db,
+        day_start(),
+        day_start() + Duration::days(1),
+        vec![status(1, ConnectionState::Connected)],
+        10,
+    )
+    .await
+    .unwrap();
+
+    assert!(overview.appointments.is_empty(), "soft-deleted appointment excluded");
+    let ids: Vec<i64> = overview.recent_patients.iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![active], "inactive patient excluded");
+    assert!(overview.devices_in_error.is_empty());
+}
+
+#[tokio::test]
+async fn overview_lists_are_bounded() {
+    let db = create_test_db_with_migrations().await;
+    for i in 0..5 {
+        let p = create_test_patient(&db, &format!("Pet {}", i), 1, None).await;
+        seed_appointment(&db, p, "Visit", day_start() + Duration::hours(8 + i)).await;
+    }
+
+    let overview = DashboardService::get_today_overview(
+        &db,
+        day_start(),
+        day_start() + Duration::days(1),
+        vec![],
+        3,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(overview.appointments.len(), 3);
+    assert_eq!(overview.recent_patients.len(), 3);
+}
+
+#[tokio::test]
+async fn overview_lists_overdue_vaccinations() {
+    let db = create_test_db_with_migrations().await;
+    let rex = create_test_patient(&db, "Rex", 1, None).await;
+    let bella = create_test_patient(&db, "Bella", 1, None).await;
+    let inactive = create_test_patient(&db, "Inactive", 1, None).await;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE patients SET is_active = 0 WHERE id = ?",
+        [inactive.into()],
+    ))
+    .await
+    .unwrap();
+
+    let year_ago = day_start() - Duration::days(400);
+    let rabies = seed_vaccination(&db, rex, "Rabies", year_ago, day_start() - Duration::days(35)).await;
+    let dhpp = seed_vaccination(&db, bella, "DHPP", year_ago, day_start() - Duration::days(2)).await;
+    // Due today or later isn't overdue yet
+    seed_vaccination(&db, rex, "DHPP", year_ago, day_start() + Duration::hours(10)).await;
+    // A booster since supersedes the overdue dose, whatever the name's case
+    seed_vaccination(&db, bella, "Leptospirosis", year_ago, day_start() - Duration::days(30)).await;
+    seed_vaccination(&db, bella, "leptospirosis", day_start() - Duration::days(20), day_start() + Duration::days(345)).await;
+    seed_vaccination(&db, inactive, "Rabies", year_ago, day_start() - Duration::days(35)).await;
+
+    let overview = DashboardService::get_today_overview(
+        &db,
+        day_start(),
+        day_start() + Duration::days(1),
+        vec![],
+        10,
+    )
+    .await
+    .unwrap();
+
+    let overdue: Vec<(i64, Option<&str>)> = overview
+        .overdue_vaccinations
+        .iter()
+        .map(|v| (v.vaccination_id, v.patient_name.as_deref()))
+        .collect();
+    assert_eq!(overdue, vec![(rabies, Some("Rex")), (dhpp, Some("Bella"))], "most overdue first");
+}
+
+#[test]
+fn day_bounds_cover_the_practice_local_day() {
+    let skopje = chrono_tz::Europe::Skopje;
+
+    // Already the 16th in Skopje while still the 15th in UTC
+    let (start, end) = DashboardService::day_bounds(Utc.with_ymd_and_hms(2030, 6, 15, 22, 30, 0).unwrap(), &skopje)
+        .unwrap();
+    assert_eq!(start, Utc.with_ymd_and_hms(2030, 6, 15, 22, 0, 0).unwrap());
+    assert_eq!(end, Utc.with_ymd_and_hms(2030, 6, 16, 22, 0, 0).unwrap());
+    for bound in [start, end] {
+        assert_eq!(bound.with_timezone(&skopje).time(), chrono::NaiveTime::MIN);
+    }
+
+    // The spring-forward day is 23 hours long
+    let (start, end) = DashboardService::day_bounds(Utc.with_ymd_and_hms(2030, 3, 31, 12, 0, 0).unwrap(), &skopje)
+        .unwrap();
+    assert_eq!(start, Utc.with_ymd_and_hms(2030, 3, 30, 23, 0, 0).unwrap());
+    assert_eq!(end - start, Duration::hours(23));
+}
@@ -0,0 +1,227 @@
+//! Tests for `DataIntegrityService::run_scan` and attachment hash checks.
+//!
+//! The inconsistencies are seeded on a single pooled connection with
+//! foreign keys switched off, the way rows drift on databases written
+//! without enforcement.
+
+use crate::models::medical::UploadFile;
+use crate::services::data_integrity::{
+    AttachmentIntegrityProblemKind, DataIntegrityService, IntegrityRepairOptions,
+};
+use crate::services::file_storage::FileStorageService;
+use crate::test_utils::{create_test_db_with_migrations, TestDb};
+
+async fn seed_inconsistencies(db: &TestDb, storage_dir: &std::path::Path) {
+    let mut conn = db.get_sqlite_connection_pool().acquire().await.unwrap();
+    let statements = [
+        "PRAGMA foreign_keys = OFF",
+        // A consistent patient and household, which the scan must leave alone
+        "INSERT INTO households (id, household_name) VALUES (1, 'Kept')",
+        "INSERT INTO people (id, household_id, first_name, last_name) VALUES (1, 1, 'Ana', 'Kept')",
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Fine', 1)",
+        // One of each inconsistency
+        "INSERT INTO patients (id, name, species_id) VALUES (2, 'Lost species', 9999)",
+        "INSERT INTO patients (id, name, species_id, breed_id) VALUES (3, 'Lost breed', 1, 9999)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, is_archived, version) \
+         VALUES (1, 1, 'note', 'Scan', '', 0, 1)",
+        "INSERT INTO medical_attachments (id, medical_record_id, file_id, original_name) VALUES (1, 1, 'present', 'a.pdf')",
+        "INSERT INTO medical_attachments (id, medical_record_id, file_id, original_name) VALUES (2, 1, 'gone', 'b.pdf')",
+        "INSERT INTO calendar_event_mappings (id, appointment_id, event_id, calendar_id) VALUES (1, 9999, 'evt', 'primary')",
+        "INSERT INTO people (id, household_id, first_name, last_name) VALUES (2, 9999, 'Orphan', 'Person')",
+        "INSERT INTO person_contacts (person_id, contact_type, contact_value) VALUES (2, 'phone', '070000000')",
+        "PRAGMA foreign_keys = ON",
+    ];
+    for sql in statements {
+        sqlx::query(sql).execute(&mut *conn).await.unwrap();
+    }
+    std::fs::write(storage_dir.join("present"), b"pdf").unwrap();
+}
+
+#[tokio::test]
+async fn scan_reports_each_inconsistency_without_changing_anything() {
+    let db = create_test_db_with_migrations().await;
+    let storage = tempfile::tempdir().unwrap();
+    seed_inconsistencies(&db, storage.path()).await;
+
+    let report = DataIntegrityService::run_scan(&db, storage.path(), &IntegrityRepairOptions::default())
+        .await
+        .unwrap();
+
+    for (issue, id) in [
+        (&report.patients_invalid_species, 2),
+        (&report.patients_invalid_breed, 3),
+        (&report.attachments_missing_file, 2),
+        (&report.mappings_without_appointment, 1),
+        (&report.people_without_household, 2),
+    ] {
+        assert_eq!(issue.count, 1, "{:?}", issue);
+        assert_eq!(issue.sample_ids, vec![id]);
+        assert_eq!(issue.repaired, 0);
+    }
+    assert_eq!(report.total_issues(), 5);
+
+    let again = DataIntegrityService::run_scan(&db, storage.path(), &IntegrityRepairOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(again.total_issues(), 5, "a scan without repair flags is read-only");
+}
+
+#[tokio::test]
+async fn repair_flags_fix_only_their_category() {
+    let db = create_test_db_with_migrations().await;
+    let storage = tempfile::tempdir().unwrap();
+    seed_inconsistencies(&db, storage.path()).await;
+
+    let repair = IntegrityRepairOptions {
+        repair_patient_references: true,
+        repair_missing_attachments: true,
+        repair_orphan_mappings: true,
+        repair_orphan_people: false,
+    };
+    let report = DataIntegrityService::run_scan(&db, storage.path(), &repair).await.unwrap();
+    assert_eq!(report.patients_invalid_species.repaired, 1);
+    assert_eq!(report.patients_invalid_breed.repaired, 1);
+    assert_eq!(report.attachments_missing_file.repaired, 1);
+    assert_eq!(report.mappings_without_appointment.repaired, 1);
+    assert_eq!(report.people_without_household.repaired, 0);
+
+    let after = DataIntegrityService::run_scan(&db, storage.path(), &IntegrityRepairOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(after.total_issues(), 1);
+    assert_eq!(after.people_without_household.sample_ids, vec![2]);
+
+    let repair_people = IntegrityRepairOptions {
+        repair_orphan_people: true,
+        ..Default::default()
+    };
+    let report = DataIntegrityService::run_scan(&db, storage.path(), &repair_people).await.unwrap();
+    assert_eq!(report.people_without_household.repaired, 1);
+
+    let clean = DataIntegrityService::run_scan(&db, storage.path(), &IntegrityRepairOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(clean.total_issues(), 0);
+}
+
+#[tokio::test]
+async fn verify_attachment_hashes_flags_modified_and_missing_files() {
+    let db = create_test_db_with_migrations().await;
+    let storage = tempfile::tempdir().unwrap();
+    let conn = db.get_sqlite_connection_pool();
+    for sql in [
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)",
+        "INSERT INTO patients (id, name, species_id) VALUES (2, 'Other', 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, is_archived, version) \
+         VALUES (1, 1, 'note', 'Lab', '', 0, 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, is_archived, version) \
+         VALUES (2, 2, 'note', 'Lab', '', 0, 1)",
+    ] {
+        sqlx::query(sql).execute(conn).await.unwrap();
+    }
+
+    let upload = |name: &str, bytes: &[u8]| UploadFile {
+        file_name: name.to_string(),
+        file_data: bytes.to_vec(),
+        mime_type: "image/png".to_string(),
+        device_type: None,
+        device_name: None,
+        connection_method: None,
+        attachment_type: None,
+    };
+    let rex = FileStorageService::upload_attachments_to_dir(
+        storage.path(),
+        &db,
+        1,
+        vec![upload("kept.png", b"kept"), upload("edited.png", b"original"), upload("lost.png", b"lost")],
+        10,
+        false,
+    )
+    .await
+    .unwrap()
+    .attachments;
+    FileStorageService::upload_attachments_to_dir(storage.path(), &db, 2, vec![upload("other.png", b"x")], 10, false)
+        .await
+        .unwrap();
+
+    let clean = DataIntegrityService::verify_attachment_hashes(&db, storage.path(), None).await.unwrap();
+    assert_eq!(clean.checked, 4);
+    assert!(clean.problems.is_empty(), "{:?}", clean.problems);
+
+    std::fs::write(storage.path().join(&rex[1].file_id), b"tampered").unwrap();
+    std::fs::remove_file(storage.path().join(&rex[2].file_id)).unwrap();
+    // An attachment from before hashes were stored is only checked for presence
+    sqlx::query("INSERT INTO medical_attachments (medical_record_id, file_id, original_name) VALUES (1, 'legacy', 'old.pdf')")
+        .execute(conn)
+        .await
+        .unwrap();
+    std::fs::write(storage.path().join("legacy"), b"pdf").unwrap();
+
+    let report = DataIntegrityService::verify_attachment_hashes(&db, storage.path(), Some(1)).await.unwrap();
+    assert_eq!(report.checked, 2, "only the patient's hashed files that are still present");
+    assert_eq!(report.unhashed, 1);
+    let problems: Vec<_> = report.problems.iter().map(|p| (p.attachment_id, p.kind)).collect();
+    assert_eq!(
+        problems,
+        vec![
+            (rex[1].id, AttachmentIntegrityProblemKind::Modified),
+            (rex[2].id, AttachmentIntegrityProblemKind::Missing),
+        ]
+    );
+    let modified = &report.problems[0];
+    assert_eq!(modified.expected_sha256.as_deref(), Some(FileStorageService::content_sha256(b"original").as_str()));
+    assert_eq!(modified.actual_sha256.as_deref(), Some(FileStorageService::content_sha256(b"tampered").as_str()));
+
+    let scan = DataIntegrityService::run_scan(&db, storage.path(), &IntegrityRepairOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(scan.attachments_hash_mismatch.sample_ids, vec![rex[1].id]);
+    assert_eq!(scan.attachments_missing_file.sample_ids, vec![rex[2].id]);
+}
+
+#[tokio::test]
+async fn repair_primary_flags_leaves_exactly_one_primary_per_group() {
+    let db = create_test_db_with_migrations().await;
+    let conn = db.get_sqlite_connection_pool();
+    for sql in [
+        "INSERT INTO households (id, household_name) VALUES (1, 'Two primaries'), (2, 'No primary'), (3, 'Fine')",
+        // The insert trigger demotes earlier primaries, so the second one is set by update
+        "INSERT INTO people (id, household_id, first_name, last_name, is_primary, created_at) \
+         VALUES (1, 1, 'Ana', 'A', 1, '2024-01-01 08:00:00'), (2, 1, 'Boris', 'A', 0, '2024-02-01 08:00:00')",
+        "UPDATE people SET is_primary = 1 WHERE id = 2",
+        "INSERT INTO people (id, household_id, first_name, last_name, is_primary, created_at) \
+         VALUES (3, 2, 'Cveta', 'B', 0, '2024-03-01 08:00:00'), (4, 2, 'Dimitar', 'B', 0, '2024-01-15 08:00:00')",
+        "INSERT INTO people (id, household_id, first_name, last_name, is_primary) VALUES (5, 3, 'Elena', 'C', 1)",
+        "INSERT INTO person_contacts (id, person_id, contact_type, contact_value, is_primary) \
+         VALUES (1, 1, 'phone', '070000001', 1), (2, 1, 'phone', '070000002', 0), (3, 1, 'email', 'ana@example.com', 0)",
+        "UPDATE person_contacts SET is_primary = 1 WHERE id = 2",
+    ] {
+        sqlx::query(sql).execute(conn).await.unwrap();
+    }
+
+    let report = DataIntegrityService::repair_primary_flags(&db).await.unwrap();
+    assert_eq!((report.household_people.promoted, report.household_people.demoted), (1, 1));
+    assert_eq!(report.household_people.sample_ids, vec![1, 2]);
+    // The email group had no primary and the phone group had two
+    assert_eq!((report.person_contacts.promoted, report.person_contacts.demoted), (1, 1));
+    assert_eq!(report.patient_households.promoted + report.patient_households.demoted, 0);
+
+    let primaries: Vec<(i64, i64)> =
+        sqlx::query_as("SELECT household_id, id FROM people WHERE is_primary = 1 ORDER BY household_id")
+            .fetch_all(conn)
+            .await
+            .unwrap();
+    // The earliest primary is kept; with none, the earliest-created person is promoted
+    assert_eq!(primaries, vec![(1, 1), (2, 4), (3, 5)]);
+
+    let primary_contacts: Vec<(i64,)> =
+        sqlx::query_as("SELECT id FROM person_contacts WHERE is_primary = 1 ORDER BY id")
+            .fetch_all(conn)
+            .await
+            .unwrap();
+    assert_eq!(primary_contacts, vec![(1,), (3,)]);
+
+    let again = DataIntegrityService::repair_primary_flags(&db).await.unwrap();
+    assert_eq!(again.household_people.promoted + again.household_people.demoted, 0);
+    assert_eq!(again.person_contacts.promoted + again.person_contacts.demoted, 0);
+}
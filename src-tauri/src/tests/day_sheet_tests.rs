@@ -0,0 +1,207 @@
+//! Tests for `AppointmentService::get_day_sheet` and the day sheet PDF.
+//!
+//! Days are taken in UTC so the results don't depend on the machine's
+//! timezone. Rendering needs the built JAR and `java`, and is skipped
+//! without them.
+
+use crate::models::dto::CreatePatientDto;
+use crate::models::{AppointmentStatus, CreateAppointmentInput, UpdateAppointmentInput};
+use crate::services::appointments::AppointmentService;
+use crate::services::java_pdf_service::JavaPdfService;
+use crate::services::patient::PatientService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient, create_test_room};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+async fn book(
+    db: &DatabaseConnection,
+    patient_id: i64,
+    room_id: i64,
+    title: &str,
+    start: DateTime<Utc>,
+) -> i64 {
+    AppointmentService::create_appointment(
+        db,
+        CreateAppointmentInput {
+            patient_id,
+            title: title.to_string(),
+            description: Some(format!("{} notes", title)),
+            start_time: start,
+            end_time: start + Duration::minutes(30),
+            room_id: Some(room_id),
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 4, day, hour, minute, 0).unwrap()
+}
+
+fn day() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2030, 4, 9).unwrap()
+}
+
+/// Three appointments on 2030-04-09 across two rooms, plus a cancelled one
+/// that day and one the next day. Returns (room_a, room_b, ids in time order).
+async fn seed_day(db: &DatabaseConnection) -> (i64, i64, Vec<i64>) {
+    let rex = create_test_patient(db, "Rex", 1, None).await;
+    let bella = create_test_patient(db, "Бела", 1, None).await;
+    let room_a = create_test_room(db, "Exam 1").await;
+    let room_b = create_test_room(db, "Surgery").await;
+
+    let vaccine = book(db, rex, room_a, "Vaccine", at(9, 9, 0)).await;
+    let surgery = book(db, bella, room_b, "Spay", at(9, 10, 0)).await;
+    let recheck = book(db, rex, room_a, "Recheck", at(9, 14, 30)).await;
+    // Booked last but earliest in the day, so ordering isn't insertion order
+    let cancelled = book(db, bella, room_b, "Cancelled", at(9, 8, 0)).await;
+    AppointmentService::update_appointment(
+        db,
+        cancelled,
+        UpdateAppointmentInput {
+            title: None,
+            description: None,
+            start_time: None,
+            end_time: None,
+            room_id: None,
+            status: Some(AppointmentStatus::Cancelled),
+            override_status_check: false,
+        },
+        "test".to_string(),
+        None,
+    )
+    .await
+    .unwrap();
+    book(db, rex, room_a, "Tomorrow", at(10, 9, 0)).await;
+
+    (room_a, room_b, vec![vaccine, surgery, recheck])
+}
+
+#[tokio::test]
+async fn day_sheet_lists_the_days_appointments_in_time_order() {
+    let db = create_test_db_with_migrations().await;
+    let (_, _, expected) = seed_day(&db).await;
+
+    let sections = AppointmentService::get_day_sheet(&db, day(), &[], false, &Utc).await.unwrap();
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].room_name, None);
+
+    let entries = &sections[0].entries;
+    let ids: Vec<i64> = entries.iter().map(|e| e.appointment_id).collect();
+    assert_eq!(ids, expected, "cancelled and next-day appointments are left out");
+    assert_eq!(entries[0].time, "09:00–09:30");
+    assert_eq!(entries[0].patient_name, "Rex");
+    assert_eq!(entries[0].room_name.as_deref(), Some("Exam 1"));
+    assert_eq!(entries[0].notes.as_deref(), Some("Vaccine notes"));
+
+    let with_cancelled = AppointmentService::get_day_sheet(&db, day(), &[], true, &Utc).await.unwrap();
+    let first = &with_cancelled[0].entries[0];
+    assert_eq!(with_cancelled[0].entries.len(), 4);
+    assert_eq!(first.status, AppointmentStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn day_sheet_groups_by_room_when_several_rooms_are_requested() {
+    let db = create_test_db_with_migrations().await;
+    let (room_a, room_b, expected) = seed_day(&db).await;
+
+    let sections = AppointmentService::get_day_sheet(&db, day(), &[room_b, room_a], false, &Utc)
+        .await
+        .unwrap();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].room_name.as_deref(), Some("Surgery"));
+    assert_eq!(
+        sections[0].entries.iter().map(|e| e.appointment_id).collect::<Vec<_>>(),
+        vec![expected[1]]
+    );
+    assert_eq!(sections[1].room_name.as_deref(), Some("Exam 1"));
+    assert_eq!(
+        sections[1].entries.iter().map(|e| e.appointment_id).collect::<Vec<_>>(),
+        vec![expected[0], expected[2]]
+    );
+
+    let single = AppointmentService::get_day_sheet(&db, day(), &[room_a], false, &Utc).await.unwrap();
+    assert_eq!(single.len(), 1);
+    assert_eq!(single[0].room_name, None);
+    assert_eq!(single[0].entries.len(), 2);
+}
+
+#[tokio::test]
+async fn day_sheet_shows_the_primary_contact_as_owner() {
+    let db = create_test_db_with_migrations().await;
+    let household_id = db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "INSERT INTO households (household_name) VALUES ('Novak')",
+    )).await.unwrap().last_insert_id() as i64;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO people (household_id, first_name, last_name, is_primary) VALUES (?, 'Ana', 'Novak', 1)",
+        [household_id.into()],
+    )).await.unwrap();
+    let rex = PatientService::create(
+        &db,
+        CreatePatientDto {
+            name: Some("Rex".to_string()),
+            species_id: Some(1),
+            breed_id: None,
+            gender: None,
+            date_of_birth: None,
+            color: None,
+            weight: None,
+            microchip_id: None,
+            medical_notes: None,
+            household_id: Some(household_id),
+        },
+    )
+    .await
+    .unwrap()
+    .id;
+    let bella = create_test_patient(&db, "Bella", 1, None).await;
+    let room = create_test_room(&db, "Exam 1").await;
+    book(&db, rex, room, "Vaccine", at(9, 9, 0)).await;
+    book(&db, bella, room, "Recheck", at(9, 10, 0)).await;
+
+    let sections = AppointmentService::get_day_sheet(&db, day(), &[], false, &Utc).await.unwrap();
+    let owners: Vec<Option<&str>> = sections[0].entries.iter().map(|e| e.owner.as_deref()).collect();
+    assert_eq!(owners, vec![Some("Ana Novak"), None]);
+
+    // A soft-deleted household no longer owns anyone on the sheet
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE households SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?",
+        [household_id.into()],
+    )).await.unwrap();
+    let sections = AppointmentService::get_day_sheet(&db, day(), &[], false, &Utc).await.unwrap();
+    assert_eq!(sections[0].entries[0].owner, None);
+}
+
+#[tokio::test]
+async fn day_sheet_renders_non_empty_pdf() {
+    let jar = std::path::PathBuf::from("../pdf-generator-cli/build/libs/pdf-generator-cli-1.0.0.jar");
+    if !jar.exists() {
+        eprintln!("SKIP: JAR not built ({}). Run: cd pdf-generator-cli && ./gradlew build", jar.display());
+        return;
+    }
+    if std::process::Command::new("java").arg("-version").output().is_err() {
+        eprintln!("SKIP: no `java` on PATH");
+        return;
+    }
+
+    let db = create_test_db_with_migrations().await;
+    seed_day(&db).await;
+    let sections = AppointmentService::get_day_sheet(&db, day(), &[], false, &Utc).await.unwrap();
+    assert_eq!(sections[0].entries.len(), 3);
+
+    let pdf_path = std::env::temp_dir().join("day_sheet_test.pdf");
+    let _ = std::fs::remove_file(&pdf_path);
+
+    JavaPdfService::generate_day_sheet_with_jar(&jar, pdf_path.to_str().unwrap(), "09/04/2030", &sections)
+        .expect("day sheet failed to render");
+
+    let size = std::fs::metadata(&pdf_path).map(|m| m.len()).unwrap_or(0);
+    assert!(size > 0, "day sheet PDF is empty");
+    let _ = std::fs::remove_file(&pdf_path);
+}
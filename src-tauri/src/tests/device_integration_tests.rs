@@ -1,11 +1,20 @@
 //! DeviceIntegrationService CRUD + lifecycle tests.
 
 use crate::models::device_integration::{
-    ConnectionType, CreateDeviceIntegrationInput, DeviceType, UpdateDeviceIntegrationInput,
+    ConnectionSettings, ConnectionType, CreateDeviceIntegrationInput, DeviceIntegrationValidationError,
+    DeviceType, UpdateDeviceIntegrationInput,
 };
 use crate::models::dto::MaybeNull;
+use crate::services::device_input::{
+    active_listener_integration_ids, clear_connection_status, dismiss_connection_status,
+    get_all_connection_statuses, get_device_protocol_defaults, insert_connection_status,
+    register_idle_listener, ConnectionState, DeviceConnectionStatus,
+};
 use crate::services::device_integration::DeviceIntegrationService;
+use crate::services::events::{self, EventSink};
 use crate::test_utils::create_test_db_with_migrations;
+use sea_orm::ConnectionTrait;
+use serde_json::json;
 
 fn serial_input(name: &str, device_type: DeviceType, port: &str, baud: i64) -> CreateDeviceIntegrationInput {
     CreateDeviceIntegrationInput {
@@ -215,6 +224,57 @@ async fn toggle_enabled_nonexistent_errors() {
     assert!(result.is_err());
 }
 
+// ---------------------------------------------------------------------------
+// set_enabled_with_listener
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn disabling_stops_the_running_listener() {
+    let db = create_test_db_with_migrations().await;
+    let i = DeviceIntegrationService::create(&db, serial_input("Pause", DeviceType::HealvetHvFia3000, "/dev/tty.pause", 9600))
+        .await.unwrap();
+    register_idle_listener("/dev/tty.pause", "healvet_hv_fia_3000", i.id);
+    assert!(active_listener_integration_ids().contains(&i.id));
+
+    let result = DeviceIntegrationService::set_enabled_with_listener(&db, i.id, false, |_, _| {
+        panic!("disabling must not start a listener")
+    })
+    .await
+    .unwrap();
+
+    assert!(!result.integration.enabled);
+    assert!(!result.listener_active);
+    assert!(!active_listener_integration_ids().contains(&i.id), "listener thread still running");
+    assert!(!DeviceIntegrationService::get_by_id(&db, i.id).await.unwrap().enabled);
+}
+
+#[tokio::test]
+async fn enabling_starts_the_listener_or_stays_disabled() {
+    let db = create_test_db_with_migrations().await;
+    let i = DeviceIntegrationService::create(&db, serial_input("Resume", DeviceType::HealvetHvFia3000, "/dev/tty.resume", 9600))
+        .await.unwrap();
+    DeviceIntegrationService::set_enabled(&db, i.id, false).await.unwrap();
+
+    let mut started = None;
+    let result = DeviceIntegrationService::set_enabled_with_listener(&db, i.id, true, |integration, port| {
+        started = Some((integration.id, port.to_string()));
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert!(result.integration.enabled);
+    assert_eq!(started, Some((i.id, "/dev/tty.resume".to_string())));
+
+    DeviceIntegrationService::set_enabled(&db, i.id, false).await.unwrap();
+    let err = DeviceIntegrationService::set_enabled_with_listener(&db, i.id, true, |_, port| {
+        Err(format!("Port {} is already in use by another device integration", port))
+    })
+    .await
+    .unwrap_err();
+    assert!(err.contains("left disabled"), "got: {}", err);
+    assert!(!DeviceIntegrationService::get_by_id(&db, i.id).await.unwrap().enabled);
+}
+
 // ---------------------------------------------------------------------------
 // update_last_connected
 // ---------------------------------------------------------------------------
@@ -267,3 +327,381 @@ async fn delete_then_get_all_excludes_row() {
     assert!(ids.contains(&i1.id));
     assert!(!ids.contains(&i2.id));
 }
+
+// ---------------------------------------------------------------------------
+// Connection field validation
+// ---------------------------------------------------------------------------
+
+fn tcp_input(name: &str, host: Option<&str>, port: Option<i64>) -> CreateDeviceIntegrationInput {
+    CreateDeviceIntegrationInput {
+        name: name.to_string(),
+        device_type: DeviceType::MnchipPcrAnalyzer,
+        connection_type: ConnectionType::Hl7Tcp,
+        watch_directory: None,
+        file_pattern: None,
+        serial_port_name: None,
+        serial_baud_rate: None,
+        tcp_host: host.map(|h| h.to_string()),
+        tcp_port: port,
+    }
+}
+
+#[test]
+fn validate_serial_requires_port_name() {
+    let errors = ConnectionSettings { serial_baud_rate: Some(9600), ..Default::default() }
+        .validate(&ConnectionType::SerialPort)
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![DeviceIntegrationValidationError::MissingField {
+            connection_type: "serial_port",
+            field: "serial_port_name",
+        }]
+    );
+}
+
+#[test]
+fn validate_file_watch_requires_directory_and_pattern() {
+    let errors = ConnectionSettings { file_pattern: Some("  ".to_string()), ..Default::default() }
+        .validate(&ConnectionType::FileWatch)
+        .unwrap_err();
+    assert!(errors.contains(&DeviceIntegrationValidationError::MissingField {
+        connection_type: "file_watch",
+        field: "watch_directory",
+    }));
+    assert!(errors.contains(&DeviceIntegrationValidationError::MissingField {
+        connection_type: "file_watch",
+        field: "file_pattern",
+    }), "blank pattern counts as missing");
+}
+
+#[test]
+fn validate_hl7_tcp_requires_host_and_port() {
+    let errors = ConnectionSettings::default().validate(&ConnectionType::Hl7Tcp).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|e| matches!(e, DeviceIntegrationValidationError::MissingField { .. })));
+}
+
+#[test]
+fn validate_rejects_tcp_host_on_serial() {
+    let errors = ConnectionSettings {
+        serial_port_name: Some("/dev/ttyUSB0".to_string()),
+        tcp_host: Some("10.0.0.5".to_string()),
+        ..Default::default()
+    }
+    .validate(&ConnectionType::SerialPort)
+    .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![DeviceIntegrationValidationError::ConflictingField {
+            connection_type: "serial_port",
+            field: "tcp_host",
+        }]
+    );
+}
+
+#[test]
+fn validate_rejects_out_of_range_tcp_port() {
+    let errors = ConnectionSettings {
+        tcp_host: Some("10.0.0.5".to_string()),
+        tcp_port: Some(70000),
+        ..Default::default()
+    }
+    .validate(&ConnectionType::Hl7Tcp)
+    .unwrap_err();
+    assert!(matches!(errors[0], DeviceIntegrationValidationError::InvalidField { field: "tcp_port", .. }));
+}
+
+#[tokio::test]
+async fn create_with_missing_fields_is_rejected_and_not_saved() {
+    let db = create_test_db_with_migrations().await;
+
+    let mut serial = serial_input("No port", DeviceType::HealvetHvFia3000, "", 9600);
+    serial.serial_port_name = None;
+    let err = DeviceIntegrationService::create(&db, serial).await.unwrap_err();
+    assert!(err.contains("serial_port_name"), "got: {}", err);
+
+    let err = DeviceIntegrationService::create(&db, file_watch_input("No dir", "", "*.xml"))
+        .await
+        .unwrap_err();
+    assert!(err.contains("watch_directory"), "got: {}", err);
+
+    let err = DeviceIntegrationService::create(&db, tcp_input("No host", None, Some(5000)))
+        .await
+        .unwrap_err();
+    assert!(err.contains("tcp_host"), "got: {}", err);
+
+    assert!(DeviceIntegrationService::get_all(&db).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn create_hl7_tcp_succeeds() {
+    let db = create_test_db_with_migrations().await;
+    let i = DeviceIntegrationService::create(&db, tcp_input("PCR LAN", Some("192.168.1.20"), Some(5000)))
+        .await
+        .unwrap();
+    assert_eq!(i.connection_type, ConnectionType::Hl7Tcp);
+    assert_eq!(i.tcp_port, Some(5000));
+}
+
+#[tokio::test]
+async fn update_switching_type_drops_old_fields() {
+    let db = create_test_db_with_migrations().await;
+    let i = DeviceIntegrationService::create(&db, serial_input("Switch", DeviceType::MnchipPcrAnalyzer, "/dev/a", 9600))
+        .await.unwrap();
+
+    let updated = DeviceIntegrationService::update(
+        &db,
+        i.id,
+        UpdateDeviceIntegrationInput {
+            name: None,
+            connection_type: Some(ConnectionType::Hl7Tcp),
+            watch_directory: MaybeNull::Undefined, file_pattern: None,
+            serial_port_name: MaybeNull::Undefined, serial_baud_rate: MaybeNull::Undefined,
+            tcp_host: MaybeNull::Value("10.0.0.9".to_string()),
+            tcp_port: MaybeNull::Value(2575),
+            enabled: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.connection_type, ConnectionType::Hl7Tcp);
+    assert!(updated.serial_port_name.is_none());
+    assert!(updated.serial_baud_rate.is_none());
+}
+
+#[tokio::test]
+async fn update_clears_stale_fields_saved_before_validation() {
+    let db = create_test_db_with_migrations().await;
+    let i = DeviceIntegrationService::create(&db, serial_input("Legacy", DeviceType::HealvetHvFia3000, "/dev/a", 9600))
+        .await.unwrap();
+    db.execute_unprepared(&format!(
+        "UPDATE device_integrations SET tcp_host = '10.0.0.9', watch_directory = '/tmp' WHERE id = {}",
+        i.id
+    ))
+    .await
+    .unwrap();
+
+    let updated = DeviceIntegrationService::update(
+        &db,
+        i.id,
+        UpdateDeviceIntegrationInput {
+            name: Some("Renamed".to_string()), connection_type: None,
+            watch_directory: MaybeNull::Undefined, file_pattern: None,
+            serial_port_name: MaybeNull::Undefined, serial_baud_rate: MaybeNull::Undefined,
+            tcp_host: MaybeNull::Undefined, tcp_port: MaybeNull::Undefined,
+            enabled: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.name, "Renamed");
+    assert_eq!(updated.serial_port_name.as_deref(), Some("/dev/a"));
+    assert!(updated.tcp_host.is_none());
+    assert!(updated.watch_directory.is_none());
+}
+
+#[tokio::test]
+async fn update_setting_conflicting_field_is_rejected() {
+    let db = create_test_db_with_migrations().await;
+    let i = DeviceIntegrationService::create(&db, serial_input("Serial", DeviceType::HealvetHvFia3000, "/dev/a", 9600))
+        .await.unwrap();
+
+    let err = DeviceIntegrationService::update(
+        &db,
+        i.id,
+        UpdateDeviceIntegrationInput {
+            name: None, connection_type: None,
+            watch_directory: MaybeNull::Undefined, file_pattern: None,
+            serial_port_name: MaybeNull::Undefined, serial_baud_rate: MaybeNull::Undefined,
+            tcp_host: MaybeNull::Value("10.0.0.9".to_string()),
+            tcp_port: MaybeNull::Undefined,
+            enabled: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("must not set tcp_host"), "got: {}", err);
+}
+
+fn status(integration_id: i64, port: &str, state: ConnectionState) -> DeviceConnectionStatus {
+    DeviceConnectionStatus {
+        integration_id,
+        port_name: port.to_string(),
+        device_type: "healvet_hv_fia_3000".to_string(),
+        status: state,
+        last_connected: None,
+        last_error: None,
+        retry_count: 0,
+        next_retry: None,
+    }
+}
+
+#[tokio::test]
+async fn get_all_with_status_joins_live_state() {
+    let db = create_test_db_with_migrations().await;
+    let active = DeviceIntegrationService::create(
+        &db,
+        serial_input("Active", DeviceType::HealvetHvFia3000, "/dev/tty.a", 9600),
+    )
+    .await
+    .unwrap();
+    let stopped = DeviceIntegrationService::create(
+        &db,
+        serial_input("Stopped", DeviceType::HealvetHvFia3000, "/dev/tty.s", 9600),
+    )
+    .await
+    .unwrap();
+    let deleted = DeviceIntegrationService::create(
+        &db,
+        serial_input("Deleted", DeviceType::HealvetHvFia3000, "/dev/tty.d", 9600),
+    )
+    .await
+    .unwrap();
+    DeviceIntegrationService::update_last_connected(&db, active.id).await.unwrap();
+    DeviceIntegrationService::delete(&db, deleted.id).await.unwrap();
+
+    let statuses = vec![
+        // Left over from before the port was changed
+        status(active.id, "/dev/tty.old", ConnectionState::Error),
+        status(active.id, "/dev/tty.a", ConnectionState::Connected),
+        status(stopped.id, "/dev/tty.s", ConnectionState::Disconnected),
+    ];
+    let list = DeviceIntegrationService::get_all_with_status(&db, statuses, &[active.id])
+        .await
+        .unwrap();
+
+    assert_eq!(list.len(), 2, "deleted integrations are left out");
+    let a = list.iter().find(|i| i.integration.id == active.id).unwrap();
+    assert!(a.listener_active);
+    assert!(a.integration.last_connected_at.is_some());
+    let a_status = a.connection_status.as_ref().unwrap();
+    assert_eq!((a_status.port_name.as_str(), &a_status.status), ("/dev/tty.a", &ConnectionState::Connected));
+
+    let s = list.iter().find(|i| i.integration.id == stopped.id).unwrap();
+    assert!(!s.listener_active);
+    assert!(s.integration.last_connected_at.is_none());
+    assert_eq!(s.connection_status.as_ref().unwrap().status, ConnectionState::Disconnected);
+}
+
+// ---------------------------------------------------------------------------
+// get_device_protocol_defaults
+// ---------------------------------------------------------------------------
+
+#[test]
+fn protocol_defaults_match_the_serial_protocol_of_each_type() {
+    let expected = [
+        (DeviceType::ExigoEosVet, 9600, None, 0x0A),
+        (DeviceType::HealvetHvFia3000, 9600, None, b'E'),
+        (DeviceType::MnchipPointcareChemistry, 115200, Some(0x0B), 0x1C),
+        (DeviceType::MnchipPcrAnalyzer, 115200, Some(0x0B), 0x1C),
+    ];
+
+    for (device_type, baud_rate, start_symbol, end_symbol) in expected {
+        let defaults = get_device_protocol_defaults(device_type.to_db_string()).unwrap();
+        assert_eq!(defaults.display_name, device_type.display_name());
+        assert_eq!(defaults.baud_rate, baud_rate, "{}", defaults.device_type);
+        assert_eq!(defaults.start_symbol, start_symbol, "{}", defaults.device_type);
+        assert_eq!(defaults.end_symbol, end_symbol, "{}", defaults.device_type);
+        assert!(!defaults.framing.is_empty());
+
+        let supported: Vec<&str> = defaults
+            .supported_device_types
+            .iter()
+            .map(|t| t.device_type.as_str())
+            .collect();
+        assert_eq!(
+            supported,
+            ["exigo_eos_vet", "healvet_hv_fia_3000", "mnchip_pointcare_chemistry", "mnchip_pcr_analyzer"]
+        );
+    }
+
+    assert!(get_device_protocol_defaults("unknown_analyzer").is_err());
+}
+
+// ---------------------------------------------------------------------------
+// clear_device_connection_status
+// ---------------------------------------------------------------------------
+
+#[test]
+fn clearing_a_status_drops_only_that_integration() {
+    let status = |integration_id: i64, port: &str| DeviceConnectionStatus {
+        integration_id,
+        port_name: port.to_string(),
+        device_type: "healvet_hv_fia_3000".to_string(),
+        status: ConnectionState::Error,
+        last_connected: None,
+        last_error: Some("Port busy".to_string()),
+        retry_count: 3,
+        next_retry: Some("2030-01-01T00:00:00Z".to_string()),
+    };
+    insert_connection_status(status(1999, "/dev/tty.stale"));
+    insert_connection_status(status(2000, "/dev/tty.other"));
+
+    let cleared = clear_connection_status(1999);
+    assert_eq!(cleared.len(), 1);
+    assert_eq!(cleared[0].port_name, "/dev/tty.stale");
+    assert_eq!(cleared[0].status, ConnectionState::Disconnected);
+    assert!(cleared[0].last_error.is_none());
+    assert_eq!(cleared[0].retry_count, 0);
+
+    let remaining = get_all_connection_statuses();
+    assert!(!remaining.iter().any(|s| s.integration_id == 1999));
+    assert!(remaining.iter().any(|s| s.integration_id == 2000));
+    assert!(clear_connection_status(1999).is_empty());
+    clear_connection_status(2000);
+}
+
+/// Keeps what would have been emitted, in order.
+#[derive(Default)]
+struct RecordedEvents(std::cell::RefCell<Vec<(&'static str, serde_json::Value)>>);
+
+impl EventSink for RecordedEvents {
+    fn emit_event<S: serde::Serialize + Clone>(&self, event: &'static str, payload: S) -> tauri::Result<()> {
+        self.0.borrow_mut().push((event, serde_json::to_value(payload).unwrap()));
+        Ok(())
+    }
+}
+
+#[test]
+fn dismissing_a_status_emits_a_disconnected_status_per_port() {
+    for port in ["/dev/tty.a", "/dev/tty.b"] {
+        insert_connection_status(DeviceConnectionStatus {
+            integration_id: 2001,
+            port_name: port.to_string(),
+            device_type: "healvet_hv_fia_3000".to_string(),
+            status: ConnectionState::Error,
+            last_connected: None,
+            last_error: Some("Port busy".to_string()),
+            retry_count: 2,
+            next_retry: Some("2030-01-01T00:00:00Z".to_string()),
+        });
+    }
+
+    let sink = RecordedEvents::default();
+    assert_eq!(dismiss_connection_status(&sink, 2001), 2);
+
+    let mut emitted = sink.0.into_inner();
+    emitted.sort_by(|a, b| a.1["port_name"].as_str().cmp(&b.1["port_name"].as_str()));
+    assert_eq!(emitted.len(), 2);
+    for ((event, payload), port) in emitted.iter().zip(["/dev/tty.a", "/dev/tty.b"]) {
+        assert_eq!(*event, events::DEVICE_CONNECTION_STATUS);
+        assert_eq!(
+            *payload,
+            json!({
+                "integration_id": 2001,
+                "port_name": port,
+                "device_type": "healvet_hv_fia_3000",
+                "status": "Disconnected",
+                "last_connected": null,
+                "last_error": null,
+                "retry_count": 0,
+                "next_retry": null,
+            })
+        );
+    }
+
+    let nothing = RecordedEvents::default();
+    assert_eq!(dismiss_connection_status(&nothing, 2001), 0);
+    assert!(nothing.0.into_inner().is_empty());
+}
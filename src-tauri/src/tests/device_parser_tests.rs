@@ -985,6 +985,70 @@ mod pdf_end_to_end {
     }
 }
 
+/// `render_sample_device_report` data: canned payloads for every device must
+/// survive their parser, and the Exigo one must render to an actual PDF
+/// (skipped like `pdf_end_to_end` when the JAR or `java` is missing).
+mod sample_report {
+    use crate::services::device_parser::EXIGO_PDF_ANALYTE_KEYS;
+    use crate::services::device_pdf_service::DevicePdfService;
+    use crate::services::java_pdf_service::JavaPdfService;
+    use crate::services::pdf_jobs::PdfCancelToken;
+
+    #[test]
+    fn sample_data_parses_for_every_device_type() {
+        for device_type in [
+            "exigo_eos_vet",
+            "mnchip_pointcare_chemistry",
+            "mnchip_pcr_analyzer",
+            "healvet_hv_fia_3000",
+        ] {
+            for language in ["en", "mk"] {
+                let (patient, data) = DevicePdfService::sample_report_data(device_type, language)
+                    .unwrap_or_else(|e| panic!("{} / {}: {}", device_type, language, e));
+                assert_eq!(data.device_type, device_type);
+                assert!(!patient.name.is_empty());
+                assert!(
+                    data.test_results.as_object().is_some_and(|m| !m.is_empty()),
+                    "{} sample has no results",
+                    device_type
+                );
+            }
+        }
+
+        let (patient, exigo) = DevicePdfService::sample_report_data("exigo_eos_vet", "mk").unwrap();
+        assert!(patient.name.chars().any(|c| ('\u{0400}'..='\u{04FF}').contains(&c)));
+        let map = exigo.test_results.as_object().unwrap();
+        assert!(EXIGO_PDF_ANALYTE_KEYS.iter().all(|k| map.contains_key(*k)));
+
+        assert!(DevicePdfService::sample_report_data("unknown_device", "en").is_err());
+        assert!(DevicePdfService::sample_report_data("exigo_eos_vet", "de").is_err());
+    }
+
+    #[test]
+    fn exigo_sample_report_renders_non_empty_pdf() {
+        let jar = std::path::PathBuf::from("../pdf-generator-cli/build/libs/pdf-generator-cli-1.0.0.jar");
+        if !jar.exists() {
+            eprintln!("SKIP: JAR not built ({}). Run: cd pdf-generator-cli && ./gradlew build", jar.display());
+            return;
+        }
+        if std::process::Command::new("java").arg("-version").output().is_err() {
+            eprintln!("SKIP: no `java` on PATH");
+            return;
+        }
+
+        let (patient, data) = DevicePdfService::sample_report_data("exigo_eos_vet", "mk").unwrap();
+        let pdf_path = std::env::temp_dir().join("sample_exigo_report.pdf");
+        let _ = std::fs::remove_file(&pdf_path);
+
+        JavaPdfService::generate_pdf_multi_with_jar(&jar, pdf_path.to_str().unwrap(), &patient, &[data], &PdfCancelToken::none())
+            .expect("sample report failed to render");
+
+        let size = std::fs::metadata(&pdf_path).map(|m| m.len()).unwrap_or(0);
+        assert!(size > 0, "sample report PDF is empty");
+        let _ = std::fs::remove_file(&pdf_path);
+    }
+}
+
 /// REGRESSION GUARD against the cross-patient blend. A later sample that
 /// omits values an earlier one had (error/QC run writing only ranges/flags
 /// for some analytes — the BM800 does this, note `_F="ER"`) must NOT let the
@@ -1012,3 +1076,184 @@ fn daily_file_no_cross_patient_blend() {
     // SECOND's own range/flag keys are still present.
     assert_eq!(m.get("PLT_L").unwrap(), "200");
 }
+
+mod payload_validation {
+    use super::*;
+
+    #[test]
+    fn exigo_payload_missing_two_parameters_reports_them() {
+        // All 19 CBC codes except GR and RDWA, plus sample metadata
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<samples SNO="53672">
+<sample SEQ="1" ID2="REX" PLT="285" PLT_L="200" PLT_H="500" PLT_F="ER" MPV="9.1" HGB="17.4"
+        WBC="10.4" LA="2.1" MA="0.6" GA="7.7" LR="20.1" MR="5.9"
+        EA="0.4" ER="3.8" RBC="7.10" MCV="66.2" HCT="47.0" MCH="24.5"
+        MCHC="37.0" RDWR="14.2" />
+</samples>"#;
+
+        let validation =
+            DeviceParserService::validate_device_payload("exigo_eos_vet", xml.as_bytes(), "file_watch")
+                .unwrap();
+
+        assert_eq!(validation.missing, vec!["GR", "RDWA"]);
+        assert_eq!(validation.present.len(), 17);
+        assert!(validation.present.contains(&"PLT".to_string()));
+        assert_eq!(validation.extra, vec!["ID2", "SEQ"]);
+    }
+
+    #[test]
+    fn device_without_parameter_list_is_rejected() {
+        let result = DeviceParserService::validate_device_payload(
+            "mnchip_pointcare_chemistry",
+            b"{\"GLU\": \"5.1\"}",
+            "file_watch",
+        );
+        assert!(result.unwrap_err().contains("No expected parameter list"));
+    }
+}
+
+mod result_schema {
+    use super::*;
+
+    #[test]
+    fn exigo_schema_lists_the_19_parameters_in_report_order() {
+        let schema = DeviceParserService::result_schema("exigo_eos_vet").unwrap();
+
+        let codes: Vec<&str> = schema.iter().map(|p| p.code).collect();
+        assert_eq!(
+            codes,
+            [
+                "PLT", "MPV", "HGB", "WBC", "LA", "MA", "GA", "LR", "MR", "GR", "EA",
+                "ER", "RBC", "MCV", "HCT", "MCH", "MCHC", "RDWR", "RDWA",
+            ]
+        );
+        assert_eq!((schema[6].display_name, schema[6].unit), ("NEUT - неутрофили", "10e+9/L"));
+        assert_eq!((schema[12].display_name, schema[12].unit), ("RBC - еритроцити", "10e+12/L"));
+        assert!(DeviceParserService::result_schema("mnchip_pointcare_chemistry").is_err());
+    }
+
+    #[test]
+    fn exigo_schema_matches_the_pdf_generator() {
+        let enum_source = std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../pdf-generator-cli/src/main/java/com/vetclinic/pdf/constants/parameters/ExigoParameterEnum.java"),
+        )
+        .unwrap();
+        // Constants look like `PLT("PLT", "PLT - тромбоцити", "10e+9/L"),`
+        let java: Vec<Vec<String>> = enum_source
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.ends_with("),") || line.ends_with(");"))
+            .filter_map(|line| line.split_once('(').map(|(_, args)| args))
+            .map(|args| args.split('"').skip(1).step_by(2).map(str::to_string).collect::<Vec<_>>())
+            .filter(|args| args.len() == 3)
+            .collect();
+
+        let rust: Vec<Vec<String>> = DeviceParserService::result_schema("exigo_eos_vet")
+            .unwrap()
+            .iter()
+            .map(|p| vec![p.code.to_string(), p.display_name.to_string(), p.unit.to_string()])
+            .collect();
+        assert_eq!(rust, java);
+    }
+}
+
+mod json_mapping {
+    use super::*;
+    use crate::models::device_integration::{
+        DeviceParseMapping, DeviceResultPath, SaveDeviceParseMappingInput,
+    };
+    use crate::services::device_parse_mapping::DeviceParseMappingService;
+    use crate::test_utils::create_test_db_with_migrations;
+
+    const PAYLOAD: &str = r#"{
+        "analyzer": { "serial": "VC-220" },
+        "patient": { "chip": 941000012345678 },
+        "results": [
+            { "code": "GLU", "value": 5.4, "unit": "mmol/L" },
+            { "code": "BUN", "value": "7.1", "unit": "mmol/L" }
+        ],
+        "flags": { "GLU": "N" }
+    }"#;
+
+    fn path(result_key: &str, path: &str) -> DeviceResultPath {
+        DeviceResultPath { result_key: result_key.to_string(), path: path.to_string() }
+    }
+
+    fn result_paths() -> Vec<DeviceResultPath> {
+        vec![
+            path("GLU", "results[code=GLU].value"),
+            path("BUN", "$.results[1].value"),
+            path("GLU_F", "flags.GLU"),
+        ]
+    }
+
+    #[test]
+    fn maps_json_payload_into_test_results() {
+        let mut result_paths = result_paths();
+        // Not in this payload: skipped rather than failing the whole result
+        result_paths.push(path("CREA", "results[code=CREA].value"));
+        let mapping = DeviceParseMapping {
+            id: 1,
+            device_type: "vetchem_json".to_string(),
+            patient_identifier_path: Some("patient.chip".to_string()),
+            result_paths,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        let data = DeviceParserService::parse_json_device_data(
+            &mapping,
+            "VetChem",
+            "result.json",
+            PAYLOAD.as_bytes(),
+            "file_watch",
+        )
+        .unwrap();
+
+        assert_eq!(data.device_type, "vetchem_json");
+        assert_eq!(data.patient_identifier.as_deref(), Some("941000012345678"));
+        assert_eq!(
+            data.test_results,
+            serde_json::json!({ "GLU": 5.4, "BUN": "7.1", "GLU_F": "N" })
+        );
+    }
+
+    #[test]
+    fn malformed_paths_are_rejected() {
+        for bad in ["", "$.", "results[0", "results..value", "results[x].value", "results[=GLU]"] {
+            assert!(DeviceParserService::validate_json_path(bad).is_err(), "{:?} accepted", bad);
+        }
+        assert!(DeviceParserService::validate_json_path("results[code=GLU].value").is_ok());
+    }
+
+    #[tokio::test]
+    async fn saving_checks_paths_against_the_sample_payload() {
+        let db = create_test_db_with_migrations().await;
+        let input = |extra: Option<DeviceResultPath>| SaveDeviceParseMappingInput {
+            device_type: "vetchem_json".to_string(),
+            patient_identifier_path: Some("patient.chip".to_string()),
+            result_paths: result_paths().into_iter().chain(extra).collect(),
+        };
+
+        let err = DeviceParseMappingService::save(
+            &db,
+            input(Some(path("CREA", "results[code=CREA].value"))),
+            Some(PAYLOAD),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("results[code=CREA].value"), "{}", err);
+
+        let builtin = SaveDeviceParseMappingInput { device_type: "exigo_eos_vet".to_string(), ..input(None) };
+        assert!(DeviceParseMappingService::save(&db, builtin, None).await.is_err());
+
+        let saved = DeviceParseMappingService::save(&db, input(None), Some(PAYLOAD)).await.unwrap();
+        assert_eq!(saved.result_paths, result_paths());
+
+        let data = DeviceParseMappingService::parse_payload(&db, "vetchem_json", PAYLOAD.as_bytes(), "file_watch")
+            .await
+            .unwrap();
+        assert_eq!(data.test_results["GLU"], serde_json::json!(5.4));
+    }
+}
@@ -9,6 +9,8 @@
 //!     leaves the junction table in the same state
 //!   - list_for_record returns even inactive diagnoses (so historical
 //!     records show their full original tag set)
+//!   - Bulk add/remove across records, and force delete removing or
+//!     reassigning the links of a diagnosis still in use
 
 use crate::models::diagnosis::{CreateDiagnosisInput, UpdateDiagnosisInput};
 use crate::services::diagnosis::DiagnosisService;
@@ -622,3 +624,134 @@ async fn cascade_delete_record_clears_diagnosis_links() {
     let count: i64 = rows[0].try_get("", "c").unwrap();
     assert_eq!(count, 0, "cascade should have cleared junction rows");
 }
+
+#[tokio::test]
+async fn add_to_records_tags_three_records_and_skips_existing_links() {
+    let test_db = create_test_db_with_migrations().await;
+    let pool = std::sync::Arc::new(test_db.db.clone());
+
+    let patient_id = insert_test_patient(&pool).await;
+    let mut records = Vec::new();
+    for _ in 0..3 {
+        records.push(insert_test_medical_record(&pool, patient_id).await);
+    }
+    let d = DiagnosisService::create(
+        &pool,
+        CreateDiagnosisInput {
+            name: "Arthritis".to_string(),
+            description: None,
+            color: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let added = DiagnosisService::add_to_records(&pool, d.id, &records).await.unwrap();
+    assert_eq!(added, 3);
+    for record_id in &records {
+        let linked = DiagnosisService::list_for_record(&pool, *record_id).await.unwrap();
+        assert_eq!(linked.len(), 1);
+    }
+
+    // Re-applying is a no-op; one unknown record rolls the batch back
+    let again = DiagnosisService::add_to_records(&pool, d.id, &records).await.unwrap();
+    assert_eq!(again, 0);
+    let extra = insert_test_medical_record(&pool, patient_id).await;
+    DiagnosisService::add_to_records(&pool, d.id, &[extra, 999_999])
+        .await
+        .expect_err("unknown record must fail the FK check");
+    assert!(DiagnosisService::list_for_record(&pool, extra).await.unwrap().is_empty());
+
+    let removed = DiagnosisService::remove_from_records(&pool, d.id, &records[..2])
+        .await
+        .unwrap();
+    assert_eq!(removed, 2);
+    assert!(DiagnosisService::list_for_record(&pool, records[0]).await.unwrap().is_empty());
+    assert_eq!(DiagnosisService::list_for_record(&pool, records[2]).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn rename_shows_on_every_tagged_record() {
+    let test_db = create_test_db_with_migrations().await;
+    let pool = std::sync::Arc::new(test_db.db.clone());
+
+    let patient_id = insert_test_patient(&pool).await;
+    let first = insert_test_medical_record(&pool, patient_id).await;
+    let second = insert_test_medical_record(&pool, patient_id).await;
+    let d = DiagnosisService::create(
+        &pool,
+        CreateDiagnosisInput {
+            name: "Artritis".to_string(),
+            description: None,
+            color: None,
+        },
+    )
+    .await
+    .unwrap();
+    DiagnosisService::add_to_records(&pool, d.id, &[first, second]).await.unwrap();
+
+    DiagnosisService::update(
+        &pool,
+        d.id,
+        UpdateDiagnosisInput {
+            name: Some("Arthritis".to_string()),
+            description: None,
+            color: None,
+            is_active: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    for record_id in [first, second] {
+        let linked = DiagnosisService::list_for_record(&pool, record_id).await.unwrap();
+        assert_eq!(linked[0].name, "Arthritis");
+    }
+    let for_patient = DiagnosisService::list_for_patient(&pool, patient_id).await.unwrap();
+    assert_eq!(for_patient.len(), 1);
+    assert_eq!(for_patient[0].name, "Arthritis");
+}
+
+#[tokio::test]
+async fn force_delete_removes_or_reassigns_links() {
+    let test_db = create_test_db_with_migrations().await;
+    let pool = std::sync::Arc::new(test_db.db.clone());
+
+    let patient_id = insert_test_patient(&pool).await;
+    let first = insert_test_medical_record(&pool, patient_id).await;
+    let second = insert_test_medical_record(&pool, patient_id).await;
+    let mut ids = Vec::new();
+    for name in ["Otitis", "Otitis externa", "Obsolete"] {
+        let d = DiagnosisService::create(
+            &pool,
+            CreateDiagnosisInput {
+                name: name.to_string(),
+                description: None,
+                color: None,
+            },
+        )
+        .await
+        .unwrap();
+        ids.push(d.id);
+    }
+    let (duplicate, kept, obsolete) = (ids[0], ids[1], ids[2]);
+    DiagnosisService::add_to_records(&pool, duplicate, &[first, second]).await.unwrap();
+    DiagnosisService::add_to_records(&pool, kept, &[second]).await.unwrap();
+    DiagnosisService::add_to_records(&pool, obsolete, &[first]).await.unwrap();
+
+    // Merge the duplicate into the kept term; `second` ends with one link
+    let moved = DiagnosisService::force_delete(&pool, duplicate, Some(kept)).await.unwrap();
+    assert_eq!(moved, 2);
+    for record_id in [first, second] {
+        let linked = DiagnosisService::list_for_record(&pool, record_id).await.unwrap();
+        assert_eq!(linked.iter().filter(|d| d.id == kept).count(), 1);
+        assert!(linked.iter().all(|d| d.id != duplicate));
+    }
+
+    let removed = DiagnosisService::force_delete(&pool, obsolete, None).await.unwrap();
+    assert_eq!(removed, 1);
+    assert!(DiagnosisService::get_by_id(&pool, obsolete).await.is_err());
+    let linked = DiagnosisService::list_for_record(&pool, first).await.unwrap();
+    assert_eq!(linked.len(), 1);
+    assert_eq!(linked[0].id, kept);
+}
@@ -209,7 +209,8 @@ mod appointment_e2e {
             &db,
             appointment.id,
             update_input,
-            "user".to_string()
+            "user".to_string(),
+            None,
         ).await;
 
         assert!(result.is_ok());
@@ -0,0 +1,69 @@
+//! Tests for the event catalog in `services::events`.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::services::events::{self, event_channels};
+
+fn rust_sources(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            rust_sources(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn every_emission_site_uses_an_events_constant() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut files = Vec::new();
+    rust_sources(&src, &mut files);
+
+    let mut offenders = Vec::new();
+    for file in files.iter().filter(|f| !f.starts_with(src.join("tests"))) {
+        let text = std::fs::read_to_string(file).unwrap();
+        for call in ["emit_all(", ".emit(", "emit_to(", ".emit_event("] {
+            for (offset, _) in text.match_indices(call) {
+                let event = text[offset + call.len()..].trim_start();
+                let event = match call {
+                    // emit_to(label, event, payload): the event is the second argument
+                    "emit_to(" => event.split_once(',').map_or("", |(_, rest)| rest.trim_start()),
+                    _ => event,
+                };
+                // EventSink's AppHandle impl forwards its caller's constant
+                let forwarded = file.ends_with("services/events.rs") && event.starts_with("event,");
+                if !event.starts_with("events::") && !forwarded {
+                    let line = text[..offset].lines().count();
+                    offenders.push(format!("{}:{}", file.display(), line));
+                }
+            }
+        }
+    }
+    assert!(offenders.is_empty(), "emitted without an events:: constant: {:?}", offenders);
+}
+
+#[test]
+fn catalog_lists_every_event_once_with_an_example() {
+    let channels = event_channels();
+    let names: HashSet<&str> = channels.iter().map(|c| c.name).collect();
+    assert_eq!(names.len(), channels.len(), "duplicate event names in the catalog");
+
+    for name in [
+        events::DEVICE_DATA_RECEIVED,
+        events::DEVICE_CONNECTION_STATUS,
+        events::FILE_WATCHER_STATUS,
+        events::SCANNER_BARCODE,
+        events::WAKE_FROM_TRAY,
+        events::ATTACHMENT_OCR_COMPLETE,
+        events::ATTACHMENT_THUMBNAILS_PROGRESS,
+        events::HOUSEHOLD_SEARCH_REINDEX_PROGRESS,
+    ] {
+        assert!(names.contains(name), "{} missing from the catalog", name);
+    }
+    for channel in &channels {
+        assert!(channel.payload_example.is_object(), "{} has no example payload", channel.name);
+    }
+}
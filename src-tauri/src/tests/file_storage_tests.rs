@@ -6,9 +6,17 @@
 //! contracts of the `medical_attachments` table the service relies on.
 //!
 //! Cross-boundary file I/O (upload + download + delete on disk) is covered
-//! by the Layer 3 WebdriverIO suite against a real Tauri binary.
+//! by the Layer 3 WebdriverIO suite against a real Tauri binary; the `_dir`
+//! variants are exercised here against a temp storage directory.
 
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::models::medical::UploadFile;
+use crate::services::attachment_thumbnails::{AttachmentThumbnailService, PageRenderer};
 use crate::services::file_storage::FileStorageService;
+use crate::services::record_lock::{is_tracked, lock_record};
 use crate::services::patient::PatientService;
 use crate::models::dto::CreatePatientDto;
 use crate::test_utils::create_test_db_with_migrations;
@@ -235,3 +243,534 @@ async fn delete_single_attachment_leaves_others_intact() {
     let remaining: i64 = row.try_get("", "c").unwrap();
     assert_eq!(remaining, 1);
 }
+
+// ---------------------------------------------------------------------------
+// upload_attachments_to_dir — batch upload into a temp storage dir
+// ---------------------------------------------------------------------------
+
+fn upload_file(name: &str, bytes: &[u8]) -> UploadFile {
+    UploadFile {
+        file_name: name.to_string(),
+        file_data: bytes.to_vec(),
+        mime_type: "image/png".to_string(),
+        device_type: None,
+        device_name: None,
+        connection_method: None,
+        attachment_type: None,
+    }
+}
+
+#[tokio::test]
+async fn batch_upload_stores_all_files_in_submission_order() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+
+    let files = vec![
+        upload_file("page1.png", b"one"),
+        upload_file("page2.png", b"two"),
+        upload_file("page3.png", b"three"),
+    ];
+    let response = FileStorageService::upload_attachments_to_dir(
+        storage.path(), &db, record_id, files, 10, false,
+    ).await.unwrap();
+
+    assert_eq!(response.attachments.len(), 3);
+    assert!(response.results.iter().all(|r| r.error.is_none()));
+    for a in &response.attachments {
+        assert!(storage.path().join(&a.file_id).exists(), "file written for {}", a.original_name);
+    }
+
+    let rows = db.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT original_name, sort_order FROM medical_attachments \
+         WHERE medical_record_id = ? ORDER BY sort_order, id",
+        [record_id.into()],
+    )).await.unwrap();
+    let names: Vec<String> = rows.iter().map(|r| r.try_get("", "original_name").unwrap()).collect();
+    let orders: Vec<i64> = rows.iter().map(|r| r.try_get("", "sort_order").unwrap()).collect();
+    assert_eq!(names, vec!["page1.png", "page2.png", "page3.png"]);
+    assert_eq!(orders, vec![0, 1, 2]);
+}
+
+#[tokio::test]
+async fn batch_upload_rejects_whole_batch_on_invalid_file() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+
+    let too_big = vec![0u8; 1024 * 1024 + 1];
+    let files = vec![
+        upload_file("ok.png", b"fine"),
+        upload_file("huge.png", &too_big),
+    ];
+    let err = FileStorageService::upload_attachments_to_dir(
+        storage.path(), &db, record_id, files, 1, false,
+    ).await.unwrap_err();
+    assert!(err.contains("huge.png"), "error names the bad file: {}", err);
+
+    let row = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS c FROM medical_attachments WHERE medical_record_id = ?",
+        [record_id.into()],
+    )).await.unwrap().unwrap();
+    let count: i64 = row.try_get("", "c").unwrap();
+    assert_eq!(count, 0, "nothing stored when the batch is rejected");
+    assert_eq!(std::fs::read_dir(storage.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn batch_upload_skip_invalid_stores_the_rest() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+
+    let too_big = vec![0u8; 1024 * 1024 + 1];
+    let files = vec![
+        upload_file("a.png", b"a"),
+        upload_file("huge.png", &too_big),
+        upload_file("b.png", b"b"),
+    ];
+    let response = FileStorageService::upload_attachments_to_dir(
+        storage.path(), &db, record_id, files, 1, true,
+    ).await.unwrap();
+
+    assert_eq!(response.attachments.len(), 2);
+    assert_eq!(response.results.len(), 3);
+    assert!(response.results[1].error.is_some());
+    assert_eq!(response.attachments[0].sort_order, 0);
+    assert_eq!(response.attachments[1].sort_order, 1);
+}
+
+// ---------------------------------------------------------------------------
+// identical content is stored once
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn identical_uploads_share_one_file_until_both_are_deleted() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let xml = b"<Sample><WBC>7.2</WBC></Sample>";
+
+    let first = FileStorageService::upload_attachment_to_dir(storage.path(), &db, record_id, upload_file("run1.xml", xml))
+        .await
+        .unwrap();
+    let second = FileStorageService::upload_attachment_to_dir(storage.path(), &db, record_id, upload_file("run2.xml", xml))
+        .await
+        .unwrap();
+    assert_ne!(first.file_id, second.file_id);
+    assert_eq!(std::fs::read_dir(storage.path()).unwrap().count(), 1, "the bytes are written once");
+    let downloaded = FileStorageService::download_attachment_in_dir(storage.path(), &db, second.id).await.unwrap();
+    assert_eq!(downloaded.file_data, xml);
+
+    FileStorageService::delete_attachment_in_dir(storage.path(), &db, first.id).await.unwrap();
+    let downloaded = FileStorageService::download_attachment_in_dir(storage.path(), &db, second.id).await.unwrap();
+    assert_eq!(downloaded.file_data, xml, "still readable through the other attachment");
+
+    FileStorageService::delete_attachment_in_dir(storage.path(), &db, second.id).await.unwrap();
+    assert_eq!(std::fs::read_dir(storage.path()).unwrap().count(), 0, "last reference removes the file");
+}
+
+#[tokio::test]
+async fn batch_upload_stores_repeated_content_once() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+
+    FileStorageService::upload_attachment_to_dir(storage.path(), &db, record_id, upload_file("scan.png", b"scan"))
+        .await
+        .unwrap();
+    let files = vec![
+        upload_file("scan-again.png", b"scan"),
+        upload_file("new.png", b"new"),
+        upload_file("new-again.png", b"new"),
+    ];
+    let response = FileStorageService::upload_attachments_to_dir(storage.path(), &db, record_id, files, 10, false)
+        .await
+        .unwrap();
+
+    assert_eq!(response.attachments.len(), 3);
+    assert_eq!(std::fs::read_dir(storage.path()).unwrap().count(), 2);
+    for attachment in &response.attachments {
+        let data = FileStorageService::download_attachment_in_dir(storage.path(), &db, attachment.id).await.unwrap();
+        let expected: &[u8] = if attachment.original_name.starts_with("scan") { b"scan" } else { b"new" };
+        assert_eq!(data.file_data, expected);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// record_lock — attachment mutations on one record are serialized
+// ---------------------------------------------------------------------------
+
+async fn attachment_names(db: &DatabaseConnection, record_id: i64) -> Vec<String> {
+    let rows = db.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT original_name FROM medical_attachments WHERE medical_record_id = ? ORDER BY id",
+        [record_id.into()],
+    )).await.unwrap();
+    rows.iter().map(|r| r.try_get("", "original_name").unwrap()).collect()
+}
+
+#[tokio::test]
+async fn regenerate_and_delete_on_same_record_do_not_interleave() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+
+    let seeded = FileStorageService::upload_attachments_to_dir(
+        storage.path(), &db, record_id,
+        vec![upload_file("keep.xml", b"<a/>"), upload_file("remove.xml", b"<b/>")],
+        10, false,
+    ).await.unwrap();
+    let victim = seeded.attachments[1].clone();
+
+    // Mirrors regenerate_pdf_from_medical_record: read the attachment set,
+    // do slow work, then attach the generated PDF, all under the lock
+    let regenerate = async {
+        let _lock = lock_record(record_id).await;
+        let before = attachment_names(&db, record_id).await;
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        let after = attachment_names(&db, record_id).await;
+        let mut pdf = upload_file("report.pdf", b"%PDF-1.4");
+        pdf.mime_type = "application/pdf".to_string();
+        pdf.attachment_type = Some("generated_pdf".to_string());
+        FileStorageService::upload_attachments_to_dir(
+            storage.path(), &db, record_id, vec![pdf], 10, false,
+        ).await.unwrap();
+        (before, after)
+    };
+    // Mirrors delete_medical_attachment
+    let delete = async {
+        let owner = FileStorageService::get_attachment_record_id(&db, victim.id).await.unwrap();
+        let _lock = lock_record(owner).await;
+        FileStorageService::delete_attachment_in_dir(storage.path(), &db, victim.id).await.unwrap();
+    };
+
+    let ((before, after), ()) = tokio::join!(regenerate, delete);
+
+    assert_eq!(before, after, "attachment set changed while regenerate held the lock");
+    assert_eq!(before, vec!["keep.xml", "remove.xml"]);
+    assert_eq!(attachment_names(&db, record_id).await, vec!["keep.xml", "report.pdf"]);
+    assert!(!storage.path().join(&victim.file_id).exists());
+    assert!(!is_tracked(record_id), "lock entry pruned once released");
+}
+
+#[tokio::test]
+async fn record_locks_are_independent_per_record() {
+    let first = lock_record(900_001).await;
+    // A different record must not wait on the first one
+    let second = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        lock_record(900_002),
+    ).await;
+    assert!(second.is_ok());
+
+    // The same record does wait until the holder releases
+    let blocked = tokio::time::timeout(
+        std::time::Duration::from_millis(50),
+        lock_record(900_001),
+    ).await;
+    assert!(blocked.is_err());
+
+    drop(first);
+    drop(second);
+    assert!(!is_tracked(900_001));
+    assert!(!is_tracked(900_002));
+}
+
+// ---------------------------------------------------------------------------
+// reveal in file manager
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn reveal_materializes_file_and_runs_platform_command() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+    std::fs::write(storage.path().join("file-abc"), b"%PDF-1.4").unwrap();
+    let attachment_id = insert_attachment(&db, record_id, "file-abc", "blood panel.pdf", "file").await;
+
+    let path = FileStorageService::materialize_attachment_in_dir(
+        storage.path(), target.path(), &db, attachment_id,
+    ).await.unwrap();
+    assert_eq!(path, target.path().join("blood panel.pdf"));
+    assert_eq!(std::fs::read(&path).unwrap(), b"%PDF-1.4");
+
+    let mut programs = Vec::new();
+    let result = FileStorageService::reveal_in_file_manager_with(&path, |cmd, _wait| {
+        programs.push(cmd.get_program().to_string_lossy().into_owned());
+        Ok(())
+    });
+
+    if cfg!(any(target_os = "macos", target_os = "windows", target_os = "linux")) {
+        assert!(result.is_ok(), "got: {:?}", result);
+        let expected = if cfg!(target_os = "macos") {
+            "open"
+        } else if cfg!(target_os = "windows") {
+            "explorer"
+        } else {
+            "dbus-send"
+        };
+        assert_eq!(programs, vec![expected.to_string()], "first candidate succeeds, no fallback");
+    }
+}
+
+#[tokio::test]
+async fn reveal_reports_missing_file_manager() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scan.png");
+    std::fs::write(&path, b"png").unwrap();
+
+    let mut attempts = 0;
+    let err = FileStorageService::reveal_in_file_manager_with(&path, |_cmd, _wait| {
+        attempts += 1;
+        Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+    }).unwrap_err();
+
+    assert!(err.starts_with("No file manager available"), "got: {}", err);
+    if cfg!(target_os = "linux") {
+        assert_eq!(attempts, 2, "falls back from D-Bus to opening the folder");
+    }
+}
+
+#[tokio::test]
+async fn materialize_strips_directories_from_stored_name() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+    std::fs::write(storage.path().join("file-evil"), b"x").unwrap();
+    let attachment_id = insert_attachment(&db, record_id, "file-evil", "../../etc/passwd", "file").await;
+
+    let path = FileStorageService::materialize_attachment_in_dir(
+        storage.path(), target.path(), &db, attachment_id,
+    ).await.unwrap();
+
+    assert_eq!(path, target.path().join("passwd"));
+    assert_eq!(FileStorageService::sanitize_file_name("..\\..\\report?.pdf"), "report_.pdf");
+    assert_eq!(FileStorageService::sanitize_file_name(".."), "attachment");
+}
+
+// ---------------------------------------------------------------------------
+// blocking work on a materialized attachment
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn query_is_not_blocked_by_in_progress_render() {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}?mode=rwc", dir.path().join("render.db").display());
+    let storage = tempfile::tempdir().unwrap();
+    let target = tempfile::tempdir().unwrap();
+    std::fs::write(storage.path().join("file-scan"), b"%PDF-1.4").unwrap();
+
+    let setup = sea_orm::Database::connect(url.as_str()).await.unwrap();
+    crate::database::migrations::run_migrations(setup.get_sqlite_connection_pool()).await.unwrap();
+    let record_id = seed_record(&setup).await;
+    let attachment_id = insert_attachment(&setup, record_id, "file-scan", "scan.pdf", "file").await;
+    setup.close().await.unwrap();
+
+    // One connection: if the render held it, the query below would time out
+    let mut options = sea_orm::ConnectOptions::new(url);
+    options
+        .max_connections(1)
+        .acquire_timeout(std::time::Duration::from_secs(2))
+        .sqlx_logging(false);
+    let db = sea_orm::Database::connect(options).await.unwrap();
+
+    let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let render = FileStorageService::with_materialized_attachment_in_dir(
+        storage.path(), target.path(), &db, attachment_id,
+        move |path| {
+            started_tx.send(()).unwrap();
+            // Stand-in for a slow render: runs until the query has finished
+            release_rx.recv().unwrap();
+            std::fs::read(&path).map(|bytes| bytes.len()).map_err(|e| e.to_string())
+        },
+    );
+    let query = async {
+        started_rx.await.unwrap();
+        let row = db.query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT COUNT(*) AS n FROM medical_attachments".to_string(),
+        )).await;
+        release_tx.send(()).unwrap();
+        row
+    };
+
+    let (rendered, row) = tokio::join!(render, query);
+    let count: i64 = row.expect("query ran during the render").unwrap().try_get("", "n").unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(rendered.unwrap(), 8);
+}
+
+// ---------------------------------------------------------------------------
+// External attachments — referenced in place, never copied into storage
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn external_attachment_is_read_from_its_own_path() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let share = tempfile::tempdir().unwrap();
+    let source = share.path().join("ct-scan.dcm");
+    std::fs::write(&source, b"DICM").unwrap();
+
+    let attachment = FileStorageService::attach_external_file(
+        &db, record_id, &source.display().to_string(), Some("application/dicom".to_string()),
+    ).await.unwrap();
+    assert!(attachment.is_external);
+    assert_eq!(attachment.original_name, "ct-scan.dcm");
+    assert_eq!(attachment.file_size, Some(4));
+    assert_eq!(std::fs::read_dir(storage.path()).unwrap().count(), 0, "nothing copied into storage");
+
+    let data = FileStorageService::download_attachment_in_dir(storage.path(), &db, attachment.id)
+        .await
+        .unwrap();
+    assert_eq!(data.file_data, b"DICM");
+    assert_eq!(data.mime_type, "application/dicom");
+
+    let target = tempfile::tempdir().unwrap();
+    let path = FileStorageService::materialize_attachment_in_dir(storage.path(), target.path(), &db, attachment.id)
+        .await
+        .unwrap();
+    assert_eq!(path, source, "materializing hands out the external path");
+
+    FileStorageService::delete_attachment_in_dir(storage.path(), &db, attachment.id).await.unwrap();
+    assert!(source.exists(), "deleting the attachment leaves the external file alone");
+}
+
+#[tokio::test]
+async fn external_attachment_reports_missing_file() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let share = tempfile::tempdir().unwrap();
+    let source = share.path().join("xray.png");
+
+    let err = FileStorageService::attach_external_file(&db, record_id, &source.display().to_string(), None)
+        .await
+        .unwrap_err();
+    assert!(err.starts_with("External file not found"), "got: {}", err);
+    let err = FileStorageService::attach_external_file(&db, record_id, "relative/xray.png", None)
+        .await
+        .unwrap_err();
+    assert!(err.contains("must be absolute"), "got: {}", err);
+
+    std::fs::write(&source, b"png").unwrap();
+    let attachment = FileStorageService::attach_external_file(&db, record_id, &source.display().to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(attachment.mime_type.as_deref(), Some("application/octet-stream"));
+    std::fs::remove_file(&source).unwrap();
+
+    let err = FileStorageService::download_attachment_in_dir(storage.path(), &db, attachment.id)
+        .await
+        .unwrap_err();
+    assert!(err.starts_with("External file is no longer available"), "got: {}", err);
+}
+
+// ---------------------------------------------------------------------------
+// PDF thumbnail cache
+// ---------------------------------------------------------------------------
+
+/// A renderer that counts its calls and returns a fake PNG.
+fn counting_renderer() -> (PageRenderer, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let render: PageRenderer = Arc::new(move |pdf_path: &Path, page_index: u32, width: u32| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        assert!(pdf_path.exists());
+        Ok(format!("png p{} w{}", page_index, width).into_bytes())
+    });
+    (render, calls)
+}
+
+#[tokio::test]
+async fn prewarmed_thumbnails_are_served_from_the_cache() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    let mut pdfs = Vec::new();
+    for file_id in ["scan-1", "scan-2", "scan-3"] {
+        std::fs::write(storage.path().join(file_id), b"%PDF-1.4").unwrap();
+        pdfs.push(insert_attachment(&db, record_id, file_id, &format!("{}.pdf", file_id), "file").await);
+    }
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, mime_type) \
+         VALUES (?, 'photo', 'photo.png', 'image/png')",
+        [record_id.into()],
+    )).await.unwrap();
+
+    let ids = AttachmentThumbnailService::pdf_attachment_ids(&db, Some(record_id), None).await.unwrap();
+    assert_eq!(ids, pdfs, "only the PDFs are prewarmed");
+
+    let (render, calls) = counting_renderer();
+    let reported = Mutex::new(Vec::new());
+    let progress = AttachmentThumbnailService::prewarm(
+        storage.path().to_path_buf(),
+        cache.path().to_path_buf(),
+        Arc::new(db.db.clone()),
+        ids.clone(),
+        300,
+        render.clone(),
+        |p| reported.lock().unwrap().push((p.completed, p.total)),
+    ).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert!(progress.iter().all(|p| !p.cached && p.error.is_none()), "{:?}", progress);
+    assert_eq!(reported.into_inner().unwrap(), vec![(1, 3), (2, 3), (3, 3)]);
+
+    // Opening the files view renders on demand: every page is already cached
+    for &attachment_id in &pdfs {
+        let path = AttachmentThumbnailService::render_page(
+            storage.path(), cache.path(), &db, attachment_id, 0, 300, false, render.clone(),
+        ).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"png p0 w300");
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 3, "no re-render after prewarming");
+
+    // A second prewarm finds them cached; another width is a new thumbnail
+    let again = AttachmentThumbnailService::prewarm(
+        storage.path().to_path_buf(), cache.path().to_path_buf(), Arc::new(db.db.clone()),
+        ids.clone(), 300, render.clone(), |_| {},
+    ).await;
+    assert!(again.iter().all(|p| p.cached));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    AttachmentThumbnailService::render_page(
+        storage.path(), cache.path(), &db, pdfs[0], 0, 600, false, render,
+    ).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn prewarm_reports_a_failed_render_and_carries_on() {
+    let db = create_test_db_with_migrations().await;
+    let record_id = seed_record(&db).await;
+    let storage = tempfile::tempdir().unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    std::fs::write(storage.path().join("scan-ok"), b"%PDF-1.4").unwrap();
+    let ok = insert_attachment(&db, record_id, "scan-ok", "ok.pdf", "file").await;
+    // No file on disk behind this one
+    let missing = insert_attachment(&db, record_id, "scan-missing", "missing.pdf", "file").await;
+
+    let (render, calls) = counting_renderer();
+    let progress = AttachmentThumbnailService::prewarm(
+        storage.path().to_path_buf(), cache.path().to_path_buf(), Arc::new(db.db.clone()),
+        vec![missing, ok], 300, render, |_| {},
+    ).await;
+
+    assert_eq!(progress.len(), 2);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    let failed = progress.iter().find(|p| p.attachment_id == missing).unwrap();
+    assert!(failed.error.is_some());
+    assert!(AttachmentThumbnailService::cached_preview(
+        &AttachmentThumbnailService::preview_path(cache.path(), ok, 0, 300)
+    ).is_some());
+}
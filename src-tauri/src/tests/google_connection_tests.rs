@@ -0,0 +1,173 @@
+//! `GoogleConnectionService::test_connection_with` against a fake Google.
+
+use chrono::{Duration, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use std::sync::Mutex;
+
+use crate::models::google_calendar::GoogleCalendar;
+use crate::services::google_connection::{GoogleClient, GoogleConnectionService, GoogleConnectionStatus};
+use crate::test_utils::create_test_db_with_migrations;
+
+/// Stands in for Google: refreshes succeed or fail as configured, and
+/// the token each calendar listing was made with is recorded.
+struct FakeGoogle {
+    refresh: Result<(String, i64), String>,
+    listed_with: Mutex<Vec<String>>,
+}
+
+impl FakeGoogle {
+    fn new(refresh: Result<(String, i64), String>) -> Self {
+        Self { refresh, listed_with: Mutex::new(Vec::new()) }
+    }
+}
+
+impl GoogleClient for FakeGoogle {
+    async fn refresh_access_token(&self, _refresh_token: String) -> Result<(String, i64), String> {
+        self.refresh.clone()
+    }
+
+    async fn list_calendars(&self, access_token: &str) -> Result<Vec<GoogleCalendar>, String> {
+        self.listed_with.lock().unwrap().push(access_token.to_string());
+        let calendar = |id: &str, summary: &str, primary: bool, access_role: &str| GoogleCalendar {
+            id: id.to_string(),
+            summary: summary.to_string(),
+            primary,
+            access_role: access_role.to_string(),
+        };
+        Ok(vec![
+            calendar("vet@example.com", "vet@example.com", true, "owner"),
+            calendar("clinic@example.com", "Clinic Appointments", false, "writer"),
+            calendar("holidays@example.com", "Public holidays", false, "reader"),
+        ])
+    }
+}
+
+/// Store tokens for the default user that expire `expires_in` from now.
+async fn store_tokens(db: &DatabaseConnection, expires_in: Duration) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE google_calendar_settings SET access_token = 'old-token', refresh_token = 'refresh', \
+         token_expires_at = ? WHERE user_id = 'default'",
+        [(Utc::now() + expires_in).to_rfc3339().into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn connected_refreshes_an_expired_token_and_lists_calendars() {
+    let db = create_test_db_with_migrations().await;
+    store_tokens(&db, Duration::minutes(-10)).await;
+    let google = FakeGoogle::new(Ok(("new-token".to_string(), 3600)));
+
+    let result = GoogleConnectionService::test_connection_with(&db, &google).await.unwrap();
+
+    assert!(result.ok);
+    assert_eq!(result.status, GoogleConnectionStatus::Connected);
+    assert_eq!(result.calendars.len(), 3);
+    assert!(result.error.is_none());
+    assert_eq!(*google.listed_with.lock().unwrap(), vec!["new-token".to_string()]);
+
+    let stored: String = db
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT access_token FROM google_calendar_settings WHERE user_id = 'default'".to_string(),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get("", "access_token")
+        .unwrap();
+    assert_eq!(stored, "new-token");
+}
+
+#[tokio::test]
+async fn failed_refresh_is_reported_without_listing_calendars() {
+    let db = create_test_db_with_migrations().await;
+    store_tokens(&db, Duration::minutes(-10)).await;
+    let google = FakeGoogle::new(Err("invalid_grant".to_string()));
+
+    let result = GoogleConnectionService::test_connection_with(&db, &google).await.unwrap();
+
+    assert!(!result.ok);
+    assert_eq!(result.status, GoogleConnectionStatus::RefreshFailed);
+    assert_eq!(result.error.as_deref(), Some("invalid_grant"));
+    assert!(result.calendars.is_empty());
+    assert!(google.listed_with.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn without_stored_tokens_it_is_not_connected() {
+    let db = create_test_db_with_migrations().await;
+    let google = FakeGoogle::new(Ok(("unused".to_string(), 3600)));
+
+    let result = GoogleConnectionService::test_connection_with(&db, &google).await.unwrap();
+
+    assert!(!result.ok);
+    assert_eq!(result.status, GoogleConnectionStatus::NotConnected);
+}
+
+#[tokio::test]
+async fn changing_the_sync_calendar_clears_mappings_of_the_old_one() {
+    let db = create_test_db_with_migrations().await;
+    store_tokens(&db, Duration::hours(1)).await;
+    let google = FakeGoogle::new(Ok(("unused".to_string(), 3600)));
+    for sql in [
+        "UPDATE google_calendar_settings SET calendar_id = 'vet@example.com' WHERE user_id = 'default'",
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)",
+    ] {
+        db.execute_unprepared(sql).await.unwrap();
+    }
+    for id in 1..=2 {
+        db.execute_unprepared(&format!(
+            "INSERT INTO appointments (id, patient_id, title, start_time, end_time, status, created_by) \
+             VALUES ({id}, 1, 'Checkup', '2026-05-0{id}T09:00:00Z', '2026-05-0{id}T09:30:00Z', 'scheduled', 'test')"
+        ))
+        .await
+        .unwrap();
+        db.execute_unprepared(&format!(
+            "INSERT INTO calendar_event_mappings (appointment_id, event_id, calendar_id) VALUES ({id}, 'evt-{id}', 'vet@example.com')"
+        ))
+        .await
+        .unwrap();
+    }
+
+    // A read-only or unknown calendar is refused and nothing changes
+    assert!(GoogleConnectionService::set_sync_calendar_with(&db, &google, "holidays@example.com").await.is_err());
+    assert!(GoogleConnectionService::set_sync_calendar_with(&db, &google, "missing@example.com").await.is_err());
+    assert_eq!(mapping_count(&db).await, 2);
+
+    // Re-selecting the current calendar keeps its mappings
+    let same = GoogleConnectionService::set_sync_calendar_with(&db, &google, "vet@example.com").await.unwrap();
+    assert_eq!(same.cleared_mappings, 0);
+    assert_eq!(mapping_count(&db).await, 2);
+
+    let change = GoogleConnectionService::set_sync_calendar_with(&db, &google, "clinic@example.com").await.unwrap();
+    assert_eq!(change.calendar.summary, "Clinic Appointments");
+    assert_eq!(change.cleared_mappings, 2);
+    assert_eq!(mapping_count(&db).await, 0);
+
+    let calendar_id: String = db
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT calendar_id FROM google_calendar_settings WHERE user_id = 'default'".to_string(),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get("", "calendar_id")
+        .unwrap();
+    assert_eq!(calendar_id, "clinic@example.com");
+}
+
+async fn mapping_count(db: &DatabaseConnection) -> i64 {
+    db.query_one(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS count FROM calendar_event_mappings".to_string(),
+    ))
+    .await
+    .unwrap()
+    .unwrap()
+    .try_get("", "count")
+    .unwrap()
+}
@@ -0,0 +1,133 @@
+//! Tests for the per-household data package (`export_household_data_package`).
+
+use crate::database::queries::household as q;
+use crate::models::household::*;
+use crate::services::household_export::HouseholdExportService;
+use crate::test_utils::create_test_db_with_migrations;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+async fn create_household(db: &DatabaseConnection) -> i32 {
+    let dto = CreateHouseholdWithPeopleDto {
+        household: CreateHouseholdDto {
+            household_name: Some("Petrovi".to_string()),
+            address: Some("Partizanska 12".to_string()),
+            city: None,
+            postal_code: None,
+            notes: None,
+        },
+        people: vec![
+            CreatePersonWithContactsDto {
+                person: CreatePersonDto {
+                    first_name: "Ana".to_string(),
+                    last_name: "Petrova".to_string(),
+                    is_primary: Some(true),
+                },
+                contacts: vec![CreateContactDto {
+                    contact_type: "mobile".to_string(),
+                    contact_value: "070 123 456".to_string(),
+                    is_primary: Some(true),
+                }],
+            },
+            CreatePersonWithContactsDto {
+                person: CreatePersonDto {
+                    first_name: "Marko".to_string(),
+                    last_name: "Petrov".to_string(),
+                    is_primary: Some(false),
+                },
+                contacts: vec![],
+            },
+        ],
+    };
+    q::create_household_with_people(db, dto).await.unwrap().created.household.id
+}
+
+async fn add_patient(db: &DatabaseConnection, household_id: i32, name: &str, is_active: bool) -> i64 {
+    let patient_id = db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO patients (name, species_id, is_active) VALUES (?, 1, ?)",
+            [name.into(), is_active.into()],
+        ))
+        .await
+        .unwrap()
+        .last_insert_id() as i64;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO patient_households (patient_id, household_id) VALUES (?, ?)",
+        [patient_id.into(), household_id.into()],
+    ))
+    .await
+    .unwrap();
+    patient_id
+}
+
+#[tokio::test]
+async fn package_contains_linked_patients_and_people() {
+    let db = create_test_db_with_migrations().await;
+    let household_id = create_household(&db).await;
+    let rex = add_patient(&db, household_id, "Rex", true).await;
+    add_patient(&db, household_id, "Mila", true).await;
+    add_patient(&db, household_id, "Old Tom", false).await;
+
+    // A patient of another household stays out
+    let other = create_household(&db).await;
+    add_patient(&db, other, "Stranger", true).await;
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_records (patient_id, record_type, name, description, is_archived, created_by) \
+         VALUES (?, 'procedure', 'Vaccination', 'Rabies', 0, 'dr.stojanov'), \
+                (?, 'note', 'Old note', '', 1, 'dr.stojanov')",
+        [rex.into(), rex.into()],
+    ))
+    .await
+    .unwrap();
+
+    let package = HouseholdExportService::export_data_package(&db, household_id, false)
+        .await
+        .unwrap();
+
+    assert_eq!(package.household.id, household_id);
+    let mut people: Vec<&str> = package.people.iter().map(|p| p.first_name.as_str()).collect();
+    people.sort();
+    assert_eq!(people, ["Ana", "Marko"]);
+
+    let mut patients: Vec<&str> = package
+        .patients
+        .iter()
+        .filter_map(|p| p.patient.name.as_deref())
+        .collect();
+    patients.sort();
+    assert_eq!(patients, ["Mila", "Rex"], "inactive and unrelated patients are left out");
+
+    let rex_export = package.patients.iter().find(|p| p.patient.id == rex).unwrap();
+    assert_eq!(rex_export.medical_records.len(), 2, "archived records are part of the package");
+
+    assert_eq!(package.manifest.counts.people, 2);
+    assert_eq!(package.manifest.counts.contacts, 1);
+    assert_eq!(package.manifest.counts.patients, 2);
+    assert_eq!(package.manifest.counts.medical_records, 2);
+
+    let json = serde_json::to_string(&package).unwrap();
+    assert!(!json.contains("dr.stojanov"), "staff names are redacted");
+}
+
+#[tokio::test]
+async fn deleted_data_needs_include_deleted() {
+    let db = create_test_db_with_migrations().await;
+    let household_id = create_household(&db).await;
+    add_patient(&db, household_id, "Old Tom", false).await;
+    q::delete_household(&db, household_id, false).await.unwrap();
+
+    let err = HouseholdExportService::export_data_package(&db, household_id, false)
+        .await
+        .unwrap_err();
+    assert!(err.contains("not found"));
+
+    let package = HouseholdExportService::export_data_package(&db, household_id, true)
+        .await
+        .unwrap();
+    assert!(package.manifest.include_deleted);
+    assert_eq!(package.patients.len(), 1);
+    assert_eq!(package.people.len(), 2);
+}
@@ -112,6 +112,28 @@ fn validation_rejects_email_without_at_sign() {
     assert!(err.contains("Invalid email"));
 }
 
+#[test]
+fn validate_contact_checks_email_syntax() {
+    assert_eq!(validate_contact("email", "  ana@vet.example.mk ").unwrap(), "ana@vet.example.mk");
+    for bad in ["ana", "@vet.mk", "ana@", "ana@vet", "ana@vet..mk", "ana@@vet.mk", "ana petrova@vet.mk"] {
+        let err = validate_contact("email", bad).unwrap_err();
+        assert!(err.contains("Invalid email"), "{} -> {}", bad, err);
+    }
+}
+
+#[test]
+fn validate_contact_normalizes_phone_numbers() {
+    assert_eq!(validate_contact("phone", "(070) 340-846").unwrap(), "070340846");
+    assert_eq!(validate_contact("mobile", "+389 70 340 846").unwrap(), "+38970340846");
+    assert_eq!(validate_contact("work_phone", "02/3123.456").unwrap(), "023123456");
+
+    for bad in ["070 340 846 ext 2", "070+340846", "12-34", "+1234567890123456"] {
+        let err = validate_contact("phone", bad).unwrap_err();
+        assert!(err.contains("Invalid phone number"), "{} -> {}", bad, err);
+    }
+    assert!(validate_contact("fax", "070340846").unwrap_err().contains("Invalid contact type"));
+}
+
 #[test]
 fn validation_accepts_all_four_contact_types() {
     for ct in ["phone", "email", "mobile", "work_phone"] {
@@ -133,7 +155,8 @@ async fn create_single_person_household_succeeds() {
         dto("Smith family", vec![person("Alice", "Smith", true)]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     assert_eq!(result.household.household_name.as_deref(), Some("Smith family"));
     assert_eq!(result.people.len(), 1);
@@ -163,7 +186,8 @@ async fn create_multi_person_household_first_defaults_to_primary() {
         ]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     assert_eq!(result.people.len(), 2);
     assert!(result.people[0].is_primary, "first person defaults to primary when none marked");
@@ -183,7 +207,8 @@ async fn create_multi_person_household_honors_explicit_false_primary() {
         ]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     assert!(!result.people.iter().any(|p| p.is_primary), "no one is primary when all explicit false");
 }
@@ -223,7 +248,8 @@ async fn create_with_contacts_persists_them() {
         },
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     let contacts = &result.people[0].contacts;
     assert_eq!(contacts.len(), 2);
@@ -248,7 +274,8 @@ async fn create_household_with_nullable_fields_omitted() {
         },
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     assert!(result.household.household_name.is_none());
     assert!(result.household.address.is_none());
@@ -269,6 +296,22 @@ async fn create_household_rejects_validation_failure_before_touching_db() {
     assert_eq!(household_count(&test_db).await, initial_count, "no row should be inserted");
 }
 
+#[tokio::test]
+async fn create_stores_phone_numbers_normalized() {
+    let test_db = create_test_db_with_migrations().await;
+    let result = q::create_household_with_people(
+        &test_db,
+        dto("Petrov family", vec![person_with("Ana", "Petrov", "mobile", "(070) 340-846")]),
+    )
+    .await
+    .unwrap()
+    .created;
+
+    assert_eq!(result.people[0].contacts[0].contact_value, "070340846");
+    let stored = q::get_person_with_contacts(&test_db, result.people[0].id).await.unwrap().unwrap();
+    assert_eq!(stored.contacts[0].contact_value, "070340846");
+}
+
 // ---------------------------------------------------------------------------
 // get_household_with_people
 // ---------------------------------------------------------------------------
@@ -281,7 +324,8 @@ async fn get_household_with_people_roundtrips() {
         dto("Roundtrip", vec![person("Alice", "S", true)]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     let fetched = q::get_household_with_people(&test_db, created.household.id as i32).await.unwrap().expect("Some(household)");
     assert_eq!(fetched.household.id, created.household.id);
@@ -308,7 +352,8 @@ async fn update_household_name_persists() {
         dto("Original", vec![person("A", "B", true)]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     q::update_household(
         &test_db,
@@ -345,7 +390,8 @@ async fn update_household_none_preserves_existing_via_coalesce() {
         },
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     q::update_household(
         &test_db,
@@ -365,6 +411,45 @@ async fn update_household_none_preserves_existing_via_coalesce() {
     assert_eq!(fetched.household.notes.as_deref(), Some("Added notes"));
 }
 
+// ---------------------------------------------------------------------------
+// update_person
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn making_a_person_primary_demotes_the_previous_primary() {
+    let test_db = create_test_db_with_migrations().await;
+    let created = q::create_household_with_people(
+        &test_db,
+        dto("Petrov family", vec![person("Ana", "Petrov", true), person("Boris", "Petrov", false)]),
+    )
+    .await
+    .unwrap()
+    .created;
+    let other = q::create_household_with_people(&test_db, dto("Other", vec![person("Cveta", "Ivanova", true)]))
+        .await
+        .unwrap()
+        .created;
+    let (ana, boris) = (created.people[0].id, created.people[1].id);
+
+    q::update_person(&test_db, boris, &serde_json::json!({ "isPrimary": true })).await.unwrap();
+
+    let primaries = test_db
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT id FROM people WHERE household_id = ? AND is_primary = 1",
+            [created.household.id.into()],
+        ))
+        .await
+        .unwrap();
+    let ids: Vec<i32> = primaries.iter().map(|r| r.try_get("", "id").unwrap()).collect();
+    assert_eq!(ids, vec![boris]);
+    assert!(!q::get_person_with_contacts(&test_db, ana).await.unwrap().unwrap().is_primary);
+    assert!(
+        q::get_person_with_contacts(&test_db, other.people[0].id).await.unwrap().unwrap().is_primary,
+        "other households keep their primary"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // delete_household
 // ---------------------------------------------------------------------------
@@ -377,9 +462,10 @@ async fn delete_household_removes_row() {
         dto("Doomed", vec![person("A", "B", true)]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
-    q::delete_household(&test_db, created.household.id as i32).await.unwrap();
+    q::delete_household(&test_db, created.household.id as i32, false).await.unwrap();
     let result = q::get_household_with_people(&test_db, created.household.id as i32).await.unwrap();
     assert!(result.is_none(), "should be gone");
 }
@@ -408,10 +494,11 @@ async fn delete_household_cascades_to_people_and_contacts() {
         },
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
     let person_id = created.people[0].id as i64;
 
-    q::delete_household(&test_db, created.household.id as i32).await.unwrap();
+    q::delete_household(&test_db, created.household.id as i32, true).await.unwrap();
 
     // people row should be gone (FK CASCADE expected)
     let person_count: i64 = test_db.query_one(Statement::from_sql_and_values(
@@ -430,14 +517,130 @@ async fn delete_household_cascades_to_people_and_contacts() {
     assert_eq!(contact_count, 0, "contacts should cascade-delete with person");
 }
 
+#[tokio::test]
+async fn soft_delete_and_restore_round_trip_reindexes_search() {
+    let test_db = create_test_db_with_migrations().await;
+    let created = q::create_household_with_people(
+        &test_db,
+        dto("Misclick", vec![person("Ana", "Misclick", true)]),
+    )
+    .await
+    .unwrap()
+    .created;
+    let id = created.household.id;
+    test_db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (50, 'Rex', 1)").await.unwrap();
+    test_db.execute_unprepared(&format!(
+        "INSERT INTO patient_households (patient_id, household_id, is_primary) VALUES (50, {}, 1)",
+        id
+    )).await.unwrap();
+
+    q::delete_household(&test_db, id, false).await.unwrap();
+    let found = household_search::search_households(&test_db, "Misclick", None, None).await.unwrap();
+    assert!(found.results.is_empty(), "soft-deleted households leave search");
+    let listed = household_search::search_households(&test_db, "", None, None).await.unwrap();
+    assert_eq!(listed.total, 0, "and the unfiltered list");
+    let patient = crate::services::patient::PatientService::get_by_id(&test_db, 50).await.unwrap();
+    assert!(patient.is_some(), "a patient whose only household is deleted still loads");
+
+    q::restore_household(&test_db, id).await.unwrap();
+    let found = household_search::search_households(&test_db, "Misclick", None, None).await.unwrap();
+    assert_eq!(found.results.len(), 1);
+    assert_eq!(found.results[0].people.len(), 1, "people survive a soft delete");
+    let again = q::restore_household(&test_db, id).await.unwrap_err();
+    assert!(again.contains("No deleted household"), "unexpected error: {}", again);
+}
+
+#[tokio::test]
+async fn purge_removes_only_households_deleted_long_enough_ago() {
+    let test_db = create_test_db_with_migrations().await;
+    let mut ids = Vec::new();
+    for name in ["Old", "Recent", "Live"] {
+        ids.push(q::create_household_with_people(&test_db, dto(name, vec![person("A", name, true)])).await.unwrap().created.household.id);
+    }
+    q::delete_household(&test_db, ids[0], false).await.unwrap();
+    q::delete_household(&test_db, ids[1], false).await.unwrap();
+    test_db.execute_unprepared(&format!(
+        "UPDATE households SET deleted_at = '2020-01-01T00:00:00+00:00' WHERE id = {}",
+        ids[0]
+    )).await.unwrap();
+
+    assert_eq!(q::purge_households(&test_db, 30).await.unwrap(), 1);
+    let remaining: i64 = test_db.query_one(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS c FROM households".to_string(),
+    )).await.unwrap().unwrap().try_get("", "c").unwrap();
+    assert_eq!(remaining, 2);
+    q::restore_household(&test_db, ids[1]).await.unwrap();
+}
+
 #[tokio::test]
 async fn delete_nonexistent_household_does_not_error() {
     let test_db = create_test_db_with_migrations().await;
     // DELETE … WHERE id = 99999 just affects 0 rows; should be Ok.
-    let result = q::delete_household(&test_db, 99999).await;
+    let result = q::delete_household(&test_db, 99999, false).await;
     assert!(result.is_ok());
 }
 
+// ---------------------------------------------------------------------------
+// patient households
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn patient_linked_to_two_households_lists_primary_first() {
+    let test_db = create_test_db_with_migrations().await;
+    let mut ids = Vec::new();
+    for name in ["Home", "Foster", "New home"] {
+        ids.push(q::create_household_with_people(&test_db, dto(name, vec![person("A", name, true)])).await.unwrap().created.household.id);
+    }
+    test_db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (50, 'Rex', 1)").await.unwrap();
+    test_db.execute_unprepared(&format!(
+        "INSERT INTO patient_households (patient_id, household_id, relationship_type, is_primary) VALUES (50, {}, 'Pet', 1)",
+        ids[0]
+    )).await.unwrap();
+
+    let added = q::add_secondary_household(&test_db, 50, ids[1], Some("Foster".to_string())).await.unwrap();
+    assert_eq!((added.household.id, added.is_primary), (ids[1], false));
+
+    let linked = q::get_patient_households(&*test_db, 50).await.unwrap();
+    let summary: Vec<(i32, &str, bool)> = linked
+        .iter()
+        .map(|l| (l.household.id, l.relationship_type.as_str(), l.is_primary))
+        .collect();
+    assert_eq!(summary, vec![(ids[0], "Pet", true), (ids[1], "Foster", false)]);
+
+    // A new primary link demotes the old one through the trigger
+    test_db.execute_unprepared(&format!(
+        "INSERT INTO patient_households (patient_id, household_id, is_primary) VALUES (50, {}, 1)",
+        ids[2]
+    )).await.unwrap();
+    let linked = q::get_patient_households(&*test_db, 50).await.unwrap();
+    let order: Vec<(i32, bool)> = linked.iter().map(|l| (l.household.id, l.is_primary)).collect();
+    assert_eq!(order, vec![(ids[2], true), (ids[0], false), (ids[1], false)]);
+}
+
+#[tokio::test]
+async fn first_added_household_becomes_primary() {
+    let test_db = create_test_db_with_migrations().await;
+    let home = q::create_household_with_people(&test_db, dto("Home", vec![person("A", "B", true)])).await.unwrap().created.household.id;
+    let gone = q::create_household_with_people(&test_db, dto("Gone", vec![person("C", "D", true)])).await.unwrap().created.household.id;
+    test_db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (50, 'Rex', 1)").await.unwrap();
+
+    let added = q::add_secondary_household(&test_db, 50, home, None).await.unwrap();
+    assert!(added.is_primary, "a patient without a primary household gets this one");
+    assert_eq!(added.relationship_type, "secondary_household");
+
+    let again = q::add_secondary_household(&test_db, 50, home, None).await.unwrap_err();
+    assert!(again.contains("already linked"), "unexpected error: {}", again);
+    let missing = q::add_secondary_household(&test_db, 99, home, None).await.unwrap_err();
+    assert!(missing.contains("Patient 99 not found"), "unexpected error: {}", missing);
+
+    q::add_secondary_household(&test_db, 50, gone, None).await.unwrap();
+    q::delete_household(&test_db, gone, false).await.unwrap();
+    let linked = q::get_patient_households(&*test_db, 50).await.unwrap();
+    assert_eq!(linked.len(), 1, "soft-deleted households are left out");
+    assert_eq!(linked[0].household.id, home);
+}
+
 // ---------------------------------------------------------------------------
 // FTS5 search
 // ---------------------------------------------------------------------------
@@ -461,7 +664,8 @@ async fn search_finds_household_by_person_name() {
         dto("Household1", vec![person("Charlotte", "Walker", true)]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     let results = household_search::search_households(&test_db, "Charlotte", None, None).await.unwrap();
     assert!(results.results.iter().any(|h| h.household_name.as_deref() == Some("Household1")));
@@ -475,7 +679,8 @@ async fn search_finds_household_by_contact_email() {
         dto("EmailMatch", vec![person_with("A", "B", "email", "uniquetestemail@example.com")]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     let results = household_search::search_households(&test_db, "uniquetestemail", None, None).await.unwrap();
     assert!(
@@ -525,7 +730,8 @@ async fn search_handles_special_fts5_characters_safely() {
         dto("X", vec![person_with("Alice", "Smith", "email", "alice@example.com")]),
     )
     .await
-    .unwrap();
+    .unwrap()
+    .created;
 
     let result = household_search::search_households(&test_db, "ali@ce.com", None, None).await;
     assert!(result.is_ok(), "should not panic on special chars: {:?}", result.err());
@@ -534,7 +740,7 @@ async fn search_handles_special_fts5_characters_safely() {
 #[tokio::test]
 async fn rebuild_search_index_does_not_panic_on_fresh_db() {
     let test_db = create_test_db_with_migrations().await;
-    let result = household_search::rebuild_search_index(&test_db).await;
+    let result = household_search::rebuild_search_index(&test_db, |_| {}).await;
     assert!(result.is_ok(), "rebuild error: {:?}", result.err());
 }
 
@@ -542,12 +748,195 @@ async fn rebuild_search_index_does_not_panic_on_fresh_db() {
 async fn rebuild_search_index_restores_searchability() {
     let test_db = create_test_db_with_migrations().await;
     q::create_household_with_people(&test_db, dto("RebuildTest", vec![person("A", "B", true)])).await.unwrap();
-    household_search::rebuild_search_index(&test_db).await.unwrap();
+    household_search::rebuild_search_index(&test_db, |_| {}).await.unwrap();
 
     let results = household_search::search_households(&test_db, "RebuildTest", None, None).await.unwrap();
     assert!(results.results.iter().any(|h| h.household_name.as_deref() == Some("RebuildTest")));
 }
 
+async fn indexed_people_names(db: &sea_orm::DatabaseConnection, household_id: i32) -> Option<String> {
+    db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT people_names FROM household_search WHERE household_id = ?",
+        [household_id.into()],
+    ))
+    .await
+    .unwrap()
+    .map(|row| row.try_get("", "people_names").unwrap())
+}
+
+#[tokio::test]
+async fn rebuild_search_index_reports_progress_per_batch() {
+    let test_db = create_test_db_with_migrations().await;
+    for name in ["First", "Second", "Third"] {
+        q::create_household_with_people(&test_db, dto(name, vec![person("A", "B", true)])).await.unwrap();
+    }
+
+    let progress = std::sync::Mutex::new(Vec::new());
+    let indexed = household_search::rebuild_search_index(&test_db, |p| {
+        progress.lock().unwrap().push((p.indexed, p.total));
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(indexed, 3);
+    assert_eq!(progress.into_inner().unwrap(), vec![(3, 3)], "three households fit in one batch");
+}
+
+#[tokio::test]
+async fn reindex_household_picks_up_a_renamed_person() {
+    let test_db = create_test_db_with_migrations().await;
+    let created = q::create_household_with_people(&test_db, dto("Horvat", vec![person("Ivan", "Horvat", true)]))
+        .await
+        .unwrap()
+        .created;
+    let other = q::create_household_with_people(&test_db, dto("Other", vec![person("Mia", "Kralj", true)]))
+        .await
+        .unwrap()
+        .created;
+
+    // Renamed without going through update_person, so the row is stale
+    test_db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE people SET first_name = 'Josip' WHERE id = ?",
+            [created.people[0].id.into()],
+        ))
+        .await
+        .unwrap();
+    assert_eq!(indexed_people_names(&test_db, created.household.id).await.as_deref(), Some("Ivan Horvat"));
+
+    household_search::reindex_household(&*test_db, created.household.id as i64).await.unwrap();
+
+    assert_eq!(indexed_people_names(&test_db, created.household.id).await.as_deref(), Some("Josip Horvat"));
+    assert_eq!(indexed_people_names(&test_db, other.household.id).await.as_deref(), Some("Mia Kralj"));
+    let results = household_search::search_households(&test_db, "Josip", None, None).await.unwrap();
+    assert_eq!(results.results.len(), 1);
+
+    // update_person keeps the row fresh by itself
+    q::update_person(&test_db, created.people[0].id, &serde_json::json!({ "lastName": "Babić" })).await.unwrap();
+    assert_eq!(indexed_people_names(&test_db, created.household.id).await.as_deref(), Some("Josip Babić"));
+}
+
+#[tokio::test]
+async fn reindex_household_without_people_indexes_empty_strings() {
+    let test_db = create_test_db_with_migrations().await;
+    test_db
+        .execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "INSERT INTO households (id, household_name) VALUES (7, 'Nobody home')".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    household_search::reindex_household(&*test_db, 7).await.unwrap();
+
+    let row = test_db
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT people_names, contact_values FROM household_search WHERE household_id = 7".to_string(),
+        ))
+        .await
+        .unwrap()
+        .expect("household is indexed");
+    let people_names: String = row.try_get("", "people_names").unwrap();
+    let contact_values: String = row.try_get("", "contact_values").unwrap();
+    assert_eq!((people_names.as_str(), contact_values.as_str()), ("", ""));
+}
+
+#[tokio::test]
+async fn rebuild_with_unicode61_matches_names_without_diacritics() {
+    let test_db = create_test_db_with_migrations().await;
+    q::create_household_with_people(&test_db, dto("Jović", vec![person("Đorđe", "Jović", true)])).await.unwrap();
+
+    household_search::rebuild_search_index_with_tokenizer(&test_db, "porter").await.unwrap();
+    household_search::rebuild_search_index_with_tokenizer(&test_db, "unicode61").await.unwrap();
+
+    for query in ["jovic", "Jović", "jov"] {
+        let results = household_search::search_households(&test_db, query, None, None).await.unwrap();
+        assert!(
+            results.results.iter().any(|h| h.household_name.as_deref() == Some("Jović")),
+            "{:?} should match after the rebuild",
+            query
+        );
+    }
+
+    // New households still reach the recreated table through the triggers
+    q::create_household_with_people(&test_db, dto("Škoro", vec![person("A", "B", true)])).await.unwrap();
+    let results = household_search::search_households(&test_db, "skoro", None, None).await.unwrap();
+    assert!(results.results.iter().any(|h| h.household_name.as_deref() == Some("Škoro")));
+
+    let err = household_search::rebuild_search_index_with_tokenizer(&test_db, "trigram").await.unwrap_err();
+    assert!(err.contains("Invalid search tokenizer"), "unexpected error: {}", err);
+}
+
+#[tokio::test]
+async fn create_household_reports_trigger_indexing() {
+    let test_db = create_test_db_with_migrations().await;
+    let result = q::create_household_with_people(&test_db, dto("Indexed", vec![person("A", "B", true)]))
+        .await
+        .unwrap();
+    assert_eq!(result.search_index, SearchIndexSource::Trigger);
+}
+
+#[tokio::test]
+async fn create_household_indexes_directly_when_trigger_is_missing() {
+    let test_db = create_test_db_with_migrations().await;
+    test_db
+        .execute_unprepared("DROP TRIGGER household_search_insert")
+        .await
+        .unwrap();
+
+    let result = q::create_household_with_people(
+        &test_db,
+        dto("Untriggered", vec![person_with("Mila", "Petrova", "email", "zzbilling@example.com")]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.search_index, SearchIndexSource::Fallback);
+
+    // The fallback row carries people and contacts, not just the name
+    for query in ["Untriggered", "Petrova", "zzbilling"] {
+        let results = household_search::search_households(&test_db, query, None, None).await.unwrap();
+        assert!(
+            results.results.iter().any(|h| h.id == result.created.household.id),
+            "{:?} should find the household",
+            query
+        );
+    }
+}
+
+#[tokio::test]
+async fn phone_search_matches_digits_across_formatting() {
+    let test_db = create_test_db_with_migrations().await;
+    q::create_household_with_people(
+        &test_db,
+        dto("Dimitrov", vec![person_with("Ivan", "Dimitrov", "mobile", "070/340 846")]),
+    )
+    .await
+    .unwrap();
+    q::create_household_with_people(
+        &test_db,
+        dto("Other", vec![person_with("Ana", "Other", "phone", "02 111 222")]),
+    )
+    .await
+    .unwrap();
+
+    // "340846" spans the space in the stored number
+    let results = household_search::search_households_by_phone(&test_db, "340846").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].household_name.as_deref(), Some("Dimitrov"));
+    assert_eq!(results[0].people[0].first_name, "Ivan");
+
+    // The query is normalized too
+    let results = household_search::search_households_by_phone(&test_db, "340-846").await.unwrap();
+    assert_eq!(results.len(), 1);
+
+    // Too few digits to be useful
+    let results = household_search::search_households_by_phone(&test_db, "07").await.unwrap();
+    assert!(results.is_empty());
+}
+
 // ---------------------------------------------------------------------------
 // helpers
 // ---------------------------------------------------------------------------
@@ -564,3 +953,56 @@ async fn household_count(db: &sea_orm::DatabaseConnection) -> i64 {
     .try_get("", "c")
     .unwrap()
 }
+
+async fn indexed_display_name(test_db: &sea_orm::DatabaseConnection, household_id: i32) -> String {
+    let row = test_db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT display_name FROM household_search WHERE household_id = ?",
+        [household_id.into()],
+    ))
+    .await
+    .unwrap()
+    .unwrap();
+    row.try_get("", "display_name").unwrap()
+}
+
+#[tokio::test]
+async fn recompute_display_names_covers_one_two_and_named_households() {
+    let test_db = create_test_db_with_migrations().await;
+    let mut single = dto("", vec![person("Ana", "Petrova", true)]);
+    single.household.household_name = None;
+    let mut couple = dto("", vec![person("Ana", "Petrova", true), person("Marko", "Ilievski", false)]);
+    couple.household.household_name = None;
+    let named = dto("The Petrovs", vec![person("Ana", "Petrova", true), person("Ivo", "Petrov", false)]);
+
+    let mut ids = Vec::new();
+    for household in [single, couple, named] {
+        ids.push(q::create_household_with_people(&test_db, household).await.unwrap().created.household.id);
+    }
+
+    // Drift the index the way older rows and logic changes leave it
+    test_db.execute_unprepared("UPDATE household_search SET display_name = ''").await.unwrap();
+
+    let changed = household_search::recompute_household_display_names(&test_db).await.unwrap();
+    assert_eq!(changed, 3);
+    assert_eq!(indexed_display_name(&test_db, ids[0]).await, "Ana Petrova");
+    assert_eq!(indexed_display_name(&test_db, ids[1]).await, "Petrova & Ilievski");
+    assert_eq!(indexed_display_name(&test_db, ids[2]).await, "The Petrovs");
+
+    assert_eq!(
+        household_search::recompute_household_display_names(&test_db).await.unwrap(),
+        0,
+        "a second pass has nothing left to change"
+    );
+}
+
+#[test]
+fn display_name_ignores_blank_household_name() {
+    let people = vec![
+        ("A".to_string(), "One".to_string()),
+        ("B".to_string(), "Two".to_string()),
+        ("C".to_string(), "Three".to_string()),
+    ];
+    assert_eq!(household_search::household_display_name(7, Some("  "), &people), "Household 7");
+    assert_eq!(household_search::household_display_name(7, Some("  "), &people[..2]), "One & Two");
+}
@@ -5,17 +5,24 @@
 //! can't be invoked with `tauri::test::mock_builder` which produces
 //! `AppHandle<MockRuntime>`. Tests that need those go through direct SQL to
 //! seed fixtures and exercise the runtime-independent service methods
-//! (`archive_medical_record`, `search_medical_records`, `get_medical_records`).
+//! (`archive_medical_record`, `search_medical_records`, `get_medical_records`),
+//! or through `insert_medical_record` / `apply_medical_record_update`, the
+//! AppHandle-free parts of create/update that skip PDF generation.
 //!
 //! Follow-up: see task #14 — refactor to runtime-generic to recover full
 //! coverage of create/update.
 
-use crate::models::dto::CreatePatientDto;
-use crate::models::medical::MedicalRecordFilter;
-use crate::services::medical_record::MedicalRecordService;
+use crate::models::dto::{CreatePatientDto, MaybeNull};
+use crate::models::medical::{
+    page_metadata, CreateMedicalRecordInput, FieldChange, MedicalRecordFilter, PaginationParams,
+    PatientAttachmentFilter, TemplatePreviewOverrides, UpdateMedicalRecordInput,
+};
+use crate::services::medical_record::{MedicalRecordService, ATTACHMENT_QUERY_COUNT};
 use crate::services::patient::PatientService;
 use crate::test_utils::create_test_db_with_migrations;
+use chrono::{NaiveDate, Utc};
 use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use serde_json::json;
 
 async fn seed_patient(db: &DatabaseConnection) -> i64 {
     PatientService::create(
@@ -162,6 +169,41 @@ async fn list_excludes_archived_by_default_even_with_no_filter() {
     assert_eq!(response.records[0].name, "Active");
 }
 
+#[test]
+fn page_metadata_rounds_up_and_never_reports_zero_pages() {
+    assert_eq!(page_metadata(0, 1, 50), (1, false, false));
+    assert_eq!(page_metadata(50, 1, 50), (1, false, false));
+    assert_eq!(page_metadata(51, 1, 50), (2, true, false));
+    assert_eq!(page_metadata(51, 2, 50), (2, false, true));
+    assert_eq!(page_metadata(100, 2, 50), (2, false, true));
+    assert_eq!(page_metadata(100, 1, 0), (100, true, false), "a page size below 1 counts as 1");
+}
+
+#[tokio::test]
+async fn list_reports_page_count_and_neighbours() {
+    let test_db = create_test_db_with_migrations().await;
+    let page = |page| Some(PaginationParams { page: Some(page), page_size: Some(50) });
+
+    for (records, pages) in [(0, 1), (50, 1), (51, 2), (100, 2)] {
+        let patient_id = seed_patient(&test_db).await;
+        for i in 0..records {
+            insert_record(&test_db, patient_id, &format!("Visit {}", i), "").await;
+        }
+
+        let first = MedicalRecordService::get_medical_records(&test_db, patient_id, None, page(1))
+            .await
+            .unwrap();
+        assert_eq!(first.total_pages, pages, "{} records", records);
+        assert_eq!((first.has_next, first.has_previous), (pages > 1, false), "{} records", records);
+
+        let last = MedicalRecordService::get_medical_records(&test_db, patient_id, None, page(pages as i32))
+            .await
+            .unwrap();
+        assert!(!last.has_next, "{} records", records);
+        assert_eq!(last.records.len() as i64, records - (pages - 1) * 50);
+    }
+}
+
 #[tokio::test]
 async fn list_filters_by_record_type() {
     let test_db = create_test_db_with_migrations().await;
@@ -295,3 +337,1208 @@ async fn search_does_not_cross_patient_boundaries() {
     assert_eq!(a_results.len(), 1);
     assert!(a_results[0].name.starts_with("A"));
 }
+
+#[tokio::test]
+async fn search_terms_with_quotes_and_wildcards_match_literally() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+
+    insert_record(&test_db, patient_id, "Referral from Dr O'Brien", "ortho consult").await;
+    insert_record(&test_db, patient_id, "Discount", "50% off dental").await;
+    insert_record(&test_db, patient_id, "Dental", "500 ml fluids").await;
+
+    let filter = |term: &str| MedicalRecordFilter {
+        record_type: None,
+        is_archived: None,
+        search_term: Some(term.to_string()),
+    };
+
+    let quoted = MedicalRecordService::get_medical_records(&test_db, patient_id, Some(filter("O'Brien")), None)
+        .await
+        .expect("a quote in the term must not break the query");
+    assert_eq!(quoted.total, 1);
+    assert_eq!(quoted.records[0].name, "Referral from Dr O'Brien");
+
+    // '%' is an ordinary character, not a LIKE wildcard
+    let percent = MedicalRecordService::get_medical_records(&test_db, patient_id, Some(filter("50%")), None)
+        .await
+        .unwrap();
+    assert_eq!(percent.total, 1);
+    assert_eq!(percent.records[0].name, "Discount");
+
+    let injected = MedicalRecordService::get_medical_records(
+        &test_db,
+        patient_id,
+        Some(filter("' OR '1'='1")),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(injected.total, 0);
+
+    let searched = MedicalRecordService::search_medical_records(&test_db, patient_id, "o'brien", false)
+        .await
+        .unwrap();
+    assert_eq!(searched.len(), 1);
+    let searched = MedicalRecordService::search_medical_records(&test_db, patient_id, "%", false)
+        .await
+        .unwrap();
+    assert_eq!(searched.len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// change_record_type
+// ---------------------------------------------------------------------------
+
+async fn set_type_and_price(db: &DatabaseConnection, record_id: i64, record_type: &str, price: Option<f64>) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE medical_records SET record_type = ?, price = ?, currency_id = ? WHERE id = ?",
+        [
+            record_type.into(),
+            sea_orm::Value::Double(price),
+            sea_orm::Value::BigInt(price.map(|_| 1)),
+            record_id.into(),
+        ],
+    ))
+    .await
+    .unwrap();
+}
+
+async fn history_count(db: &DatabaseConnection, record_id: i64) -> i64 {
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT COUNT(*) AS c FROM medical_record_history WHERE medical_record_id = ?",
+            [record_id.into()],
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    row.try_get("", "c").unwrap()
+}
+
+#[tokio::test]
+async fn change_note_to_procedure_with_price() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let id = insert_record(&test_db, patient_id, "Phone call", "Owner called").await;
+    set_type_and_price(&test_db, id, "note", None).await;
+
+    let record = MedicalRecordService::change_record_type(&test_db, id, "procedure", Some(45.0), Some(1), None)
+        .await
+        .expect("change type");
+
+    assert_eq!(record.record_type, "procedure");
+    assert_eq!(record.price, Some(45.0));
+    assert_eq!(record.currency_id, Some(1));
+    assert_eq!(record.version, 2);
+    assert_eq!(history_count(&test_db, id).await, 1);
+}
+
+#[tokio::test]
+async fn change_procedure_to_note_clears_price() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let id = insert_record(&test_db, patient_id, "Dental", "Cleaning").await;
+    set_type_and_price(&test_db, id, "procedure", Some(120.0)).await;
+
+    let record = MedicalRecordService::change_record_type(&test_db, id, "note", None, None, None)
+        .await
+        .expect("change type");
+
+    assert_eq!(record.record_type, "note");
+    assert_eq!(record.price, None);
+    assert_eq!(record.currency_id, None);
+
+    let row = test_db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT changed_fields, old_values FROM medical_record_history WHERE medical_record_id = ?",
+            [id.into()],
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let changed: String = row.try_get("", "changed_fields").unwrap();
+    let old_values: String = row.try_get("", "old_values").unwrap();
+    assert!(changed.contains("record_type") && changed.contains("price"), "got: {}", changed);
+    assert!(old_values.contains("120"), "old snapshot keeps the price: {}", old_values);
+}
+
+#[tokio::test]
+async fn change_record_type_rejects_invalid_targets() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let id = insert_record(&test_db, patient_id, "Dental", "Cleaning").await;
+
+    assert!(MedicalRecordService::change_record_type(&test_db, id, "invoice", None, None, None).await.is_err());
+    // Note with a price
+    set_type_and_price(&test_db, id, "test_result", None).await;
+    assert!(MedicalRecordService::change_record_type(&test_db, id, "note", Some(10.0), Some(1), None).await.is_err());
+    // Priced procedure without currency
+    assert!(MedicalRecordService::change_record_type(&test_db, id, "procedure", Some(10.0), None, None).await.is_err());
+
+    // Nothing changed, nothing recorded
+    let record = MedicalRecordService::get_medical_record(&test_db, id, false).await.unwrap().record;
+    assert_eq!(record.record_type, "test_result");
+    assert_eq!(history_count(&test_db, id).await, 0);
+}
+
+// ---------------------------------------------------------------------------
+// get_medical_record_summaries — field selection
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn summaries_omit_unrequested_fields_and_attachments() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let id = insert_record(&test_db, patient_id, "X-Ray", "Long description text").await;
+    test_db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at) \
+             VALUES (?, 'uuid-1', 'xray.png', 10, 'image/png', CURRENT_TIMESTAMP)",
+            [id.into()],
+        ))
+        .await
+        .unwrap();
+
+    let fields = vec!["name".to_string(), "price".to_string()];
+    let before = ATTACHMENT_QUERY_COUNT.with(|c| c.get());
+    let resp = MedicalRecordService::get_medical_record_summaries(&test_db, patient_id, None, None, &fields)
+        .await
+        .expect("summaries");
+    assert_eq!(ATTACHMENT_QUERY_COUNT.with(|c| c.get()), before, "no attachment query for summaries");
+
+    assert_eq!(resp.total, 1);
+    let summary = &resp.records[0];
+    assert_eq!(summary.id, id);
+    assert_eq!(summary.record_type, "procedure");
+    assert_eq!(summary.name.as_deref(), Some("X-Ray"));
+    assert!(summary.description.is_none());
+
+    // The payload carries neither the description nor any attachment data
+    let json = serde_json::to_value(summary).unwrap();
+    assert!(json.get("description").is_none(), "got: {}", json);
+    assert!(json.get("attachments").is_none(), "got: {}", json);
+}
+
+#[tokio::test]
+async fn summaries_search_still_matches_description() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    insert_record(&test_db, patient_id, "Checkup", "Routine").await;
+    insert_record(&test_db, patient_id, "Surgery", "Tumor removed").await;
+
+    let filter = MedicalRecordFilter {
+        record_type: None,
+        is_archived: Some(false),
+        search_term: Some("tumor".to_string()),
+    };
+    let resp = MedicalRecordService::get_medical_record_summaries(
+        &test_db, patient_id, Some(filter), None, &["name".to_string()],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(resp.total, 1);
+    assert_eq!(resp.records[0].name.as_deref(), Some("Surgery"));
+    assert!(resp.records[0].description.is_none());
+}
+
+#[tokio::test]
+async fn summaries_reject_unknown_fields() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+
+    let err = MedicalRecordService::get_medical_record_summaries(
+        &test_db, patient_id, None, None, &["patient_id; DROP TABLE x".to_string()],
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("Unknown field"), "got: {}", err);
+}
+
+// ---------------------------------------------------------------------------
+// get_medical_records — attachments batched per page
+// ---------------------------------------------------------------------------
+
+async fn insert_attachment(db: &DatabaseConnection, record_id: i64, file_id: &str) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at) \
+         VALUES (?, ?, ?, 10, 'application/pdf', CURRENT_TIMESTAMP)",
+        [record_id.into(), file_id.into(), format!("{}.pdf", file_id).into()],
+    ))
+    .await
+    .unwrap();
+}
+
+/// Attachment queries issued while loading one page of `page_size` records.
+async fn attachment_queries_for_page(page_size: i32) -> usize {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    for i in 0..page_size {
+        let id = insert_record(&test_db, patient_id, &format!("Record {}", i), "desc").await;
+        insert_attachment(&test_db, id, &format!("file-{}", i)).await;
+    }
+
+    let before = ATTACHMENT_QUERY_COUNT.with(|c| c.get());
+    let resp = MedicalRecordService::get_medical_records(
+        &test_db,
+        patient_id,
+        None,
+        Some(PaginationParams { page: Some(1), page_size: Some(page_size) }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.records.len(), page_size as usize);
+    assert!(resp.records.iter().all(|r| r.attachments.as_ref().map(|a| a.len()) == Some(1)));
+    ATTACHMENT_QUERY_COUNT.with(|c| c.get()) - before
+}
+
+#[tokio::test]
+async fn list_attachment_queries_do_not_grow_with_page_size() {
+    assert_eq!(attachment_queries_for_page(3).await, 1);
+    assert_eq!(attachment_queries_for_page(30).await, 1);
+}
+
+#[tokio::test]
+async fn list_groups_attachments_under_their_own_record() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let with_two = insert_record(&test_db, patient_id, "Two files", "d").await;
+    let with_none = insert_record(&test_db, patient_id, "No files", "d").await;
+    insert_attachment(&test_db, with_two, "a").await;
+    insert_attachment(&test_db, with_two, "b").await;
+
+    let resp = MedicalRecordService::get_medical_records(&test_db, patient_id, None, None)
+        .await
+        .unwrap();
+    let two = resp.records.iter().find(|r| r.id == with_two).unwrap();
+    let none = resp.records.iter().find(|r| r.id == with_none).unwrap();
+    assert_eq!(two.attachments.as_ref().map(|a| a.len()), Some(2));
+    assert!(none.attachments.is_none());
+}
+
+// ---------------------------------------------------------------------------
+// get_patient_attachments
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn patient_attachments_span_records_with_parent_context() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let other_patient = seed_patient(&test_db).await;
+    let xray = insert_record(&test_db, patient_id, "X-Ray", "d").await;
+    let blood = insert_record(&test_db, patient_id, "Blood panel", "d").await;
+    let foreign = insert_record(&test_db, other_patient, "Other", "d").await;
+    insert_attachment(&test_db, xray, "xray-1").await;
+    insert_attachment(&test_db, blood, "blood-1").await;
+    insert_attachment(&test_db, foreign, "other-1").await;
+
+    let resp = MedicalRecordService::get_patient_attachments(&test_db, patient_id, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.total, 2);
+    let by_file = |file_id: &str| {
+        resp.attachments
+            .iter()
+            .find(|a| a.attachment.file_id == file_id)
+            .unwrap_or_else(|| panic!("missing {}", file_id))
+    };
+    assert_eq!(by_file("xray-1").record_name, "X-Ray");
+    assert_eq!(by_file("xray-1").attachment.medical_record_id, xray);
+    assert_eq!(by_file("blood-1").record_name, "Blood panel");
+    assert_eq!(by_file("blood-1").record_type, "procedure");
+}
+
+#[tokio::test]
+async fn patient_attachments_filter_and_archived_exclusion() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let active = insert_record(&test_db, patient_id, "Active", "d").await;
+    let archived = insert_record(&test_db, patient_id, "Archived", "d").await;
+    insert_attachment(&test_db, active, "pdf-1").await;
+    insert_attachment(&test_db, archived, "pdf-2").await;
+    test_db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at) \
+             VALUES (?, 'img-1', 'photo.jpg', 10, 'image/jpeg', CURRENT_TIMESTAMP)",
+            [active.into()],
+        ))
+        .await
+        .unwrap();
+    MedicalRecordService::archive_medical_record(&test_db, archived, true).await.unwrap();
+
+    let default = MedicalRecordService::get_patient_attachments(&test_db, patient_id, None, None)
+        .await
+        .unwrap();
+    assert_eq!(default.total, 2, "archived record's attachment hidden by default");
+
+    let with_archived = MedicalRecordService::get_patient_attachments(
+        &test_db,
+        patient_id,
+        Some(PatientAttachmentFilter { include_archived: Some(true), ..Default::default() }),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(with_archived.total, 3);
+
+    let images = MedicalRecordService::get_patient_attachments(
+        &test_db,
+        patient_id,
+        Some(PatientAttachmentFilter { mime_type: Some("image/*".to_string()), ..Default::default() }),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(images.total, 1);
+    assert_eq!(images.attachments[0].attachment.file_id, "img-1");
+
+    let paged = MedicalRecordService::get_patient_attachments(
+        &test_db,
+        patient_id,
+        Some(PatientAttachmentFilter { include_archived: Some(true), ..Default::default() }),
+        Some(PaginationParams { page: Some(2), page_size: Some(2) }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(paged.total, 3);
+    assert_eq!(paged.attachments.len(), 1);
+}
+
+#[tokio::test]
+async fn patient_attachments_mime_family_matches_wildcards_literally() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let record = insert_record(&test_db, patient_id, "Scans", "d").await;
+    for (file_id, mime) in [("img-1", "image/jpeg"), ("odd-1", "x%_\\y/bin")] {
+        test_db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at) \
+                 VALUES (?, ?, 'file', 10, ?, CURRENT_TIMESTAMP)",
+                [record.into(), file_id.into(), mime.into()],
+            ))
+            .await
+            .unwrap();
+    }
+
+    let matching = |mime: &str| {
+        let filter = PatientAttachmentFilter { mime_type: Some(mime.to_string()), ..Default::default() };
+        let db = &test_db;
+        async move {
+            MedicalRecordService::get_patient_attachments(db, patient_id, Some(filter), None)
+                .await
+                .unwrap()
+                .attachments
+                .into_iter()
+                .map(|a| a.attachment.file_id)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Unescaped, these would be wildcards matching image/jpeg
+    assert!(matching("i%/*").await.is_empty());
+    assert!(matching("_mage/*").await.is_empty());
+    assert_eq!(matching("image/*").await, vec!["img-1"]);
+    assert_eq!(matching("x%_\\y/*").await, vec!["odd-1"]);
+}
+
+// ---------------------------------------------------------------------------
+// preview_record_from_template
+// ---------------------------------------------------------------------------
+
+async fn insert_template(
+    db: &DatabaseConnection,
+    record_type: &str,
+    title: &str,
+    price: Option<f64>,
+    currency_id: Option<i64>,
+) -> i64 {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO record_templates (record_type, title, description, price, currency_id) VALUES (?, ?, ?, ?, ?)",
+        [
+            record_type.into(),
+            title.into(),
+            format!("{} (template text)", title).into(),
+            sea_orm::Value::Double(price),
+            sea_orm::Value::BigInt(currency_id),
+        ],
+    ))
+    .await
+    .expect("insert template")
+    .last_insert_id() as i64
+}
+
+async fn count_records(db: &DatabaseConnection) -> i64 {
+    db.query_one(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS c FROM medical_records".to_string(),
+    ))
+    .await
+    .unwrap()
+    .unwrap()
+    .try_get("", "c")
+    .unwrap()
+}
+
+#[tokio::test]
+async fn preview_overrides_take_precedence_over_template() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let template_id = insert_template(&test_db, "procedure", "Vaccination", Some(25.0), Some(1)).await;
+
+    let overrides = TemplatePreviewOverrides {
+        name: Some("Rabies booster".to_string()),
+        price: Some(40.0),
+        currency_id: Some(3),
+        ..Default::default()
+    };
+    let preview = MedicalRecordService::preview_record_from_template(&test_db, patient_id, template_id, overrides)
+        .await
+        .expect("preview");
+
+    assert_eq!(preview.patient_id, patient_id);
+    assert_eq!(preview.record_type, "procedure");
+    assert_eq!(preview.name, "Rabies booster");
+    assert_eq!(preview.description, "Vaccination (template text)", "untouched fields come from the template");
+    assert_eq!(preview.price, Some(40.0));
+    assert_eq!(preview.currency_id, Some(3));
+    assert_eq!(count_records(&test_db).await, 0, "preview must not insert anything");
+}
+
+#[tokio::test]
+async fn preview_resolves_missing_currency_from_settings() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let template_id = insert_template(&test_db, "procedure", "Nail trim", Some(10.0), None).await;
+    test_db
+        .execute_unprepared("UPDATE app_settings SET currency_id = 3 WHERE user_id = 'default'")
+        .await
+        .unwrap();
+
+    let preview = MedicalRecordService::preview_record_from_template(
+        &test_db, patient_id, template_id, TemplatePreviewOverrides::default(),
+    )
+    .await
+    .expect("preview");
+
+    assert_eq!(preview.price, Some(10.0));
+    assert_eq!(preview.currency_id, Some(3));
+}
+
+#[tokio::test]
+async fn preview_rejects_incompatible_overrides() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let note_id = insert_template(&test_db, "note", "Follow-up call", None, None).await;
+
+    let wrong_type = TemplatePreviewOverrides {
+        record_type: Some("procedure".to_string()),
+        ..Default::default()
+    };
+    let err = MedicalRecordService::preview_record_from_template(&test_db, patient_id, note_id, wrong_type)
+        .await
+        .unwrap_err();
+    assert!(err.contains("is for note records"), "unexpected error: {}", err);
+
+    let priced_note = TemplatePreviewOverrides {
+        price: Some(5.0),
+        ..Default::default()
+    };
+    let err = MedicalRecordService::preview_record_from_template(&test_db, patient_id, note_id, priced_note)
+        .await
+        .unwrap_err();
+    assert!(err.contains("note cannot have a price"), "unexpected error: {}", err);
+
+    // Same validation the create command runs
+    let blank_name = TemplatePreviewOverrides {
+        name: Some(String::new()),
+        ..Default::default()
+    };
+    let err = MedicalRecordService::preview_record_from_template(&test_db, patient_id, note_id, blank_name)
+        .await
+        .unwrap_err();
+    assert_eq!(err, "Name is required");
+
+    let err = MedicalRecordService::preview_record_from_template(
+        &test_db, patient_id, 9_999, TemplatePreviewOverrides::default(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err, "Template not found");
+}
+
+// ---------------------------------------------------------------------------
+// follow-ups
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn follow_up_due_within_window_is_pending() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+    let due_soon = insert_record(&test_db, patient_id, "Dental", "Recheck in 3 days").await;
+    let later = insert_record(&test_db, patient_id, "Vaccine", "Booster in a month").await;
+    let archived = insert_record(&test_db, patient_id, "Old", "Archived recheck").await;
+    let done = insert_record(&test_db, patient_id, "Wound", "Already rechecked").await;
+    for (id, days) in [(due_soon, 3), (later, 30), (archived, 2), (done, 1)] {
+        MedicalRecordService::set_follow_up(&test_db, id, Some(today + chrono::Duration::days(days)))
+            .await
+            .unwrap();
+    }
+    MedicalRecordService::archive_medical_record(&test_db, archived, true).await.unwrap();
+    MedicalRecordService::set_follow_up_done(&test_db, done, true).await.unwrap();
+
+    let pending = MedicalRecordService::get_pending_followups(&test_db, 7, today).await.unwrap();
+
+    assert_eq!(pending.len(), 1, "got: {:?}", pending);
+    assert_eq!(pending[0].record_id, due_soon);
+    assert_eq!(pending[0].days_until_due, 3);
+    assert_eq!(pending[0].patient_name.as_deref(), Some("TestPet"));
+}
+
+#[tokio::test]
+async fn clearing_follow_up_removes_it_from_worklist() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+    let r1 = insert_record(&test_db, patient_id, "Ear infection", "Recheck").await;
+
+    // Overdue follow-ups stay on the list
+    MedicalRecordService::set_follow_up(&test_db, r1, Some(today - chrono::Duration::days(2))).await.unwrap();
+    let pending = MedicalRecordService::get_pending_followups(&test_db, 0, today).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].days_until_due, -2);
+
+    MedicalRecordService::set_follow_up(&test_db, r1, None).await.unwrap();
+    let pending = MedicalRecordService::get_pending_followups(&test_db, 7, today).await.unwrap();
+    assert!(pending.is_empty());
+
+    let err = MedicalRecordService::set_follow_up_done(&test_db, r1, true).await.unwrap_err();
+    assert_eq!(err, "Medical record has no follow-up scheduled");
+}
+
+// ---------------------------------------------------------------------------
+// charge summary
+// ---------------------------------------------------------------------------
+
+// Currency ids seeded by the migrations: 1=MKD, 2=USD, 3=EUR, 4=GBP
+const MKD: i64 = 1;
+const USD: i64 = 2;
+const EUR: i64 = 3;
+const GBP: i64 = 4;
+
+async fn insert_priced_record(
+    db: &DatabaseConnection,
+    patient_id: i64,
+    record_type: &str,
+    price: Option<f64>,
+    currency_id: Option<i64>,
+) -> i64 {
+    let record_id = insert_record(db, patient_id, record_type, "").await;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE medical_records SET record_type = ?, price = ?, currency_id = ? WHERE id = ?",
+        [
+            record_type.into(),
+            sea_orm::Value::Double(price),
+            sea_orm::Value::BigInt(currency_id),
+            record_id.into(),
+        ],
+    ))
+    .await
+    .unwrap();
+    record_id
+}
+
+async fn insert_line_item(db: &DatabaseConnection, record_id: i64, unit_price: f64, quantity: i32, currency_id: i64) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_record_line_items (medical_record_id, name, unit_price, currency_id, quantity, created_at) \
+         VALUES (?, 'Item', ?, ?, ?, CURRENT_TIMESTAMP)",
+        [record_id.into(), unit_price.into(), currency_id.into(), quantity.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn charge_summary_converts_mixed_currencies_into_target() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+
+    MedicalRecordService::set_exchange_rate(&test_db, USD, EUR, 0.9).await.unwrap();
+    // Only the EUR -> MKD direction is stored; MKD -> EUR uses its inverse
+    MedicalRecordService::set_exchange_rate(&test_db, EUR, MKD, 61.5).await.unwrap();
+
+    insert_priced_record(&test_db, patient_id, "procedure", Some(100.0), Some(USD)).await;
+    let with_items = insert_priced_record(&test_db, patient_id, "procedure", None, None).await;
+    insert_line_item(&test_db, with_items, 10.0, 2, EUR).await;
+    insert_line_item(&test_db, with_items, 615.0, 1, MKD).await;
+    test_db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE medical_records SET discount_percent = 10 WHERE id = ?",
+            [with_items.into()],
+        ))
+        .await
+        .unwrap();
+    insert_priced_record(&test_db, patient_id, "test_result", Some(50.0), Some(EUR)).await;
+    let no_rate = insert_priced_record(&test_db, patient_id, "test_result", Some(30.0), Some(GBP)).await;
+    let archived = insert_priced_record(&test_db, patient_id, "procedure", Some(1000.0), Some(MKD)).await;
+    MedicalRecordService::archive_medical_record(&test_db, archived, true).await.unwrap();
+    insert_priced_record(&test_db, patient_id, "note", None, None).await;
+
+    let summary = MedicalRecordService::get_patient_charge_summary(&test_db, patient_id, EUR, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(summary.currency.code, "EUR");
+    let totals: Vec<(&str, i64, f64)> = summary
+        .by_type
+        .iter()
+        .map(|t| (t.record_type.as_str(), t.count, t.total))
+        .collect();
+    // 100 USD -> 90; (2 x 10 EUR + 615 MKD) less 10% -> 27
+    assert_eq!(totals, vec![("procedure", 2, 117.0), ("test_result", 1, 50.0)]);
+    assert_eq!(summary.grand_total, 167.0);
+    assert_eq!(summary.skipped.len(), 1);
+    assert_eq!(summary.skipped[0].record_id, no_rate);
+    assert_eq!(summary.skipped[0].currency_id, GBP);
+
+    // Everything was created just now, so an earlier range is empty
+    let before = chrono::Utc::now().date_naive() - chrono::Duration::days(2);
+    let earlier = MedicalRecordService::get_patient_charge_summary(&test_db, patient_id, EUR, None, Some(before))
+        .await
+        .unwrap();
+    assert!(earlier.by_type.is_empty());
+    assert_eq!(earlier.grand_total, 0.0);
+
+    let err = MedicalRecordService::set_exchange_rate(&test_db, EUR, EUR, 1.0).await.unwrap_err();
+    assert_eq!(err, "Exchange rate needs two different currencies");
+}
+
+#[tokio::test]
+async fn list_records_by_type_reports_procedures_in_range_with_total() {
+    let test_db = create_test_db_with_migrations().await;
+    let rex = seed_patient(&test_db).await;
+    let bella = seed_patient(&test_db).await;
+    let deleted = seed_patient(&test_db).await;
+    MedicalRecordService::set_exchange_rate(&test_db, USD, EUR, 0.9).await.unwrap();
+
+    let first = insert_priced_record(&test_db, rex, "procedure", Some(200.0), Some(EUR)).await;
+    set_created_at(&test_db, first, "2030-01-01T08:00:00+00:00").await;
+    let second = insert_priced_record(&test_db, bella, "procedure", Some(100.0), Some(USD)).await;
+    set_created_at(&test_db, second, "2030-02-15T10:00:00+00:00").await;
+    // Last day of the range, in SQLite's own timestamp format
+    let last = insert_priced_record(&test_db, rex, "procedure", Some(50.0), Some(EUR)).await;
+    set_created_at(&test_db, last, "2030-03-31 23:30:00").await;
+
+    let before = insert_priced_record(&test_db, rex, "procedure", Some(999.0), Some(EUR)).await;
+    set_created_at(&test_db, before, "2029-12-31T23:59:00+00:00").await;
+    let after = insert_priced_record(&test_db, rex, "procedure", Some(999.0), Some(EUR)).await;
+    set_created_at(&test_db, after, "2030-04-01T00:00:00+00:00").await;
+    let other_type = insert_priced_record(&test_db, bella, "vaccination", Some(999.0), Some(EUR)).await;
+    set_created_at(&test_db, other_type, "2030-02-01T09:00:00+00:00").await;
+    let archived = insert_priced_record(&test_db, bella, "procedure", Some(999.0), Some(EUR)).await;
+    set_created_at(&test_db, archived, "2030-02-01T09:00:00+00:00").await;
+    MedicalRecordService::archive_medical_record(&test_db, archived, true).await.unwrap();
+    let of_deleted = insert_priced_record(&test_db, deleted, "procedure", Some(999.0), Some(EUR)).await;
+    set_created_at(&test_db, of_deleted, "2030-02-01T09:00:00+00:00").await;
+    PatientService::delete(&test_db, deleted).await.unwrap();
+
+    let from = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(2030, 3, 31).unwrap();
+    let page = |page| Some(PaginationParams { page: Some(page), page_size: Some(2) });
+
+    let report = MedicalRecordService::list_records_by_type(&test_db, "procedure", from, to, EUR, page(1))
+        .await
+        .unwrap();
+    assert_eq!(report.total, 3);
+    // 200 EUR + 100 USD -> 90 + 50 EUR, over every match and not just the page
+    assert_eq!(report.total_charge, 340.0);
+    assert!(report.skipped.is_empty());
+    let ids: Vec<i64> = report.records.iter().map(|r| r.record_id).collect();
+    assert_eq!(ids, vec![last, second], "newest first");
+    assert_eq!(report.records[1].patient_id, bella);
+    assert_eq!(report.records[1].charge, Some(90.0));
+
+    let rest = MedicalRecordService::list_records_by_type(&test_db, "procedure", from, to, EUR, page(2))
+        .await
+        .unwrap();
+    assert_eq!(rest.records.iter().map(|r| r.record_id).collect::<Vec<_>>(), vec![first]);
+    assert_eq!(rest.total_charge, 340.0);
+
+    let err = MedicalRecordService::list_records_by_type(&test_db, "procedure", to, from, EUR, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err, "The end date is before the start date");
+}
+
+// ---------------------------------------------------------------------------
+// auto-archive
+// ---------------------------------------------------------------------------
+
+async fn set_created_at(db: &DatabaseConnection, record_id: i64, created_at: &str) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE medical_records SET created_at = ? WHERE id = ?",
+        [created_at.into(), record_id.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+async fn is_archived(db: &DatabaseConnection, record_id: i64) -> bool {
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT is_archived FROM medical_records WHERE id = ?",
+            [record_id.into()],
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    row.try_get::<i64>("", "is_archived").unwrap() != 0
+}
+
+#[tokio::test]
+async fn auto_archive_archives_old_records_but_not_recent_or_follow_up_ones() {
+    use chrono::TimeZone;
+
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let now = chrono::Utc.with_ymd_and_hms(2030, 6, 1, 12, 0, 0).unwrap();
+
+    // Both timestamp formats found in the table count as old
+    let old = insert_record(&test_db, patient_id, "Old vaccine", "").await;
+    set_created_at(&test_db, old, "2027-01-10T09:00:00+00:00").await;
+    let old_sqlite_format = insert_record(&test_db, patient_id, "Old checkup", "").await;
+    set_created_at(&test_db, old_sqlite_format, "2027-02-01 09:00:00").await;
+    let recent = insert_record(&test_db, patient_id, "Recent", "").await;
+    set_created_at(&test_db, recent, "2030-05-01T09:00:00+00:00").await;
+    let follow_up = insert_record(&test_db, patient_id, "Old with recheck", "").await;
+    set_created_at(&test_db, follow_up, "2027-01-10T09:00:00+00:00").await;
+    MedicalRecordService::set_follow_up(&test_db, follow_up, NaiveDate::from_ymd_opt(2030, 7, 1))
+        .await
+        .unwrap();
+    let done_follow_up = insert_record(&test_db, patient_id, "Old, recheck done", "").await;
+    set_created_at(&test_db, done_follow_up, "2027-01-10T09:00:00+00:00").await;
+    MedicalRecordService::set_follow_up(&test_db, done_follow_up, NaiveDate::from_ymd_opt(2027, 2, 1))
+        .await
+        .unwrap();
+    MedicalRecordService::set_follow_up_done(&test_db, done_follow_up, true).await.unwrap();
+
+    let result = MedicalRecordService::apply_auto_archive(&test_db, 365, now).await.unwrap();
+    assert_eq!(result.archived, 3);
+    assert_eq!(result.skipped_pending_follow_up, 1);
+
+    assert!(is_archived(&test_db, old).await);
+    assert!(is_archived(&test_db, old_sqlite_format).await);
+    assert!(is_archived(&test_db, done_follow_up).await);
+    assert!(!is_archived(&test_db, recent).await);
+    assert!(!is_archived(&test_db, follow_up).await);
+    assert_eq!(history_count(&test_db, old).await, 1);
+    assert_eq!(history_count(&test_db, recent).await, 0);
+
+    // A second run finds nothing new to archive
+    let again = MedicalRecordService::apply_auto_archive(&test_db, 365, now).await.unwrap();
+    assert_eq!(again.archived, 0);
+    assert_eq!(again.skipped_pending_follow_up, 1);
+    assert_eq!(history_count(&test_db, old).await, 1);
+
+    assert!(MedicalRecordService::apply_auto_archive(&test_db, 0, now).await.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// incremental refresh
+// ---------------------------------------------------------------------------
+
+async fn set_updated_at(db: &DatabaseConnection, record_id: i64, updated_at: &str) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE medical_records SET updated_at = ? WHERE id = ?",
+        [updated_at.into(), record_id.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn records_changed_since_returns_only_updated_and_new_records() {
+    let test_db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&test_db).await;
+    let other_patient = seed_patient(&test_db).await;
+    let updated = insert_record(&test_db, patient_id, "Vaccination", "").await;
+    let untouched = insert_record(&test_db, patient_id, "Checkup", "").await;
+    let elsewhere = insert_record(&test_db, other_patient, "Dental", "").await;
+    for id in [updated, untouched, elsewhere] {
+        set_updated_at(&test_db, id, "2024-03-01 08:00:00").await;
+    }
+
+    let since = chrono::DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    MedicalRecordService::set_follow_up(&test_db, updated, NaiveDate::from_ymd_opt(2024, 4, 1))
+        .await
+        .unwrap();
+    // Created after `since`, stamped in SQLite's own format, which sorts
+    // before the RFC 3339 `since` as text
+    let created = insert_record(&test_db, patient_id, "Bloodwork", "").await;
+    set_updated_at(&test_db, created, "2024-03-01 10:00:00").await;
+
+    let changes = MedicalRecordService::get_records_changed_since(&test_db, patient_id, since)
+        .await
+        .unwrap();
+    let mut ids: Vec<i64> = changes.records.iter().map(|r| r.id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![updated, created]);
+    assert!(changes.checked_at > since);
+
+    let none = MedicalRecordService::get_records_changed_since(&test_db, patient_id, changes.checked_at)
+        .await
+        .unwrap();
+    assert!(none.records.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// move_records_to_patient
+// ---------------------------------------------------------------------------
+
+async fn insert_attachment(db: &DatabaseConnection, record_id: i64, name: &str) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, mime_type, uploaded_at) \
+         VALUES (?, ?, ?, 10, 'application/pdf', CURRENT_TIMESTAMP)",
+        [record_id.into(), format!("uuid-{}", name).into(), name.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+async fn attachment_names(db: &DatabaseConnection, patient_id: i64) -> Vec<String> {
+    let mut names: Vec<String> = MedicalRecordService::get_patient_attachments(db, patient_id, None, None)
+        .await
+        .unwrap()
+        .attachments
+        .into_iter()
+        .map(|a| a.attachment.original_name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn moved_records_take_their_attachments_to_the_new_patient() {
+    let test_db = create_test_db_with_migrations().await;
+    let wrong = seed_patient(&test_db).await;
+    let right = seed_patient(&test_db).await;
+    let blood = insert_record(&test_db, wrong, "Blood panel", "").await;
+    let xray = insert_record(&test_db, wrong, "X-Ray", "").await;
+    let kept = insert_record(&test_db, wrong, "Vaccination", "").await;
+    insert_attachment(&test_db, blood, "blood.pdf").await;
+    insert_attachment(&test_db, xray, "xray.pdf").await;
+    insert_attachment(&test_db, kept, "kept.pdf").await;
+
+    let result = MedicalRecordService::move_records_to_patient(&test_db, &[blood, xray], right, false, None)
+        .await
+        .expect("move");
+    assert_eq!(result.moved_records, 2);
+    assert_eq!(result.moved_attachments, 2);
+
+    assert_eq!(attachment_names(&test_db, right).await, vec!["blood.pdf", "xray.pdf"]);
+    assert_eq!(attachment_names(&test_db, wrong).await, vec!["kept.pdf"]);
+
+    let record = MedicalRecordService::get_medical_record(&test_db, blood, true).await.unwrap();
+    assert_eq!(record.record.patient_id, right);
+    let history = record.history.unwrap();
+    let entry = history.iter().find(|h| h.version == 2).expect("history snapshot of the move");
+    assert_eq!(entry.changed_fields.as_deref(), Some("patient_id"));
+}
+
+#[tokio::test]
+async fn move_refuses_other_species_unless_forced_and_inactive_targets() {
+    let test_db = create_test_db_with_migrations().await;
+    let dog = seed_patient(&test_db).await;
+    let record = insert_record(&test_db, dog, "Lab", "").await;
+    let cat = test_db
+        .execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "INSERT INTO patients (name, species_id) VALUES ('Tom', 2)".to_string(),
+        ))
+        .await
+        .unwrap()
+        .last_insert_id() as i64;
+
+    let err = MedicalRecordService::move_records_to_patient(&test_db, &[record], cat, false, None)
+        .await
+        .unwrap_err();
+    assert!(err.contains("another species"), "got: {}", err);
+
+    let inactive = seed_patient(&test_db).await;
+    test_db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE patients SET is_active = 0 WHERE id = ?",
+            [inactive.into()],
+        ))
+        .await
+        .unwrap();
+    let err = MedicalRecordService::move_records_to_patient(&test_db, &[record], inactive, false, None)
+        .await
+        .unwrap_err();
+    assert!(err.contains("inactive"), "got: {}", err);
+
+    let result = MedicalRecordService::move_records_to_patient(&test_db, &[record], cat, true, None)
+        .await
+        .expect("forced move");
+    assert_eq!(result.moved_records, 1);
+}
+
+
+// ---------------------------------------------------------------------------
+// export_selected_records_pdf
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn record_selection_contains_only_the_chosen_records() {
+    use crate::services::device_pdf_service::PatientData;
+    use crate::services::java_pdf_service::JavaPdfService;
+
+    let test_db = create_test_db_with_migrations().await;
+    let patient = seed_patient(&test_db).await;
+    let mut ids = Vec::new();
+    for name in ["Vaccination", "Dental", "X-ray", "Bloodwork"] {
+        ids.push(insert_record(&test_db, patient, name, "notes").await);
+    }
+    let other = seed_patient(&test_db).await;
+    let foreign = insert_record(&test_db, other, "Spay", "notes").await;
+
+    let records = MedicalRecordService::get_records_for_export(&test_db, patient, &[ids[3], ids[1]])
+        .await
+        .expect("export selection");
+    let mut names: Vec<&str> = records.iter().map(|d| d.record.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["Bloodwork", "Dental"]);
+
+    let patient_data = PatientData {
+        name: "TestPet".to_string(),
+        owner: String::new(),
+        species: "Dog".to_string(),
+        microchip_id: None,
+        gender: "Unknown".to_string(),
+        date_of_birth: None,
+    };
+    let input = JavaPdfService::record_selection_input("out.pdf", &patient_data, &records);
+    assert_eq!(input["report_type"], "records");
+    let printed: Vec<&str> = input["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(printed.len(), 2);
+    assert!(!printed.contains(&"Vaccination") && !printed.contains(&"X-ray"));
+
+    let err = MedicalRecordService::get_records_for_export(&test_db, patient, &[ids[0], foreign])
+        .await
+        .unwrap_err();
+    assert!(err.contains(&foreign.to_string()), "got: {}", err);
+}
+
+// ---------------------------------------------------------------------------
+// revert to a version
+// ---------------------------------------------------------------------------
+
+fn edit(name: &str, price: Option<f64>, prescription_notes: Option<&str>) -> UpdateMedicalRecordInput {
+    UpdateMedicalRecordInput {
+        record_type: None,
+        name: Some(name.to_string()),
+        procedure_name: None,
+        description: None,
+        prescription_notes: prescription_notes.map(str::to_string),
+        price: price.map_or(MaybeNull::Null, MaybeNull::Value),
+        currency_id: MaybeNull::Undefined,
+        discount_percent: MaybeNull::Undefined,
+        manual_total: MaybeNull::Undefined,
+        is_archived: None,
+        line_items: None,
+    }
+}
+
+/// A record created at version 1 and edited to versions 2, 3 and 4.
+async fn seed_edited_record(db: &DatabaseConnection) -> i64 {
+    let patient_id = seed_patient(db).await;
+    let input = CreateMedicalRecordInput {
+        patient_id,
+        record_type: "procedure".to_string(),
+        name: "Dental".to_string(),
+        procedure_name: None,
+        description: "Scaling".to_string(),
+        prescription_notes: None,
+        price: Some(100.0),
+        currency_id: None,
+        discount_percent: None,
+        manual_total: None,
+        device_test_data: None,
+        device_type: None,
+        device_name: None,
+        device_data_list: None,
+        line_items: None,
+    };
+    let record = MedicalRecordService::insert_medical_record(db, &input, Utc::now(), None).await.unwrap();
+    for update in [
+        edit("Dental, two extractions", Some(180.0), None),
+        edit("Dental, three extractions", Some(240.0), Some("Meloxicam 3 days")),
+        edit("Dental, three extractions", None, Some("Meloxicam 5 days")),
+    ] {
+        MedicalRecordService::apply_medical_record_update(db, record.id, update, None).await.unwrap();
+    }
+    record.id
+}
+
+#[tokio::test]
+async fn revert_to_version_restores_that_snapshot_as_a_new_version() {
+    let db = create_test_db_with_migrations().await;
+    let id = seed_edited_record(&db).await;
+
+    let updates = MedicalRecordService::updates_for_version(&db, id, 1).await.unwrap();
+    let reverted = MedicalRecordService::apply_medical_record_update(&db, id, updates, None).await.unwrap();
+
+    assert_eq!(reverted.version, 5);
+    assert_eq!(reverted.name, "Dental");
+    assert_eq!(reverted.description, "Scaling");
+    assert_eq!(reverted.price, Some(100.0));
+    assert_eq!(reverted.prescription_notes.as_deref().unwrap_or(""), "", "notes added later are cleared");
+    assert_eq!(history_count(&db, id).await, 5, "the revert is recorded, nothing is dropped");
+}
+
+#[tokio::test]
+async fn revert_undoes_a_record_type_change() {
+    let db = create_test_db_with_migrations().await;
+    let id = seed_edited_record(&db).await;
+    MedicalRecordService::change_record_type(&db, id, "note", None, None, None).await.unwrap();
+
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT old_values FROM medical_record_history WHERE medical_record_id = ? AND version = 5",
+            [id.into()],
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let old_values: serde_json::Value =
+        serde_json::from_str(&row.try_get::<String>("", "old_values").unwrap()).unwrap();
+    assert_eq!(old_values["name"], json!("Dental, three extractions"), "full snapshot: {}", old_values);
+    assert_eq!(old_values["prescription_notes"], json!("Meloxicam 5 days"));
+
+    let updates = MedicalRecordService::updates_for_version(&db, id, 3).await.unwrap();
+    let reverted = MedicalRecordService::apply_medical_record_update(&db, id, updates, None).await.unwrap();
+    assert_eq!(reverted.record_type, "procedure");
+    assert_eq!(reverted.price, Some(240.0));
+    assert_eq!(reverted.version, 6);
+}
+
+#[tokio::test]
+async fn revert_to_a_partial_version_restores_the_state_at_that_version() {
+    let db = create_test_db_with_migrations().await;
+    let id = seed_edited_record(&db).await;
+    let other = seed_patient(&db).await;
+    MedicalRecordService::change_record_type(&db, id, "note", None, None, None).await.unwrap();
+    // Version 6 only records the new patient
+    MedicalRecordService::move_records_to_patient(&db, &[id], other, false, None).await.unwrap();
+    MedicalRecordService::apply_medical_record_update(&db, id, edit("Renamed", None, None), None).await.unwrap();
+
+    let updates = MedicalRecordService::updates_for_version(&db, id, 6).await.unwrap();
+    let reverted = MedicalRecordService::apply_medical_record_update(&db, id, updates, None).await.unwrap();
+    assert_eq!(reverted.record_type, "note");
+    assert_eq!(reverted.name, "Dental, three extractions");
+    assert_eq!(reverted.prescription_notes.as_deref(), Some("Meloxicam 5 days"));
+    assert_eq!(reverted.patient_id, other, "a revert doesn't move the record back");
+
+    // Past the type change
+    let updates = MedicalRecordService::updates_for_version(&db, id, 4).await.unwrap();
+    let reverted = MedicalRecordService::apply_medical_record_update(&db, id, updates, None).await.unwrap();
+    assert_eq!(reverted.record_type, "procedure");
+    assert_eq!(reverted.version, 9);
+}
+
+#[tokio::test]
+async fn revert_to_a_version_with_nothing_to_restore_is_refused() {
+    let db = create_test_db_with_migrations().await;
+    let patient_id = seed_patient(&db).await;
+    let other = seed_patient(&db).await;
+    // No create snapshot, so the move is the only history there is
+    let id = insert_record(&db, patient_id, "Dental", "Scaling").await;
+    MedicalRecordService::move_records_to_patient(&db, &[id], other, false, None).await.unwrap();
+    MedicalRecordService::apply_medical_record_update(&db, id, edit("Renamed", None, None), None).await.unwrap();
+
+    let err = MedicalRecordService::updates_for_version(&db, id, 2).await.unwrap_err();
+    assert!(err.contains("No revertable fields in version 2"), "got: {}", err);
+}
+
+#[tokio::test]
+async fn revert_to_version_rejects_missing_and_current_versions() {
+    let db = create_test_db_with_migrations().await;
+    let id = seed_edited_record(&db).await;
+
+    let err = MedicalRecordService::updates_for_version(&db, id, 9).await.unwrap_err();
+    assert!(err.contains("Version 9 not found"), "got: {}", err);
+    let err = MedicalRecordService::updates_for_version(&db, id, 4).await.unwrap_err();
+    assert!(err.contains("already at version 4"), "got: {}", err);
+    assert!(MedicalRecordService::updates_for_version(&db, id + 1, 1).await.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// history diff
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn diff_reports_only_the_fields_an_edit_changed() {
+    let db = create_test_db_with_migrations().await;
+    let id = seed_edited_record(&db).await;
+    let update = UpdateMedicalRecordInput {
+        description: Some("Scaling and polishing".to_string()),
+        price: MaybeNull::Value(260.0),
+        ..edit("Dental, three extractions", None, None)
+    };
+    MedicalRecordService::apply_medical_record_update(&db, id, update, None).await.unwrap();
+
+    let changes = MedicalRecordService::get_medical_record_diff(&db, id, 5).await.unwrap();
+    assert_eq!(
+        changes,
+        vec![
+            FieldChange {
+                field: "description".to_string(),
+                old: json!("Scaling"),
+                new: json!("Scaling and polishing"),
+            },
+            FieldChange { field: "price".to_string(), old: json!(null), new: json!(260.0) },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn diff_of_the_create_entry_lists_every_field_as_added() {
+    let db = create_test_db_with_migrations().await;
+    let id = seed_edited_record(&db).await;
+
+    let changes = MedicalRecordService::get_medical_record_diff(&db, id, 1).await.unwrap();
+    assert!(changes.iter().all(|c| c.old.is_null()));
+    let name = changes.iter().find(|c| c.field == "name").expect("name is reported");
+    assert_eq!(name.new, json!("Dental"));
+    assert!(changes.iter().any(|c| c.field == "is_archived"));
+
+    let err = MedicalRecordService::get_medical_record_diff(&db, id, 9).await.unwrap_err();
+    assert!(err.contains("Version 9 not found"), "got: {}", err);
+}
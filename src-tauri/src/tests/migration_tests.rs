@@ -1,7 +1,7 @@
 //! Migration smoke tests — every migration must be idempotent so a crashed
 //! boot can re-run safely. Most edge-case bugs in migrations show up here.
 
-use crate::database::migrations::run_migrations;
+use crate::database::migrations::{preview_pending_migrations, run_migrations};
 use crate::test_utils::create_test_db_with_migrations;
 use sea_orm::{ConnectionTrait, DbBackend, Statement};
 
@@ -56,6 +56,43 @@ async fn migrations_are_idempotent_on_second_run() {
     assert_eq!(first_count, second_count, "no new migration rows on rerun");
 }
 
+#[tokio::test]
+async fn preview_is_empty_once_every_migration_ran() {
+    let test_db = create_test_db_with_migrations().await;
+    let preview = preview_pending_migrations(test_db.db.get_sqlite_connection_pool()).await.unwrap();
+    assert!(preview.pending.is_empty());
+    assert!(!preview.backup_recommended);
+}
+
+#[tokio::test]
+async fn preview_flags_an_unapplied_destructive_migration_with_its_row_count() {
+    let test_db = create_test_db_with_migrations().await;
+    let pool = test_db.db.get_sqlite_connection_pool().clone();
+    sqlx::query("INSERT INTO patients (name) VALUES ('Rex'), ('Bella')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    // As if the database predates 041 and 062
+    sqlx::query("DELETE FROM migrations WHERE filename IN ('041_relax_patient_required_fields', '062_add_room_display_order')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let migrations_before = count(&test_db, "migrations").await;
+
+    let preview = preview_pending_migrations(&pool).await.unwrap();
+
+    let names: Vec<&str> = preview.pending.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["041_relax_patient_required_fields", "062_add_room_display_order"]);
+    let rebuild = &preview.pending[0];
+    assert!(rebuild.destructive && !rebuild.drops_data);
+    assert_eq!(rebuild.tables.len(), 1);
+    assert_eq!((rebuild.tables[0].table.as_str(), rebuild.tables[0].rows), ("patients", 2));
+    assert!(!preview.pending[1].destructive);
+    assert!(preview.pending[1].tables.is_empty());
+    assert!(preview.backup_recommended);
+    assert_eq!(count(&test_db, "migrations").await, migrations_before, "the preview applies nothing");
+}
+
 #[tokio::test]
 async fn migration_041_makes_patient_name_nullable() {
     // Pin the recent schema relaxation. If a future migration tightens the
@@ -72,6 +109,56 @@ async fn migration_041_makes_patient_species_id_nullable() {
     assert_eq!(notnull, 0, "patients.species_id should be nullable after migration 041");
 }
 
+#[tokio::test]
+async fn migration_069_stores_integer_and_float_prices_alike() {
+    let test_db = create_test_db_with_migrations().await;
+    let pool = test_db.db.get_sqlite_connection_pool().clone();
+    // Put back the DECIMAL(10,2) column older databases have and rerun 069
+    for sql in [
+        "ALTER TABLE medical_records ADD COLUMN price_decimal DECIMAL(10,2)",
+        "ALTER TABLE medical_records DROP COLUMN price",
+        "ALTER TABLE medical_records RENAME COLUMN price_decimal TO price",
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, price, is_archived, version) \
+         VALUES (1, 1, 'procedure', 'Whole', '', 45, 0, 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, price, is_archived, version) \
+         VALUES (2, 1, 'procedure', 'Float', '', 45.0, 0, 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, price, is_archived, version) \
+         VALUES (3, 1, 'procedure', 'Cents', '', 45.5, 0, 1)",
+        "DELETE FROM migrations WHERE filename = '069_store_record_prices_as_real'",
+    ] {
+        sqlx::query(sql).execute(&pool).await.unwrap();
+    }
+    let integer_rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM medical_records WHERE typeof(price) = 'integer'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(integer_rows, 2, "45 and 45.0 are both stored as INTEGER");
+
+    run_migrations(&pool).await.unwrap();
+
+    let prices: Vec<(f64, String)> =
+        sqlx::query_as("SELECT price, typeof(price) FROM medical_records ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        prices,
+        vec![(45.0, "real".to_string()), (45.0, "real".to_string()), (45.5, "real".to_string())]
+    );
+
+    // Whole amounts written after the migration stay REAL
+    sqlx::query("UPDATE medical_records SET price = 60 WHERE id = 3")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let kind: String = sqlx::query_scalar("SELECT typeof(price) FROM medical_records WHERE id = 3")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(kind, "real");
+}
+
 #[tokio::test]
 async fn species_seeded_with_default_rows() {
     let db = create_test_db_with_migrations().await;
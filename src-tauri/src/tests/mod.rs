@@ -57,3 +57,66 @@ pub mod file_storage_tests;
 
 #[cfg(test)]
 pub mod diagnosis_tests;
+
+#[cfg(test)]
+pub mod dashboard_tests;
+
+#[cfg(test)]
+pub mod data_integrity_tests;
+
+#[cfg(test)]
+pub mod log_export_tests;
+
+#[cfg(test)]
+pub mod appointment_import_tests;
+
+#[cfg(test)]
+pub mod appointment_sync_tests;
+
+#[cfg(test)]
+pub mod calendar_pull_tests;
+
+#[cfg(test)]
+pub mod calendar_view_tests;
+
+#[cfg(test)]
+pub mod next_slot_tests;
+
+#[cfg(test)]
+pub mod room_reassignment_tests;
+
+#[cfg(test)]
+pub mod attachment_ocr_tests;
+
+#[cfg(test)]
+pub mod day_sheet_tests;
+
+#[cfg(test)]
+pub mod events_tests;
+
+#[cfg(test)]
+pub mod appointment_statistics_tests;
+
+#[cfg(test)]
+pub mod pdf_jobs_tests;
+
+#[cfg(test)]
+pub mod vaccination_tests;
+
+#[cfg(test)]
+pub mod household_export_tests;
+
+#[cfg(test)]
+pub mod user_tests;
+
+#[cfg(test)]
+pub mod storage_usage_tests;
+
+#[cfg(test)]
+pub mod google_connection_tests;
+
+#[cfg(test)]
+pub mod clinic_profile_tests;
+
+#[cfg(test)]
+pub mod patient_import_tests;
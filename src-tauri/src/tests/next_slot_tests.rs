@@ -0,0 +1,91 @@
+//! Tests for `AppointmentService::find_next_available_slot`.
+//!
+//! Working hours are resolved in UTC here so the results don't depend on
+//! the machine's timezone; the command passes `Local`.
+
+use crate::models::CreateAppointmentInput;
+use crate::services::appointments::AppointmentService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient, create_test_room};
+use chrono::{DateTime, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+
+async fn book(db: &DatabaseConnection, patient_id: i64, room_id: i64, start: DateTime<Utc>, end: DateTime<Utc>) {
+    AppointmentService::create_appointment(
+        db,
+        CreateAppointmentInput {
+            patient_id,
+            title: "Booked".to_string(),
+            description: None,
+            start_time: start,
+            end_time: end,
+            room_id: Some(room_id),
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap();
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 1, day, hour, minute, 0).unwrap()
+}
+
+#[tokio::test]
+async fn busy_morning_returns_first_afternoon_slot() {
+    let db = create_test_db_with_migrations().await;
+    let patient = create_test_patient(&db, "Rex", 1, None).await;
+    let surgery = create_test_room(&db, "Surgery").await;
+    book(&db, patient, surgery, at(16, 8, 0), at(16, 10, 30)).await;
+    book(&db, patient, surgery, at(16, 10, 30), at(16, 12, 0)).await;
+    // A 15-minute gap is too short for a 30-minute slot
+    book(&db, patient, surgery, at(16, 12, 15), at(16, 13, 0)).await;
+
+    let slot = AppointmentService::find_next_available_slot(&db, Some(surgery), 30, at(16, 7, 10), 7, &Utc)
+        .await
+        .unwrap()
+        .expect("a slot later that day");
+    assert_eq!(slot.room_id, surgery);
+    assert_eq!(slot.room_name, "Surgery");
+    assert_eq!((slot.start_time, slot.end_time), (at(16, 13, 0), at(16, 13, 30)));
+
+    // Searching from mid-afternoon starts at the next quarter hour
+    let slot = AppointmentService::find_next_available_slot(&db, Some(surgery), 30, at(16, 14, 5), 7, &Utc)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(slot.start_time, at(16, 14, 15));
+}
+
+#[tokio::test]
+async fn any_room_returns_earliest_across_rooms_and_rolls_to_next_day() {
+    let db = create_test_db_with_migrations().await;
+    let patient = create_test_patient(&db, "Rex", 1, None).await;
+    let annex = create_test_room(&db, "Annex").await;
+    let surgery = create_test_room(&db, "Surgery").await;
+    book(&db, patient, annex, at(16, 8, 0), at(16, 11, 0)).await;
+    book(&db, patient, surgery, at(16, 8, 0), at(16, 9, 30)).await;
+
+    let slot = AppointmentService::find_next_available_slot(&db, None, 60, at(16, 8, 0), 7, &Utc)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((slot.room_id, slot.start_time), (surgery, at(16, 9, 30)));
+
+    // Too late in the day for two hours, so the search moves to the next morning
+    let slot = AppointmentService::find_next_available_slot(&db, Some(annex), 120, at(16, 16, 30), 7, &Utc)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(slot.start_time, at(17, 8, 0));
+
+    // Nothing fits inside a one-day window
+    let none = AppointmentService::find_next_available_slot(&db, Some(annex), 120, at(16, 16, 30), 1, &Utc)
+        .await
+        .unwrap();
+    assert!(none.is_none());
+
+    let err = AppointmentService::find_next_available_slot(&db, None, 20, at(16, 8, 0), 7, &Utc)
+        .await
+        .unwrap_err();
+    assert_eq!(err, "Duration must be a multiple of 15 minutes");
+}
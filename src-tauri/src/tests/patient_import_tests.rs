@@ -0,0 +1,145 @@
+//! Tests for the CSV patient import (`import_patients_from_csv`).
+
+use crate::services::patient::PatientService;
+use crate::services::patient_import::PatientImportService;
+use crate::test_utils::create_test_db_with_migrations;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+async fn count(db: &DatabaseConnection, table: &str) -> i64 {
+    db.query_one(Statement::from_string(
+        DbBackend::Sqlite,
+        format!("SELECT COUNT(*) AS n FROM {}", table),
+    ))
+    .await
+    .unwrap()
+    .unwrap()
+    .try_get("", "n")
+    .unwrap()
+}
+
+const WELL_FORMED: &str = "Name,Species,Breed,Date_of_Birth,Gender,Weight,Microchip_ID\r\n\
+                           Rex,dog,Labrador,2020-03-14,male,31.5,\r\n\
+                           \"Whiskers, Jr.\",Cat,,2022-11-02,Female,4.2,900000000000001\r\n\
+                           \r\n\
+                           Buddy,Dog,labrador,,,,\r\n";
+
+#[tokio::test]
+async fn imports_a_well_formed_csv_and_dry_run_writes_nothing() {
+    let test_db = create_test_db_with_migrations().await;
+
+    let preview = PatientImportService::import_csv(&test_db, WELL_FORMED, true, true)
+        .await
+        .unwrap();
+    assert_eq!(preview.iter().map(|r| r.line).collect::<Vec<_>>(), vec![2, 3, 5]);
+    assert!(preview.iter().all(|r| r.success && r.patient_id.is_none()), "{:?}", preview);
+    assert_eq!(preview[0].created_breed.as_deref(), Some("Labrador"));
+    assert_eq!(preview[2].created_breed, None, "the breed is only created once");
+    assert_eq!(count(&test_db, "patients").await, 0);
+    assert_eq!(count(&test_db, "breeds").await, 0);
+
+    let results = PatientImportService::import_csv(&test_db, WELL_FORMED, false, true)
+        .await
+        .unwrap();
+    assert!(results.iter().all(|r| r.success), "{:?}", results);
+    assert_eq!(results[0].created_breed.as_deref(), Some("Labrador"));
+    assert_eq!(count(&test_db, "breeds").await, 1);
+
+    let rex = PatientService::get_by_id(&test_db, results[0].patient_id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(rex.name.as_deref(), Some("Rex"));
+    assert_eq!(rex.gender.as_deref(), Some("Male"));
+    assert_eq!(rex.weight, Some(31.5));
+    assert_eq!(rex.date_of_birth, chrono::NaiveDate::from_ymd_opt(2020, 3, 14));
+    let buddy = PatientService::get_by_id(&test_db, results[2].patient_id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(buddy.breed_id, rex.breed_id);
+
+    let whiskers = PatientService::get_by_id(&test_db, results[1].patient_id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(whiskers.name.as_deref(), Some("Whiskers, Jr."));
+    assert_eq!(whiskers.microchip_id.as_deref(), Some("900000000000001"));
+    assert_eq!(whiskers.breed_id, None);
+}
+
+#[tokio::test]
+async fn rejects_a_row_with_an_unknown_species_by_line() {
+    let test_db = create_test_db_with_migrations().await;
+    let csv = "name,species\nRex,Dog\nNessie,Dragon\nTom,Cat\n";
+
+    let results = PatientImportService::import_csv(&test_db, csv, false, false)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results[0].success && results[2].success);
+    assert!(!results[1].success);
+    assert_eq!(results[1].line, 3);
+    assert_eq!(results[1].patient_id, None);
+    assert_eq!(results[1].error.as_deref(), Some("Unknown species 'Dragon'"));
+    assert_eq!(count(&test_db, "patients").await, 2);
+
+    // Breeds aren't created unless asked for
+    let results = PatientImportService::import_csv(&test_db, "name,species,breed\nMax,Dog,Beagle\n", false, false)
+        .await
+        .unwrap();
+    assert_eq!(results[0].error.as_deref(), Some("Unknown breed 'Beagle' for species 'Dog'"));
+
+    let err = PatientImportService::import_csv(&test_db, "name,colour\nRex,brown\n", true, false)
+        .await
+        .unwrap_err();
+    assert!(err.starts_with("Unknown column 'colour'"), "{}", err);
+}
+
+#[tokio::test]
+async fn rejects_a_row_with_a_malformed_weight() {
+    let test_db = create_test_db_with_migrations().await;
+    let csv = "name,species,weight\nRex,Dog,12kg\nBella,Dog,-3\nLuna,Cat,3.8\n";
+
+    let results = PatientImportService::import_csv(&test_db, csv, true, false)
+        .await
+        .unwrap();
+    assert_eq!(results[0].line, 2);
+    assert_eq!(results[0].error.as_deref(), Some("Invalid weight '12kg'; expected a positive number"));
+    assert_eq!(results[1].error.as_deref(), Some("Invalid weight '-3'; expected a positive number"));
+    assert!(results[2].success);
+    assert_eq!(count(&test_db, "patients").await, 0);
+}
+
+#[tokio::test]
+async fn matches_cyrillic_species_and_breeds_whatever_the_case() {
+    let test_db = create_test_db_with_migrations().await;
+    let species_id = test_db
+        .execute(Statement::from_string(DbBackend::Sqlite, "INSERT INTO species (name) VALUES ('Куче')"))
+        .await
+        .unwrap()
+        .last_insert_id() as i64;
+    let breed_id = test_db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO breeds (name, species_id) VALUES ('Шарпланинец', ?)",
+            [species_id.into()],
+        ))
+        .await
+        .unwrap()
+        .last_insert_id() as i64;
+    let csv = "name,species,breed\nШарко,куче,шарпланинец\nБели,КУЧЕ,ШАРПЛАНИНЕЦ\n";
+
+    let results = PatientImportService::import_csv(&test_db, csv, false, false)
+        .await
+        .unwrap();
+    assert!(results.iter().all(|r| r.success && r.created_breed.is_none()), "{:?}", results);
+    for result in &results {
+        let patient = PatientService::get_by_id(&test_db, result.patient_id.unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(patient.species_id, Some(species_id));
+        assert_eq!(patient.breed_id, Some(breed_id));
+    }
+    assert_eq!(count(&test_db, "breeds").await, 1);
+}
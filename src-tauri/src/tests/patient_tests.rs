@@ -5,8 +5,12 @@
 //! they can run in parallel without colliding.
 
 use crate::models::dto::{CreatePatientDto, UpdatePatientDto, MaybeNull};
+use crate::models::{CreateAppointmentInput, DuplicatePatientError, PatientWriteError, WeightTrendDirection};
+use crate::services::appointments::AppointmentService;
 use crate::services::patient::PatientService;
 use crate::test_utils::create_test_db_with_migrations;
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
 
 /// Build a `CreatePatientDto` with mostly-empty defaults so tests can override
 /// only the field(s) they care about.
@@ -463,7 +467,7 @@ async fn delete_with_no_references_succeeds() {
     assert!(deleted);
 
     let fetched = PatientService::get_by_id(&db, created.id).await.unwrap();
-    assert!(fetched.is_none(), "should be gone");
+    assert_eq!(fetched.map(|p| p.is_active), Some(false), "soft delete keeps the row");
 }
 
 #[tokio::test]
@@ -473,6 +477,198 @@ async fn delete_nonexistent_returns_false() {
     assert!(!deleted);
 }
 
+#[tokio::test]
+async fn soft_deleted_patient_keeps_history_and_can_be_restored() {
+    let db = create_test_db_with_migrations().await;
+    let rex = PatientService::create(&db, CreatePatientDto { name: Some("Rex".to_string()), ..minimal_dto() })
+        .await
+        .unwrap();
+    PatientService::create(&db, CreatePatientDto { name: Some("Bella".to_string()), ..minimal_dto() })
+        .await
+        .unwrap();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_records (patient_id, record_type, name, description) VALUES (?, 'note', 'Checkup', '')",
+        [rex.id.into()],
+    )).await.unwrap();
+    let start = Utc::now() + Duration::days(2);
+    AppointmentService::create_appointment(&db, CreateAppointmentInput {
+        patient_id: rex.id,
+        title: "Booster".to_string(),
+        description: None,
+        start_time: start,
+        end_time: start + Duration::minutes(30),
+        room_id: None,
+    }, "test".to_string()).await.unwrap();
+
+    assert!(PatientService::delete(&db, rex.id).await.unwrap());
+
+    let names = |patients: Vec<crate::models::Patient>| {
+        patients.into_iter().map(|p| p.name.unwrap()).collect::<Vec<_>>()
+    };
+    assert_eq!(names(PatientService::get_all(&db, false).await.unwrap()), vec!["Bella"]);
+    assert_eq!(names(PatientService::get_all(&db, true).await.unwrap()).len(), 2);
+    assert!(PatientService::search(&db, "Rex", false).await.unwrap().is_empty());
+    assert_eq!(names(PatientService::search(&db, "Rex", true).await.unwrap()), vec!["Rex"]);
+
+    // Nothing cascaded
+    let remaining = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT (SELECT COUNT(*) FROM medical_records WHERE patient_id = ?) AS records, \
+                (SELECT COUNT(*) FROM appointments WHERE patient_id = ?) AS appointments",
+        [rex.id.into(), rex.id.into()],
+    )).await.unwrap().unwrap();
+    assert_eq!(remaining.try_get::<i64>("", "records").unwrap(), 1);
+    assert_eq!(remaining.try_get::<i64>("", "appointments").unwrap(), 1);
+
+    let restored = PatientService::restore(&db, rex.id).await.unwrap().expect("patient exists");
+    assert!(restored.is_active);
+    assert_eq!(PatientService::search(&db, "Rex", false).await.unwrap().len(), 1);
+    assert!(PatientService::restore(&db, 99999).await.unwrap().is_none());
+}
+
+async fn patient_row_counts(db: &sea_orm::DatabaseConnection, patient_id: i64) -> (i64, i64, i64) {
+    let row = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT (SELECT COUNT(*) FROM medical_records WHERE patient_id = ?1) AS records, \
+                (SELECT COUNT(*) FROM appointments WHERE patient_id = ?1) AS appointments, \
+                (SELECT COUNT(*) FROM patient_households WHERE patient_id = ?1) AS households",
+        [patient_id.into()],
+    )).await.unwrap().unwrap();
+    (
+        row.try_get("", "records").unwrap(),
+        row.try_get("", "appointments").unwrap(),
+        row.try_get("", "households").unwrap(),
+    )
+}
+
+/// Rex (duplicate, two records, an appointment, two households) and Rexy
+/// (one record, already in one of those households).
+async fn seed_duplicate_patients(db: &sea_orm::DatabaseConnection) -> (i64, i64) {
+    let dup = PatientService::create(db, CreatePatientDto { name: Some("Rex".to_string()), ..minimal_dto() })
+        .await
+        .unwrap()
+        .id;
+    let keep = PatientService::create(db, CreatePatientDto { name: Some("Rexy".to_string()), ..minimal_dto() })
+        .await
+        .unwrap()
+        .id;
+    let record_sql = "INSERT INTO medical_records (patient_id, record_type, name, description) VALUES (?, 'note', 'Visit', '')";
+    for patient_id in [dup, dup, keep] {
+        insert_id(db, record_sql, vec![patient_id.into()]).await;
+    }
+    let start = Utc::now() + Duration::days(2);
+    AppointmentService::create_appointment(db, CreateAppointmentInput {
+        patient_id: dup,
+        title: "Booster".to_string(),
+        description: None,
+        start_time: start,
+        end_time: start + Duration::minutes(30),
+        room_id: None,
+    }, "test".to_string()).await.unwrap();
+    let shared = insert_id(db, "INSERT INTO households (household_name) VALUES ('Novak')", vec![]).await;
+    let other = insert_id(db, "INSERT INTO households (household_name) VALUES ('Kovac')", vec![]).await;
+    let link = "INSERT INTO patient_households (patient_id, household_id, is_primary) VALUES (?, ?, 1)";
+    insert_id(db, link, vec![keep.into(), shared.into()]).await;
+    insert_id(db, link, vec![dup.into(), shared.into()]).await;
+    insert_id(db, link, vec![dup.into(), other.into()]).await;
+    (dup, keep)
+}
+
+#[tokio::test]
+async fn merge_moves_records_appointments_and_households_then_deletes_the_source() {
+    let db = create_test_db_with_migrations().await;
+    let (dup, keep) = seed_duplicate_patients(&db).await;
+
+    let merged = PatientService::merge(&db, dup, keep, None).await.unwrap();
+
+    assert_eq!(merged.patient.id, keep);
+    assert_eq!(merged.medical_records_moved, 2);
+    assert_eq!(merged.appointments_moved, 1);
+    assert_eq!((merged.households_moved, merged.households_skipped), (1, 1));
+    assert_eq!(patient_row_counts(&db, keep).await, (3, 1, 2));
+    assert_eq!(patient_row_counts(&db, dup).await, (0, 0, 1), "the shared household stays on the source");
+
+    let primaries = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS n FROM patient_households WHERE patient_id = ? AND is_primary = 1",
+        [keep.into()],
+    )).await.unwrap().unwrap();
+    assert_eq!(primaries.try_get::<i64>("", "n").unwrap(), 1);
+    let history = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS n FROM medical_record_history WHERE changed_fields = 'patient_id'",
+        [],
+    )).await.unwrap().unwrap();
+    assert_eq!(history.try_get::<i64>("", "n").unwrap(), 2);
+
+    let source = PatientService::get_by_id(&db, dup).await.unwrap().unwrap();
+    assert!(!source.is_active, "the duplicate is soft-deleted");
+    assert_eq!(PatientService::merge(&db, keep, keep, None).await.unwrap_err(), "Can't merge a patient into itself");
+    assert_eq!(PatientService::merge(&db, keep, dup, None).await.unwrap_err(), format!("Patient {} is inactive", dup));
+}
+
+#[tokio::test]
+async fn merge_rolls_back_everything_when_a_step_fails() {
+    let db = create_test_db_with_migrations().await;
+    let (dup, keep) = seed_duplicate_patients(&db).await;
+    // Records move first; make the appointment step fail after them
+    db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "CREATE TRIGGER fail_appointment_move BEFORE UPDATE OF patient_id ON appointments \
+         BEGIN SELECT RAISE(ABORT, 'appointments are locked'); END",
+    )).await.unwrap();
+
+    let err = PatientService::merge(&db, dup, keep, None).await.unwrap_err();
+    assert!(err.contains("appointments are locked"), "{}", err);
+
+    assert_eq!(patient_row_counts(&db, dup).await, (2, 1, 2));
+    assert_eq!(patient_row_counts(&db, keep).await, (1, 0, 1));
+    assert!(PatientService::get_by_id(&db, dup).await.unwrap().unwrap().is_active);
+    let history = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS n FROM medical_record_history WHERE changed_fields = 'patient_id'",
+        [],
+    )).await.unwrap().unwrap();
+    assert_eq!(history.try_get::<i64>("", "n").unwrap(), 0);
+}
+
+#[tokio::test]
+async fn hard_delete_is_for_admins_and_removes_history() {
+    use crate::models::user::CreateUserInput;
+    use crate::services::users::UserService;
+
+    let db = create_test_db_with_migrations().await;
+    let rex = PatientService::create(&db, CreatePatientDto { name: Some("Rex".to_string()), ..minimal_dto() })
+        .await
+        .unwrap();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_records (patient_id, record_type, name, description) VALUES (?, 'note', 'Checkup', '')",
+        [rex.id.into()],
+    )).await.unwrap();
+    let user = |role: &str| CreateUserInput { name: format!("Test {}", role), role: role.to_string() };
+    let receptionist = UserService::create_user(&db, user("receptionist")).await.unwrap();
+    let admin = UserService::create_user(&db, user("admin")).await.unwrap();
+
+    let refused = PatientService::hard_delete(&db, rex.id, Some(&receptionist)).await;
+    assert!(refused.unwrap_err().contains("Only admins"));
+    // Nobody picked is no admin either
+    let refused = PatientService::hard_delete(&db, rex.id, None).await;
+    assert!(refused.unwrap_err().contains("no user is selected"));
+    assert!(PatientService::get_by_id(&db, rex.id).await.unwrap().is_some());
+
+    assert!(PatientService::hard_delete(&db, rex.id, Some(&admin)).await.unwrap());
+    assert!(PatientService::get_by_id(&db, rex.id).await.unwrap().is_none());
+    let records = db.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT COUNT(*) AS n FROM medical_records WHERE patient_id = ?",
+        [rex.id.into()],
+    )).await.unwrap().unwrap();
+    assert_eq!(records.try_get::<i64>("", "n").unwrap(), 0);
+    assert!(!PatientService::hard_delete(&db, rex.id, Some(&admin)).await.unwrap());
+}
+
 // ---------------------------------------------------------------------------
 // search
 // ---------------------------------------------------------------------------
@@ -504,7 +700,7 @@ async fn search_by_exact_microchip_match() {
     .await
     .unwrap();
 
-    let results = PatientService::search(&db, "807010000007678").await.unwrap();
+    let results = PatientService::search(&db, "807010000007678", false).await.unwrap();
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].name.as_deref(), Some("Findme"));
 }
@@ -526,7 +722,7 @@ async fn search_by_partial_name() {
         .unwrap();
     }
 
-    let results = PatientService::search(&db, "Rex").await.unwrap();
+    let results = PatientService::search(&db, "Rex", false).await.unwrap();
     assert_eq!(results.len(), 2, "Rex and Rexy match prefix");
 }
 
@@ -547,7 +743,7 @@ async fn search_empty_query_returns_all() {
         .unwrap();
     }
 
-    let results = PatientService::search(&db, "").await.unwrap();
+    let results = PatientService::search(&db, "", false).await.unwrap();
     assert_eq!(results.len(), 3);
 }
 
@@ -570,15 +766,15 @@ async fn search_is_case_insensitive_for_cyrillic() {
     .unwrap();
 
     // Lowercase query must find the capitalized stored name.
-    let lower = PatientService::search(&db, "шарко").await.unwrap();
+    let lower = PatientService::search(&db, "шарко", false).await.unwrap();
     assert_eq!(lower.len(), 1, "lowercase Cyrillic query should match");
 
     // Uppercase query must also match.
-    let upper = PatientService::search(&db, "ШАРКО").await.unwrap();
+    let upper = PatientService::search(&db, "ШАРКО", false).await.unwrap();
     assert_eq!(upper.len(), 1, "uppercase Cyrillic query should match");
 
     // Partial (substring) Cyrillic match.
-    let partial = PatientService::search(&db, "арк").await.unwrap();
+    let partial = PatientService::search(&db, "арк", false).await.unwrap();
     assert_eq!(partial.len(), 1, "Cyrillic substring should match");
 }
 
@@ -598,7 +794,7 @@ async fn search_chip_only_patient_findable_by_chip() {
     .await
     .unwrap();
 
-    let results = PatientService::search(&db, "807010000007678").await.unwrap();
+    let results = PatientService::search(&db, "807010000007678", false).await.unwrap();
     assert_eq!(results.len(), 1);
     assert!(results[0].name.is_none());
 }
@@ -715,7 +911,7 @@ async fn create_each_valid_gender_value_succeeds() {
 #[tokio::test]
 async fn get_all_on_empty_database_returns_empty_vec() {
     let db = create_test_db_with_migrations().await;
-    let patients = PatientService::get_all(&db).await.unwrap();
+    let patients = PatientService::get_all(&db, false).await.unwrap();
     assert!(patients.is_empty());
 }
 
@@ -745,12 +941,12 @@ async fn search_special_characters_does_not_panic() {
 
     // Each of these should return Ok (possibly empty), never panic / error
     for q in ["'", "\"", ";", "DROP TABLE patients;", "%", "_", "*"] {
-        let result = PatientService::search(&db, q).await;
+        let result = PatientService::search(&db, q, false).await;
         assert!(result.is_ok(), "search panicked on input: {}", q);
     }
 
     // Verify the table is still intact afterward
-    let all = PatientService::get_all(&db).await.unwrap();
+    let all = PatientService::get_all(&db, false).await.unwrap();
     assert_eq!(all.len(), 1, "Bobby Tables should still exist");
 }
 
@@ -767,9 +963,9 @@ async fn case_insensitive_name_search() {
     )
     .await
     .unwrap();
-    let upper = PatientService::search(&db, "BUDDY").await.unwrap();
-    let lower = PatientService::search(&db, "buddy").await.unwrap();
-    let mixed = PatientService::search(&db, "Buddy").await.unwrap();
+    let upper = PatientService::search(&db, "BUDDY", false).await.unwrap();
+    let lower = PatientService::search(&db, "buddy", false).await.unwrap();
+    let mixed = PatientService::search(&db, "Buddy", false).await.unwrap();
     assert_eq!(upper.len(), 1);
     assert_eq!(lower.len(), 1, "search should be case-insensitive");
     assert_eq!(mixed.len(), 1);
@@ -817,42 +1013,691 @@ async fn update_with_all_undefined_is_no_op_but_succeeds() {
 // Microchip uniqueness pinning (task #20)
 // ---------------------------------------------------------------------------
 
+fn chip_dto(name: &str, chip: &str) -> CreatePatientDto {
+    CreatePatientDto {
+        name: Some(name.to_string()),
+        species_id: Some(1),
+        microchip_id: Some(chip.to_string()),
+        ..minimal_dto()
+    }
+}
+
 #[tokio::test]
-async fn microchip_id_is_not_unique_today() {
-    // No UNIQUE index exists on patients.microchip_id (verify via the schema).
-    // If a future migration adds one, this test fails — that's the signal to
-    // either rip out duplicate-microchip data or update this test to assert
-    // the new constraint.
+async fn create_with_new_microchip_succeeds() {
     let db = create_test_db_with_migrations().await;
 
-    PatientService::create(
-        &db,
-        CreatePatientDto {
-            name: Some("First".to_string()),
+    PatientService::create(&db, chip_dto("First", "807010000007678")).await.unwrap();
+    let second = PatientService::create(&db, chip_dto("Second", "900000000000001")).await;
+    assert!(second.is_ok(), "different chips don't collide");
+}
+
+#[tokio::test]
+async fn create_with_colliding_microchip_is_rejected_with_the_other_patient() {
+    // No UNIQUE index exists on patients.microchip_id (existing data may hold
+    // duplicates); the service refuses new ones instead.
+    let db = create_test_db_with_migrations().await;
+    let first = PatientService::create(&db, chip_dto("First", "807010000007678")).await.unwrap();
+
+    let err = PatientService::create_with_options(&db, chip_dto("Duplicate-chip", " 807010000007678 "), false)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        PatientWriteError::DuplicateMicrochip(DuplicatePatientError {
+            patient_id: first.id,
+            patient_name: Some("First".to_string()),
+            microchip_id: "807010000007678".to_string(),
+        })
+    );
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["code"], "DUPLICATE_MICROCHIP");
+    assert_eq!(json["details"]["patientId"], first.id);
+
+    // The String-returning path reports the same conflict
+    let err = PatientService::create(&db, chip_dto("Duplicate-chip", "807010000007678")).await.unwrap_err();
+    assert_eq!(err, format!("Microchip 807010000007678 is already used by First (patient #{})", first.id));
+
+    // Legitimate edge cases can opt out
+    let allowed = PatientService::create_with_options(&db, chip_dto("Re-chipped", "807010000007678"), true).await;
+    assert!(allowed.is_ok());
+    let matches = PatientService::search(&db, "807010000007678", false).await.unwrap();
+    assert_eq!(matches.len(), 2);
+}
+
+#[tokio::test]
+async fn update_to_a_colliding_microchip_is_rejected() {
+    let db = create_test_db_with_migrations().await;
+    let first = PatientService::create(&db, chip_dto("First", "807010000007678")).await.unwrap();
+    let second = PatientService::create(&db, chip_dto("Second", "900000000000001")).await.unwrap();
+    let set_chip = |chip: &str| UpdatePatientDto {
+        name: None,
+        species_id: MaybeNull::Undefined,
+        breed_id: MaybeNull::Undefined,
+        gender: MaybeNull::Undefined,
+        date_of_birth: MaybeNull::Undefined,
+        color: MaybeNull::Undefined,
+        weight: MaybeNull::Undefined,
+        microchip_id: MaybeNull::Value(chip.to_string()),
+        medical_notes: MaybeNull::Undefined,
+        is_active: None,
+    };
+
+    let err = PatientService::update_with_options(&db, second.id, set_chip("807010000007678"), false)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PatientWriteError::DuplicateMicrochip(ref d) if d.patient_id == first.id), "{:?}", err);
+    let unchanged = PatientService::get_by_id(&db, second.id).await.unwrap().unwrap();
+    assert_eq!(unchanged.microchip_id.as_deref(), Some("900000000000001"));
+
+    // Saving a patient with its own chip isn't a collision
+    let same = PatientService::update(&db, first.id, set_chip("807010000007678")).await;
+    assert!(same.is_ok());
+}
+
+// ---------------------------------------------------------------------------
+// get_by_age_bracket (recall campaigns)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn age_bracket_includes_only_patients_in_range_with_owner_contact() {
+    let db = create_test_db_with_migrations().await;
+
+    let household_id = db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "INSERT INTO households (household_name) VALUES ('Novak')",
+    )).await.unwrap().last_insert_id() as i64;
+    let person_id = db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO people (household_id, first_name, last_name, is_primary) VALUES (?, 'Ana', 'Novak', 1)",
+        [household_id.into()],
+    )).await.unwrap().last_insert_id() as i64;
+    for (kind, value) in [("mobile", "+389 70 123 456"), ("email", "ana@example.com")] {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, 1)",
+            [person_id.into(), kind.into(), value.into()],
+        )).await.unwrap();
+    }
+
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    for (name, dob) in [
+        ("Puppy", NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()),   // 6 months
+        ("Senior", NaiveDate::from_ymd_opt(2017, 3, 10).unwrap()),  // 111 months
+        ("Ancient", NaiveDate::from_ymd_opt(2008, 1, 1).unwrap()),  // 221 months
+    ] {
+        PatientService::create(&db, CreatePatientDto {
+            name: Some(name.to_string()),
             species_id: Some(1),
-            microchip_id: Some("807010000007678".to_string()),
+            date_of_birth: Some(dob),
+            household_id: Some(household_id),
             ..minimal_dto()
-        },
-    )
-    .await
-    .unwrap();
+        }).await.unwrap();
+    }
+    // No DOB: can't be placed in a bracket
+    PatientService::create(&db, CreatePatientDto {
+        name: Some("Unknown age".to_string()),
+        species_id: Some(1),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let matches = PatientService::get_by_age_bracket(&db, Some(1), 84, 180, Some(true), today)
+        .await
+        .unwrap();
+
+    assert_eq!(matches.len(), 1);
+    let senior = &matches[0];
+    assert_eq!(senior.patient.name.as_deref(), Some("Senior"));
+    assert_eq!(senior.age_months, 111);
+    assert_eq!(senior.owner_name.as_deref(), Some("Ana Novak"));
+    assert_eq!(senior.owner_phone.as_deref(), Some("+389 70 123 456"));
+    assert_eq!(senior.owner_email.as_deref(), Some("ana@example.com"));
+}
+
+#[tokio::test]
+async fn age_bracket_takes_no_owner_from_a_deleted_household() {
+    let db = create_test_db_with_migrations().await;
+    let household_id = db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "INSERT INTO households (household_name, deleted_at) VALUES ('Novak', '2026-01-01T00:00:00+00:00')",
+    )).await.unwrap().last_insert_id() as i64;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO people (household_id, first_name, last_name, is_primary) VALUES (?, 'Ana', 'Novak', 1)",
+        [household_id.into()],
+    )).await.unwrap();
+    PatientService::create(&db, CreatePatientDto {
+        name: Some("Senior".to_string()),
+        species_id: Some(1),
+        date_of_birth: Some(NaiveDate::from_ymd_opt(2017, 3, 10).unwrap()),
+        household_id: Some(household_id),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let matches = PatientService::get_by_age_bracket(&db, Some(1), 84, 180, None, today)
+        .await
+        .unwrap();
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].owner_name, None);
+    assert_eq!(matches[0].owner_phone, None);
+}
+
+#[tokio::test]
+async fn age_bracket_rejects_inverted_range() {
+    let db = create_test_db_with_migrations().await;
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let err = PatientService::get_by_age_bracket(&db, None, 24, 12, None, today)
+        .await
+        .unwrap_err();
+    assert!(err.contains("Invalid age bracket"), "got: {}", err);
+}
+
+// ---------------------------------------------------------------------------
+// get_profile (patient detail screen)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn profile_populates_every_section_for_a_linked_patient() {
+    let db = create_test_db_with_migrations().await;
+
+    let household_id = db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "INSERT INTO households (household_name) VALUES ('Petrovski')",
+    )).await.unwrap().last_insert_id() as i64;
+    for (first, is_primary) in [("Marko", 0), ("Elena", 1)] {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO people (household_id, first_name, last_name, is_primary) VALUES (?, ?, 'Petrovski', ?)",
+            [household_id.into(), first.into(), is_primary.into()],
+        )).await.unwrap();
+    }
+
+    let patient = PatientService::create(&db, CreatePatientDto {
+        name: Some("Luna".to_string()),
+        species_id: Some(1),
+        date_of_birth: Some(NaiveDate::from_ymd_opt(2023, 2, 20).unwrap()),
+        household_id: Some(household_id),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let record_id = db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_records (patient_id, record_type, name, description) VALUES (?, 'procedure', 'Vaccination', '')",
+        [patient.id.into()],
+    )).await.unwrap().last_insert_id() as i64;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_records (patient_id, record_type, name, description, is_archived) VALUES (?, 'note', 'Old note', '', 1)",
+        [patient.id.into()],
+    )).await.unwrap();
+    for file_id in ["profile-a", "profile-b"] {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, mime_type) VALUES (?, ?, 'scan.pdf', 10, 'application/pdf')",
+            [record_id.into(), file_id.into()],
+        )).await.unwrap();
+    }
+
+    let now = Utc.with_ymd_and_hms(2026, 6, 15, 9, 0, 0).unwrap();
+    for (title, start) in [
+        ("Past visit", now - Duration::days(7)),
+        ("Booster", now + Duration::days(14)),
+        ("Checkup", now + Duration::days(2)),
+    ] {
+        AppointmentService::create_appointment(&db, CreateAppointmentInput {
+            patient_id: patient.id,
+            title: title.to_string(),
+            description: None,
+            start_time: start,
+            end_time: start + Duration::minutes(30),
+            room_id: None,
+        }, "test".to_string()).await.unwrap();
+    }
+
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let profile = PatientService::get_profile(&db, patient.id, today, now)
+        .await
+        .unwrap()
+        .expect("patient exists");
+
+    assert_eq!(profile.patient.name.as_deref(), Some("Luna"));
+    assert!(profile.patient.species.is_some(), "species name is resolved");
+    assert_eq!(profile.age_months, Some(39));
+    assert_eq!(profile.household.as_ref().map(|h| h.people.len()), Some(2));
+    assert_eq!(profile.primary_owner.as_ref().map(|p| p.first_name.as_str()), Some("Elena"));
+    assert_eq!(profile.medical_record_count, 1, "archived records are not counted");
+    assert_eq!(profile.attachment_count, 2);
+    assert_eq!(profile.next_appointment.as_ref().map(|a| a.title.as_str()), Some("Checkup"));
+}
+
+#[tokio::test]
+async fn profile_without_household_or_appointments() {
+    let db = create_test_db_with_migrations().await;
+    let patient = PatientService::create(&db, CreatePatientDto {
+        name: Some("Stray".to_string()),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 6, 15, 9, 0, 0).unwrap();
+    let profile = PatientService::get_profile(&db, patient.id, today, now)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(profile.household.is_none());
+    assert!(profile.primary_owner.is_none());
+    assert!(profile.age_months.is_none());
+    assert_eq!(profile.medical_record_count, 0);
+    assert!(profile.next_appointment.is_none());
+
+    assert!(PatientService::get_profile(&db, patient.id + 1000, today, now).await.unwrap().is_none());
+}
+
+// ---------------------------------------------------------------------------
+// get_primary_contact (PDF owner line, recall)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn primary_contact_uses_primary_person_and_their_contacts() {
+    let db = create_test_db_with_migrations().await;
+
+    let household_id = db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "INSERT INTO households (household_name) VALUES ('Trajkovski')",
+    )).await.unwrap().last_insert_id() as i64;
+    let mut primary_id = 0;
+    for (first, is_primary) in [("Goran", 0), ("Ivana", 1)] {
+        let id = db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO people (household_id, first_name, last_name, is_primary) VALUES (?, ?, 'Trajkovski', ?)",
+            [household_id.into(), first.into(), is_primary.into()],
+        )).await.unwrap().last_insert_id() as i64;
+        if is_primary == 1 {
+            primary_id = id;
+        }
+    }
+    for (kind, value, is_primary) in [
+        ("phone", "02 123 456", 0),
+        ("mobile", "+389 71 222 333", 1),
+        ("email", "ivana@example.com", 1),
+    ] {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO person_contacts (person_id, contact_type, contact_value, is_primary) VALUES (?, ?, ?, ?)",
+            [primary_id.into(), kind.into(), value.into(), is_primary.into()],
+        )).await.unwrap();
+    }
+    let patient = PatientService::create(&db, CreatePatientDto {
+        name: Some("Bella".to_string()),
+        household_id: Some(household_id),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let contact = PatientService::get_primary_contact(&db, patient.id)
+        .await
+        .unwrap()
+        .expect("linked patient has a contact");
+    assert_eq!(contact.name, "Ivana Trajkovski");
+    assert_eq!(contact.phone.as_deref(), Some("+389 71 222 333"));
+    assert_eq!(contact.email.as_deref(), Some("ivana@example.com"));
+    assert_eq!(contact.household_id, household_id);
+}
+
+#[tokio::test]
+async fn primary_contact_falls_back_to_household_name() {
+    let db = create_test_db_with_migrations().await;
+
+    let household_id = db.execute(Statement::from_string(
+        DbBackend::Sqlite,
+        "INSERT INTO households (household_name) VALUES ('Farm Stojanovski')",
+    )).await.unwrap().last_insert_id() as i64;
+    let patient = PatientService::create(&db, CreatePatientDto {
+        name: Some("Milka".to_string()),
+        household_id: Some(household_id),
+        ..minimal_dto()
+    }).await.unwrap();
+    let stray = PatientService::create(&db, CreatePatientDto {
+        name: Some("Stray".to_string()),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let contact = PatientService::get_primary_contact(&db, patient.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contact.name, "Farm Stojanovski");
+    assert_eq!((contact.phone, contact.email), (None, None));
+    assert_eq!(contact.household_id, household_id);
+
+    assert!(PatientService::get_primary_contact(&db, stray.id).await.unwrap().is_none());
+}
+
+// ---------------------------------------------------------------------------
+// get_weight_trend
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn weight_trend_flags_a_clear_loss() {
+    let db = create_test_db_with_migrations().await;
+    let patient = PatientService::create(&db, CreatePatientDto {
+        name: Some("Maxi".to_string()),
+        ..minimal_dto()
+    }).await.unwrap();
+    for (weight, recorded_at) in [
+        (20.0, "2026-01-10 09:00:00"), // outside the 90-day window
+        (18.0, "2026-04-01 09:00:00"),
+        (17.1, "2026-05-01 09:00:00"),
+        (15.3, "2026-06-10 09:00:00"),
+    ] {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO patient_weights (patient_id, weight, recorded_at) VALUES (?, ?, ?)",
+            [patient.id.into(), weight.into(), recorded_at.into()],
+        )).await.unwrap();
+    }
+
+    let now = Utc.with_ymd_and_hms(2026, 6, 15, 9, 0, 0).unwrap();
+    let trend = PatientService::get_weight_trend(&db, patient.id, 90, 10.0, now)
+        .await
+        .unwrap();
+
+    assert_eq!(trend.direction, WeightTrendDirection::Losing);
+    assert_eq!(trend.points.len(), 3);
+    assert_eq!(trend.points[0].weight, 18.0);
+    let pct = trend.percent_change.unwrap();
+    assert!((pct - -15.0).abs() < 1e-9, "got {}", pct);
+    assert!(trend.is_significant);
+
+    let lenient = PatientService::get_weight_trend(&db, patient.id, 90, 20.0, now)
+        .await
+        .unwrap();
+    assert!(!lenient.is_significant, "below a higher threshold");
+}
+
+#[tokio::test]
+async fn weight_trend_with_a_single_point_is_insufficient() {
+    let db = create_test_db_with_migrations().await;
+    let patient = PatientService::create(&db, CreatePatientDto {
+        name: Some("Coco".to_string()),
+        weight: Some(12.5),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let trend = PatientService::get_weight_trend(&db, patient.id, 30, 10.0, Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(trend.points.len(), 1, "the weight given at creation is recorded");
+    assert_eq!(trend.points[0].weight, 12.5);
+    assert_eq!(trend.direction, WeightTrendDirection::InsufficientData);
+    assert_eq!(trend.percent_change, None);
+    assert!(!trend.is_significant);
+}
+
+// ---------------------------------------------------------------------------
+// reclassify
+// ---------------------------------------------------------------------------
+
+async fn insert_id(db: &sea_orm::DatabaseConnection, sql: &str, values: Vec<sea_orm::Value>) -> i64 {
+    db.execute(Statement::from_sql_and_values(DbBackend::Sqlite, sql, values))
+        .await
+        .unwrap()
+        .last_insert_id() as i64
+}
+
+#[tokio::test]
+async fn reclassify_moves_patients_and_rejects_a_foreign_breed() {
+    let db = create_test_db_with_migrations().await;
+    let lizard = insert_id(&db, "INSERT INTO species (name) VALUES (?)", vec!["Lizard".into()]).await;
+    let snake = insert_id(&db, "INSERT INTO species (name) VALUES (?)", vec!["Snake".into()]).await;
+    let gecko = insert_id(&db, "INSERT INTO breeds (name, species_id) VALUES (?, ?)", vec!["Leopard gecko".into(), lizard.into()]).await;
+    let python = insert_id(&db, "INSERT INTO breeds (name, species_id) VALUES (?, ?)", vec!["Ball python".into(), snake.into()]).await;
+
+    let mut ids = Vec::new();
+    for name in ["Spike", "Rex", "Noodle"] {
+        let patient = PatientService::create(&db, CreatePatientDto {
+            name: Some(name.to_string()),
+            species_id: Some(snake),
+            breed_id: Some(python),
+            ..minimal_dto()
+        }).await.unwrap();
+        ids.push(patient.id);
+    }
+
+    let outcome = PatientService::reclassify(&db, ids.clone(), lizard, Some(gecko)).await.unwrap();
+    assert_eq!(outcome.patients_updated, 3);
+    assert_eq!(outcome.breeds_cleared, 0);
+    for id in &ids {
+        let patient = PatientService::get_by_id(&db, *id).await.unwrap().unwrap();
+        assert_eq!(patient.species_id, Some(lizard));
+        assert_eq!(patient.breed_id, Some(gecko));
+    }
+
+    // A snake breed under the lizard species is refused before any write
+    let err = PatientService::reclassify(&db, vec![ids[0]], lizard, Some(python)).await.unwrap_err();
+    assert!(err.contains("does not belong"), "unexpected error: {}", err);
+    let untouched = PatientService::get_by_id(&db, ids[0]).await.unwrap().unwrap();
+    assert_eq!(untouched.breed_id, Some(gecko));
+
+    // So is a list with a patient that doesn't exist
+    let err = PatientService::reclassify(&db, vec![ids[1], 99999], snake, None).await.unwrap_err();
+    assert!(err.contains("99999"), "unexpected error: {}", err);
+    let untouched = PatientService::get_by_id(&db, ids[1]).await.unwrap().unwrap();
+    assert_eq!(untouched.species_id, Some(lizard));
+
+    // Without a breed the old one is cleared
+    let outcome = PatientService::reclassify(&db, ids.clone(), snake, None).await.unwrap();
+    assert_eq!(outcome.breeds_cleared, 3);
+    let patient = PatientService::get_by_id(&db, ids[2]).await.unwrap().unwrap();
+    assert_eq!(patient.breed_id, None);
+}
 
-    let dup = PatientService::create(
+// ---------------------------------------------------------------------------
+// list_patients (main patient table)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn list_patients_pages_a_species_with_names_resolved() {
+    use crate::models::{PaginationParams, PatientListFilter, PatientSort, PatientSortField};
+
+    let db = create_test_db_with_migrations().await;
+    let household_id = insert_id(&db, "INSERT INTO households (household_name) VALUES (?)", vec!["Novak".into()]).await;
+    insert_id(
         &db,
-        CreatePatientDto {
-            name: Some("Duplicate-chip".to_string()),
+        "INSERT INTO people (household_id, first_name, last_name, is_primary) VALUES (?, 'Ana', 'Novak', 1)",
+        vec![household_id.into()],
+    ).await;
+    let beagle = insert_id(&db, "INSERT INTO breeds (name, species_id) VALUES (?, 1)", vec!["Beagle".into()]).await;
+
+    // Five dogs (one with an owner, breed and birthday) and a cat
+    for name in ["Dora", "Bruno", "Eli", "Cody"] {
+        PatientService::create(&db, CreatePatientDto {
+            name: Some(name.to_string()),
             species_id: Some(1),
-            microchip_id: Some("807010000007678".to_string()),
             ..minimal_dto()
-        },
+        }).await.unwrap();
+    }
+    let alfie = PatientService::create(&db, CreatePatientDto {
+        name: Some("Alfie".to_string()),
+        species_id: Some(1),
+        breed_id: Some(beagle),
+        date_of_birth: NaiveDate::from_ymd_opt(2024, 3, 20),
+        household_id: Some(household_id),
+        ..minimal_dto()
+    }).await.unwrap();
+    PatientService::create(&db, CreatePatientDto {
+        name: Some("Aaron the cat".to_string()),
+        species_id: Some(2),
+        ..minimal_dto()
+    }).await.unwrap();
+
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 6, 15, 9, 0, 0).unwrap();
+    let dogs = || Some(PatientListFilter { species_id: Some(1), ..Default::default() });
+    let page = |page| Some(PaginationParams { page: Some(page), page_size: Some(2) });
+
+    let first = PatientService::list_patients(&db, dogs(), page(1), None, today, now).await.unwrap();
+    assert_eq!(first.total, 5);
+    assert_eq!((first.page, first.page_size), (1, 2));
+    let names: Vec<_> = first.patients.iter().map(|p| p.patient.name.clone().unwrap()).collect();
+    assert_eq!(names, vec!["Alfie", "Bruno"]);
+
+    let alfie_row = &first.patients[0];
+    assert_eq!(alfie_row.patient.id, alfie.id);
+    assert_eq!(alfie_row.patient.species.as_deref(), Some("Dog"));
+    assert_eq!(alfie_row.patient.breed.as_deref(), Some("Beagle"));
+    assert_eq!(alfie_row.owner_name.as_deref(), Some("Ana Novak"));
+    assert_eq!(alfie_row.age_months, Some(26));
+    // The patient is aged on the same `today` as the row
+    assert_eq!(alfie_row.patient.age_display.as_deref(), Some("2y 2m"));
+    assert_eq!(first.patients[1].owner_name, None);
+
+    let last = PatientService::list_patients(&db, dogs(), page(3), None, today, now).await.unwrap();
+    let names: Vec<_> = last.patients.iter().map(|p| p.patient.name.clone().unwrap()).collect();
+    assert_eq!(names, vec!["Eli"]);
+
+    let descending = Some(PatientSort { field: PatientSortField::Name, descending: true });
+    let reversed = PatientService::list_patients(&db, dogs(), page(1), descending, today, now).await.unwrap();
+    let names: Vec<_> = reversed.patients.iter().map(|p| p.patient.name.clone().unwrap()).collect();
+    assert_eq!(names, vec!["Eli", "Dora"]);
+
+    // Only Alfie has an appointment coming up
+    AppointmentService::create_appointment(&db, CreateAppointmentInput {
+        patient_id: alfie.id,
+        title: "Vaccination".to_string(),
+        description: None,
+        start_time: now + Duration::days(3),
+        end_time: now + Duration::days(3) + Duration::minutes(30),
+        room_id: None,
+    }, "test".to_string()).await.unwrap();
+    let upcoming = PatientService::list_patients(
+        &db,
+        Some(PatientListFilter { has_upcoming_appointment: Some(true), ..Default::default() }),
+        None,
+        None,
+        today,
+        now,
+    ).await.unwrap();
+    assert_eq!(upcoming.total, 1);
+    assert_eq!(upcoming.patients[0].patient.id, alfie.id);
+}
+
+/// (name, record_count, attachment_count) for every row of the patient table
+async fn record_counts(db: &sea_orm::DatabaseConnection, today: NaiveDate, now: chrono::DateTime<Utc>) -> Vec<(String, i64, i64)> {
+    PatientService::list_patients(db, None, None, None, today, now)
+        .await
+        .unwrap()
+        .patients
+        .into_iter()
+        .map(|row| (row.patient.name.unwrap(), row.record_count, row.attachment_count))
+        .collect()
+}
+
+#[tokio::test]
+async fn list_patients_record_counts_follow_record_changes() {
+    let db = create_test_db_with_migrations().await;
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 6, 15, 9, 0, 0).unwrap();
+    let luna = PatientService::create(&db, CreatePatientDto { name: Some("Luna".to_string()), ..minimal_dto() })
+        .await
+        .unwrap();
+    let max = PatientService::create(&db, CreatePatientDto { name: Some("Max".to_string()), ..minimal_dto() })
+        .await
+        .unwrap();
+    assert_eq!(record_counts(&db, today, now).await, vec![("Luna".to_string(), 0, 0), ("Max".to_string(), 0, 0)]);
+
+    let record_sql = "INSERT INTO medical_records (patient_id, record_type, name, description) VALUES (?, 'note', 'Visit', '')";
+    let first = insert_id(&db, record_sql, vec![luna.id.into()]).await;
+    let second = insert_id(&db, record_sql, vec![luna.id.into()]).await;
+    for (record_id, file_id) in [(first, "count-a"), (first, "count-b"), (second, "count-c")] {
+        insert_id(
+            &db,
+            "INSERT INTO medical_attachments (medical_record_id, file_id, original_name) VALUES (?, ?, 'scan.pdf')",
+            vec![record_id.into(), file_id.into()],
+        ).await;
+    }
+    assert_eq!(record_counts(&db, today, now).await[0], ("Luna".to_string(), 2, 3));
+
+    // Archived records drop out of record_count; their attachments stay
+    db.execute(Statement::from_sql_and_values(DbBackend::Sqlite, "UPDATE medical_records SET is_archived = 1 WHERE id = ?", vec![second.into()])).await.unwrap();
+    assert_eq!(record_counts(&db, today, now).await[0], ("Luna".to_string(), 1, 3));
+
+    // Moving a record moves its counts to the new patient
+    db.execute(Statement::from_sql_and_values(DbBackend::Sqlite, "UPDATE medical_records SET patient_id = ?, is_archived = 0 WHERE id = ?", vec![max.id.into(), second.into()])).await.unwrap();
+    assert_eq!(record_counts(&db, today, now).await, vec![("Luna".to_string(), 1, 2), ("Max".to_string(), 1, 1)]);
+
+    // Deleting a record takes its attachments with it
+    db.execute(Statement::from_sql_and_values(DbBackend::Sqlite, "DELETE FROM medical_records WHERE id = ?", vec![first.into()])).await.unwrap();
+    assert_eq!(record_counts(&db, today, now).await, vec![("Luna".to_string(), 0, 0), ("Max".to_string(), 1, 1)]);
+
+    // A rebuild from scratch agrees with the triggers
+    db.execute(Statement::from_string(DbBackend::Sqlite, "DELETE FROM patient_record_counts")).await.unwrap();
+    assert_eq!(PatientService::rebuild_record_counts(&db).await.unwrap(), 1);
+    assert_eq!(record_counts(&db, today, now).await, vec![("Luna".to_string(), 0, 0), ("Max".to_string(), 1, 1)]);
+}
+
+// ---------------------------------------------------------------------------
+// age
+// ---------------------------------------------------------------------------
+
+fn age_on(dob: (i32, u32, u32), today: (i32, u32, u32)) -> Option<(i64, String)> {
+    PatientService::age_on(
+        NaiveDate::from_ymd_opt(dob.0, dob.1, dob.2),
+        NaiveDate::from_ymd_opt(today.0, today.1, today.2).unwrap(),
     )
-    .await;
+}
+
+#[test]
+fn age_display_by_offset() {
+    let today = (2026, 6, 15);
+    let display = |dob| age_on(dob, today).map(|(_, display)| display);
+    assert_eq!(age_on((2023, 4, 10), today), Some((38, "3y 2m".to_string())));
+    assert_eq!(display((2025, 6, 15)), Some("1y".to_string()));
+    assert_eq!(display((2025, 6, 16)), Some("11 months".to_string()));
+    assert_eq!(display((2026, 1, 15)), Some("5 months".to_string()));
+    assert_eq!(display((2026, 5, 15)), Some("1 month".to_string()));
+    assert_eq!(display((2026, 6, 1)), Some("2 weeks".to_string()));
+    assert_eq!(display((2026, 6, 8)), Some("1 week".to_string()));
+    assert_eq!(display((2026, 6, 12)), Some("3 days".to_string()));
+    assert_eq!(display((2026, 6, 15)), Some("0 days".to_string()));
+}
+
+#[test]
+fn age_across_leap_days() {
+    // Born on a leap day: a full year only once March starts
+    assert_eq!(age_on((2024, 2, 29), (2025, 2, 28)), Some((11, "11 months".to_string())));
+    assert_eq!(age_on((2024, 2, 29), (2025, 3, 1)), Some((12, "1y".to_string())));
+    assert_eq!(age_on((2024, 2, 29), (2028, 2, 29)), Some((48, "4y".to_string())));
+    // Weeks count the leap day
+    assert_eq!(age_on((2024, 2, 1), (2024, 2, 29)), Some((0, "4 weeks".to_string())));
+    assert_eq!(age_on((2023, 2, 1), (2023, 3, 1)), Some((1, "1 month".to_string())));
+}
+
+#[test]
+fn age_without_or_with_future_date_of_birth_is_none() {
+    assert_eq!(PatientService::age_on(None, NaiveDate::from_ymd_opt(2026, 6, 15).unwrap()), None);
+    assert_eq!(age_on((2026, 6, 16), (2026, 6, 15)), None);
+}
+
+#[tokio::test]
+async fn get_age_reports_the_patient_and_missing_ones() {
+    let db = create_test_db_with_migrations().await;
+    let luna = PatientService::create(&db, CreatePatientDto {
+        name: Some("Luna".to_string()),
+        date_of_birth: NaiveDate::from_ymd_opt(2023, 4, 10),
+        ..minimal_dto()
+    }).await.unwrap();
+    let stray = PatientService::create(&db, CreatePatientDto { name: Some("Stray".to_string()), ..minimal_dto() })
+        .await
+        .unwrap();
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
 
-    // Pin current behavior — duplicates ARE allowed today. Domain-wise this is
-    // wrong (a chip should be globally unique), but adding a UNIQUE constraint
-    // requires deduplication of existing data first. See: ARC42 §6 / task list.
-    assert!(dup.is_ok(), "duplicates allowed today; revisit if UNIQUE added");
+    let age = PatientService::get_age(&db, luna.id, today).await.unwrap();
+    assert_eq!((age.age_months, age.age_display.as_deref()), (Some(38), Some("3y 2m")));
+    let unknown = PatientService::get_age(&db, stray.id, today).await.unwrap();
+    assert_eq!((unknown.age_months, unknown.age_display), (None, None));
+    assert!(PatientService::get_age(&db, 99999, today).await.is_err());
 
-    let matches = PatientService::search(&db, "807010000007678").await.unwrap();
-    assert_eq!(matches.len(), 2, "both patients share the chip — both findable");
+    // Rows read from the database carry the age as of the real today
+    assert!(PatientService::get_by_id(&db, luna.id).await.unwrap().unwrap().age_display.is_some());
 }
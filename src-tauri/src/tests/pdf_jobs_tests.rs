@@ -0,0 +1,105 @@
+//! Cancellation of PDF jobs: the registry behind `create_pdf_job` /
+//! `cancel_pdf_job` and the process runner used for the Java JAR.
+
+use crate::services::device_pdf_service::DevicePdfService;
+use crate::services::java_pdf_service::JavaPdfService;
+use crate::services::pdf_jobs::{
+    cancel_job, create_job, prune_unstarted, JobEntry, PdfJob, PDF_JOB_CANCELLED,
+};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[test]
+fn cancelling_a_job_flips_its_token_until_the_job_ends() {
+    let handle = create_job();
+    let job = PdfJob::start(Some(handle.job_id.clone()));
+    assert!(job.token().check().is_ok());
+
+    assert!(cancel_job(&handle.job_id));
+    assert_eq!(job.token().check().unwrap_err(), PDF_JOB_CANCELLED);
+
+    drop(job);
+    assert!(!cancel_job(&handle.job_id), "finished jobs are unregistered");
+    assert!(!cancel_job("no-such-job"));
+}
+
+#[test]
+fn jobs_that_never_start_expire() {
+    let created = Instant::now();
+    let mut jobs = HashMap::new();
+    jobs.insert("waiting".to_string(), JobEntry::new(created));
+    let mut running = JobEntry::new(created);
+    running.mark_started();
+    jobs.insert("running".to_string(), running);
+
+    prune_unstarted(&mut jobs, created + Duration::from_secs(60));
+    assert_eq!(jobs.len(), 2, "a fresh job is kept until its command starts");
+
+    prune_unstarted(&mut jobs, created + Duration::from_secs(60 * 60));
+    assert!(!jobs.contains_key("waiting"));
+    assert!(jobs.contains_key("running"), "started jobs unregister on their own");
+}
+
+#[test]
+fn work_without_a_job_id_is_never_cancelled() {
+    let job = PdfJob::start(None);
+    assert!(!job.token().is_cancelled());
+    let unknown = PdfJob::start(Some("never-created".to_string()));
+    assert!(!unknown.token().is_cancelled());
+}
+
+#[test]
+fn generation_cancelled_before_it_starts_does_not_run() {
+    let handle = create_job();
+    cancel_job(&handle.job_id);
+    let job = PdfJob::start(Some(handle.job_id));
+
+    let (patient, data) = DevicePdfService::sample_report_data("exigo_eos_vet", "mk").unwrap();
+    let pdf_path = std::env::temp_dir().join("cancelled_before_start.pdf");
+    let err = JavaPdfService::generate_pdf_multi_with_jar(
+        &std::path::PathBuf::from("missing.jar"),
+        pdf_path.to_str().unwrap(),
+        &patient,
+        &[data],
+        job.token(),
+    )
+    .unwrap_err();
+    assert_eq!(err, PDF_JOB_CANCELLED);
+    assert!(!pdf_path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn cancelling_kills_a_running_process_promptly() {
+    use crate::services::pdf_jobs::run_cancellable;
+    use std::process::{Command, Stdio};
+    use std::thread;
+
+    let handle = create_job();
+    let job = PdfJob::start(Some(handle.job_id.clone()));
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        cancel_job(&handle.job_id);
+    });
+
+    let mut cmd = Command::new("sleep");
+    cmd.arg("30").stdout(Stdio::piped()).stderr(Stdio::piped());
+    let started = Instant::now();
+    let err = run_cancellable(cmd, job.token()).unwrap_err();
+
+    assert_eq!(err, PDF_JOB_CANCELLED);
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+}
+
+#[cfg(unix)]
+#[test]
+fn uncancelled_process_output_is_returned() {
+    use crate::services::pdf_jobs::{run_cancellable, PdfCancelToken};
+    use std::process::{Command, Stdio};
+
+    let mut cmd = Command::new("echo");
+    cmd.arg("done").stdout(Stdio::piped()).stderr(Stdio::piped());
+    let output = run_cancellable(cmd, &PdfCancelToken::none()).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "done");
+}
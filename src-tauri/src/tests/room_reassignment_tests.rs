@@ -0,0 +1,58 @@
+//! Tests for `AppointmentService::reassign_room_appointments`.
+
+use crate::models::CreateAppointmentInput;
+use crate::services::appointments::AppointmentService;
+use crate::services::rooms::RoomService;
+use crate::test_utils::{create_test_db_with_migrations, create_test_patient, create_test_room};
+use chrono::{DateTime, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+
+async fn book(db: &DatabaseConnection, patient_id: i64, room_id: i64, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+    AppointmentService::create_appointment(
+        db,
+        CreateAppointmentInput {
+            patient_id,
+            title: "Booked".to_string(),
+            description: None,
+            start_time: start,
+            end_time: end,
+            room_id: Some(room_id),
+        },
+        "test".to_string(),
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 3, 12, hour, minute, 0).unwrap()
+}
+
+#[tokio::test]
+async fn conflicting_appointment_stays_and_keeps_the_room_active() {
+    let db = create_test_db_with_migrations().await;
+    let patient = create_test_patient(&db, "Rex", 1, None).await;
+    let old_room = create_test_room(&db, "Old exam").await;
+    let new_room = create_test_room(&db, "New exam").await;
+
+    let fits = book(&db, patient, old_room, at(9, 0), at(10, 0)).await;
+    let clashes = book(&db, patient, old_room, at(11, 0), at(12, 0)).await;
+    let already_there = book(&db, patient, new_room, at(11, 30), at(12, 30)).await;
+
+    let result = AppointmentService::reassign_room_appointments(&db, old_room, new_room, at(0, 0), true)
+        .await
+        .unwrap();
+
+    assert_eq!(result.moved_appointment_ids, vec![fits]);
+    assert_eq!(result.unmoved.len(), 1);
+    assert_eq!(result.unmoved[0].appointment_id, clashes);
+    assert_eq!(result.unmoved[0].conflicting_appointment_ids, vec![already_there]);
+    assert!(!result.source_deactivated, "the room still has an appointment");
+
+    let moved = AppointmentService::get_appointment_by_id(&db, fits).await.unwrap();
+    assert_eq!(moved.appointment.room_id, Some(new_room));
+    let left = AppointmentService::get_appointment_by_id(&db, clashes).await.unwrap();
+    assert_eq!(left.appointment.room_id, Some(old_room), "unmoved appointments are untouched");
+    assert!(RoomService::get_room_by_id(&db, old_room).await.unwrap().is_active);
+}
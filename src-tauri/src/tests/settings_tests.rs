@@ -1,8 +1,15 @@
 //! SettingsService CRUD + edge cases.
 
+use crate::models::dto::CreatePatientDto;
 use crate::models::settings::UpdateSettingsRequest;
+use crate::models::{CalendarViewMode, CreateAppointmentInput, CreateRoomInput};
+use crate::services::appointments::AppointmentService;
+use crate::services::patient::PatientService;
+use crate::services::rooms::RoomService;
 use crate::services::settings::SettingsService;
 use crate::test_utils::create_test_db_with_migrations;
+use chrono::{NaiveDate, TimeZone, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 
 const USER: &str = "default";
 
@@ -154,3 +161,142 @@ async fn round_trip_language_change_survives_restart() {
     let fresh = SettingsService::get_settings(&db, USER).await.unwrap();
     assert_eq!(fresh.settings.language, "mk");
 }
+
+// ---------------------------------------------------------------------------
+// default room + appointment room auto-assignment
+// ---------------------------------------------------------------------------
+
+/// Replace the seeded rooms with `names` (all capacity 1), returning their ids.
+async fn setup_rooms(db: &DatabaseConnection, names: &[&str]) -> Vec<i64> {
+    db.execute_unprepared("UPDATE rooms SET is_active = 0").await.unwrap();
+    let mut ids = Vec::new();
+    for name in names {
+        let room = RoomService::create_room(db, CreateRoomInput {
+            name: name.to_string(),
+            description: None,
+            capacity: Some(1),
+            color: None,
+            buffer_minutes: None,
+        }).await.unwrap();
+        ids.push(room.id);
+    }
+    ids
+}
+
+async fn book(db: &DatabaseConnection, room_id: i64, hour: u32) {
+    let patient = PatientService::create(db, CreatePatientDto {
+        name: Some("Rex".to_string()), species_id: Some(1),
+        breed_id: None, gender: None, date_of_birth: None,
+        color: None, weight: None, microchip_id: None,
+        medical_notes: None, household_id: None,
+    }).await.unwrap();
+    AppointmentService::create_appointment(db, CreateAppointmentInput {
+        patient_id: patient.id,
+        title: "Checkup".to_string(),
+        description: None,
+        start_time: Utc.with_ymd_and_hms(2026, 5, 4, hour, 0, 0).unwrap(),
+        end_time: Utc.with_ymd_and_hms(2026, 5, 4, hour, 30, 0).unwrap(),
+        room_id: Some(room_id),
+    }, "test".to_string()).await.unwrap();
+}
+
+#[tokio::test]
+async fn set_default_room_persists_and_clears() {
+    let db = create_test_db_with_migrations().await;
+    let ids = setup_rooms(&db, &["Main Exam"]).await;
+
+    let updated = SettingsService::set_default_room(&db, USER, Some(ids[0])).await.unwrap();
+    assert_eq!(updated.settings.default_room_id, Some(ids[0]));
+
+    let cleared = SettingsService::set_default_room(&db, USER, None).await.unwrap();
+    assert_eq!(cleared.settings.default_room_id, None);
+
+    let err = SettingsService::set_default_room(&db, USER, Some(99_999)).await.unwrap_err();
+    assert!(err.contains("Invalid room_id"), "unexpected error: {}", err);
+}
+
+#[tokio::test]
+async fn search_tokenizer_defaults_to_unicode61_and_validates() {
+    let db = create_test_db_with_migrations().await;
+    let response = SettingsService::get_settings(&db, USER).await.unwrap();
+    assert_eq!(response.settings.search_tokenizer, "unicode61");
+
+    let updated = SettingsService::set_search_tokenizer(&db, USER, "porter").await.unwrap();
+    assert_eq!(updated.settings.search_tokenizer, "porter");
+
+    let err = SettingsService::set_search_tokenizer(&db, USER, "simple").await.unwrap_err();
+    assert!(err.contains("Invalid search tokenizer"), "unexpected error: {}", err);
+}
+
+#[tokio::test]
+async fn auto_assign_prefers_default_room_when_free() {
+    let db = create_test_db_with_migrations().await;
+    let ids = setup_rooms(&db, &["Annex", "Main Exam"]).await;
+    SettingsService::set_default_room(&db, USER, Some(ids[1])).await.unwrap();
+
+    let start = Utc.with_ymd_and_hms(2026, 5, 4, 10, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2026, 5, 4, 10, 30, 0).unwrap();
+    let room = AppointmentService::auto_assign_room(&db, start, end).await.unwrap();
+    assert_eq!(room, Some(ids[1]), "default room wins over alphabetical order");
+}
+
+#[tokio::test]
+async fn auto_assign_picks_next_free_room_when_default_is_busy() {
+    let db = create_test_db_with_migrations().await;
+    let ids = setup_rooms(&db, &["Annex", "Main Exam", "Side Room"]).await;
+    let (annex, main, side) = (ids[0], ids[1], ids[2]);
+    SettingsService::set_default_room(&db, USER, Some(main)).await.unwrap();
+    book(&db, main, 10).await;
+
+    let start = Utc.with_ymd_and_hms(2026, 5, 4, 10, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2026, 5, 4, 10, 30, 0).unwrap();
+    assert_eq!(AppointmentService::auto_assign_room(&db, start, end).await.unwrap(), Some(annex));
+
+    // With every room taken nothing is assigned rather than double-booking
+    book(&db, annex, 10).await;
+    book(&db, side, 10).await;
+    assert_eq!(AppointmentService::auto_assign_room(&db, start, end).await.unwrap(), None);
+
+    // A different slot is free again, so the default is back in play
+    let later = Utc.with_ymd_and_hms(2026, 5, 4, 14, 0, 0).unwrap();
+    let later_end = Utc.with_ymd_and_hms(2026, 5, 4, 14, 30, 0).unwrap();
+    assert_eq!(AppointmentService::auto_assign_room(&db, later, later_end).await.unwrap(), Some(main));
+}
+
+#[tokio::test]
+async fn practice_timezone_is_validated_and_clearable() {
+    let db = create_test_db_with_migrations().await;
+    assert_eq!(SettingsService::get_practice_timezone(&db, USER).await.unwrap(), None);
+    assert_eq!(SettingsService::practice_timezone_name(&db, USER).await, "UTC");
+
+    let updated = SettingsService::set_practice_timezone(&db, USER, Some(" Europe/Skopje ")).await.unwrap();
+    assert_eq!(updated.settings.practice_timezone.as_deref(), Some("Europe/Skopje"));
+    assert_eq!(SettingsService::practice_timezone_name(&db, USER).await, "Europe/Skopje");
+
+    let err = SettingsService::set_practice_timezone(&db, USER, Some("Mars/Olympus")).await.unwrap_err();
+    assert!(err.contains("Unknown timezone"), "unexpected error: {}", err);
+
+    let cleared = SettingsService::set_practice_timezone(&db, USER, None).await.unwrap();
+    assert_eq!(cleared.settings.practice_timezone, None);
+}
+
+#[tokio::test]
+async fn calendar_days_follow_the_practice_timezone() {
+    let db = create_test_db_with_migrations().await;
+    let ids = setup_rooms(&db, &["Main Exam"]).await;
+    // 22:00 UTC on 4 May is already midnight on 5 May in Skopje (UTC+2 in summer)
+    book(&db, ids[0], 22).await;
+    SettingsService::set_practice_timezone(&db, USER, Some("Europe/Skopje")).await.unwrap();
+    let tz = SettingsService::get_practice_timezone(&db, USER).await.unwrap().unwrap();
+
+    let may_4 = NaiveDate::from_ymd_opt(2026, 5, 4).unwrap();
+    let view = AppointmentService::get_calendar_view(&db, CalendarViewMode::Day, may_4, &[], &tz)
+        .await
+        .unwrap();
+    assert!(view.days[0].entries.is_empty());
+
+    let view = AppointmentService::get_calendar_view(&db, CalendarViewMode::Day, may_4.succ_opt().unwrap(), &[], &tz)
+        .await
+        .unwrap();
+    assert_eq!(view.days[0].entries.len(), 1);
+}
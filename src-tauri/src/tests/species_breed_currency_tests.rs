@@ -36,6 +36,7 @@ async fn create_species_with_unique_name_succeeds() {
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "Ferret".to_string(),
         display_order: Some(100),
+        color: None,
     }).await.unwrap();
     assert_eq!(s.name, "Ferret");
     assert!(s.id > 9, "should get a fresh id beyond seeds");
@@ -49,6 +50,7 @@ async fn create_species_with_duplicate_name_fails_unique_constraint() {
     let result = SpeciesService::create(&db, CreateSpeciesInput {
         name: "Dog".to_string(),
         display_order: None,
+        color: None,
     }).await;
     assert!(result.is_err(), "duplicate species name should fail");
 }
@@ -59,6 +61,7 @@ async fn create_species_without_display_order_assigns_default() {
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "Hedgehog".to_string(),
         display_order: None,
+        color: None,
     }).await.unwrap();
     assert!(s.display_order >= 0, "display_order should be set");
 }
@@ -83,12 +86,14 @@ async fn update_species_name_persists() {
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "OldName".to_string(),
         display_order: Some(99),
+        color: None,
     }).await.unwrap();
 
     let updated = SpeciesService::update(&db, s.id, UpdateSpeciesInput {
         name: Some("NewName".to_string()),
         active: None,
         display_order: None,
+        color: None,
     }).await.unwrap();
     assert_eq!(updated.name, "NewName");
     assert_eq!(updated.display_order, 99, "display_order untouched");
@@ -100,12 +105,14 @@ async fn update_species_rename_to_existing_fails_unique() {
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "ToRename".to_string(),
         display_order: None,
+        color: None,
     }).await.unwrap();
 
     let result = SpeciesService::update(&db, s.id, UpdateSpeciesInput {
         name: Some("Dog".to_string()),  // conflicts with seeded
         active: None,
         display_order: None,
+        color: None,
     }).await;
     assert!(result.is_err());
 }
@@ -116,10 +123,11 @@ async fn update_species_active_toggle() {
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "Toggle".to_string(),
         display_order: None,
+        color: None,
     }).await.unwrap();
 
     let updated = SpeciesService::update(&db, s.id, UpdateSpeciesInput {
-        name: None, active: Some(false), display_order: None,
+        name: None, active: Some(false), display_order: None, color: None,
     }).await.unwrap();
     assert!(!updated.active);
 
@@ -130,12 +138,42 @@ async fn update_species_active_toggle() {
     assert!(all.iter().any(|x| x.id == s.id), "inactive should appear when active_only=false");
 }
 
+#[tokio::test]
+async fn species_colors_return_seeded_colors_and_reject_invalid_hex() {
+    let db = create_test_db_with_migrations().await;
+    let colors = SpeciesService::get_colors(&db).await.unwrap();
+    let color_of = |name: &str| {
+        colors.iter().find(|c| c.name == name).map(|c| (c.species_id, c.color.clone()))
+    };
+    assert_eq!(color_of("Dog"), Some((1, "#1890ff".to_string())));
+    assert_eq!(color_of("Cat").map(|(_, color)| color), Some("#ff7a45".to_string()));
+
+    let updated = SpeciesService::update(&db, 1, UpdateSpeciesInput {
+        name: None, active: None, display_order: None, color: Some(" #A0B1C2 ".to_string()),
+    }).await.unwrap();
+    assert_eq!(updated.color, "#A0B1C2");
+
+    for invalid in ["red", "#12345", "1890ff", "#ggg000"] {
+        let err = SpeciesService::update(&db, 1, UpdateSpeciesInput {
+            name: None, active: None, display_order: None, color: Some(invalid.to_string()),
+        }).await.unwrap_err();
+        assert!(err.contains("Invalid color"), "{}: {}", invalid, err);
+    }
+    assert_eq!(SpeciesService::get_by_id(&db, 1).await.unwrap().color, "#A0B1C2");
+
+    // Inactive species are left out of the map
+    SpeciesService::delete(&db, 2).await.unwrap();
+    let colors = SpeciesService::get_colors(&db).await.unwrap();
+    assert!(!colors.iter().any(|c| c.species_id == 2));
+}
+
 #[tokio::test]
 async fn delete_species_with_no_patients_succeeds() {
     let db = create_test_db_with_migrations().await;
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "DeleteMe".to_string(),
         display_order: None,
+        color: None,
     }).await.unwrap();
     SpeciesService::delete(&db, s.id).await.unwrap();
 }
@@ -146,6 +184,7 @@ async fn hard_delete_species_with_patients_fails_fk() {
     let s = SpeciesService::create(&db, CreateSpeciesInput {
         name: "Referenced".to_string(),
         display_order: None,
+        color: None,
     }).await.unwrap();
 
     PatientService::create(&db, CreatePatientDto {
@@ -282,6 +321,110 @@ async fn hard_delete_breed_with_patient_ref_silently_soft_deletes() {
     assert!(!fetched.active, "should be marked inactive, not removed");
 }
 
+#[tokio::test]
+async fn suggest_breeds_matches_prefix_and_tolerates_one_typo() {
+    let db = create_test_db_with_migrations().await;
+    let species = SpeciesService::create(&db, CreateSpeciesInput {
+        name: "Suggest Dog".to_string(),
+        display_order: Some(100),
+        color: None,
+    }).await.unwrap();
+    for name in ["Labrador Retriever", "Lhasa Apso", "Český Terrier", "German Shepherd"] {
+        BreedService::create(&db, CreateBreedInput { name: name.to_string(), species_id: species.id })
+            .await
+            .unwrap();
+    }
+    let names = |breeds: Vec<crate::models::breed::Breed>| -> Vec<String> {
+        breeds.into_iter().map(|b| b.name).collect()
+    };
+
+    let exact = BreedService::suggest(&db, species.id, "lab", 10).await.unwrap();
+    assert_eq!(names(exact), vec!["Labrador Retriever"]);
+
+    let typo = BreedService::suggest(&db, species.id, "Labrdor", 10).await.unwrap();
+    assert_eq!(names(typo), vec!["Labrador Retriever"]);
+
+    // Diacritics and later words both match
+    let folded = BreedService::suggest(&db, species.id, "cesky", 10).await.unwrap();
+    assert_eq!(names(folded), vec!["Český Terrier"]);
+    let word = BreedService::suggest(&db, species.id, "shep", 10).await.unwrap();
+    assert_eq!(names(word), vec!["German Shepherd"]);
+
+    let unrelated = BreedService::suggest(&db, species.id, "Poodle", 10).await.unwrap();
+    assert!(unrelated.is_empty());
+}
+
+async fn create_patient_with_breed(db: &sea_orm::DatabaseConnection, name: &str, species_id: i64, breed_id: i64) -> i64 {
+    PatientService::create(db, CreatePatientDto {
+        name: Some(name.to_string()),
+        species_id: Some(species_id),
+        breed_id: Some(breed_id),
+        gender: None, date_of_birth: None, color: None,
+        weight: None, microchip_id: None, medical_notes: None, household_id: None,
+    }).await.unwrap().id
+}
+
+#[tokio::test]
+async fn find_duplicate_breeds_clusters_similar_names_and_merge_moves_patients() {
+    let db = create_test_db_with_migrations().await;
+    let species = SpeciesService::create(&db, CreateSpeciesInput {
+        name: "Merge Dog".to_string(),
+        display_order: Some(100),
+        color: None,
+    }).await.unwrap();
+    let mut ids = Vec::new();
+    for name in ["Labrador", "Lab", "Poodle", "Beagle"] {
+        let breed = BreedService::create(&db, CreateBreedInput { name: name.to_string(), species_id: species.id })
+            .await
+            .unwrap();
+        ids.push(breed.id);
+    }
+    let (labrador, lab) = (ids[0], ids[1]);
+    let rex = create_patient_with_breed(&db, "Rex", species.id, lab).await;
+    let max = create_patient_with_breed(&db, "Max", species.id, lab).await;
+    let bella = create_patient_with_breed(&db, "Bella", species.id, labrador).await;
+
+    let clusters = BreedService::find_duplicates(&db, species.id).await.unwrap();
+    assert_eq!(clusters.len(), 1, "{:?}", clusters);
+    let cluster: Vec<(&str, i64)> = clusters[0]
+        .breeds
+        .iter()
+        .map(|c| (c.breed.name.as_str(), c.patient_count))
+        .collect();
+    // Most used first
+    assert_eq!(cluster, vec![("Lab", 2), ("Labrador", 1)]);
+
+    let moved = BreedService::merge(&db, labrador, lab).await.unwrap();
+    assert_eq!(moved, 2);
+    for patient_id in [rex, max, bella] {
+        let patient = PatientService::get_by_id(&db, patient_id).await.unwrap().unwrap();
+        assert_eq!(patient.breed_id, Some(labrador));
+    }
+    assert!(BreedService::get_by_id(&db, lab).await.is_err(), "the duplicate is deleted");
+    assert!(BreedService::find_duplicates(&db, species.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn merge_breeds_refuses_different_species() {
+    let db = create_test_db_with_migrations().await;
+    let dog = BreedService::create(&db, CreateBreedInput { name: "Husky".to_string(), species_id: 1 })
+        .await
+        .unwrap();
+    let cat = BreedService::create(&db, CreateBreedInput { name: "Snowshoe".to_string(), species_id: 2 })
+        .await
+        .unwrap();
+    let patient_id = create_patient_with_breed(&db, "Snow", 2, cat.id).await;
+
+    let err = BreedService::merge(&db, dog.id, cat.id).await.unwrap_err();
+    assert_eq!(err, "Can't merge breeds of different species ('Husky' and 'Snowshoe')");
+    let patient = PatientService::get_by_id(&db, patient_id).await.unwrap().unwrap();
+    assert_eq!(patient.breed_id, Some(cat.id));
+    assert!(BreedService::get_by_id(&db, cat.id).await.is_ok());
+
+    let err = BreedService::merge(&db, dog.id, dog.id).await.unwrap_err();
+    assert_eq!(err, "Can't merge a breed into itself");
+}
+
 // ===========================================================================
 // CURRENCY
 // ===========================================================================
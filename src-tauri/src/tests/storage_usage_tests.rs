@@ -0,0 +1,114 @@
+//! Tests for `StorageUsageService::get_breakdown`.
+
+use sea_orm::ConnectionTrait;
+
+use crate::models::medical::UploadFile;
+use crate::services::file_storage::FileStorageService;
+use crate::services::storage_usage::StorageUsageService;
+use crate::test_utils::create_test_db_with_migrations;
+
+fn upload(name: &str, size: usize, mime_type: &str, attachment_type: Option<&str>) -> UploadFile {
+    UploadFile {
+        file_name: name.to_string(),
+        file_data: vec![0u8; size],
+        mime_type: mime_type.to_string(),
+        device_type: None,
+        device_name: None,
+        connection_method: None,
+        attachment_type: attachment_type.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn breakdown_totals_bytes_per_type_and_counts_orphans() {
+    let db = create_test_db_with_migrations().await;
+    let storage = tempfile::tempdir().unwrap();
+    for sql in [
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, is_archived, version) \
+         VALUES (1, 1, 'test_result', 'Blood panel', '', 0, 1)",
+    ] {
+        db.execute_unprepared(sql).await.unwrap();
+    }
+
+    let attachments = FileStorageService::upload_attachments_to_dir(
+        storage.path(),
+        &db,
+        1,
+        vec![
+            upload("report.pdf", 3000, "application/pdf", Some("generated_pdf")),
+            upload("scan.jpg", 1200, "image/jpeg", None),
+            upload("scan-2.jpg", 800, "image/jpeg", None),
+        ],
+        10,
+        false,
+    )
+    .await
+    .unwrap()
+    .attachments;
+
+    // A tracked device file is not an orphan; an untracked one is
+    db.execute_unprepared(
+        "INSERT INTO file_access_history (file_id, original_name, file_path, device_type, device_name) \
+         VALUES ('device-file', 'result.xml', '', 'exigo_eos_vet', 'Exigo')",
+    )
+    .await
+    .unwrap();
+    std::fs::write(storage.path().join("device-file"), vec![0u8; 50]).unwrap();
+    std::fs::write(storage.path().join("stray"), vec![0u8; 70]).unwrap();
+    // External attachments don't use app storage
+    db.execute_unprepared(
+        "INSERT INTO medical_attachments (medical_record_id, file_id, original_name, file_size, is_external) \
+         VALUES (1, 'external', 'xray.dcm', 99999, 1)",
+    )
+    .await
+    .unwrap();
+
+    let breakdown = StorageUsageService::get_breakdown(&db, storage.path(), 2).await.unwrap();
+
+    assert_eq!(breakdown.total_count, 3);
+    assert_eq!(breakdown.total_bytes, 5000);
+    let groups: Vec<_> = breakdown
+        .groups
+        .iter()
+        .map(|g| (g.attachment_type.as_str(), g.mime_type.as_str(), g.count, g.bytes))
+        .collect();
+    assert_eq!(
+        groups,
+        vec![("generated_pdf", "application/pdf", 1, 3000), ("file", "image/jpeg", 2, 2000)]
+    );
+
+    let largest: Vec<_> = breakdown.largest.iter().map(|a| (a.attachment_id, a.file_size)).collect();
+    assert_eq!(largest, vec![(attachments[0].id, 3000), (attachments[1].id, 1200)]);
+    assert_eq!(breakdown.largest[0].record_name.as_deref(), Some("Blood panel"));
+    assert_eq!(breakdown.largest[0].patient_name.as_deref(), Some("Rex"));
+
+    assert_eq!(breakdown.orphaned_files, 1);
+    assert_eq!(breakdown.orphaned_bytes, 70);
+}
+
+#[tokio::test]
+async fn shared_content_is_counted_once() {
+    let db = create_test_db_with_migrations().await;
+    let storage = tempfile::tempdir().unwrap();
+    for sql in [
+        "INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)",
+        "INSERT INTO medical_records (id, patient_id, record_type, name, description, is_archived, version) \
+         VALUES (1, 1, 'test_result', 'Blood panel', '', 0, 1)",
+    ] {
+        db.execute_unprepared(sql).await.unwrap();
+    }
+
+    for name in ["scan.jpg", "scan-copy.jpg"] {
+        FileStorageService::upload_attachment_to_dir(storage.path(), &db, 1, upload(name, 1200, "image/jpeg", None))
+            .await
+            .unwrap();
+    }
+
+    let breakdown = StorageUsageService::get_breakdown(&db, storage.path(), 2).await.unwrap();
+
+    assert_eq!(breakdown.total_count, 2);
+    assert_eq!(breakdown.total_bytes, 1200, "both attachments use one file");
+    assert_eq!(breakdown.groups[0].bytes, 1200);
+    assert_eq!(breakdown.orphaned_files, 0);
+}
@@ -0,0 +1,133 @@
+//! Tests for clinic staff users and created_by attribution.
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+use crate::models::medical::CreateMedicalRecordInput;
+use crate::models::user::{CreateUserInput, UpdateUserInput};
+use crate::services::medical_record::MedicalRecordService;
+use crate::services::users::{CurrentUser, UserService};
+use crate::test_utils::create_test_db_with_migrations;
+
+fn procedure(patient_id: i64) -> CreateMedicalRecordInput {
+    CreateMedicalRecordInput {
+        patient_id,
+        record_type: "procedure".to_string(),
+        name: "Dental cleaning".to_string(),
+        procedure_name: None,
+        description: "Scaling under sedation".to_string(),
+        prescription_notes: None,
+        price: None,
+        currency_id: None,
+        discount_percent: None,
+        manual_total: None,
+        device_test_data: None,
+        device_type: None,
+        device_name: None,
+        device_data_list: None,
+        line_items: None,
+    }
+}
+
+fn staff(name: &str, role: &str) -> CreateUserInput {
+    CreateUserInput { name: name.to_string(), role: role.to_string() }
+}
+
+#[tokio::test]
+async fn records_created_after_set_current_user_carry_the_user_id() {
+    let db = create_test_db_with_migrations().await;
+    db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)")
+        .await
+        .unwrap();
+
+    let current_user = CurrentUser::default();
+    let vet = UserService::create_user(&db, staff("Dr. Petrova", "veterinarian")).await.unwrap();
+    let current = UserService::set_current_user(&db, &current_user, Some(vet.id)).await.unwrap();
+    assert_eq!(current.map(|u| u.id), Some(vet.id));
+    assert_eq!(current_user.actor(), Some(vet.id.to_string()));
+
+    let record = MedicalRecordService::insert_medical_record(&db, &procedure(1), Utc::now(), current_user.get().as_ref())
+        .await
+        .unwrap();
+    assert_eq!(record.created_by, Some(vet.id.to_string()));
+    assert_eq!(record.updated_by, Some(vet.id.to_string()));
+
+    let changed_by: Option<String> = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT changed_by FROM medical_record_history WHERE medical_record_id = ?",
+            [record.id.into()],
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get("", "changed_by")
+        .unwrap();
+    assert_eq!(changed_by, Some(vet.id.to_string()));
+
+    // Inactive users can't be picked, and deactivating the current one clears it
+    UserService::update_user(&db, &current_user, vet.id, UpdateUserInput { name: None, role: None, active: Some(false) })
+        .await
+        .unwrap();
+    assert!(current_user.get().is_none());
+    assert!(UserService::set_current_user(&db, &current_user, Some(vet.id)).await.is_err());
+}
+
+#[tokio::test]
+async fn deleting_a_referenced_user_only_deactivates_it() {
+    let db = create_test_db_with_migrations().await;
+    db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)")
+        .await
+        .unwrap();
+
+    let current_user = CurrentUser::default();
+    let referenced = UserService::create_user(&db, staff("Ivan", "technician")).await.unwrap();
+    let unused = UserService::create_user(&db, staff("Maria", "receptionist")).await.unwrap();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO medical_records (patient_id, record_type, name, description, created_by) \
+         VALUES (1, 'note', 'Check-in', '', ?)",
+        [referenced.id.to_string().into()],
+    ))
+    .await
+    .unwrap();
+
+    assert!(!UserService::delete_user(&db, &current_user, referenced.id).await.unwrap());
+    let kept = UserService::get_user(&db, referenced.id).await.unwrap();
+    assert!(!kept.active);
+
+    assert!(UserService::delete_user(&db, &current_user, unused.id).await.unwrap());
+    assert!(UserService::get_user(&db, unused.id).await.is_err());
+
+    let active = UserService::get_users(&db, false).await.unwrap();
+    assert!(active.is_empty());
+    assert_eq!(UserService::get_users(&db, true).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn user_input_is_validated() {
+    let db = create_test_db_with_migrations().await;
+
+    assert!(UserService::create_user(&db, staff("  ", "admin")).await.is_err());
+    assert!(UserService::create_user(&db, staff("Ana", "janitor")).await.is_err());
+
+    let user = UserService::create_user(&db, staff("  Ana ", "admin")).await.unwrap();
+    assert_eq!(user.name, "Ana");
+    assert!(user.active);
+}
+
+#[tokio::test]
+async fn deleting_the_current_user_clears_only_that_session() {
+    let db = create_test_db_with_migrations().await;
+    let ana = UserService::create_user(&db, staff("Ana", "admin")).await.unwrap();
+    let ivan = UserService::create_user(&db, staff("Ivan", "technician")).await.unwrap();
+
+    let mine = CurrentUser::default();
+    let other = CurrentUser::default();
+    UserService::set_current_user(&db, &mine, Some(ana.id)).await.unwrap();
+    UserService::set_current_user(&db, &other, Some(ivan.id)).await.unwrap();
+
+    assert!(UserService::delete_user(&db, &mine, ana.id).await.unwrap());
+    assert!(mine.get().is_none());
+    assert_eq!(other.actor(), Some(ivan.id.to_string()));
+}
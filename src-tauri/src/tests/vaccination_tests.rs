@@ -0,0 +1,122 @@
+//! Tests for vaccine protocols and next-due dates on recorded vaccinations.
+
+use chrono::{Duration, TimeZone, Utc};
+use sea_orm::ConnectionTrait;
+
+use crate::models::vaccination::{CreateVaccineProtocolInput, RecordVaccinationInput};
+use crate::services::vaccination::VaccinationService;
+use crate::test_utils::create_test_db_with_migrations;
+
+// Species 1 is the seeded "Dog"
+async fn insert_dog(db: &sea_orm::DatabaseConnection) -> i64 {
+    db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (1, 'Rex', 1)")
+        .await
+        .unwrap();
+    1
+}
+
+fn rabies(patient_id: i64, next_due_at: Option<chrono::DateTime<Utc>>) -> RecordVaccinationInput {
+    RecordVaccinationInput {
+        patient_id,
+        vaccine_name: "rabies".to_string(),
+        administered_at: Utc.with_ymd_and_hms(2026, 3, 10, 9, 30, 0).unwrap(),
+        next_due_at,
+        notes: None,
+    }
+}
+
+#[tokio::test]
+async fn rabies_protocol_sets_next_due_date() {
+    let db = create_test_db_with_migrations().await;
+    let patient_id = insert_dog(&db).await;
+
+    VaccinationService::create_protocol(&db, CreateVaccineProtocolInput {
+        vaccine_name: "Rabies".to_string(),
+        species_id: 1,
+        interval_days: 365,
+    })
+    .await
+    .unwrap();
+
+    let input = rabies(patient_id, None);
+    let administered_at = input.administered_at;
+    let vaccination = VaccinationService::record_vaccination(&db, input).await.unwrap();
+
+    assert_eq!(vaccination.administered_at, administered_at);
+    assert_eq!(vaccination.next_due_at, Some(administered_at + Duration::days(365)));
+
+    let history = VaccinationService::get_patient_vaccinations(&db, patient_id).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].next_due_at, vaccination.next_due_at);
+}
+
+#[tokio::test]
+async fn explicit_due_date_wins_and_no_protocol_means_no_due_date() {
+    let db = create_test_db_with_migrations().await;
+    let patient_id = insert_dog(&db).await;
+
+    let without_protocol = VaccinationService::record_vaccination(&db, rabies(patient_id, None))
+        .await
+        .unwrap();
+    assert_eq!(without_protocol.next_due_at, None);
+
+    VaccinationService::create_protocol(&db, CreateVaccineProtocolInput {
+        vaccine_name: "Rabies".to_string(),
+        species_id: 1,
+        interval_days: 365,
+    })
+    .await
+    .unwrap();
+
+    let explicit = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+    let overridden = VaccinationService::record_vaccination(&db, rabies(patient_id, Some(explicit)))
+        .await
+        .unwrap();
+    assert_eq!(overridden.next_due_at, Some(explicit));
+}
+
+#[tokio::test]
+async fn protocol_is_unique_per_vaccine_and_species() {
+    let db = create_test_db_with_migrations().await;
+
+    let input = CreateVaccineProtocolInput {
+        vaccine_name: "Rabies".to_string(),
+        species_id: 1,
+        interval_days: 365,
+    };
+    VaccinationService::create_protocol(&db, input.clone()).await.unwrap();
+
+    let duplicate = VaccinationService::create_protocol(&db, CreateVaccineProtocolInput {
+        vaccine_name: "RABIES".to_string(),
+        ..input.clone()
+    })
+    .await;
+    assert!(duplicate.unwrap_err().contains("already exists"));
+
+    let zero_interval = VaccinationService::create_protocol(&db, CreateVaccineProtocolInput {
+        vaccine_name: "DHPP".to_string(),
+        interval_days: 0,
+        ..input
+    })
+    .await;
+    assert!(zero_interval.is_err());
+}
+
+#[tokio::test]
+async fn patient_without_species_gets_no_protocol_due_date() {
+    let db = create_test_db_with_migrations().await;
+    db.execute_unprepared("INSERT INTO patients (id, name, species_id) VALUES (2, 'Stray', NULL)")
+        .await
+        .unwrap();
+    VaccinationService::create_protocol(&db, CreateVaccineProtocolInput {
+        vaccine_name: "Rabies".to_string(),
+        species_id: 1,
+        interval_days: 365,
+    })
+    .await
+    .unwrap();
+
+    let vaccination = VaccinationService::record_vaccination(&db, rabies(2, None)).await.unwrap();
+    assert_eq!(vaccination.patient_id, 2);
+    assert_eq!(vaccination.next_due_at, None);
+}
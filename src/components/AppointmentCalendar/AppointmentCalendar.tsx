@@ -47,7 +47,7 @@ const AppointmentCalendar: React.FC<AppointmentCalendarProps> = ({
 
 
   // Fetch room data for colors
-  const { data: rooms = [] } = useRooms({ activeOnly: true });
+  const { data: rooms = [] } = useRooms({ activeOnly: true, sort: 'display_order' });
 
   // Helper function to get room color
   const getRoomColor = (appointment: Appointment): string => {
@@ -25,7 +25,7 @@ const DayViewSimple: React.FC<DayViewSimpleProps> = ({
 }) => {
   const { t } = useTranslation('appointments');
   const themeColors = useThemeColors();
-  const { data: rooms = [] } = useRooms({ activeOnly: true });
+  const { data: rooms = [] } = useRooms({ activeOnly: true, sort: 'display_order' });
   const containerRef = useRef<HTMLDivElement>(null);
   const [isDragging, setIsDragging] = useState(false);
   const [dragStart, setDragStart] = useState<{ slot: number; y: number } | null>(null);
@@ -25,7 +25,7 @@ const WeekView: React.FC<WeekViewProps> = ({
   onDayHeaderClick,
 }) => {
   const themeColors = useThemeColors();
-  const { data: rooms = [] } = useRooms({ activeOnly: true });
+  const { data: rooms = [] } = useRooms({ activeOnly: true, sort: 'display_order' });
   const containerRef = useRef<HTMLDivElement>(null);
   const [isDragging, setIsDragging] = useState(false);
   const [dragStart, setDragStart] = useState<{ day: number; slot: number; y: number } | null>(null);
@@ -256,6 +256,8 @@ export function useHouseholdPatients(householdId: number) {
           dateOfBirth: patient.dateOfBirth,
           weight: patient.weight,
           gender: patient.gender,
+          relationshipType: patient.relationshipType,
+          isPrimaryHousehold: patient.isPrimaryHousehold,
           status: patient.status || 'active'
         }));
 
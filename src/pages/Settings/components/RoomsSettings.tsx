@@ -34,6 +34,7 @@ const RoomsSettings: React.FC<RoomsSettingsProps> = ({ isUpdating }) => {
     // Set default values for new room
     roomForm.setFieldsValue({
       capacity: 1,
+      bufferMinutes: 0,
       color: '#1890ff',
       isActive: true,
     });
@@ -47,6 +48,7 @@ const RoomsSettings: React.FC<RoomsSettingsProps> = ({ isUpdating }) => {
       name: room.name,
       description: room.description,
       capacity: room.capacity,
+      bufferMinutes: room.bufferMinutes,
       color: room.color || '#1890ff',
       isActive: room.isActive,
     };
@@ -72,6 +74,7 @@ const RoomsSettings: React.FC<RoomsSettingsProps> = ({ isUpdating }) => {
         name: values.name,
         description: values.description || null,
         capacity: values.capacity || 1,
+        bufferMinutes: values.bufferMinutes || 0,
         color: values.color || '#1890ff',
         isActive: values.isActive !== undefined ? values.isActive : true,
       };
@@ -245,6 +248,20 @@ const RoomsSettings: React.FC<RoomsSettingsProps> = ({ isUpdating }) => {
             />
           </Form.Item>
 
+          <Form.Item
+            name="bufferMinutes"
+            label="Buffer Between Appointments (minutes)"
+            extra="Time kept free before and after each appointment for cleaning"
+            initialValue={0}
+          >
+            <InputNumber
+              min={0}
+              max={240}
+              step={5}
+              className={styles.fullWidth}
+            />
+          </Form.Item>
+
           <Form.Item
             name="color"
             label="Room Color"
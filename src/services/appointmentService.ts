@@ -4,6 +4,8 @@ import {
   AppointmentDetail,
   AppointmentFilter,
   AppointmentListResponse,
+  AppointmentStatistics,
+  AppointmentStatsGroupBy,
   ConflictCheckInput,
   ConflictCheckResponse,
   CreateAppointmentInput,
@@ -93,6 +95,24 @@ export class AppointmentService {
     return ApiService.invokeRaw('get_room_availability', { roomId, checkTime });
   }
 
+  // Renders the printable day sheet and returns the temp PDF path.
+  // `date` is a plain YYYY-MM-DD day; several roomIds group it by room.
+  static async exportDaySheetPdf(
+    date: string,
+    roomIds?: number[],
+    includeCancelled?: boolean
+  ): Promise<string> {
+    return ApiService.invokeRaw('export_day_sheet_pdf', { date, roomIds, includeCancelled });
+  }
+
+  static async getAppointmentStatistics(
+    from: string,
+    to: string,
+    groupBy: AppointmentStatsGroupBy
+  ): Promise<AppointmentStatistics> {
+    return ApiService.invokeRaw('get_appointment_statistics', { from, to, groupBy });
+  }
+
   // Helper methods
   static formatTimeSlot(date: Date): string {
     // Round to nearest 15 minutes
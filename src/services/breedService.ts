@@ -1,5 +1,5 @@
 import { ApiService } from './api';
-import { Breed, CreateBreedInput, UpdateBreedInput } from '../types/breed';
+import { Breed, CreateBreedInput, DuplicateBreedCluster, UpdateBreedInput } from '../types/breed';
 
 export class BreedService {
   static async getBreeds(speciesId?: number, activeOnly?: boolean): Promise<Breed[]> {
@@ -10,6 +10,11 @@ export class BreedService {
     return ApiService.invoke('get_breeds', { filter });
   }
 
+  // Autocomplete for the breed field; tolerates accents and small typos
+  static async suggestBreeds(speciesId: number, prefix: string, limit?: number): Promise<Breed[]> {
+    return ApiService.invokeRaw('suggest_breeds', { speciesId, prefix, limit: limit ?? null });
+  }
+
   static async getBreed(id: number): Promise<Breed> {
     return ApiService.invoke('get_breed', { id });
   }
@@ -25,4 +30,14 @@ export class BreedService {
   static async deleteBreed(id: number, hardDelete: boolean = false): Promise<void> {
     return ApiService.invokeRaw('delete_breed', { id, hardDelete });
   }
+
+  // Similar-named breeds of a species ("Lab" / "Labrador"), candidates for merging
+  static async findDuplicateBreeds(speciesId: number): Promise<DuplicateBreedCluster[]> {
+    return ApiService.invokeRaw('find_duplicate_breeds', { speciesId });
+  }
+
+  // Moves mergeId's patients to keepId and deletes mergeId; resolves to the number moved
+  static async mergeBreeds(keepId: number, mergeId: number): Promise<number> {
+    return ApiService.invokeRaw('merge_breeds', { keepId, mergeId });
+  }
 }
@@ -1,11 +1,27 @@
 import { ApiService } from './api';
-import { DeviceIntegration, CreateDeviceIntegrationInput, UpdateDeviceIntegrationInput } from '../types/deviceIntegration';
+import {
+  DeviceIntegration,
+  DeviceIntegrationWithStatus,
+  DevicePayloadValidation,
+  DeviceResultParameter,
+  DeviceProtocolDefaults,
+  DeviceParseMapping,
+  ParsedDeviceData,
+  RawCapture,
+  SaveDeviceParseMappingInput,
+  CreateDeviceIntegrationInput,
+  UpdateDeviceIntegrationInput,
+} from '../types/deviceIntegration';
 
 export class DeviceIntegrationService {
   static async getDeviceIntegrations(): Promise<DeviceIntegration[]> {
     return ApiService.invoke('get_device_integrations');
   }
 
+  static async getDeviceIntegrationsWithStatus(): Promise<DeviceIntegrationWithStatus[]> {
+    return ApiService.invoke('get_device_integrations_with_status');
+  }
+
   static async getDeviceIntegration(id: number): Promise<DeviceIntegration> {
     return ApiService.invoke('get_device_integration', { id });
   }
@@ -25,4 +41,77 @@ export class DeviceIntegrationService {
   static async toggleDeviceIntegrationEnabled(id: number): Promise<DeviceIntegration> {
     return ApiService.invoke('toggle_device_integration_enabled', { id });
   }
+
+  // Pauses/resumes an integration; its listener is stopped or started to match
+  static async setDeviceIntegrationEnabled(
+    integrationId: number,
+    enabled: boolean
+  ): Promise<DeviceIntegrationWithStatus> {
+    return ApiService.invokeRaw('set_device_integration_enabled', { integrationId, enabled });
+  }
+
+  static async validateDevicePayload(
+    deviceType: string,
+    rawBytes: number[],
+    connectionMethod: string
+  ): Promise<DevicePayloadValidation> {
+    return ApiService.invokeRaw('validate_device_payload', { deviceType, rawBytes, connectionMethod });
+  }
+
+  static async getDeviceResultSchema(deviceType: string): Promise<DeviceResultParameter[]> {
+    return ApiService.invokeRaw('get_device_result_schema', { deviceType });
+  }
+
+  static async getDeviceProtocolDefaults(deviceType: string): Promise<DeviceProtocolDefaults> {
+    return ApiService.invokeRaw('get_device_protocol_defaults', { deviceType });
+  }
+
+  static async getDeviceParseMappings(): Promise<DeviceParseMapping[]> {
+    return ApiService.invoke('get_device_parse_mappings');
+  }
+
+  // With samplePayload, every path must resolve in it or the save is rejected
+  static async saveDeviceParseMapping(
+    input: SaveDeviceParseMappingInput,
+    samplePayload?: string
+  ): Promise<DeviceParseMapping> {
+    return ApiService.invokeRaw('save_device_parse_mapping', {
+      input,
+      samplePayload: samplePayload ?? null,
+    });
+  }
+
+  static async deleteDeviceParseMapping(deviceType: string): Promise<void> {
+    return ApiService.invokeRaw('delete_device_parse_mapping', { deviceType });
+  }
+
+  static async parseJsonDevicePayload(deviceType: string, payload: string): Promise<ParsedDeviceData> {
+    return ApiService.invokeRaw('parse_json_device_payload', { deviceType, payload });
+  }
+
+  // Newest parsed result since startup, for the "last reading" preview
+  static async getLastDeviceResult(integrationId: number): Promise<ParsedDeviceData | null> {
+    return ApiService.invokeRaw('get_last_device_result', { integrationId });
+  }
+
+  // Dismisses a stale connection status without restarting the listener;
+  // returns how many ports were cleared
+  static async clearConnectionStatus(integrationId: number): Promise<number> {
+    return ApiService.invokeRaw('clear_device_connection_status', { integrationId });
+  }
+
+  // Protocol debugging: tee a running listener's raw bytes (newest maxBytes
+  // kept) until stopped or ten minutes pass. Debug builds or
+  // ARKIVET_DEVICE_DEBUG=1 only
+  static async startRawCapture(integrationId: number, maxBytes?: number): Promise<RawCapture> {
+    return ApiService.invokeRaw('start_raw_capture', { integrationId, maxBytes: maxBytes ?? null });
+  }
+
+  static async getRawCapture(integrationId: number): Promise<RawCapture | null> {
+    return ApiService.invokeRaw('get_raw_capture', { integrationId });
+  }
+
+  static async stopRawCapture(integrationId: number): Promise<RawCapture | null> {
+    return ApiService.invokeRaw('stop_raw_capture', { integrationId });
+  }
 }
@@ -1,8 +1,13 @@
 // T031: Google Calendar service layer
 import { ApiService } from './api';
 import type {
+  AppointmentSyncLog,
+  GoogleCalendar,
   GoogleCalendarSettings,
+  GoogleConnectionTest,
   OAuthFlowState,
+  PendingSyncAppointment,
+  SyncCalendarChange,
   SyncLog
 } from '../types/googleCalendar';
 
@@ -63,6 +68,27 @@ export class GoogleCalendarService {
     return ApiService.invoke('revoke_google_access');
   }
 
+  /**
+   * Check the stored credentials by refreshing the token if needed and listing calendars
+   */
+  static async testConnection(): Promise<GoogleConnectionTest> {
+    return ApiService.invoke<GoogleConnectionTest>('test_google_connection');
+  }
+
+  /**
+   * List the connected account's calendars
+   */
+  static async listCalendars(): Promise<GoogleCalendar[]> {
+    return ApiService.invoke<GoogleCalendar[]>('list_google_calendars');
+  }
+
+  /**
+   * Sync appointments to another calendar; mappings to the old calendar's events are cleared
+   */
+  static async setSyncCalendar(calendarId: string): Promise<SyncCalendarChange> {
+    return ApiService.invokeRaw<SyncCalendarChange>('set_sync_calendar', { calendarId });
+  }
+
   /**
    * Trigger manual sync of appointments to Google Calendar
    */
@@ -83,6 +109,20 @@ export class GoogleCalendarService {
   static async checkSyncStatus(): Promise<SyncLog | null> {
     return ApiService.invoke<SyncLog | null>('check_sync_status');
   }
+
+  /**
+   * Every push attempt for one appointment, oldest first, with its errors
+   */
+  static async getAppointmentSyncLog(appointmentId: number): Promise<AppointmentSyncLog[]> {
+    return ApiService.invoke<AppointmentSyncLog[]>('get_appointment_sync_log', { appointmentId });
+  }
+
+  /**
+   * Appointments still to be created, updated or deleted on Google
+   */
+  static async getPendingSync(): Promise<PendingSyncAppointment[]> {
+    return ApiService.invoke<PendingSyncAppointment[]>('get_appointments_pending_sync');
+  }
 }
 
 // Export instance wrapper for backward compatibility with hooks
@@ -95,7 +135,12 @@ export const googleCalendarService = {
   updateSyncEnabled: GoogleCalendarService.updateSyncEnabled,
   disconnect: GoogleCalendarService.disconnect,
   revokeAccess: GoogleCalendarService.revokeAccess,
+  testConnection: GoogleCalendarService.testConnection,
+  listCalendars: GoogleCalendarService.listCalendars,
+  setSyncCalendar: GoogleCalendarService.setSyncCalendar,
   triggerSync: GoogleCalendarService.triggerSync,
   getSyncHistory: GoogleCalendarService.getSyncHistory,
   checkSyncStatus: GoogleCalendarService.checkSyncStatus,
+  getPendingSync: GoogleCalendarService.getPendingSync,
+  getAppointmentSyncLog: GoogleCalendarService.getAppointmentSyncLog,
 };
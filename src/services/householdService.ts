@@ -10,6 +10,8 @@ import {
   CreatePatientWithHouseholdDto,
   CreatePatientWithHouseholdResponse,
   SearchHouseholdsResponse,
+  HouseholdSearchResult,
+  HouseholdDataPackage,
   validateHouseholdDto,
 } from '../types/household';
 
@@ -78,6 +80,27 @@ export class HouseholdService {
     });
   }
 
+  /**
+   * Search by part of a phone number; separators are ignored on both sides
+   */
+  static async searchHouseholdsByPhone(partial: string): Promise<HouseholdSearchResult[]> {
+    return ApiService.invoke<HouseholdSearchResult[]>('search_households_by_phone', { partial });
+  }
+
+  /**
+   * Everything held about a household, for an owner's data-access request.
+   * Soft-deleted data is only included with `includeDeleted`.
+   */
+  static async exportHouseholdDataPackage(
+    householdId: number,
+    includeDeleted = false
+  ): Promise<HouseholdDataPackage> {
+    return ApiService.invokeRaw<HouseholdDataPackage>('export_household_data_package', {
+      householdId,
+      includeDeleted,
+    });
+  }
+
   /**
    * Quick search for autocomplete
    */
@@ -142,17 +165,41 @@ export class HouseholdService {
   }
 
   /**
-   * Delete household (will cascade delete people and contacts)
+   * Delete household. Soft by default (restorable); `hard` cascades to
+   * people, contacts and patient links.
+   */
+  static async deleteHousehold(householdId: number, hard = false): Promise<void> {
+    await ApiService.invokeRaw('delete_household', { householdId, hard });
+  }
+
+  /**
+   * Restore a soft-deleted household
+   */
+  static async restoreHousehold(householdId: number): Promise<void> {
+    await ApiService.invokeRaw('restore_household', { householdId });
+  }
+
+  /**
+   * Permanently remove households deleted more than `olderThanDays` ago
+   */
+  static async purgeHouseholds(olderThanDays: number): Promise<number> {
+    return ApiService.invokeRaw('purge_households', { olderThanDays });
+  }
+
+  /**
+   * Rebuild search index (for maintenance). Runs in batches, reporting each
+   * on the 'household-search-reindex-progress' event; resolves with how
+   * many households were indexed.
    */
-  static async deleteHousehold(householdId: number): Promise<void> {
-    await ApiService.invokeRaw('delete_household', { householdId });
+  static async rebuildHouseholdSearchIndex(): Promise<number> {
+    return ApiService.invoke('rebuild_household_search_index');
   }
 
   /**
-   * Rebuild search index (for maintenance)
+   * Refresh one household's search entry from its current people and contacts
    */
-  static async rebuildHouseholdSearchIndex(): Promise<void> {
-    await ApiService.invoke('rebuild_household_search_index');
+  static async rebuildHouseholdSearchIndexFor(householdId: number): Promise<void> {
+    await ApiService.invokeRaw('rebuild_household_search_index_for', { householdId });
   }
 
   /**
@@ -271,7 +318,10 @@ export const createHouseholdWithPeople = HouseholdService.createHouseholdWithPeo
 export const createPatientWithHousehold = HouseholdService.createPatientWithHousehold.bind(HouseholdService);
 export const searchHouseholds = HouseholdService.searchHouseholds.bind(HouseholdService);
 export const quickSearchHouseholds = HouseholdService.quickSearchHouseholds.bind(HouseholdService);
+export const searchHouseholdsByPhone = HouseholdService.searchHouseholdsByPhone.bind(HouseholdService);
+export const exportHouseholdDataPackage = HouseholdService.exportHouseholdDataPackage.bind(HouseholdService);
 export const getHouseholdWithPeople = HouseholdService.getHouseholdWithPeople.bind(HouseholdService);
 export const updateHousehold = HouseholdService.updateHousehold.bind(HouseholdService);
 export const deleteHousehold = HouseholdService.deleteHousehold.bind(HouseholdService);
+export const restoreHousehold = HouseholdService.restoreHousehold.bind(HouseholdService);
 export const rebuildHouseholdSearchIndex = HouseholdService.rebuildHouseholdSearchIndex.bind(HouseholdService);
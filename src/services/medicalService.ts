@@ -8,8 +8,14 @@ import type {
   MedicalRecordFilter,
   PaginationParams,
   MedicalAttachment,
+  AttachmentTextMatch,
+  AutoArchiveResult,
+  MoveRecordsResult,
   DownloadAttachmentResponse,
   SearchMedicalRecordsResponse,
+  MedicalRecordChanges,
+  FieldChange,
+  PdfJobHandle,
   Currency
 } from '@/types/medical';
 import type { PatientOverrides } from '@/types/report';
@@ -75,6 +81,30 @@ export class MedicalService {
     });
   }
 
+  // Archives records older than the auto-archive threshold in settings
+  static async applyAutoArchive(): Promise<AutoArchiveResult> {
+    return ApiService.invokeRaw('apply_auto_archive');
+  }
+
+  // Re-files records under another patient; other-species moves need force
+  static async moveRecordsToPatient(
+    recordIds: number[],
+    targetPatientId: number,
+    force = false
+  ): Promise<MoveRecordsResult> {
+    return ApiService.invokeRaw('move_records_to_patient', {
+      recordIds,
+      targetPatientId,
+      force
+    });
+  }
+
+  // Renders only the chosen records of a patient for a referral and
+  // returns the temp PDF path; fails if any record isn't the patient's
+  static async exportSelectedRecordsPdf(patientId: number, recordIds: number[]): Promise<string> {
+    return ApiService.invokeRaw('export_selected_records_pdf', { patientId, recordIds });
+  }
+
   static async uploadAttachment(
     medicalRecordId: number,
     file: File,
@@ -108,6 +138,28 @@ export class MedicalService {
     });
   }
 
+  // Reference a file where it lives (e.g. a network share) instead of copying it
+  static async attachExternalFile(
+    medicalRecordId: number,
+    path: string,
+    mimeType?: string
+  ): Promise<MedicalAttachment> {
+    return ApiService.invokeRaw('attach_external_file', {
+      medicalRecordId,
+      medical_record_id: medicalRecordId,
+      path,
+      mimeType,
+      mime_type: mimeType,
+    });
+  }
+
+  /**
+   * Search the OCR'd text of scanned attachments
+   */
+  static async searchAttachmentText(query: string, limit?: number): Promise<AttachmentTextMatch[]> {
+    return ApiService.invokeRaw('search_attachment_text', { query, limit });
+  }
+
   static async downloadAttachment(
     attachmentId: number
   ): Promise<Blob> {
@@ -132,6 +184,22 @@ export class MedicalService {
     });
   }
 
+  /**
+   * Render first-page thumbnails for every PDF of a record (or of all of a
+   * patient's records) in the background. Progress arrives on the
+   * 'attachment-thumbnails-progress' event; resolves with how many were queued.
+   */
+  static async prewarmAttachmentThumbnails(
+    target: { recordId: number } | { patientId: number },
+    width = 900,
+  ): Promise<number> {
+    return ApiService.invokeRaw<number>('prewarm_attachment_thumbnails', {
+      recordId: 'recordId' in target ? target.recordId : null,
+      patientId: 'patientId' in target ? target.patientId : null,
+      width,
+    });
+  }
+
   static async renderPdfAttachmentThumbnailForce(
     attachmentId: number,
     page = 1,
@@ -171,6 +239,16 @@ export class MedicalService {
     return response.records;
   }
 
+  static async getRecordsChangedSince(
+    patientId: number,
+    since: string
+  ): Promise<MedicalRecordChanges> {
+    return ApiService.invokeRaw<MedicalRecordChanges>('get_records_changed_since', {
+      patientId,
+      since
+    });
+  }
+
   static async getCurrencies(): Promise<Currency[]> {
     return ApiService.invoke('get_currencies');
   }
@@ -186,10 +264,18 @@ export class MedicalService {
     });
   }
 
+  static async getMedicalRecordDiff(recordId: number, version: number): Promise<FieldChange[]> {
+    return ApiService.invokeRaw('get_medical_record_diff', { recordId, version });
+  }
+
   static async revertMedicalRecord(recordId: number): Promise<MedicalRecord> {
     return ApiService.invokeRaw('revert_medical_record', { recordId, record_id: recordId });
   }
 
+  static async revertMedicalRecordToVersion(recordId: number, version: number): Promise<MedicalRecord> {
+    return ApiService.invokeRaw('revert_medical_record_to_version', { recordId, version });
+  }
+
   static async downloadAndOpenAttachment(
     attachmentId: number,
     fileName: string
@@ -291,7 +377,8 @@ export class MedicalService {
   static async generateConfiguredReport(
     medicalRecordId: number,
     selectedAttachmentIds: number[],
-    patientOverrides: PatientOverrides
+    patientOverrides: PatientOverrides,
+    jobId?: string
   ): Promise<MedicalAttachment> {
     return ApiService.invokeRaw('generate_configured_report', {
       medicalRecordId,
@@ -299,7 +386,22 @@ export class MedicalService {
       selectedAttachmentIds,
       selected_attachment_ids: selectedAttachmentIds,
       patientOverrides,
-      patient_overrides: patientOverrides
+      patient_overrides: patientOverrides,
+      jobId: jobId ?? null,
     });
   }
+
+  /**
+   * Register a cancellable PDF job; pass its jobId to the report or render call
+   */
+  static async createPdfJob(): Promise<PdfJobHandle> {
+    return ApiService.invokeRaw('create_pdf_job');
+  }
+
+  /**
+   * Cancel a running PDF job; the report call then fails with "Cancelled"
+   */
+  static async cancelPdfJob(jobId: string): Promise<boolean> {
+    return ApiService.invokeRaw('cancel_pdf_job', { jobId });
+  }
 }
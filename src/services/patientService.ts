@@ -9,15 +9,45 @@ import {
   Patient,
   PatientWithOwners,
   CreatePatientInput,
-  UpdatePatientInput
+  UpdatePatientInput,
+  PatientMergeResult,
+  PatientReclassification,
+  PatientImportRowResult,
+  PatientAge,
+  PatientListFilter,
+  PatientListResponse,
+  PatientSort,
+  LinkedHousehold,
 } from '../types';
 
 export class PatientService {
   /**
-   * Get all patients
+   * Get all patients. Deleted (inactive) patients only with includeInactive.
    */
-  static async getPatients(): Promise<PatientWithOwners[]> {
-    return ApiService.invoke<PatientWithOwners[]>('get_patients');
+  static async getPatients(includeInactive = false): Promise<PatientWithOwners[]> {
+    return ApiService.invokeRaw<PatientWithOwners[]>('get_patients', { includeInactive });
+  }
+
+  /**
+   * One page of the patient table, with species/breed names, owner, age and record counts resolved
+   */
+  static async listPatients(
+    filter?: PatientListFilter,
+    pagination?: { page?: number; pageSize?: number },
+    sort?: PatientSort
+  ): Promise<PatientListResponse> {
+    return ApiService.invokeRaw<PatientListResponse>('list_patients', {
+      filter: filter ?? null,
+      pagination: pagination ?? null,
+      sort: sort ?? null,
+    });
+  }
+
+  /**
+   * Recompute the cached record counts shown in the patient table
+   */
+  static async rebuildRecordCounts(): Promise<number> {
+    return ApiService.invoke<number>('rebuild_patient_record_counts');
   }
 
   /**
@@ -34,9 +64,10 @@ export class PatientService {
   }
 
   /**
-   * Create a new patient
+   * Create a new patient. Rejects with code DUPLICATE_MICROCHIP (details: the
+   * other patient) when the microchip is taken, unless allowDuplicateMicrochip.
    */
-  static async createPatient(input: CreatePatientInput): Promise<PatientWithOwners> {
+  static async createPatient(input: CreatePatientInput, allowDuplicateMicrochip = false): Promise<PatientWithOwners> {
     const dto = {
       name: input.name,
       speciesId: input.speciesId,
@@ -53,15 +84,19 @@ export class PatientService {
     if (input.color !== undefined) (dto as any).color = input.color || null;
     if (input.microchipId !== undefined) (dto as any).microchipId = input.microchipId || null;
 
-    const created = await ApiService.invokeRaw<PatientWithOwners>('create_patient', { dto });
+    const created = await ApiService.invokeRaw<PatientWithOwners>('create_patient', { dto, allowDuplicateMicrochip });
     try { await emit('patient-created', created as any); } catch {}
     return created;
   }
 
   /**
-   * Update an existing patient
+   * Update an existing patient; same microchip check as createPatient
    */
-  static async updatePatient(id: number, updates: UpdatePatientInput): Promise<PatientWithOwners> {
+  static async updatePatient(
+    id: number,
+    updates: UpdatePatientInput,
+    allowDuplicateMicrochip = false
+  ): Promise<PatientWithOwners> {
     // Only include fields that are actually being updated (not undefined)
     const dto: Record<string, unknown> = {};
 
@@ -77,23 +112,87 @@ export class PatientService {
     if (updates.isActive !== undefined) dto.isActive = updates.isActive;
 
     // Use raw invoke to preserve camelCase DTO expected by backend
-    return ApiService.invokeRaw<PatientWithOwners>('update_patient', { id, dto });
+    return ApiService.invokeRaw<PatientWithOwners>('update_patient', { id, dto, allowDuplicateMicrochip });
   }
 
   /**
-   * Delete a patient
+   * Delete a patient. This is a soft delete: the patient is marked inactive
+   * and its records and appointments are kept, so it can be restored.
    */
   static async deletePatient(id: number): Promise<void> {
     return ApiService.invoke<void>('delete_patient', { id });
   }
 
+  /**
+   * Age today from the date of birth, e.g. "3y 2m"; null fields without one
+   */
+  static async getPatientAge(patientId: number): Promise<PatientAge> {
+    return ApiService.invokeRaw<PatientAge>('get_patient_age', { patientId });
+  }
+
+  /**
+   * Restore a deleted patient
+   */
+  static async restorePatient(patientId: number): Promise<Patient | null> {
+    return ApiService.invokeRaw<Patient | null>('restore_patient', { patientId });
+  }
+
+  /**
+   * Permanently delete a patient with its appointments and medical records.
+   * Refused unless the current user is an admin.
+   */
+  static async hardDeletePatient(patientId: number): Promise<boolean> {
+    return ApiService.invokeRaw<boolean>('hard_delete_patient', { patientId });
+  }
+
+  /**
+   * Merge a duplicate patient into another: its medical records, appointments
+   * and household links move to targetId, then the duplicate is soft-deleted.
+   */
+  static async mergePatients(sourceId: number, targetId: number): Promise<PatientMergeResult> {
+    return ApiService.invokeRaw<PatientMergeResult>('merge_patients', { sourceId, targetId });
+  }
+
+  /**
+   * Move several patients to another species (and optionally breed) at once.
+   * Nothing changes if a patient is missing or the breed belongs to another species.
+   */
+  static async reclassifyPatients(
+    patientIds: number[],
+    speciesId: number,
+    breedId?: number | null
+  ): Promise<PatientReclassification> {
+    return ApiService.invokeRaw<PatientReclassification>('reclassify_patients', {
+      patientIds,
+      speciesId,
+      breedId: breedId ?? null,
+    });
+  }
+
+  /**
+   * Import patients from CSV text with a header row. With dryRun nothing is
+   * written; each row's result says whether it would be imported.
+   */
+  static async importPatientsFromCsv(
+    csvData: string,
+    dryRun: boolean,
+    createMissingBreeds = false
+  ): Promise<PatientImportRowResult[]> {
+    return ApiService.invokeRaw<PatientImportRowResult[]>('import_patients_from_csv', {
+      csvData,
+      dryRun,
+      createMissingBreeds,
+    });
+  }
+
   /**
    * Search patients by query
    */
-  static async searchPatients(query: string, limit?: number): Promise<Patient[]> {
-    return ApiService.invoke<Patient[]>('search_patients', {
+  static async searchPatients(query: string, limit?: number, includeInactive = false): Promise<Patient[]> {
+    return ApiService.invokeRaw<Patient[]>('search_patients', {
       query,
-      limit: limit || 100
+      limit: limit || 100,
+      includeInactive,
     });
   }
 
@@ -137,6 +236,29 @@ export class PatientService {
     });
   }
 
+  /**
+   * Every household a patient is linked to, primary first
+   */
+  static async getPatientHouseholds(patientId: number): Promise<LinkedHousehold[]> {
+    return ApiService.invokeRaw<LinkedHousehold[]>('get_patient_households', { patientId });
+  }
+
+  /**
+   * Link a patient to another household, keeping its primary one. A patient
+   * without a household gets this one as primary.
+   */
+  static async addSecondaryHousehold(
+    patientId: number,
+    householdId: number,
+    relationshipType?: string
+  ): Promise<LinkedHousehold> {
+    return ApiService.invokeRaw<LinkedHousehold>('add_secondary_household', {
+      patientId,
+      householdId,
+      relationshipType,
+    });
+  }
+
   /**
    * Unlink a patient from a household
    */
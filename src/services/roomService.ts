@@ -1,11 +1,19 @@
 import { ApiService } from './api';
-import { Room, RoomFilter, CreateRoomInput, UpdateRoomInput, RoomAvailability } from '../types/rooms';
+import { Room, RoomFilter, RoomList, CreateRoomInput, UpdateRoomInput, RoomAvailability } from '../types/rooms';
 
 export class RoomService {
   static async getRooms(filter?: RoomFilter): Promise<Room[]> {
     return ApiService.invoke<Room[]>('get_rooms', { filter });
   }
 
+  static async getRoomList(filter?: RoomFilter): Promise<RoomList> {
+    return ApiService.invoke<RoomList>('get_room_list', { filter });
+  }
+
+  static async reorderRooms(roomIds: number[]): Promise<Room[]> {
+    return ApiService.invokeRaw<Room[]>('reorder_rooms', { roomIds });
+  }
+
   static async getRoomById(id: number): Promise<Room> {
     return ApiService.invoke<Room>('get_room', { id });
   }
@@ -18,6 +26,10 @@ export class RoomService {
     return ApiService.invoke<Room>('update_room', { id, input: data });
   }
 
+  static async duplicateRoom(roomId: number, newName: string): Promise<Room> {
+    return ApiService.invokeRaw<Room>('duplicate_room', { roomId, newName });
+  }
+
   static async deleteRoom(id: number): Promise<void> {
     return ApiService.invoke<void>('delete_room', { id });
   }
@@ -32,6 +32,70 @@ export interface UpdateSettingsRequest {
   googleCalendarSync?: boolean;
 }
 
+// Clinic configuration profile, matched by natural key on import
+export interface ClinicProfile {
+  formatVersion: number;
+  generatedAt: string;
+  settings: {
+    language: 'en' | 'mk';
+    currencyCode: string | null;
+    dateFormat: string;
+    defaultRoom: string | null;
+    practiceTimezone: string | null;
+    ocrEnabled: boolean;
+    autoArchiveAfterDays: number | null;
+  } | null;
+  currencies: { code: string; name: string; symbol: string | null }[];
+  species: {
+    name: string;
+    active: boolean;
+    displayOrder: number;
+    color: string;
+    breeds: { name: string; active: boolean; displayOrder: number }[];
+  }[];
+  rooms: {
+    name: string;
+    description: string | null;
+    capacity: number;
+    isActive: boolean;
+    color: string;
+    displayOrder: number;
+  }[];
+  recordTemplates: {
+    recordType: string;
+    title: string;
+    description: string;
+    price: number | null;
+    currencyCode: string | null;
+  }[];
+  lineItemTemplates: {
+    name: string;
+    description: string | null;
+    defaultPrice: number;
+    currencyCode: string;
+    displayOrder: number;
+    isActive: boolean;
+  }[];
+}
+
+export type ProfileMergeStrategy = 'merge' | 'replace';
+
+export interface ProfileImportCount {
+  created: number;
+  updated: number;
+  retired: number;
+}
+
+export interface ClinicProfileImportReport {
+  currencies: ProfileImportCount;
+  species: ProfileImportCount;
+  breeds: ProfileImportCount;
+  rooms: ProfileImportCount;
+  recordTemplates: ProfileImportCount;
+  lineItemTemplates: ProfileImportCount;
+  settingsApplied: boolean;
+}
+
 export class SettingsService {
   static async getSettings(): Promise<SettingsResponse> {
     try {
@@ -67,4 +131,18 @@ export class SettingsService {
   static async getCurrencies(): Promise<Currency[]> {
     return ApiService.invoke<Currency[]>('get_currencies');
   }
+
+  // Species, rooms, templates, currencies and clinic defaults as a profile
+  // for another install; no patient data
+  static async exportClinicProfile(): Promise<ClinicProfile> {
+    return ApiService.invoke<ClinicProfile>('export_clinic_profile');
+  }
+
+  // 'replace' retires configuration the profile doesn't list; 'merge' keeps it
+  static async importClinicProfile(
+    json: string,
+    mergeStrategy: ProfileMergeStrategy
+  ): Promise<ClinicProfileImportReport> {
+    return ApiService.invokeRaw<ClinicProfileImportReport>('import_clinic_profile', { json, mergeStrategy });
+  }
 }
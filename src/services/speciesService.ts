@@ -1,11 +1,15 @@
 import { ApiService } from './api';
-import { Species, CreateSpeciesInput, UpdateSpeciesInput } from '../types/species';
+import { Species, CreateSpeciesInput, UpdateSpeciesInput, SpeciesColor } from '../types/species';
 
 export class SpeciesService {
   static async getSpecies(activeOnly?: boolean): Promise<Species[]> {
     return ApiService.invokeRaw<Species[]>('get_species', { activeOnly });
   }
 
+  static async getSpeciesColors(): Promise<SpeciesColor[]> {
+    return ApiService.invokeRaw<SpeciesColor[]>('get_species_colors');
+  }
+
   static async getSpeciesById(id: number): Promise<Species> {
     return ApiService.invoke<Species>('get_species_by_id', { id });
   }
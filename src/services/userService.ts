@@ -0,0 +1,30 @@
+import { ApiService } from './api';
+import { User, CreateUserInput, UpdateUserInput } from '../types/user';
+
+export class UserService {
+  static async getUsers(includeInactive = false): Promise<User[]> {
+    return ApiService.invokeRaw<User[]>('get_users', { includeInactive });
+  }
+
+  static async createUser(input: CreateUserInput): Promise<User> {
+    return ApiService.invokeRaw<User>('create_user', { input });
+  }
+
+  static async updateUser(id: number, input: UpdateUserInput): Promise<User> {
+    return ApiService.invokeRaw<User>('update_user', { id, input });
+  }
+
+  /** Resolves to false when the user was deactivated instead, because records still name them */
+  static async deleteUser(id: number): Promise<boolean> {
+    return ApiService.invoke<boolean>('delete_user', { id });
+  }
+
+  /** Attribute new changes to this user; null clears the current user */
+  static async setCurrentUser(userId: number | null): Promise<User | null> {
+    return ApiService.invokeRaw<User | null>('set_current_user', { userId });
+  }
+
+  static async getCurrentUser(): Promise<User | null> {
+    return ApiService.invoke<User | null>('get_current_user');
+  }
+}
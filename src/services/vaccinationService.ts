@@ -0,0 +1,37 @@
+import { ApiService } from './api';
+import {
+  VaccineProtocol,
+  CreateVaccineProtocolInput,
+  UpdateVaccineProtocolInput,
+  Vaccination,
+  RecordVaccinationInput,
+} from '../types/vaccination';
+
+export class VaccinationService {
+  static async getVaccineProtocols(): Promise<VaccineProtocol[]> {
+    return ApiService.invoke<VaccineProtocol[]>('get_vaccine_protocols');
+  }
+
+  static async createVaccineProtocol(input: CreateVaccineProtocolInput): Promise<VaccineProtocol> {
+    return ApiService.invokeRaw<VaccineProtocol>('create_vaccine_protocol', { input });
+  }
+
+  static async updateVaccineProtocol(
+    id: number,
+    input: UpdateVaccineProtocolInput
+  ): Promise<VaccineProtocol> {
+    return ApiService.invokeRaw<VaccineProtocol>('update_vaccine_protocol', { id, input });
+  }
+
+  static async deleteVaccineProtocol(id: number): Promise<void> {
+    return ApiService.invoke<void>('delete_vaccine_protocol', { id });
+  }
+
+  static async recordVaccination(input: RecordVaccinationInput): Promise<Vaccination> {
+    return ApiService.invokeRaw<Vaccination>('record_vaccination', { input });
+  }
+
+  static async getPatientVaccinations(patientId: number): Promise<Vaccination[]> {
+    return ApiService.invokeRaw<Vaccination[]>('get_patient_vaccinations', { patientId });
+  }
+}
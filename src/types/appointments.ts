@@ -41,6 +41,9 @@ export interface Room {
   capacity: number;
   color: string;
   isActive: boolean;
+  displayOrder: number;
+  /** Turnover time kept free before and after each appointment */
+  bufferMinutes: number;
   createdAt: string;
   updatedAt: string;
 }
@@ -61,6 +64,8 @@ export interface UpdateAppointmentInput {
   endTime?: string;
   roomId?: number;
   status?: AppointmentStatus;
+  /** Admin correction: set `status` even where the transition isn't allowed */
+  overrideStatusCheck?: boolean;
 }
 
 export interface AppointmentFilter {
@@ -92,14 +97,20 @@ export interface ConflictCheckInput {
 }
 
 export interface ConflictCheckResponse {
+  /** The room's capacity is already filled by overlapping appointments */
   hasConflicts: boolean;
+  /** Every overlapping appointment, whether or not the room is full */
   conflicts: Appointment[];
+  occupancy: number;
+  /** Null when no room was given */
+  capacity: number | null;
 }
 
 export interface CreateRoomInput {
   name: string;
   description?: string;
   capacity?: number;
+  bufferMinutes?: number;
 }
 
 export interface UpdateRoomInput {
@@ -107,10 +118,13 @@ export interface UpdateRoomInput {
   description?: string;
   capacity?: number;
   isActive?: boolean;
+  bufferMinutes?: number;
 }
 
 export interface RoomFilter {
   activeOnly?: boolean;
+  includeInactive?: boolean;
+  sort?: 'name' | 'display_order';
 }
 
 export interface RoomAvailability {
@@ -128,6 +142,28 @@ export interface RoomAppointmentSlot {
   status: string;
 }
 
+export type AppointmentStatsGroupBy = 'room' | 'species' | 'status' | 'day_of_week';
+
+export interface AppointmentStatsGroup {
+  key?: string;  // room/species id, status, or ISO weekday (1 = Monday)
+  label?: string;
+  total: number;
+  completed: number;
+  cancelled: number;
+  averageDurationMinutes?: number;
+}
+
+export interface AppointmentStatistics {
+  total: number;
+  completed: number;
+  cancelled: number;
+  averageDurationMinutes?: number;
+  busiestDayOfWeek?: number;
+  busiestHour?: number;
+  groupBy: AppointmentStatsGroupBy;
+  groups: AppointmentStatsGroup[];
+}
+
 // Calendar view types
 export type CalendarView = 'month' | 'week' | 'day';
 
@@ -18,3 +18,14 @@ export interface UpdateBreedInput {
   speciesId?: number;
   active?: boolean;
 }
+
+// A breed in a find_duplicate_breeds cluster
+export interface DuplicateBreedCandidate {
+  breed: Breed;
+  patientCount: number;
+}
+
+// Similar-named breeds of one species, most used first
+export interface DuplicateBreedCluster {
+  breeds: DuplicateBreedCandidate[];
+}
@@ -86,6 +86,105 @@ export interface DeviceConnectionStatus {
   next_retry?: string;
 }
 
+// An integration with its live listener state (get_device_integrations_with_status,
+// set_device_integration_enabled).
+// ApiService camelCases the whole response, including the nested status.
+export interface DeviceIntegrationWithStatus extends DeviceIntegration {
+  connectionStatus: {
+    integrationId: number;
+    portName: string;
+    deviceType: string;
+    status: ConnectionState;
+    lastConnected?: string;
+    lastError?: string;
+    retryCount: number;
+    nextRetry?: string;
+  } | null;
+  listenerActive: boolean;
+}
+
+// Result of validate_device_payload: the device's expected parameters split
+// into present/missing, plus result keys outside that list.
+export interface DevicePayloadValidation {
+  deviceType: string;
+  present: string[];
+  missing: string[];
+  extra: string[];
+}
+
+// One row of get_device_result_schema, in the order the PDF report prints them
+export interface DeviceResultParameter {
+  /** Key in the result's testResults */
+  code: string;
+  displayName: string;
+  unit: string;
+}
+
+// Serial defaults of a device type, from get_device_protocol_defaults
+export interface SupportedDeviceType {
+  deviceType: DeviceType;
+  displayName: string;
+}
+
+export interface DeviceProtocolDefaults {
+  deviceType: DeviceType;
+  displayName: string;
+  baudRate: number;
+  startSymbol: number | null;
+  endSymbol: number;
+  framing: string;
+  supportedDeviceTypes: SupportedDeviceType[];
+}
+
+// Stored JSON-path mapping for a device without a dedicated parser.
+// Paths use dot keys, [n] indexes and [field=value] matches,
+// e.g. `results[code=GLU].value`.
+export interface DeviceResultPath {
+  resultKey: string;
+  path: string;
+}
+
+export interface DeviceParseMapping {
+  id: number;
+  deviceType: string;
+  patientIdentifierPath: string | null;
+  resultPaths: DeviceResultPath[];
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface SaveDeviceParseMappingInput {
+  deviceType: string;
+  patientIdentifierPath: string | null;
+  resultPaths: DeviceResultPath[];
+}
+
+// A payload parsed by parse_json_device_payload
+export interface ParsedDeviceData {
+  deviceType: string;
+  deviceName: string;
+  connectionMethod: string;
+  patientIdentifier: string | null;
+  testResults: Record<string, unknown>;
+  originalFileName: string;
+  fileData: number[];
+  mimeType: string;
+  detectedAt: string;
+}
+
+// Raw bytes teed from a running serial listener (start_raw_capture)
+export interface RawCapture {
+  integrationId: number;
+  maxBytes: number;
+  startedAt: string;
+  expiresAt: string;
+  // False once the capture expired and stopped recording
+  active: boolean;
+  bytesReceived: number;
+  bytesDropped: number;
+  hexDump: string;
+}
+
 // File watcher status types
 export type FileWatcherState = 'Watching' | 'Error' | 'Stopped';
 
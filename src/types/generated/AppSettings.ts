@@ -1,3 +1,23 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type AppSettings = { id: number, userId: string, language: string, currencyId: number | null, theme: string, dateFormat: string, createdAt: string, updatedAt: string, };
+export type AppSettings = { id: number, userId: string, language: string, currencyId: number | null, theme: string, dateFormat: string, 
+/**
+ * Room new appointments default to when none is chosen
+ */
+defaultRoomId: number | null, 
+/**
+ * Tokenizer the household search index is built with ("unicode61" or "porter")
+ */
+searchTokenizer: string, 
+/**
+ * IANA timezone of the practice; the machine's timezone when unset
+ */
+practiceTimezone: string | null, 
+/**
+ * Read text out of scanned attachments after upload so they're searchable
+ */
+ocrEnabled: boolean, 
+/**
+ * Records older than this many days are archived automatically; off when unset
+ */
+autoArchiveAfterDays: number | null, createdAt: string, updatedAt: string, };
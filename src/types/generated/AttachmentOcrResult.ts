@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `attachment-ocr-complete` event, sent once per attachment
+ * after OCR finishes or fails.
+ */
+export type AttachmentOcrResult = { attachmentId: number, 
+/**
+ * False when no text was found or OCR failed; see `error`
+ */
+indexed: boolean, error: string | null, };
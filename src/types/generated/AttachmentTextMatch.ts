@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An attachment whose OCR text matched a `search_attachment_text` query
+ */
+export type AttachmentTextMatch = { attachmentId: number, medicalRecordId: number, originalName: string, 
+/**
+ * Text around the hits, which are wrapped in `[` `]`
+ */
+snippet: string, };
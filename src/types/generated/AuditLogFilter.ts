@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AuditLogFilter = { patientId: number | null, medicalRecordId: number | null, 
+/**
+ * Exact match on the recorded actor
+ */
+changedBy: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What one auto-archive pass did.
+ */
+export type AutoArchiveResult = { archived: number, 
+/**
+ * Old enough to archive but left alone because a follow-up is still open
+ */
+skippedPendingFollowUp: number, };
@@ -1,3 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type BackupConfig = { directory: string | null, lastBackupAt: string | null, lastError: string | null, };
+export type BackupConfig = { directory: string | null, lastBackupAt: string | null, lastError: string | null, autoBackupEnabled: boolean, 
+/**
+ * Hours between automatic snapshots; `None` means the default (24).
+ */
+autoBackupIntervalHours: number | null, 
+/**
+ * Number of automatic snapshots to keep; `None` means the default (7).
+ */
+autoBackupKeep: number | null, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MedicalAttachment } from "./MedicalAttachment";
+import type { UploadFileResult } from "./UploadFileResult";
+
+export type BatchUploadResponse = { attachments: Array<MedicalAttachment>, results: Array<UploadFileResult>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Charged total for one record_type, in the summary's target currency.
+ */
+export type ChargeTypeTotal = { recordType: string, count: number, total: number, };
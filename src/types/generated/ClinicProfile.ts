@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClinicProfileCurrency } from "./ClinicProfileCurrency";
+import type { ClinicProfileLineItemTemplate } from "./ClinicProfileLineItemTemplate";
+import type { ClinicProfileRecordTemplate } from "./ClinicProfileRecordTemplate";
+import type { ClinicProfileRoom } from "./ClinicProfileRoom";
+import type { ClinicProfileSettings } from "./ClinicProfileSettings";
+import type { ClinicProfileSpecies } from "./ClinicProfileSpecies";
+
+export type ClinicProfile = { formatVersion: number, generatedAt: string, settings: ClinicProfileSettings | null, currencies: Array<ClinicProfileCurrency>, species: Array<ClinicProfileSpecies>, rooms: Array<ClinicProfileRoom>, recordTemplates: Array<ClinicProfileRecordTemplate>, lineItemTemplates: Array<ClinicProfileLineItemTemplate>, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProfileImportCount } from "./ProfileImportCount";
+
+export type ClinicProfileImportReport = { currencies: ProfileImportCount, species: ProfileImportCount, breeds: ProfileImportCount, rooms: ProfileImportCount, recordTemplates: ProfileImportCount, lineItemTemplates: ProfileImportCount, settingsApplied: boolean, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClinicProfileLineItemTemplate = { name: string, description: string | null, defaultPrice: number, currencyCode: string, displayOrder: number, isActive: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClinicProfileRoom = { name: string, description: string | null, capacity: number, isActive: boolean, color: string, displayOrder: number, 
+/**
+ * Missing in profiles exported before rooms had a buffer
+ */
+bufferMinutes: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The clinic's default settings row. Per-user preferences (theme) and the
+ * search tokenizer, which needs an index rebuild, are not part of it.
+ */
+export type ClinicProfileSettings = { language: string, currencyCode: string | null, dateFormat: string, defaultRoom: string | null, practiceTimezone: string | null, ocrEnabled: boolean, autoArchiveAfterDays: number | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClinicProfileBreed } from "./ClinicProfileBreed";
+
+export type ClinicProfileSpecies = { name: string, active: boolean, displayOrder: number, color: string, breeds: Array<ClinicProfileBreed>, };
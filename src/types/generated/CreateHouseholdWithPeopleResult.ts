@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HouseholdWithPeople } from "./HouseholdWithPeople";
+import type { SearchIndexSource } from "./SearchIndexSource";
+
+export type CreateHouseholdWithPeopleResult = { search_index: SearchIndexSource, } & HouseholdWithPeople;
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type CreateSpeciesInput = { name: string, display_order: number | null, };
+export type CreateSpeciesInput = { name: string, display_order: number | null, 
+/**
+ * `#RRGGBB` or `#RGB`; the column default applies when omitted.
+ */
+color: string | null, };
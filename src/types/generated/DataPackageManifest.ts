@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataPackageCounts } from "./DataPackageCounts";
+
+export type DataPackageManifest = { formatVersion: number, generatedAt: string, householdId: number, includeDeleted: boolean, counts: DataPackageCounts, redactedFields: Array<string>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeviceResultPath } from "./DeviceResultPath";
+
+/**
+ * How the JSON payload of a device without a dedicated parser maps onto
+ * `DeviceData`. One per device type, in `device_parse_mappings`.
+ */
+export type DeviceParseMapping = { id: number, deviceType: string, patientIdentifierPath: string | null, resultPaths: Array<DeviceResultPath>, createdAt: string, updatedAt: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DeviceResultPath = { 
+/**
+ * Key in `test_results`
+ */
+resultKey: string, 
+/**
+ * e.g. `sample.results[0].value` or `results[code=WBC].value`
+ */
+path: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Breed } from "./Breed";
+
+/**
+ * A breed in a `find_duplicate_breeds` cluster, with how many patients
+ * use it.
+ */
+export type DuplicateBreedCandidate = { breed: Breed, patient_count: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DuplicateBreedCandidate } from "./DuplicateBreedCandidate";
+
+/**
+ * Breeds of one species with similar names, most used first.
+ */
+export type DuplicateBreedCluster = { breeds: Array<DuplicateBreedCandidate>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Another patient already carries the microchip being saved.
+ */
+export type DuplicatePatientError = { patientId: number, patientName: string | null, microchipId: string, };
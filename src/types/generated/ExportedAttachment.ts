@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ExportedAttachment = { id: number, originalName: string, fileSize: number | null, mimeType: string | null, uploadedAt: string, attachmentType: string | null, deviceName: string | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportedAttachment } from "./ExportedAttachment";
+
+export type ExportedMedicalRecord = { id: number, recordType: string, name: string, procedureName: string | null, description: string, prescriptionNotes: string | null, price: number | null, discountPercent: number | null, manualTotal: number | null, invoiceNumber: string | null, isArchived: boolean, followUpDate: string | null, createdAt: string, updatedAt: string, attachments: Array<ExportedAttachment>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportedAppointment } from "./ExportedAppointment";
+import type { ExportedMedicalRecord } from "./ExportedMedicalRecord";
+import type { Patient } from "./Patient";
+
+export type ExportedPatient = { patient: Patient, medicalRecords: Array<ExportedMedicalRecord>, appointments: Array<ExportedAppointment>, };
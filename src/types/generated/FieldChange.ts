@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A field that differs between a history entry's old and new snapshot.
+ * The create entry (version 1) has no old snapshot, so every field is
+ * reported with a null `old`.
+ */
+export type FieldChange = { field: string, old: unknown, new: unknown, };
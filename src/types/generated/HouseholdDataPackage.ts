@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataPackageManifest } from "./DataPackageManifest";
+import type { ExportedPatient } from "./ExportedPatient";
+import type { Household } from "./Household";
+import type { PersonWithContacts } from "./PersonWithContacts";
+
+export type HouseholdDataPackage = { manifest: DataPackageManifest, household: Household, people: Array<PersonWithContacts>, patients: Array<ExportedPatient>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the household search reindex progress event, sent after each
+ * batch of a full rebuild.
+ */
+export type HouseholdSearchReindexProgress = { indexed: number, total: number, };
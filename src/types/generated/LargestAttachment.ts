@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LargestAttachment = { attachmentId: number, originalName: string, attachmentType: string, mimeType: string, fileSize: number, medicalRecordId: number, recordName: string | null, patientId: number | null, patientName: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Household } from "./Household";
+
+/**
+ * One of the households a patient is linked to, with how it is linked.
+ */
+export type LinkedHousehold = { household: Household, relationship_type: string, 
+/**
+ * The household the patient's owner details come from
+ */
+is_primary: boolean, };
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type MedicalAttachment = { id: number, medicalRecordId: number, fileId: string, originalName: string, fileSize: number | null, mimeType: string | null, uploadedAt: string, deviceType: string | null, deviceName: string | null, connectionMethod: string | null, attachmentType: string | null, };
+export type MedicalAttachment = { id: number, medicalRecordId: number, fileId: string, originalName: string, fileSize: number | null, mimeType: string | null, uploadedAt: string, deviceType: string | null, deviceName: string | null, connectionMethod: string | null, attachmentType: string | null, sortOrder: number, 
+/**
+ * Referenced in place (see `external_path`) rather than copied into app storage
+ */
+isExternal: boolean, externalPath: string | null, };
@@ -2,4 +2,4 @@
 import type { MedicalAttachment } from "./MedicalAttachment";
 import type { MedicalRecordLineItem } from "./MedicalRecordLineItem";
 
-export type MedicalRecord = { id: number, patientId: number, recordType: string, name: string, procedureName: string | null, description: string, prescriptionNotes: string | null, price: number | null, currencyId: number | null, discountPercent: number | null, manualTotal: number | null, invoiceNumber: string | null, isArchived: boolean, createdAt: string, updatedAt: string, createdBy: string | null, updatedBy: string | null, version: number, attachments: Array<MedicalAttachment> | null, lineItems: Array<MedicalRecordLineItem> | null, };
+export type MedicalRecord = { id: number, patientId: number, recordType: string, name: string, procedureName: string | null, description: string, prescriptionNotes: string | null, price: number | null, currencyId: number | null, discountPercent: number | null, manualTotal: number | null, invoiceNumber: string | null, isArchived: boolean, followUpDate: string | null, followUpDone: boolean, createdAt: string, updatedAt: string, createdBy: string | null, updatedBy: string | null, version: number, attachments: Array<MedicalAttachment> | null, lineItems: Array<MedicalRecordLineItem> | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MedicalRecordSummary } from "./MedicalRecordSummary";
+
+export type MedicalRecordSummariesResponse = { records: Array<MedicalRecordSummary>, total: number, page: number, pageSize: number, totalPages: number, hasNext: boolean, hasPrevious: boolean, };
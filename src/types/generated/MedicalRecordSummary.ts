@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Lightweight projection of a medical record for list views. Only `id` and
+ * `record_type` are always present; every other field is filled in only
+ * when requested via `fields` and omitted from the payload otherwise.
+ */
+export type MedicalRecordSummary = { id: number, recordType: string, name?: string, procedureName?: string, description?: string, prescriptionNotes?: string, price?: number, currencyId?: number, discountPercent?: number, manualTotal?: number, isArchived?: boolean, createdAt?: string, updatedAt?: string, };
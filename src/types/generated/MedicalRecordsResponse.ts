@@ -1,4 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { MedicalRecord } from "./MedicalRecord";
 
-export type MedicalRecordsResponse = { records: Array<MedicalRecord>, total: number, page: number, pageSize: number, };
+export type MedicalRecordsResponse = { records: Array<MedicalRecord>, total: number, page: number, pageSize: number, 
+/**
+ * At least 1, even with no records
+ */
+totalPages: number, hasNext: boolean, hasPrevious: boolean, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MedicalRecordSummariesResponse } from "./MedicalRecordSummariesResponse";
+import type { MedicalRecordsResponse } from "./MedicalRecordsResponse";
+
+/**
+ * Result of `get_medical_records`: full records by default, summaries when
+ * the caller passed a `fields` selection.
+ */
+export type MedicalRecordsResult = MedicalRecordsResponse | MedicalRecordSummariesResponse;
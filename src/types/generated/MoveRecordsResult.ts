@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What `move_records_to_patient` moved.
+ */
+export type MoveRecordsResult = { movedRecords: number, 
+/**
+ * Attachments of the moved records, which follow them to the new patient
+ */
+movedAttachments: number, };
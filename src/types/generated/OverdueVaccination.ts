@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A patient's latest dose of a vaccine whose next dose is past due.
+ */
+export type OverdueVaccination = { vaccinationId: number, patientId: number, patientName: string | null, vaccineName: string, administeredAt: string, nextDueAt: string, };
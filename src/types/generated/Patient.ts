@@ -1,3 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Patient = { id: number, name: string | null, speciesId: number | null, breedId: number | null, species: string | null, breed: string | null, gender: string | null, dateOfBirth: string | null, color: string | null, weight: number | null, microchipId: string | null, medicalNotes: string | null, isActive: boolean, householdId: number | null, createdAt: string, updatedAt: string, };
+export type Patient = { id: number, name: string | null, speciesId: number | null, breedId: number | null, species: string | null, breed: string | null, gender: string | null, dateOfBirth: string | null, color: string | null, weight: number | null, microchipId: string | null, medicalNotes: string | null, isActive: boolean, householdId: number | null, createdAt: string, updatedAt: string, 
+/**
+ * Whole months since date_of_birth as of today; None without a date
+ * of birth or with one in the future
+ */
+ageMonths: number | null, 
+/**
+ * "3y 2m", "5 months" or "2 weeks"; None when age_months is
+ */
+ageDisplay: string | null, };
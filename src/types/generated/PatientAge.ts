@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Age of a patient today, for `get_patient_age`.
+ */
+export type PatientAge = { patientId: number, dateOfBirth: string | null, ageMonths: number | null, ageDisplay: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MedicalAttachment } from "./MedicalAttachment";
+
+/**
+ * An attachment together with the record it hangs off, for the patient
+ * "files" tab which lists attachments across all of a patient's records.
+ */
+export type PatientAttachment = { recordName: string, recordType: string, recordIsArchived: boolean, recordCreatedAt: string, } & MedicalAttachment;
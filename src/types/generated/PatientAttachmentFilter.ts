@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PatientAttachmentFilter = { 
+/**
+ * Exact mime type, or a family like "image/*"
+ */
+mimeType: string | null, attachmentType: string | null, 
+/**
+ * Include attachments on archived records (default false)
+ */
+includeArchived: boolean | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PatientAttachment } from "./PatientAttachment";
+
+export type PatientAttachmentsResponse = { attachments: Array<PatientAttachment>, total: number, page: number, pageSize: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChargeTypeTotal } from "./ChargeTypeTotal";
+import type { Currency } from "./Currency";
+import type { SkippedChargeRecord } from "./SkippedChargeRecord";
+
+/**
+ * A patient's charges grouped by record_type and converted into one
+ * currency. Totals are rounded to two decimals.
+ */
+export type PatientChargeSummary = { patientId: number, currency: Currency, byType: Array<ChargeTypeTotal>, grandTotal: number, skipped: Array<SkippedChargeRecord>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happened to one CSV row.
+ */
+export type PatientImportRowResult = { 
+/**
+ * 1-based line in the CSV where the row starts (the header is line 1)
+ */
+line: number, name: string | null, 
+/**
+ * Valid; on a dry run, would be imported
+ */
+success: boolean, 
+/**
+ * The created patient; always null on a dry run
+ */
+patientId: number | null, 
+/**
+ * Breed created (or, on a dry run, to be created) for this row
+ */
+createdBreed: string | null, error: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Filters of `list_patients`; all optional and ANDed.
+ */
+export type PatientListFilter = { speciesId: number | null, isActive: boolean | null, 
+/**
+ * Whether the patient has an appointment starting from now on that
+ * isn't cancelled or deleted
+ */
+hasUpcomingAppointment: boolean | null, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Patient } from "./Patient";
+
+/**
+ * A row of the main patient table.
+ */
+export type PatientListItem = { patient: Patient, 
+/**
+ * Whole months since date_of_birth, if known
+ */
+ageMonths: number | null, 
+/**
+ * Primary person of the patient's primary household
+ */
+ownerName: string | null, 
+/**
+ * Non-archived medical records
+ */
+recordCount: number, 
+/**
+ * Attachments across all of the patient's medical records, archived
+ * ones included
+ */
+attachmentCount: number, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PatientListItem } from "./PatientListItem";
+
+export type PatientListResponse = { patients: Array<PatientListItem>, total: number, page: number, pageSize: number, };
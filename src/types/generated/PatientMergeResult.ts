@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Patient } from "./Patient";
+
+/**
+ * Outcome of `merge_patients`: the surviving patient and what was moved
+ * to it from the merged one.
+ */
+export type PatientMergeResult = { patient: Patient, medicalRecordsMoved: number, appointmentsMoved: number, vaccinationsMoved: number, weightsMoved: number, householdsMoved: number, 
+/**
+ * Households the target was already linked to; left on the source
+ */
+householdsSkipped: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Who to contact about a patient: the primary household's primary person
+ * (or the first one added) with their preferred phone and email.
+ */
+export type PatientPrimaryContact = { 
+/**
+ * Person's full name, or the household name if it has no people
+ */
+name: string, phone: string | null, email: string | null, householdId: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of `reclassify_patients`.
+ */
+export type PatientReclassification = { patientsUpdated: number, 
+/**
+ * Patients that had a breed and were reclassified without one
+ */
+breedsCleared: number, };
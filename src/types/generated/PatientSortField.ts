@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Columns the patient list can be sorted by.
+ */
+export type PatientSortField = "name" | "species" | "breed" | "owner" | "date_of_birth" | "created_at";
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WeightPoint } from "./WeightPoint";
+import type { WeightTrendDirection } from "./WeightTrendDirection";
+
+/**
+ * A patient's weight change over the last `window_days`, oldest point first.
+ */
+export type PatientWeightTrend = { patientId: number, windowDays: number, direction: WeightTrendDirection, 
+/**
+ * First to last point in the window; `None` with insufficient data
+ */
+percentChange: number | null, 
+/**
+ * Whether the change reaches `threshold_percent` either way
+ */
+isSignificant: boolean, thresholdPercent: number, points: Array<WeightPoint>, };
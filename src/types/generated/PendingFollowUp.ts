@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A record whose follow-up is due, with enough patient and owner context
+ * to act on it from the worklist.
+ */
+export type PendingFollowUp = { recordId: number, recordName: string, recordType: string, followUpDate: string, 
+/**
+ * Negative when overdue
+ */
+daysUntilDue: number, patientId: number, patientName: string | null, species: string | null, ownerName: string | null, ownerPhone: string | null, };
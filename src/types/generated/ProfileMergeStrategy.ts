@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How an import treats configuration the profile doesn't mention. `Merge`
+ * leaves it alone; `Replace` retires it: species, breeds, rooms and line
+ * item templates are deactivated (patients and records still point at
+ * them) and record templates are deleted. Currencies are never removed.
+ */
+export type ProfileMergeStrategy = "merge" | "replace";
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Patient } from "./Patient";
+
+/**
+ * A patient matched by an age-bracket recall query, with the household
+ * contact to reach out to.
+ */
+export type RecallCandidate = { patient: Patient, 
+/**
+ * Whole months since date_of_birth
+ */
+ageMonths: number, 
+/**
+ * Primary person of the patient's primary household
+ */
+ownerName: string | null, ownerPhone: string | null, ownerEmail: string | null, };
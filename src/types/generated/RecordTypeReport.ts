@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Currency } from "./Currency";
+import type { RecordTypeReportRow } from "./RecordTypeReportRow";
+import type { SkippedChargeRecord } from "./SkippedChargeRecord";
+
+/**
+ * A page of the clinic's records of one type in a date range, with the
+ * charge total of every matching record in one currency.
+ */
+export type RecordTypeReport = { recordType: string, from: string, to: string, currency: Currency, records: Array<RecordTypeReportRow>, total: number, page: number, pageSize: number, totalCharge: number, skipped: Array<SkippedChargeRecord>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One record in a `RecordTypeReport`, with patient and owner context.
+ */
+export type RecordTypeReportRow = { recordId: number, recordName: string, createdAt: string, patientId: number, patientName: string | null, species: string | null, ownerName: string | null, 
+/**
+ * In the report's currency; null when unpriced or skipped
+ */
+charge: number | null, };
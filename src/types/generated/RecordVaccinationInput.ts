@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RecordVaccinationInput = { patientId: number, vaccineName: string, administeredAt: string, 
+/**
+ * Overrides the protocol; when absent the protocol for the vaccine and
+ * the patient's species decides, and without one there is no next dose
+ */
+nextDueAt: string | null, notes: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a household's `household_search` row was written: by the insert
+ * triggers, or directly because the triggers left it missing.
+ */
+export type SearchIndexSource = "trigger" | "fallback";
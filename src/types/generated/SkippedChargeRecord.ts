@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A priced record left out of a charge summary because one of its
+ * currencies has no exchange rate to the target currency.
+ */
+export type SkippedChargeRecord = { recordId: number, recordName: string, recordType: string, currencyId: number, };
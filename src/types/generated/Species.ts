@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Species = { id: number, name: string, active: boolean, display_order: number, created_at: string, updated_at: string, };
+export type Species = { id: number, name: string, active: boolean, display_order: number, color: string, created_at: string, updated_at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Species id → color, for legends and coloring patients by species.
+ */
+export type SpeciesColor = { species_id: number, name: string, color: string, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LargestAttachment } from "./LargestAttachment";
+import type { StorageUsageGroup } from "./StorageUsageGroup";
+
+export type StorageBreakdown = { totalBytes: number, totalCount: number, 
+/**
+ * Largest first
+ */
+groups: Array<StorageUsageGroup>, largest: Array<LargestAttachment>, orphanedBytes: number, orphanedFiles: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Values that replace a template's defaults when previewing a record.
+ * Only provided fields override the template.
+ */
+export type TemplatePreviewOverrides = { 
+/**
+ * Must match the template's record type when given
+ */
+recordType: string | null, name: string | null, description: string | null, prescriptionNotes: string | null, price: number | null, currencyId: number | null, discountPercent: number | null, manualTotal: number | null, };
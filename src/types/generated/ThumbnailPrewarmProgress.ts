@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `attachment-thumbnails-progress` event, sent once per
+ * attachment as `prewarm_attachment_thumbnails` works through them.
+ */
+export type ThumbnailPrewarmProgress = { attachmentId: number, 
+/**
+ * Attachments done so far, this one included
+ */
+completed: number, total: number, 
+/**
+ * A preview was already cached; nothing was rendered
+ */
+cached: boolean, error: string | null, };
@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type UpdateSpeciesInput = { name: string | null, active: boolean | null, display_order: number | null, };
+export type UpdateSpeciesInput = { name: string | null, active: boolean | null, display_order: number | null, color: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One file in a batch upload (see `upload_medical_attachments`)
+ */
+export type UploadFile = { fileName: string, fileData: Array<number>, mimeType: string, deviceType: string | null, deviceName: string | null, connectionMethod: string | null, attachmentType: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-file outcome of a batch upload. `index` is the file's position in
+ * the submitted list so the UI can match results back to its queue.
+ */
+export type UploadFileResult = { index: number, fileName: string, attachmentId: number | null, error: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type User = { id: number, name: string, role: string, active: boolean, createdAt: string, updatedAt: string, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Vaccination = { id: number, patientId: number, vaccineName: string, administeredAt: string, nextDueAt: string | null, notes: string | null, createdAt: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type VaccineProtocol = { id: number, vaccineName: string, speciesId: number, 
+/**
+ * Days from administration until the next dose is due
+ */
+intervalDays: number, createdAt: string, updatedAt: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which way a patient's weight moved over the trend window.
+ */
+export type WeightTrendDirection = "gaining" | "stable" | "losing" | "insufficient_data";
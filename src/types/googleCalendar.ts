@@ -30,12 +30,55 @@ export interface SyncLog {
   completed_at?: string;
 }
 
+export interface PendingSyncAppointment {
+  appointment_id: number;
+  action: 'create' | 'update' | 'delete';
+  event_id: string | null;
+  calendar_id: string | null;
+}
+
+// One push attempt from get_appointment_sync_log
+export interface AppointmentSyncLog {
+  id: number;
+  appointment_id: number;
+  external_id: string | null;
+  sync_action: 'create' | 'update' | 'delete';
+  sync_status: 'success' | 'failed' | 'pending';
+  error_message: string | null;
+  synced_at: string;
+  /** Retries of a failed attempt so far */
+  retry_count: number;
+  next_retry_at: string | null;
+}
+
 export interface OAuthFlowState {
   auth_url: string;
   state: string;
   redirect_port: number;
 }
 
+export interface GoogleCalendar {
+  id: string;
+  summary: string;
+  primary: boolean;
+  /** 'owner' | 'writer' | 'reader' | 'freeBusyReader'; only owner and writer can be synced to */
+  access_role: string;
+}
+
+export interface SyncCalendarChange {
+  calendar: GoogleCalendar;
+  cleared_mappings: number;
+}
+
+export type GoogleConnectionStatus = 'not_connected' | 'refresh_failed' | 'api_error' | 'connected';
+
+export interface GoogleConnectionTest {
+  ok: boolean;
+  status: GoogleConnectionStatus;
+  calendars: GoogleCalendar[];
+  error: string | null;
+}
+
 export interface UpdateSettingsInput {
   sync_enabled?: boolean;
 }
@@ -1,3 +1,5 @@
+import { Patient as PatientModel } from './models';
+
 // Core household entities
 export interface Household {
   id: number;
@@ -97,6 +99,13 @@ export interface SearchHouseholdsResponse {
   hasMore: boolean;
 }
 
+// Payload of the 'household-search-reindex-progress' event
+export interface HouseholdSearchReindexProgress {
+  /** Households indexed so far */
+  indexed: number;
+  total: number;
+}
+
 // Complete household with all relations
 export interface HouseholdWithPeople {
   household: Household;
@@ -104,6 +113,14 @@ export interface HouseholdWithPeople {
   petCount: number;
 }
 
+// One of the households a patient is linked to
+export interface LinkedHousehold {
+  household: Household;
+  relationshipType: string;
+  /** The household the patient's owner details come from */
+  isPrimary: boolean;
+}
+
 // Patient/Animal type for display
 export interface Patient {
   id: number;
@@ -113,6 +130,9 @@ export interface Patient {
   dateOfBirth?: string;
   weight?: number;
   gender?: 'male' | 'female' | 'unknown';
+  relationshipType?: string;
+  /** False when this is one of the patient's secondary households */
+  isPrimaryHousehold?: boolean;
   status?: 'active' | 'inactive';
 }
 
@@ -150,6 +170,76 @@ export interface CreatePatientWithHouseholdResponse {
   patientId: number;
 }
 
+// Everything held about one household (export_household_data_package)
+export interface DataPackageCounts {
+  people: number;
+  contacts: number;
+  patients: number;
+  medicalRecords: number;
+  attachments: number;
+  appointments: number;
+}
+
+export interface DataPackageManifest {
+  formatVersion: number;
+  generatedAt: string;
+  householdId: number;
+  includeDeleted: boolean;
+  counts: DataPackageCounts;
+  redactedFields: string[];
+}
+
+export interface ExportedAttachment {
+  id: number;
+  originalName: string;
+  fileSize?: number | null;
+  mimeType?: string | null;
+  uploadedAt: string;
+  attachmentType?: string | null;
+  deviceName?: string | null;
+}
+
+export interface ExportedMedicalRecord {
+  id: number;
+  recordType: string;
+  name: string;
+  procedureName?: string | null;
+  description: string;
+  prescriptionNotes?: string | null;
+  price?: number | null;
+  discountPercent?: number | null;
+  manualTotal?: number | null;
+  invoiceNumber?: string | null;
+  isArchived: boolean;
+  followUpDate?: string | null;
+  createdAt: string;
+  updatedAt: string;
+  attachments: ExportedAttachment[];
+}
+
+export interface ExportedAppointment {
+  id: number;
+  title: string;
+  description?: string | null;
+  startTime: string;
+  endTime: string;
+  status: string;
+  deletedAt?: string | null;
+}
+
+export interface ExportedPatient {
+  patient: PatientModel;
+  medicalRecords: ExportedMedicalRecord[];
+  appointments: ExportedAppointment[];
+}
+
+export interface HouseholdDataPackage {
+  manifest: DataPackageManifest;
+  household: Household;
+  people: PersonWithContacts[];
+  patients: ExportedPatient[];
+}
+
 // Validation helpers
 export function validateHouseholdDto(dto: CreateHouseholdWithPeopleDto): string | null {
   // Must have at least 1 person, max 5
@@ -44,6 +44,48 @@ export interface MedicalAttachment {
   deviceName?: string;
   connectionMethod?: string;
   attachmentType?: AttachmentType;
+  // Referenced in place at externalPath rather than copied into app storage
+  isExternal?: boolean;
+  externalPath?: string;
+}
+
+// An attachment whose OCR'd text matched search_attachment_text; hits in
+// the snippet are wrapped in [ ]
+export interface AttachmentTextMatch {
+  attachmentId: number;
+  medicalRecordId: number;
+  originalName: string;
+  snippet: string;
+}
+
+// Payload of the 'attachment-thumbnails-progress' event
+export interface ThumbnailPrewarmProgress {
+  attachmentId: number;
+  /** Attachments done so far, this one included */
+  completed: number;
+  total: number;
+  /** A preview was already cached; nothing was rendered */
+  cached: boolean;
+  error?: string;
+}
+
+// Payload of the 'attachment-ocr-complete' event
+export interface AttachmentOcrResult {
+  attachmentId: number;
+  indexed: boolean;
+  error?: string;
+}
+
+// Result of apply_auto_archive
+export interface AutoArchiveResult {
+  archived: number;
+  skippedPendingFollowUp: number;
+}
+
+// Result of move_records_to_patient
+export interface MoveRecordsResult {
+  movedRecords: number;
+  movedAttachments: number;
 }
 
 export interface MedicalRecordHistory {
@@ -57,6 +99,13 @@ export interface MedicalRecordHistory {
   changedAt: string;
 }
 
+// One field changed by a history entry; on version 1 every field, with a null old value
+export interface FieldChange {
+  field: string;
+  old: unknown;
+  new: unknown;
+}
+
 export interface Currency {
   id: number;
   code: string;
@@ -122,6 +171,10 @@ export interface MedicalRecordsResponse {
   total: number;
   page: number;
   pageSize: number;
+  /** At least 1, even with no records */
+  totalPages: number;
+  hasNext: boolean;
+  hasPrevious: boolean;
 }
 
 export interface MedicalRecordDetail {
@@ -148,6 +201,17 @@ export interface DownloadAttachmentResponse {
   testResults?: any;
 }
 
+// Result of get_records_changed_since; pass checkedAt as the next `since`
+export interface MedicalRecordChanges {
+  records: MedicalRecord[];
+  checkedAt: string;
+}
+
+// Returned by create_pdf_job; cancel with cancel_pdf_job
+export interface PdfJobHandle {
+  jobId: string;
+}
+
 export interface SearchMedicalRecordsResponse {
   records: MedicalRecord[];
   matchCount: number;
@@ -17,6 +17,9 @@ export interface Patient {
   householdId?: number;
   createdAt: string;
   updatedAt: string;
+  // Computed from dateOfBirth; null without one or with one in the future
+  ageMonths?: number | null;
+  ageDisplay?: string | null; // "3y 2m", "5 months", "2 weeks"
 }
 
 export interface PatientWithHousehold extends Patient {
@@ -13,6 +13,14 @@ export interface PatientWithAge extends Patient {
   };
 }
 
+// Result of get_patient_age
+export interface PatientAge {
+  patientId: number;
+  dateOfBirth: string | null;
+  ageMonths: number | null;
+  ageDisplay: string | null;
+}
+
 // Contact information for household members
 export interface Contact {
   id: number;
@@ -54,6 +62,76 @@ export interface DeletePatientResponse {
   redirectTo: string;
 }
 
+// Result of reclassify_patients
+export interface PatientReclassification {
+  patientsUpdated: number;
+  breedsCleared: number;
+}
+
+// `details` of a DUPLICATE_MICROCHIP error from create/update patient
+export interface DuplicatePatientError {
+  patientId: number;
+  patientName: string | null;
+  microchipId: string;
+}
+
+// Outcome of merge_patients; patient is the one kept
+export interface PatientMergeResult {
+  patient: Patient;
+  medicalRecordsMoved: number;
+  appointmentsMoved: number;
+  vaccinationsMoved: number;
+  weightsMoved: number;
+  householdsMoved: number;
+  /** Households the target was already linked to; left on the source */
+  householdsSkipped: number;
+}
+
+// One row's outcome from import_patients_from_csv
+export interface PatientImportRowResult {
+  /** Line in the CSV where the row starts; the header is line 1 */
+  line: number;
+  name: string | null;
+  /** Valid; on a dry run, would be imported */
+  success: boolean;
+  /** Always null on a dry run */
+  patientId: number | null;
+  /** Breed created (or to be created) for this row */
+  createdBreed: string | null;
+  error: string | null;
+}
+
+export interface PatientListFilter {
+  speciesId?: number;
+  isActive?: boolean;
+  /** Has an appointment from now on that isn't cancelled or deleted */
+  hasUpcomingAppointment?: boolean;
+}
+
+export type PatientSortField = 'name' | 'species' | 'breed' | 'owner' | 'date_of_birth' | 'created_at';
+
+export interface PatientSort {
+  field: PatientSortField;
+  descending: boolean;
+}
+
+export interface PatientListItem {
+  patient: Patient;
+  ageMonths: number | null;
+  ownerName: string | null;
+  /** Non-archived medical records */
+  recordCount: number;
+  /** Attachments across all medical records, archived ones included */
+  attachmentCount: number;
+}
+
+export interface PatientListResponse {
+  patients: PatientListItem[];
+  total: number;
+  page: number;
+  pageSize: number;
+}
+
 // Validation types
 export interface ValidationError {
   field: string;
@@ -5,12 +5,23 @@ export interface Room {
   capacity: number;
   color: string;
   isActive: boolean;
+  displayOrder: number;
   createdAt: string;
   updatedAt: string;
 }
 
+export type RoomSort = 'name' | 'display_order';
+
 export interface RoomFilter {
-  activeOnly: boolean;
+  activeOnly?: boolean;
+  includeInactive?: boolean;
+  sort?: RoomSort;
+}
+
+export interface RoomList {
+  rooms: Room[];
+  activeCount: number;
+  inactiveCount: number;
 }
 
 export interface CreateRoomInput {
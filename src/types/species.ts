@@ -3,7 +3,7 @@ export interface Species {
   name: string;
   active: boolean;
   display_order: number;
-  color?: string;
+  color: string;
   created_at: string;
   updated_at: string;
 }
@@ -11,10 +11,18 @@ export interface Species {
 export interface CreateSpeciesInput {
   name: string;
   display_order?: number;
+  color?: string;
 }
 
 export interface UpdateSpeciesInput {
   name?: string;
   active?: boolean;
   display_order?: number;
+  color?: string;
+}
+
+export interface SpeciesColor {
+  species_id: number;
+  name: string;
+  color: string;
 }
@@ -0,0 +1,21 @@
+export type UserRole = 'admin' | 'veterinarian' | 'technician' | 'receptionist';
+
+export interface User {
+  id: number;
+  name: string;
+  role: UserRole;
+  active: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CreateUserInput {
+  name: string;
+  role: UserRole;
+}
+
+export interface UpdateUserInput {
+  name?: string;
+  role?: UserRole;
+  active?: boolean;
+}
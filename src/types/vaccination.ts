@@ -0,0 +1,39 @@
+export interface VaccineProtocol {
+  id: number;
+  vaccineName: string;
+  speciesId: number;
+  intervalDays: number;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CreateVaccineProtocolInput {
+  vaccineName: string;
+  speciesId: number;
+  intervalDays: number;
+}
+
+export interface UpdateVaccineProtocolInput {
+  vaccineName?: string;
+  speciesId?: number;
+  intervalDays?: number;
+}
+
+export interface Vaccination {
+  id: number;
+  patientId: number;
+  vaccineName: string;
+  administeredAt: string;
+  nextDueAt?: string | null;
+  notes?: string | null;
+  createdAt: string;
+}
+
+export interface RecordVaccinationInput {
+  patientId: number;
+  vaccineName: string;
+  administeredAt: string;
+  /** Leave out to take the next due date from the vaccine protocol */
+  nextDueAt?: string;
+  notes?: string;
+}